@@ -0,0 +1,155 @@
+//! Adapter trait, sessions and per-adapter health tracking
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use futures::future::BoxFuture;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use tokio::sync::broadcast;
+
+use dash_core::{AdapterHealth, ChannelHealth, FeedChannel, Subscription, Symbol, WsMessage};
+
+/// Connection, subscription or protocol failure
+pub type AdapterError = Box<dyn std::error::Error + Send + Sync>;
+
+pub type AdapterResult<T> = Result<T, AdapterError>;
+
+/// Normalized market data from one venue connection
+pub type MessageStream = BoxStream<'static, WsMessage>;
+
+// ============================================================================
+// STRATEGY PATTERN: Market Data Adapters
+// ============================================================================
+
+/// Strategy trait for a market data venue
+pub trait MarketDataAdapter: Send + Sync {
+    /// Venue name used for health, source status and venue attribution
+    fn name(&self) -> &str;
+
+    /// Open a connection to the venue
+    fn connect(&self) -> BoxFuture<'static, AdapterResult<Box<dyn AdapterSession>>>;
+
+    /// Channel liveness of the current (or last) session
+    fn health(&self) -> AdapterHealth;
+
+    /// Venue instrument id for a canonical symbol
+    fn instrument(&self, symbol: &Symbol) -> String {
+        symbol.as_str().to_uppercase()
+    }
+}
+
+/// An open venue connection
+pub trait AdapterSession: Send {
+    /// Request trades, book, ticker and candles for a symbol
+    fn subscribe(&mut self, subscription: &Subscription) -> BoxFuture<'_, AdapterResult<()>>;
+
+    /// Normalized messages until the venue disconnects
+    fn into_stream(self: Box<Self>) -> MessageStream;
+}
+
+/// Connect, subscribe and forward messages into `tx` until the venue disconnects
+pub async fn run_adapter(
+    adapter: Arc<dyn MarketDataAdapter>,
+    subscriptions: Vec<Subscription>,
+    tx: broadcast::Sender<WsMessage>,
+) {
+    let mut session = match adapter.connect().await {
+        Ok(session) => session,
+        Err(e) => {
+            tracing::warn!("{}: connect failed: {}", adapter.name(), e);
+            return;
+        }
+    };
+
+    for subscription in &subscriptions {
+        if let Err(e) = session.subscribe(subscription).await {
+            tracing::warn!("{}: subscribe to {} failed: {}", adapter.name(), subscription.symbol, e);
+            return;
+        }
+    }
+
+    let mut stream = session.into_stream();
+    while let Some(msg) = stream.next().await {
+        let _ = tx.send(msg);
+    }
+    tracing::info!("{}: stream ended", adapter.name());
+}
+
+// ============================================================================
+// HEALTH TRACKING
+// ============================================================================
+
+/// Last message time per channel, shared between an adapter and its sessions
+#[derive(Debug, Clone)]
+pub struct HealthTracker {
+    name: String,
+    last_message: Arc<Mutex<HashMap<FeedChannel, Instant>>>,
+}
+
+impl HealthTracker {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            last_message: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn last_message(&self) -> std::sync::MutexGuard<'_, HashMap<FeedChannel, Instant>> {
+        self.last_message.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn record(&self, msg: &WsMessage) {
+        if let Some(channel) = FeedChannel::for_message(msg) {
+            self.last_message().insert(channel, Instant::now());
+        }
+    }
+
+    /// Forget timings from a previous session
+    pub fn reset(&self) {
+        self.last_message().clear();
+    }
+
+    /// Record every message passing through `stream` (a new session starts fresh)
+    pub fn track(&self, stream: MessageStream) -> MessageStream {
+        self.reset();
+        let tracker = self.clone();
+        stream.inspect(move |msg| tracker.record(msg)).boxed()
+    }
+
+    pub fn report(&self, now: Instant) -> AdapterHealth {
+        let last_message = self.last_message();
+        let channels = FeedChannel::all()
+            .iter()
+            .map(|channel| {
+                let age = last_message
+                    .get(channel)
+                    .map(|at| now.saturating_duration_since(*at).as_millis() as u64);
+                ChannelHealth::new(*channel, age)
+            })
+            .collect();
+        AdapterHealth::new(self.name.clone(), channels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dash_core::{FeedStatus, Symbol, Ticker};
+
+    #[tokio::test]
+    async fn test_tracker_follows_stream() {
+        let tracker = HealthTracker::new("test");
+        assert_eq!(tracker.report(Instant::now()).status, FeedStatus::Down);
+
+        let msgs = vec![WsMessage::Ticker(Ticker::new(Symbol::new("BTC-USD"), 100.0))];
+        let seen: Vec<WsMessage> = tracker.track(futures::stream::iter(msgs).boxed()).collect().await;
+        assert_eq!(seen.len(), 1);
+
+        let report = tracker.report(Instant::now());
+        assert_eq!(report.name, "test");
+        assert_eq!(report.status, FeedStatus::Degraded);
+        assert!(report.channels.iter().any(|c| c.channel == FeedChannel::Ticker && !c.stale));
+    }
+}
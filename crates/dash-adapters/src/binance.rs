@@ -0,0 +1,399 @@
+//! Binance spot public streams (combined stream endpoint)
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+
+use crate::symbols::{SymbolFormat, SymbolMapper};
+use crate::ws::{self, string_f64, string_levels, FrameDecoder, WsConnection};
+use crate::{AdapterResult, AdapterSession, HealthTracker, MarketDataAdapter, MessageStream};
+use dash_core::{
+    AdapterHealth, Candle, CandleInterval, OrderBookLevel, OrderBookSnapshot, Price, Quantity,
+    Subscription, Symbol, Ticker, Trade, TradeSide, WsMessage,
+};
+
+pub const BINANCE_WS_URL: &str = "wss://stream.binance.com:9443/stream";
+
+/// Book levels per side (partial depth stream)
+const BOOK_DEPTH: usize = 20;
+
+pub struct BinanceAdapter {
+    name: String,
+    endpoint: String,
+    health: HealthTracker,
+    symbols: SymbolMapper,
+}
+
+impl BinanceAdapter {
+    pub fn new() -> Self {
+        Self::with_endpoint(BINANCE_WS_URL)
+    }
+
+    /// Connect somewhere other than the production endpoint (testnet, proxy)
+    pub fn with_endpoint(endpoint: impl Into<String>) -> Self {
+        Self {
+            name: "binance".to_string(),
+            endpoint: endpoint.into(),
+            health: HealthTracker::new("binance"),
+            symbols: SymbolMapper::new(SymbolFormat::Concat).usd_as("USDT"),
+        }
+    }
+
+    /// Pin canonical symbols to specific instruments on top of the venue rule
+    pub fn with_symbol_overrides(mut self, overrides: &[(Symbol, String)]) -> Self {
+        self.symbols = self.symbols.with_overrides(overrides);
+        self
+    }
+}
+
+impl Default for BinanceAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MarketDataAdapter for BinanceAdapter {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn connect(&self) -> BoxFuture<'static, AdapterResult<Box<dyn AdapterSession>>> {
+        let endpoint = self.endpoint.clone();
+        let health = self.health.clone();
+        let symbols = self.symbols.clone();
+        Box::pin(async move {
+            let ws = ws::connect(&endpoint).await?;
+            Ok(Box::new(BinanceSession {
+                ws,
+                health,
+                symbols,
+                decoder: BinanceDecoder::default(),
+                request_id: 0,
+            }) as Box<dyn AdapterSession>)
+        })
+    }
+
+    fn health(&self) -> AdapterHealth {
+        self.health.report(Instant::now())
+    }
+
+    fn instrument(&self, symbol: &Symbol) -> String {
+        self.symbols.to_venue(symbol)
+    }
+}
+
+struct BinanceSession {
+    ws: WsConnection,
+    health: HealthTracker,
+    symbols: SymbolMapper,
+    decoder: BinanceDecoder,
+    request_id: u64,
+}
+
+impl AdapterSession for BinanceSession {
+    fn subscribe(&mut self, subscription: &Subscription) -> BoxFuture<'_, AdapterResult<()>> {
+        let instrument = self.symbols.to_venue(&subscription.symbol).to_lowercase();
+        let streams = [
+            format!("{}@trade", instrument),
+            format!("{}@depth{}@100ms", instrument, BOOK_DEPTH),
+            format!("{}@ticker", instrument),
+            format!("{}@kline_{}", instrument, subscription.interval.code()),
+        ];
+        self.decoder.symbols.insert(instrument, subscription.symbol.clone());
+        self.request_id += 1;
+        let request = json!({ "method": "SUBSCRIBE", "params": streams, "id": self.request_id });
+        Box::pin(async move { ws::send_json(&mut self.ws, &request).await })
+    }
+
+    fn into_stream(self: Box<Self>) -> MessageStream {
+        let session = *self;
+        session.health.track(ws::decode_frames(session.ws, session.decoder))
+    }
+}
+
+// ============================================================================
+// WIRE FORMAT
+// ============================================================================
+
+/// Combined stream frame: `{"stream":"btcusdt@trade","data":{...}}`
+#[derive(Deserialize)]
+struct Envelope {
+    stream: String,
+    data: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct BinanceTrade {
+    #[serde(rename = "t")]
+    id: u64,
+    #[serde(rename = "p", deserialize_with = "string_f64")]
+    price: f64,
+    #[serde(rename = "q", deserialize_with = "string_f64")]
+    quantity: f64,
+    #[serde(rename = "T")]
+    time: i64,
+    /// Buyer was the maker, i.e. the aggressor sold
+    #[serde(rename = "m")]
+    buyer_is_maker: bool,
+}
+
+#[derive(Deserialize)]
+struct BinanceDepth {
+    #[serde(rename = "lastUpdateId")]
+    last_update_id: u64,
+    #[serde(deserialize_with = "string_levels")]
+    bids: Vec<(f64, f64)>,
+    #[serde(deserialize_with = "string_levels")]
+    asks: Vec<(f64, f64)>,
+}
+
+#[derive(Deserialize)]
+struct BinanceTicker {
+    #[serde(rename = "E")]
+    time: i64,
+    #[serde(rename = "c", deserialize_with = "string_f64")]
+    last: f64,
+    #[serde(rename = "b", deserialize_with = "string_f64")]
+    bid: f64,
+    #[serde(rename = "B", deserialize_with = "string_f64")]
+    bid_qty: f64,
+    #[serde(rename = "a", deserialize_with = "string_f64")]
+    ask: f64,
+    #[serde(rename = "A", deserialize_with = "string_f64")]
+    ask_qty: f64,
+    #[serde(rename = "o", deserialize_with = "string_f64")]
+    open: f64,
+    #[serde(rename = "h", deserialize_with = "string_f64")]
+    high: f64,
+    #[serde(rename = "l", deserialize_with = "string_f64")]
+    low: f64,
+    #[serde(rename = "v", deserialize_with = "string_f64")]
+    volume: f64,
+    #[serde(rename = "q", deserialize_with = "string_f64")]
+    quote_volume: f64,
+    #[serde(rename = "p", deserialize_with = "string_f64")]
+    change: f64,
+    #[serde(rename = "P", deserialize_with = "string_f64")]
+    change_percent: f64,
+    #[serde(rename = "n")]
+    count: u64,
+}
+
+#[derive(Deserialize)]
+struct BinanceKline {
+    #[serde(rename = "k")]
+    kline: KlineData,
+}
+
+#[derive(Deserialize)]
+struct KlineData {
+    #[serde(rename = "t")]
+    open_time: i64,
+    #[serde(rename = "i")]
+    interval: String,
+    #[serde(rename = "o", deserialize_with = "string_f64")]
+    open: f64,
+    #[serde(rename = "h", deserialize_with = "string_f64")]
+    high: f64,
+    #[serde(rename = "l", deserialize_with = "string_f64")]
+    low: f64,
+    #[serde(rename = "c", deserialize_with = "string_f64")]
+    close: f64,
+    #[serde(rename = "v", deserialize_with = "string_f64")]
+    volume: f64,
+    #[serde(rename = "q", deserialize_with = "string_f64")]
+    quote_volume: f64,
+    #[serde(rename = "n")]
+    trades: u32,
+    #[serde(rename = "x")]
+    closed: bool,
+}
+
+/// Normalizes combined stream frames for the subscribed instruments
+#[derive(Default)]
+pub(crate) struct BinanceDecoder {
+    /// Lowercase instrument → dashboard symbol
+    symbols: HashMap<String, Symbol>,
+}
+
+fn parse<T: DeserializeOwned>(data: serde_json::Value) -> Option<T> {
+    serde_json::from_value(data)
+        .map_err(|e| tracing::debug!("binance: unexpected payload: {}", e))
+        .ok()
+}
+
+impl BinanceDecoder {
+    fn normalize(&self, envelope: Envelope) -> Option<WsMessage> {
+        let (instrument, kind) = envelope.stream.split_once('@')?;
+        let symbol = self.symbols.get(instrument)?.clone();
+
+        let msg = match kind {
+            "trade" => {
+                let trade: BinanceTrade = parse(envelope.data)?;
+                WsMessage::Trade(Trade {
+                    id: trade.id.to_string(),
+                    symbol,
+                    price: Price::new(trade.price),
+                    quantity: Quantity::new(trade.quantity),
+                    side: if trade.buyer_is_maker { TradeSide::Sell } else { TradeSide::Buy },
+                    timestamp: DateTime::from_timestamp_millis(trade.time).unwrap_or_else(Utc::now),
+                    maker_order_id: None,
+                    taker_order_id: None,
+                    venue: None,
+                })
+            }
+            kind if kind.starts_with("depth") => {
+                let depth: BinanceDepth = parse(envelope.data)?;
+                let levels = |side: Vec<(f64, f64)>| {
+                    side.into_iter().map(|(price, qty)| OrderBookLevel::new(price, qty, 1)).collect()
+                };
+                WsMessage::OrderBook(OrderBookSnapshot {
+                    symbol,
+                    bids: levels(depth.bids),
+                    asks: levels(depth.asks),
+                    timestamp: Utc::now().timestamp_millis(),
+                    sequence: depth.last_update_id,
+                    venue: None,
+                })
+            }
+            "ticker" => {
+                let ticker: BinanceTicker = parse(envelope.data)?;
+                WsMessage::Ticker(Ticker {
+                    symbol,
+                    last_price: Price::new(ticker.last),
+                    bid_price: Price::new(ticker.bid),
+                    bid_qty: Quantity::new(ticker.bid_qty),
+                    ask_price: Price::new(ticker.ask),
+                    ask_qty: Quantity::new(ticker.ask_qty),
+                    high_24h: Price::new(ticker.high),
+                    low_24h: Price::new(ticker.low),
+                    volume_24h: Quantity::new(ticker.volume),
+                    quote_volume_24h: ticker.quote_volume,
+                    change_24h: ticker.change,
+                    change_percent_24h: ticker.change_percent,
+                    open_24h: Price::new(ticker.open),
+                    trade_count_24h: ticker.count,
+                    timestamp: ticker.time,
+                })
+            }
+            kind if kind.starts_with("kline") => {
+                let kline = parse::<BinanceKline>(envelope.data)?.kline;
+                WsMessage::Candle(Candle {
+                    symbol,
+                    interval: CandleInterval::from_code(&kline.interval)?,
+                    timestamp: kline.open_time,
+                    open: Price::new(kline.open),
+                    high: Price::new(kline.high),
+                    low: Price::new(kline.low),
+                    close: Price::new(kline.close),
+                    volume: Quantity::new(kline.volume),
+                    quote_volume: kline.quote_volume,
+                    trade_count: kline.trades,
+                    buy_volume: None,
+                    sell_volume: None,
+                    is_closed: kline.closed,
+                    synthetic: false,
+                })
+            }
+            _ => return None,
+        };
+        Some(msg)
+    }
+}
+
+impl FrameDecoder for BinanceDecoder {
+    fn decode(&mut self, text: &str) -> Vec<WsMessage> {
+        // Subscription acks (`{"result":null,"id":1}`) have no stream
+        serde_json::from_str::<Envelope>(text)
+            .ok()
+            .and_then(|envelope| self.normalize(envelope))
+            .into_iter()
+            .collect()
+    }
+}
+
+/// Decoder subscribed to BTC-USD, for tests and the fuzz targets
+#[cfg(any(test, feature = "fuzzing"))]
+pub(crate) fn decoder() -> BinanceDecoder {
+    let mut decoder = BinanceDecoder::default();
+    decoder.symbols.insert("btcusdt".into(), Symbol::new("BTC-USD"));
+    decoder
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbol_mapping() {
+        let adapter = BinanceAdapter::new();
+        assert_eq!(adapter.instrument(&Symbol::new("BTC-USD")), "BTCUSDT");
+        assert_eq!(adapter.instrument(&Symbol::new("eth-btc")), "ETHBTC");
+
+        let adapter = adapter.with_symbol_overrides(&[(Symbol::new("BTC-USD"), "BTCFDUSD".into())]);
+        assert_eq!(adapter.instrument(&Symbol::new("BTC-USD")), "BTCFDUSD");
+    }
+
+    #[test]
+    fn test_decode_trade_and_depth() {
+        let mut decoder = decoder();
+        let trade = r#"{"stream":"btcusdt@trade","data":{"e":"trade","E":1700000000001,"s":"BTCUSDT",
+            "t":42,"p":"95000.10","q":"0.5","T":1700000000000,"m":true,"M":true}}"#;
+        match decoder.decode(trade).as_slice() {
+            [WsMessage::Trade(t)] => {
+                assert_eq!(t.symbol.as_str(), "BTC-USD");
+                assert_eq!(t.price.as_f64(), 95000.10);
+                assert_eq!(t.side, TradeSide::Sell);
+                assert_eq!(t.id, "42");
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let depth = r#"{"stream":"btcusdt@depth20@100ms","data":{"lastUpdateId":7,
+            "bids":[["95000.00","1.0"],["94999.00","2.0"]],"asks":[["95001.00","0.3"]]}}"#;
+        match decoder.decode(depth).as_slice() {
+            [WsMessage::OrderBook(book)] => {
+                assert_eq!(book.sequence, 7);
+                assert_eq!(book.bids.len(), 2);
+                assert_eq!(book.spread(), Some(1.0));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_decode_ticker_and_kline() {
+        let mut decoder = decoder();
+        let ticker = r#"{"stream":"btcusdt@ticker","data":{"e":"24hrTicker","E":1700000000000,"s":"BTCUSDT",
+            "p":"500.0","P":"0.53","c":"95000.0","b":"94999.0","B":"1.2","a":"95001.0","A":"0.8",
+            "o":"94500.0","h":"96000.0","l":"94000.0","v":"12000","q":"1140000000","n":250000}}"#;
+        match decoder.decode(ticker).as_slice() {
+            [WsMessage::Ticker(t)] => {
+                assert_eq!(t.last_price.as_f64(), 95000.0);
+                assert_eq!(t.change_percent_24h, 0.53);
+                assert_eq!(t.trade_count_24h, 250000);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let kline = r#"{"stream":"btcusdt@kline_1m","data":{"e":"kline","E":1,"s":"BTCUSDT","k":{
+            "t":1700000040000,"T":1700000099999,"s":"BTCUSDT","i":"1m","o":"1","c":"2","h":"3","l":"0.5",
+            "v":"10","n":5,"x":false,"q":"20"}}}"#;
+        match decoder.decode(kline).as_slice() {
+            [WsMessage::Candle(c)] => {
+                assert_eq!(c.interval, CandleInterval::M1);
+                assert_eq!(c.high.as_f64(), 3.0);
+                assert!(!c.is_closed);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        assert!(decoder.decode(r#"{"result":null,"id":1}"#).is_empty());
+        assert!(decoder.decode(r#"{"stream":"ethusdt@trade","data":{}}"#).is_empty());
+    }
+}
@@ -0,0 +1,119 @@
+//! Local order book rebuilt from venue snapshots and level updates
+
+use std::collections::BTreeMap;
+
+use dash_core::{OrderBookLevel, OrderBookSnapshot, OrderSide, Symbol};
+
+/// Price-keyed levels for one symbol. Keys are the raw bits of positive
+/// prices, which sort in the same order as the prices themselves.
+#[derive(Debug, Clone)]
+pub struct LocalBook {
+    symbol: Symbol,
+    bids: BTreeMap<u64, f64>,
+    asks: BTreeMap<u64, f64>,
+    sequence: u64,
+}
+
+impl LocalBook {
+    pub fn new(symbol: Symbol) -> Self {
+        Self {
+            symbol,
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+            sequence: 0,
+        }
+    }
+
+    pub fn symbol(&self) -> &Symbol {
+        &self.symbol
+    }
+
+    /// Drop every level (before applying a fresh venue snapshot)
+    pub fn clear(&mut self) {
+        self.bids.clear();
+        self.asks.clear();
+    }
+
+    /// Set a level's quantity; zero removes it
+    pub fn set(&mut self, side: OrderSide, price: f64, quantity: f64) {
+        if !(price > 0.0 && price.is_finite()) {
+            return;
+        }
+        let levels = match side {
+            OrderSide::Bid => &mut self.bids,
+            OrderSide::Ask => &mut self.asks,
+        };
+        if quantity > 0.0 {
+            levels.insert(price.to_bits(), quantity);
+        } else {
+            levels.remove(&price.to_bits());
+        }
+    }
+
+    /// Keep only the best `depth` levels per side (venues stop updating the rest)
+    pub fn truncate(&mut self, depth: usize) {
+        while self.bids.len() > depth {
+            self.bids.pop_first();
+        }
+        while self.asks.len() > depth {
+            self.asks.pop_last();
+        }
+    }
+
+    /// Best bids first (descending)
+    pub fn bids(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.bids.iter().rev().map(|(price, qty)| (f64::from_bits(*price), *qty))
+    }
+
+    /// Best asks first (ascending)
+    pub fn asks(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.asks.iter().map(|(price, qty)| (f64::from_bits(*price), *qty))
+    }
+
+    /// Top `depth` levels per side; each call advances the sequence
+    pub fn snapshot(&mut self, depth: usize, timestamp: i64) -> OrderBookSnapshot {
+        self.sequence += 1;
+        let levels = |side: &mut dyn Iterator<Item = (f64, f64)>| {
+            side.take(depth)
+                .map(|(price, qty)| OrderBookLevel::new(price, qty, 1))
+                .collect()
+        };
+        OrderBookSnapshot {
+            symbol: self.symbol.clone(),
+            bids: levels(&mut self.bids()),
+            asks: levels(&mut self.asks()),
+            timestamp,
+            sequence: self.sequence,
+            venue: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levels_stay_sorted() {
+        let mut book = LocalBook::new(Symbol::new("BTC-USD"));
+        book.set(OrderSide::Bid, 99.0, 1.0);
+        book.set(OrderSide::Bid, 100.0, 2.0);
+        book.set(OrderSide::Bid, 98.5, 1.0);
+        book.set(OrderSide::Ask, 101.0, 1.0);
+        book.set(OrderSide::Ask, 100.5, 3.0);
+        book.set(OrderSide::Bid, 98.5, 0.0);
+
+        let snapshot = book.snapshot(10, 0);
+        let bids: Vec<f64> = snapshot.bids.iter().map(|l| l.price.as_f64()).collect();
+        assert_eq!(bids, vec![100.0, 99.0]);
+        assert_eq!(snapshot.best_ask().unwrap().price.as_f64(), 100.5);
+        assert_eq!(snapshot.sequence, 1);
+
+        assert_eq!(book.snapshot(1, 0).bids.len(), 1);
+        book.truncate(1);
+        assert_eq!(book.bids().collect::<Vec<_>>(), vec![(100.0, 2.0)]);
+        assert_eq!(book.asks().collect::<Vec<_>>(), vec![(100.5, 3.0)]);
+        book.clear();
+        assert!(book.snapshot(10, 0).asks.is_empty());
+    }
+}
@@ -0,0 +1,416 @@
+//! Bybit v5 spot public topics (publicTrade, orderbook, tickers, kline)
+//!
+//! Spot tickers carry no best bid/ask, so those fields come from the local
+//! book built from the orderbook topic.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+
+use crate::book::LocalBook;
+use crate::symbols::{SymbolFormat, SymbolMapper};
+use crate::ws::{self, string_f64, string_levels, FrameDecoder, WsConnection};
+use crate::{AdapterResult, AdapterSession, HealthTracker, MarketDataAdapter, MessageStream};
+use dash_core::{
+    AdapterHealth, Candle, CandleInterval, OrderSide, Price, Quantity, Subscription, Symbol,
+    Ticker, Trade, TradeSide, WsMessage,
+};
+
+pub const BYBIT_WS_URL: &str = "wss://stream.bybit.com/v5/public/spot";
+
+/// Orderbook topic depth (one of Bybit's fixed spot depths)
+const BOOK_DEPTH: usize = 50;
+
+/// Bybit recommends a ping every 20s
+const PING_INTERVAL: Duration = Duration::from_secs(20);
+
+/// Kline topic interval
+fn bybit_interval(interval: CandleInterval) -> &'static str {
+    match interval {
+        CandleInterval::M1 => "1",
+        CandleInterval::M5 => "5",
+        CandleInterval::M15 => "15",
+        CandleInterval::M30 => "30",
+        CandleInterval::H1 => "60",
+        CandleInterval::H4 => "240",
+        CandleInterval::D1 => "D",
+        CandleInterval::W1 => "W",
+    }
+}
+
+pub struct BybitAdapter {
+    name: String,
+    endpoint: String,
+    health: HealthTracker,
+    symbols: SymbolMapper,
+}
+
+impl BybitAdapter {
+    pub fn new() -> Self {
+        Self::with_endpoint(BYBIT_WS_URL)
+    }
+
+    /// Connect somewhere other than the production endpoint (testnet, proxy)
+    pub fn with_endpoint(endpoint: impl Into<String>) -> Self {
+        Self {
+            name: "bybit".to_string(),
+            endpoint: endpoint.into(),
+            health: HealthTracker::new("bybit"),
+            symbols: SymbolMapper::new(SymbolFormat::Concat).usd_as("USDT"),
+        }
+    }
+
+    /// Pin canonical symbols to specific instruments on top of the venue rule
+    pub fn with_symbol_overrides(mut self, overrides: &[(Symbol, String)]) -> Self {
+        self.symbols = self.symbols.with_overrides(overrides);
+        self
+    }
+}
+
+impl Default for BybitAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MarketDataAdapter for BybitAdapter {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn connect(&self) -> BoxFuture<'static, AdapterResult<Box<dyn AdapterSession>>> {
+        let endpoint = self.endpoint.clone();
+        let health = self.health.clone();
+        let symbols = self.symbols.clone();
+        Box::pin(async move {
+            let ws = ws::connect(&endpoint).await?;
+            Ok(Box::new(BybitSession {
+                ws,
+                health,
+                symbols,
+                decoder: BybitDecoder::default(),
+            }) as Box<dyn AdapterSession>)
+        })
+    }
+
+    fn health(&self) -> AdapterHealth {
+        self.health.report(Instant::now())
+    }
+
+    fn instrument(&self, symbol: &Symbol) -> String {
+        self.symbols.to_venue(symbol)
+    }
+}
+
+struct BybitSession {
+    ws: WsConnection,
+    health: HealthTracker,
+    symbols: SymbolMapper,
+    decoder: BybitDecoder,
+}
+
+impl AdapterSession for BybitSession {
+    fn subscribe(&mut self, subscription: &Subscription) -> BoxFuture<'_, AdapterResult<()>> {
+        let instrument = self.symbols.to_venue(&subscription.symbol);
+        self.decoder.markets.insert(
+            instrument.clone(),
+            BybitMarket::new(subscription.symbol.clone(), subscription.interval),
+        );
+        let request = json!({
+            "op": "subscribe",
+            "args": [
+                format!("publicTrade.{}", instrument),
+                format!("orderbook.{}.{}", BOOK_DEPTH, instrument),
+                format!("tickers.{}", instrument),
+                format!("kline.{}.{}", bybit_interval(subscription.interval), instrument),
+            ],
+        });
+        Box::pin(async move { ws::send_json(&mut self.ws, &request).await })
+    }
+
+    fn into_stream(self: Box<Self>) -> MessageStream {
+        let session = *self;
+        session.health.track(ws::decode_frames(session.ws, session.decoder))
+    }
+}
+
+// ============================================================================
+// WIRE FORMAT
+// ============================================================================
+
+/// `{"topic":"publicTrade.BTCUSDT","type":"snapshot","ts":...,"data":...}`;
+/// op responses (subscribe, pong) have no topic
+#[derive(Deserialize)]
+struct Envelope {
+    topic: String,
+    #[serde(rename = "type", default)]
+    kind: String,
+    #[serde(default)]
+    ts: i64,
+    data: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct BybitTrade {
+    #[serde(rename = "i")]
+    id: String,
+    #[serde(rename = "T")]
+    time: i64,
+    /// Taker side (`Buy` / `Sell`)
+    #[serde(rename = "S")]
+    side: String,
+    #[serde(rename = "v", deserialize_with = "string_f64")]
+    size: f64,
+    #[serde(rename = "p", deserialize_with = "string_f64")]
+    price: f64,
+}
+
+#[derive(Deserialize)]
+struct BybitBook {
+    #[serde(rename = "b", deserialize_with = "string_levels")]
+    bids: Vec<(f64, f64)>,
+    #[serde(rename = "a", deserialize_with = "string_levels")]
+    asks: Vec<(f64, f64)>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitTicker {
+    #[serde(deserialize_with = "string_f64")]
+    last_price: f64,
+    #[serde(rename = "highPrice24h", deserialize_with = "string_f64")]
+    high: f64,
+    #[serde(rename = "lowPrice24h", deserialize_with = "string_f64")]
+    low: f64,
+    #[serde(rename = "prevPrice24h", deserialize_with = "string_f64")]
+    open: f64,
+    #[serde(rename = "volume24h", deserialize_with = "string_f64")]
+    volume: f64,
+    #[serde(rename = "turnover24h", deserialize_with = "string_f64")]
+    turnover: f64,
+}
+
+#[derive(Deserialize)]
+struct BybitKline {
+    start: i64,
+    #[serde(deserialize_with = "string_f64")]
+    open: f64,
+    #[serde(deserialize_with = "string_f64")]
+    close: f64,
+    #[serde(deserialize_with = "string_f64")]
+    high: f64,
+    #[serde(deserialize_with = "string_f64")]
+    low: f64,
+    #[serde(deserialize_with = "string_f64")]
+    volume: f64,
+    #[serde(deserialize_with = "string_f64")]
+    turnover: f64,
+    confirm: bool,
+}
+
+/// Per-instrument state rebuilt from the feed
+struct BybitMarket {
+    book: LocalBook,
+    interval: CandleInterval,
+}
+
+impl BybitMarket {
+    fn new(symbol: Symbol, interval: CandleInterval) -> Self {
+        Self {
+            book: LocalBook::new(symbol),
+            interval,
+        }
+    }
+}
+
+/// Normalizes topic pushes for the subscribed instruments
+#[derive(Default)]
+pub(crate) struct BybitDecoder {
+    markets: HashMap<String, BybitMarket>,
+}
+
+fn parse<T: DeserializeOwned>(data: serde_json::Value) -> Option<T> {
+    serde_json::from_value(data)
+        .map_err(|e| tracing::debug!("bybit: unexpected payload: {}", e))
+        .ok()
+}
+
+impl BybitDecoder {
+    fn normalize(&mut self, envelope: Envelope) -> Vec<WsMessage> {
+        // Instrument is the last topic segment (`kline.1.BTCUSDT`)
+        let Some((kind, instrument)) = envelope.topic.split_once('.') else { return Vec::new() };
+        let instrument = instrument.rsplit('.').next().unwrap_or(instrument);
+        let Some(market) = self.markets.get_mut(instrument) else { return Vec::new() };
+        let symbol = market.book.symbol().clone();
+
+        match kind {
+            "publicTrade" => parse::<Vec<BybitTrade>>(envelope.data)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|t| {
+                    WsMessage::Trade(Trade {
+                        id: t.id,
+                        symbol: symbol.clone(),
+                        price: Price::new(t.price),
+                        quantity: Quantity::new(t.size),
+                        side: if t.side == "Sell" { TradeSide::Sell } else { TradeSide::Buy },
+                        timestamp: DateTime::from_timestamp_millis(t.time).unwrap_or_else(Utc::now),
+                        maker_order_id: None,
+                        taker_order_id: None,
+                        venue: None,
+                    })
+                })
+                .collect(),
+            "orderbook" => {
+                let Some(book) = parse::<BybitBook>(envelope.data) else { return Vec::new() };
+                if envelope.kind == "snapshot" {
+                    market.book.clear();
+                }
+                for (price, qty) in book.bids {
+                    market.book.set(OrderSide::Bid, price, qty);
+                }
+                for (price, qty) in book.asks {
+                    market.book.set(OrderSide::Ask, price, qty);
+                }
+                vec![WsMessage::OrderBook(market.book.snapshot(BOOK_DEPTH, envelope.ts))]
+            }
+            "tickers" => {
+                let Some(t) = parse::<BybitTicker>(envelope.data) else { return Vec::new() };
+                let (bid, bid_qty) = market.book.bids().next().unwrap_or((t.last_price, 0.0));
+                let (ask, ask_qty) = market.book.asks().next().unwrap_or((t.last_price, 0.0));
+                let change = t.last_price - t.open;
+                vec![WsMessage::Ticker(Ticker {
+                    symbol,
+                    last_price: Price::new(t.last_price),
+                    bid_price: Price::new(bid),
+                    bid_qty: Quantity::new(bid_qty),
+                    ask_price: Price::new(ask),
+                    ask_qty: Quantity::new(ask_qty),
+                    high_24h: Price::new(t.high),
+                    low_24h: Price::new(t.low),
+                    volume_24h: Quantity::new(t.volume),
+                    quote_volume_24h: t.turnover,
+                    change_24h: change,
+                    change_percent_24h: if t.open > 0.0 { change / t.open * 100.0 } else { 0.0 },
+                    open_24h: Price::new(t.open),
+                    trade_count_24h: 0,
+                    timestamp: envelope.ts,
+                })]
+            }
+            "kline" => parse::<Vec<BybitKline>>(envelope.data)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|k| {
+                    WsMessage::Candle(Candle {
+                        symbol: symbol.clone(),
+                        interval: market.interval,
+                        timestamp: k.start,
+                        open: Price::new(k.open),
+                        high: Price::new(k.high),
+                        low: Price::new(k.low),
+                        close: Price::new(k.close),
+                        volume: Quantity::new(k.volume),
+                        quote_volume: k.turnover,
+                        // Bybit doesn't report a trade count per kline
+                        trade_count: 0,
+                        buy_volume: None,
+                        sell_volume: None,
+                        is_closed: k.confirm,
+                        synthetic: false,
+                    })
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl FrameDecoder for BybitDecoder {
+    fn decode(&mut self, text: &str) -> Vec<WsMessage> {
+        match serde_json::from_str::<Envelope>(text) {
+            Ok(envelope) => self.normalize(envelope),
+            Err(_) => {
+                if text.contains("\"success\":false") {
+                    tracing::warn!("bybit: {}", text);
+                }
+                Vec::new()
+            }
+        }
+    }
+
+    fn keepalive(&self) -> Option<(Duration, String)> {
+        Some((PING_INTERVAL, json!({ "op": "ping" }).to_string()))
+    }
+}
+
+/// Decoder subscribed to BTC-USD, for tests and the fuzz targets
+#[cfg(any(test, feature = "fuzzing"))]
+pub(crate) fn decoder() -> BybitDecoder {
+    let mut decoder = BybitDecoder::default();
+    decoder
+        .markets
+        .insert("BTCUSDT".into(), BybitMarket::new(Symbol::new("BTC-USD"), CandleInterval::M1));
+    decoder
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbol_mapping() {
+        let adapter = BybitAdapter::new();
+        assert_eq!(adapter.instrument(&Symbol::new("BTC-USD")), "BTCUSDT");
+        assert_eq!(adapter.instrument(&Symbol::new("ETH-BTC")), "ETHBTC");
+        assert_eq!(bybit_interval(CandleInterval::H4), "240");
+    }
+
+    #[test]
+    fn test_decode_topics() {
+        let mut decoder = decoder();
+        let trades = decoder.decode(
+            r#"{"topic":"publicTrade.BTCUSDT","type":"snapshot","ts":1704067200000,"data":[
+            {"T":1704067200000,"s":"BTCUSDT","S":"Sell","v":"0.01","p":"95000.5","L":"MinusTick","i":"abc","BT":false}]}"#,
+        );
+        let [WsMessage::Trade(trade)] = trades.as_slice() else { panic!("unexpected {:?}", trades) };
+        assert_eq!(trade.side, TradeSide::Sell);
+        assert_eq!(trade.id, "abc");
+
+        decoder.decode(
+            r#"{"topic":"orderbook.50.BTCUSDT","type":"snapshot","ts":1,"data":{"s":"BTCUSDT",
+            "b":[["100","1"],["99","2"]],"a":[["101","3"]],"u":1,"seq":10}}"#,
+        );
+        let delta = decoder.decode(
+            r#"{"topic":"orderbook.50.BTCUSDT","type":"delta","ts":2,"data":{"s":"BTCUSDT",
+            "b":[["100","0"]],"a":[["100.5","1"]],"u":2,"seq":11}}"#,
+        );
+        let [WsMessage::OrderBook(book)] = delta.as_slice() else { panic!("unexpected {:?}", delta) };
+        assert_eq!(book.spread(), Some(1.5));
+
+        let ticker = decoder.decode(
+            r#"{"topic":"tickers.BTCUSDT","type":"snapshot","ts":3,"cs":1,"data":{"symbol":"BTCUSDT",
+            "lastPrice":"101","highPrice24h":"110","lowPrice24h":"90","prevPrice24h":"100","volume24h":"50",
+            "turnover24h":"5000","price24hPcnt":"0.01","usdIndexPrice":"101"}}"#,
+        );
+        let [WsMessage::Ticker(ticker)] = ticker.as_slice() else { panic!("unexpected {:?}", ticker) };
+        assert_eq!(ticker.bid_price.as_f64(), 99.0);
+        assert_eq!(ticker.ask_price.as_f64(), 100.5);
+        assert_eq!(ticker.change_percent_24h, 1.0);
+
+        let kline = decoder.decode(
+            r#"{"topic":"kline.1.BTCUSDT","type":"snapshot","ts":4,"data":[{"start":1704067200000,
+            "end":1704067259999,"interval":"1","open":"1","close":"2","high":"3","low":"0.5","volume":"4",
+            "turnover":"6","confirm":true,"timestamp":1704067260000}]}"#,
+        );
+        let [WsMessage::Candle(candle)] = kline.as_slice() else { panic!("unexpected {:?}", kline) };
+        assert!(candle.is_closed);
+        assert_eq!(candle.symbol.as_str(), "BTC-USD");
+
+        assert!(decoder.decode(r#"{"success":true,"ret_msg":"pong","op":"ping"}"#).is_empty());
+    }
+}
@@ -0,0 +1,81 @@
+//! Candles built from trades, for venues without a usable candle channel
+
+use dash_core::{Candle, CandleInterval, Trade, WsMessage};
+
+/// Forming candle for one symbol at a fixed interval
+#[derive(Debug, Clone)]
+pub struct TradeCandles {
+    interval: CandleInterval,
+    candle: Option<Candle>,
+}
+
+impl TradeCandles {
+    pub fn new(interval: CandleInterval) -> Self {
+        Self { interval, candle: None }
+    }
+
+    pub fn interval(&self) -> CandleInterval {
+        self.interval
+    }
+
+    /// Fold a trade in; returns the closed candle on rollover, then the
+    /// forming one. Late trades for an already closed candle are dropped.
+    pub fn update(&mut self, trade: &Trade) -> Vec<WsMessage> {
+        let open_time = self.interval.open_time(trade.timestamp.timestamp_millis());
+        let price = trade.price.as_f64();
+
+        let mut out = Vec::with_capacity(2);
+        match self.candle.take() {
+            Some(candle) if candle.timestamp > open_time => {
+                self.candle = Some(candle);
+                return out;
+            }
+            Some(candle) if candle.timestamp == open_time => self.candle = Some(candle),
+            Some(mut previous) => {
+                previous.close_candle();
+                out.push(WsMessage::Candle(previous));
+            }
+            None => {}
+        }
+
+        let candle = self
+            .candle
+            .get_or_insert_with(|| Candle::new(trade.symbol.clone(), self.interval, open_time, price));
+        candle.add_trade(trade);
+        out.push(WsMessage::Candle(candle.clone()));
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+    use dash_core::{Symbol, TradeSide};
+
+    fn trade(secs: i64, price: f64) -> Trade {
+        let mut trade = Trade::new(Symbol::new("BTC-USD"), price, 0.5, TradeSide::Buy);
+        trade.timestamp = DateTime::from_timestamp(secs, 0).unwrap();
+        trade
+    }
+
+    #[test]
+    fn test_rollover_and_late_trades() {
+        let mut candles = TradeCandles::new(CandleInterval::M1);
+        assert_eq!(candles.update(&trade(10, 100.0)).len(), 1);
+        candles.update(&trade(30, 101.0));
+
+        let rollover = candles.update(&trade(65, 99.0));
+        let [WsMessage::Candle(closed), WsMessage::Candle(forming)] = rollover.as_slice() else {
+            panic!("unexpected {:?}", rollover)
+        };
+        assert!(closed.is_closed);
+        assert_eq!(closed.high.as_f64(), 101.0);
+        assert_eq!(closed.trade_count, 2);
+        assert_eq!(forming.open.as_f64(), 99.0);
+        assert_eq!(forming.volume.as_f64(), 0.5);
+        assert_eq!(closed.volume_split(), Some((1.0, 0.0)));
+
+        assert!(candles.update(&trade(50, 500.0)).is_empty());
+    }
+}
@@ -0,0 +1,369 @@
+//! Coinbase Exchange public feed (matches, ticker, level2_batch)
+//!
+//! The feed has no candle channel, so candles are built from matches at the
+//! subscribed interval (`TradeCandles`). Level 2 updates are applied to a
+//! local book and published as top-of-book snapshots.
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::book::LocalBook;
+use crate::candles::TradeCandles;
+use crate::symbols::{SymbolFormat, SymbolMapper};
+use crate::ws::{self, string_f64, string_levels, FrameDecoder, WsConnection};
+use crate::{AdapterResult, AdapterSession, HealthTracker, MarketDataAdapter, MessageStream};
+use dash_core::{
+    AdapterHealth, CandleInterval, OrderSide, Price, Quantity, Subscription, Symbol,
+    Ticker, Trade, TradeSide, WsMessage,
+};
+
+pub const COINBASE_WS_URL: &str = "wss://ws-feed.exchange.coinbase.com";
+
+/// Book levels per side published from the local book
+const BOOK_DEPTH: usize = 20;
+
+pub struct CoinbaseAdapter {
+    name: String,
+    endpoint: String,
+    health: HealthTracker,
+    symbols: SymbolMapper,
+}
+
+impl CoinbaseAdapter {
+    pub fn new() -> Self {
+        Self::with_endpoint(COINBASE_WS_URL)
+    }
+
+    /// Connect somewhere other than the production endpoint (sandbox, proxy)
+    pub fn with_endpoint(endpoint: impl Into<String>) -> Self {
+        Self {
+            name: "coinbase".to_string(),
+            endpoint: endpoint.into(),
+            health: HealthTracker::new("coinbase"),
+            symbols: SymbolMapper::new(SymbolFormat::Dashed),
+        }
+    }
+
+    /// Pin canonical symbols to specific instruments on top of the venue rule
+    pub fn with_symbol_overrides(mut self, overrides: &[(Symbol, String)]) -> Self {
+        self.symbols = self.symbols.with_overrides(overrides);
+        self
+    }
+}
+
+impl Default for CoinbaseAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MarketDataAdapter for CoinbaseAdapter {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn connect(&self) -> BoxFuture<'static, AdapterResult<Box<dyn AdapterSession>>> {
+        let endpoint = self.endpoint.clone();
+        let health = self.health.clone();
+        let symbols = self.symbols.clone();
+        Box::pin(async move {
+            let ws = ws::connect(&endpoint).await?;
+            Ok(Box::new(CoinbaseSession {
+                ws,
+                health,
+                symbols,
+                decoder: CoinbaseDecoder::default(),
+            }) as Box<dyn AdapterSession>)
+        })
+    }
+
+    fn health(&self) -> AdapterHealth {
+        self.health.report(Instant::now())
+    }
+
+    fn instrument(&self, symbol: &Symbol) -> String {
+        self.symbols.to_venue(symbol)
+    }
+}
+
+struct CoinbaseSession {
+    ws: WsConnection,
+    health: HealthTracker,
+    symbols: SymbolMapper,
+    decoder: CoinbaseDecoder,
+}
+
+impl AdapterSession for CoinbaseSession {
+    fn subscribe(&mut self, subscription: &Subscription) -> BoxFuture<'_, AdapterResult<()>> {
+        let product = self.symbols.to_venue(&subscription.symbol);
+        self.decoder.markets.insert(
+            product.clone(),
+            CoinbaseMarket::new(subscription.symbol.clone(), subscription.interval),
+        );
+        let request = json!({
+            "type": "subscribe",
+            "product_ids": [product],
+            "channels": ["matches", "ticker", "level2_batch"],
+        });
+        Box::pin(async move { ws::send_json(&mut self.ws, &request).await })
+    }
+
+    fn into_stream(self: Box<Self>) -> MessageStream {
+        let session = *self;
+        session.health.track(ws::decode_frames(session.ws, session.decoder))
+    }
+}
+
+// ============================================================================
+// WIRE FORMAT
+// ============================================================================
+
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum CoinbaseMessage {
+    #[serde(alias = "last_match")]
+    Match(CoinbaseMatch),
+    Ticker(CoinbaseTicker),
+    Snapshot(CoinbaseSnapshot),
+    L2update(CoinbaseL2Update),
+    Error { message: String },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Deserialize)]
+struct CoinbaseMatch {
+    trade_id: u64,
+    product_id: String,
+    /// Maker side; the aggressor traded the other way
+    side: String,
+    #[serde(deserialize_with = "string_f64")]
+    size: f64,
+    #[serde(deserialize_with = "string_f64")]
+    price: f64,
+    time: DateTime<Utc>,
+    maker_order_id: Option<String>,
+    taker_order_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct CoinbaseTicker {
+    product_id: String,
+    #[serde(deserialize_with = "string_f64")]
+    price: f64,
+    #[serde(deserialize_with = "string_f64")]
+    open_24h: f64,
+    #[serde(deserialize_with = "string_f64")]
+    volume_24h: f64,
+    #[serde(deserialize_with = "string_f64")]
+    low_24h: f64,
+    #[serde(deserialize_with = "string_f64")]
+    high_24h: f64,
+    #[serde(deserialize_with = "string_f64")]
+    best_bid: f64,
+    #[serde(default, deserialize_with = "string_f64")]
+    best_bid_size: f64,
+    #[serde(deserialize_with = "string_f64")]
+    best_ask: f64,
+    #[serde(default, deserialize_with = "string_f64")]
+    best_ask_size: f64,
+    time: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize)]
+struct CoinbaseSnapshot {
+    product_id: String,
+    #[serde(deserialize_with = "string_levels")]
+    bids: Vec<(f64, f64)>,
+    #[serde(deserialize_with = "string_levels")]
+    asks: Vec<(f64, f64)>,
+}
+
+#[derive(Deserialize)]
+struct CoinbaseL2Update {
+    product_id: String,
+    /// `["buy" | "sell", price, size]`
+    changes: Vec<(String, String, String)>,
+    time: Option<DateTime<Utc>>,
+}
+
+/// Per-product state rebuilt from the feed
+struct CoinbaseMarket {
+    book: LocalBook,
+    candles: TradeCandles,
+}
+
+impl CoinbaseMarket {
+    fn new(symbol: Symbol, interval: CandleInterval) -> Self {
+        Self {
+            book: LocalBook::new(symbol),
+            candles: TradeCandles::new(interval),
+        }
+    }
+}
+
+/// Normalizes feed messages for the subscribed products
+#[derive(Default)]
+pub(crate) struct CoinbaseDecoder {
+    markets: HashMap<String, CoinbaseMarket>,
+}
+
+impl CoinbaseDecoder {
+    fn normalize(&mut self, msg: CoinbaseMessage) -> Vec<WsMessage> {
+        match msg {
+            CoinbaseMessage::Match(m) => {
+                let Some(market) = self.markets.get_mut(&m.product_id) else { return Vec::new() };
+                let trade = Trade {
+                    id: m.trade_id.to_string(),
+                    symbol: market.book.symbol().clone(),
+                    price: Price::new(m.price),
+                    quantity: Quantity::new(m.size),
+                    side: if m.side == "sell" { TradeSide::Buy } else { TradeSide::Sell },
+                    timestamp: m.time,
+                    maker_order_id: m.maker_order_id,
+                    taker_order_id: m.taker_order_id,
+                    venue: None,
+                };
+                let mut out = market.candles.update(&trade);
+                out.push(WsMessage::Trade(trade));
+                out
+            }
+            CoinbaseMessage::Ticker(t) => {
+                let Some(market) = self.markets.get(&t.product_id) else { return Vec::new() };
+                let change = t.price - t.open_24h;
+                vec![WsMessage::Ticker(Ticker {
+                    symbol: market.book.symbol().clone(),
+                    last_price: Price::new(t.price),
+                    bid_price: Price::new(t.best_bid),
+                    bid_qty: Quantity::new(t.best_bid_size),
+                    ask_price: Price::new(t.best_ask),
+                    ask_qty: Quantity::new(t.best_ask_size),
+                    high_24h: Price::new(t.high_24h),
+                    low_24h: Price::new(t.low_24h),
+                    volume_24h: Quantity::new(t.volume_24h),
+                    quote_volume_24h: t.volume_24h * t.price,
+                    change_24h: change,
+                    change_percent_24h: if t.open_24h > 0.0 { change / t.open_24h * 100.0 } else { 0.0 },
+                    open_24h: Price::new(t.open_24h),
+                    trade_count_24h: 0,
+                    timestamp: t.time.unwrap_or_else(Utc::now).timestamp_millis(),
+                })]
+            }
+            CoinbaseMessage::Snapshot(s) => {
+                let Some(market) = self.markets.get_mut(&s.product_id) else { return Vec::new() };
+                market.book.clear();
+                for (price, qty) in s.bids {
+                    market.book.set(OrderSide::Bid, price, qty);
+                }
+                for (price, qty) in s.asks {
+                    market.book.set(OrderSide::Ask, price, qty);
+                }
+                vec![WsMessage::OrderBook(market.book.snapshot(BOOK_DEPTH, Utc::now().timestamp_millis()))]
+            }
+            CoinbaseMessage::L2update(update) => {
+                let Some(market) = self.markets.get_mut(&update.product_id) else { return Vec::new() };
+                for (side, price, size) in &update.changes {
+                    let side = if side == "buy" { OrderSide::Bid } else { OrderSide::Ask };
+                    if let (Ok(price), Ok(size)) = (price.parse(), size.parse()) {
+                        market.book.set(side, price, size);
+                    }
+                }
+                let timestamp = update.time.unwrap_or_else(Utc::now).timestamp_millis();
+                vec![WsMessage::OrderBook(market.book.snapshot(BOOK_DEPTH, timestamp))]
+            }
+            CoinbaseMessage::Error { message } => {
+                tracing::warn!("coinbase: {}", message);
+                Vec::new()
+            }
+            CoinbaseMessage::Other => Vec::new(),
+        }
+    }
+}
+
+impl FrameDecoder for CoinbaseDecoder {
+    fn decode(&mut self, text: &str) -> Vec<WsMessage> {
+        match serde_json::from_str::<CoinbaseMessage>(text) {
+            Ok(msg) => self.normalize(msg),
+            Err(e) => {
+                tracing::debug!("coinbase: unexpected payload: {}", e);
+                Vec::new()
+            }
+        }
+    }
+}
+
+/// Decoder subscribed to BTC-USD, for tests and the fuzz targets
+#[cfg(any(test, feature = "fuzzing"))]
+pub(crate) fn decoder() -> CoinbaseDecoder {
+    let mut decoder = CoinbaseDecoder::default();
+    decoder
+        .markets
+        .insert("BTC-USD".into(), CoinbaseMarket::new(Symbol::new("BTC-USD"), CandleInterval::M1));
+    decoder
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade_frame(id: u64, price: &str, time: &str) -> String {
+        format!(
+            r#"{{"type":"match","trade_id":{},"maker_order_id":"m","taker_order_id":"t","side":"sell",
+            "size":"0.5","price":"{}","product_id":"BTC-USD","sequence":1,"time":"{}"}}"#,
+            id, price, time
+        )
+    }
+
+    #[test]
+    fn test_matches_build_candles() {
+        let mut decoder = decoder();
+        let first = decoder.decode(&trade_frame(1, "95000", "2024-01-01T00:00:10.000000Z"));
+        let [WsMessage::Candle(candle), WsMessage::Trade(trade)] = first.as_slice() else {
+            panic!("unexpected {:?}", first)
+        };
+        // Maker sold, so the taker bought
+        assert_eq!(trade.side, TradeSide::Buy);
+        assert_eq!(trade.maker_order_id.as_deref(), Some("m"));
+        assert_eq!(candle.volume.as_f64(), 0.5);
+
+        let rollover = decoder.decode(&trade_frame(2, "94900", "2024-01-01T00:01:05.000000Z"));
+        assert!(matches!(rollover.as_slice(), [WsMessage::Candle(c), WsMessage::Candle(_), WsMessage::Trade(_)] if c.is_closed));
+    }
+
+    #[test]
+    fn test_level2_maintains_book() {
+        let mut decoder = decoder();
+        decoder.decode(
+            r#"{"type":"snapshot","product_id":"BTC-USD","bids":[["100.00","1.0"],["99.00","2.0"]],
+            "asks":[["101.00","1.5"]]}"#,
+        );
+        let update = decoder.decode(
+            r#"{"type":"l2update","product_id":"BTC-USD","changes":[["buy","100.00","0"],["sell","100.50","0.4"]],
+            "time":"2024-01-01T00:00:00.000000Z"}"#,
+        );
+        let [WsMessage::OrderBook(book)] = update.as_slice() else { panic!("unexpected {:?}", update) };
+        assert_eq!(book.best_bid().unwrap().price.as_f64(), 99.0);
+        assert_eq!(book.best_ask().unwrap().price.as_f64(), 100.5);
+        assert_eq!(book.sequence, 2);
+
+        assert!(decoder.decode(r#"{"type":"subscriptions","channels":[]}"#).is_empty());
+    }
+
+    #[test]
+    fn test_ticker() {
+        let mut decoder = decoder();
+        let msgs = decoder.decode(
+            r#"{"type":"ticker","product_id":"BTC-USD","price":"101","open_24h":"100","volume_24h":"10",
+            "low_24h":"95","high_24h":"105","best_bid":"100.9","best_bid_size":"1","best_ask":"101.1",
+            "best_ask_size":"2","time":"2024-01-01T00:00:00.000000Z"}"#,
+        );
+        let [WsMessage::Ticker(ticker)] = msgs.as_slice() else { panic!("unexpected {:?}", msgs) };
+        assert_eq!(ticker.change_percent_24h, 1.0);
+        assert_eq!(ticker.quote_volume_24h, 1010.0);
+    }
+}
@@ -0,0 +1,110 @@
+//! Fuzzing entry points
+//!
+//! Runs arbitrary frames through every venue decoder. The cargo-fuzz targets
+//! under `fuzz/` reach this through the `fuzzing` feature; the tests below
+//! feed it truncated and corrupted copies of real venue frames.
+
+use dash_core::WsMessage;
+
+use crate::ws::FrameDecoder;
+use crate::{binance, bybit, coinbase, kraken, okx};
+
+/// One stateful decoder per venue, each subscribed to BTC-USD
+pub struct VenueDecoders {
+    decoders: Vec<Box<dyn FrameDecoder>>,
+}
+
+impl Default for VenueDecoders {
+    fn default() -> Self {
+        Self {
+            decoders: vec![
+                Box::new(binance::decoder()),
+                Box::new(bybit::decoder()),
+                Box::new(coinbase::decoder()),
+                Box::new(kraken::decoder()),
+                Box::new(okx::decoder()),
+            ],
+        }
+    }
+}
+
+impl VenueDecoders {
+    /// Decode `text` with every venue, draining any resync requests it
+    /// queues, and return all normalized messages
+    pub fn decode(&mut self, text: &str) -> Vec<WsMessage> {
+        self.decoders
+            .iter_mut()
+            .flat_map(|decoder| {
+                let messages = decoder.decode(text);
+                decoder.outgoing();
+                messages
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEED_FRAMES: &[&str] = &[
+        r#"{"stream":"btcusdt@trade","data":{"e":"trade","E":1700000000001,"s":"BTCUSDT","t":42,
+            "p":"95000.10","q":"0.5","T":1700000000000,"m":true,"M":true}}"#,
+        r#"{"stream":"btcusdt@depth20@100ms","data":{"lastUpdateId":7,"bids":[["95000.00","1.0"]],
+            "asks":[["95001.00","0.3"]]}}"#,
+        r#"{"topic":"publicTrade.BTCUSDT","type":"snapshot","ts":1704067200000,
+            "data":[{"T":1704067200000,"s":"BTCUSDT","S":"Sell","v":"0.01","p":"95000.5",
+            "L":"MinusTick","i":"abc","BT":false}]}"#,
+        r#"{"topic":"orderbook.50.BTCUSDT","type":"snapshot","ts":1,"data":{"s":"BTCUSDT",
+            "b":[["100","1"]],"a":[["101","3"]],"u":1,"seq":10}}"#,
+        r#"{"type":"snapshot","product_id":"BTC-USD","bids":[["100.00","1.0"]],
+            "asks":[["101.00","1.5"]]}"#,
+        r#"{"type":"l2update","product_id":"BTC-USD","changes":[["buy","100.00","0"]],
+            "time":"2024-01-01T00:00:00.000000Z"}"#,
+        r#"{"channel":"trade","type":"update","data":[{"symbol":"BTC/USD","side":"sell",
+            "price":95000.1,"qty":0.25,"ord_type":"market","trade_id":7,
+            "timestamp":"2024-01-01T00:00:00.000000Z"}]}"#,
+        r#"{"channel":"book","type":"snapshot","data":[{"symbol":"BTC/USD",
+            "bids":[{"price":100.0,"qty":1.0}],"asks":[],"checksum":1}]}"#,
+        r#"{"arg":{"channel":"trades","instId":"BTC-USDT"},"data":[{"instId":"BTC-USDT",
+            "tradeId":"9","px":"95000.5","sz":"0.1","side":"sell","ts":"1704067200000",
+            "count":"1"}]}"#,
+        r#"{"arg":{"channel":"books","instId":"BTC-USDT"},"action":"update","data":[{"asks":[],
+            "bids":[["95000","0","0","0"]],"ts":"1704067200100","checksum":0}]}"#,
+    ];
+
+    /// Every prefix of `frame`, plus copies with one byte bumped or one
+    /// number swapped for an extreme value
+    fn mangled(frame: &str) -> Vec<String> {
+        let mut frames: Vec<String> = (0..frame.len())
+            .filter(|&cut| frame.is_char_boundary(cut))
+            .map(|cut| frame[..cut].to_string())
+            .collect();
+        for at in 0..frame.len() {
+            let mut bytes = frame.as_bytes().to_vec();
+            bytes[at] = bytes[at].wrapping_add(1);
+            frames.extend(String::from_utf8(bytes).ok());
+        }
+        for extreme in ["-1", "0", "1e309", "\"NaN\"", "null", "18446744073709551616"] {
+            frames.push(frame.replace("95000", extreme).replace("100", extreme));
+        }
+        frames
+    }
+
+    #[test]
+    fn test_seed_frames_decode() {
+        let mut decoders = VenueDecoders::default();
+        let decoded = SEED_FRAMES.iter().filter(|f| !decoders.decode(f).is_empty()).count();
+        assert!(decoded >= SEED_FRAMES.len() / 2, "only {} seed frames decoded", decoded);
+    }
+
+    #[test]
+    fn test_mangled_frames_never_panic() {
+        let mut decoders = VenueDecoders::default();
+        for frame in SEED_FRAMES {
+            for text in mangled(frame) {
+                decoders.decode(&text);
+            }
+        }
+    }
+}
@@ -0,0 +1,565 @@
+//! Kraken WebSocket v2 public channels (trade, book, ticker, ohlc)
+//!
+//! Kraken streams book updates with a CRC32 checksum of the top ten levels.
+//! After applying each update the local book is checked against it; on a
+//! mismatch the book is dropped, publishing pauses and the book channel is
+//! resubscribed for a fresh snapshot, which restarts the sequence clients
+//! see. Checksums need the pair's price and quantity precision, taken from
+//! the instrument channel; until it arrives books pass unchecked.
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+
+use crate::book::LocalBook;
+use crate::symbols::{SymbolFormat, SymbolMapper};
+use crate::ws::{self, FrameDecoder, WsConnection};
+use crate::{AdapterResult, AdapterSession, HealthTracker, MarketDataAdapter, MessageStream};
+use dash_core::{
+    AdapterHealth, Candle, CandleInterval, OrderSide, Price, Quantity, Subscription, Symbol,
+    Ticker, Trade, TradeSide, WsMessage,
+};
+
+pub const KRAKEN_WS_URL: &str = "wss://ws.kraken.com/v2";
+
+/// Book depth subscribed (one of Kraken's fixed depths)
+const BOOK_DEPTH: usize = 25;
+
+/// Levels per side covered by the book checksum
+const CHECKSUM_LEVELS: usize = 10;
+
+/// OHLC interval in minutes
+fn kraken_interval(interval: CandleInterval) -> i64 {
+    interval.as_secs() / 60
+}
+
+pub struct KrakenAdapter {
+    name: String,
+    endpoint: String,
+    health: HealthTracker,
+    symbols: SymbolMapper,
+}
+
+impl KrakenAdapter {
+    pub fn new() -> Self {
+        Self::with_endpoint(KRAKEN_WS_URL)
+    }
+
+    /// Connect somewhere other than the production endpoint (proxy, replay)
+    pub fn with_endpoint(endpoint: impl Into<String>) -> Self {
+        Self {
+            name: "kraken".to_string(),
+            endpoint: endpoint.into(),
+            health: HealthTracker::new("kraken"),
+            symbols: SymbolMapper::new(SymbolFormat::Slashed),
+        }
+    }
+
+    /// Pin canonical symbols to specific instruments on top of the venue rule
+    pub fn with_symbol_overrides(mut self, overrides: &[(Symbol, String)]) -> Self {
+        self.symbols = self.symbols.with_overrides(overrides);
+        self
+    }
+}
+
+impl Default for KrakenAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MarketDataAdapter for KrakenAdapter {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn connect(&self) -> BoxFuture<'static, AdapterResult<Box<dyn AdapterSession>>> {
+        let endpoint = self.endpoint.clone();
+        let health = self.health.clone();
+        let symbols = self.symbols.clone();
+        Box::pin(async move {
+            let mut ws = ws::connect(&endpoint).await?;
+            // Pair precisions for checksum validation
+            ws::send_json(&mut ws, &json!({ "method": "subscribe", "params": { "channel": "instrument" } })).await?;
+            Ok(Box::new(KrakenSession {
+                ws,
+                health,
+                symbols,
+                decoder: KrakenDecoder::default(),
+            }) as Box<dyn AdapterSession>)
+        })
+    }
+
+    fn health(&self) -> AdapterHealth {
+        self.health.report(Instant::now())
+    }
+
+    fn instrument(&self, symbol: &Symbol) -> String {
+        self.symbols.to_venue(symbol)
+    }
+}
+
+struct KrakenSession {
+    ws: WsConnection,
+    health: HealthTracker,
+    symbols: SymbolMapper,
+    decoder: KrakenDecoder,
+}
+
+impl AdapterSession for KrakenSession {
+    fn subscribe(&mut self, subscription: &Subscription) -> BoxFuture<'_, AdapterResult<()>> {
+        let pair = self.symbols.to_venue(&subscription.symbol);
+        self.decoder.markets.insert(
+            pair.clone(),
+            KrakenMarket::new(subscription.symbol.clone(), subscription.interval),
+        );
+        let requests = [
+            json!({ "method": "subscribe", "params": { "channel": "trade", "symbol": [pair], "snapshot": false } }),
+            book_request("subscribe", &pair),
+            json!({ "method": "subscribe", "params": { "channel": "ticker", "symbol": [pair] } }),
+            json!({ "method": "subscribe", "params": {
+                "channel": "ohlc",
+                "symbol": [pair],
+                "interval": kraken_interval(subscription.interval),
+            } }),
+        ];
+        Box::pin(async move {
+            for request in &requests {
+                ws::send_json(&mut self.ws, request).await?;
+            }
+            Ok(())
+        })
+    }
+
+    fn into_stream(self: Box<Self>) -> MessageStream {
+        let session = *self;
+        session.health.track(ws::decode_frames(session.ws, session.decoder))
+    }
+}
+
+fn book_request(method: &str, pair: &str) -> serde_json::Value {
+    json!({ "method": method, "params": { "channel": "book", "symbol": [pair], "depth": BOOK_DEPTH } })
+}
+
+// ============================================================================
+// BOOK CHECKSUM
+// ============================================================================
+
+/// Price and quantity decimals of a pair
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Precision {
+    price: usize,
+    qty: usize,
+}
+
+/// Kraken's checksum field: fixed decimals, no point, no leading zeros
+fn checksum_field(value: f64, decimals: usize) -> String {
+    let formatted = format!("{:.*}", decimals, value).replace('.', "");
+    formatted.trim_start_matches('0').to_string()
+}
+
+/// CRC32 over the top asks (ascending) then the top bids (descending)
+fn book_checksum(book: &LocalBook, precision: Precision) -> u32 {
+    let mut payload = String::new();
+    let asks = book.asks().take(CHECKSUM_LEVELS);
+    let bids = book.bids().take(CHECKSUM_LEVELS);
+    for (price, qty) in asks.chain(bids) {
+        payload.push_str(&checksum_field(price, precision.price));
+        payload.push_str(&checksum_field(qty, precision.qty));
+    }
+    crc32fast::hash(payload.as_bytes())
+}
+
+// ============================================================================
+// WIRE FORMAT
+// ============================================================================
+
+/// `{"channel":"book","type":"update","data":[...]}`; acks and heartbeats
+/// carry no data
+#[derive(Deserialize)]
+struct Envelope {
+    channel: String,
+    #[serde(rename = "type", default)]
+    kind: String,
+    #[serde(default)]
+    data: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct KrakenTrade {
+    symbol: String,
+    /// Taker side
+    side: String,
+    price: f64,
+    qty: f64,
+    trade_id: u64,
+    timestamp: DateTime<Utc>,
+}
+
+#[derive(Deserialize)]
+struct KrakenLevel {
+    price: f64,
+    qty: f64,
+}
+
+#[derive(Deserialize)]
+struct KrakenBook {
+    symbol: String,
+    #[serde(default)]
+    bids: Vec<KrakenLevel>,
+    #[serde(default)]
+    asks: Vec<KrakenLevel>,
+    checksum: u32,
+    timestamp: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize)]
+struct KrakenTicker {
+    symbol: String,
+    bid: f64,
+    bid_qty: f64,
+    ask: f64,
+    ask_qty: f64,
+    last: f64,
+    volume: f64,
+    vwap: f64,
+    low: f64,
+    high: f64,
+    change: f64,
+    change_pct: f64,
+}
+
+#[derive(Deserialize)]
+struct KrakenOhlc {
+    symbol: String,
+    open: f64,
+    high: f64,
+    low: f64,
+    close: f64,
+    trades: u32,
+    volume: f64,
+    vwap: f64,
+    interval_begin: DateTime<Utc>,
+}
+
+#[derive(Deserialize)]
+struct KrakenInstruments {
+    #[serde(default)]
+    pairs: Vec<KrakenPair>,
+}
+
+#[derive(Deserialize)]
+struct KrakenPair {
+    symbol: String,
+    price_precision: usize,
+    qty_precision: usize,
+}
+
+/// Per-pair state rebuilt from the feed
+struct KrakenMarket {
+    book: LocalBook,
+    /// Checksum failed; updates are ignored until a fresh snapshot
+    resyncing: bool,
+    interval: CandleInterval,
+    candle: Option<Candle>,
+}
+
+impl KrakenMarket {
+    fn new(symbol: Symbol, interval: CandleInterval) -> Self {
+        Self {
+            book: LocalBook::new(symbol),
+            resyncing: false,
+            interval,
+            candle: None,
+        }
+    }
+}
+
+/// Normalizes channel messages for the subscribed pairs
+#[derive(Default)]
+pub(crate) struct KrakenDecoder {
+    markets: HashMap<String, KrakenMarket>,
+    precision: HashMap<String, Precision>,
+    outgoing: Vec<serde_json::Value>,
+    /// Checksum mismatches seen this session
+    resyncs: u64,
+}
+
+fn parse<T: DeserializeOwned>(data: serde_json::Value) -> Vec<T> {
+    serde_json::from_value(data)
+        .map_err(|e| tracing::debug!("kraken: unexpected payload: {}", e))
+        .unwrap_or_default()
+}
+
+impl KrakenDecoder {
+    fn trades(&mut self, data: serde_json::Value) -> Vec<WsMessage> {
+        parse::<KrakenTrade>(data)
+            .into_iter()
+            .filter_map(|t| {
+                let market = self.markets.get(&t.symbol)?;
+                Some(WsMessage::Trade(Trade {
+                    id: t.trade_id.to_string(),
+                    symbol: market.book.symbol().clone(),
+                    price: Price::new(t.price),
+                    quantity: Quantity::new(t.qty),
+                    side: if t.side == "sell" { TradeSide::Sell } else { TradeSide::Buy },
+                    timestamp: t.timestamp,
+                    maker_order_id: None,
+                    taker_order_id: None,
+                    venue: None,
+                }))
+            })
+            .collect()
+    }
+
+    fn books(&mut self, kind: &str, data: serde_json::Value) -> Vec<WsMessage> {
+        let mut out = Vec::new();
+        for update in parse::<KrakenBook>(data) {
+            let Some(market) = self.markets.get_mut(&update.symbol) else { continue };
+            if kind == "snapshot" {
+                market.book.clear();
+                market.resyncing = false;
+            } else if market.resyncing {
+                continue;
+            }
+
+            for level in &update.bids {
+                market.book.set(OrderSide::Bid, level.price, level.qty);
+            }
+            for level in &update.asks {
+                market.book.set(OrderSide::Ask, level.price, level.qty);
+            }
+            market.book.truncate(BOOK_DEPTH);
+
+            if let Some(precision) = self.precision.get(&update.symbol).copied() {
+                let local = book_checksum(&market.book, precision);
+                if local != update.checksum {
+                    self.resyncs += 1;
+                    tracing::warn!(
+                        "kraken: {} book checksum mismatch (local {}, venue {}), resyncing",
+                        update.symbol,
+                        local,
+                        update.checksum
+                    );
+                    market.book.clear();
+                    market.resyncing = true;
+                    self.outgoing.push(book_request("unsubscribe", &update.symbol));
+                    self.outgoing.push(book_request("subscribe", &update.symbol));
+                    continue;
+                }
+            }
+
+            let timestamp = update.timestamp.unwrap_or_else(Utc::now).timestamp_millis();
+            out.push(WsMessage::OrderBook(market.book.snapshot(BOOK_DEPTH, timestamp)));
+        }
+        out
+    }
+
+    fn tickers(&mut self, data: serde_json::Value) -> Vec<WsMessage> {
+        parse::<KrakenTicker>(data)
+            .into_iter()
+            .filter_map(|t| {
+                let market = self.markets.get(&t.symbol)?;
+                let open = t.last - t.change;
+                Some(WsMessage::Ticker(Ticker {
+                    symbol: market.book.symbol().clone(),
+                    last_price: Price::new(t.last),
+                    bid_price: Price::new(t.bid),
+                    bid_qty: Quantity::new(t.bid_qty),
+                    ask_price: Price::new(t.ask),
+                    ask_qty: Quantity::new(t.ask_qty),
+                    high_24h: Price::new(t.high),
+                    low_24h: Price::new(t.low),
+                    volume_24h: Quantity::new(t.volume),
+                    quote_volume_24h: t.volume * t.vwap,
+                    change_24h: t.change,
+                    change_percent_24h: t.change_pct,
+                    open_24h: Price::new(open),
+                    trade_count_24h: 0,
+                    timestamp: Utc::now().timestamp_millis(),
+                }))
+            })
+            .collect()
+    }
+
+    /// OHLC updates; a new interval closes the previous candle
+    fn candles(&mut self, data: serde_json::Value) -> Vec<WsMessage> {
+        let mut out = Vec::new();
+        for bar in parse::<KrakenOhlc>(data) {
+            let Some(market) = self.markets.get_mut(&bar.symbol) else { continue };
+            let candle = Candle {
+                symbol: market.book.symbol().clone(),
+                interval: market.interval,
+                timestamp: bar.interval_begin.timestamp_millis(),
+                open: Price::new(bar.open),
+                high: Price::new(bar.high),
+                low: Price::new(bar.low),
+                close: Price::new(bar.close),
+                volume: Quantity::new(bar.volume),
+                quote_volume: bar.volume * bar.vwap,
+                trade_count: bar.trades,
+                buy_volume: None,
+                sell_volume: None,
+                is_closed: false,
+                synthetic: false,
+            };
+            if let Some(mut previous) = market.candle.take()
+                && previous.timestamp < candle.timestamp
+            {
+                previous.close_candle();
+                out.push(WsMessage::Candle(previous));
+            }
+            market.candle = Some(candle.clone());
+            out.push(WsMessage::Candle(candle));
+        }
+        out
+    }
+
+    fn instruments(&mut self, data: serde_json::Value) {
+        let Ok(instruments) = serde_json::from_value::<KrakenInstruments>(data) else { return };
+        for pair in instruments.pairs {
+            self.precision.insert(
+                pair.symbol,
+                Precision {
+                    price: pair.price_precision,
+                    qty: pair.qty_precision,
+                },
+            );
+        }
+    }
+}
+
+impl FrameDecoder for KrakenDecoder {
+    fn decode(&mut self, text: &str) -> Vec<WsMessage> {
+        // Method acks (`{"method":"subscribe",...}`) have no channel
+        let Ok(envelope) = serde_json::from_str::<Envelope>(text) else { return Vec::new() };
+        match envelope.channel.as_str() {
+            "trade" => self.trades(envelope.data),
+            "book" => self.books(&envelope.kind, envelope.data),
+            "ticker" => self.tickers(envelope.data),
+            "ohlc" => self.candles(envelope.data),
+            "instrument" => {
+                self.instruments(envelope.data);
+                Vec::new()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn outgoing(&mut self) -> Vec<serde_json::Value> {
+        std::mem::take(&mut self.outgoing)
+    }
+}
+
+/// Decoder subscribed to BTC-USD, for tests and the fuzz targets
+#[cfg(any(test, feature = "fuzzing"))]
+pub(crate) fn decoder() -> KrakenDecoder {
+    let mut decoder = KrakenDecoder::default();
+    decoder
+        .markets
+        .insert("BTC/USD".into(), KrakenMarket::new(Symbol::new("BTC-USD"), CandleInterval::M1));
+    decoder.decode(
+        r#"{"channel":"instrument","type":"snapshot","data":{"assets":[],"pairs":[
+        {"symbol":"BTC/USD","base":"BTC","quote":"USD","price_precision":1,"qty_precision":8}]}}"#,
+    );
+    decoder
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn book_frame(kind: &str, bids: &str, asks: &str, checksum: u32) -> String {
+        format!(
+            r#"{{"channel":"book","type":"{}","data":[{{"symbol":"BTC/USD","bids":[{}],"asks":[{}],"checksum":{}}}]}}"#,
+            kind, bids, asks, checksum
+        )
+    }
+
+    /// Checksum of the book the decoder would hold after `levels`
+    fn expected(bids: &[(f64, f64)], asks: &[(f64, f64)]) -> u32 {
+        let mut book = LocalBook::new(Symbol::new("BTC-USD"));
+        bids.iter().for_each(|(p, q)| book.set(OrderSide::Bid, *p, *q));
+        asks.iter().for_each(|(p, q)| book.set(OrderSide::Ask, *p, *q));
+        book_checksum(&book, Precision { price: 1, qty: 8 })
+    }
+
+    #[test]
+    fn test_checksum_fields() {
+        assert_eq!(checksum_field(45285.2, 1), "452852");
+        assert_eq!(checksum_field(0.001, 8), "100000");
+        assert_eq!(checksum_field(12.5, 8), "1250000000");
+    }
+
+    #[test]
+    fn test_book_checksum_and_resync() {
+        let mut decoder = decoder();
+        let checksum = expected(&[(100.0, 1.0)], &[(101.0, 2.0)]);
+        let snapshot = book_frame("snapshot", r#"{"price":100.0,"qty":1.0}"#, r#"{"price":101.0,"qty":2.0}"#, checksum);
+        let msgs = decoder.decode(&snapshot);
+        let [WsMessage::OrderBook(book)] = msgs.as_slice() else { panic!("no book") };
+        assert_eq!(book.sequence, 1);
+        assert!(decoder.outgoing().is_empty());
+
+        let checksum = expected(&[(100.0, 1.0), (99.5, 0.5)], &[(101.0, 2.0)]);
+        let update = book_frame("update", r#"{"price":99.5,"qty":0.5}"#, "", checksum);
+        let msgs = decoder.decode(&update);
+        let [WsMessage::OrderBook(book)] = msgs.as_slice() else { panic!("no book") };
+        assert_eq!(book.bids.len(), 2);
+
+        // Venue and local book disagree: drop the book and resubscribe
+        let bad = book_frame("update", r#"{"price":99.0,"qty":3.0}"#, "", 12345);
+        assert!(decoder.decode(&bad).is_empty());
+        assert_eq!(decoder.resyncs, 1);
+        let requests = decoder.outgoing();
+        assert_eq!(requests[0]["method"], "unsubscribe");
+        assert_eq!(requests[1]["method"], "subscribe");
+        assert_eq!(requests[1]["params"]["channel"], "book");
+
+        // Updates are ignored until the fresh snapshot arrives
+        assert!(decoder.decode(&update).is_empty());
+        let checksum = expected(&[(100.0, 1.0)], &[(101.0, 2.0)]);
+        let snapshot = book_frame("snapshot", r#"{"price":100.0,"qty":1.0}"#, r#"{"price":101.0,"qty":2.0}"#, checksum);
+        assert_eq!(decoder.decode(&snapshot).len(), 1);
+    }
+
+    #[test]
+    fn test_trades_tickers_and_candles() {
+        let mut decoder = decoder();
+        let trades = decoder.decode(
+            r#"{"channel":"trade","type":"update","data":[{"symbol":"BTC/USD","side":"sell","price":95000.1,
+            "qty":0.25,"ord_type":"market","trade_id":7,"timestamp":"2024-01-01T00:00:00.000000Z"}]}"#,
+        );
+        let [WsMessage::Trade(trade)] = trades.as_slice() else { panic!("unexpected {:?}", trades) };
+        assert_eq!(trade.side, TradeSide::Sell);
+        assert_eq!(trade.symbol.as_str(), "BTC-USD");
+
+        let ticker = decoder.decode(
+            r#"{"channel":"ticker","type":"snapshot","data":[{"symbol":"BTC/USD","bid":99.9,"bid_qty":1.0,
+            "ask":100.1,"ask_qty":2.0,"last":100.0,"volume":10.0,"vwap":98.0,"low":95.0,"high":105.0,
+            "change":2.0,"change_pct":2.04}]}"#,
+        );
+        let [WsMessage::Ticker(ticker)] = ticker.as_slice() else { panic!("unexpected {:?}", ticker) };
+        assert_eq!(ticker.open_24h.as_f64(), 98.0);
+
+        let bar = |begin: &str| {
+            format!(
+                r#"{{"channel":"ohlc","type":"update","data":[{{"symbol":"BTC/USD","open":1.0,"high":2.0,
+                "low":0.5,"close":1.5,"trades":3,"volume":4.0,"vwap":1.2,"interval_begin":"{}","interval":1}}]}}"#,
+                begin
+            )
+        };
+        assert_eq!(decoder.decode(&bar("2024-01-01T00:00:00Z")).len(), 1);
+        let rollover = decoder.decode(&bar("2024-01-01T00:01:00Z"));
+        let [WsMessage::Candle(closed), WsMessage::Candle(forming)] = rollover.as_slice() else {
+            panic!("unexpected {:?}", rollover)
+        };
+        assert!(closed.is_closed && !forming.is_closed);
+        assert_eq!(forming.timestamp - closed.timestamp, 60_000);
+    }
+}
@@ -0,0 +1,33 @@
+//! # dash-adapters
+//!
+//! Exchange market data adapters for the BTC Exchange Dashboard.
+//! Each venue implements the `MarketDataAdapter` strategy and normalizes
+//! its wire format into dash-core types; the registry lets the server
+//! pick adapters by name without knowing about individual venues.
+
+pub mod adapter;
+pub mod binance;
+pub mod bybit;
+pub mod book;
+pub mod candles;
+pub mod coinbase;
+#[cfg(any(test, feature = "fuzzing"))]
+pub mod fuzzing;
+pub mod kraken;
+pub mod mock;
+pub mod okx;
+pub mod registry;
+pub mod symbols;
+pub mod ws;
+
+pub use adapter::*;
+pub use binance::BinanceAdapter;
+pub use bybit::BybitAdapter;
+pub use book::LocalBook;
+pub use candles::TradeCandles;
+pub use coinbase::CoinbaseAdapter;
+pub use kraken::KrakenAdapter;
+pub use mock::MockAdapter;
+pub use okx::OkxAdapter;
+pub use registry::*;
+pub use symbols::*;
@@ -0,0 +1,494 @@
+//! Mock data engine for demo/development
+//!
+//! An operator can push the mock market into a scenario (rally, crash,
+//! volatility, stalled feed) for a while; its venues share the scenario.
+//! A seeded mock walks the same price path every run.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use futures::future::BoxFuture;
+use futures::{stream, StreamExt};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use tokio::sync::mpsc;
+use tokio::time::interval;
+
+use crate::{AdapterResult, AdapterSession, HealthTracker, MarketDataAdapter, MessageStream};
+use dash_core::{
+    AdapterHealth, Candle, CandleInterval, MarketDepth, MockScenario, OrderBookLevel,
+    OrderBookSnapshot, Price, Quantity, Subscription, Symbol, Ticker, Trade, TradeSide, WsMessage,
+};
+
+/// Last price per symbol of the leading mock, shared with its venues
+type ReferencePrices = Arc<Mutex<HashMap<Symbol, f64>>>;
+
+/// Running scenario and when it ends, shared by a mock and its venues
+type ActiveScenario = Arc<Mutex<Option<(MockScenario, Instant)>>>;
+
+/// Scenario running at `now`, if it has not ended
+fn current_scenario(active: &ActiveScenario, now: Instant) -> Option<MockScenario> {
+    let active = active.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    active.filter(|(_, until)| now < *until).map(|(scenario, _)| scenario)
+}
+
+/// Book levels per side (the most a client can negotiate)
+const BOOK_LEVELS: usize = 100;
+
+/// Random source for one market; seeded per adapter name and symbol so
+/// venues and symbols sharing a seed still differ
+fn market_rng(seed: Option<u64>, name: &str, symbol: &Symbol) -> StdRng {
+    let Some(seed) = seed else {
+        return StdRng::from_entropy();
+    };
+    // FNV-1a, stable across builds unlike the std hasher
+    let key = format!("{}/{}", name, symbol.as_str());
+    let hash = key
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325u64, |h, b| (h ^ b as u64).wrapping_mul(0x0100_0000_01b3));
+    StdRng::seed_from_u64(seed ^ hash)
+}
+
+struct MockMarket {
+    symbol: Symbol,
+    interval: CandleInterval,
+    price: f64,
+    floor: f64,
+    volatility: f64,
+    trend: f64,
+    sequence: u64,
+    candle_open_time: i64,
+    current_candle: Option<Candle>,
+    rng: StdRng,
+}
+
+impl MockMarket {
+    fn new(symbol: Symbol, interval: CandleInterval, initial_price: f64, rng: StdRng) -> Self {
+        Self {
+            symbol,
+            interval,
+            price: initial_price,
+            floor: initial_price * 0.01,
+            volatility: 0.0005,
+            trend: 0.0,
+            sequence: 0,
+            candle_open_time: 0,
+            current_candle: None,
+            rng,
+        }
+    }
+
+    /// Plausible starting price for well-known bases
+    fn initial_price(symbol: &Symbol) -> f64 {
+        match symbol.base() {
+            "BTC" => 95000.0,
+            "ETH" => 3500.0,
+            "SOL" => 180.0,
+            _ => 100.0,
+        }
+    }
+
+    fn tick(&mut self, scenario: Option<MockScenario>) -> f64 {
+        let rng = &mut self.rng;
+        // Scenarios override the wandering trend (per trade, ten a second)
+        let drift = match scenario {
+            Some(MockScenario::Rally) => 0.0001,
+            Some(MockScenario::Crash) => -0.0003,
+            _ => self.trend * 0.0001,
+        };
+        let volatility = match scenario {
+            Some(MockScenario::Volatile) => self.volatility * 5.0,
+            _ => self.volatility,
+        };
+        let random = (rng.r#gen::<f64>() - 0.5) * 2.0 * volatility;
+
+        if rng.r#gen::<f64>() < 0.01 {
+            self.trend = (rng.r#gen::<f64>() - 0.5) * 2.0;
+        }
+
+        self.price *= 1.0 + drift + random;
+        self.price = self.price.max(self.floor);
+        self.price
+    }
+
+    /// Mean-revert towards a reference price (another venue's market)
+    fn follow(&mut self, reference: f64) {
+        if reference > 0.0 {
+            self.price += (reference - self.price) * 0.2;
+        }
+    }
+
+    fn generate_trade(&mut self, scenario: Option<MockScenario>) -> Trade {
+        let price = self.tick(scenario);
+        let rng = &mut self.rng;
+        let side = if rng.r#gen::<bool>() { TradeSide::Buy } else { TradeSide::Sell };
+        let base_qty = rng.r#gen::<f64>().exp() * 0.1;
+        let quantity = base_qty.min(10.0);
+        Trade::new(self.symbol.clone(), price, quantity, side)
+    }
+
+    fn generate_orderbook(&mut self) -> OrderBookSnapshot {
+        self.sequence += 1;
+        let rng = &mut self.rng;
+
+        let mid = self.price;
+        let spread = mid * 0.0002;
+        // Level spacing scaled to the price (about $1-$6 at BTC prices)
+        let tick = mid / 95000.0;
+
+        let mut bids = Vec::with_capacity(BOOK_LEVELS);
+        let mut asks = Vec::with_capacity(BOOK_LEVELS);
+
+        let mut bid_price = mid - spread / 2.0;
+        for _ in 0..BOOK_LEVELS {
+            let qty = rng.r#gen::<f64>() * 2.0 + 0.1;
+            let orders = rng.gen_range(1..10);
+            bids.push(OrderBookLevel::new(bid_price, qty, orders));
+            bid_price -= (rng.r#gen::<f64>() * 5.0 + 1.0) * tick;
+        }
+
+        let mut ask_price = mid + spread / 2.0;
+        for _ in 0..BOOK_LEVELS {
+            let qty = rng.r#gen::<f64>() * 2.0 + 0.1;
+            let orders = rng.gen_range(1..10);
+            asks.push(OrderBookLevel::new(ask_price, qty, orders));
+            ask_price += (rng.r#gen::<f64>() * 5.0 + 1.0) * tick;
+        }
+
+        OrderBookSnapshot {
+            symbol: self.symbol.clone(),
+            bids,
+            asks,
+            timestamp: Utc::now().timestamp_millis(),
+            sequence: self.sequence,
+            venue: None,
+        }
+    }
+
+    fn generate_ticker(&mut self) -> Ticker {
+        let rng = &mut self.rng;
+
+        let open = self.price * (1.0 - rng.r#gen::<f64>() * 0.02);
+        let high = self.price * (1.0 + rng.r#gen::<f64>() * 0.03);
+        let low = self.price * (1.0 - rng.r#gen::<f64>() * 0.03);
+
+        let change = self.price - open;
+        let change_pct = change / open * 100.0;
+
+        Ticker {
+            symbol: self.symbol.clone(),
+            last_price: Price::new(self.price),
+            bid_price: Price::new(self.price * 0.9999),
+            bid_qty: Quantity::new(rng.r#gen::<f64>() * 5.0),
+            ask_price: Price::new(self.price * 1.0001),
+            ask_qty: Quantity::new(rng.r#gen::<f64>() * 5.0),
+            high_24h: Price::new(high),
+            low_24h: Price::new(low),
+            volume_24h: Quantity::new(rng.r#gen::<f64>() * 10000.0 + 1000.0),
+            quote_volume_24h: rng.r#gen::<f64>() * 500_000_000.0,
+            change_24h: change,
+            change_percent_24h: change_pct,
+            open_24h: Price::new(open),
+            trade_count_24h: rng.gen_range(10000..100000),
+            timestamp: Utc::now().timestamp_millis(),
+        }
+    }
+
+    fn update_candle(&mut self, trade: &Trade) -> Option<Candle> {
+        let now = Utc::now().timestamp_millis();
+        let candle_time = self.interval.open_time(now);
+
+        let price = trade.price.as_f64();
+
+        if self.candle_open_time != candle_time {
+            let prev = self.current_candle.take().map(|mut c| {
+                c.close_candle();
+                c
+            });
+
+            self.candle_open_time = candle_time;
+            self.current_candle = Some(Candle::new(
+                self.symbol.clone(),
+                self.interval,
+                candle_time,
+                price,
+            ));
+
+            prev
+        } else {
+            if let Some(ref mut candle) = self.current_candle {
+                candle.add_trade(trade);
+            }
+            None
+        }
+    }
+}
+
+/// Synthetic market used standalone or as the fallback source
+pub struct MockAdapter {
+    name: String,
+    health: HealthTracker,
+    reference: ReferencePrices,
+    scenario: ActiveScenario,
+    follows: bool,
+    seed: Option<u64>,
+}
+
+impl MockAdapter {
+    pub fn new() -> Self {
+        Self::named("mock")
+    }
+
+    /// Leading mock market reporting under `name`
+    pub fn named(name: impl Into<String>) -> Self {
+        let name = name.into();
+        Self {
+            health: HealthTracker::new(name.clone()),
+            name,
+            reference: ReferencePrices::default(),
+            scenario: ActiveScenario::default(),
+            follows: false,
+            seed: None,
+        }
+    }
+
+    /// Reproducible markets: the same seed gives the same prices, books and
+    /// trades in the same order (timestamps still follow the clock)
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Extra mock venue tracking this market's price with its own noise
+    pub fn venue(&self, name: impl Into<String>) -> Self {
+        Self {
+            follows: true,
+            seed: self.seed,
+            reference: self.reference.clone(),
+            scenario: self.scenario.clone(),
+            ..Self::named(name)
+        }
+    }
+
+    /// Run `scenario` for `duration` from now, replacing the running one;
+    /// `None` ends it
+    pub fn set_scenario(&self, scenario: Option<MockScenario>, duration: Duration) {
+        let until = Instant::now() + duration;
+        *self.scenario.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) =
+            scenario.map(|scenario| (scenario, until));
+    }
+
+    /// Scenario running now, if any
+    pub fn scenario(&self) -> Option<MockScenario> {
+        current_scenario(&self.scenario, Instant::now())
+    }
+}
+
+impl Default for MockAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MarketDataAdapter for MockAdapter {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn connect(&self) -> BoxFuture<'static, AdapterResult<Box<dyn AdapterSession>>> {
+        let session = MockSession {
+            name: self.name.clone(),
+            seed: self.seed,
+            health: self.health.clone(),
+            reference: self.reference.clone(),
+            scenario: self.scenario.clone(),
+            follows: self.follows,
+            subscriptions: Vec::new(),
+        };
+        Box::pin(async move { Ok(Box::new(session) as Box<dyn AdapterSession>) })
+    }
+
+    fn health(&self) -> AdapterHealth {
+        self.health.report(Instant::now())
+    }
+}
+
+struct MockSession {
+    name: String,
+    seed: Option<u64>,
+    health: HealthTracker,
+    reference: ReferencePrices,
+    scenario: ActiveScenario,
+    follows: bool,
+    subscriptions: Vec<Subscription>,
+}
+
+impl AdapterSession for MockSession {
+    fn subscribe(&mut self, subscription: &Subscription) -> BoxFuture<'_, AdapterResult<()>> {
+        self.subscriptions.retain(|s| s.symbol != subscription.symbol);
+        self.subscriptions.push(subscription.clone());
+        Box::pin(async { Ok(()) })
+    }
+
+    fn into_stream(self: Box<Self>) -> MessageStream {
+        let (tx, rx) = mpsc::channel(1024);
+        for subscription in self.subscriptions {
+            let market = MockMarket::new(
+                subscription.symbol.clone(),
+                subscription.interval,
+                MockMarket::initial_price(&subscription.symbol),
+                market_rng(self.seed, &self.name, &subscription.symbol),
+            );
+            let engine = run_mock_engine(
+                market,
+                tx.clone(),
+                self.reference.clone(),
+                self.scenario.clone(),
+                self.follows,
+            );
+            tokio::spawn(engine);
+        }
+
+        let messages = stream::unfold(rx, |mut rx| async move { rx.recv().await.map(|msg| (msg, rx)) });
+        self.health.track(messages.boxed())
+    }
+}
+
+/// Run one mock market until the session's stream is dropped. Followers pull
+/// their price towards the leader's; the leader publishes its price.
+/// A stall scenario keeps the market moving but sends nothing.
+async fn run_mock_engine(
+    mut market: MockMarket,
+    tx: mpsc::Sender<WsMessage>,
+    reference: ReferencePrices,
+    scenario: ActiveScenario,
+    follows: bool,
+) {
+    tracing::info!("Starting mock data engine for {}", market.symbol);
+
+    let mut trade_interval = interval(Duration::from_millis(100));
+    let mut book_interval = interval(Duration::from_millis(250));
+    let mut ticker_interval = interval(Duration::from_secs(1));
+    let mut heartbeat_interval = interval(Duration::from_secs(30));
+
+    loop {
+        let mut batch = Vec::with_capacity(3);
+        let scenario = current_scenario(&scenario, Instant::now());
+
+        tokio::select! {
+            _ = trade_interval.tick() => {
+                {
+                    let mut prices = reference.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+                    if follows {
+                        market.follow(prices.get(&market.symbol).copied().unwrap_or_default());
+                    }
+                    let trade = market.generate_trade(scenario);
+                    if !follows {
+                        prices.insert(market.symbol.clone(), market.price);
+                    }
+
+                    if let Some(closed_candle) = market.update_candle(&trade) {
+                        batch.push(WsMessage::Candle(closed_candle));
+                    }
+                    if let Some(ref candle) = market.current_candle {
+                        batch.push(WsMessage::Candle(candle.clone()));
+                    }
+                    batch.push(WsMessage::Trade(trade));
+                }
+            }
+
+            _ = book_interval.tick() => {
+                let book = market.generate_orderbook();
+                let depth = MarketDepth::from_orderbook(&book);
+
+                batch.push(WsMessage::OrderBook(book));
+                batch.push(WsMessage::Depth(depth));
+            }
+
+            _ = ticker_interval.tick() => {
+                batch.push(WsMessage::Ticker(market.generate_ticker()));
+            }
+
+            _ = heartbeat_interval.tick() => {
+                batch.push(WsMessage::Heartbeat {
+                    timestamp: Utc::now().timestamp_millis(),
+                });
+            }
+        }
+
+        if scenario == Some(MockScenario::Stall) {
+            continue;
+        }
+        for msg in batch {
+            if tx.send(msg).await.is_err() {
+                tracing::debug!("Mock engine for {} stopped", market.symbol);
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dash_core::FeedChannel;
+
+    #[tokio::test]
+    async fn test_session_streams_subscribed_symbols() {
+        let adapter = MockAdapter::new();
+        let mut session = adapter.connect().await.unwrap();
+        session.subscribe(&Subscription::new("ETH-USD")).await.unwrap();
+        let mut stream = session.into_stream();
+
+        let mut channels = std::collections::HashSet::new();
+        while channels.len() < 3 {
+            let msg = stream.next().await.expect("mock stream ended");
+            if let WsMessage::Trade(trade) = &msg {
+                assert_eq!(trade.symbol.as_str(), "ETH-USD");
+                assert!(trade.price.as_f64() < 10_000.0);
+            }
+            channels.extend(FeedChannel::for_message(&msg));
+        }
+
+        let health = adapter.health();
+        assert_eq!(health.name, "mock");
+        assert!(health.channels.iter().any(|c| !c.stale));
+    }
+
+    #[test]
+    fn test_scenarios() {
+        let symbol = Symbol::new("BTC-USD");
+        let rng = market_rng(None, "mock", &symbol);
+        let mut market = MockMarket::new(symbol, CandleInterval::M1, 95000.0, rng);
+        for _ in 0..100 {
+            market.tick(Some(MockScenario::Crash));
+        }
+        assert!(market.price < 95000.0 * 0.99);
+
+        // Venues share the leader's scenario until it runs out
+        let adapter = MockAdapter::new();
+        let venue = adapter.venue("mock-2");
+        adapter.set_scenario(Some(MockScenario::Stall), Duration::from_secs(60));
+        assert_eq!(venue.scenario(), Some(MockScenario::Stall));
+        adapter.set_scenario(Some(MockScenario::Rally), Duration::ZERO);
+        assert_eq!(venue.scenario(), None);
+    }
+
+    #[test]
+    fn test_seeded_markets_repeat() {
+        let symbol = Symbol::new("BTC-USD");
+        let run = |seed, name| {
+            let rng = market_rng(Some(seed), name, &symbol);
+            let mut market = MockMarket::new(symbol.clone(), CandleInterval::M1, 95000.0, rng);
+            let prices: Vec<f64> =
+                (0..50).map(|_| market.generate_trade(None).price.as_f64()).collect();
+            (prices, market.generate_orderbook().bids[3].quantity.as_f64())
+        };
+        assert_eq!(run(7, "mock"), run(7, "mock"));
+        assert_ne!(run(7, "mock"), run(8, "mock"));
+        // Venues sharing the seed still wander on their own
+        assert_ne!(run(7, "mock"), run(7, "mock-2"));
+    }
+}
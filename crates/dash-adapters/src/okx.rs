@@ -0,0 +1,369 @@
+//! OKX v5 public channels (trades, books, tickers)
+//!
+//! OKX serves candles from a separate business endpoint, so candles are
+//! built from trades at the subscribed interval (`TradeCandles`) to keep a
+//! single connection per session.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::json;
+
+use crate::book::LocalBook;
+use crate::candles::TradeCandles;
+use crate::symbols::{SymbolFormat, SymbolMapper};
+use crate::ws::{self, string_f64, string_levels, FrameDecoder, WsConnection};
+use crate::{AdapterResult, AdapterSession, HealthTracker, MarketDataAdapter, MessageStream};
+use dash_core::{
+    AdapterHealth, CandleInterval, OrderSide, Price, Quantity, Subscription, Symbol, Ticker,
+    Trade, TradeSide, WsMessage,
+};
+
+pub const OKX_WS_URL: &str = "wss://ws.okx.com:8443/ws/v5/public";
+
+/// Book levels per side published from the local book
+const BOOK_DEPTH: usize = 20;
+
+/// OKX drops connections idle for 30s
+const PING_INTERVAL: Duration = Duration::from_secs(25);
+
+pub struct OkxAdapter {
+    name: String,
+    endpoint: String,
+    health: HealthTracker,
+    symbols: SymbolMapper,
+}
+
+impl OkxAdapter {
+    pub fn new() -> Self {
+        Self::with_endpoint(OKX_WS_URL)
+    }
+
+    /// Connect somewhere other than the production endpoint (demo trading, proxy)
+    pub fn with_endpoint(endpoint: impl Into<String>) -> Self {
+        Self {
+            name: "okx".to_string(),
+            endpoint: endpoint.into(),
+            health: HealthTracker::new("okx"),
+            symbols: SymbolMapper::new(SymbolFormat::Dashed).usd_as("USDT"),
+        }
+    }
+
+    /// Pin canonical symbols to specific instruments on top of the venue rule
+    pub fn with_symbol_overrides(mut self, overrides: &[(Symbol, String)]) -> Self {
+        self.symbols = self.symbols.with_overrides(overrides);
+        self
+    }
+}
+
+impl Default for OkxAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MarketDataAdapter for OkxAdapter {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn connect(&self) -> BoxFuture<'static, AdapterResult<Box<dyn AdapterSession>>> {
+        let endpoint = self.endpoint.clone();
+        let health = self.health.clone();
+        let symbols = self.symbols.clone();
+        Box::pin(async move {
+            let ws = ws::connect(&endpoint).await?;
+            Ok(Box::new(OkxSession {
+                ws,
+                health,
+                symbols,
+                decoder: OkxDecoder::default(),
+            }) as Box<dyn AdapterSession>)
+        })
+    }
+
+    fn health(&self) -> AdapterHealth {
+        self.health.report(Instant::now())
+    }
+
+    fn instrument(&self, symbol: &Symbol) -> String {
+        self.symbols.to_venue(symbol)
+    }
+}
+
+struct OkxSession {
+    ws: WsConnection,
+    health: HealthTracker,
+    symbols: SymbolMapper,
+    decoder: OkxDecoder,
+}
+
+impl AdapterSession for OkxSession {
+    fn subscribe(&mut self, subscription: &Subscription) -> BoxFuture<'_, AdapterResult<()>> {
+        let instrument = self.symbols.to_venue(&subscription.symbol);
+        self.decoder.markets.insert(
+            instrument.clone(),
+            OkxMarket::new(subscription.symbol.clone(), subscription.interval),
+        );
+        let args: Vec<_> = ["trades", "books", "tickers"]
+            .iter()
+            .map(|channel| json!({ "channel": channel, "instId": instrument }))
+            .collect();
+        let request = json!({ "op": "subscribe", "args": args });
+        Box::pin(async move { ws::send_json(&mut self.ws, &request).await })
+    }
+
+    fn into_stream(self: Box<Self>) -> MessageStream {
+        let session = *self;
+        session.health.track(ws::decode_frames(session.ws, session.decoder))
+    }
+}
+
+// ============================================================================
+// WIRE FORMAT
+// ============================================================================
+
+/// `{"arg":{"channel":"trades","instId":"BTC-USDT"},"data":[...]}`;
+/// subscription events and `pong` carry no data
+#[derive(Deserialize)]
+struct Envelope {
+    arg: Arg,
+    #[serde(default)]
+    action: Option<String>,
+    data: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct Arg {
+    channel: String,
+    #[serde(rename = "instId")]
+    inst_id: String,
+}
+
+#[derive(Deserialize)]
+struct OkxTrade {
+    #[serde(rename = "tradeId")]
+    trade_id: String,
+    #[serde(rename = "px", deserialize_with = "string_f64")]
+    price: f64,
+    #[serde(rename = "sz", deserialize_with = "string_f64")]
+    size: f64,
+    /// Taker side
+    side: String,
+    #[serde(deserialize_with = "string_f64")]
+    ts: f64,
+}
+
+#[derive(Deserialize)]
+struct OkxBook {
+    #[serde(deserialize_with = "string_levels")]
+    asks: Vec<(f64, f64)>,
+    #[serde(deserialize_with = "string_levels")]
+    bids: Vec<(f64, f64)>,
+    #[serde(deserialize_with = "string_f64")]
+    ts: f64,
+}
+
+#[derive(Deserialize)]
+struct OkxTicker {
+    #[serde(deserialize_with = "string_f64")]
+    last: f64,
+    #[serde(rename = "askPx", deserialize_with = "string_f64")]
+    ask: f64,
+    #[serde(rename = "askSz", deserialize_with = "string_f64")]
+    ask_size: f64,
+    #[serde(rename = "bidPx", deserialize_with = "string_f64")]
+    bid: f64,
+    #[serde(rename = "bidSz", deserialize_with = "string_f64")]
+    bid_size: f64,
+    #[serde(rename = "open24h", deserialize_with = "string_f64")]
+    open: f64,
+    #[serde(rename = "high24h", deserialize_with = "string_f64")]
+    high: f64,
+    #[serde(rename = "low24h", deserialize_with = "string_f64")]
+    low: f64,
+    /// Base volume
+    #[serde(rename = "vol24h", deserialize_with = "string_f64")]
+    volume: f64,
+    /// Quote volume (spot)
+    #[serde(rename = "volCcy24h", deserialize_with = "string_f64")]
+    quote_volume: f64,
+    #[serde(deserialize_with = "string_f64")]
+    ts: f64,
+}
+
+/// Per-instrument state rebuilt from the feed
+struct OkxMarket {
+    book: LocalBook,
+    candles: TradeCandles,
+}
+
+impl OkxMarket {
+    fn new(symbol: Symbol, interval: CandleInterval) -> Self {
+        Self {
+            book: LocalBook::new(symbol),
+            candles: TradeCandles::new(interval),
+        }
+    }
+}
+
+/// Normalizes channel pushes for the subscribed instruments
+#[derive(Default)]
+pub(crate) struct OkxDecoder {
+    markets: HashMap<String, OkxMarket>,
+}
+
+fn parse<T: DeserializeOwned>(data: serde_json::Value) -> Vec<T> {
+    serde_json::from_value(data)
+        .map_err(|e| tracing::debug!("okx: unexpected payload: {}", e))
+        .unwrap_or_default()
+}
+
+fn millis(ts: f64) -> DateTime<Utc> {
+    DateTime::from_timestamp_millis(ts as i64).unwrap_or_else(Utc::now)
+}
+
+impl OkxDecoder {
+    fn normalize(&mut self, envelope: Envelope) -> Vec<WsMessage> {
+        let Some(market) = self.markets.get_mut(&envelope.arg.inst_id) else { return Vec::new() };
+        let mut out = Vec::new();
+
+        match envelope.arg.channel.as_str() {
+            "trades" => {
+                for t in parse::<OkxTrade>(envelope.data) {
+                    let trade = Trade {
+                        id: t.trade_id,
+                        symbol: market.book.symbol().clone(),
+                        price: Price::new(t.price),
+                        quantity: Quantity::new(t.size),
+                        side: if t.side == "sell" { TradeSide::Sell } else { TradeSide::Buy },
+                        timestamp: millis(t.ts),
+                        maker_order_id: None,
+                        taker_order_id: None,
+                        venue: None,
+                    };
+                    out.extend(market.candles.update(&trade));
+                    out.push(WsMessage::Trade(trade));
+                }
+            }
+            "books" => {
+                for book in parse::<OkxBook>(envelope.data) {
+                    if envelope.action.as_deref() == Some("snapshot") {
+                        market.book.clear();
+                    }
+                    for (price, qty) in book.bids {
+                        market.book.set(OrderSide::Bid, price, qty);
+                    }
+                    for (price, qty) in book.asks {
+                        market.book.set(OrderSide::Ask, price, qty);
+                    }
+                    out.push(WsMessage::OrderBook(market.book.snapshot(BOOK_DEPTH, book.ts as i64)));
+                }
+            }
+            "tickers" => {
+                for t in parse::<OkxTicker>(envelope.data) {
+                    let change = t.last - t.open;
+                    out.push(WsMessage::Ticker(Ticker {
+                        symbol: market.book.symbol().clone(),
+                        last_price: Price::new(t.last),
+                        bid_price: Price::new(t.bid),
+                        bid_qty: Quantity::new(t.bid_size),
+                        ask_price: Price::new(t.ask),
+                        ask_qty: Quantity::new(t.ask_size),
+                        high_24h: Price::new(t.high),
+                        low_24h: Price::new(t.low),
+                        volume_24h: Quantity::new(t.volume),
+                        quote_volume_24h: t.quote_volume,
+                        change_24h: change,
+                        change_percent_24h: if t.open > 0.0 { change / t.open * 100.0 } else { 0.0 },
+                        open_24h: Price::new(t.open),
+                        trade_count_24h: 0,
+                        timestamp: t.ts as i64,
+                    }));
+                }
+            }
+            _ => {}
+        }
+        out
+    }
+}
+
+impl FrameDecoder for OkxDecoder {
+    fn decode(&mut self, text: &str) -> Vec<WsMessage> {
+        match serde_json::from_str::<Envelope>(text) {
+            Ok(envelope) => self.normalize(envelope),
+            Err(_) => {
+                if text.contains("\"event\":\"error\"") {
+                    tracing::warn!("okx: {}", text);
+                }
+                Vec::new()
+            }
+        }
+    }
+
+    fn keepalive(&self) -> Option<(Duration, String)> {
+        Some((PING_INTERVAL, "ping".to_string()))
+    }
+}
+
+/// Decoder subscribed to BTC-USD, for tests and the fuzz targets
+#[cfg(any(test, feature = "fuzzing"))]
+pub(crate) fn decoder() -> OkxDecoder {
+    let mut decoder = OkxDecoder::default();
+    decoder
+        .markets
+        .insert("BTC-USDT".into(), OkxMarket::new(Symbol::new("BTC-USD"), CandleInterval::M1));
+    decoder
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbol_mapping() {
+        let adapter = OkxAdapter::new();
+        assert_eq!(adapter.instrument(&Symbol::new("BTC-USD")), "BTC-USDT");
+        assert_eq!(adapter.instrument(&Symbol::new("ETH-BTC")), "ETH-BTC");
+        assert_eq!(adapter.instrument(&Symbol::new("doge-usd")), "DOGE-USDT");
+    }
+
+    #[test]
+    fn test_decode_channels() {
+        let mut decoder = decoder();
+        let trades = decoder.decode(
+            r#"{"arg":{"channel":"trades","instId":"BTC-USDT"},"data":[{"instId":"BTC-USDT","tradeId":"9",
+            "px":"95000.5","sz":"0.1","side":"sell","ts":"1704067200000","count":"1"}]}"#,
+        );
+        let [WsMessage::Candle(_), WsMessage::Trade(trade)] = trades.as_slice() else { panic!("unexpected {:?}", trades) };
+        assert_eq!(trade.side, TradeSide::Sell);
+        assert_eq!(trade.symbol.as_str(), "BTC-USD");
+
+        decoder.decode(
+            r#"{"arg":{"channel":"books","instId":"BTC-USDT"},"action":"snapshot","data":[{
+            "asks":[["95001","1","0","2"]],"bids":[["95000","2","0","1"],["94999","1","0","1"]],"ts":"1704067200000","checksum":0}]}"#,
+        );
+        let update = decoder.decode(
+            r#"{"arg":{"channel":"books","instId":"BTC-USDT"},"action":"update","data":[{
+            "asks":[],"bids":[["95000","0","0","0"]],"ts":"1704067200100","checksum":0}]}"#,
+        );
+        let [WsMessage::OrderBook(book)] = update.as_slice() else { panic!("unexpected {:?}", update) };
+        assert_eq!(book.best_bid().unwrap().price.as_f64(), 94999.0);
+        assert_eq!(book.timestamp, 1704067200100);
+
+        let ticker = decoder.decode(
+            r#"{"arg":{"channel":"tickers","instId":"BTC-USDT"},"data":[{"instId":"BTC-USDT","last":"101",
+            "lastSz":"1","askPx":"101.5","askSz":"2","bidPx":"100.5","bidSz":"3","open24h":"100","high24h":"110",
+            "low24h":"90","volCcy24h":"5000","vol24h":"50","ts":"1704067200000"}]}"#,
+        );
+        let [WsMessage::Ticker(ticker)] = ticker.as_slice() else { panic!("unexpected {:?}", ticker) };
+        assert_eq!(ticker.change_percent_24h, 1.0);
+
+        assert!(decoder.decode("pong").is_empty());
+        assert!(decoder.decode(r#"{"event":"subscribe","arg":{"channel":"trades","instId":"BTC-USDT"}}"#).is_empty());
+    }
+}
@@ -0,0 +1,121 @@
+//! Adapter registry: venues by name, so the server never names them directly
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use dash_core::Symbol;
+
+use crate::{
+    BinanceAdapter, BybitAdapter, CoinbaseAdapter, KrakenAdapter, MarketDataAdapter, MockAdapter,
+    OkxAdapter,
+};
+
+/// Per-adapter settings supplied by the host
+#[derive(Debug, Clone, Default)]
+pub struct AdapterConfig {
+    /// WebSocket endpoint override (testnet, sandbox or a local proxy)
+    pub endpoint: Option<String>,
+    /// Canonical symbol → instrument id overrides on top of the venue's rule
+    pub symbols: Vec<(Symbol, String)>,
+}
+
+/// Builds an adapter from its config
+pub type AdapterFactory = fn(&AdapterConfig) -> Arc<dyn MarketDataAdapter>;
+
+/// Adapter factories keyed by venue name
+#[derive(Clone, Default)]
+pub struct AdapterRegistry {
+    factories: BTreeMap<String, AdapterFactory>,
+}
+
+impl AdapterRegistry {
+    /// Empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry with every adapter shipped in this crate
+    pub fn builtin() -> Self {
+        let mut registry = Self::new();
+        registry
+            .register("mock", |_| Arc::new(MockAdapter::new()))
+            .register("binance", |config| {
+                let adapter = match &config.endpoint {
+                    Some(url) => BinanceAdapter::with_endpoint(url),
+                    None => BinanceAdapter::new(),
+                };
+                Arc::new(adapter.with_symbol_overrides(&config.symbols))
+            })
+            .register("coinbase", |config| {
+                let adapter = match &config.endpoint {
+                    Some(url) => CoinbaseAdapter::with_endpoint(url),
+                    None => CoinbaseAdapter::new(),
+                };
+                Arc::new(adapter.with_symbol_overrides(&config.symbols))
+            })
+            .register("kraken", |config| {
+                let adapter = match &config.endpoint {
+                    Some(url) => KrakenAdapter::with_endpoint(url),
+                    None => KrakenAdapter::new(),
+                };
+                Arc::new(adapter.with_symbol_overrides(&config.symbols))
+            })
+            .register("okx", |config| {
+                let adapter = match &config.endpoint {
+                    Some(url) => OkxAdapter::with_endpoint(url),
+                    None => OkxAdapter::new(),
+                };
+                Arc::new(adapter.with_symbol_overrides(&config.symbols))
+            })
+            .register("bybit", |config| {
+                let adapter = match &config.endpoint {
+                    Some(url) => BybitAdapter::with_endpoint(url),
+                    None => BybitAdapter::new(),
+                };
+                Arc::new(adapter.with_symbol_overrides(&config.symbols))
+            });
+        registry
+    }
+
+    /// Add (or replace) a venue
+    pub fn register(&mut self, name: &str, factory: AdapterFactory) -> &mut Self {
+        self.factories.insert(name.to_lowercase(), factory);
+        self
+    }
+
+    /// Registered venue names, sorted
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.factories.keys().map(String::as_str)
+    }
+
+    pub fn create(&self, name: &str, config: &AdapterConfig) -> Option<Arc<dyn MarketDataAdapter>> {
+        self.factories.get(&name.trim().to_lowercase()).map(|factory| factory(config))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_registry() {
+        let registry = AdapterRegistry::builtin();
+        assert_eq!(registry.names().collect::<Vec<_>>(), vec!["binance", "bybit", "coinbase", "kraken", "mock", "okx"]);
+
+        let adapter = registry.create(" Coinbase ", &AdapterConfig::default()).unwrap();
+        assert_eq!(adapter.name(), "coinbase");
+
+        let config = AdapterConfig {
+            symbols: vec![(Symbol::new("BTC-USD"), "XBT/USD".into())],
+            ..Default::default()
+        };
+        let kraken = registry.create("kraken", &config).unwrap();
+        assert_eq!(kraken.instrument(&Symbol::new("BTC-USD")), "XBT/USD");
+        assert_eq!(kraken.instrument(&Symbol::new("ETH-USD")), "ETH/USD");
+        assert!(registry.create("ftx", &AdapterConfig::default()).is_none());
+
+        let mut registry = AdapterRegistry::new();
+        registry.register("demo", |_| Arc::new(MockAdapter::named("demo")));
+        assert_eq!(registry.create("demo", &AdapterConfig::default()).unwrap().name(), "demo");
+    }
+}
@@ -0,0 +1,168 @@
+//! Venue symbol mapping: canonical dashboard symbols (`BTC-USD`) to venue
+//! instrument ids (`BTCUSDT`, `BTC/USD`, ...) and back
+//!
+//! Each venue has a naming rule plus optional per-symbol overrides for the
+//! pairs the rule gets wrong (or that should point at a different market,
+//! e.g. `BTC-USD` → `BTCFDUSD`).
+
+use std::collections::BTreeMap;
+
+use dash_core::Symbol;
+
+/// Quote currencies recognised when splitting separator-less instruments,
+/// longest first so `USDT` wins over `USD`
+const KNOWN_QUOTES: &[&str] = &[
+    "FDUSD", "USDT", "USDC", "TUSD", "USD", "EUR", "GBP", "JPY", "BTC", "ETH", "BNB",
+];
+
+/// How a venue spells base and quote
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolFormat {
+    /// `BTC-USD`
+    Dashed,
+    /// `BTC/USD`
+    Slashed,
+    /// `BTCUSD`
+    Concat,
+}
+
+impl SymbolFormat {
+    fn join(self, base: &str, quote: &str) -> String {
+        match self {
+            Self::Dashed => format!("{}-{}", base, quote),
+            Self::Slashed => format!("{}/{}", base, quote),
+            Self::Concat => format!("{}{}", base, quote),
+        }
+    }
+
+    fn split(self, instrument: &str) -> Option<(&str, &str)> {
+        match self {
+            Self::Dashed => instrument.split_once('-'),
+            Self::Slashed => instrument.split_once('/'),
+            Self::Concat => KNOWN_QUOTES.iter().find_map(|quote| {
+                let base = instrument.strip_suffix(quote)?;
+                (!base.is_empty()).then(|| instrument.split_at(base.len()))
+            }),
+        }
+    }
+}
+
+/// Canonical ↔ instrument translation for one venue
+#[derive(Debug, Clone)]
+pub struct SymbolMapper {
+    format: SymbolFormat,
+    /// Quote the venue lists USD pairs against (e.g. `USDT`)
+    usd_quote: Option<String>,
+    /// Canonical symbol → instrument, applied before the rule
+    overrides: BTreeMap<String, String>,
+}
+
+impl SymbolMapper {
+    pub fn new(format: SymbolFormat) -> Self {
+        Self {
+            format,
+            usd_quote: None,
+            overrides: BTreeMap::new(),
+        }
+    }
+
+    /// List USD pairs against a stablecoin instead (`BTC-USD` → `BTCUSDT`)
+    pub fn usd_as(mut self, quote: &str) -> Self {
+        self.usd_quote = Some(quote.to_uppercase());
+        self
+    }
+
+    /// Pin one canonical symbol to an instrument id
+    pub fn with_override(mut self, symbol: &Symbol, instrument: impl Into<String>) -> Self {
+        self.overrides.insert(symbol.as_str().to_uppercase(), instrument.into());
+        self
+    }
+
+    /// Apply a batch of overrides (later entries win)
+    pub fn with_overrides<'a>(self, overrides: impl IntoIterator<Item = &'a (Symbol, String)>) -> Self {
+        overrides
+            .into_iter()
+            .fold(self, |mapper, (symbol, instrument)| mapper.with_override(symbol, instrument.clone()))
+    }
+
+    /// Venue instrument id for a canonical symbol
+    pub fn to_venue(&self, symbol: &Symbol) -> String {
+        let symbol = Symbol::new(symbol.as_str().to_uppercase());
+        if let Some(instrument) = self.overrides.get(symbol.as_str()) {
+            return instrument.clone();
+        }
+        let quote = match (symbol.quote(), &self.usd_quote) {
+            ("USD", Some(usd)) => usd.as_str(),
+            (quote, _) => quote,
+        };
+        self.format.join(symbol.base(), quote)
+    }
+
+    /// Canonical symbol for a venue instrument id. The venue's USD stand-in
+    /// maps back to USD, mirroring `to_venue`.
+    pub fn to_canonical(&self, instrument: &str) -> Option<Symbol> {
+        if let Some((symbol, _)) = self
+            .overrides
+            .iter()
+            .find(|(_, id)| id.eq_ignore_ascii_case(instrument))
+        {
+            return Some(Symbol::new(symbol.clone()));
+        }
+        let upper = instrument.to_uppercase();
+        let (base, quote) = self.format.split(&upper)?;
+        let quote = match &self.usd_quote {
+            Some(usd) if usd == quote => "USD",
+            _ => quote,
+        };
+        Some(Symbol::new(format!("{}-{}", base, quote)))
+    }
+}
+
+/// Parse `BTC-USD=BTCFDUSD,ETH-USD=ETHFDUSD` override specs; malformed
+/// entries are skipped with a warning
+pub fn parse_overrides(spec: &str) -> Vec<(Symbol, String)> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .filter_map(|entry| match entry.split_once('=') {
+            Some((symbol, instrument)) if !symbol.trim().is_empty() && !instrument.trim().is_empty() => Some((
+                Symbol::new(symbol.trim().to_uppercase()),
+                instrument.trim().to_string(),
+            )),
+            _ => {
+                tracing::warn!("ignoring malformed symbol override {:?}", entry);
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_per_format() {
+        let binance = SymbolMapper::new(SymbolFormat::Concat).usd_as("usdt");
+        assert_eq!(binance.to_venue(&Symbol::new("btc-usd")), "BTCUSDT");
+        assert_eq!(binance.to_venue(&Symbol::new("ETH-BTC")), "ETHBTC");
+        assert_eq!(binance.to_canonical("btcusdt"), Some(Symbol::new("BTC-USD")));
+        assert_eq!(binance.to_canonical("ETHBTC"), Some(Symbol::new("ETH-BTC")));
+        assert_eq!(binance.to_canonical("USDT"), None);
+
+        let kraken = SymbolMapper::new(SymbolFormat::Slashed);
+        assert_eq!(kraken.to_venue(&Symbol::new("SOL-EUR")), "SOL/EUR");
+        assert_eq!(kraken.to_canonical("SOL/EUR"), Some(Symbol::new("SOL-EUR")));
+    }
+
+    #[test]
+    fn test_overrides() {
+        let overrides = parse_overrides(" btc-usd = BTCFDUSD ,bogus,=X");
+        assert_eq!(overrides, vec![(Symbol::new("BTC-USD"), "BTCFDUSD".to_string())]);
+
+        let mapper = SymbolMapper::new(SymbolFormat::Concat).usd_as("USDT").with_overrides(&overrides);
+        assert_eq!(mapper.to_venue(&Symbol::new("BTC-USD")), "BTCFDUSD");
+        assert_eq!(mapper.to_venue(&Symbol::new("ETH-USD")), "ETHUSDT");
+        assert_eq!(mapper.to_canonical("BTCFDUSD"), Some(Symbol::new("BTC-USD")));
+    }
+}
@@ -0,0 +1,162 @@
+//! WebSocket transport shared by the exchange adapters
+
+use std::time::Duration;
+
+use futures::stream;
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Deserializer};
+use tokio::net::TcpStream;
+use tokio::time::{interval_at, Instant};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+use crate::{AdapterResult, MessageStream};
+use dash_core::WsMessage;
+
+pub type WsConnection = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Connect to a venue endpoint (`wss://` uses rustls with the bundled web roots)
+pub async fn connect(url: &str) -> AdapterResult<WsConnection> {
+    // Only the ring provider is compiled in; ignore "already installed"
+    let _ = rustls::crypto::ring::default_provider().install_default();
+    let (ws, _) = tokio_tungstenite::connect_async(url).await?;
+    tracing::info!("Connected to {}", url);
+    Ok(ws)
+}
+
+/// Send a JSON control frame (subscribe requests etc.)
+pub async fn send_json(ws: &mut WsConnection, value: &serde_json::Value) -> AdapterResult<()> {
+    ws.send(Message::Text(value.to_string())).await?;
+    Ok(())
+}
+
+// ============================================================================
+// STRATEGY PATTERN: Frame Decoding
+// ============================================================================
+
+/// Strategy trait turning one venue text frame into normalized messages
+pub trait FrameDecoder: Send + 'static {
+    fn decode(&mut self, text: &str) -> Vec<WsMessage>;
+
+    /// Control frames to send back after a decode (e.g. resubscribe to resync a book)
+    fn outgoing(&mut self) -> Vec<serde_json::Value> {
+        Vec::new()
+    }
+
+    /// Application-level ping the venue expects, and how often
+    fn keepalive(&self) -> Option<(Duration, String)> {
+        None
+    }
+}
+
+/// Decode text frames until the venue closes the connection
+pub fn decode_frames<D: FrameDecoder>(ws: WsConnection, decoder: D) -> MessageStream {
+    let keepalive = decoder
+        .keepalive()
+        .map(|(period, ping)| (interval_at(Instant::now() + period, period), ping));
+
+    stream::unfold((ws, decoder, keepalive), |(mut ws, mut decoder, mut keepalive)| async move {
+        loop {
+            let frame = match keepalive.as_mut() {
+                Some((ticker, ping)) => tokio::select! {
+                    frame = ws.next() => frame,
+                    _ = ticker.tick() => {
+                        if let Err(e) = ws.send(Message::Text(ping.clone())).await {
+                            tracing::warn!("Venue keepalive failed: {}", e);
+                            return None;
+                        }
+                        continue;
+                    }
+                },
+                None => ws.next().await,
+            };
+
+            match frame? {
+                Ok(Message::Text(text)) => {
+                    let messages = decoder.decode(&text);
+                    for request in decoder.outgoing() {
+                        if let Err(e) = send_json(&mut ws, &request).await {
+                            tracing::warn!("Venue request failed: {}", e);
+                            return None;
+                        }
+                    }
+                    return Some((messages, (ws, decoder, keepalive)));
+                }
+                Ok(Message::Close(frame)) => {
+                    tracing::info!("Venue closed connection: {:?}", frame);
+                    return None;
+                }
+                // Pings are answered by tungstenite
+                Ok(_) => continue,
+                Err(e) => {
+                    tracing::warn!("Venue connection error: {}", e);
+                    return None;
+                }
+            }
+        }
+    })
+    .flat_map(stream::iter)
+    .boxed()
+}
+
+/// A number venues may send as a JSON string (`"95000.10"`)
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Number {
+    Str(String),
+    Num(f64),
+}
+
+impl Number {
+    fn parse<E: serde::de::Error>(self) -> Result<f64, E> {
+        match self {
+            Self::Str(s) => s.parse().map_err(E::custom),
+            Self::Num(n) => Ok(n),
+        }
+    }
+}
+
+/// Numbers venues send as JSON strings
+pub fn string_f64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    Number::deserialize(deserializer)?.parse()
+}
+
+/// `[price, quantity, ...]` levels sent as strings; extra fields (order
+/// counts, liquidated size) are ignored
+pub fn string_levels<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<(f64, f64)>, D::Error> {
+    Vec::<Vec<Number>>::deserialize(deserializer)?
+        .into_iter()
+        .map(|level| {
+            let mut fields = level.into_iter();
+            match (fields.next(), fields.next()) {
+                (Some(price), Some(qty)) => Ok((price.parse()?, qty.parse()?)),
+                _ => Err(serde::de::Error::custom("price level needs price and quantity")),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Sample {
+        #[serde(deserialize_with = "string_f64")]
+        price: f64,
+        #[serde(deserialize_with = "string_levels")]
+        bids: Vec<(f64, f64)>,
+    }
+
+    #[test]
+    fn test_string_numbers() {
+        let sample: Sample = serde_json::from_str(r#"{"price":"95000.5","bids":[["1.5","2"],["1.0","0.25"]]}"#).unwrap();
+        assert_eq!(sample.price, 95000.5);
+        assert_eq!(sample.bids, vec![(1.5, 2.0), (1.0, 0.25)]);
+
+        let sample: Sample = serde_json::from_str(r#"{"price":12,"bids":[["2","3","0","4"]]}"#).unwrap();
+        assert_eq!(sample.price, 12.0);
+        assert_eq!(sample.bids, vec![(2.0, 3.0)]);
+        assert!(serde_json::from_str::<Sample>(r#"{"price":1,"bids":[["2"]]}"#).is_err());
+    }
+}
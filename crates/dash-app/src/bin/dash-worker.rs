@@ -0,0 +1,6 @@
+//! Compute worker: answers the dashboard's derived-data requests in a Web
+//! Worker, off the page's main thread
+
+fn main() {
+    dash_state::ComputeWorker::register();
+}
@@ -1,33 +1,200 @@
 //! BTC Exchange Dashboard - WASM Entry Point
 
-use dash_components::Dashboard;
-use dash_state::provide_app_state;
-use dash_websocket::{use_websocket, WsConfig, ExponentialBackoff};
+use dash_components::{AdminPage, Dashboard};
+use dash_state::{provide_app_state, use_app_state, DashboardRoute, LogLevel, RingLayer};
+#[cfg(feature = "indicators")]
+use dash_state::{ComputePool, COMPUTE_WORKER_PATH, MAX_COMPUTE_WORKERS};
+use dash_core::{CandleInterval, ClientCommand, Locale, Subscription, Symbol, WireEncoding};
+use dash_websocket::{
+    load_candle_history, load_snapshot, poll_calendar, poll_health, rest_base_url, resync_snapshot,
+    sync_account, sync_admin, sync_alerts, use_websocket_with_config, ExponentialBackoff,
+    WsConfig,
+};
 use leptos::prelude::*;
+use leptos_router::{
+    components::{Redirect, Route, Router, Routes},
+    hooks::{use_location, use_navigate, use_params_map},
+    path, NavigateOptions,
+};
+use tracing_subscriber::layer::SubscriberExt;
 use wasm_bindgen::JsCast;
 
 #[component]
 fn App() -> impl IntoView {
     let state = provide_app_state();
 
-    let ws_config = WsConfig::new(get_ws_url())
+    // Indicator histories are recomputed in Web Workers
+    #[cfg(feature = "indicators")]
+    state.market.compute.install(ComputePool::spawn(COMPUTE_WORKER_PATH, compute_workers()));
+
+    // Start in the browser's language when we have a bundle for it, and keep
+    // <html lang> in step so screen readers pick the right voice
+    if let Some(locale) = window().navigator().language().and_then(|tag| Locale::from_tag(&tag)) {
+        state.set_locale(locale);
+    }
+    let locale = state.locale();
+    Effect::new(move |_| {
+        if let Some(root) = document().document_element() {
+            let _ = root.set_attribute("lang", locale.get().tag());
+        }
+    });
+
+    let ws_url = get_ws_url();
+    let rest_url = rest_base_url(&ws_url);
+    let ws_config = WsConfig::new(ws_url)
         .with_policy(ExponentialBackoff::aggressive())
-        .heartbeat(30000);
+        .heartbeat(30000)
+        .encoding(WireEncoding::Gzip);
+
+    let ws_handle = use_websocket_with_config(state.clone(), ws_config);
+
+    // Tell the stream who is signed in so alerts from their own rules reach
+    // this tab; an empty token signs the connection out again
+    let account = state.account;
+    let auth_handle = ws_handle.clone();
+    Effect::new(move |signed_in: Option<bool>| {
+        let token = account.with(|session| session.as_ref().map(|s| s.token.clone()));
+        if token.is_some() || signed_in == Some(true) {
+            auth_handle.send(ClientCommand::auth(token.clone().unwrap_or_default()));
+        }
+        token.is_some()
+    });
 
-    let _ws_handle = use_websocket(state.clone(), Some(ws_config.url.clone()));
+    // Upstream feed status for the status bar
+    if let Some(rest_url) = rest_url.clone() {
+        leptos::task::spawn_local(poll_health(rest_url.clone(), state.clone()));
+        // Scheduled events for the session clock and chart markers
+        leptos::task::spawn_local(poll_calendar(rest_url.clone(), state.clone()));
+        // Alert rules the server checks while the dashboard is closed
+        sync_alerts(rest_url.clone(), state.clone());
+        // Settings, layout and alert rules of the signed-in user
+        sync_account(rest_url.clone(), state.clone());
+        // Server status and controls for the admin page
+        sync_admin(rest_url, state.clone());
+    }
+
+    // Catch up on what the stream missed once it is back after an outage
+    if let Some(rest_url) = rest_url.clone() {
+        let market = state.market.clone();
+        let stale_since = state.stale_since;
+        Effect::new(move |was_stale: Option<bool>| {
+            let stale = stale_since.get().is_some();
+            if was_stale == Some(true) && !stale {
+                let symbol = market.symbol.get_untracked();
+                let interval = market.interval.get_untracked();
+                tracing::info!(%symbol, "stream restored, resyncing");
+                let resync = resync_snapshot(rest_url.clone(), market.clone(), symbol.clone());
+                let history =
+                    load_candle_history(rest_url.clone(), market.clone(), symbol, interval);
+                leptos::task::spawn_local(resync);
+                leptos::task::spawn_local(history);
+            }
+            stale
+        });
+    }
+
+    // Keep the server stream in step with the displayed symbol, interval and
+    // book depth (re-subscribing renegotiates the stream parameters)
+    let market = state.market.clone();
+    let subscribed = StoredValue::new(None::<Symbol>);
+    let history_for = StoredValue::new(None::<(Symbol, CandleInterval)>);
+    Effect::new(move |_| {
+        let sub = Subscription::new(market.symbol.get())
+            .interval(market.interval.get())
+            .depth_levels(market.depth_levels.get());
+        let symbol_changed = subscribed.with_value(|s| s.as_ref() != Some(&sub.symbol));
+        if let Some(previous) = subscribed.get_value().filter(|s| *s != sub.symbol) {
+            ws_handle.unsubscribe(previous);
+        }
+        // Render from REST snapshots until the stream catches up
+        if let Some(rest_url) = rest_url.clone().filter(|_| symbol_changed) {
+            leptos::task::spawn_local(load_snapshot(rest_url, market.clone(), sub.symbol.clone()));
+        }
+        // Backfill the chart from server history for each symbol/interval pair
+        let key = (sub.symbol.clone(), sub.interval);
+        let history_stale = history_for.with_value(|h| h.as_ref() != Some(&key));
+        if let Some(rest_url) = rest_url.clone().filter(|_| history_stale) {
+            let (symbol, interval) = key.clone();
+            let load = load_candle_history(rest_url, market.clone(), symbol, interval);
+            leptos::task::spawn_local(load);
+        }
+        history_for.set_value(Some(key));
+        subscribed.set_value(Some(sub.symbol.clone()));
+        ws_handle.subscribe_with(sub);
+    });
+
+    view! {
+        <Router>
+            <Routes fallback=|| view! { <Redirect path="/" /> }>
+                <Route path=path!("/") view=RoutedDashboard />
+                <Route path=path!("/d/:symbol") view=RoutedDashboard />
+                <Route path=path!("/d/:symbol/:interval") view=RoutedDashboard />
+                <Route path=path!("/admin") view=AdminPage />
+            </Routes>
+        </Router>
+    }
+}
+
+/// Dashboard bound to the URL: `/d/{symbol}/{interval}` selects the view on
+/// load and follows symbol/interval changes so links can be shared
+#[component]
+fn RoutedDashboard() -> impl IntoView {
+    let market = use_app_state().market;
+    let params = use_params_map();
+    let pathname = use_location().pathname;
+    let navigate = use_navigate();
+
+    // URL -> state
+    let route_market = market.clone();
+    Effect::new(move |_| {
+        let default_interval = route_market.interval.get_untracked();
+        let route = params.with(|p| {
+            DashboardRoute::from_params(p.get_str("symbol"), p.get_str("interval"), default_interval)
+        });
+        match route {
+            Some(route) => route.apply(&route_market),
+            None if params.with(|p| p.get_str("symbol").is_some()) => {
+                tracing::warn!("Ignoring invalid dashboard link: {}", pathname.get_untracked());
+            }
+            None => {}
+        }
+    });
+
+    // State -> URL (partial or invalid links are replaced, not pushed)
+    Effect::new(move |_| {
+        let path = DashboardRoute::current(&market).path();
+        let current = pathname.get_untracked();
+        if current != path {
+            let replace = DashboardRoute::from_path(&current).is_none();
+            navigate(&path, NavigateOptions { replace, ..Default::default() });
+        }
+    });
 
     view! {
         <Dashboard />
     }
 }
 
+/// One compute worker per core the page's own thread leaves free
+#[cfg(feature = "indicators")]
+fn compute_workers() -> usize {
+    let cores = window().navigator().hardware_concurrency() as usize;
+    cores.saturating_sub(1).clamp(1, MAX_COMPUTE_WORKERS)
+}
+
 fn get_ws_url() -> String {
-    dash_websocket::DEFAULT_WS_URL.to_string()
+    let url = dash_websocket::resolve_ws_url();
+    tracing::info!("WebSocket endpoint: {}", url);
+    url
 }
 
 fn main() {
     console_error_panic_hook::set_once();
-    tracing_wasm::set_as_global_default();
+    // Browser console, plus the last lines for the in-app log panel
+    let console = tracing_wasm::WASMLayer::new(tracing_wasm::WASMLayerConfig::default());
+    let subscriber =
+        tracing_subscriber::registry().with(console).with(RingLayer::new(LogLevel::Debug));
+    let _ = tracing::subscriber::set_global_default(subscriber);
 
     tracing::info!("🚀 BTC Exchange Dashboard starting...");
 
@@ -0,0 +1,53 @@
+//! Benchmarks for depth chart path generation
+//!
+//! Run with `cargo bench -p dash-charts`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use dash_charts::{area_path, area_path_with, LinearScale, Scale, StepPath, StepPosition};
+use dash_core::{MarketDepth, OrderBookLevel, OrderBookSnapshot, Symbol};
+
+fn sample_depth(levels: usize) -> MarketDepth {
+    let mut book = OrderBookSnapshot::new(Symbol::new("BTC-USD"));
+    book.bids = (0..levels)
+        .map(|i| OrderBookLevel::new(50_000.0 - i as f64, 0.5 + (i % 17) as f64 * 0.3, 1))
+        .collect();
+    book.asks = (0..levels)
+        .map(|i| OrderBookLevel::new(50_001.0 + i as f64, 0.5 + (i % 11) as f64 * 0.4, 1))
+        .collect();
+    MarketDepth::from_orderbook(&book)
+}
+
+/// Project one side of the depth curve into screen space
+fn depth_points(depth: &MarketDepth) -> Vec<(f64, f64)> {
+    let (min_price, max_price) = depth.price_range().unwrap_or((0.0, 1.0));
+    let x_scale = LinearScale::new().domain(min_price, max_price).range(0.0, 800.0);
+    let y_scale = LinearScale::new().domain(0.0, depth.max_depth() * 1.1).range(300.0, 0.0);
+
+    depth
+        .bid_depth
+        .iter()
+        .map(|p| (x_scale.scale(p.price), y_scale.scale(p.cumulative_quantity)))
+        .collect()
+}
+
+fn bench_depth_paths(c: &mut Criterion) {
+    let mut group = c.benchmark_group("depth_path");
+
+    for levels in [50, 500] {
+        let depth = sample_depth(levels);
+        let points = depth_points(&depth);
+        let step = StepPath { step_position: StepPosition::After };
+
+        group.bench_function(format!("{levels}/project"), |b| b.iter(|| depth_points(black_box(&depth))));
+        group.bench_function(format!("{levels}/area_linear"), |b| {
+            b.iter(|| area_path(black_box(&points), 300.0))
+        });
+        group.bench_function(format!("{levels}/area_stepped"), |b| {
+            b.iter(|| area_path_with(black_box(&points), 300.0, &step))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_depth_paths);
+criterion_main!(benches);
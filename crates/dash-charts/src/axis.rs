@@ -0,0 +1,99 @@
+//! Axis components
+//!
+//! Tick placement comes from the scales; these only lay out marks and labels.
+
+use crate::{chartkit::TimeScale, colors};
+use chrono::{DateTime, Local, TimeZone, Timelike, Utc};
+use dash_core::CandleInterval;
+use leptos::prelude::*;
+
+/// Tick label: "Mar 7" on daily steps and at midnight, "14:05" otherwise
+pub fn format_time_tick<Tz: TimeZone>(timestamp: i64, step: CandleInterval, tz: &Tz) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let Some(time) = DateTime::from_timestamp_millis(timestamp) else {
+        return String::new();
+    };
+    let time = time.with_timezone(tz);
+    let midnight = time.hour() == 0 && time.minute() == 0;
+    if step.as_millis() >= CandleInterval::D1.as_millis() || midnight {
+        time.format("%b %-d").to_string()
+    } else {
+        time.format("%H:%M").to_string()
+    }
+}
+
+/// (x, label) for each tick of `scale` in `tz`
+fn time_ticks<Tz: TimeZone>(scale: &TimeScale, tz: &Tz, count: usize) -> Vec<(f64, String)>
+where
+    Tz::Offset: std::fmt::Display,
+{
+    let step = scale.tick_step(count);
+    scale
+        .ticks_in(tz, count)
+        .into_iter()
+        .map(|ts| (scale.scale(ts), format_time_tick(ts, step, tz)))
+        .collect()
+}
+
+/// Horizontal time axis along the top edge of its group; translate it to the
+/// bottom of the plot
+#[component]
+pub fn TimeAxis(
+    scale: TimeScale,
+    /// Minimum horizontal gap between labels, in pixels
+    #[prop(default = 80.0)] min_spacing: f64,
+    /// Label in UTC rather than the browser's time zone
+    #[prop(optional)] utc: bool,
+) -> impl IntoView {
+    let (x_min, x_max) = scale.range_bounds();
+    let count = ((x_max - x_min).abs() / min_spacing.max(1.0)) as usize;
+    let ticks = if utc {
+        time_ticks(&scale, &Utc, count)
+    } else {
+        time_ticks(&scale, &Local, count)
+    };
+
+    view! {
+        <g class="time-axis">
+            <line
+                x1=x_min y1="0"
+                x2=x_max y2="0"
+                stroke=colors::BORDER
+                stroke-width="1"
+            />
+            {ticks.into_iter().map(|(x, label)| {
+                view! {
+                    <g transform=format!("translate({}, 0)", x)>
+                        <line y1="0" y2="4" stroke=colors::BORDER />
+                        <text
+                            y="14"
+                            text-anchor="middle"
+                            fill=colors::TEXT_MUTED
+                            font-size="10"
+                            font-family="JetBrains Mono, monospace"
+                        >
+                            {label}
+                        </text>
+                    </g>
+                }
+            }).collect_view()}
+        </g>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_time_tick() {
+        // 2025-03-07 14:05 UTC
+        let ts = 1_741_356_300_000;
+        assert_eq!(format_time_tick(ts, CandleInterval::M5, &Utc), "14:05");
+        assert_eq!(format_time_tick(ts, CandleInterval::D1, &Utc), "Mar 7");
+        let midnight = ts - (14 * 60 + 5) * 60_000;
+        assert_eq!(format_time_tick(midnight, CandleInterval::H1, &Utc), "Mar 7");
+    }
+}
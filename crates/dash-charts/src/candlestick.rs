@@ -3,32 +3,130 @@
 //! Renders OHLCV data as traditional candlestick chart with optional volume bars.
 
 use crate::{
-    chartkit::{BandScale, LinearScale, Scale, format_price},
-    colors, ChartDimensions, ChartMargin,
+    axis::TimeAxis,
+    chartkit::{
+        format_price, format_volume, next_defs_id, BandScale, Curve, DomainStrategy, LinearScale,
+        Scale, TimeScale,
+    },
+    colors,
+    measure::{format_span, Measurement},
+    series::{series_renderer, BaselineLevel, SeriesFrame},
+    ChartDimensions, ChartMargin, ChartViewport, RenderScheduler,
+};
+use dash_core::{
+    format_countdown, Candle, CandleHistory, CandleInterval, ChartType, MarketSession,
+    ScheduledEvent, TradingHours, VolumeMode,
 };
-use dash_core::{Candle, CandleHistory};
 use leptos::prelude::*;
+use wasm_bindgen::JsCast;
+
+/// Volume bars grow from zero with headroom above the tallest
+const VOLUME_DOMAIN: DomainStrategy = DomainStrategy::IncludeZero { ratio: 0.1 };
+
+/// Window size change per wheel notch
+const ZOOM_STEP: f64 = 1.15;
+
+/// Pointer travel (chart units) before a press becomes a pan instead of a click
+const DRAG_THRESHOLD: f64 = 4.0;
+
+/// Axis drags scale by e^(rate × dragged fraction of the pane)
+const AXIS_DRAG_RATE: f64 = 2.0;
+
+/// How close (chart units) a press must land to a level to grab it
+const LEVEL_GRAB: f64 = 5.0;
+
+/// What a press on the chart drags
+#[derive(Debug, Clone, Copy)]
+enum Drag {
+    /// Plot area: pan through time
+    Pan { x: f64, start: ChartViewport },
+    /// Price axis: scale the drawn price range around the pressed price
+    Price { y: f64, anchor: f64, range: (f64, f64), start: ChartViewport },
+    /// Time axis: stretch the window from its right edge
+    Time { x: f64, start: ChartViewport },
+    /// Measure tool: rule from a (candle open time, price) point
+    Measure { from: (i64, f64) },
+    /// Draggable level: move it to another price
+    Level { id: u64 },
+}
+
+/// Axis strip under the pointer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AxisHit {
+    Price,
+    Time,
+}
+
+/// Horizontal level across the price pane, such as a paper order
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartLevel {
+    /// Handed back when the level is dragged
+    pub id: u64,
+    pub price: f64,
+    pub label: String,
+    pub color: &'static str,
+    /// Whether the level can be dragged to another price
+    pub draggable: bool,
+}
+
+/// Tag floating above a price near the price axis, such as an open
+/// position's PnL at the last price
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartBadge {
+    pub price: f64,
+    pub text: String,
+    pub color: &'static str,
+}
+
+/// Indicator drawn over the price series
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndicatorLine {
+    pub label: String,
+    pub color: &'static str,
+    /// (candle open time, value), oldest first
+    pub points: Vec<(i64, f64)>,
+}
 
 /// Candlestick chart configuration
 #[derive(Debug, Clone)]
 pub struct CandlestickConfig {
+    pub chart_type: ChartType,
+    /// Reference price when `chart_type` is `ChartType::Baseline`
+    pub baseline: BaselineLevel,
+    /// Interpolation for the line, area and baseline chart types
+    pub curve: Curve,
     pub width: f64,
     pub height: f64,
     pub show_volume: bool,
+    /// What the volume bars show
+    pub volume_mode: VolumeMode,
     pub volume_height_ratio: f64,
     pub show_grid: bool,
     pub show_crosshair: bool,
+    /// Label the highest high and lowest low among the drawn candles
+    pub show_extremes: bool,
+    /// Price axis auto-scaling
+    pub y_domain: DomainStrategy,
+    /// Time labels under the candles
+    pub show_time_axis: bool,
 }
 
 impl Default for CandlestickConfig {
     fn default() -> Self {
         Self {
+            chart_type: ChartType::default(),
+            baseline: BaselineLevel::default(),
+            curve: Curve::default(),
             width: 800.0,
             height: 400.0,
             show_volume: true,
+            volume_mode: VolumeMode::default(),
             volume_height_ratio: 0.2,
             show_grid: true,
             show_crosshair: false,
+            show_extremes: true,
+            y_domain: DomainStrategy::default(),
+            show_time_axis: true,
         }
     }
 }
@@ -36,12 +134,19 @@ impl Default for CandlestickConfig {
 impl CandlestickConfig {
     pub fn compact() -> Self {
         Self {
+            chart_type: ChartType::default(),
+            baseline: BaselineLevel::default(),
+            curve: Curve::default(),
             width: 400.0,
             height: 200.0,
             show_volume: false,
+            volume_mode: VolumeMode::default(),
             volume_height_ratio: 0.0,
             show_grid: false,
             show_crosshair: false,
+            show_extremes: false,
+            y_domain: DomainStrategy::default(),
+            show_time_axis: false,
         }
     }
 }
@@ -51,9 +156,17 @@ impl CandlestickConfig {
 struct ChartState {
     candles: Vec<Candle>,
     y_scale: LinearScale,
-    vol_scale: LinearScale,
+    /// Only computed when volume is shown
+    vol_scale: Option<LinearScale>,
     x_scale: BandScale,
+    /// Candle open times mapped to band centers (assumes even spacing)
+    time_scale: TimeScale,
     bandwidth: f64,
+    interval: CandleInterval,
+    /// Unfilled gaps: (x position between candles, missing intervals)
+    gap_markers: Vec<(f64, usize)>,
+    /// (candle index, price) of the highest high and lowest low, when shown
+    extremes: Option<((usize, f64), (usize, f64))>,
 }
 
 /// Candlestick chart component
@@ -61,6 +174,38 @@ struct ChartState {
 pub fn CandlestickChart(
     candles: RwSignal<CandleHistory>,
     #[prop(optional)] config: Option<CandlestickConfig>,
+    /// Coalesce redraws to animation frames, capped at this rate (`None` = display rate)
+    #[prop(optional, into)] max_fps: Option<Signal<Option<u32>>>,
+    /// User-drawn horizontal lines across the price pane
+    #[prop(optional, into)] price_lines: Option<Signal<Vec<f64>>>,
+    /// Receives the price under the pointer when the price pane is clicked
+    #[prop(optional, into)] on_price_click: Option<Callback<f64>>,
+    /// Sessions whose opens are marked with vertical lines
+    #[prop(optional, into)] sessions: Option<Signal<Vec<MarketSession>>>,
+    /// Scheduled events marked with labelled vertical lines
+    #[prop(optional, into)] events: Option<Signal<Vec<ScheduledEvent>>>,
+    /// Recurring time ranges (market hours, weekends) shaded behind the candles
+    #[prop(optional, into)] shading: Option<Signal<Vec<TradingHours>>>,
+    /// Order levels with a price tag; draggable ones have a handle
+    #[prop(optional, into)] levels: Option<Signal<Vec<ChartLevel>>>,
+    /// Receives (level id, price) when a dragged level is dropped
+    #[prop(optional, into)] on_level_drag: Option<Callback<(u64, f64)>>,
+    /// Server time; shows the countdown to the forming candle's close
+    #[prop(optional, into)] now_ms: Option<Signal<i64>>,
+    /// Current price and its direction color, marked across the price pane
+    #[prop(optional, into)] last_price: Option<Signal<Option<(f64, &'static str)>>>,
+    /// Tag drawn above its price, clamped to the pane
+    #[prop(optional, into)] badge: Option<Signal<Option<ChartBadge>>>,
+    /// Indicator lines over the price series
+    #[prop(optional, into)] indicators: Option<Signal<Vec<IndicatorLine>>>,
+    /// Ticker 24h (low, high), drawn as dotted watermarks
+    #[prop(optional, into)] range_24h: Option<Signal<Option<(f64, f64)>>>,
+    /// Zoom/pan window; enables wheel zoom, drag to pan, "Go to latest" and
+    /// axis drag-to-scale. Without it the whole history is drawn auto-scaled.
+    #[prop(optional)] viewport: Option<RwSignal<ChartViewport>>,
+    /// While true, dragging across the plot measures the range instead of
+    /// panning; the ruler stays until the next press or the tool is left
+    #[prop(optional, into)] measure: Option<Signal<bool>>,
 ) -> impl IntoView {
     let config = config.unwrap_or_default();
     
@@ -77,30 +222,59 @@ pub fn CandlestickChart(
     let volume_y_offset = price_height + 10.0;
 
     let show_volume = config.show_volume;
+    let volume_mode = config.volume_mode;
     let show_grid = config.show_grid;
+    let show_extremes = config.show_extremes;
+    let show_time_axis = config.show_time_axis;
+    let time_axis_y = if show_volume { volume_y_offset + volume_height } else { price_height };
+    let chart_type = config.chart_type;
+    let y_domain = config.y_domain;
+    let baseline = config.baseline;
+    let curve = config.curve;
+    let defs_id = next_defs_id();
+    let pane_clip = format!("{}-pane", defs_id);
 
-    // Compute chart state from candle data
-    let chart_state = move || {
-        let history = candles.get();
-        let candle_list = &history.candles;
+    // Compute chart state from the candles inside the window
+    let compute = move |history: &CandleHistory, window: ChartViewport| {
+        let all = history.candles.as_slice();
+        let candle_list = &all[window.window(all)];
 
         if candle_list.is_empty() {
             return None;
         }
 
-        // Price range with padding
-        let (price_min, price_max) = history.price_range().unwrap_or((0.0, 1.0));
-        let price_padding = (price_max - price_min) * 0.05;
-        
+        // Price range, scaled per the configured strategy
+        let (price_min, price_max) = candle_list.iter().fold((f64::MAX, f64::MIN), |(lo, hi), c| {
+            (lo.min(c.low.as_f64()), hi.max(c.high.as_f64()))
+        });
+        let last_close = candle_list.last().map(|c| c.close.as_f64());
+        let (y_min, y_max) = window
+            .price_range
+            .unwrap_or_else(|| y_domain.domain(price_min, price_max, last_close));
+
         let y_scale = LinearScale::new()
-            .domain(price_min - price_padding, price_max + price_padding)
+            .domain(y_min, y_max)
             .range(price_height, 0.0);
 
-        // Volume scale
-        let (_, vol_max) = history.volume_range().unwrap_or((0.0, 1.0));
-        let vol_scale = LinearScale::new()
-            .domain(0.0, vol_max * 1.1)
-            .range(volume_height, 0.0);
+        // Volume scale; hidden layers skip their work
+        let vol_scale = show_volume.then(|| {
+            let (vol_min, vol_max) = match volume_mode {
+                VolumeMode::Delta => {
+                    let max = candle_list
+                        .iter()
+                        .filter_map(|c| c.volume_delta())
+                        .fold(0.0, |max: f64, d| max.max(d.abs()));
+                    (0.0, if max > 0.0 { max } else { 1.0 })
+                }
+                _ => candle_list.iter().fold((f64::MAX, f64::MIN), |(lo, hi), c| {
+                    (lo.min(c.volume.as_f64()), hi.max(c.volume.as_f64()))
+                }),
+            };
+            let (vol_lo, vol_hi) = VOLUME_DOMAIN.domain(vol_min, vol_max, None);
+            LinearScale::new()
+                .domain(vol_lo, vol_hi)
+                .range(volume_height, 0.0)
+        });
 
         // X scale (band scale for candle positions)
         let x_scale = BandScale::new(candle_list.len())
@@ -109,21 +283,324 @@ pub fn CandlestickChart(
 
         let bandwidth = x_scale.bandwidth();
 
+        let (first, last) = (&candle_list[0], &candle_list[candle_list.len() - 1]);
+        let time_scale = TimeScale::new()
+            .domain(first.timestamp, last.timestamp)
+            .range(x_scale.scale_center(0), x_scale.scale_center(candle_list.len() - 1));
+
+        // Mark gaps that were too large to fill instead of silently compressing time
+        let step = history.interval.as_millis();
+        let gap_markers = candle_list
+            .windows(2)
+            .enumerate()
+            .filter_map(|(i, pair)| {
+                let missing = (pair[1].timestamp - pair[0].timestamp) / step - 1;
+                (missing > 0).then(|| {
+                    let x = (x_scale.scale(i) + bandwidth + x_scale.scale(i + 1)) / 2.0;
+                    (x, missing as usize)
+                })
+            })
+            .collect();
+
         Some(ChartState {
-            candles: candle_list.clone(),
+            candles: candle_list.to_vec(),
             y_scale,
             vol_scale,
             x_scale,
+            time_scale,
             bandwidth,
+            interval: history.interval,
+            gap_markers,
+            extremes: show_extremes.then(|| extremes(candle_list)).flatten(),
+        })
+    };
+
+    // Recompute once per change, or once per scheduled frame when throttled
+    let scheduler = max_fps.map(RenderScheduler::new);
+    if let Some(scheduler) = scheduler {
+        scheduler.watch(candles);
+    }
+    // Window moves redraw straight away, also when throttled
+    let window = move || viewport.map(|v| v.get()).unwrap_or_default();
+    let chart_state = Memo::new_with_compare(
+        move |_| match scheduler {
+            Some(scheduler) => {
+                scheduler.track();
+                let window = window();
+                candles.with_untracked(|history| compute(history, window))
+            }
+            None => {
+                let window = window();
+                candles.with(|history| compute(history, window))
+            }
+        },
+        |_, _| true,
+    );
+
+    // Pointer position in inner chart coordinates
+    let pointer = move |ev: &leptos::ev::MouseEvent| {
+        let svg = ev.current_target()?.dyn_into::<web_sys::Element>().ok()?;
+        let rect = svg.get_bounding_client_rect();
+        Some(dims.to_inner(
+            ev.client_x() as f64 - rect.left(),
+            ev.client_y() as f64 - rect.top(),
+            rect.width(),
+            rect.height(),
+        ))
+    };
+
+    // Price axis right of the price pane, time axis under the candles
+    let axis_hit = move |x: f64, y: f64| {
+        if x > dims.inner_width() && (0.0..=price_height).contains(&y) {
+            Some(AxisHit::Price)
+        } else if show_time_axis && y > time_axis_y && (0.0..=dims.inner_width()).contains(&x) {
+            Some(AxisHit::Time)
+        } else {
+            None
+        }
+    };
+
+    // Candle open time and price under a point of the plot
+    let plot_point = move |x: f64, y: f64| {
+        chart_state.with_untracked(|state| {
+            let state = state.as_ref()?;
+            let candle = state.candles.get(state.x_scale.invert(x) as usize)?;
+            Some((candle.timestamp, state.y_scale.invert(y.clamp(0.0, price_height))))
+        })
+    };
+
+    // Measure ruler ends; a new press or leaving the tool removes it
+    let ruler = RwSignal::new(None::<((i64, f64), (i64, f64))>);
+    let measuring = move || measure.is_some_and(|m| m.get_untracked());
+    if let Some(measure) = measure {
+        Effect::new(move |_| {
+            if !measure.get() {
+                ruler.set(None);
+            }
+        });
+    }
+
+    // `dragged` swallows the click that ends a pan so it doesn't reach the
+    // drawing tools
+    let drag = StoredValue::new(None::<Drag>);
+    let dragged = StoredValue::new(false);
+
+    // Level being dragged and where it would drop
+    let level_drag = RwSignal::new(None::<(u64, f64)>);
+    let grab_level = move |y: f64| {
+        on_level_drag?;
+        let levels = levels?.get_untracked();
+        chart_state.with_untracked(|state| {
+            let y_scale = &state.as_ref()?.y_scale;
+            levels
+                .iter()
+                .filter(|level| level.draggable)
+                .map(|level| (level.id, (y_scale.scale(level.price) - y).abs()))
+                .filter(|(_, distance)| *distance <= LEVEL_GRAB)
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map(|(id, _)| Drag::Level { id })
         })
     };
 
+    let on_pointer_down = move |ev: leptos::ev::PointerEvent| {
+        let Some((x, y)) = pointer(&ev) else { return };
+        if ev.button() != 0 {
+            return;
+        }
+        let hit = axis_hit(x, y);
+        let grabbed = hit.is_none().then(|| grab_level(y)).flatten();
+        let press = if grabbed.is_some() {
+            grabbed
+        } else if hit.is_none() && measuring() {
+            ruler.set(None);
+            plot_point(x, y).map(|from| Drag::Measure { from })
+        } else {
+            let Some(viewport) = viewport else { return };
+            let start = viewport.get_untracked();
+            match hit {
+                Some(AxisHit::Price) => chart_state.with_untracked(|state| {
+                    let y_scale = &state.as_ref()?.y_scale;
+                    let range = y_scale.domain_bounds();
+                    Some(Drag::Price { y, anchor: y_scale.invert(y), range, start })
+                }),
+                Some(AxisHit::Time) => Some(Drag::Time { x, start }),
+                None => Some(Drag::Pan { x, start }),
+            }
+        };
+        drag.set_value(press);
+        dragged.set_value(false);
+    };
+
+    let on_pointer_move = move |ev: leptos::ev::PointerEvent| {
+        let Some(press) = drag.get_value() else { return };
+        let Some((x, y)) = pointer(&ev) else { return };
+        let next = match press {
+            Drag::Pan { x: x0, start } => {
+                let dx = x - x0;
+                if !dragged.get_value() && dx.abs() < DRAG_THRESHOLD {
+                    return;
+                }
+                candles.with_untracked(|history| {
+                    let all = history.candles.as_slice();
+                    let shown = start.window(all).len().max(1);
+                    let mut next = start;
+                    next.pan(all, -(dx * shown as f64 / dims.inner_width()).round() as isize);
+                    next
+                })
+            }
+            Drag::Price { y: y0, anchor, range, start } => {
+                // Dragging down shows more prices, up zooms in
+                let factor = ((y - y0) / price_height * AXIS_DRAG_RATE).exp();
+                let mut next = start;
+                next.scale_price(range, anchor, factor);
+                next
+            }
+            Drag::Time { x: x0, start } => {
+                // Dragging right shows fewer, wider candles
+                let factor = ((x0 - x) / dims.inner_width() * AXIS_DRAG_RATE).exp();
+                candles.with_untracked(|history| {
+                    let mut next = start;
+                    next.zoom(history.candles.as_slice(), factor, 1.0);
+                    next
+                })
+            }
+            Drag::Measure { from } => {
+                if let Some(to) = plot_point(x, y) {
+                    ruler.set(Some((from, to)));
+                }
+                dragged.set_value(true);
+                return;
+            }
+            Drag::Level { id } => {
+                let price = chart_state.with_untracked(|state| {
+                    Some(state.as_ref()?.y_scale.invert(y.clamp(0.0, price_height)))
+                });
+                level_drag.set(price.map(|price| (id, price)));
+                dragged.set_value(true);
+                return;
+            }
+        };
+        dragged.set_value(true);
+        let Some(viewport) = viewport else { return };
+        if next != viewport.get_untracked() {
+            viewport.set(next);
+        }
+    };
+
+    // Double-clicking an axis returns it to auto-scale
+    let on_double_click = move |ev: leptos::ev::MouseEvent| {
+        let Some(viewport) = viewport else { return };
+        let Some((x, y)) = pointer(&ev) else { return };
+        match axis_hit(x, y) {
+            Some(AxisHit::Price) => viewport.update(ChartViewport::auto_price),
+            Some(AxisHit::Time) => viewport.update(ChartViewport::reset_time),
+            None => {}
+        }
+    };
+
+    // Releasing a dragged level drops it; leaving the chart puts it back
+    let end_drag = move |ev: leptos::ev::PointerEvent| {
+        drag.set_value(None);
+        let Some((id, price)) = level_drag.get_untracked() else { return };
+        level_drag.set(None);
+        if ev.type_() == "pointerup"
+            && let Some(callback) = on_level_drag
+        {
+            callback.run((id, price));
+        }
+    };
+
+    let on_wheel = move |ev: leptos::ev::WheelEvent| {
+        let Some(viewport) = viewport else { return };
+        let Some((x, _)) = pointer(&ev) else { return };
+        if ev.delta_y() == 0.0 {
+            return;
+        }
+        ev.prevent_default();
+        let factor = if ev.delta_y() > 0.0 { ZOOM_STEP } else { 1.0 / ZOOM_STEP };
+        let focus = x / dims.inner_width();
+        candles.with_untracked(|history| {
+            viewport.update(|v| v.zoom(history.candles.as_slice(), factor, focus));
+        });
+    };
+
+    let on_click = move |ev: leptos::ev::MouseEvent| {
+        if dragged.get_value() {
+            dragged.set_value(false);
+            return;
+        }
+        let Some(callback) = on_price_click else { return };
+        let Some((x, y)) = pointer(&ev) else { return };
+        if (0.0..=price_height).contains(&y)
+            && axis_hit(x, y).is_none()
+            && let Some(state) = chart_state.get_untracked()
+        {
+            callback.run(state.y_scale.invert(y));
+        }
+    };
+
+    // Close price and countdown of the forming candle; ticks re-render only
+    // this label, the chart state is read without cloning
+    let countdown = move || {
+        let now = now_ms?.get();
+        chart_state.with(|state| {
+            let state = state.as_ref()?;
+            let last = state.candles.last()?;
+            let close_at = state.interval.next_close(now);
+            (last.timestamp + state.interval.as_millis() == close_at)
+                .then(|| (state.y_scale.scale(last.close.as_f64()), format_countdown(close_at - now)))
+        })
+    };
+
+    // Last price, clamped to the pane edge when outside the candle range;
+    // updates on every print without touching the candles
+    let last_price_mark = move || {
+        let (price, color) = last_price?.get()?;
+        chart_state.with(|state| {
+            let y = state.as_ref()?.y_scale.scale(price).clamp(0.0, price_height);
+            Some((y, price, color))
+        })
+    };
+
+    // Ruler ends in pane coordinates, pinned to the window edge once
+    // scrolled out of it, with the range measured over the whole history
+    let ruler_mark = move || {
+        let (from, to) = ruler.get()?;
+        let measurement = candles.with(|history| {
+            let all = history.candles.as_slice();
+            let last = all.len().checked_sub(1)?;
+            let index = |ts: i64| all.partition_point(|c| c.timestamp < ts).min(last);
+            Measurement::between(all, (index(from.0), from.1), (index(to.0), to.1))
+        })?;
+        chart_state.with(|state| {
+            let state = state.as_ref()?;
+            let last = state.candles.len().checked_sub(1)?;
+            let x = |ts: i64| {
+                let index = state.candles.partition_point(|c| c.timestamp < ts).min(last);
+                state.x_scale.scale_center(index)
+            };
+            let y = |price: f64| state.y_scale.scale(price).clamp(0.0, price_height);
+            Some(((x(from.0), y(from.1)), (x(to.0), y(to.1)), measurement))
+        })
+    };
+
+    let chart_state = move || chart_state.get();
+
     view! {
         <svg
             class="candlestick-chart"
+            role="img"
+            aria-label="Candlestick price chart"
             viewBox=dims.viewbox()
             preserveAspectRatio="xMidYMid meet"
             style="width: 100%; height: 100%;"
+            on:click=on_click
+            on:pointerdown=on_pointer_down
+            on:pointermove=on_pointer_move
+            on:pointerup=end_drag
+            on:pointerleave=end_drag
+            on:wheel=on_wheel
+            on:dblclick=on_double_click
         >
             // Background
             <rect
@@ -135,6 +612,25 @@ pub fn CandlestickChart(
 
             // Chart area
             <g transform=dims.inner_transform()>
+                // Shaded time ranges, behind everything else
+                {move || {
+                    let shading = shading?.get();
+                    chart_state().map(|state| {
+                        shade_spans(&state, &shading).into_iter().map(|(x1, x2)| {
+                            view! {
+                                <rect
+                                    class="session-shade"
+                                    x=x1 y="0"
+                                    width=x2 - x1
+                                    height=dims.inner_height()
+                                    fill=colors::TEXT_MUTED
+                                    fill-opacity="0.06"
+                                />
+                            }
+                        }).collect_view()
+                    })
+                }}
+
                 // Grid lines
                 {move || {
                     if show_grid {
@@ -151,47 +647,141 @@ pub fn CandlestickChart(
                     }
                 }}
 
-                // Candlesticks
+                // Price series, clipped so a hand-scaled price axis stays in its pane
+                <defs>
+                    <clipPath id=pane_clip.clone()>
+                        <rect x="0" y="0" width=dims.inner_width() height=price_height />
+                    </clipPath>
+                </defs>
+                <g clip-path=format!("url(#{})", pane_clip)>
+                    {move || {
+                        chart_state().map(|state| {
+                            let frame = SeriesFrame {
+                                candles: &state.candles,
+                                x_scale: &state.x_scale,
+                                y_scale: &state.y_scale,
+                                bandwidth: state.bandwidth,
+                                width: dims.inner_width(),
+                                height: price_height,
+                                defs_id: &defs_id,
+                                curve,
+                            };
+                            series_renderer(chart_type, baseline).render(&frame)
+                        })
+                    }}
+                    {move || {
+                        let lines = indicators?.get();
+                        let state = chart_state()?;
+                        Some(lines.iter().enumerate().map(|(row, line)| {
+                            let path = indicator_path(&state, &line.points);
+                            let label_y = 12.0 + row as f64 * 12.0;
+                            view! {
+                                <g class="chart-indicator">
+                                    <path
+                                        d=path
+                                        fill="none"
+                                        stroke=line.color
+                                        stroke-width="1.25"
+                                    />
+                                    <text
+                                        x="6"
+                                        y=label_y
+                                        fill=line.color
+                                        font-size="10"
+                                        font-family="JetBrains Mono, monospace"
+                                    >
+                                        {line.label.clone()}
+                                    </text>
+                                </g>
+                            }
+                        }).collect_view())
+                    }}
+                </g>
+
+                // Gap markers
                 {move || {
                     chart_state().map(|state| {
-                        state.candles.iter().enumerate().map(|(i, candle)| {
-                            let x = state.x_scale.scale(i);
-                            let x_center = x + state.bandwidth / 2.0;
-
-                            // Wick coordinates
-                            let wick_y1 = state.y_scale.scale(candle.high.as_f64());
-                            let wick_y2 = state.y_scale.scale(candle.low.as_f64());
-
-                            // Body coordinates
-                            let body_top = candle.open.as_f64().max(candle.close.as_f64());
-                            let body_bottom = candle.open.as_f64().min(candle.close.as_f64());
-                            let body_y = state.y_scale.scale(body_top);
-                            let body_h = (state.y_scale.scale(body_bottom) - body_y).max(1.0);
-
-                            let fill = candle.fill_color();
+                        state.gap_markers.into_iter().map(|(x, missing)| {
+                            view! {
+                                <line
+                                    class="candle-gap"
+                                    x1=x y1="0"
+                                    x2=x y2=price_height
+                                    stroke=colors::WARN
+                                    stroke-width="1"
+                                    stroke-opacity="0.6"
+                                    stroke-dasharray="3,3"
+                                >
+                                    <title>{format!("{} missing candles", missing)}</title>
+                                </line>
+                            }
+                        }).collect_view()
+                    })
+                }}
 
+                // Session opens
+                {move || {
+                    let sessions = sessions?.get();
+                    chart_state().map(|state| {
+                        session_marks(&state, &sessions).into_iter().map(|(x, session)| {
                             view! {
-                                <g class=format!("candle {}", candle.css_class())>
-                                    // Wick
+                                <g class="session-marker" transform=format!("translate({}, 0)", x)>
                                     <line
-                                        x1=x_center
-                                        y1=wick_y1
-                                        x2=x_center
-                                        y2=wick_y2
-                                        stroke=fill
+                                        x1="0" y1="0"
+                                        x2="0" y2=price_height
+                                        stroke=colors::TEXT_MUTED
                                         stroke-width="1"
+                                        stroke-opacity="0.4"
+                                        stroke-dasharray="1,3"
                                     />
-                                    // Body
-                                    <rect
-                                        x=x
-                                        y=body_y
-                                        width=state.bandwidth
-                                        height=body_h
-                                        fill=fill
-                                        stroke=fill
+                                    <text
+                                        x="3"
+                                        y="10"
+                                        fill=colors::TEXT_MUTED
+                                        font-size="9"
+                                        font-family="JetBrains Mono, monospace"
+                                    >
+                                        {session.code()}
+                                    </text>
+                                </g>
+                            }
+                        }).collect_view()
+                    })
+                }}
+
+                // Scheduled events, labelled along the bottom of the price pane
+                {move || {
+                    let events = events?.get();
+                    chart_state().map(|state| {
+                        event_marks(&state, &events).into_iter().map(|(x, event)| {
+                            let color = event.kind.color();
+                            let tooltip = format!(
+                                "{} · {}",
+                                event.title,
+                                chrono::DateTime::from_timestamp_millis(event.timestamp)
+                                    .unwrap_or_default()
+                                    .format("%b %-d %H:%M UTC"),
+                            );
+                            view! {
+                                <g class="event-marker" transform=format!("translate({}, 0)", x)>
+                                    <title>{tooltip}</title>
+                                    <line
+                                        x1="0" y1="0"
+                                        x2="0" y2=price_height
+                                        stroke=color
                                         stroke-width="1"
-                                        rx="1"
+                                        stroke-opacity="0.6"
+                                        stroke-dasharray="4,2"
                                     />
+                                    <text
+                                        x="3"
+                                        y=price_height - 4.0
+                                        fill=color
+                                        font-size="9"
+                                        font-family="JetBrains Mono, monospace"
+                                    >
+                                        {event.marker_label()}
+                                    </text>
                                 </g>
                             }
                         }).collect_view()
@@ -200,38 +790,328 @@ pub fn CandlestickChart(
 
                 // Volume bars
                 {move || {
-                    if show_volume {
-                        chart_state().map(|state| {
+                    chart_state().and_then(|state| {
+                        let vol_scale = state.vol_scale.clone()?;
+                        Some(view! {
+                            <g transform=format!("translate(0, {})", volume_y_offset)>
+                                {state.candles.iter().enumerate().flat_map(|(i, candle)| {
+                                    let x = state.x_scale.scale(i);
+                                    let bandwidth = state.bandwidth;
+                                    volume_bars(candle, volume_mode)
+                                        .into_iter()
+                                        .map(|(base, top, fill)| {
+                                            let bar_y = vol_scale.scale(top);
+                                            let bar_h = (vol_scale.scale(base) - bar_y).max(0.0);
+                                            view! {
+                                                <rect
+                                                    x=x
+                                                    y=bar_y
+                                                    width=bandwidth
+                                                    height=bar_h
+                                                    fill=fill
+                                                />
+                                            }
+                                        })
+                                        .collect::<Vec<_>>()
+                                }).collect_view()}
+                            </g>
+                        })
+                    })
+                }}
+
+                // Time axis
+                {move || {
+                    if !show_time_axis {
+                        return None;
+                    }
+                    chart_state().map(|state| view! {
+                        <g transform=format!("translate(0, {})", time_axis_y)>
+                            <TimeAxis scale=state.time_scale />
+                        </g>
+                    })
+                }}
+
+                // User price lines
+                {move || {
+                    let lines = price_lines?.get();
+                    chart_state().map(|state| {
+                        let (lo, hi) = state.y_scale.domain_bounds();
+                        lines.into_iter().filter(|p| (lo..=hi).contains(p)).map(|price| {
+                            let y = state.y_scale.scale(price);
                             view! {
-                                <g transform=format!("translate(0, {})", volume_y_offset)>
-                                    {state.candles.iter().enumerate().map(|(i, candle)| {
-                                        let x = state.x_scale.scale(i);
-                                        let vol = candle.volume.as_f64();
-                                        let bar_y = state.vol_scale.scale(vol);
-                                        let bar_h = (volume_height - bar_y).max(0.0);
-                                        
-                                        let fill = if candle.is_bullish() {
-                                            colors::bull_alpha(0.5)
-                                        } else {
-                                            colors::bear_alpha(0.5)
-                                        };
-
-                                        view! {
-                                            <rect
-                                                x=x
-                                                y=bar_y
-                                                width=state.bandwidth
-                                                height=bar_h
-                                                fill=fill
-                                            />
-                                        }
-                                    }).collect_view()}
+                                <g class="price-line" transform=format!("translate(0, {})", y)>
+                                    <line
+                                        x1="0" y1="0"
+                                        x2=dims.inner_width() y2="0"
+                                        stroke=colors::WARN
+                                        stroke-width="1"
+                                    />
+                                    <text
+                                        x=dims.inner_width() + 8.0
+                                        dy="0.32em"
+                                        fill=colors::WARN
+                                        font-size="10"
+                                        font-family="JetBrains Mono, monospace"
+                                    >
+                                        {format_price(price, 2)}
+                                    </text>
                                 </g>
                             }
-                        })
-                    } else {
-                        None
+                        }).collect_view()
+                    })
+                }}
+
+                // Visible-range high / low
+                {move || {
+                    if !show_extremes {
+                        return None;
                     }
+                    chart_state().and_then(|state| {
+                        let ((hi_i, high), (lo_i, low)) = state.extremes?;
+                        let label_x = |i: usize| {
+                            (state.x_scale.scale(i) + state.bandwidth / 2.0).clamp(30.0, dims.inner_width() - 30.0)
+                        };
+                        Some(view! {
+                            <g
+                                class="range-extremes"
+                                font-size="9"
+                                font-family="JetBrains Mono, monospace"
+                                text-anchor="middle"
+                            >
+                                <text x=label_x(hi_i) y=state.y_scale.scale(high) - 4.0 fill=colors::BULL>
+                                    {format!("H {}", format_price(high, 2))}
+                                </text>
+                                <text x=label_x(lo_i) y=state.y_scale.scale(low) + 11.0 fill=colors::BEAR>
+                                    {format!("L {}", format_price(low, 2))}
+                                </text>
+                            </g>
+                        })
+                    })
+                }}
+
+                // 24h high / low
+                {move || {
+                    let (low, high) = range_24h?.get()?;
+                    chart_state().map(|state| {
+                        let (lo, hi) = state.y_scale.domain_bounds();
+                        [(high, "24h H", colors::BULL), (low, "24h L", colors::BEAR)]
+                            .into_iter()
+                            .filter(|(price, _, _)| (lo..=hi).contains(price))
+                            .map(|(price, label, color)| {
+                                let y = state.y_scale.scale(price);
+                                view! {
+                                    <g class="range-24h" transform=format!("translate(0, {})", y)>
+                                        <line
+                                            x1="0" y1="0"
+                                            x2=dims.inner_width() y2="0"
+                                            stroke=color
+                                            stroke-width="1"
+                                            stroke-opacity="0.5"
+                                            stroke-dasharray="1,2"
+                                        />
+                                        <text
+                                            x="4"
+                                            dy="-3"
+                                            fill=color
+                                            font-size="9"
+                                            font-family="JetBrains Mono, monospace"
+                                        >
+                                            {format!("{} {}", label, format_price(price, 2))}
+                                        </text>
+                                    </g>
+                                }
+                            })
+                            .collect_view()
+                    })
+                }}
+
+                // Order levels, the dragged one at its drop price
+                {move || {
+                    let levels = levels?.get();
+                    let dragging = level_drag.get();
+                    chart_state().map(|state| {
+                        let (lo, hi) = state.y_scale.domain_bounds();
+                        levels.into_iter().filter_map(|level| {
+                            let price = match dragging {
+                                Some((id, price)) if id == level.id => price,
+                                _ => level.price,
+                            };
+                            if !(lo..=hi).contains(&price) {
+                                return None;
+                            }
+                            let y = state.y_scale.scale(price);
+                            let class =
+                                if level.draggable { "order-level draggable" } else { "order-level" };
+                            Some(view! {
+                                <g class=class transform=format!("translate(0, {})", y)>
+                                    <line
+                                        x1="0" y1="0"
+                                        x2=dims.inner_width() y2="0"
+                                        stroke=level.color
+                                        stroke-width="1"
+                                        stroke-dasharray=if level.draggable { "6,3" } else { "2,3" }
+                                    />
+                                    <text
+                                        x="4"
+                                        dy="-3"
+                                        fill=level.color
+                                        font-size="9"
+                                        font-family="JetBrains Mono, monospace"
+                                    >
+                                        {format!("{} {}", level.label, format_price(price, 2))}
+                                    </text>
+                                    {level.draggable.then(|| view! {
+                                        <rect
+                                            class="level-handle"
+                                            x=dims.inner_width() - 14.0 y="-4"
+                                            width="12" height="8"
+                                            rx="2"
+                                            fill=level.color
+                                        />
+                                    })}
+                                </g>
+                            })
+                        }).collect_view()
+                    })
+                }}
+
+                // Last price line
+                {move || {
+                    last_price_mark().map(|(y, _, color)| view! {
+                        <line
+                            class="last-price-line"
+                            x1="0" y1=y
+                            x2=dims.inner_width() y2=y
+                            stroke=color
+                            stroke-width="1"
+                            stroke-dasharray="4,3"
+                        />
+                    })
+                }}
+
+                // Badge above its price, clear of the level handles
+                {move || {
+                    let badge = badge?.get()?;
+                    let y = chart_state()?.y_scale.scale(badge.price).clamp(0.0, price_height);
+                    let width = badge.text.chars().count() as f64 * 6.0 + 10.0;
+                    let x = dims.inner_width() - width - 20.0;
+                    let y = if y < 18.0 { y + 4.0 } else { y - 18.0 };
+                    Some(view! {
+                        <g class="chart-badge" transform=format!("translate({}, {})", x, y)>
+                            <rect
+                                width=width height="14"
+                                rx="3"
+                                fill=colors::BG_VOID
+                                stroke=badge.color
+                                stroke-width="1"
+                            />
+                            <text
+                                x="5"
+                                y="7"
+                                dy="0.32em"
+                                fill=badge.color
+                                font-size="10"
+                                font-weight="600"
+                                font-family="JetBrains Mono, monospace"
+                            >
+                                {badge.text}
+                            </text>
+                        </g>
+                    })
+                }}
+
+                // Measure ruler with its readout beside the end point
+                {move || {
+                    ruler_mark().map(|((x0, y0), (x1, y1), m)| {
+                        let color = if m.is_up() { colors::BULL } else { colors::BEAR };
+                        let (label_w, label_h) = (136.0, 46.0);
+                        let label_x = if x1 + 8.0 + label_w > dims.inner_width() {
+                            (x1 - 8.0 - label_w).max(0.0)
+                        } else {
+                            x1 + 8.0
+                        };
+                        let label_y =
+                            (y1 - label_h / 2.0).clamp(0.0, (price_height - label_h).max(0.0));
+                        let sign = if m.price_change() < 0.0 { "-" } else { "+" };
+                        view! {
+                            <g
+                                class="measure-ruler"
+                                pointer-events="none"
+                                font-size="10"
+                                font-family="JetBrains Mono, monospace"
+                            >
+                                <rect
+                                    x=x0.min(x1) y=y0.min(y1)
+                                    width=(x1 - x0).abs() height=(y1 - y0).abs()
+                                    fill=color
+                                    fill-opacity="0.12"
+                                    stroke=color
+                                    stroke-opacity="0.6"
+                                    stroke-dasharray="3,2"
+                                />
+                                <line x1=x0 y1=y0 x2=x1 y2=y1 stroke=color stroke-width="1" />
+                                <g transform=format!("translate({}, {})", label_x, label_y)>
+                                    <rect
+                                        width=label_w height=label_h
+                                        fill=colors::BG_ELEVATED
+                                        stroke=color
+                                        rx="3"
+                                    />
+                                    <text x="6" y="14" fill=color>
+                                        {format!(
+                                            "{}{} ({:+.2}%)",
+                                            sign,
+                                            format_price(m.price_change().abs(), 2),
+                                            m.percent_change(),
+                                        )}
+                                    </text>
+                                    <text x="6" y="28" fill=colors::TEXT_PRIMARY>
+                                        {format!("{} bars, {}", m.bars, format_span(m.duration_ms))}
+                                    </text>
+                                    <text x="6" y="41" fill=colors::TEXT_MUTED>
+                                        {format!("Vol {}", format_volume(m.volume))}
+                                    </text>
+                                </g>
+                            </g>
+                        }
+                    })
+                }}
+
+                // Back to the newest candle once panned away
+                {move || {
+                    let viewport = viewport?;
+                    (!viewport.with(ChartViewport::is_following)).then(|| view! {
+                        <g
+                            class="go-latest"
+                            role="button"
+                            transform=format!(
+                                "translate({}, {})",
+                                dims.inner_width() - 100.0,
+                                price_height - 26.0,
+                            )
+                            on:pointerdown=|ev| ev.stop_propagation()
+                            on:click=move |ev| {
+                                ev.stop_propagation();
+                                viewport.update(ChartViewport::go_to_latest);
+                            }
+                        >
+                            <rect
+                                width="94" height="20"
+                                fill=colors::BG_ELEVATED
+                                stroke=colors::BORDER
+                                rx="3"
+                            />
+                            <text
+                                x="47" y="13.5"
+                                text-anchor="middle"
+                                fill=colors::TEXT_PRIMARY
+                                font-size="10"
+                                font-family="JetBrains Mono, monospace"
+                            >
+                                "Go to latest →"
+                            </text>
+                        </g>
+                    })
                 }}
 
                 // Y-Axis (right side)
@@ -242,6 +1122,20 @@ pub fn CandlestickChart(
                         stroke=colors::BORDER
                         stroke-width="1"
                     />
+                    {move || {
+                        countdown().map(|(y, remaining)| view! {
+                            <text
+                                class="candle-countdown"
+                                x="8"
+                                y=y + 18.0
+                                fill=colors::TEXT_MUTED
+                                font-size="9"
+                                font-family="JetBrains Mono, monospace"
+                            >
+                                {remaining}
+                            </text>
+                        })
+                    }}
                     {move || {
                         chart_state().map(|state| {
                             let ticks = state.y_scale.nice_ticks(5);
@@ -266,12 +1160,174 @@ pub fn CandlestickChart(
                             }).collect_view()
                         })
                     }}
+                    {move || {
+                        last_price_mark().map(|(y, price, color)| view! {
+                            <g class="last-price-tag" transform=format!("translate(0, {})", y)>
+                                <rect
+                                    x="2" y="-7"
+                                    width=dims.margin.right - 4.0 height="14"
+                                    fill=color
+                                    rx="2"
+                                />
+                                <text
+                                    x="8"
+                                    dy="0.32em"
+                                    fill=colors::BG_VOID
+                                    font-size="10"
+                                    font-weight="600"
+                                    font-family="JetBrains Mono, monospace"
+                                >
+                                    {format_price(price, 2)}
+                                </text>
+                            </g>
+                        })
+                    }}
                 </g>
+
+                // Axis drag handles, only there to show the resize cursors
+                {viewport.map(|_| view! {
+                    <rect
+                        class="price-axis-handle"
+                        x=dims.inner_width() y="0"
+                        width=dims.margin.right height=price_height
+                        fill="transparent"
+                    />
+                    {show_time_axis.then(|| view! {
+                        <rect
+                            class="time-axis-handle"
+                            x="0" y=time_axis_y
+                            width=dims.inner_width() height=dims.margin.bottom
+                            fill="transparent"
+                        />
+                    })}
+                })}
             </g>
         </svg>
     }
 }
 
+/// Volume bar segments for one candle as (base, top, fill) in volume units.
+/// Split stacks sell on top of buy; Delta draws |buy − sell| colored by
+/// sign. Candles without a buy/sell split fall back to the total bar in
+/// Split mode and draw nothing in Delta mode.
+fn volume_bars(candle: &Candle, mode: VolumeMode) -> Vec<(f64, f64, String)> {
+    let total = || {
+        let fill = if candle.is_bullish() {
+            colors::bull_alpha(0.5)
+        } else {
+            colors::bear_alpha(0.5)
+        };
+        vec![(0.0, candle.volume.as_f64(), fill)]
+    };
+    match (mode, candle.volume_split()) {
+        (VolumeMode::Split, Some((buy, sell))) => vec![
+            (0.0, buy, colors::bull_alpha(0.5)),
+            (buy, buy + sell, colors::bear_alpha(0.5)),
+        ],
+        (VolumeMode::Delta, Some(_)) => {
+            let delta = candle.volume_delta().unwrap_or(0.0);
+            let fill = if delta >= 0.0 {
+                colors::bull_alpha(0.6)
+            } else {
+                colors::bear_alpha(0.6)
+            };
+            vec![(0.0, delta.abs(), fill)]
+        }
+        (VolumeMode::Delta, None) => Vec::new(),
+        _ => total(),
+    }
+}
+
+/// Highest high and lowest low among real (non-synthetic) candles
+fn extremes(candles: &[Candle]) -> Option<((usize, f64), (usize, f64))> {
+    let real = || candles.iter().enumerate().filter(|(_, c)| !c.synthetic);
+    let high = real()
+        .map(|(i, c)| (i, c.high.as_f64()))
+        .max_by(|a, b| a.1.total_cmp(&b.1))?;
+    let low = real()
+        .map(|(i, c)| (i, c.low.as_f64()))
+        .min_by(|a, b| a.1.total_cmp(&b.1))?;
+    Some((high, low))
+}
+
+/// X position of time `ts` inside the drawn candles; `None` outside them or
+/// in a gap between candles
+fn time_x(state: &ChartState, ts: i64) -> Option<f64> {
+    let step = state.interval.as_millis();
+    let i = state.candles.partition_point(|c| c.timestamp <= ts).checked_sub(1)?;
+    let offset = ts - state.candles[i].timestamp;
+    (offset < step).then(|| state.x_scale.scale(i) + state.bandwidth * offset as f64 / step as f64)
+}
+
+/// SVG path through the points of an indicator falling on drawn candles,
+/// lifting the pen where a point is missing
+fn indicator_path(state: &ChartState, points: &[(i64, f64)]) -> String {
+    let mut path = String::new();
+    let mut pen_down = false;
+    let mut points = points.iter().peekable();
+    for (i, candle) in state.candles.iter().enumerate() {
+        while points.next_if(|(ts, _)| *ts < candle.timestamp).is_some() {}
+        match points.next_if(|(ts, _)| *ts == candle.timestamp) {
+            Some((_, value)) => {
+                let (x, y) = (state.x_scale.scale_center(i), state.y_scale.scale(*value));
+                let cmd = if pen_down { 'L' } else { 'M' };
+                path.push_str(&format!("{}{:.1},{:.1}", cmd, x, y));
+                pen_down = true;
+            }
+            None => pen_down = false,
+        }
+    }
+    path
+}
+
+/// X position of each session open inside the drawn candles; an open
+/// falling in a gap between candles is skipped
+fn session_marks(state: &ChartState, sessions: &[MarketSession]) -> Vec<(f64, MarketSession)> {
+    let (Some(first), Some(last)) = (state.candles.first(), state.candles.last()) else {
+        return Vec::new();
+    };
+    let end = last.timestamp + state.interval.as_millis();
+    sessions
+        .iter()
+        .flat_map(|session| {
+            session.opens_between(first.timestamp, end).into_iter().map(move |ts| (ts, *session))
+        })
+        .filter_map(|(ts, session)| Some((time_x(state, ts)?, session)))
+        .collect()
+}
+
+/// X extent of each shaded range over the drawn candles; a range starting
+/// or ending in a gap between candles snaps to the candle beside it
+fn shade_spans(state: &ChartState, shading: &[TradingHours]) -> Vec<(f64, f64)> {
+    let (Some(first), Some(last)) = (state.candles.first(), state.candles.last()) else {
+        return Vec::new();
+    };
+    let step = state.interval.as_millis();
+    let end = last.timestamp + step;
+    let x = |i: usize, ts: i64| {
+        let offset = (ts - state.candles[i].timestamp).clamp(0, step);
+        state.x_scale.scale(i) + state.bandwidth * offset as f64 / step as f64
+    };
+    shading
+        .iter()
+        .flat_map(|hours| hours.spans_between(first.timestamp, end))
+        .filter_map(|(from, to)| {
+            // First candle ending after `from`, last starting before `to`
+            let i = state.candles.partition_point(|c| c.timestamp + step <= from);
+            let j = state.candles.partition_point(|c| c.timestamp < to).checked_sub(1)?;
+            (i <= j).then(|| (x(i, from), x(j, to)))
+        })
+        .collect()
+}
+
+/// X position of each scheduled event inside the drawn candles
+fn event_marks<'a>(
+    state: &ChartState,
+    events: &'a [ScheduledEvent],
+) -> Vec<(f64, &'a ScheduledEvent)> {
+    events.iter().filter_map(|event| Some((time_x(state, event.timestamp)?, event))).collect()
+}
+
 /// Grid lines component
 #[component]
 fn ChartGrid(
@@ -329,7 +1385,11 @@ pub fn CandlestickSparkline(
     #[prop(into)] candles: Signal<Vec<Candle>>,
     #[prop(default = 120.0)] width: f64,
     #[prop(default = 40.0)] height: f64,
+    /// Price axis auto-scaling (10% padding by default)
+    #[prop(optional)] domain: Option<DomainStrategy>,
 ) -> impl IntoView {
+    let domain = domain.unwrap_or(DomainStrategy::Padded { ratio: 0.1 });
+
     let chart_data = move || {
         let candle_list = candles.get();
         if candle_list.is_empty() {
@@ -344,9 +1404,10 @@ pub fn CandlestickSparkline(
             max = max.max(c.high.as_f64());
         }
 
-        let padding = (max - min) * 0.1;
+        let last_close = candle_list.last().map(|c| c.close.as_f64());
+        let (y_min, y_max) = domain.domain(min, max, last_close);
         let y_scale = LinearScale::new()
-            .domain(min - padding, max + padding)
+            .domain(y_min, y_max)
             .range(height - 2.0, 2.0);
 
         let x_scale = BandScale::new(candle_list.len())
@@ -4,12 +4,19 @@
 //! Implements Strategy pattern for flexible scale and rendering behaviors.
 
 use std::fmt::Write;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use chrono::{DateTime, Datelike, LocalResult, NaiveDateTime, TimeDelta, TimeZone, Timelike, Utc};
+use dash_core::CandleInterval;
 
 // ============================================================================
 // STRATEGY PATTERN: Scale Trait
 // ============================================================================
 
 /// Strategy trait for scales (maps domain values to range values)
+///
+/// Object-safe, so axes and path generation take `&dyn Scale` and work with
+/// linear, log, time (Unix millis) and band (index) scales alike.
 pub trait Scale: Send + Sync {
     /// Scale a value from domain to range
     fn scale(&self, value: f64) -> f64;
@@ -19,6 +26,17 @@ pub trait Scale: Send + Sync {
     
     /// Generate tick values
     fn ticks(&self, count: usize) -> Vec<f64>;
+
+    /// Domain as (start, end) in value units
+    fn domain_bounds(&self) -> (f64, f64);
+
+    /// Range as (start, end) in pixels
+    fn range_bounds(&self) -> (f64, f64);
+}
+
+/// Map data-space points to pixels through an x and a y scale
+pub fn project(data: &[(f64, f64)], x: &dyn Scale, y: &dyn Scale) -> Vec<(f64, f64)> {
+    data.iter().map(|&(vx, vy)| (x.scale(vx), y.scale(vy))).collect()
 }
 
 // ============================================================================
@@ -152,12 +170,136 @@ impl Scale for LinearScale {
         let step = (max - min) / (count - 1) as f64;
         (0..count).map(|i| min + step * i as f64).collect()
     }
+
+    fn domain_bounds(&self) -> (f64, f64) {
+        self.domain
+    }
+
+    fn range_bounds(&self) -> (f64, f64) {
+        self.range
+    }
+}
+
+// ============================================================================
+// LOG SCALE
+// ============================================================================
+
+/// Base-10 logarithmic scale; the domain must be strictly positive
+#[derive(Debug, Clone)]
+pub struct LogScale {
+    domain: (f64, f64),
+    range: (f64, f64),
+}
+
+impl LogScale {
+    pub fn new() -> Self {
+        Self {
+            domain: (1.0, 10.0),
+            range: (0.0, 1.0),
+        }
+    }
+
+    /// Non-positive bounds are raised to the smallest positive value
+    pub fn domain(mut self, min: f64, max: f64) -> Self {
+        self.domain = (min.max(f64::MIN_POSITIVE), max.max(f64::MIN_POSITIVE));
+        self
+    }
+
+    pub fn range(mut self, min: f64, max: f64) -> Self {
+        self.range = (min, max);
+        self
+    }
+
+    fn log_domain(&self) -> (f64, f64) {
+        (self.domain.0.log10(), self.domain.1.log10())
+    }
+}
+
+impl Default for LogScale {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Scale for LogScale {
+    fn scale(&self, value: f64) -> f64 {
+        let (d_min, d_max) = self.log_domain();
+        let (r_min, r_max) = self.range;
+        if d_max == d_min || value <= 0.0 {
+            return (r_min + r_max) / 2.0;
+        }
+        r_min + (value.log10() - d_min) / (d_max - d_min) * (r_max - r_min)
+    }
+
+    fn invert(&self, value: f64) -> f64 {
+        let (d_min, d_max) = self.log_domain();
+        let (r_min, r_max) = self.range;
+        if (r_max - r_min).abs() < f64::EPSILON {
+            return 10f64.powf((d_min + d_max) / 2.0);
+        }
+        10f64.powf(d_min + (value - r_min) / (r_max - r_min) * (d_max - d_min))
+    }
+
+    /// 1-2-5 multiples of each decade, thinned to whole decades when too many
+    fn ticks(&self, count: usize) -> Vec<f64> {
+        let (min, max) = (self.domain.0.min(self.domain.1), self.domain.0.max(self.domain.1));
+        let (first, last) = (min.log10().floor() as i32, max.log10().ceil() as i32);
+        let in_domain = |v: &f64| (min..=max).contains(v);
+        let decades: Vec<f64> = (first..=last).map(|e| 10f64.powi(e)).filter(in_domain).collect();
+        let fine: Vec<f64> = (first..=last)
+            .flat_map(|e| [1.0, 2.0, 5.0].map(|m| m * 10f64.powi(e)))
+            .filter(in_domain)
+            .collect();
+        if fine.len() <= count.max(1) { fine } else { decades }
+    }
+
+    fn domain_bounds(&self) -> (f64, f64) {
+        self.domain
+    }
+
+    fn range_bounds(&self) -> (f64, f64) {
+        self.range
+    }
 }
 
 // ============================================================================
 // TIME SCALE
 // ============================================================================
 
+/// Calendar steps a time axis ticks at, finest first
+pub const TIME_TICK_STEPS: [CandleInterval; 7] = [
+    CandleInterval::M1,
+    CandleInterval::M5,
+    CandleInterval::M15,
+    CandleInterval::H1,
+    CandleInterval::H4,
+    CandleInterval::D1,
+    CandleInterval::W1,
+];
+
+/// Latest wall-clock time at or before `time` that lies on a `step` boundary
+/// (weeks start on Monday)
+fn floor_wall_clock(time: NaiveDateTime, step: CandleInterval) -> NaiveDateTime {
+    let date = time.date();
+    let floor = |value: u32, multiple: u32| value - value % multiple;
+    let (date, hour, minute) = match step {
+        CandleInterval::M1 | CandleInterval::M5 | CandleInterval::M15 | CandleInterval::M30 => {
+            let minutes = (step.as_secs() / 60) as u32;
+            (date, time.hour(), floor(time.minute(), minutes))
+        }
+        CandleInterval::H1 | CandleInterval::H4 => {
+            let hours = (step.as_secs() / 3600) as u32;
+            (date, floor(time.hour(), hours), 0)
+        }
+        CandleInterval::D1 => (date, 0, 0),
+        CandleInterval::W1 => {
+            let monday = date - TimeDelta::days(date.weekday().num_days_from_monday() as i64);
+            (monday, 0, 0)
+        }
+    };
+    date.and_hms_opt(hour, minute, 0).unwrap_or(time)
+}
+
 /// Time scale (maps timestamps to pixel positions)
 #[derive(Debug, Clone)]
 pub struct TimeScale {
@@ -208,6 +350,74 @@ impl TimeScale {
         let normalized = (value - r_min) / (r_max - r_min);
         (d_min as f64 + normalized * (d_max - d_min) as f64) as i64
     }
+
+    pub fn domain_bounds(&self) -> (i64, i64) {
+        self.domain
+    }
+
+    pub fn range_bounds(&self) -> (f64, f64) {
+        self.range
+    }
+
+    /// Finest calendar step giving at most `count` ticks over the domain
+    pub fn tick_step(&self, count: usize) -> CandleInterval {
+        let span = (self.domain.1 - self.domain.0).abs();
+        let count = count.max(1) as i64;
+        TIME_TICK_STEPS
+            .iter()
+            .copied()
+            .find(|step| span / step.as_millis() < count)
+            .unwrap_or(CandleInterval::W1)
+    }
+
+    /// Roughly `count` ticks on UTC calendar boundaries
+    pub fn ticks(&self, count: usize) -> Vec<i64> {
+        self.ticks_in(&Utc, count)
+    }
+
+    /// Roughly `count` ticks on whole minutes/hours, midnights or Mondays of
+    /// `tz`'s wall clock.
+    ///
+    /// Steps are taken on the wall clock and mapped back to instants, so daily
+    /// ticks stay at midnight across DST changes. Wall times skipped by a
+    /// spring-forward gap are dropped (a day tick moves to the end of the gap);
+    /// sub-daily ticks in a repeated fall-back hour appear for both offsets.
+    pub fn ticks_in<Tz: TimeZone>(&self, tz: &Tz, count: usize) -> Vec<i64> {
+        let (start, end) = (self.domain.0.min(self.domain.1), self.domain.0.max(self.domain.1));
+        let Some(first) = DateTime::from_timestamp_millis(start) else {
+            return Vec::new();
+        };
+        let step = self.tick_step(count);
+        let delta = TimeDelta::milliseconds(step.as_millis());
+        let daily = step.as_millis() >= CandleInterval::D1.as_millis();
+
+        let mut ticks = Vec::new();
+        let mut wall = floor_wall_clock(first.with_timezone(tz).naive_local(), step);
+        // Two extra steps cover a first tick floored before `start` and DST slack
+        let max_steps = ((end - start) / step.as_millis()).max(0) as usize + 2;
+        for _ in 0..=max_steps {
+            let instants = match tz.from_local_datetime(&wall) {
+                LocalResult::Single(t) => vec![t],
+                LocalResult::Ambiguous(early, _) if daily => vec![early],
+                LocalResult::Ambiguous(early, late) => vec![early, late],
+                LocalResult::None if daily => {
+                    let after_gap = tz.from_local_datetime(&(wall + TimeDelta::hours(1)));
+                    after_gap.earliest().into_iter().collect()
+                }
+                LocalResult::None => Vec::new(),
+            };
+            ticks.extend(
+                instants
+                    .into_iter()
+                    .map(|t| t.timestamp_millis())
+                    .filter(|ts| (start..=end).contains(ts)),
+            );
+            wall += delta;
+        }
+        ticks.sort_unstable();
+        ticks.dedup();
+        ticks
+    }
 }
 
 impl Default for TimeScale {
@@ -216,6 +426,34 @@ impl Default for TimeScale {
     }
 }
 
+/// Values are Unix millis; ticks are the UTC calendar ticks
+impl Scale for TimeScale {
+    fn scale(&self, value: f64) -> f64 {
+        let (d_min, d_max) = (self.domain.0 as f64, self.domain.1 as f64);
+        let (r_min, r_max) = self.range;
+        if d_max == d_min {
+            return (r_min + r_max) / 2.0;
+        }
+        r_min + (value - d_min) / (d_max - d_min) * (r_max - r_min)
+    }
+
+    fn invert(&self, value: f64) -> f64 {
+        TimeScale::invert(self, value) as f64
+    }
+
+    fn ticks(&self, count: usize) -> Vec<f64> {
+        TimeScale::ticks(self, count).into_iter().map(|ts| ts as f64).collect()
+    }
+
+    fn domain_bounds(&self) -> (f64, f64) {
+        (self.domain.0 as f64, self.domain.1 as f64)
+    }
+
+    fn range_bounds(&self) -> (f64, f64) {
+        self.range
+    }
+}
+
 // ============================================================================
 // BAND SCALE (for categorical/ordinal data like candlesticks)
 // ============================================================================
@@ -307,6 +545,104 @@ impl Default for BandScale {
     }
 }
 
+/// Values are (possibly fractional) band indices mapped to band centers
+impl Scale for BandScale {
+    fn scale(&self, value: f64) -> f64 {
+        let offset = self.padding_outer * self.step();
+        self.range.0 + offset + value * self.step() + self.bandwidth() / 2.0
+    }
+
+    /// Nearest band index
+    fn invert(&self, value: f64) -> f64 {
+        let step = self.step();
+        if step == 0.0 {
+            return 0.0;
+        }
+        let offset = self.padding_outer * step + self.bandwidth() / 2.0;
+        let last = self.domain_count.saturating_sub(1) as f64;
+        ((value - self.range.0 - offset) / step).round().clamp(0.0, last)
+    }
+
+    /// Every k-th index so that at most `count` bands are labelled
+    fn ticks(&self, count: usize) -> Vec<f64> {
+        let every = self.domain_count.div_ceil(count.max(1)).max(1);
+        (0..self.domain_count).step_by(every).map(|i| i as f64).collect()
+    }
+
+    fn domain_bounds(&self) -> (f64, f64) {
+        (0.0, self.domain_count.saturating_sub(1) as f64)
+    }
+
+    fn range_bounds(&self) -> (f64, f64) {
+        self.range
+    }
+}
+
+// ============================================================================
+// DOMAIN STRATEGIES (value axis auto-scaling)
+// ============================================================================
+
+/// Padding used on either side of a flat series (zero data span)
+const FLAT_DOMAIN_PADDING: f64 = 1.0;
+
+/// How a value axis domain is derived from the data extent
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DomainStrategy {
+    /// Data min/max padded by `ratio` of the span on each side
+    Padded { ratio: f64 },
+    /// Fixed bounds regardless of the data
+    Fixed { min: f64, max: f64 },
+    /// Data extent stretched to include zero, padded away from it (volumes, depth)
+    IncludeZero { ratio: f64 },
+    /// Centered on the last value, wide enough for the whole extent plus `ratio`
+    SymmetricAroundLast { ratio: f64 },
+}
+
+impl Default for DomainStrategy {
+    fn default() -> Self {
+        Self::Padded { ratio: 0.05 }
+    }
+}
+
+impl DomainStrategy {
+    /// Domain for data spanning `min..=max` whose latest value is `last`
+    pub fn domain(&self, min: f64, max: f64, last: Option<f64>) -> (f64, f64) {
+        let pad = |span: f64, ratio: f64| {
+            if span > 0.0 { span * ratio } else { FLAT_DOMAIN_PADDING }
+        };
+        match *self {
+            Self::Padded { ratio } => {
+                let padding = pad(max - min, ratio);
+                (min - padding, max + padding)
+            }
+            Self::Fixed { min, max } => (min, max),
+            Self::IncludeZero { ratio } => {
+                let (lo, hi) = (min.min(0.0), max.max(0.0));
+                let padding = pad(hi - lo, ratio);
+                (
+                    if lo < 0.0 { lo - padding } else { lo },
+                    if hi > 0.0 || lo == 0.0 { hi + padding } else { hi },
+                )
+            }
+            Self::SymmetricAroundLast { ratio } => match last {
+                Some(last) => {
+                    let half = (last - min).max(max - last);
+                    let half = half + pad(half, ratio);
+                    (last - half, last + half)
+                }
+                None => Self::Padded { ratio }.domain(min, max, None),
+            },
+        }
+    }
+
+    /// Domain over `values` (last item taken as the latest); `None` if empty
+    pub fn domain_of(&self, values: &[f64]) -> Option<(f64, f64)> {
+        let min = values.iter().copied().reduce(f64::min)?;
+        let max = values.iter().copied().reduce(f64::max)?;
+        Some(self.domain(min, max, values.last().copied()))
+    }
+}
+
 // ============================================================================
 // STRATEGY PATTERN: Path Generator Trait
 // ============================================================================
@@ -314,6 +650,11 @@ impl Default for BandScale {
 /// Strategy trait for path generation
 pub trait PathGenerator: Send + Sync {
     fn generate(&self, points: &[(f64, f64)]) -> String;
+
+    /// Path through data-space points placed by any pair of scales
+    fn generate_scaled(&self, data: &[(f64, f64)], x: &dyn Scale, y: &dyn Scale) -> String {
+        self.generate(&project(data, x, y))
+    }
 }
 
 /// Line path generator
@@ -344,7 +685,7 @@ pub struct StepPath {
     pub step_position: StepPosition,
 }
 
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub enum StepPosition {
     #[default]
     Before,
@@ -371,7 +712,7 @@ impl PathGenerator for StepPath {
         write!(path, "M{:.2},{:.2}", x, y).unwrap();
 
         for i in 1..points.len() {
-            let (x0, y0) = points[i - 1];
+            let (x0, _) = points[i - 1];
             let (x1, y1) = points[i];
 
             match self.step_position {
@@ -392,6 +733,183 @@ impl PathGenerator for StepPath {
     }
 }
 
+// ============================================================================
+// CURVE INTERPOLATION (d3.curve equivalents)
+// ============================================================================
+
+/// Monotone cubic in x (d3.curveMonotoneX): smooth, but never overshoots
+/// the data, so a price line can't dip below a real low
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MonotonePath;
+
+impl MonotonePath {
+    /// Fritsch-Carlson tangent at each point
+    fn tangents(points: &[(f64, f64)]) -> Vec<f64> {
+        let slope = |a: (f64, f64), b: (f64, f64)| {
+            let h = b.0 - a.0;
+            if h == 0.0 { 0.0 } else { (b.1 - a.1) / h }
+        };
+        let n = points.len();
+        let mut tangents = vec![0.0; n];
+        for i in 1..n.saturating_sub(1) {
+            let (h0, h1) = (points[i].0 - points[i - 1].0, points[i + 1].0 - points[i].0);
+            let (s0, s1) = (slope(points[i - 1], points[i]), slope(points[i], points[i + 1]));
+            let p = if h0 + h1 == 0.0 { 0.0 } else { (s0 * h1 + s1 * h0) / (h0 + h1) };
+            // Zero at local extrema; capped so the curve stays within the data
+            tangents[i] = (s0.signum() + s1.signum()) * s0.abs().min(s1.abs()).min(0.5 * p.abs());
+        }
+        if n >= 2 {
+            // One-sided three-point estimate at the ends
+            let first = slope(points[0], points[1]);
+            let last = slope(points[n - 2], points[n - 1]);
+            tangents[0] = if n > 2 { (3.0 * first - tangents[1]) / 2.0 } else { first };
+            tangents[n - 1] = if n > 2 { (3.0 * last - tangents[n - 2]) / 2.0 } else { last };
+        }
+        tangents
+    }
+}
+
+impl PathGenerator for MonotonePath {
+    fn generate(&self, points: &[(f64, f64)]) -> String {
+        let Some(&(x, y)) = points.first() else {
+            return String::new();
+        };
+        let tangents = Self::tangents(points);
+        let mut builder = PathBuilder::new().move_to(x, y);
+        for i in 1..points.len() {
+            let ((x0, y0), (x1, y1)) = (points[i - 1], points[i]);
+            let dx = (x1 - x0) / 3.0;
+            builder = builder.cubic_to(
+                x0 + dx,
+                y0 + tangents[i - 1] * dx,
+                x1 - dx,
+                y1 - tangents[i] * dx,
+                x1,
+                y1,
+            );
+        }
+        builder.build()
+    }
+}
+
+/// Catmull-Rom spline through every point (d3.curveCatmullRom); `alpha`
+/// 0.5 is centripetal (no cusps or self-loops), 0 uniform, 1 chordal
+#[derive(Debug, Clone, Copy)]
+pub struct CatmullRomPath {
+    pub alpha: f64,
+}
+
+impl Default for CatmullRomPath {
+    fn default() -> Self {
+        Self { alpha: 0.5 }
+    }
+}
+
+impl PathGenerator for CatmullRomPath {
+    fn generate(&self, points: &[(f64, f64)]) -> String {
+        let Some(&(x, y)) = points.first() else {
+            return String::new();
+        };
+        let n = points.len();
+        let mut builder = PathBuilder::new().move_to(x, y);
+        for i in 1..n {
+            // Ends are clamped by repeating the first/last point
+            let p0 = points[i.saturating_sub(2)];
+            let (p1, p2) = (points[i - 1], points[i]);
+            let p3 = points[(i + 1).min(n - 1)];
+
+            let dist = |a: (f64, f64), b: (f64, f64)| (b.0 - a.0).hypot(b.1 - a.1);
+            let (l01, l12, l23) = (
+                dist(p0, p1).powf(self.alpha),
+                dist(p1, p2).powf(self.alpha),
+                dist(p2, p3).powf(self.alpha),
+            );
+
+            let control = |near: (f64, f64), far: (f64, f64), back: (f64, f64), la: f64| {
+                if la < 1e-12 || l12 < 1e-12 {
+                    return near;
+                }
+                let (a2, b2) = (la * la, l12 * l12);
+                let scale = 2.0 * a2 + 3.0 * la * l12 + b2;
+                let denom = 3.0 * la * (la + l12);
+                (
+                    (far.0 * a2 - back.0 * b2 + near.0 * scale) / denom,
+                    (far.1 * a2 - back.1 * b2 + near.1 * scale) / denom,
+                )
+            };
+            let c1 = control(p1, p2, p0, l01);
+            let c2 = control(p2, p1, p3, l23);
+            builder = builder.cubic_to(c1.0, c1.1, c2.0, c2.1, p2.0, p2.1);
+        }
+        builder.build()
+    }
+}
+
+/// Uniform cubic B-spline (d3.curveBasis): the smoothest option; pinned at
+/// the ends but only approximates the points in between
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BasisPath;
+
+impl PathGenerator for BasisPath {
+    fn generate(&self, points: &[(f64, f64)]) -> String {
+        let n = points.len();
+        let Some(&(x, y)) = points.first() else {
+            return String::new();
+        };
+        let mut builder = PathBuilder::new().move_to(x, y);
+        if n == 1 {
+            return builder.build();
+        }
+        if n == 2 {
+            return builder.line_to(points[1].0, points[1].1).build();
+        }
+
+        let segment = |builder: PathBuilder, p0: (f64, f64), p1: (f64, f64), p: (f64, f64)| {
+            builder.cubic_to(
+                (2.0 * p0.0 + p1.0) / 3.0,
+                (2.0 * p0.1 + p1.1) / 3.0,
+                (p0.0 + 2.0 * p1.0) / 3.0,
+                (p0.1 + 2.0 * p1.1) / 3.0,
+                (p0.0 + 4.0 * p1.0 + p.0) / 6.0,
+                (p0.1 + 4.0 * p1.1 + p.1) / 6.0,
+            )
+        };
+        let (p0, p1) = (points[0], points[1]);
+        builder = builder.line_to((5.0 * p0.0 + p1.0) / 6.0, (5.0 * p0.1 + p1.1) / 6.0);
+        for i in 2..n {
+            builder = segment(builder, points[i - 2], points[i - 1], points[i]);
+        }
+        // Close out on the last point
+        let last = points[n - 1];
+        builder = segment(builder, points[n - 2], last, last);
+        builder.line_to(last.0, last.1).build()
+    }
+}
+
+/// Interpolation between points, selectable per chart
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Curve {
+    #[default]
+    Linear,
+    Step(StepPosition),
+    Monotone,
+    /// Catmull-Rom with the given alpha
+    CatmullRom(f64),
+    Basis,
+}
+
+impl PathGenerator for Curve {
+    fn generate(&self, points: &[(f64, f64)]) -> String {
+        match *self {
+            Self::Linear => LinePath.generate(points),
+            Self::Step(step_position) => StepPath { step_position }.generate(points),
+            Self::Monotone => MonotonePath.generate(points),
+            Self::CatmullRom(alpha) => CatmullRomPath { alpha }.generate(points),
+            Self::Basis => BasisPath.generate(points),
+        }
+    }
+}
+
 // ============================================================================
 // PATH BUILDER (fluent API)
 // ============================================================================
@@ -444,6 +962,7 @@ impl PathBuilder {
         self
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn arc_to(
         mut self,
         rx: f64,
@@ -504,11 +1023,146 @@ pub fn area_path(points: &[(f64, f64)], baseline_y: f64) -> String {
     builder.close().build()
 }
 
+/// Generate closed area path using a custom path generator for the top edge
+pub fn area_path_with<G: PathGenerator>(
+    points: &[(f64, f64)],
+    baseline_y: f64,
+    generator: &G,
+) -> String {
+    let (Some(&(first_x, _)), Some(&(last_x, _))) = (points.first(), points.last()) else {
+        return String::new();
+    };
+
+    // Generator output starts with a move; continue it from the baseline instead
+    let edge = generator.generate(points);
+    let edge = edge.strip_prefix('M').unwrap_or(&edge);
+
+    let mut path = String::with_capacity(edge.len() + 48);
+    write!(path, "M{:.2},{:.2}L{}", first_x, baseline_y, edge).unwrap();
+    write!(path, "L{:.2},{:.2}Z", last_x, baseline_y).unwrap();
+    path
+}
+
 /// Generate line path (non-closed)
 pub fn line_path(points: &[(f64, f64)]) -> String {
     LinePath.generate(points)
 }
 
+// ============================================================================
+// SVG DEFS (gradients, filters, patterns)
+// ============================================================================
+
+/// Source of unique id prefixes for SVG defs across chart instances
+static NEXT_DEFS_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// Fresh id prefix for one chart's `<defs>` entries; take it once per chart
+/// instance so ids stay stable across redraws
+pub fn next_defs_id() -> String {
+    format!("chart-{}", NEXT_DEFS_ID.fetch_add(1, Ordering::Relaxed))
+}
+
+/// Builder for a chart's `<defs>` markup. Entries get `{prefix}-{name}` ids;
+/// reference them with `url`, render with `build` into `<defs inner_html=..>`
+#[derive(Debug, Clone)]
+pub struct Defs {
+    prefix: String,
+    markup: String,
+}
+
+impl Defs {
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            markup: String::with_capacity(512),
+        }
+    }
+
+    pub fn id(&self, name: &str) -> String {
+        format!("{}-{}", self.prefix, name)
+    }
+
+    /// `url(#id)` for use as a fill, stroke or filter
+    pub fn url(&self, name: &str) -> String {
+        format!("url(#{})", self.id(name))
+    }
+
+    /// Gradient through `(offset 0..=1, color, opacity)` stops, top to
+    /// bottom when `vertical`, else left to right
+    pub fn linear_gradient(
+        mut self,
+        name: &str,
+        stops: &[(f64, &str, f64)],
+        vertical: bool,
+    ) -> Self {
+        let (x2, y2) = if vertical { (0, 1) } else { (1, 0) };
+        write!(
+            self.markup,
+            r#"<linearGradient id="{}" x1="0" y1="0" x2="{}" y2="{}">"#,
+            self.id(name),
+            x2,
+            y2
+        )
+        .unwrap();
+        for (offset, color, opacity) in stops {
+            write!(
+                self.markup,
+                r#"<stop offset="{:.1}%" stop-color="{}" stop-opacity="{}"/>"#,
+                offset.clamp(0.0, 1.0) * 100.0,
+                color,
+                opacity
+            )
+            .unwrap();
+        }
+        self.markup.push_str("</linearGradient>");
+        self
+    }
+
+    /// Vertical fade from `color` at `opacity` to transparent, for area fills
+    pub fn fade(self, name: &str, color: &str, opacity: f64) -> Self {
+        self.linear_gradient(name, &[(0.0, color, opacity), (1.0, color, 0.0)], true)
+    }
+
+    /// Soft glow around the filtered element's own colors
+    pub fn glow(mut self, name: &str, blur: f64) -> Self {
+        write!(
+            self.markup,
+            concat!(
+                r#"<filter id="{}" x="-50%" y="-50%" width="200%" height="200%">"#,
+                r#"<feGaussianBlur in="SourceGraphic" stdDeviation="{}" result="blur"/>"#,
+                r#"<feMerge><feMergeNode in="blur"/><feMergeNode in="SourceGraphic"/></feMerge>"#,
+                "</filter>"
+            ),
+            self.id(name),
+            blur
+        )
+        .unwrap();
+        self
+    }
+
+    /// Diagonal hatching: `color` lines `spacing` px apart, rotated `angle` degrees
+    pub fn hatch(mut self, name: &str, color: &str, spacing: f64, angle: f64) -> Self {
+        write!(
+            self.markup,
+            concat!(
+                r#"<pattern id="{id}" patternUnits="userSpaceOnUse" width="{s}" height="{s}" "#,
+                r#"patternTransform="rotate({angle})">"#,
+                r#"<line x1="0" y1="0" x2="0" y2="{s}" stroke="{color}" stroke-width="1"/>"#,
+                "</pattern>"
+            ),
+            id = self.id(name),
+            s = spacing,
+            angle = angle,
+            color = color
+        )
+        .unwrap();
+        self
+    }
+
+    pub fn build(self) -> String {
+        self.markup
+    }
+}
+
 // ============================================================================
 // FORMATTERS
 // ============================================================================
@@ -563,8 +1217,6 @@ pub fn format_time(timestamp_ms: i64, interval_secs: i64) -> String {
 
     if interval_secs >= 86400 {
         dt.format("%b %d").to_string()
-    } else if interval_secs >= 3600 {
-        dt.format("%H:%M").to_string()
     } else {
         dt.format("%H:%M").to_string()
     }
@@ -606,6 +1258,134 @@ mod tests {
         assert!(bw < 20.0); // Should be less than 100/5
     }
 
+    #[test]
+    fn test_domain_strategies() {
+        let padded = DomainStrategy::Padded { ratio: 0.1 };
+        assert_eq!(padded.domain(100.0, 200.0, None), (90.0, 210.0));
+        assert_eq!(padded.domain(50.0, 50.0, None), (49.0, 51.0));
+
+        let fixed = DomainStrategy::Fixed { min: -1.0, max: 1.0 };
+        assert_eq!(fixed.domain(100.0, 200.0, None), (-1.0, 1.0));
+
+        let zero = DomainStrategy::IncludeZero { ratio: 0.1 };
+        assert_eq!(zero.domain(20.0, 100.0, None), (0.0, 110.0));
+        assert_eq!(zero.domain(-100.0, -20.0, None), (-110.0, 0.0));
+        assert_eq!(zero.domain(0.0, 0.0, None), (0.0, 1.0));
+
+        let symmetric = DomainStrategy::SymmetricAroundLast { ratio: 0.5 };
+        assert_eq!(symmetric.domain(90.0, 130.0, Some(100.0)), (55.0, 145.0));
+        assert_eq!(symmetric.domain_of(&[1.0, 3.0, 2.0]), Some((0.5, 3.5)));
+        assert_eq!(symmetric.domain_of(&[]), None);
+    }
+
+    /// UTC+1, springing forward to UTC+2 at 2025-03-30 01:00 UTC (02:00 local)
+    #[derive(Debug, Clone, Copy)]
+    struct SpringForward;
+
+    const SWITCH_MS: i64 = 1_743_296_400_000;
+
+    impl TimeZone for SpringForward {
+        type Offset = chrono::FixedOffset;
+
+        fn from_offset(_: &Self::Offset) -> Self {
+            SpringForward
+        }
+
+        fn offset_from_local_date(&self, local: &chrono::NaiveDate) -> LocalResult<Self::Offset> {
+            self.offset_from_local_datetime(&local.and_hms_opt(0, 0, 0).unwrap())
+        }
+
+        fn offset_from_local_datetime(&self, local: &NaiveDateTime) -> LocalResult<Self::Offset> {
+            let utc_ms = |hours: i64| local.and_utc().timestamp_millis() - hours * 3_600_000;
+            match (utc_ms(1) < SWITCH_MS, utc_ms(2) >= SWITCH_MS) {
+                (true, _) => LocalResult::Single(chrono::FixedOffset::east_opt(3600).unwrap()),
+                (false, true) => LocalResult::Single(chrono::FixedOffset::east_opt(7200).unwrap()),
+                (false, false) => LocalResult::None,
+            }
+        }
+
+        fn offset_from_utc_date(&self, utc: &chrono::NaiveDate) -> Self::Offset {
+            self.offset_from_utc_datetime(&utc.and_hms_opt(0, 0, 0).unwrap())
+        }
+
+        fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> Self::Offset {
+            let hours = if utc.and_utc().timestamp_millis() < SWITCH_MS { 1 } else { 2 };
+            chrono::FixedOffset::east_opt(hours * 3600).unwrap()
+        }
+    }
+
+    #[test]
+    fn test_time_tick_step() {
+        let hour = 3_600_000;
+        let scale = TimeScale::new().domain(0, 10 * hour).range(0.0, 800.0);
+        assert_eq!(scale.tick_step(100), CandleInterval::M15);
+        assert_eq!(scale.tick_step(10), CandleInterval::H4);
+        assert_eq!(scale.tick_step(0), CandleInterval::D1);
+    }
+
+    #[test]
+    fn test_time_ticks_utc() {
+        let minute = 60_000;
+        // 2025-03-07 14:03 to 14:29 UTC
+        let start = 1_741_356_180_000;
+        let scale = TimeScale::new().domain(start, start + 26 * minute);
+        let ticks = scale.ticks(8);
+        assert_eq!(scale.tick_step(8), CandleInterval::M5);
+        assert_eq!(ticks.len(), 5);
+        assert_eq!(ticks[0], start + 2 * minute);
+        assert!(ticks.windows(2).all(|w| w[1] - w[0] == 5 * minute));
+    }
+
+    #[test]
+    fn test_time_ticks_across_dst() {
+        let hour = 3_600_000;
+        // Daily ticks stay on local midnight: the switch day is 23h long
+        let days = TimeScale::new().domain(SWITCH_MS - 3 * 24 * hour, SWITCH_MS + 3 * 24 * hour);
+        let ticks = days.ticks_in(&SpringForward, 8);
+        let gaps: Vec<i64> = ticks.windows(2).map(|w| (w[1] - w[0]) / hour).collect();
+        assert_eq!(gaps, vec![24, 24, 23, 24, 24]);
+        for ts in &ticks {
+            let local = DateTime::from_timestamp_millis(*ts).unwrap().with_timezone(&SpringForward);
+            assert_eq!((local.hour(), local.minute()), (0, 0));
+        }
+
+        // Hourly ticks skip the missing 02:00 and stay one real hour apart
+        let hours = TimeScale::new().domain(SWITCH_MS - 3 * hour, SWITCH_MS + 3 * hour);
+        let ticks = hours.ticks_in(&SpringForward, 8);
+        assert_eq!(ticks.len(), 7);
+        assert!(ticks.windows(2).all(|w| w[1] - w[0] == hour));
+    }
+
+    #[test]
+    fn test_scales_as_trait_objects() {
+        let hour = 3_600_000.0;
+        let scales: Vec<Box<dyn Scale>> = vec![
+            Box::new(LinearScale::new().domain(0.0, 10.0).range(0.0, 100.0)),
+            Box::new(LogScale::new().domain(1.0, 100.0).range(0.0, 100.0)),
+            Box::new(TimeScale::new().domain(0, 10 * hour as i64).range(0.0, 100.0)),
+            Box::new(BandScale::new(10).range(-5.0, 95.0).padding(0.0, 0.0)),
+        ];
+        let mids = [5.0, 10.0, 5.0 * hour, 5.0];
+        for (scale, mid) in scales.iter().zip(mids) {
+            assert!((scale.scale(mid) - 50.0).abs() < 1e-9);
+            assert!((scale.invert(scale.scale(mid)) - mid).abs() < 1e-6);
+            assert!(!scale.ticks(5).is_empty());
+        }
+
+        let path = LinePath.generate_scaled(&[(0.0, 1.0), (10.0, 100.0)], &*scales[0], &*scales[1]);
+        assert_eq!(path, "M0.00,0.00L100.00,100.00");
+    }
+
+    #[test]
+    fn test_log_scale_ticks() {
+        let scale = LogScale::new().domain(1.0, 1000.0);
+        assert_eq!(
+            scale.ticks(20),
+            vec![1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0, 1000.0]
+        );
+        assert_eq!(scale.ticks(5), vec![1.0, 10.0, 100.0, 1000.0]);
+    }
+
     #[test]
     fn test_path_builder() {
         let path = PathBuilder::new()
@@ -628,6 +1408,58 @@ mod tests {
         assert!(path.contains("L50.00,50.00"));
     }
 
+    #[test]
+    fn test_area_path_with_step() {
+        let step = StepPath { step_position: StepPosition::After };
+        let path = area_path_with(&[(0.0, 50.0), (10.0, 40.0), (20.0, 30.0)], 100.0, &step);
+
+        assert_eq!(
+            path,
+            "M0.00,100.00L0.00,50.00H10.00V40.00H20.00V30.00L20.00,100.00Z"
+        );
+        assert!(area_path_with(&[], 100.0, &step).is_empty());
+    }
+
+    #[test]
+    fn test_monotone_path_no_overshoot() {
+        // A plateau next to a jump must stay flat: control points keep y
+        let points = [(0.0, 0.0), (10.0, 0.0), (20.0, 100.0), (30.0, 100.0)];
+        let tangents = MonotonePath::tangents(&points);
+        assert_eq!(tangents[1], 0.0);
+        assert_eq!(tangents[2], 0.0);
+        let path = MonotonePath.generate(&points);
+        assert!(path.starts_with("M0.00,0.00C3.33,0.00,6.67,0.00,10.00,0.00"));
+        assert!(path.ends_with("C23.33,100.00,26.67,100.00,30.00,100.00"));
+    }
+
+    #[test]
+    fn test_catmull_rom_and_basis_endpoints() {
+        let points = [(0.0, 0.0), (10.0, 20.0), (20.0, 0.0), (30.0, 20.0)];
+        for curve in [Curve::CatmullRom(0.5), Curve::CatmullRom(0.0), Curve::Basis] {
+            let path = curve.generate(&points);
+            assert!(path.starts_with("M0.00,0.00"), "{:?}", curve);
+            assert!(path.ends_with("30.00,20.00"), "{:?}", curve);
+        }
+        // Catmull-Rom passes through interior points
+        assert!(Curve::CatmullRom(0.5).generate(&points).contains(",10.00,20.00C"));
+        assert_eq!(Curve::Basis.generate(&points[..2]), "M0.00,0.00L10.00,20.00");
+        assert!(Curve::Monotone.generate(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_defs_builder() {
+        let defs = Defs::new("chart-7").fade("bid", "#22c55e", 0.4).glow("mid", 2.0);
+        assert_eq!(defs.url("bid"), "url(#chart-7-bid)");
+
+        let markup = defs.hatch("spread", "#888888", 4.0, 45.0).build();
+        let gradient = r#"<linearGradient id="chart-7-bid" x1="0" y1="0" x2="0" y2="1">"#;
+        assert!(markup.starts_with(gradient));
+        assert!(markup.contains(r##"stop-color="#22c55e" stop-opacity="0"/>"##));
+        assert!(markup.contains(r#"<filter id="chart-7-mid""#));
+        assert!(markup.contains(r#"<pattern id="chart-7-spread" patternUnits="userSpaceOnUse""#));
+        assert!(markup.ends_with("</pattern>"));
+    }
+
     #[test]
     fn test_format_large_number() {
         assert_eq!(format_large_number(1_500_000.0), "1.50M");
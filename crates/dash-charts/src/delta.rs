@@ -0,0 +1,251 @@
+//! Volume delta histogram
+//!
+//! Plots buy − sell volume per candle as bars around a zero line, with an
+//! optional cumulative delta line. Candles without a buy/sell split (loaded
+//! from a source that only reports totals) leave a blank slot and add
+//! nothing to the running total.
+
+use crate::{
+    chartkit::{format_large_number, line_path, BandScale, DomainStrategy, LinearScale, Scale},
+    colors, ChartDimensions, ChartMargin, ChartViewport, RenderScheduler,
+};
+use dash_core::{Candle, CandleHistory};
+use leptos::prelude::*;
+
+/// Delta pane configuration
+#[derive(Debug, Clone)]
+pub struct DeltaChartConfig {
+    pub width: f64,
+    pub height: f64,
+    /// Delta axis auto-scaling; the zero line is always in view
+    pub y_domain: DomainStrategy,
+}
+
+impl Default for DeltaChartConfig {
+    fn default() -> Self {
+        Self {
+            width: 800.0,
+            height: 120.0,
+            y_domain: DomainStrategy::IncludeZero { ratio: 0.1 },
+        }
+    }
+}
+
+/// Buy − sell volume of each candle, `None` where the split is unknown
+pub fn candle_deltas(candles: &[Candle]) -> Vec<Option<f64>> {
+    candles.iter().map(Candle::volume_delta).collect()
+}
+
+/// Running total of `deltas`; unknown candles carry the previous total
+pub fn cumulative_deltas(deltas: &[Option<f64>]) -> Vec<f64> {
+    deltas
+        .iter()
+        .scan(0.0, |total, delta| {
+            *total += delta.unwrap_or(0.0);
+            Some(*total)
+        })
+        .collect()
+}
+
+/// One histogram bar
+#[derive(Clone)]
+struct DeltaBar {
+    x: f64,
+    y: f64,
+    height: f64,
+    delta: f64,
+    trades: u32,
+}
+
+#[derive(Clone)]
+struct DeltaState {
+    bars: Vec<DeltaBar>,
+    bandwidth: f64,
+    zero_y: f64,
+    /// Cumulative line path and its latest value
+    cumulative: Option<(String, f64)>,
+}
+
+/// Delta histogram component; x positions line up with a `CandlestickChart`
+/// of the same width
+#[component]
+pub fn DeltaHistogram(
+    candles: RwSignal<CandleHistory>,
+    #[prop(optional)] config: Option<DeltaChartConfig>,
+    /// Draw the cumulative delta line over the bars
+    #[prop(optional, into)] cumulative: Option<Signal<bool>>,
+    /// Coalesce redraws to animation frames, capped at this rate (`None` = display rate)
+    #[prop(optional, into)] max_fps: Option<Signal<Option<u32>>>,
+    /// Window shared with the candlestick chart above
+    #[prop(optional, into)] viewport: Option<Signal<ChartViewport>>,
+) -> impl IntoView {
+    let config = config.unwrap_or_default();
+    let dims = ChartDimensions::new(config.width, config.height)
+        .with_margin(ChartMargin::right_axis());
+    let y_domain = config.y_domain;
+    let height = dims.inner_height();
+
+    let compute = move |history: &CandleHistory, window: ChartViewport, cumulative: bool| {
+        let all = history.candles.as_slice();
+        let candle_list = &all[window.window(all)];
+        if candle_list.is_empty() {
+            return None;
+        }
+
+        let deltas = candle_deltas(candle_list);
+        let known: Vec<f64> = deltas.iter().flatten().copied().collect();
+        let min = known.iter().copied().fold(0.0, f64::min);
+        let max = known.iter().copied().fold(0.0, f64::max);
+        let (y_min, y_max) = y_domain.domain(min, max, None);
+        let y_scale = LinearScale::new().domain(y_min, y_max).range(height, 0.0);
+        let zero_y = y_scale.scale(0.0);
+
+        // Same band layout as the candlestick chart
+        let x_scale = BandScale::new(candle_list.len())
+            .range(0.0, dims.inner_width())
+            .padding(0.2, 0.1);
+
+        let bars = deltas
+            .iter()
+            .zip(candle_list)
+            .enumerate()
+            .filter_map(|(i, (delta, candle))| {
+                let delta = (*delta)?;
+                let y = y_scale.scale(delta);
+                Some(DeltaBar {
+                    x: x_scale.scale(i),
+                    y: y.min(zero_y),
+                    height: (y - zero_y).abs(),
+                    delta,
+                    trades: candle.trade_count,
+                })
+            })
+            .collect();
+
+        // The running total gets its own scale so small bars stay readable
+        let cumulative = (cumulative && !known.is_empty()).then(|| {
+            let totals = cumulative_deltas(&deltas);
+            let lo = totals.iter().copied().fold(0.0, f64::min);
+            let hi = totals.iter().copied().fold(0.0, f64::max);
+            let (lo, hi) = y_domain.domain(lo, hi, None);
+            let total_scale = LinearScale::new().domain(lo, hi).range(height, 0.0);
+            let points: Vec<(f64, f64)> = totals
+                .iter()
+                .enumerate()
+                .map(|(i, total)| (x_scale.scale_center(i), total_scale.scale(*total)))
+                .collect();
+            (line_path(&points), totals.last().copied().unwrap_or(0.0))
+        });
+
+        Some(DeltaState {
+            bars,
+            bandwidth: x_scale.bandwidth(),
+            zero_y,
+            cumulative,
+        })
+    };
+
+    let cumulative = cumulative.unwrap_or_else(|| Signal::stored(false));
+    let scheduler = max_fps.map(RenderScheduler::new);
+    if let Some(scheduler) = scheduler {
+        scheduler.watch(candles);
+    }
+    let delta_state = Memo::new_with_compare(
+        move |_| {
+            let cumulative = cumulative.get();
+            let window = viewport.map(|v| v.get()).unwrap_or_default();
+            match scheduler {
+                Some(scheduler) => {
+                    scheduler.track();
+                    candles.with_untracked(|h| compute(h, window, cumulative))
+                }
+                None => candles.with(|h| compute(h, window, cumulative)),
+            }
+        },
+        |_, _| true,
+    );
+    let delta_state = move || delta_state.get();
+
+    view! {
+        <svg
+            class="delta-chart"
+            role="img"
+            aria-label="Volume delta histogram"
+            viewBox=dims.viewbox()
+            preserveAspectRatio="xMidYMid meet"
+            style="width: 100%; height: 100%;"
+        >
+            <rect width=dims.width height=dims.height fill=colors::BG_PANEL rx="4" />
+            <g transform=dims.inner_transform()>
+                {move || delta_state().map(|state| {
+                    let width = dims.inner_width();
+                    let bandwidth = state.bandwidth;
+                    view! {
+                        <line
+                            x1="0"
+                            x2=width
+                            y1=state.zero_y
+                            y2=state.zero_y
+                            stroke=colors::BORDER
+                            stroke-width="1"
+                        />
+                        {state.bars.into_iter().map(|bar| {
+                            let fill = if bar.delta >= 0.0 {
+                                colors::bull_alpha(0.6)
+                            } else {
+                                colors::bear_alpha(0.6)
+                            };
+                            view! {
+                                <rect x=bar.x y=bar.y width=bandwidth height=bar.height fill=fill>
+                                    <title>
+                                        {format!(
+                                            "Δ {} · {} trades",
+                                            format_large_number(bar.delta),
+                                            bar.trades,
+                                        )}
+                                    </title>
+                                </rect>
+                            }
+                        }).collect_view()}
+                        {state.cumulative.map(|(path, last)| view! {
+                            <path
+                                d=path
+                                fill="none"
+                                stroke=colors::TEXT_PRIMARY
+                                stroke-width="1.5"
+                            />
+                            <text
+                                x=width + 6.0
+                                y="10"
+                                fill=colors::TEXT_MUTED
+                                font-size="10"
+                                font-family="monospace"
+                            >
+                                {format!("Σ {}", format_large_number(last))}
+                            </text>
+                        })}
+                    }
+                })}
+            </g>
+        </svg>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dash_core::{CandleInterval, Symbol, Trade, TradeSide};
+
+    #[test]
+    fn test_cumulative_deltas() {
+        let mut split = Candle::new(Symbol::default(), CandleInterval::M1, 0, 100.0);
+        split.add_trade(&Trade::new(Symbol::default(), 100.0, 3.0, TradeSide::Buy));
+        split.add_trade(&Trade::new(Symbol::default(), 100.0, 1.0, TradeSide::Sell));
+        let plain = Candle::new(Symbol::default(), CandleInterval::M1, 60_000, 100.0);
+
+        let deltas = candle_deltas(&[split.clone(), plain, split]);
+        assert_eq!(deltas, vec![Some(2.0), None, Some(2.0)]);
+        assert_eq!(cumulative_deltas(&deltas), vec![2.0, 2.0, 4.0]);
+        assert!(cumulative_deltas(&[]).is_empty());
+    }
+}
@@ -3,11 +3,15 @@
 //! Renders cumulative order book depth as filled area chart.
 
 use crate::{
-    chartkit::{area_path, format_large_number, format_price, LinearScale, Scale},
-    colors, ChartDimensions, ChartMargin,
+    chartkit::{
+        area_path, area_path_with, format_large_number, format_price, next_defs_id, Defs,
+        DomainStrategy, LinearScale, Scale, StepPath, StepPosition,
+    },
+    colors, ChartDimensions, ChartMargin, RenderScheduler,
 };
-use dash_core::MarketDepth;
+use dash_core::{DepthAxis, DepthScale, MarketDepth, TrackedWall};
 use leptos::prelude::*;
+use std::sync::Arc;
 
 /// Depth chart configuration
 #[derive(Debug, Clone)]
@@ -17,6 +21,16 @@ pub struct DepthChartConfig {
     pub spread_multiplier: f64, // How much of the spread to show (e.g., 20x)
     pub show_mid_line: bool,
     pub show_legend: bool,
+    /// Render cumulative depth as a staircase (exchange style) instead of slopes
+    pub stepped: bool,
+    /// Draw vertical markers at tracked liquidity walls
+    pub show_walls: bool,
+    /// Cumulative size axis auto-scaling
+    pub y_domain: DomainStrategy,
+    /// Plot cumulative quantity or cumulative notional value
+    pub axis: DepthAxis,
+    /// Both sides on one scale, or each as a percentage of its own total
+    pub scale: DepthScale,
 }
 
 impl Default for DepthChartConfig {
@@ -27,6 +41,11 @@ impl Default for DepthChartConfig {
             spread_multiplier: 20.0,
             show_mid_line: true,
             show_legend: true,
+            stepped: true,
+            show_walls: false,
+            y_domain: DomainStrategy::IncludeZero { ratio: 0.1 },
+            axis: DepthAxis::default(),
+            scale: DepthScale::default(),
         }
     }
 }
@@ -38,15 +57,22 @@ struct DepthState {
     ask_path: String,
     mid_x: Option<f64>,
     mid_price: Option<f64>,
+    /// Best bid and best ask x, bounding the spread
+    spread_x: Option<(f64, f64)>,
     x_scale: LinearScale,
     y_scale: LinearScale,
+    /// Bid and ask totals, the 100% marks when each side has its own scale
+    side_totals: (f64, f64),
 }
 
 /// Depth chart component
 #[component]
 pub fn DepthChart(
-    depth: RwSignal<Option<MarketDepth>>,
+    depth: RwSignal<Option<Arc<MarketDepth>>>,
     #[prop(optional)] config: Option<DepthChartConfig>,
+    #[prop(optional, into)] walls: Option<Signal<Vec<TrackedWall>>>,
+    /// Coalesce redraws to animation frames, capped at this rate (`None` = display rate)
+    #[prop(optional, into)] max_fps: Option<Signal<Option<u32>>>,
 ) -> impl IntoView {
     let config = config.unwrap_or_default();
     
@@ -56,10 +82,15 @@ pub fn DepthChart(
     let show_mid = config.show_mid_line;
     let show_legend = config.show_legend;
     let spread_mult = config.spread_multiplier;
+    let stepped = config.stepped;
+    let y_domain = config.y_domain;
+    let axis = config.axis;
+    let scale = config.scale;
+    let walls = walls.filter(|_| config.show_walls);
 
     // Compute chart state
-    let chart_state = move || {
-        depth.get().map(|d| {
+    let compute = move |d: &Option<Arc<MarketDepth>>| {
+        d.as_ref().map(|d| {
             // Get best bid/ask for centering
             let bid_first = d.bid_depth.first().map(|p| p.price);
             let ask_first = d.ask_depth.first().map(|p| p.price);
@@ -79,48 +110,108 @@ pub fn DepthChart(
                 .domain(min_price, max_price)
                 .range(0.0, dims.inner_width());
 
-            let max_depth = d.max_depth();
+            // Per side, each total maps to 100% and the axis is fixed
+            let side_totals = d.side_totals(axis);
+            let percent_of = |total: f64| if total > 0.0 { 100.0 / total } else { 0.0 };
+            let ((y_min, y_max), bid_unit, ask_unit) = match scale {
+                DepthScale::Shared => (y_domain.domain(0.0, d.max_on(axis), None), 1.0, 1.0),
+                DepthScale::PerSide => {
+                    let (bid_total, ask_total) = side_totals;
+                    ((0.0, 100.0), percent_of(bid_total), percent_of(ask_total))
+                }
+            };
             let y_scale = LinearScale::new()
-                .domain(0.0, max_depth * 1.1)
+                .domain(y_min, y_max)
                 .range(dims.inner_height(), 0.0);
 
             // Build bid area points
             let bid_points: Vec<(f64, f64)> = d.bid_depth
                 .iter()
                 .filter(|p| p.price >= min_price && p.price <= max_price)
-                .map(|p| (x_scale.scale(p.price), y_scale.scale(p.cumulative_quantity)))
+                .map(|p| (x_scale.scale(p.price), y_scale.scale(axis.value(p) * bid_unit)))
                 .collect();
 
             // Build ask area points
             let ask_points: Vec<(f64, f64)> = d.ask_depth
                 .iter()
                 .filter(|p| p.price >= min_price && p.price <= max_price)
-                .map(|p| (x_scale.scale(p.price), y_scale.scale(p.cumulative_quantity)))
+                .map(|p| (x_scale.scale(p.price), y_scale.scale(axis.value(p) * ask_unit)))
                 .collect();
 
-            // Generate area paths
+            // Generate area paths. Depth at a price holds until the next level,
+            // so each step runs horizontally first, then jumps.
             let baseline = dims.inner_height();
-            let bid_path = area_path(&bid_points, baseline);
-            let ask_path = area_path(&ask_points, baseline);
+            let (bid_path, ask_path) = if stepped {
+                let step = StepPath { step_position: StepPosition::After };
+                (
+                    area_path_with(&bid_points, baseline, &step),
+                    area_path_with(&ask_points, baseline, &step),
+                )
+            } else {
+                (area_path(&bid_points, baseline), area_path(&ask_points, baseline))
+            };
 
             // Mid price
             let mid_price = bid_first.zip(ask_first).map(|(b, a)| (b + a) / 2.0);
             let mid_x = mid_price.map(|p| x_scale.scale(p));
+            let spread_x = bid_first
+                .zip(ask_first)
+                .map(|(bid, ask)| (x_scale.scale(bid), x_scale.scale(ask)));
 
             DepthState {
                 bid_path,
                 ask_path,
                 mid_x,
                 mid_price,
+                spread_x,
                 x_scale,
                 y_scale,
+                side_totals,
             }
         })
     };
 
+    // Recompute once per change, or once per scheduled frame when throttled
+    let scheduler = max_fps.map(RenderScheduler::new);
+    if let Some(scheduler) = scheduler {
+        scheduler.watch(depth);
+    }
+    let chart_state = Memo::new_with_compare(
+        move |_| match scheduler {
+            Some(scheduler) => {
+                scheduler.track();
+                depth.with_untracked(compute)
+            }
+            None => depth.with(compute),
+        },
+        |_, _| true,
+    );
+    let chart_state = move || chart_state.get();
+
+    let legend_label = move |name: &'static str, bid: bool| {
+        move || match (scale, chart_state()) {
+            (DepthScale::PerSide, Some(state)) => {
+                let (bid_total, ask_total) = state.side_totals;
+                let total = if bid { bid_total } else { ask_total };
+                format!("{} · 100% = {}", name, format_axis_value(axis, total))
+            }
+            _ => name.to_string(),
+        }
+    };
+
+    let defs = Defs::new(next_defs_id())
+        .fade("bid", colors::BULL, 0.45)
+        .fade("ask", colors::BEAR, 0.45)
+        .glow("glow", 2.0)
+        .hatch("spread", colors::TEXT_MUTED, 4.0, 45.0);
+    let (bid_fill, ask_fill) = (defs.url("bid"), defs.url("ask"));
+    let (glow, spread_fill) = (defs.url("glow"), defs.url("spread"));
+
     view! {
         <svg
             class="depth-chart"
+            role="img"
+            aria-label="Cumulative market depth chart"
             viewBox=dims.viewbox()
             preserveAspectRatio="xMidYMid meet"
             style="width: 100%; height: 100%;"
@@ -133,6 +224,8 @@ pub fn DepthChart(
                 rx="4"
             />
 
+            <defs inner_html=defs.build() />
+
             <g transform=dims.inner_transform()>
                 // Grid
                 <DepthGrid
@@ -145,10 +238,23 @@ pub fn DepthChart(
                     chart_state().map(|state| {
                         view! {
                             <>
+                                // Spread between best bid and ask
+                                {state.spread_x.map(|(bid_x, ask_x)| view! {
+                                    <rect
+                                        class="depth-spread"
+                                        x=bid_x
+                                        y="0"
+                                        width=(ask_x - bid_x).max(0.0)
+                                        height=dims.inner_height()
+                                        fill=spread_fill.clone()
+                                        opacity="0.4"
+                                    />
+                                })}
+
                                 // Bid area (green)
                                 <path
                                     d=state.bid_path.clone()
-                                    fill=colors::bull_alpha(0.3)
+                                    fill=bid_fill.clone()
                                     stroke=colors::BULL
                                     stroke-width="2"
                                 />
@@ -156,7 +262,7 @@ pub fn DepthChart(
                                 // Ask area (red)
                                 <path
                                     d=state.ask_path.clone()
-                                    fill=colors::bear_alpha(0.3)
+                                    fill=ask_fill.clone()
                                     stroke=colors::BEAR
                                     stroke-width="2"
                                 />
@@ -171,6 +277,7 @@ pub fn DepthChart(
                                                 stroke=colors::WARN
                                                 stroke-width="1"
                                                 stroke-dasharray="4,4"
+                                                filter=glow.clone()
                                             />
                                         }
                                     })
@@ -178,6 +285,38 @@ pub fn DepthChart(
                                     None
                                 }}
 
+                                // Wall markers
+                                {walls.map(|walls| {
+                                    let (lo, hi) = state.x_scale.domain_bounds();
+                                    walls.get()
+                                        .into_iter()
+                                        .filter(|w| w.price >= lo && w.price <= hi)
+                                        .map(|w| {
+                                            let x = state.x_scale.scale(w.price);
+                                            view! {
+                                                <line
+                                                    class="depth-wall"
+                                                    x1=x y1="0"
+                                                    x2=x y2=dims.inner_height()
+                                                    stroke=w.side.color()
+                                                    stroke-width="1"
+                                                    stroke-opacity="0.7"
+                                                    stroke-dasharray="2,3"
+                                                >
+                                                    <title>
+                                                        {format!(
+                                                            "{} wall {} ({})",
+                                                            w.side.label(),
+                                                            format_price(w.price, 2),
+                                                            w.age_label(),
+                                                        )}
+                                                    </title>
+                                                </line>
+                                            }
+                                        })
+                                        .collect_view()
+                                })}
+
                                 // Mid price label
                                 {state.mid_price.zip(state.mid_x).map(|(price, x)| {
                                     view! {
@@ -232,8 +371,21 @@ pub fn DepthChart(
                     }}
                 </g>
 
-                // Y-Axis (quantity)
+                // Y-Axis (quantity or notional)
                 <g>
+                    <text
+                        class="depth-axis-label"
+                        transform=format!(
+                            "translate({}, {}) rotate(-90)",
+                            -dims.margin.left + 12.0,
+                            dims.inner_height() / 2.0,
+                        )
+                        text-anchor="middle"
+                        fill=colors::TEXT_MUTED
+                        font-size="10"
+                    >
+                        {axis_label(axis, scale)}
+                    </text>
                     <line
                         x1="0" y1="0"
                         x2="0" y2=dims.inner_height()
@@ -245,7 +397,10 @@ pub fn DepthChart(
                             let ticks = state.y_scale.nice_ticks(5);
                             ticks.into_iter().map(|tick| {
                                 let y = state.y_scale.scale(tick);
-                                let label = format_large_number(tick);
+                                let label = match scale {
+                                    DepthScale::Shared => format_axis_value(axis, tick),
+                                    DepthScale::PerSide => format!("{:.0}%", tick),
+                                };
 
                                 view! {
                                     <g transform=format!("translate(0, {})", y)>
@@ -268,15 +423,29 @@ pub fn DepthChart(
                 </g>
             </g>
 
-            // Legend
+            // Legend; per side it also says what 100% stands for
             {if show_legend {
+                let (legend_width, entry_width) = match scale {
+                    DepthScale::Shared => (100.0, 50.0),
+                    DepthScale::PerSide => (300.0, 150.0),
+                };
                 Some(view! {
-                    <g transform=format!("translate({}, 15)", dims.width - 100.0)>
+                    <g transform=format!("translate({}, 15)", dims.width - legend_width)>
                         <rect x="0" y="-4" width="12" height="12" fill=colors::bull_alpha(0.5) />
-                        <text x="16" y="5" fill=colors::TEXT_MUTED font-size="10">"Bids"</text>
-
-                        <rect x="50" y="-4" width="12" height="12" fill=colors::bear_alpha(0.5) />
-                        <text x="66" y="5" fill=colors::TEXT_MUTED font-size="10">"Asks"</text>
+                        <text x="16" y="5" fill=colors::TEXT_MUTED font-size="10">
+                            {legend_label("Bids", true)}
+                        </text>
+
+                        <rect
+                            x=entry_width
+                            y="-4"
+                            width="12"
+                            height="12"
+                            fill=colors::bear_alpha(0.5)
+                        />
+                        <text x=entry_width + 16.0 y="5" fill=colors::TEXT_MUTED font-size="10">
+                            {legend_label("Asks", false)}
+                        </text>
                     </g>
                 })
             } else {
@@ -286,6 +455,24 @@ pub fn DepthChart(
     }
 }
 
+/// Y-axis title
+fn axis_label(axis: DepthAxis, scale: DepthScale) -> &'static str {
+    match (scale, axis) {
+        (DepthScale::Shared, DepthAxis::Quantity) => "Cumulative size",
+        (DepthScale::Shared, DepthAxis::Notional) => "Cumulative value",
+        (DepthScale::PerSide, DepthAxis::Quantity) => "% of side size",
+        (DepthScale::PerSide, DepthAxis::Notional) => "% of side value",
+    }
+}
+
+/// Y-axis tick label; notional values read as quote currency
+fn format_axis_value(axis: DepthAxis, value: f64) -> String {
+    match axis {
+        DepthAxis::Quantity => format_large_number(value),
+        DepthAxis::Notional => format!("${}", format_large_number(value)),
+    }
+}
+
 /// Grid lines for depth chart
 #[component]
 fn DepthGrid(width: f64, height: f64) -> impl IntoView {
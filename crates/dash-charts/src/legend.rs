@@ -0,0 +1,75 @@
+//! Chart legend
+//!
+//! Lists the series drawn on a chart with their swatch colors. Items are
+//! plain data, so any chart can feed it; clicks report the item id and the
+//! owner decides what toggling means.
+
+use leptos::prelude::*;
+
+/// One legend row
+#[derive(Debug, Clone, PartialEq)]
+pub struct LegendItem {
+    /// Stable key passed to `on_toggle`
+    pub id: &'static str,
+    pub label: String,
+    pub color: &'static str,
+    pub visible: bool,
+}
+
+impl LegendItem {
+    pub fn new(id: &'static str, label: impl Into<String>, color: &'static str) -> Self {
+        Self {
+            id,
+            label: label.into(),
+            color,
+            visible: true,
+        }
+    }
+
+    pub fn visible(mut self, visible: bool) -> Self {
+        self.visible = visible;
+        self
+    }
+}
+
+/// Row of swatches; without `on_toggle` the items are not clickable
+#[component]
+pub fn Legend(
+    #[prop(into)] items: Signal<Vec<LegendItem>>,
+    /// Receives the id of the clicked item
+    #[prop(optional, into)] on_toggle: Option<Callback<&'static str>>,
+    /// Accessible name of the group
+    #[prop(optional, into)] label: Option<Signal<String>>,
+) -> impl IntoView {
+    view! {
+        <div
+            class="chart-legend"
+            role="group"
+            aria-label=move || label.map(|l| l.get()).unwrap_or_else(|| "Legend".to_string())
+        >
+            {move || items.get().into_iter().map(|item| {
+                let id = item.id;
+                let class = if item.visible { "legend-item" } else { "legend-item hidden" };
+                view! {
+                    <button
+                        class=class
+                        aria-pressed=item.visible.to_string()
+                        disabled=on_toggle.is_none()
+                        on:click=move |_| {
+                            if let Some(on_toggle) = on_toggle {
+                                on_toggle.run(id);
+                            }
+                        }
+                    >
+                        <span
+                            class="legend-swatch"
+                            style=format!("background: {}", item.color)
+                            aria-hidden="true"
+                        ></span>
+                        {item.label}
+                    </button>
+                }
+            }).collect_view()}
+        </div>
+    }
+}
@@ -7,25 +7,48 @@
 //!
 //! Uses Strategy pattern for:
 //! - Scale computation (linear, log, time, band)
-//! - Path generation (line, area, step)
+//! - Value axis domains (padded, fixed, include-zero, symmetric)
+//! - Path generation (line, area, step, monotone, Catmull-Rom, basis)
+//! - Price series rendering (candles, OHLC, line, area, baseline)
 //! - Axis rendering
 //!
 //! ## Modules
 //!
 //! - `chartkit` - Core primitives: scales, paths, axes
+//! - `axis` - Axis components (time axis)
 //! - `candlestick` - OHLCV candlestick charts
+//! - `series` - Price series renderers (candles, OHLC bars, line, area, baseline)
+//! - `delta` - Buy − sell volume histogram with cumulative line
 //! - `depth` - Market depth / order book visualization
+//! - `legend` - Series legend with click-to-toggle
+//! - `measure` - Price/time range ruler
 //! - `sparkline` - Compact inline charts
+//! - `viewport` - Zoom/pan window and price range, following the newest candle by default
+//! - `scheduler` - Frame-coalesced render scheduling
 
+pub mod axis;
 pub mod candlestick;
 pub mod chartkit;
+pub mod delta;
 pub mod depth;
+pub mod legend;
+pub mod measure;
+pub mod scheduler;
+pub mod series;
 pub mod sparkline;
+pub mod viewport;
 
+pub use axis::*;
 pub use candlestick::*;
 pub use chartkit::*;
+pub use delta::*;
 pub use depth::*;
+pub use legend::*;
+pub use measure::*;
+pub use scheduler::*;
+pub use series::*;
 pub use sparkline::*;
+pub use viewport::*;
 
 // Re-export colors from dash-core for convenience
 pub use dash_core::colors;
@@ -115,6 +138,22 @@ impl ChartDimensions {
     pub fn viewbox(&self) -> String {
         format!("0 0 {} {}", self.width, self.height)
     }
+
+    /// Map a point inside the rendered element (CSS pixels from its top-left,
+    /// element `box_width` x `box_height`) to inner chart coordinates, assuming
+    /// `preserveAspectRatio="xMidYMid meet"`
+    pub fn to_inner(&self, x: f64, y: f64, box_width: f64, box_height: f64) -> (f64, f64) {
+        let scale = (box_width / self.width).min(box_height / self.height);
+        if !scale.is_finite() || scale <= 0.0 {
+            return (f64::NAN, f64::NAN);
+        }
+        let pad_x = (box_width - self.width * scale) / 2.0;
+        let pad_y = (box_height - self.height * scale) / 2.0;
+        (
+            (x - pad_x) / scale - self.margin.left,
+            (y - pad_y) / scale - self.margin.top,
+        )
+    }
 }
 
 impl Default for ChartDimensions {
@@ -122,3 +161,17 @@ impl Default for ChartDimensions {
         Self::new(800.0, 400.0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_inner_letterboxed() {
+        let dims = ChartDimensions::new(800.0, 400.0).with_margin(ChartMargin::right_axis());
+        // Rendered at half size inside a taller box: 100px bars above and below
+        let (x, y) = dims.to_inner(200.0, 100.0 + 105.0, 400.0, 400.0);
+        assert_eq!((x, y), (390.0, 200.0));
+        assert!(dims.to_inner(0.0, 0.0, 0.0, 0.0).0.is_nan());
+    }
+}
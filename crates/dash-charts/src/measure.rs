@@ -0,0 +1,111 @@
+//! Price/time range ruler
+//!
+//! Measures between two points on the candle chart: the price move, the
+//! candles and wall-clock time spanned, and the volume traded in the
+//! candles covered (both ends included).
+
+use dash_core::Candle;
+
+/// A measured range; `from` is where the drag started
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Measurement {
+    pub from_price: f64,
+    pub to_price: f64,
+    /// Candles from start to end, negative when dragged back in time
+    pub bars: i64,
+    /// Open time of the end candle minus that of the start candle
+    pub duration_ms: i64,
+    pub volume: f64,
+}
+
+impl Measurement {
+    /// Measure from `from` to `to`, each a (candle index, price) in `candles`
+    pub fn between(candles: &[Candle], from: (usize, f64), to: (usize, f64)) -> Option<Self> {
+        let (first, last) = (from.0.min(to.0), from.0.max(to.0));
+        let span = candles.get(first..=last)?;
+        Some(Self {
+            from_price: from.1,
+            to_price: to.1,
+            bars: to.0 as i64 - from.0 as i64,
+            duration_ms: candles[to.0].timestamp - candles[from.0].timestamp,
+            volume: span.iter().map(|c| c.volume.as_f64()).sum(),
+        })
+    }
+
+    pub fn price_change(&self) -> f64 {
+        self.to_price - self.from_price
+    }
+
+    /// Price change relative to the start price; zero from a zero price
+    pub fn percent_change(&self) -> f64 {
+        if self.from_price == 0.0 {
+            0.0
+        } else {
+            self.price_change() / self.from_price * 100.0
+        }
+    }
+
+    pub fn is_up(&self) -> bool {
+        self.to_price >= self.from_price
+    }
+}
+
+/// Compact duration in its two largest units: "45s", "12m", "3h 20m", "2d 4h"
+pub fn format_span(millis: i64) -> String {
+    let secs = millis.unsigned_abs() / 1000;
+    let (days, hours, minutes) = (secs / 86_400, secs / 3600 % 24, secs / 60 % 60);
+    let span = match (days, hours, minutes) {
+        (0, 0, 0) => format!("{}s", secs),
+        (0, 0, m) => format!("{}m", m),
+        (0, h, 0) => format!("{}h", h),
+        (0, h, m) => format!("{}h {}m", h, m),
+        (d, 0, _) => format!("{}d", d),
+        (d, h, _) => format!("{}d {}h", d, h),
+    };
+    if millis < 0 { format!("-{}", span) } else { span }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dash_core::{CandleInterval, Quantity, Symbol};
+
+    fn candles() -> Vec<Candle> {
+        (0..10)
+            .map(|i| {
+                let mut candle =
+                    Candle::new(Symbol::default(), CandleInterval::M5, i * 300_000, 100.0);
+                candle.volume = Quantity(i as f64);
+                candle
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_measure_range() {
+        let history = candles();
+        let m = Measurement::between(&history, (2, 100.0), (6, 105.0)).unwrap();
+        assert_eq!((m.bars, m.duration_ms, m.volume), (4, 1_200_000, 20.0));
+        assert_eq!((m.price_change(), m.percent_change()), (5.0, 5.0));
+        assert!(m.is_up());
+
+        // Dragged backwards: same candles, negative span
+        let back = Measurement::between(&history, (6, 105.0), (2, 100.0)).unwrap();
+        assert_eq!((back.bars, back.duration_ms, back.volume), (-4, -1_200_000, 20.0));
+        assert!(!back.is_up());
+
+        assert!(Measurement::between(&history, (2, 1.0), (10, 1.0)).is_none());
+        let flat = Measurement::between(&history, (0, 0.0), (0, 5.0)).unwrap();
+        assert_eq!(flat.percent_change(), 0.0);
+    }
+
+    #[test]
+    fn test_format_span() {
+        assert_eq!(format_span(45_000), "45s");
+        assert_eq!(format_span(12 * 60_000), "12m");
+        assert_eq!(format_span(3 * 3_600_000), "3h");
+        assert_eq!(format_span(200 * 60_000), "3h 20m");
+        assert_eq!(format_span(2 * 86_400_000 + 4 * 3_600_000 + 60_000), "2d 4h");
+        assert_eq!(format_span(-300_000), "-5m");
+    }
+}
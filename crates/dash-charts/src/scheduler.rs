@@ -0,0 +1,102 @@
+//! Render scheduling for chart updates
+//!
+//! Market data can arrive many times per frame. Charts wired to a
+//! `RenderScheduler` recompute at most once per animation frame, optionally
+//! capped to a lower frame rate, independent of the data arrival rate.
+
+use leptos::prelude::*;
+
+/// Slack subtracted from the frame budget so a 60 fps cap doesn't skip
+/// frames due to requestAnimationFrame jitter
+const FRAME_JITTER_MS: f64 = 1.0;
+
+/// Minimum time between renders for a frame-rate cap (`None`/0 = uncapped)
+pub fn min_frame_interval_ms(max_fps: Option<u32>) -> f64 {
+    match max_fps {
+        Some(fps) if fps > 0 => (1000.0 / fps as f64 - FRAME_JITTER_MS).max(0.0),
+        _ => 0.0,
+    }
+}
+
+#[derive(Debug, Default)]
+struct FrameState {
+    /// A frame callback is already queued
+    pending: bool,
+    /// Timestamp of the last render (ms)
+    last_frame: f64,
+}
+
+/// Coalesces change notifications into animation-frame render ticks
+#[derive(Clone, Copy)]
+pub struct RenderScheduler {
+    tick: RwSignal<u64>,
+    frame: StoredValue<FrameState>,
+    max_fps: Signal<Option<u32>>,
+}
+
+impl RenderScheduler {
+    pub fn new(max_fps: Signal<Option<u32>>) -> Self {
+        Self {
+            tick: RwSignal::new(0),
+            frame: StoredValue::new(FrameState::default()),
+            max_fps,
+        }
+    }
+
+    /// Request a render whenever `source` changes
+    pub fn watch(&self, source: impl Track + 'static) {
+        let scheduler = *self;
+        Effect::new(move |_| {
+            source.track();
+            scheduler.request();
+        });
+    }
+
+    /// Subscribe the current reactive scope to render ticks
+    pub fn track(&self) {
+        self.tick.track();
+    }
+
+    /// Schedule a render on the next allowed frame (no-op if one is queued)
+    pub fn request(&self) {
+        let already_pending = self
+            .frame
+            .try_update_value(|f| std::mem::replace(&mut f.pending, true))
+            .unwrap_or(true);
+        if !already_pending {
+            let scheduler = *self;
+            request_animation_frame(move || scheduler.on_frame());
+        }
+    }
+
+    fn on_frame(self) {
+        let now = js_sys::Date::now();
+        let min_interval = min_frame_interval_ms(self.max_fps.get_untracked());
+        let last = self.frame.with_value(|f| f.last_frame);
+
+        if now - last < min_interval {
+            // Over the cap: wait for a later frame
+            request_animation_frame(move || self.on_frame());
+            return;
+        }
+
+        self.frame.update_value(|f| {
+            f.pending = false;
+            f.last_frame = now;
+        });
+        self.tick.update(|t| *t = t.wrapping_add(1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_min_frame_interval() {
+        assert_eq!(min_frame_interval_ms(None), 0.0);
+        assert_eq!(min_frame_interval_ms(Some(0)), 0.0);
+        assert_eq!(min_frame_interval_ms(Some(10)), 99.0);
+        assert!((min_frame_interval_ms(Some(60)) - 15.67).abs() < 0.01);
+    }
+}
@@ -0,0 +1,351 @@
+//! Price series renderers for the candle chart
+//!
+//! Every chart type draws from the same `SeriesFrame` (the candles plus the
+//! chart's scales), so axes, overlays and annotations line up whichever
+//! series is shown.
+
+use crate::{
+    chartkit::{
+        area_path_with, format_price, BandScale, Curve, Defs, LinearScale, PathGenerator, Scale,
+    },
+    colors,
+};
+use dash_core::{Candle, ChartType};
+use leptos::prelude::*;
+
+const DAY_MS: i64 = 86_400_000;
+
+// ============================================================================
+// STRATEGY PATTERN: Series Renderer
+// ============================================================================
+
+/// Candles and scales shared by every series renderer
+pub struct SeriesFrame<'a> {
+    pub candles: &'a [Candle],
+    pub x_scale: &'a BandScale,
+    pub y_scale: &'a LinearScale,
+    pub bandwidth: f64,
+    /// Price pane size
+    pub width: f64,
+    pub height: f64,
+    /// Prefix for ids of any SVG defs the renderer emits
+    pub defs_id: &'a str,
+    /// Interpolation for line-based series
+    pub curve: Curve,
+}
+
+impl SeriesFrame<'_> {
+    /// Horizontal center of candle `i`
+    pub fn center(&self, i: usize) -> f64 {
+        self.x_scale.scale_center(i)
+    }
+
+    /// One point per candle at its close
+    pub fn close_points(&self) -> Vec<(f64, f64)> {
+        self.candles
+            .iter()
+            .enumerate()
+            .map(|(i, c)| (self.center(i), self.y_scale.scale(c.close.as_f64())))
+            .collect()
+    }
+
+    /// Bull color if the series closed at or above where it opened
+    pub fn trend_color(&self) -> &'static str {
+        match (self.candles.first(), self.candles.last()) {
+            (Some(first), Some(last)) if last.close.as_f64() < first.open.as_f64() => colors::BEAR,
+            _ => colors::BULL,
+        }
+    }
+}
+
+/// Strategy trait for drawing the price series
+pub trait SeriesRenderer: Send + Sync {
+    fn render(&self, frame: &SeriesFrame<'_>) -> AnyView;
+}
+
+/// Renderer for a chart type; `baseline` only affects `ChartType::Baseline`
+pub fn series_renderer(chart_type: ChartType, baseline: BaselineLevel) -> Box<dyn SeriesRenderer> {
+    match chart_type {
+        ChartType::Candles => Box::new(CandleSeries),
+        ChartType::Ohlc => Box::new(OhlcSeries),
+        ChartType::Line => Box::new(LineSeries),
+        ChartType::Area => Box::new(AreaSeries),
+        ChartType::Baseline => Box::new(BaselineSeries { level: baseline }),
+    }
+}
+
+/// Stroke/fill and class for a candle; synthetic (gap-filled) candles are muted
+fn candle_style(candle: &Candle) -> (&'static str, String) {
+    if candle.synthetic {
+        (colors::TEXT_MUTED, "candle candle-synthetic".to_string())
+    } else {
+        (candle.fill_color(), format!("candle {}", candle.css_class()))
+    }
+}
+
+/// Body and wick candlesticks
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CandleSeries;
+
+impl SeriesRenderer for CandleSeries {
+    fn render(&self, frame: &SeriesFrame<'_>) -> AnyView {
+        frame.candles.iter().enumerate().map(|(i, candle)| {
+            let x = frame.x_scale.scale(i);
+            let x_center = frame.center(i);
+
+            // Wick coordinates
+            let wick_y1 = frame.y_scale.scale(candle.high.as_f64());
+            let wick_y2 = frame.y_scale.scale(candle.low.as_f64());
+
+            // Body coordinates
+            let body_top = candle.open.as_f64().max(candle.close.as_f64());
+            let body_bottom = candle.open.as_f64().min(candle.close.as_f64());
+            let body_y = frame.y_scale.scale(body_top);
+            let body_h = (frame.y_scale.scale(body_bottom) - body_y).max(1.0);
+
+            let (fill, class) = candle_style(candle);
+
+            view! {
+                <g class=class>
+                    // Wick
+                    <line
+                        x1=x_center
+                        y1=wick_y1
+                        x2=x_center
+                        y2=wick_y2
+                        stroke=fill
+                        stroke-width="1"
+                    />
+                    // Body
+                    <rect
+                        x=x
+                        y=body_y
+                        width=frame.bandwidth
+                        height=body_h
+                        fill=fill
+                        stroke=fill
+                        stroke-width="1"
+                        rx="1"
+                    />
+                </g>
+            }
+        }).collect_view().into_any()
+    }
+}
+
+/// OHLC bars: high-low stem, open tick left, close tick right
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OhlcSeries;
+
+impl SeriesRenderer for OhlcSeries {
+    fn render(&self, frame: &SeriesFrame<'_>) -> AnyView {
+        frame.candles.iter().enumerate().map(|(i, candle)| {
+            let x = frame.x_scale.scale(i);
+            let x_center = frame.center(i);
+            let open_y = frame.y_scale.scale(candle.open.as_f64());
+            let close_y = frame.y_scale.scale(candle.close.as_f64());
+            let (stroke, class) = candle_style(candle);
+
+            view! {
+                <g class=class stroke=stroke stroke-width="1.5">
+                    <line
+                        x1=x_center
+                        y1=frame.y_scale.scale(candle.high.as_f64())
+                        x2=x_center
+                        y2=frame.y_scale.scale(candle.low.as_f64())
+                    />
+                    <line x1=x y1=open_y x2=x_center y2=open_y />
+                    <line x1=x_center y1=close_y x2=x + frame.bandwidth y2=close_y />
+                </g>
+            }
+        }).collect_view().into_any()
+    }
+}
+
+/// Close prices joined by a line, colored by the overall trend
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LineSeries;
+
+impl SeriesRenderer for LineSeries {
+    fn render(&self, frame: &SeriesFrame<'_>) -> AnyView {
+        view! {
+            <path
+                class="series-line"
+                d=frame.curve.generate(&frame.close_points())
+                fill="none"
+                stroke=frame.trend_color()
+                stroke-width="1.5"
+                stroke-linejoin="round"
+            />
+        }
+        .into_any()
+    }
+}
+
+/// Close line over a fill fading to transparent at the pane bottom
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AreaSeries;
+
+impl SeriesRenderer for AreaSeries {
+    fn render(&self, frame: &SeriesFrame<'_>) -> AnyView {
+        let points = frame.close_points();
+        let color = frame.trend_color();
+        let defs = Defs::new(frame.defs_id).fade("area", color, 0.35);
+
+        view! {
+            <g class="series-area">
+                <defs inner_html=defs.clone().build() />
+                <path
+                    d=area_path_with(&points, frame.height, &frame.curve)
+                    fill=defs.url("area")
+                    stroke="none"
+                />
+                <path
+                    d=frame.curve.generate(&points)
+                    fill="none"
+                    stroke=color
+                    stroke-width="1.5"
+                    stroke-linejoin="round"
+                />
+            </g>
+        }
+        .into_any()
+    }
+}
+
+/// Reference price of a baseline chart
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum BaselineLevel {
+    /// Open of the first candle in the latest candle's UTC day
+    #[default]
+    SessionOpen,
+    Price(f64),
+}
+
+impl BaselineLevel {
+    pub fn resolve(&self, candles: &[Candle]) -> Option<f64> {
+        match *self {
+            Self::SessionOpen => session_open(candles),
+            Self::Price(price) => Some(price),
+        }
+    }
+}
+
+/// Open of the first candle sharing the last candle's UTC day (the first
+/// drawn candle if that day started off-screen)
+pub fn session_open(candles: &[Candle]) -> Option<f64> {
+    let day_start = candles.last()?.timestamp.div_euclid(DAY_MS) * DAY_MS;
+    let i = candles.partition_point(|c| c.timestamp < day_start);
+    Some(candles[i].open.as_f64())
+}
+
+/// Close line against a reference price: green fill above it, red below
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BaselineSeries {
+    pub level: BaselineLevel,
+}
+
+impl SeriesRenderer for BaselineSeries {
+    fn render(&self, frame: &SeriesFrame<'_>) -> AnyView {
+        let Some(reference) = self.level.resolve(frame.candles) else {
+            return ().into_any();
+        };
+        let points = frame.close_points();
+        let base_y = frame.y_scale.scale(reference).clamp(0.0, frame.height);
+        let above_id = format!("{}-above", frame.defs_id);
+        let below_id = format!("{}-below", frame.defs_id);
+        let area = area_path_with(&points, base_y, &frame.curve);
+        let line = frame.curve.generate(&points);
+
+        view! {
+            <g class="series-baseline">
+                <defs>
+                    <clipPath id=above_id.clone()>
+                        <rect x="0" y="0" width=frame.width height=base_y />
+                    </clipPath>
+                    <clipPath id=below_id.clone()>
+                        <rect x="0" y=base_y width=frame.width height=frame.height - base_y />
+                    </clipPath>
+                </defs>
+                <g clip-path=format!("url(#{})", above_id)>
+                    <path d=area.clone() fill=colors::bull_alpha(0.2) stroke="none" />
+                    <path d=line.clone() fill="none" stroke=colors::BULL stroke-width="1.5" />
+                </g>
+                <g clip-path=format!("url(#{})", below_id)>
+                    <path d=area fill=colors::bear_alpha(0.2) stroke="none" />
+                    <path d=line fill="none" stroke=colors::BEAR stroke-width="1.5" />
+                </g>
+                <line
+                    class="baseline-level"
+                    x1="0" y1=base_y
+                    x2=frame.width y2=base_y
+                    stroke=colors::TEXT_MUTED
+                    stroke-width="1"
+                    stroke-dasharray="2,3"
+                />
+                <text
+                    x="4"
+                    y=base_y - 4.0
+                    fill=colors::TEXT_MUTED
+                    font-size="9"
+                    font-family="JetBrains Mono, monospace"
+                >
+                    {format!("BASE {}", format_price(reference, 2))}
+                </text>
+            </g>
+        }
+        .into_any()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dash_core::{CandleInterval, Symbol};
+
+    #[test]
+    fn test_series_frame_closes() {
+        let candles: Vec<Candle> = [(100.0, 110.0), (110.0, 90.0)]
+            .iter()
+            .enumerate()
+            .map(|(i, &(open, close))| {
+                let mut c = Candle::new(Symbol::default(), CandleInterval::M1, i as i64 * 60_000, open);
+                c.update(close, 1.0);
+                c
+            })
+            .collect();
+        let x_scale = BandScale::new(2).range(0.0, 100.0).padding(0.0, 0.0);
+        let y_scale = LinearScale::new().domain(0.0, 200.0).range(200.0, 0.0);
+        let frame = SeriesFrame {
+            candles: &candles,
+            x_scale: &x_scale,
+            y_scale: &y_scale,
+            bandwidth: x_scale.bandwidth(),
+            width: 100.0,
+            height: 200.0,
+            defs_id: "test",
+            curve: Curve::Linear,
+        };
+
+        let points = frame.close_points();
+        assert_eq!(points.iter().map(|p| p.0).collect::<Vec<_>>(), vec![25.0, 75.0]);
+        assert!((points[0].1 - 90.0).abs() < 1e-9 && (points[1].1 - 110.0).abs() < 1e-9);
+        assert_eq!(frame.trend_color(), colors::BEAR);
+    }
+
+    #[test]
+    fn test_session_open() {
+        let hour = 3_600_000;
+        // 22:00 to 03:00 UTC across midnight
+        let candles: Vec<Candle> = (0..6)
+            .map(|i| {
+                let ts = DAY_MS - 2 * hour + i * hour;
+                Candle::new(Symbol::default(), CandleInterval::H1, ts, i as f64)
+            })
+            .collect();
+        assert_eq!(session_open(&candles), Some(2.0));
+        assert_eq!(session_open(&candles[..2]), Some(0.0));
+        assert_eq!(session_open(&[]), None);
+        assert_eq!(BaselineLevel::Price(7.5).resolve(&candles), Some(7.5));
+    }
+}
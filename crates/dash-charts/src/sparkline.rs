@@ -3,7 +3,10 @@
 //! Lightweight, minimal charts for embedding in tables, headers, and tight spaces.
 
 use crate::{
-    chartkit::{line_path, LinearScale, Scale},
+    chartkit::{
+        area_path_with, next_defs_id, Curve, Defs, DomainStrategy, LinearScale, PathGenerator,
+        Scale,
+    },
     colors,
 };
 use leptos::prelude::*;
@@ -20,6 +23,9 @@ pub struct SparklineConfig {
     pub stroke_width: f64,
     pub show_endpoint: bool,
     pub endpoint_radius: f64,
+    /// Value axis auto-scaling
+    pub y_domain: DomainStrategy,
+    pub curve: Curve,
 }
 
 impl Default for SparklineConfig {
@@ -30,6 +36,8 @@ impl Default for SparklineConfig {
             stroke_width: 1.5,
             show_endpoint: true,
             endpoint_radius: 3.0,
+            y_domain: DomainStrategy::Padded { ratio: 0.1 },
+            curve: Curve::default(),
         }
     }
 }
@@ -47,6 +55,8 @@ pub fn PriceSparkline(
     let stroke_w = config.stroke_width;
     let show_end = config.show_endpoint;
     let end_r = config.endpoint_radius;
+    let y_domain = config.y_domain;
+    let curve = config.curve;
 
     let chart_data = move || {
         let data = prices.get();
@@ -54,15 +64,9 @@ pub fn PriceSparkline(
             return None;
         }
 
-        let min = data.iter().cloned().fold(f64::MAX, f64::min);
-        let max = data.iter().cloned().fold(f64::MIN, f64::max);
-
-        // Add padding
-        let range = max - min;
-        let padding = if range > 0.0 { range * 0.1 } else { 1.0 };
-        
+        let (y_min, y_max) = y_domain.domain_of(&data)?;
         let y_scale = LinearScale::new()
-            .domain(min - padding, max + padding)
+            .domain(y_min, y_max)
             .range(h - 2.0, 2.0);
 
         let x_step = (w - 4.0) / (data.len() - 1) as f64;
@@ -73,7 +77,7 @@ pub fn PriceSparkline(
             .map(|(i, &price)| (2.0 + i as f64 * x_step, y_scale.scale(price)))
             .collect();
 
-        let path = line_path(&points);
+        let path = curve.generate(&points);
 
         // Determine color
         let is_positive = positive.unwrap_or_else(|| {
@@ -139,9 +143,13 @@ pub fn VolumeSparkline(
     #[prop(into)] volumes: Signal<Vec<f64>>,
     #[prop(default = 120.0)] width: f64,
     #[prop(default = 24.0)] height: f64,
+    /// Not applied yet; bars keep the bull tint
     #[prop(optional)] color: Option<&'static str>,
+    /// Bar axis auto-scaling (from zero with 10% headroom by default)
+    #[prop(optional)] domain: Option<DomainStrategy>,
 ) -> impl IntoView {
-    let bar_color = color.unwrap_or(colors::BULL);
+    let _ = color;
+    let domain = domain.unwrap_or(DomainStrategy::IncludeZero { ratio: 0.1 });
 
     let chart_data = move || {
         let data = volumes.get();
@@ -149,9 +157,9 @@ pub fn VolumeSparkline(
             return None;
         }
 
-        let max = data.iter().cloned().fold(0.0_f64, f64::max);
+        let (y_min, y_max) = domain.domain_of(&data)?;
         let y_scale = LinearScale::new()
-            .domain(0.0, max * 1.1)
+            .domain(y_min, y_max)
             .range(height - 2.0, 2.0);
 
         let bar_width = ((width - 4.0) / data.len() as f64 - 1.0).max(1.0);
@@ -387,8 +395,14 @@ pub fn AreaSparkline(
     #[prop(default = 120.0)] width: f64,
     #[prop(default = 32.0)] height: f64,
     #[prop(optional)] color: Option<&'static str>,
+    /// Value axis auto-scaling (10% padding by default)
+    #[prop(optional)] domain: Option<DomainStrategy>,
+    #[prop(optional)] curve: Curve,
 ) -> impl IntoView {
     let stroke_color = color.unwrap_or(colors::BULL);
+    let domain = domain.unwrap_or(DomainStrategy::Padded { ratio: 0.1 });
+    let defs = Defs::new(next_defs_id()).fade("fill", stroke_color, 0.35);
+    let fill = defs.url("fill");
 
     let chart_data = move || {
         let data = values.get();
@@ -396,14 +410,9 @@ pub fn AreaSparkline(
             return None;
         }
 
-        let min = data.iter().cloned().fold(f64::MAX, f64::min);
-        let max = data.iter().cloned().fold(f64::MIN, f64::max);
-
-        let range = max - min;
-        let padding = if range > 0.0 { range * 0.1 } else { 1.0 };
-
+        let (y_min, y_max) = domain.domain_of(&data)?;
         let y_scale = LinearScale::new()
-            .domain(min - padding, max + padding)
+            .domain(y_min, y_max)
             .range(height - 2.0, 2.0);
 
         let x_step = (width - 4.0) / (data.len() - 1) as f64;
@@ -416,8 +425,8 @@ pub fn AreaSparkline(
 
         // Build area path
         let baseline = height - 2.0;
-        let area = crate::chartkit::area_path(&points, baseline);
-        let line = line_path(&points);
+        let area = area_path_with(&points, baseline, &curve);
+        let line = curve.generate(&points);
 
         Some((area, line))
     };
@@ -428,14 +437,15 @@ pub fn AreaSparkline(
             viewBox=format!("0 0 {} {}", width, height)
             style="width: 100%; height: 100%;"
         >
+            <defs inner_html=defs.build() />
             {move || {
                 chart_data().map(|(area, line)| {
                     view! {
                         <>
-                            // Filled area
+                            // Filled area, fading out toward the baseline
                             <path
                                 d=area
-                                fill=colors::bull_alpha(0.2)
+                                fill=fill.clone()
                             />
                             // Line
                             <path
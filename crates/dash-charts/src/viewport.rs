@@ -0,0 +1,192 @@
+//! Zoom and pan window over a candle history
+//!
+//! The window is kept as a candle count plus the open time of its rightmost
+//! candle, so it holds still while new candles arrive or history is merged
+//! in. With no right edge pinned it follows the newest candle; panning or
+//! zooming away pins it, and panning back to the end follows again.
+//!
+//! The price axis auto-scales to the visible candles until it is dragged,
+//! which fixes a manual price range until reset.
+
+use std::ops::Range;
+
+use dash_core::Candle;
+
+/// Fewest candles a zoom can narrow the window to
+pub const MIN_VISIBLE_CANDLES: usize = 10;
+
+/// Visible slice of a candle history and the price range it is drawn at
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ChartViewport {
+    /// Candles across the pane; `None` fits the whole history
+    pub bars: Option<usize>,
+    /// Open time of the rightmost candle shown; `None` follows the newest
+    pub right_edge: Option<i64>,
+    /// Manual (low, high) price range; `None` auto-scales
+    pub price_range: Option<(f64, f64)>,
+}
+
+impl ChartViewport {
+    pub fn is_following(&self) -> bool {
+        self.right_edge.is_none()
+    }
+
+    pub fn is_auto_price(&self) -> bool {
+        self.price_range.is_none()
+    }
+
+    /// Indices of `candles` (oldest first) inside the window
+    pub fn window(&self, candles: &[Candle]) -> Range<usize> {
+        let len = candles.len();
+        let bars = self.bars.unwrap_or(len).min(len);
+        let end = match self.right_edge {
+            None => len,
+            Some(ts) => candles.partition_point(|c| c.timestamp <= ts).max(bars),
+        };
+        end - bars..end
+    }
+
+    /// Scale the candle count by `factor` (< 1 zooms in) around `focus`, a
+    /// fraction of the pane width. A following window zooms around its
+    /// right edge and keeps following.
+    pub fn zoom(&mut self, candles: &[Candle], factor: f64, focus: f64) {
+        let Range { start, end } = self.window(candles);
+        let (len, shown) = (candles.len(), end - start);
+        if shown == 0 || !factor.is_finite() || factor <= 0.0 {
+            return;
+        }
+        let focus = if self.is_following() { 1.0 } else { focus.clamp(0.0, 1.0) };
+        let bars = ((shown as f64 * factor).round() as usize)
+            .clamp(MIN_VISIBLE_CANDLES.min(len), len);
+        let pivot = start as f64 + focus * shown as f64;
+        let start = (pivot - focus * bars as f64).round().clamp(0.0, (len - bars) as f64) as usize;
+        self.bars = (bars < len).then_some(bars);
+        self.pin_end(candles, start + bars);
+    }
+
+    /// Move the window `shift` candles, negative towards older ones
+    pub fn pan(&mut self, candles: &[Candle], shift: isize) {
+        let Range { start, end } = self.window(candles);
+        let end = end.saturating_add_signed(shift).clamp(end - start, candles.len());
+        self.pin_end(candles, end);
+    }
+
+    /// Follow the newest candle again, keeping the zoom
+    pub fn go_to_latest(&mut self) {
+        self.right_edge = None;
+    }
+
+    /// Scale `range` by `factor` (> 1 shows more prices) keeping the price
+    /// `anchor` at the same height, and fix the result as the price range
+    pub fn scale_price(&mut self, range: (f64, f64), anchor: f64, factor: f64) {
+        let (low, high) = range;
+        if !(factor.is_finite() && factor > 0.0 && high > low) {
+            return;
+        }
+        let anchor = anchor.clamp(low, high);
+        let (below, above) = ((anchor - low) * factor, (high - anchor) * factor);
+        self.price_range = Some((anchor - below, anchor + above));
+    }
+
+    /// Auto-scale the price axis to the visible candles again
+    pub fn auto_price(&mut self) {
+        self.price_range = None;
+    }
+
+    /// Fit the whole history and follow the newest candle, keeping the
+    /// price range
+    pub fn reset_time(&mut self) {
+        self.bars = None;
+        self.right_edge = None;
+    }
+
+    fn pin_end(&mut self, candles: &[Candle], end: usize) {
+        self.right_edge = (end < candles.len()).then(|| candles[end - 1].timestamp);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dash_core::{CandleInterval, Symbol};
+
+    fn candles(count: i64) -> Vec<Candle> {
+        (0..count)
+            .map(|i| Candle::new(Symbol::default(), CandleInterval::M1, i * 60_000, 100.0))
+            .collect()
+    }
+
+    #[test]
+    fn test_follow_until_panned() {
+        let mut history = candles(100);
+        let mut viewport = ChartViewport::default();
+        assert_eq!(viewport.window(&history), 0..100);
+
+        // Zooming while following keeps the newest candle on the right edge
+        viewport.zoom(&history, 0.5, 0.2);
+        assert_eq!(viewport.window(&history), 50..100);
+        assert!(viewport.is_following());
+
+        viewport.pan(&history, -20);
+        assert_eq!(viewport.window(&history), 30..80);
+        assert!(!viewport.is_following());
+
+        // A new candle leaves a pinned window where it was
+        history.push(Candle::new(Symbol::default(), CandleInterval::M1, 100 * 60_000, 100.0));
+        assert_eq!(viewport.window(&history), 30..80);
+
+        // Panning past the end snaps back to following
+        viewport.pan(&history, 500);
+        assert_eq!(viewport.window(&history), 51..101);
+        assert!(viewport.is_following());
+
+        viewport.pan(&history, -500);
+        assert_eq!(viewport.window(&history), 0..50);
+        viewport.go_to_latest();
+        assert_eq!(viewport.window(&history), 51..101);
+    }
+
+    #[test]
+    fn test_zoom_limits() {
+        let history = candles(100);
+        let mut viewport = ChartViewport {
+            bars: Some(40),
+            right_edge: Some(59 * 60_000),
+            ..Default::default()
+        };
+        assert_eq!(viewport.window(&history), 20..60);
+
+        // The candle under the focus stays put
+        viewport.zoom(&history, 0.5, 0.5);
+        assert_eq!(viewport.window(&history), 30..50);
+
+        viewport.zoom(&history, 0.01, 0.5);
+        assert_eq!(viewport.bars, Some(MIN_VISIBLE_CANDLES));
+        viewport.zoom(&history, 100.0, 0.5);
+        assert_eq!(viewport, ChartViewport::default());
+        assert_eq!(ChartViewport::default().window(&[]), 0..0);
+    }
+
+    #[test]
+    fn test_scale_price() {
+        let mut viewport = ChartViewport::default();
+        assert!(viewport.is_auto_price());
+
+        // The anchor price keeps its place while the range doubles
+        viewport.scale_price((100.0, 200.0), 125.0, 2.0);
+        assert_eq!(viewport.price_range, Some((75.0, 275.0)));
+        viewport.scale_price((100.0, 200.0), 150.0, 0.5);
+        assert_eq!(viewport.price_range, Some((125.0, 175.0)));
+
+        // Degenerate input leaves the range alone
+        viewport.scale_price((100.0, 100.0), 100.0, 2.0);
+        viewport.scale_price((100.0, 200.0), 150.0, f64::NAN);
+        assert_eq!(viewport.price_range, Some((125.0, 175.0)));
+
+        viewport.pan(&candles(20), 0);
+        viewport.reset_time();
+        assert!(!viewport.is_auto_price());
+        viewport.auto_price();
+        assert_eq!(viewport, ChartViewport::default());
+    }
+}
@@ -0,0 +1,87 @@
+//! Accessibility helpers: keyboard row navigation and the screen-reader
+//! price announcer
+
+use std::cell::Cell;
+
+use dash_state::{price_summary, use_app_state, RowMove, TickerFields};
+use leptos::prelude::*;
+use wasm_bindgen::JsCast;
+
+/// Rows reachable with the keyboard inside a navigable list
+const ROW_SELECTOR: &str = "[role=listitem][tabindex]";
+
+/// `keydown` handler for a row list: arrows, PageUp/PageDown and Home/End
+/// move focus between its rows
+pub fn navigate_rows(ev: leptos::ev::KeyboardEvent) {
+    navigate(ev, false);
+}
+
+/// `navigate_rows` for lists drawn bottom-up (the ask ladder), so the
+/// arrows follow what is on screen rather than DOM order
+pub fn navigate_rows_bottom_up(ev: leptos::ev::KeyboardEvent) {
+    navigate(ev, true);
+}
+
+fn navigate(ev: leptos::ev::KeyboardEvent, bottom_up: bool) {
+    let Some(movement) = RowMove::from_key(&ev.key()) else { return };
+    let movement = if bottom_up { movement.reversed() } else { movement };
+    let Some(container) = ev.current_target().and_then(|t| t.dyn_into::<web_sys::Element>().ok()) else {
+        return;
+    };
+    let Ok(nodes) = container.query_selector_all(ROW_SELECTOR) else { return };
+
+    let rows: Vec<web_sys::HtmlElement> = (0..nodes.length())
+        .filter_map(|i| nodes.item(i)?.dyn_into::<web_sys::HtmlElement>().ok())
+        .collect();
+    let active = document().active_element();
+    let current = active.and_then(|active| rows.iter().position(|row| **row == active));
+
+    if let Some(next) = movement.apply(current, rows.len()) {
+        ev.prevent_default();
+        let _ = rows[next].focus();
+    }
+}
+
+/// Visually hidden live region that reads out the price at the cadence
+/// chosen in the status bar
+#[component]
+pub fn PriceAnnouncer() -> impl IntoView {
+    let state = use_app_state();
+    let ui = state.ui;
+    let symbol = state.market.symbol;
+    let fields = TickerFields::new(&state.market);
+    let message = RwSignal::new(String::new());
+
+    // Restarted whenever the cadence changes, cleared with the component
+    let cadence = Memo::new(move |_| ui.with(|ui| ui.announce));
+    let timer = StoredValue::new(None::<IntervalHandle>);
+    let stop = move || {
+        if let Some(handle) = timer.try_get_value().flatten() {
+            handle.clear();
+        }
+    };
+    Effect::new(move |_| {
+        stop();
+        let Some(period) = cadence.get().period() else {
+            timer.set_value(None);
+            return;
+        };
+
+        let last_announced = Cell::new(None);
+        let announce = move || {
+            let Some(price) = fields.last_price.get_untracked() else { return };
+            let symbol = symbol.with_untracked(|s| s.to_string());
+            let change = fields.change_percent_24h.get_untracked();
+            message.set(price_summary(&symbol, price, last_announced.get(), change));
+            last_announced.set(Some(price));
+        };
+        timer.set_value(set_interval_with_handle(announce, period).ok());
+    });
+    on_cleanup(stop);
+
+    view! {
+        <div class="sr-only" role="status" aria-live="polite" aria-atomic="true">
+            {move || message.get()}
+        </div>
+    }
+}
@@ -0,0 +1,189 @@
+//! Account menu: sign in or open an account, see how the sync is going,
+//! sign out. The session is kept in localStorage so a reload stays signed
+//! in until the token expires. Viewers get a read-only badge next to it;
+//! operators get a link to the admin page.
+
+use dash_core::{Credentials, Msg, Role, Session};
+use dash_state::{use_app_state, AccountSync, AppState, SignInRequest};
+use leptos::prelude::*;
+
+/// localStorage key holding the signed-in session
+const SESSION_KEY: &str = "dash.session";
+
+fn load_session() -> Option<Session> {
+    let storage = window().local_storage().ok().flatten()?;
+    let json = storage.get_item(SESSION_KEY).ok().flatten()?;
+    serde_json::from_str(&json).ok()
+}
+
+fn save_session(session: Option<&Session>) {
+    let Ok(Some(storage)) = window().local_storage() else { return };
+    let result = match session.map(serde_json::to_string) {
+        Some(Ok(json)) => storage.set_item(SESSION_KEY, &json),
+        Some(Err(_)) => return,
+        None => storage.remove_item(SESSION_KEY),
+    };
+    if let Err(err) = result {
+        tracing::warn!(?err, "failed to persist the session");
+    }
+}
+
+/// Restore the session from an earlier visit unless it has expired, and
+/// write it back on sign-in and sign-out
+pub fn track_account(state: &AppState) {
+    let account = state.account;
+    let now = js_sys::Date::now() as i64;
+    if let Some(session) = load_session().filter(|s| !s.is_expired(now)) {
+        account.set(Some(session));
+    }
+    Effect::new(move |previous: Option<()>| {
+        account.with(|session| {
+            if previous.is_some() {
+                save_session(session.as_ref());
+            }
+        });
+    });
+}
+
+/// Header button opening the sign-in form, or the signed-in user's name,
+/// sync status and sign-out
+#[component]
+pub fn AccountMenu() -> impl IntoView {
+    let state = use_app_state();
+    let (account, sync, sign_in, role) =
+        (state.account, state.account_sync, state.sign_in, state.role);
+    let locale = state.locale();
+    let t = move |msg: Msg| locale.get().text(msg);
+
+    let open = RwSignal::new(false);
+    let username = RwSignal::new(String::new());
+    let password = RwSignal::new(String::new());
+    let signed_in = move || account.with(Option::is_some);
+    let busy = move || sync.get() == AccountSync::SigningIn;
+
+    // Forget the password once it has been used
+    Effect::new(move |_| {
+        if signed_in() {
+            password.set(String::new());
+        }
+    });
+
+    let submit = move |signup: bool| {
+        let credentials =
+            Credentials { username: username.get_untracked(), password: password.get_untracked() };
+        // Checked here too so the reason shows without a round trip
+        if let Some(err) = credentials.signup_error().filter(|_| signup) {
+            sync.set(AccountSync::Failed(err));
+            return;
+        }
+        sign_in.set(Some(SignInRequest { credentials, signup }));
+    };
+
+    let label = move || match account.get() {
+        Some(session) => session.username,
+        None => t(Msg::SignIn).to_string(),
+    };
+    let status = move || {
+        let status = sync.get();
+        let failed = matches!(status, AccountSync::Failed(_));
+        let class = if failed { "pt-error" } else { "alert-sync" };
+        status.msg().map(|msg| view! { <div class=class role="status">{t(msg)}</div> })
+    };
+
+    let signed_in_view = move || {
+        view! {
+            <div class="account-user">
+                <span class="pt-label">{move || t(Msg::SignedInAs)}</span>
+                <span class="account-name">
+                    {move || account.with(|s| s.as_ref().map(|s| s.username.clone()))}
+                </span>
+                <span class="account-role">{move || t(role.get().msg())}</span>
+            </div>
+            {status}
+            <div class="account-actions">
+                <button
+                    type="button"
+                    class="pt-submit"
+                    on:click=move |_| {
+                        account.set(None);
+                        open.set(false);
+                    }
+                >
+                    {move || t(Msg::SignOut)}
+                </button>
+                <Show when=move || role.with(Role::is_operator)>
+                    <a class="toolbar-btn" href="/admin" on:click=move |_| open.set(false)>
+                        {move || t(Msg::Admin)}
+                    </a>
+                </Show>
+            </div>
+        }
+    };
+
+    view! {
+        <div class="account-menu">
+            <Show when=move || !role.with(Role::is_operator)>
+                <span class="role-badge" title=move || t(Msg::Viewer)>
+                    {move || t(Msg::ReadOnly)}
+                </span>
+            </Show>
+            <button
+                class=move || if open.get() { "toolbar-btn active" } else { "toolbar-btn" }
+                aria-expanded=move || open.get().to_string()
+                title=move || t(Msg::Account)
+                on:click=move |_| open.update(|o| *o = !*o)
+            >
+                {label}
+            </button>
+            <Show when=move || open.get()>
+                <div class="account-popover" role="dialog" aria-label=move || t(Msg::Account)>
+                    <Show when=signed_in fallback=move || view! {
+                        <form
+                            class="account-form"
+                            on:submit=move |ev: leptos::ev::SubmitEvent| {
+                                ev.prevent_default();
+                                submit(false);
+                            }
+                        >
+                            <label class="pt-field">
+                                <span class="pt-label">{move || t(Msg::Username)}</span>
+                                <input
+                                    type="text"
+                                    autocomplete="username"
+                                    spellcheck="false"
+                                    prop:value=move || username.get()
+                                    on:input=move |ev| username.set(event_target_value(&ev))
+                                />
+                            </label>
+                            <label class="pt-field">
+                                <span class="pt-label">{move || t(Msg::Password)}</span>
+                                <input
+                                    type="password"
+                                    autocomplete="current-password"
+                                    prop:value=move || password.get()
+                                    on:input=move |ev| password.set(event_target_value(&ev))
+                                />
+                            </label>
+                            {status}
+                            <div class="account-actions">
+                                <button type="submit" class="pt-submit" disabled=busy>
+                                    {move || t(Msg::SignIn)}
+                                </button>
+                                <button
+                                    type="button"
+                                    class="toolbar-btn"
+                                    disabled=busy
+                                    on:click=move |_| submit(true)
+                                >
+                                    {move || t(Msg::SignUp)}
+                                </button>
+                            </div>
+                        </form>
+                    }>
+                        {signed_in_view}
+                    </Show>
+                </div>
+            </Show>
+        </div>
+    }
+}
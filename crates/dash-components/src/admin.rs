@@ -0,0 +1,278 @@
+//! Admin page (`/admin`): connected clients, per-channel throughput and
+//! upstream health, with controls to switch the data source, trigger mock
+//! scenarios and pause history recording. The server answers operators
+//! only; everyone else sees none of it and is asked to sign in.
+
+use dash_core::{AdminError, AdminStatus, MockScenario, Msg, Role};
+use dash_state::{format_age, use_app_state, AdminCommand, AdminView};
+use leptos::prelude::*;
+
+use crate::{track_account, AccountMenu};
+
+fn button_class(active: bool) -> &'static str {
+    if active { "toolbar-btn active" } else { "toolbar-btn" }
+}
+
+/// Server status and controls for operators
+#[component]
+pub fn AdminPage() -> impl IntoView {
+    let state = use_app_state();
+    let (open, admin, commands) = (state.admin_open, state.admin, state.admin_command);
+    let (account, role) = (state.account, state.role);
+    let locale = state.locale();
+    let t = move |msg: Msg| locale.get().text(msg);
+    let operator = move || role.with(Role::is_operator);
+
+    // Opened straight from a link, the session still has to be restored
+    track_account(&state);
+    // Polled only while the user is an operator; anything shown before is
+    // dropped when that ends
+    Effect::new(move |_| {
+        let operator = operator();
+        if !operator {
+            admin.set(AdminView::Loading);
+        }
+        open.set(operator);
+    });
+    on_cleanup(move || {
+        open.try_set(false);
+    });
+
+    let status = Memo::new(move |_| match admin.get() {
+        AdminView::Ready(status) => Some(status),
+        _ => None,
+    });
+    let read = move |f: fn(&AdminStatus) -> String| status.with(|s| s.as_ref().map(f));
+    let note = move || match admin.get() {
+        _ if !operator() => {
+            let refused = if account.with(Option::is_some) {
+                AdminError::Forbidden
+            } else {
+                AdminError::SignedOut
+            };
+            Some((t(refused.msg()), "admin-note pt-error"))
+        }
+        AdminView::Loading => Some((t(Msg::AdminLoading), "admin-note")),
+        AdminView::Failed(err) => Some((t(err.msg()), "admin-note pt-error")),
+        AdminView::Ready(_) => None,
+    };
+    let send = move |command: AdminCommand| commands.set(Some(command));
+
+    let clients = move || {
+        let clients = status.with(|s| s.as_ref().map(|s| s.clients.clone())).unwrap_or_default();
+        if clients.is_empty() {
+            return view! { <p class="admin-empty">{t(Msg::NoClients)}</p> }.into_any();
+        }
+        view! {
+            <table class="admin-table">
+                <tbody>
+                    {clients
+                        .into_iter()
+                        .map(|client| {
+                            view! {
+                                <tr>
+                                    <td class="admin-mono">{client.ip}</td>
+                                    <td class="admin-num">{client.connections}</td>
+                                </tr>
+                            }
+                        })
+                        .collect_view()}
+                </tbody>
+            </table>
+        }
+        .into_any()
+    };
+
+    let throughput = move || {
+        let rows = status.with(|s| s.as_ref().map(|s| s.throughput.clone())).unwrap_or_default();
+        let locale = locale.get();
+        rows.into_iter()
+            .map(|row| {
+                view! {
+                    <tr>
+                        <td>{row.channel.label()}</td>
+                        <td class="admin-num">{locale.format_decimal(row.per_sec, 1)}</td>
+                        <td class="admin-num">{locale.format_compact(row.total as f64)}</td>
+                    </tr>
+                }
+            })
+            .collect_view()
+    };
+
+    let adapters = move || {
+        let report = status.with(|s| s.as_ref().map(|s| s.health.clone()));
+        report.map(|report| {
+            report
+                .adapters
+                .into_iter()
+                .map(|adapter| {
+                    let channels = adapter.channels.into_iter().map(|channel| {
+                        let age = channel.last_message_age_ms.map(|ms| format_age(ms as i64));
+                        let class =
+                            if channel.stale { "admin-channel stale" } else { "admin-channel" };
+                        view! {
+                            <span class=class>
+                                {channel.channel.label()}" "{age.unwrap_or_else(|| "—".into())}
+                            </span>
+                        }
+                    });
+                    view! {
+                        <div class="admin-adapter">
+                            <span class="admin-mono">{adapter.name}</span>
+                            <span class=format!("admin-status {}", adapter.status.css_class())>
+                                {t(Msg::feed(adapter.status))}
+                            </span>
+                            {channels.collect_view()}
+                        </div>
+                    }
+                })
+                .collect_view()
+        })
+    };
+
+    // Rebuilt only when the chain changes so a poll does not steal focus
+    let sources = Memo::new(move |_| {
+        status.with(|s| s.as_ref().map(|s| s.sources.clone())).unwrap_or_default()
+    });
+    let active_source = Memo::new(move |_| read(|s| s.active_source.clone()));
+    let source_buttons = move || {
+        sources
+            .get()
+            .into_iter()
+            .map(|source| {
+                let name = source.clone();
+                let active = move || active_source.with(|a| a.as_ref() == Some(&name));
+                let pressed = active.clone();
+                let label = source.clone();
+                view! {
+                    <button
+                        type="button"
+                        class=move || button_class(active())
+                        aria-pressed=move || pressed().to_string()
+                        on:click=move |_| send(AdminCommand::Source(source.clone()))
+                    >
+                        {label}
+                    </button>
+                }
+            })
+            .collect_view()
+    };
+
+    let scenarios = Memo::new(move |_| status.with(|s| s.as_ref().is_some_and(|s| s.scenarios)));
+    let running = Memo::new(move |_| status.with(|s| s.as_ref().and_then(|s| s.scenario)));
+    let scenario_buttons = move || {
+        MockScenario::all()
+            .iter()
+            .copied()
+            .map(|scenario| {
+                let active = move || running.get() == Some(scenario);
+                view! {
+                    <button
+                        type="button"
+                        class=move || button_class(active())
+                        aria-pressed=move || active().to_string()
+                        on:click=move |_| send(AdminCommand::Scenario(Some(scenario)))
+                    >
+                        {move || t(scenario.msg())}
+                    </button>
+                }
+            })
+            .collect_view()
+    };
+
+    let recording = Memo::new(move |_| status.with(|s| s.as_ref().and_then(|s| s.recording)));
+    let recording_control = move || match recording.get() {
+        None => view! { <span class="admin-empty">{t(Msg::NoStorage)}</span> }.into_any(),
+        Some(on) => view! {
+            <button
+                type="button"
+                class="toolbar-btn"
+                on:click=move |_| send(AdminCommand::Recording(!on))
+            >
+                {t(if on { Msg::PauseRecording } else { Msg::ResumeRecording })}
+            </button>
+        }
+        .into_any(),
+    };
+
+    view! {
+        <div class="admin-page">
+            <header class="dash-header admin-header">
+                <a class="toolbar-btn" href="/">{move || t(Msg::BackToDashboard)}</a>
+                <h1 class="admin-title">{move || t(Msg::ServerAdmin)}</h1>
+                <AccountMenu />
+            </header>
+            <main class="admin-main">
+                {move || {
+                    note().map(|(text, class)| view! { <p class=class role="status">{text}</p> })
+                }}
+                <Show when=move || operator() && status.with(Option::is_some)>
+                    <section class="panel admin-section">
+                        <div class="panel-header">
+                            <span class="panel-title">{move || t(Msg::Clients)}</span>
+                            <span class="admin-count">
+                                {move || status.with(|s| s.as_ref().map(AdminStatus::connections))}
+                                " "
+                                {move || t(Msg::Connections)}
+                            </span>
+                        </div>
+                        <div class="panel-content">{clients}</div>
+                    </section>
+                    <section class="panel admin-section">
+                        <div class="panel-header">
+                            <span class="panel-title">{move || t(Msg::Throughput)}</span>
+                        </div>
+                        <div class="panel-content">
+                            <table class="admin-table">
+                                <thead>
+                                    <tr>
+                                        <th></th>
+                                        <th class="admin-num">{move || t(Msg::PerSecond)}</th>
+                                        <th class="admin-num">{move || t(Msg::Total)}</th>
+                                    </tr>
+                                </thead>
+                                <tbody>{throughput}</tbody>
+                            </table>
+                        </div>
+                    </section>
+                    <section class="panel admin-section">
+                        <div class="panel-header">
+                            <span class="panel-title">{move || t(Msg::Upstream)}</span>
+                        </div>
+                        <div class="panel-content">{adapters}</div>
+                    </section>
+                    <section class="panel admin-section">
+                        <div class="panel-header">
+                            <span class="panel-title">{move || t(Msg::DataSource)}</span>
+                        </div>
+                        <div class="panel-content admin-controls">{source_buttons}</div>
+                    </section>
+                    <Show when=move || scenarios.get()>
+                        <section class="panel admin-section">
+                            <div class="panel-header">
+                                <span class="panel-title">{move || t(Msg::MockScenarios)}</span>
+                            </div>
+                            <div class="panel-content admin-controls">
+                                {scenario_buttons}
+                                <button
+                                    type="button"
+                                    class="toolbar-btn"
+                                    disabled=move || running.with(Option::is_none)
+                                    on:click=move |_| send(AdminCommand::Scenario(None))
+                                >
+                                    {move || t(Msg::EndScenario)}
+                                </button>
+                            </div>
+                        </section>
+                    </Show>
+                    <section class="panel admin-section">
+                        <div class="panel-header">
+                            <span class="panel-title">{move || t(Msg::Recording)}</span>
+                        </div>
+                        <div class="panel-content admin-controls">{recording_control}</div>
+                    </section>
+                </Show>
+            </main>
+        </div>
+    }
+}
@@ -0,0 +1,371 @@
+//! Alert editor: rules written as conditions on live market values, checked
+//! as data arrives, and where fired rules are delivered - browser
+//! notifications and the server's webhook. Rules and delivery settings are
+//! kept in localStorage so they survive reloads.
+
+use dash_core::{AlertBook, AlertRule, ExprError, Locale, Msg, WebhookFormat};
+use dash_state::{use_app_state, AlertDelivery, AppState, DashEvent, DashEventKind};
+use leptos::prelude::*;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Notification, NotificationOptions, NotificationPermission};
+
+/// localStorage key holding the rules as a JSON array
+const ALERTS_KEY: &str = "dash.alerts";
+
+/// localStorage key holding the delivery settings
+const DELIVERY_KEY: &str = "dash.alert-delivery";
+
+const CONDITION_PLACEHOLDER: &str = "price > 100000 && rsi(14) < 30";
+
+/// Names a condition can use, shown as the editor's tooltip
+const CONDITION_HELP: &str = "price, bid, ask, mid, spread, spread_pct, change_pct, high_24h, \
+     low_24h, volume_24h, open/high/low/close/volume; rsi(n), sma(n), ema(n), stdev(n), \
+     highest(n), lowest(n), prev(n) over closes or e.g. sma(high, n); abs, min, max";
+
+fn load_alerts() -> Option<Vec<AlertRule>> {
+    let storage = window().local_storage().ok().flatten()?;
+    let json = storage.get_item(ALERTS_KEY).ok().flatten()?;
+    serde_json::from_str(&json).ok()
+}
+
+fn save_alerts(book: &AlertBook) {
+    let Ok(Some(storage)) = window().local_storage() else { return };
+    let result = if book.is_empty() {
+        storage.remove_item(ALERTS_KEY)
+    } else {
+        match serde_json::to_string(book.rules()) {
+            Ok(json) => storage.set_item(ALERTS_KEY, &json),
+            Err(_) => return,
+        }
+    };
+    if let Err(err) = result {
+        tracing::warn!(?err, "failed to persist alert rules");
+    }
+}
+
+/// Restore the rules from earlier visits and write them back whenever one
+/// is added or removed
+pub fn track_alerts(state: &AppState) {
+    let alerts = state.alerts;
+    if let Some(rules) = load_alerts() {
+        alerts.update(|book| book.restore(rules));
+    }
+    Effect::new(move |previous: Option<()>| {
+        alerts.with(|book| {
+            if previous.is_some() {
+                save_alerts(book);
+            }
+        });
+    });
+}
+
+fn load_delivery() -> Option<AlertDelivery> {
+    let storage = window().local_storage().ok().flatten()?;
+    let json = storage.get_item(DELIVERY_KEY).ok().flatten()?;
+    serde_json::from_str(&json).ok()
+}
+
+fn save_delivery(delivery: &AlertDelivery) {
+    let Ok(Some(storage)) = window().local_storage() else { return };
+    let Ok(json) = serde_json::to_string(delivery) else { return };
+    if let Err(err) = storage.set_item(DELIVERY_KEY, &json) {
+        tracing::warn!(?err, "failed to persist alert delivery settings");
+    }
+}
+
+/// Permission to show notifications, or `None` where the browser has no
+/// Notification API (it needs a secure context)
+fn notification_permission() -> Option<NotificationPermission> {
+    let supported = js_sys::Reflect::has(&window(), &JsValue::from_str("Notification"));
+    supported.unwrap_or(false).then(Notification::permission)
+}
+
+/// Ask for permission to notify; true when granted
+async fn request_notification_permission() -> bool {
+    let Ok(promise) = Notification::request_permission() else { return false };
+    let answer = JsFuture::from(promise).await.ok().and_then(|v| v.as_string());
+    answer.as_deref() == Some("granted")
+}
+
+fn show_notification(title: &str, body: &str) {
+    let options = NotificationOptions::new();
+    options.set_body(body);
+    if let Err(err) = Notification::new_with_options(title, &options) {
+        tracing::warn!(?err, "failed to show alert notification");
+    }
+}
+
+/// Restore the delivery settings, write them back on change, and raise a
+/// browser notification for each alert while the page is in the background
+/// (the toast covers it otherwise)
+pub fn track_alert_delivery(state: &AppState) {
+    let delivery = state.alert_delivery;
+    if let Some(saved) = load_delivery() {
+        delivery.set(saved);
+    }
+    Effect::new(move |previous: Option<()>| {
+        delivery.with(|settings| {
+            if previous.is_some() {
+                save_delivery(settings);
+            }
+        });
+    });
+
+    let locale = state.locale();
+    state.events.subscribe(Some(DashEventKind::AlertTriggered), move |event| {
+        let DashEvent::AlertTriggered { message, .. } = event else { return };
+        let notify = delivery.try_with_untracked(|d| d.notify).unwrap_or(false);
+        let background = !document().has_focus().unwrap_or(true);
+        let granted = notification_permission() == Some(NotificationPermission::Granted);
+        if notify && background && granted {
+            show_notification(locale.get_untracked().text(Msg::Alerts), message);
+        }
+    });
+}
+
+/// What is wrong with a condition, naming the name or position at fault
+pub(crate) fn expr_error_text(locale: Locale, err: &ExprError) -> String {
+    let text = locale.text(err.msg());
+    match err.name() {
+        Some(name) => format!("{}: {}", text, name),
+        None if err.at > 0 => format!("{} ({})", text, err.at + 1),
+        None => text.to_string(),
+    }
+}
+
+/// Status dot of a rule: holding, not holding, or undecided
+fn status_class(holds: Option<bool>) -> &'static str {
+    match holds {
+        Some(true) => "alert-status holds",
+        Some(false) => "alert-status",
+        None => "alert-status undecided",
+    }
+}
+
+/// Condition input checked as it is typed, and the rules with their state
+#[component]
+pub fn AlertEditor() -> impl IntoView {
+    let state = use_app_state();
+    let alerts = state.alerts;
+    let locale = state.locale();
+    let t = move |msg: Msg| locale.get().text(msg);
+
+    let condition = RwSignal::new(String::new());
+    // Compiled on every keystroke so the error shows before submitting
+    let error = Memo::new(move |_| {
+        let symbol = state.market.symbol.get();
+        condition.with(|source| {
+            let source = source.trim();
+            if source.is_empty() {
+                return None;
+            }
+            AlertRule::compile(0, symbol, source).err()
+        })
+    });
+
+    let add = {
+        let state = state.clone();
+        move |ev: leptos::ev::SubmitEvent| {
+            ev.prevent_default();
+            let added = condition.with_untracked(|source| state.add_alert(source));
+            if added.is_ok() {
+                condition.set(String::new());
+            }
+        }
+    };
+
+    let rows = {
+        let state = state.clone();
+        move || {
+            let market = state.market.clone();
+            let current = market.symbol.get();
+            let rules = alerts.with(|book| book.rules().to_vec());
+            let holds = market.with_alert_inputs(|inputs| {
+                rules
+                    .iter()
+                    .map(|rule| if *rule.symbol() == current { rule.holds(inputs) } else { None })
+                    .collect::<Vec<_>>()
+            });
+            rules
+                .into_iter()
+                .zip(holds)
+                .map(|(rule, holds)| {
+                    let state = state.clone();
+                    let id = rule.id();
+                    view! {
+                        <li class="alert-rule">
+                            <span class=status_class(holds) aria-hidden="true"></span>
+                            <span class="alert-symbol">{rule.symbol().to_string()}</span>
+                            <code class="alert-source">{rule.source().to_string()}</code>
+                            <button
+                                type="button"
+                                class="pt-cancel"
+                                aria-label=format!("Remove alert {}", rule.source())
+                                on:click=move |_| state.remove_alert(id)
+                            >
+                                "×"
+                            </button>
+                        </li>
+                    }
+                })
+                .collect_view()
+        }
+    };
+
+    view! {
+        <form class="alert-form" on:submit=add>
+            <label class="pt-field" for="alert-condition">
+                <span class="pt-label">{move || t(Msg::AlertCondition)}</span>
+                <input
+                    id="alert-condition"
+                    type="text"
+                    spellcheck="false"
+                    placeholder=CONDITION_PLACEHOLDER
+                    title=CONDITION_HELP
+                    aria-invalid=move || error.with(Option::is_some).to_string()
+                    prop:value=move || condition.get()
+                    on:input=move |ev| condition.set(event_target_value(&ev))
+                />
+            </label>
+            {move || error.get().map(|err| view! {
+                <div class="pt-error" role="alert">{expr_error_text(locale.get(), &err)}</div>
+            })}
+            <button
+                type="submit"
+                class="pt-submit"
+                disabled=move || {
+                    condition.with(|c| c.trim().is_empty()) || error.with(Option::is_some)
+                }
+            >
+                {move || t(Msg::AddAlert)}
+            </button>
+        </form>
+        <Show
+            when=move || !alerts.with(AlertBook::is_empty)
+            fallback=move || {
+                view! { <div class="journal-summary">{move || t(Msg::NoAlerts)}</div> }
+            }
+        >
+            <ul class="alert-rules" aria-label=move || t(Msg::Alerts)>{rows.clone()}</ul>
+        </Show>
+    }
+}
+
+/// Panel header toggle for browser notifications; turning it on asks for
+/// permission first. Disabled where notifications are blocked.
+#[component]
+pub fn NotificationToggle() -> impl IntoView {
+    let state = use_app_state();
+    let delivery = state.alert_delivery;
+    let locale = state.locale();
+    let permission = RwSignal::new(notification_permission());
+    let granted = move || permission.get() == Some(NotificationPermission::Granted);
+    let on = Memo::new(move |_| granted() && delivery.with(|d| d.notify));
+    let blocked = move || matches!(permission.get(), None | Some(NotificationPermission::Denied));
+    let title = move || {
+        let msg = if blocked() { Msg::NotificationsBlocked } else { Msg::Notifications };
+        locale.get().text(msg)
+    };
+
+    let toggle = move |_| {
+        if on.get_untracked() {
+            delivery.update(|d| d.notify = false);
+        } else if permission.get_untracked() == Some(NotificationPermission::Granted) {
+            delivery.update(|d| d.notify = true);
+        } else {
+            leptos::task::spawn_local(async move {
+                let granted = request_notification_permission().await;
+                permission.try_set(notification_permission());
+                if granted {
+                    delivery.try_update(|d| d.notify = true);
+                }
+            });
+        }
+    };
+
+    view! {
+        <button
+            class=move || if on.get() { "tint-btn active" } else { "tint-btn" }
+            title=title
+            aria-label=title
+            aria-pressed=move || on.get().to_string()
+            disabled=blocked
+            on:click=toggle
+        >
+            "🔔"
+        </button>
+    }
+}
+
+/// Server delivery: the webhook the server posts fired rules to, the token
+/// the rules are pushed with, and how the last push went
+#[component]
+pub fn AlertDeliverySettings() -> impl IntoView {
+    let state = use_app_state();
+    let (delivery, sync) = (state.alert_delivery, state.alert_sync);
+    let locale = state.locale();
+    let t = move |msg: Msg| locale.get().text(msg);
+
+    let select_format = move |ev| {
+        let label = event_target_value(&ev);
+        if let Some(format) = WebhookFormat::all().iter().find(|f| f.label() == label) {
+            delivery.update(|d| d.format = *format);
+        }
+    };
+    let formats = WebhookFormat::all()
+        .iter()
+        .map(|format| {
+            let format = *format;
+            view! {
+                <option
+                    value=format.label()
+                    selected=move || delivery.with(|d| d.format == format)
+                >
+                    {format.label()}
+                </option>
+            }
+        })
+        .collect_view();
+
+    view! {
+        <div class="alert-delivery">
+            <span class="sb-label">{move || t(Msg::ServerDelivery)}</span>
+            <label class="pt-field">
+                <span class="pt-label">{move || t(Msg::WebhookUrl)}</span>
+                <input
+                    type="url"
+                    spellcheck="false"
+                    placeholder="https://discord.com/api/webhooks/…"
+                    prop:value=move || delivery.with(|d| d.webhook_url.clone())
+                    on:change=move |ev| {
+                        let url = event_target_value(&ev).trim().to_string();
+                        delivery.update(|d| d.webhook_url = url);
+                    }
+                />
+            </label>
+            <label class="pt-field">
+                <span class="pt-label">{move || t(Msg::WebhookFormat)}</span>
+                <select class="sb-select" on:change=select_format>{formats}</select>
+            </label>
+            <label class="pt-field">
+                <span class="pt-label">{move || t(Msg::ServerToken)}</span>
+                <input
+                    type="password"
+                    autocomplete="off"
+                    title="The server's DASH_ALERTS_TOKEN"
+                    prop:value=move || delivery.with(|d| d.token.clone())
+                    on:change=move |ev| {
+                        let token = event_target_value(&ev).trim().to_string();
+                        delivery.update(|d| d.token = token);
+                    }
+                />
+            </label>
+            {move || {
+                let status = sync.get();
+                let class = if status.is_error() { "pt-error" } else { "alert-sync" };
+                status.msg().map(|msg| view! { <div class=class role="status">{t(msg)}</div> })
+            }}
+        </div>
+    }
+}
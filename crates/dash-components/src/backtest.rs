@@ -0,0 +1,150 @@
+//! Backtest panel: runs the SMA crossover strategy over the chart's candle
+//! history through the paper engine and shows its equity curve and fills
+
+use chrono::{DateTime, Utc};
+use dash_charts::AreaSparkline;
+use dash_core::{colors, Backtest, BacktestReport, Msg, SmaCross};
+use dash_state::use_app_state;
+use leptos::prelude::*;
+
+use crate::paper::pnl_class;
+
+/// Fills listed under the curve, newest first
+const SHOWN_FILLS: usize = 30;
+
+/// Whole number of at least 1 typed into a period field
+fn parse_period(text: &str) -> Option<usize> {
+    text.trim().parse::<usize>().ok().filter(|p| *p > 0)
+}
+
+#[component]
+pub fn BacktestPanel() -> impl IntoView {
+    let state = use_app_state();
+    let candles = state.market.candles;
+    let locale = state.locale();
+    let t = move |msg: Msg| locale.get().text(msg);
+
+    let fast = RwSignal::new("9".to_string());
+    let slow = RwSignal::new("21".to_string());
+    let size = RwSignal::new("1".to_string());
+    let report = RwSignal::new(None::<BacktestReport>);
+
+    // Closed candles only; the newest is still forming
+    let run = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        let fast = fast.with(|f| parse_period(f)).unwrap_or(9);
+        let slow = slow.with(|s| parse_period(s)).unwrap_or(21);
+        let size = size.with(|s| s.trim().parse::<f64>().ok()).filter(|s| *s > 0.0);
+        let mut strategy = SmaCross::new(fast, slow, size.unwrap_or(1.0));
+        let result = candles.with_untracked(|history| {
+            let all = history.candles.as_slice();
+            let closed = &all[..all.len().saturating_sub(1)];
+            Backtest::new(history.symbol.clone()).with_candles(closed).run(&mut strategy)
+        });
+        report.set(Some(result));
+    };
+
+    let equity = Signal::derive(move || {
+        report.with(|r| r.as_ref().map(|r| r.journal.equity_curve()).unwrap_or_default())
+    });
+    let realized = move || report.with(|r| r.as_ref().map_or(0.0, |r| r.journal.realized_pnl()));
+    let open = move || report.with(|r| r.as_ref().map_or(0.0, BacktestReport::open_pnl));
+    let equity_color =
+        Memo::new(move |_| if realized() + open() < 0.0 { colors::BEAR } else { colors::BULL });
+
+    let input = move |id: &'static str, msg: Msg, value: RwSignal<String>| {
+        view! {
+            <label class="pt-field" for=id>
+                <span class="pt-label">{move || t(msg)}</span>
+                <input
+                    id=id
+                    type="number"
+                    min="1"
+                    step="any"
+                    prop:value=move || value.get()
+                    on:input=move |ev| value.set(event_target_value(&ev))
+                />
+            </label>
+        }
+    };
+
+    let summary = move || {
+        let locale = locale.get();
+        report.with(|report| {
+            let report = report.as_ref()?;
+            let metrics = *report.journal.metrics();
+            let win_rate = metrics
+                .win_rate()
+                .map_or("—".to_string(), |r| format!("{}%", locale.format_decimal(r * 100.0, 1)));
+            let (realized, open) = (report.journal.realized_pnl(), report.open_pnl());
+            Some(view! {
+                <div class="risk-metrics">
+                    <span class="pt-label">{locale.text(Msg::RealizedPnl)}</span>
+                    <span class=pnl_class(realized)>{locale.format_decimal(realized, 2)}</span>
+                    <span class="pt-label">{locale.text(Msg::OpenPnl)}</span>
+                    <span class=pnl_class(open)>{locale.format_decimal(open, 2)}</span>
+                    <span class="pt-label">{locale.text(Msg::Trades)}</span>
+                    <span class="pt-value">{metrics.trades()}</span>
+                    <span class="pt-label">{locale.text(Msg::WinRate)}</span>
+                    <span class="pt-value">{win_rate}</span>
+                    <span class="pt-label">{locale.text(Msg::MaxDrawdown)}</span>
+                    <span class=pnl_class(-metrics.max_drawdown())>
+                        {locale.format_decimal(metrics.max_drawdown(), 2)}
+                    </span>
+                </div>
+            })
+        })
+    };
+
+    let fills = move || {
+        report.with(|report| {
+            report.as_ref().map_or_else(Vec::new, |r| {
+                r.journal.fills().rev().take(SHOWN_FILLS).cloned().collect::<Vec<_>>()
+            })
+        })
+    };
+
+    view! {
+        <form class="paper-ticket backtest-form" on:submit=run>
+            {input("bt-fast", Msg::FastSma, fast)}
+            {input("bt-slow", Msg::SlowSma, slow)}
+            {input("bt-size", Msg::Size, size)}
+            <button type="submit" class="pt-submit">{move || t(Msg::RunBacktest)}</button>
+        </form>
+        {summary}
+        {move || {
+            let color = equity_color.get();
+            report.with(Option::is_some).then(|| view! {
+                <div class="journal-equity" aria-hidden="true">
+                    <AreaSparkline values=equity width=260.0 height=40.0 color=color />
+                </div>
+            })
+        }}
+        <ul class="journal-fills" aria-label=move || t(Msg::Backtest)>
+            {move || {
+                let locale = locale.get();
+                fills()
+                    .into_iter()
+                    .map(|fill| {
+                        let time = DateTime::<Utc>::from_timestamp_millis(fill.timestamp)
+                            .unwrap_or_default()
+                            .format("%m-%d %H:%M")
+                            .to_string();
+                        let pnl = fill.realized_pnl;
+                        view! {
+                            <li class="journal-fill">
+                                <span class="journal-time">{time}</span>
+                                <span class=fill.side.css_class()>
+                                    {locale.text(Msg::side(fill.side))}
+                                </span>
+                                <span>{locale.format_decimal(fill.quantity, 4)}</span>
+                                <span>{locale.format_decimal(fill.price, 2)}</span>
+                                <span class=pnl_class(pnl)>{locale.format_decimal(pnl, 2)}</span>
+                            </li>
+                        }
+                    })
+                    .collect_view()
+            }}
+        </ul>
+    }
+}
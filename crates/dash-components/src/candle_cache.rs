@@ -0,0 +1,140 @@
+//! Candle history cache
+//!
+//! Keeps the last candle history seen for each symbol and interval in
+//! IndexedDB, so revisiting a market draws the chart straight away instead
+//! of waiting for the stream to build it up. The server history fetch then
+//! reconciles over the cached candles.
+
+use dash_core::{Candle, CandleInterval, Symbol};
+use dash_state::MarketState;
+use leptos::{prelude::*, task::spawn_local};
+use wasm_bindgen::JsValue;
+use web_sys::{IdbDatabase, IdbTransactionMode};
+
+use crate::idb::{open_database, request_result};
+
+const DB_NAME: &str = "dash-candle-cache";
+const DB_VERSION: u32 = 1;
+const STORE_NAME: &str = "candles";
+
+/// Store key for one symbol and interval
+fn cache_key(symbol: &Symbol, interval: CandleInterval) -> String {
+    format!("{}:{}", symbol.as_str(), interval.code())
+}
+
+/// IndexedDB object store of candle histories as JSON, keyed by `cache_key`
+#[derive(Clone)]
+struct CandleCache {
+    db: IdbDatabase,
+}
+
+impl CandleCache {
+    async fn open() -> Result<Self, JsValue> {
+        let db = open_database(DB_NAME, DB_VERSION, |db| {
+            db.create_object_store(STORE_NAME)?;
+            Ok(())
+        })
+        .await?;
+        Ok(Self { db })
+    }
+
+    async fn load(&self, key: &str) -> Result<Vec<Candle>, JsValue> {
+        let request = self
+            .db
+            .transaction_with_str(STORE_NAME)?
+            .object_store(STORE_NAME)?
+            .get(&JsValue::from_str(key))?;
+        let json = request_result(&request).await?.as_string();
+        Ok(json.and_then(|json| serde_json::from_str(&json).ok()).unwrap_or_default())
+    }
+
+    fn save(&self, key: &str, candles: &[Candle]) -> Result<(), JsValue> {
+        let json = serde_json::to_string(candles)
+            .map_err(|err| JsValue::from_str(&err.to_string()))?;
+        self.db
+            .transaction_with_str_and_mode(STORE_NAME, IdbTransactionMode::Readwrite)?
+            .object_store(STORE_NAME)?
+            .put_with_key(&JsValue::from_str(&json), &JsValue::from_str(key))?;
+        Ok(())
+    }
+}
+
+/// Hydrate the chart from the cache on every symbol/interval change, and
+/// write the live history back whenever a candle is added or reconciled
+pub fn track_candle_cache(market: &MarketState) {
+    let cache = StoredValue::new_local(None::<CandleCache>);
+    let market = market.clone();
+
+    let selection = Memo::new({
+        let market = market.clone();
+        move |_| (market.symbol.get(), market.interval.get())
+    });
+    Effect::new({
+        let market = market.clone();
+        move |_| {
+            let (symbol, interval) = selection.get();
+            let market = market.clone();
+            spawn_local(async move {
+                let db = match cache.get_value() {
+                    Some(db) => db,
+                    None => match CandleCache::open().await {
+                        Ok(db) => {
+                            cache.set_value(Some(db.clone()));
+                            db
+                        }
+                        Err(err) => {
+                            tracing::warn!(?err, "candle cache unavailable");
+                            return;
+                        }
+                    },
+                };
+                let cached = match db.load(&cache_key(&symbol, interval)).await {
+                    Ok(cached) => cached,
+                    Err(err) => {
+                        tracing::warn!(?err, "failed to read candle cache");
+                        return;
+                    }
+                };
+                // The user may have moved on while the cache was read
+                if market.symbol.get_untracked() == symbol
+                    && market.interval.get_untracked() == interval
+                {
+                    tracing::debug!(count = cached.len(), %symbol, "hydrating candles from cache");
+                    market.hydrate_candles(cached);
+                }
+            });
+        }
+    });
+
+    // (symbol, interval, candle count, newest open time): changes when a
+    // candle opens or history is merged, not on every trade
+    let snapshot = Memo::new({
+        let market = market.clone();
+        move |_| {
+            market.candles.with(|history| {
+                let newest = history.candles.latest().map(|c| c.timestamp);
+                (history.symbol.clone(), history.interval, history.candles.len(), newest)
+            })
+        }
+    });
+    Effect::new(move |_| {
+        let (symbol, interval, count, _) = snapshot.get();
+        // Replay shows a rewound history that must not overwrite the cache
+        if count == 0 || !market.replay.with_untracked(|r| r.is_live()) {
+            return;
+        }
+        let Some(db) = cache.get_value() else { return };
+        let result = market.candles.with_untracked(|history| {
+            db.save(&cache_key(&symbol, interval), history.candles.as_slice())
+        });
+        if let Err(err) = result {
+            tracing::warn!(?err, "failed to write candle cache");
+        }
+    });
+
+    on_cleanup(move || {
+        if let Some(db) = cache.get_value() {
+            db.db.close();
+        }
+    });
+}
@@ -0,0 +1,183 @@
+//! Session clock: UTC/local time, candle-close countdown, regional market
+//! hours, the chart's shaded ranges and countdowns to scheduled events
+
+use std::time::Duration;
+
+use chrono::{DateTime, Local, Utc};
+use dash_core::{format_countdown, upcoming_events, MarketSession, Msg};
+use dash_state::{use_app_state, Overlay, ServerClock};
+use leptos::prelude::*;
+
+/// Scheduled events counted down in the clock strip
+const EVENT_CHIPS: usize = 3;
+
+/// Events further out than this get no chip (ms)
+const EVENT_HORIZON_MS: i64 = 7 * 86_400_000;
+
+/// Tick `clock` once a second; countdowns read the server time from it
+pub fn track_clock(clock: RwSignal<ServerClock>) {
+    let tick = move || clock.update(|c| c.tick(Utc::now().timestamp_millis()));
+    tick();
+
+    let timer = set_interval_with_handle(tick, Duration::from_secs(1)).ok();
+    on_cleanup(move || {
+        if let Some(handle) = timer {
+            handle.clear();
+        }
+    });
+}
+
+/// Clock strip under the ticker; the session chips toggle their chart markers
+#[component]
+pub fn SessionClock() -> impl IntoView {
+    let state = use_app_state();
+    let interval = state.market.interval;
+    let ui = state.ui;
+    let locale = state.locale();
+    let t = move |msg: Msg| locale.get().text(msg);
+
+    // Server time, advanced once a second by `track_clock`
+    let server_now = state.server_now();
+    let now_ms = move || server_now.get();
+    let now = move || DateTime::<Utc>::from_timestamp_millis(now_ms()).unwrap_or_default();
+
+    let close_in = move || {
+        let now_ms = now_ms();
+        format_countdown(interval.get().next_close(now_ms) - now_ms)
+    };
+
+    // Shaded chart ranges get chips next to the sessions; clicking one
+    // toggles the shading
+    let shading = state.shading;
+    let shaded = move || ui.with(|ui| ui.overlays.shading);
+    let state_for_shading = state.clone();
+
+    // Upcoming events as (title, kind color, start)
+    let calendar = state.calendar;
+    let upcoming = move || {
+        let now_ms = now_ms();
+        calendar.with(|events| {
+            upcoming_events(events, now_ms, EVENT_CHIPS)
+                .into_iter()
+                .filter(|e| e.timestamp - now_ms <= EVENT_HORIZON_MS)
+                .map(|e| (e.title.clone(), e.kind.color(), e.timestamp))
+                .collect::<Vec<_>>()
+        })
+    };
+
+    view! {
+        <div class="session-clock">
+            <span class="clock-time">
+                <span class="clock-label">"UTC"</span>
+                {move || now().format("%H:%M:%S").to_string()}
+            </span>
+            <span class="clock-time">
+                <span class="clock-label">{move || t(Msg::LocalTime)}</span>
+                {move || now().with_timezone(&Local).format("%H:%M:%S").to_string()}
+            </span>
+            <span class="clock-time clock-close" title=move || t(Msg::CandleClose)>
+                <span class="clock-label">
+                    {move || format!("{} {}", t(Msg::CandleClose), interval.get().label())}
+                </span>
+                {close_in}
+            </span>
+
+            <div class="clock-events" role="group" aria-label=move || t(Msg::ScheduledEvents)>
+                {move || {
+                    let now_ms = now_ms();
+                    upcoming()
+                        .into_iter()
+                        .map(|(title, color, at)| {
+                            let when = DateTime::<Utc>::from_timestamp_millis(at)
+                                .unwrap_or_default()
+                                .format("%b %-d %H:%M UTC");
+                            let tooltip = format!("{} · {}", title, when);
+                            let dot = format!("background: {}", color);
+                            view! {
+                                <span class="event-chip" title=tooltip>
+                                    <span class="session-dot" style=dot></span>
+                                    {title}
+                                    <span class="event-countdown">{format_countdown(at - now_ms)}</span>
+                                </span>
+                            }
+                        })
+                        .collect_view()
+                }}
+            </div>
+
+            <div class="clock-sessions" role="group" aria-label=move || t(Msg::Sessions)>
+                {MarketSession::all()
+                    .iter()
+                    .map(|session| {
+                        let session = *session;
+                        let state = state.clone();
+                        let is_open = move || session.is_open(now_ms());
+                        let status = move || {
+                            if is_open() {
+                                t(Msg::SessionOpen).to_string()
+                            } else {
+                                let now_ms = now_ms();
+                                format!("{} {}", t(Msg::OpensIn), format_countdown(session.next_open(now_ms) - now_ms))
+                            }
+                        };
+                        let class = move || {
+                            let open = if is_open() { " open" } else { "" };
+                            let shown = if ui.with(|ui| ui.sessions.is_shown(session)) { " marked" } else { "" };
+                            format!("session-chip{}{}", open, shown)
+                        };
+                        view! {
+                            <button
+                                class=class
+                                title=move || format!("{}: {}", t(session.msg()), status())
+                                aria-pressed=move || ui.with(|ui| ui.sessions.is_shown(session)).to_string()
+                                on:click=move |_| state.toggle_session_marker(session)
+                            >
+                                <span class="session-dot" aria-hidden="true"></span>
+                                {move || t(session.msg())}
+                            </button>
+                        }
+                    })
+                    .collect_view()}
+                {move || {
+                    let state = state_for_shading.clone();
+                    shading
+                        .get()
+                        .ranges()
+                        .iter()
+                        .map(|range| {
+                            let hours = range.hours;
+                            let name = range.name();
+                            let status = move || {
+                                let now = now_ms();
+                                if hours.contains(now) {
+                                    t(Msg::SessionOpen).to_string()
+                                } else {
+                                    let opens_in = format_countdown(hours.next_start(now) - now);
+                                    format!("{} {}", t(Msg::OpensIn), opens_in)
+                                }
+                            };
+                            let tooltip = format!("{} ({} UTC)", name, hours);
+                            let class = move || {
+                                let open = if hours.contains(now_ms()) { " open" } else { "" };
+                                let shown = if shaded() { " marked" } else { "" };
+                                format!("session-chip shade-chip{}{}", open, shown)
+                            };
+                            let state = state.clone();
+                            view! {
+                                <button
+                                    class=class
+                                    title=move || format!("{}: {}", tooltip, status())
+                                    aria-pressed=move || shaded().to_string()
+                                    on:click=move |_| state.toggle_overlay(Overlay::Shading)
+                                >
+                                    <span class="session-dot" aria-hidden="true"></span>
+                                    {name}
+                                </button>
+                            }
+                        })
+                        .collect_view()
+                }}
+            </div>
+        </div>
+    }
+}
@@ -1,95 +1,1340 @@
 //! Main dashboard layout component
 
-use dash_charts::{CandlestickChart, DepthChart};
-use dash_state::use_app_state;
+use chrono::{DateTime, Utc};
+use dash_charts::{
+    BaselineLevel, CandlestickChart, CandlestickConfig, ChartBadge, ChartLevel, ChartViewport,
+    DeltaHistogram, DepthChart, DepthChartConfig, IndicatorLine, Legend, LegendItem,
+};
+use dash_core::{
+    colors, DecodeStats, DepthAxis, DepthScale, HealthReport, Locale, MarketSession, Msg,
+    BOOK_PRICE_LEVEL_CHOICES,
+};
+#[cfg(feature = "paper-trading")]
+use dash_core::Role;
+use dash_state::{
+    format_age, use_app_state, AnnounceCadence, AppState, BookTotal, DashEvent, DrawingTool,
+    Feed, FrameRateCap, LastPrice, LayoutMode, MarketComputed, MarketState, MobileTab, Overlay, Panel,
+    StaleThreshold, TapeCollapse, TapeShading, TickerFields, MAX_SHADED_RANGES,
+    MAX_WATERMARK_CHARS,
+};
 use leptos::prelude::*;
 
-use crate::{OrderBook, TickerBar, TradeHistory};
+use crate::{
+    register_commands, sync_fullscreen, track_account, track_alert_delivery, track_alerts,
+    track_candle_cache, track_clock, track_session_log, track_viewport, AccountMenu,
+    AlertDeliverySettings, AlertEditor, ChartToolbar, CommandPalette, DiagnosticsOverlay,
+    FullscreenButton, IntervalSelector, LogPanel, LogToggle, MobileTabBar, NewsFeed, NewsKeywords,
+    NotificationToggle, OrderBook, PriceAnnouncer, SessionClock, SessionLogControls,
+    ShareSnapshot, SlippageCalculator, TickerBar, TradeFlow, TradeHistory, VenueSelector,
+};
+#[cfg(feature = "paper-trading")]
+use crate::{BacktestPanel, PaperOrders, PaperTicket, RiskSummary, TradeJournal};
 
 #[component]
 pub fn Dashboard() -> impl IntoView {
     let state = use_app_state();
-    
-    // Extract signals for charts
-    let candles = state.market.candles;
-    let depth = state.market.depth;
     let connection = state.connection;
+    let ui = state.ui;
+
+    // Below the breakpoint the dashboard shows one panel at a time
+    track_viewport(state.viewport);
+    track_clock(state.clock);
+    track_session_log(&state);
+    #[cfg(feature = "paper-trading")]
+    crate::track_journal(&state);
+    #[cfg(feature = "indicators")]
+    crate::track_indicators(&state);
+    track_alerts(&state);
+    track_alert_delivery(&state);
+    track_account(&state);
+    track_candle_cache(&state.market);
+    register_commands(&state);
+    {
+        // Age trades out of the rolling flow windows between prints; frozen
+        // while the stream is down so the panel keeps its last figures
+        let market = state.market.clone();
+        let now = state.server_now();
+        let stale_since = state.stale_since;
+        Effect::new(move |_| {
+            let now = now.get();
+            if stale_since.get().is_none() {
+                market.expire_trade_windows(now);
+            }
+        });
+    }
+    let layout_mode = state.layout_mode();
+
+    // An expanded panel replaces the layout until it is restored
+    sync_fullscreen(&state);
+    let fullscreen = Memo::new(move |_| ui.with(|ui| ui.fullscreen));
+
+    let main = {
+        let state = state.clone();
+        move || match (fullscreen.get(), layout_mode.get()) {
+            (Some(panel), _) => fullscreen_view(&state, panel).into_any(),
+            (None, LayoutMode::Desktop) => desktop_layout(&state).into_any(),
+            (None, LayoutMode::Mobile) => {
+                let state = state.clone();
+                view! {
+                    <div class="dash-tabs">
+                        <MobileTabBar />
+                        <div class="tab-content">
+                            {move || match ui.with(|ui| ui.mobile_tab) {
+                                MobileTab::Chart => view! {
+                                    <section class="tab-panel dash-center">
+                                        {chart_panel(&state)}
+                                        {depth_panel(&state)}
+                                    </section>
+                                }
+                                .into_any(),
+                                MobileTab::Book => view! {
+                                    <section class="tab-panel">
+                                        {order_book_panel(&state)}
+                                        {impact_panel(&state)}
+                                    </section>
+                                }
+                                .into_any(),
+                                MobileTab::Trades => view! {
+                                    <section class="tab-panel">{trades_panel(&state)}</section>
+                                }
+                                .into_any(),
+                            }}
+                        </div>
+                    </div>
+                }
+                .into_any()
+            }
+        }
+    };
 
     view! {
-        <div class="dashboard">
+        <div class=move || {
+            let expanded = if fullscreen.get().is_some() { " has-fullscreen" } else { "" };
+            format!("dashboard {}{}", layout_mode.get().css_class(), expanded)
+        }>
             <header class="dash-header">
                 <TickerBar
                     market=state.market.clone()
                     connection=connection
+                    now_ms=state.server_now()
+                    book_prices=Signal::derive(move || ui.with(|ui| ui.book_prices))
                 />
+                <SessionClock />
+                <AccountMenu />
             </header>
 
-            <main class="dash-main">
-                <aside class="dash-sidebar left">
-                    <div class="panel">
-                        <div class="panel-header">
-                            <span class="panel-title">"Order Book"</span>
-                        </div>
-                        <div class="panel-content">
-                            <OrderBook market=state.market.clone() />
-                        </div>
-                    </div>
-                </aside>
+            <main class="dash-main">{main}</main>
 
-                <section class="dash-center">
-                    <div class="panel chart-container">
-                        <div class="panel-header">
-                            <span class="panel-title">"Chart"</span>
-                        </div>
-                        <div class="panel-content">
-                            <CandlestickChart candles=candles />
-                        </div>
-                    </div>
+            <footer class="dash-footer">
+                <StatusBar />
+            </footer>
 
-                    <div class="panel depth-container">
-                        <div class="panel-header">
-                            <span class="panel-title">"Market Depth"</span>
-                        </div>
-                        <div class="panel-content">
-                            <DepthChart depth=depth />
-                        </div>
-                    </div>
-                </section>
+            <PriceAnnouncer />
+            <CommandPalette />
+            <LogPanel />
+            <DiagnosticsOverlay />
+        </div>
+    }
+}
 
-                <aside class="dash-sidebar right">
-                    <div class="panel">
-                        <div class="panel-header">
-                            <span class="panel-title">"Recent Trades"</span>
-                        </div>
-                        <div class="panel-content">
-                            <TradeHistory market=state.market.clone() />
-                        </div>
+/// Book and impact on the left, charts in the middle, trades on the right
+fn desktop_layout(state: &AppState) -> impl IntoView + use<> {
+    view! {
+        <aside class="dash-sidebar left">
+            {order_book_panel(state)}
+            {impact_panel(state)}
+        </aside>
+
+        <section class="dash-center">
+            {chart_panel(state)}
+            {depth_panel(state)}
+        </section>
+
+        <aside class="dash-sidebar right">
+            {trades_panel(state)}
+            {trading_panels(state)}
+            {alerts_panel(state)}
+            {news_panel(state)}
+            {custom_panel(state)}
+        </aside>
+    }
+}
+
+fn order_book_panel(state: &AppState) -> impl IntoView + use<> {
+    let locale = state.locale();
+    let ui = state.ui;
+
+    // Optional red/green wash following the smoothed imbalance
+    let computed = MarketComputed::new(&state.market);
+    let tinted = Memo::new(move |_| ui.with(|ui| ui.imbalance_tint));
+    let background = move || {
+        tinted.get().then(|| {
+            let gradient = colors::imbalance_gradient(computed.imbalance_ewma.get());
+            format!("background: {}, var(--bg-panel)", gradient)
+        })
+    };
+    let toggle_tint = {
+        let state = state.clone();
+        move |_| state.toggle_imbalance_tint()
+    };
+    let total_mode = Signal::derive(move || ui.with(|ui| ui.book_total));
+    let cumulative = move || total_mode.get() == BookTotal::Cumulative;
+    let toggle_total = {
+        let state = state.clone();
+        move |_| state.toggle_book_total()
+    };
+    let anchored = Signal::derive(move || ui.with(|ui| ui.book_anchored));
+    let toggle_anchor = {
+        let state = state.clone();
+        move |_| state.toggle_book_anchor()
+    };
+
+    view! {
+        <div
+            class="panel"
+            class:stale=stale(state)
+            role="region"
+            aria-label=move || locale.get().text(Msg::OrderBook)
+            style=background
+        >
+            <div class="panel-header">
+                <span class="panel-title">{move || locale.get().text(Msg::OrderBook)}</span>
+                {age_badge(state, Feed::OrderBook)}
+                <VenueSelector market=state.market.clone() />
+                <button
+                    class=move || if tinted.get() { "tint-btn active" } else { "tint-btn" }
+                    title=move || locale.get().text(Msg::ImbalanceTint)
+                    aria-label=move || locale.get().text(Msg::ImbalanceTint)
+                    aria-pressed=move || tinted.get().to_string()
+                    on:click=toggle_tint
+                >
+                    "◐"
+                </button>
+                <button
+                    class=move || if cumulative() { "tint-btn active" } else { "tint-btn" }
+                    title=move || locale.get().text(Msg::CumulativeTotals)
+                    aria-label=move || locale.get().text(Msg::CumulativeTotals)
+                    aria-pressed=move || cumulative().to_string()
+                    on:click=toggle_total
+                >
+                    "Σ"
+                </button>
+                <button
+                    class=move || if anchored.get() { "tint-btn active" } else { "tint-btn" }
+                    title=move || locale.get().text(Msg::AnchorBook)
+                    aria-label=move || locale.get().text(Msg::AnchorBook)
+                    aria-pressed=move || anchored.get().to_string()
+                    on:click=toggle_anchor
+                >
+                    "⇕"
+                </button>
+                <FullscreenButton panel=Panel::OrderBook />
+            </div>
+            <div class="panel-content">
+                <OrderBook
+                    market=state.market.clone()
+                    total_mode=total_mode
+                    anchored=anchored
+                />
+            </div>
+            {stale_overlay(state)}
+        </div>
+    }
+}
+
+fn impact_panel(state: &AppState) -> impl IntoView + use<> {
+    let locale = state.locale();
+
+    view! {
+        <div
+            class="panel"
+            class:stale=stale(state)
+            role="region"
+            aria-label=move || locale.get().text(Msg::MarketImpact)
+        >
+            <div class="panel-header">
+                <span class="panel-title">{move || locale.get().text(Msg::MarketImpact)}</span>
+            </div>
+            <div class="panel-content">
+                <SlippageCalculator market=state.market.clone() />
+            </div>
+            {stale_overlay(state)}
+        </div>
+    }
+}
+
+fn chart_panel(state: &AppState) -> impl IntoView + use<> {
+    let locale = state.locale();
+
+    view! {
+        {replay_controls(state.market.clone())}
+
+        <div
+            class="panel chart-container"
+            class:stale=stale(state)
+            role="region"
+            aria-label=move || locale.get().text(Msg::Chart)
+        >
+            <div class="panel-header">
+                <span class="panel-title">{move || locale.get().text(Msg::Chart)}</span>
+                {age_badge(state, Feed::Candles)}
+                <IntervalSelector market=state.market.clone() />
+                {indicator_menu()}
+                <ShareSnapshot />
+                <FullscreenButton panel=Panel::CandleChart />
+            </div>
+            <div class="panel-content">
+                {candle_chart(state, false)}
+            </div>
+            {stale_overlay(state)}
+        </div>
+    }
+}
+
+/// Candle chart with the user's overlays and lines; `drawable` routes
+/// clicks to the active drawing tool
+fn candle_chart(state: &AppState, drawable: bool) -> impl IntoView + use<> {
+    let candles = state.market.candles;
+    let chart_fps = state.chart_fps();
+    let ui = state.ui;
+    let drawings = state.drawings;
+    let overlays = Memo::new(move |_| ui.with(|ui| ui.overlays));
+    let chart_type = Memo::new(move |_| ui.with(|ui| ui.chart_type));
+    let volume_mode = Memo::new(move |_| ui.with(|ui| ui.volume_mode));
+    let baseline = Memo::new(move |_| drawings.with(|d| d.baseline));
+    let price_lines = Signal::derive(move || drawings.with(|d| d.price_lines.clone()));
+    let sessions = Signal::derive(move || ui.with(|ui| ui.sessions.shown()));
+    let calendar = state.calendar;
+    let scheduled = Signal::derive(move || {
+        if overlays.with(|o| o.events) { calendar.get() } else { Vec::new() }
+    });
+    let computed = MarketComputed::new(&state.market);
+    let paper = paper_overlays(state, computed.last_price);
+    let indicators = chart_indicators(&state.market);
+    let shading = state.shading;
+    let shaded = Signal::derive(move || {
+        if overlays.with(|o| o.shading) { shading.with(|s| s.hours()) } else { Vec::new() }
+    });
+    let now_ms = state.server_now();
+    let fields = TickerFields::new(&state.market);
+    let legend = chart_legend(state);
+
+    // Zoom window shared by the price and delta panes; survives chart
+    // option changes, and a new market follows its newest candle with the
+    // price axis auto-scaled
+    let viewport = RwSignal::new(ChartViewport::default());
+    let (symbol, interval) = (state.market.symbol, state.market.interval);
+    Effect::new(move |_| {
+        symbol.track();
+        interval.track();
+        viewport.update(|v| {
+            v.go_to_latest();
+            v.auto_price();
+        });
+    });
+
+    // Let other panels follow the time range on screen
+    let shown_range = Memo::new(move |_| {
+        let window = viewport.get();
+        candles.with(|history| {
+            let candles = history.candles.as_slice();
+            let shown = candles.get(window.window(candles))?;
+            Some((shown.first()?.timestamp, shown.last()?.timestamp))
+        })
+    });
+    let events = state.events.clone();
+    Effect::new(move |_| {
+        if let Some((from_ms, to_ms)) = shown_range.get() {
+            events.publish(DashEvent::ChartRangeChanged { from_ms, to_ms });
+        }
+    });
+
+    let chart = move || {
+        let overlays = overlays.get();
+        let last_price = overlays.last_price.then(|| {
+            Signal::derive(move || computed.last_price.get().map(|p| (p.price, p.direction.color())))
+        });
+        let range_24h = overlays.high_low.then(|| {
+            Signal::derive(move || fields.low_24h.get().zip(fields.high_24h.get()))
+        });
+        let config = CandlestickConfig {
+            chart_type: chart_type.get(),
+            baseline: baseline.get().map_or(BaselineLevel::SessionOpen, BaselineLevel::Price),
+            show_volume: overlays.volume,
+            volume_mode: volume_mode.get(),
+            show_grid: overlays.grid,
+            show_extremes: overlays.high_low,
+            ..Default::default()
+        };
+        let on_price_click = Callback::new(move |price| {
+            if drawable {
+                drawings.maybe_update(|d| d.click(price));
+            }
+        });
+        let measure = Signal::derive(move || {
+            drawable && drawings.with(|d| d.tool == DrawingTool::Measure)
+        });
+        view! {
+            <CandlestickChart
+                candles=candles
+                config=config
+                max_fps=chart_fps
+                price_lines=price_lines
+                on_price_click=on_price_click
+                sessions=sessions
+                events=scheduled
+                shading=shaded
+                levels=paper.levels
+                on_level_drag=paper.on_level_drag
+                now_ms=now_ms
+                last_price=last_price.unwrap_or_else(|| Signal::stored(None))
+                badge=paper.badge
+                indicators=indicators
+                range_24h=range_24h.unwrap_or_else(|| Signal::stored(None))
+                viewport=viewport
+                measure=measure
+            />
+        }
+    };
+
+    // Delta pane under the price chart, sharing its candle layout
+    let delta = move || {
+        overlays.with(|o| o.delta).then(|| {
+            let cumulative = Signal::derive(move || overlays.with(|o| o.cumulative_delta));
+            view! {
+                <div class="delta-pane">
+                    <DeltaHistogram
+                        candles=candles
+                        cumulative=cumulative
+                        max_fps=chart_fps
+                        viewport=viewport
+                    />
+                </div>
+            }
+        })
+    };
+
+    view! {
+        {legend}
+        {chart}
+        {delta}
+    }
+}
+
+/// Candle chart overlays and session markers; clicking one shows or hides it,
+/// and hidden layers are not computed
+fn chart_legend(state: &AppState) -> impl IntoView + use<> {
+    let ui = state.ui;
+    let locale = state.locale();
+    let items = Signal::derive(move || {
+        let locale = locale.get();
+        ui.with(|ui| {
+            let overlays = Overlay::all().iter().map(|overlay| {
+                LegendItem::new(overlay.id(), locale.text(overlay.msg()), overlay.color())
+                    .visible(ui.overlays.is_shown(*overlay))
+            });
+            let sessions = MarketSession::all().iter().map(|session| {
+                LegendItem::new(session.code(), locale.text(session.msg()), colors::TEXT_MUTED)
+                    .visible(ui.sessions.is_shown(*session))
+            });
+            overlays.chain(sessions).collect()
+        })
+    });
+    let on_toggle = {
+        let state = state.clone();
+        Callback::new(move |id: &'static str| {
+            if let Some(overlay) = Overlay::all().iter().find(|o| o.id() == id) {
+                state.toggle_overlay(*overlay);
+            } else if let Some(session) = MarketSession::all().iter().find(|s| s.code() == id) {
+                state.toggle_session_marker(*session);
+            }
+        })
+    };
+
+    view! {
+        <Legend
+            items=items
+            on_toggle=on_toggle
+            label=Signal::derive(move || locale.get().text(Msg::Legend).to_string())
+        />
+    }
+}
+
+/// A single panel filling the screen; the chart gets the extended toolbar
+fn fullscreen_view(state: &AppState, panel: Panel) -> impl IntoView + use<> {
+    let content = match panel {
+        Panel::CandleChart => {
+            let locale = state.locale();
+            let drawing = state.drawings;
+            let class = move || {
+                let tool = drawing.with(|d| d.tool != DrawingTool::None);
+                if tool { "panel chart-container drawing" } else { "panel chart-container" }
+            };
+            view! {
+                <div
+                    class=class
+                    class:stale=stale(state)
+                    role="region"
+                    aria-label=move || locale.get().text(Msg::Chart)
+                >
+                    <div class="panel-header">
+                        <span class="panel-title">{move || locale.get().text(Msg::Chart)}</span>
+                        {age_badge(state, Feed::Candles)}
+                        <ChartToolbar />
+                        <ShareSnapshot />
+                    </div>
+                    <div class="panel-content">
+                        {candle_chart(state, true)}
                     </div>
-                </aside>
-            </main>
+                    {stale_overlay(state)}
+                </div>
+            }
+            .into_any()
+        }
+        Panel::DepthChart => depth_panel(state).into_any(),
+        Panel::OrderBook => order_book_panel(state).into_any(),
+        Panel::Trades => trades_panel(state).into_any(),
+    };
 
-            <footer class="dash-footer">
-                <StatusBar />
-            </footer>
+    view! { <section class="fullscreen-panel">{content}</section> }
+}
+
+fn depth_panel(state: &AppState) -> impl IntoView + use<> {
+    let depth = state.market.depth;
+    let walls = state.market.walls;
+    let chart_fps = state.chart_fps();
+    let locale = state.locale();
+    let ui = state.ui;
+    let axis = Memo::new(move |_| ui.with(|ui| ui.depth_axis));
+    let notional = move || axis.get() == DepthAxis::Notional;
+    let toggle_axis = {
+        let state = state.clone();
+        move |_| state.toggle_depth_axis()
+    };
+    let scale = Memo::new(move |_| ui.with(|ui| ui.depth_scale));
+    let per_side = move || scale.get() == DepthScale::PerSide;
+    let toggle_scale = {
+        let state = state.clone();
+        move |_| state.toggle_depth_scale()
+    };
+
+    // Rebuilt when the axis or scale changes
+    let chart = move || {
+        let config = DepthChartConfig {
+            show_walls: true,
+            axis: axis.get(),
+            scale: scale.get(),
+            ..Default::default()
+        };
+        view! {
+            <DepthChart
+                depth=depth
+                config=config
+                walls=Signal::derive(move || walls.with(|w| w.walls().to_vec()))
+                max_fps=chart_fps
+            />
+        }
+    };
+
+    view! {
+        <div
+            class="panel depth-container"
+            class:stale=stale(state)
+            role="region"
+            aria-label=move || locale.get().text(Msg::MarketDepth)
+        >
+            <div class="panel-header">
+                <span class="panel-title">{move || locale.get().text(Msg::MarketDepth)}</span>
+                {age_badge(state, Feed::OrderBook)}
+                <button
+                    class=move || if notional() { "tint-btn active" } else { "tint-btn" }
+                    title=move || locale.get().text(Msg::NotionalDepth)
+                    aria-label=move || locale.get().text(Msg::NotionalDepth)
+                    aria-pressed=move || notional().to_string()
+                    on:click=toggle_axis
+                >
+                    "$"
+                </button>
+                <button
+                    class=move || if per_side() { "tint-btn active" } else { "tint-btn" }
+                    title=move || locale.get().text(Msg::DepthPerSide)
+                    aria-label=move || locale.get().text(Msg::DepthPerSide)
+                    aria-pressed=move || per_side().to_string()
+                    on:click=toggle_scale
+                >
+                    "%"
+                </button>
+                <FullscreenButton panel=Panel::DepthChart />
+            </div>
+            <div class="panel-content">{chart}</div>
+            {stale_overlay(state)}
+        </div>
+    }
+}
+
+fn trades_panel(state: &AppState) -> impl IntoView + use<> {
+    let locale = state.locale();
+    let ui = state.ui;
+    let running = Signal::derive(move || ui.with(|ui| ui.tape_volume));
+    let shading = Signal::derive(move || ui.with(|ui| ui.tape_shading));
+    let collapse = Signal::derive(move || ui.with(|ui| ui.tape_collapse));
+    let toggle_running = {
+        let state = state.clone();
+        move |_| state.toggle_tape_volume()
+    };
+
+    view! {
+        <div
+            class="panel"
+            class:stale=stale(state)
+            role="region"
+            aria-label=move || locale.get().text(Msg::RecentTrades)
+        >
+            <div class="panel-header">
+                <span class="panel-title">{move || locale.get().text(Msg::RecentTrades)}</span>
+                {age_badge(state, Feed::Trades)}
+                <button
+                    class=move || if running.get() { "tint-btn active" } else { "tint-btn" }
+                    title=move || locale.get().text(Msg::RunningVolume)
+                    aria-label=move || locale.get().text(Msg::RunningVolume)
+                    aria-pressed=move || running.get().to_string()
+                    on:click=toggle_running
+                >
+                    "Σ"
+                </button>
+                <FullscreenButton panel=Panel::Trades />
+            </div>
+            <div class="panel-content">
+                <TradeFlow computed=MarketComputed::new(&state.market) />
+                <TradeHistory
+                    market=state.market.clone()
+                    shading=shading
+                    running_volume=running
+                    collapse=collapse
+                />
+            </div>
+            {stale_overlay(state)}
+        </div>
+    }
+}
+
+/// Paper orders and the position, drawn on the price chart
+struct PaperOverlays {
+    levels: Signal<Vec<ChartLevel>>,
+    badge: Signal<Option<ChartBadge>>,
+    /// Moves the dragged order
+    on_level_drag: Callback<(u64, f64)>,
+}
+
+/// Paper overlays, compiled in with the `paper-trading` feature; viewers
+/// get none
+#[cfg(feature = "paper-trading")]
+fn paper_overlays(state: &AppState, last_price: Memo<Option<LastPrice>>) -> PaperOverlays {
+    let paper = state.paper;
+    let symbol = state.market.symbol;
+    let locale = state.locale();
+    let role = state.role;
+    let levels = Signal::derive(move || {
+        if !role.with(Role::is_operator) {
+            return Vec::new();
+        }
+        let locale = locale.get();
+        paper.with(|engine| symbol.with(|s| crate::paper_levels(engine, s, locale)))
+    });
+    let badge = Signal::derive(move || {
+        if !role.with(Role::is_operator) {
+            return None;
+        }
+        let last = last_price.get()?.price;
+        let locale = locale.get();
+        paper.with(|engine| symbol.with(|s| crate::position_badge(engine, s, last, locale)))
+    });
+    let on_level_drag = {
+        let state = state.clone();
+        Callback::new(move |(id, price): (u64, f64)| {
+            let _ = state.move_paper_order(id, price);
+        })
+    };
+    PaperOverlays { levels, badge, on_level_drag }
+}
+
+#[cfg(not(feature = "paper-trading"))]
+fn paper_overlays(_state: &AppState, _last_price: Memo<Option<LastPrice>>) -> PaperOverlays {
+    PaperOverlays {
+        levels: Signal::stored(Vec::new()),
+        badge: Signal::stored(None),
+        on_level_drag: Callback::new(|_| {}),
+    }
+}
+
+/// Paper trading with its journal and risk, and backtests, compiled in with
+/// the `paper-trading` feature; operators only
+#[cfg(feature = "paper-trading")]
+fn trading_panels(state: &AppState) -> impl IntoView + use<> {
+    let state = state.clone();
+    let role = state.role;
+    view! {
+        <Show when=move || role.with(Role::is_operator)>
+            {paper_panel(&state)}
+            {journal_panel(&state)}
+            {risk_panel(&state)}
+            {backtest_panel(&state)}
+        </Show>
+    }
+}
+
+/// Simulated orders for the current pair
+#[cfg(feature = "paper-trading")]
+fn paper_panel(state: &AppState) -> impl IntoView + use<> {
+    let locale = state.locale();
+    view! {
+        <div
+            class="panel paper-panel"
+            role="region"
+            aria-label=move || locale.get().text(Msg::PaperTrading)
+        >
+            <div class="panel-header">
+                <span class="panel-title">{move || locale.get().text(Msg::PaperTrading)}</span>
+            </div>
+            <div class="panel-content">
+                <PaperTicket />
+                <PaperOrders />
+            </div>
+        </div>
+    }
+}
+
+#[cfg(feature = "paper-trading")]
+fn journal_panel(state: &AppState) -> impl IntoView + use<> {
+    let locale = state.locale();
+    view! {
+        <div
+            class="panel journal-panel"
+            role="region"
+            aria-label=move || locale.get().text(Msg::Journal)
+        >
+            <div class="panel-header">
+                <span class="panel-title">{move || locale.get().text(Msg::Journal)}</span>
+            </div>
+            <div class="panel-content">
+                <TradeJournal />
+            </div>
+        </div>
+    }
+}
+
+#[cfg(feature = "paper-trading")]
+fn risk_panel(state: &AppState) -> impl IntoView + use<> {
+    let locale = state.locale();
+    view! {
+        <div
+            class="panel risk-panel"
+            role="region"
+            aria-label=move || locale.get().text(Msg::Risk)
+        >
+            <div class="panel-header">
+                <span class="panel-title">{move || locale.get().text(Msg::Risk)}</span>
+            </div>
+            <div class="panel-content">
+                <RiskSummary />
+            </div>
+        </div>
+    }
+}
+
+#[cfg(feature = "paper-trading")]
+fn backtest_panel(state: &AppState) -> impl IntoView + use<> {
+    let locale = state.locale();
+    view! {
+        <div
+            class="panel backtest-panel"
+            role="region"
+            aria-label=move || locale.get().text(Msg::Backtest)
+        >
+            <div class="panel-header">
+                <span class="panel-title">{move || locale.get().text(Msg::Backtest)}</span>
+            </div>
+            <div class="panel-content">
+                <BacktestPanel />
+            </div>
         </div>
     }
 }
 
+#[cfg(not(feature = "paper-trading"))]
+fn trading_panels(_state: &AppState) -> impl IntoView + use<> {}
+
+fn alerts_panel(state: &AppState) -> impl IntoView + use<> {
+    let locale = state.locale();
+    let show_settings = RwSignal::new(false);
+    view! {
+        <div
+            class="panel alerts-panel"
+            role="region"
+            aria-label=move || locale.get().text(Msg::Alerts)
+        >
+            <div class="panel-header">
+                <span class="panel-title">{move || locale.get().text(Msg::Alerts)}</span>
+                <NotificationToggle />
+                <button
+                    class=move || if show_settings.get() { "tint-btn active" } else { "tint-btn" }
+                    title=move || locale.get().text(Msg::PanelSettings)
+                    aria-label=move || locale.get().text(Msg::PanelSettings)
+                    aria-pressed=move || show_settings.get().to_string()
+                    on:click=move |_| show_settings.update(|s| *s = !*s)
+                >
+                    "⚙"
+                </button>
+            </div>
+            <div class="panel-settings" class:hidden=move || !show_settings.get()>
+                <AlertDeliverySettings />
+            </div>
+            <div class="panel-content">
+                <AlertEditor />
+            </div>
+        </div>
+    }
+}
+
+fn news_panel(state: &AppState) -> impl IntoView + use<> {
+    let locale = state.locale();
+    let ui = state.ui;
+    let pair_only = Signal::derive(move || ui.with(|ui| ui.news_pair_only));
+    let toggle_pair_only = {
+        let state = state.clone();
+        move |_| state.toggle_news_pair_only()
+    };
+    let show_settings = RwSignal::new(false);
+
+    view! {
+        <div
+            class="panel news-panel"
+            role="region"
+            aria-label=move || locale.get().text(Msg::News)
+        >
+            <div class="panel-header">
+                <span class="panel-title">{move || locale.get().text(Msg::News)}</span>
+                <button
+                    class=move || if pair_only.get() { "tint-btn active" } else { "tint-btn" }
+                    title=move || locale.get().text(Msg::NewsPairOnly)
+                    aria-label=move || locale.get().text(Msg::NewsPairOnly)
+                    aria-pressed=move || pair_only.get().to_string()
+                    on:click=toggle_pair_only
+                >
+                    "◎"
+                </button>
+                <button
+                    class=move || if show_settings.get() { "tint-btn active" } else { "tint-btn" }
+                    title=move || locale.get().text(Msg::PanelSettings)
+                    aria-label=move || locale.get().text(Msg::PanelSettings)
+                    aria-pressed=move || show_settings.get().to_string()
+                    on:click=move |_| show_settings.update(|s| *s = !*s)
+                >
+                    "⚙"
+                </button>
+            </div>
+            <div class="panel-settings" class:hidden=move || !show_settings.get()>
+                <NewsKeywords />
+            </div>
+            <div class="panel-content">
+                <NewsFeed pair_only=pair_only />
+            </div>
+        </div>
+    }
+}
+
+/// The registered custom panel picked in the status bar, if any
+fn custom_panel(state: &AppState) -> impl IntoView + use<> {
+    let state = state.clone();
+    let locale = state.locale();
+    let picked = Memo::new(move |_| state.ui.with(|ui| ui.custom_panel));
+    move || {
+        let panel = state.custom_panels.get(picked.get()?)?;
+        let settings = panel.settings(&state);
+        let has_settings = settings.is_some();
+        let show_settings = RwSignal::new(false);
+        let title = panel.title();
+        let label = title.clone();
+        Some(view! {
+            <div class="panel custom-panel" role="region" aria-label=label>
+                <div class="panel-header">
+                    <span class="panel-title">{title}</span>
+                    <Show when=move || has_settings>
+                        <button
+                            class=move || {
+                                if show_settings.get() { "tint-btn active" } else { "tint-btn" }
+                            }
+                            title=move || locale.get().text(Msg::PanelSettings)
+                            aria-label=move || locale.get().text(Msg::PanelSettings)
+                            aria-pressed=move || show_settings.get().to_string()
+                            on:click=move |_| show_settings.update(|s| *s = !*s)
+                        >
+                            "⚙"
+                        </button>
+                    </Show>
+                </div>
+                <div class="panel-settings" class:hidden=move || !show_settings.get()>
+                    {settings}
+                </div>
+                <div class="panel-content">{panel.render(&state)}</div>
+            </div>
+        })
+    }
+}
+
+/// "book 4s old" for the feed behind a panel, turning amber then red as the
+/// feed goes quiet past the user's thresholds
+fn age_badge(state: &AppState, feed: Feed) -> impl IntoView + use<> {
+    let market = state.market.clone();
+    let now = state.server_now();
+    let ui = state.ui;
+    let locale = state.locale();
+    move || {
+        market.feed_age(feed, now.get()).map(|age| {
+            let level = ui.with(|ui| ui.stale_threshold).classify(age);
+            let old = locale.get().text(Msg::Old);
+            let text = format!("{} {} {}", feed.label(), format_age(age), old);
+            view! { <span class=format!("age-badge {}", level.css_class())>{text}</span> }
+        })
+    }
+}
+
+/// Whether the panel shows data frozen by a dropped stream
+fn stale(state: &AppState) -> impl Fn() -> bool + Copy + use<> {
+    let stale_since = state.stale_since;
+    move || stale_since.with(Option::is_some)
+}
+
+/// "Offline, data as of HH:MM:SS" over a panel while the stream is down. It
+/// lets clicks through, so zoom, scroll and export keep working underneath.
+fn stale_overlay(state: &AppState) -> impl IntoView + use<> {
+    let stale_since = state.stale_since;
+    let locale = state.locale();
+    move || {
+        stale_since.get().map(|as_of| {
+            let label = locale.get().text(Msg::StaleData);
+            let time = DateTime::from_timestamp_millis(as_of)
+                .filter(|_| as_of > 0)
+                .map(|time| time.format("%H:%M:%S UTC").to_string())
+                .unwrap_or_else(|| "—".to_string());
+            view! { <div class="stale-overlay" role="status">{format!("{} {}", label, time)}</div> }
+        })
+    }
+}
+
+/// Replay scrubber, compiled in with the `recording-playback` feature
+#[cfg(feature = "recording-playback")]
+fn replay_controls(market: MarketState) -> impl IntoView {
+    view! { <crate::ReplayScrubber market=market /> }
+}
+
+#[cfg(not(feature = "recording-playback"))]
+fn replay_controls(_market: MarketState) -> impl IntoView {}
+
+/// Indicator menu, compiled in with the `indicators` feature
+#[cfg(feature = "indicators")]
+pub(crate) fn indicator_menu() -> impl IntoView {
+    view! { <crate::IndicatorMenu /> }
+}
+
+#[cfg(not(feature = "indicators"))]
+pub(crate) fn indicator_menu() -> impl IntoView {}
+
+/// Indicator lines over the price series; none without the `indicators`
+/// feature
+#[cfg(feature = "indicators")]
+fn chart_indicators(market: &MarketState) -> Signal<Vec<IndicatorLine>> {
+    crate::indicator_lines(market)
+}
+
+#[cfg(not(feature = "indicators"))]
+fn chart_indicators(_market: &MarketState) -> Signal<Vec<IndicatorLine>> {
+    Signal::stored(Vec::new())
+}
+
 #[component]
 fn StatusBar() -> impl IntoView {
     let state = use_app_state();
     let connection = state.connection;
     let error = state.error;
+    let stats = state.stats;
+    let decode_stats = state.decode_stats;
+    let ui = state.ui;
+    let server_health = state.server_health;
+    let source_status = state.source_status;
+    let locale = state.locale();
+    let t = move |msg: Msg| locale.get().text(msg);
+
+    let select_fps = {
+        let state = state.clone();
+        move |ev| {
+            let value = event_target_value(&ev);
+            if let Some(cap) = FrameRateCap::all().iter().find(|c| c.label() == value) {
+                state.set_frame_rate(*cap);
+            }
+        }
+    };
+    let select_announce = {
+        let state = state.clone();
+        move |ev| {
+            let value = event_target_value(&ev);
+            if let Some(cadence) = AnnounceCadence::all().iter().find(|c| c.label() == value) {
+                state.set_announce_cadence(*cadence);
+            }
+        }
+    };
+    let select_stale_threshold = {
+        let state = state.clone();
+        move |ev| {
+            let value = event_target_value(&ev);
+            if let Some(threshold) = StaleThreshold::all().iter().find(|t| t.label() == value) {
+                state.set_stale_threshold(*threshold);
+            }
+        }
+    };
+    let select_tape_shading = {
+        let state = state.clone();
+        move |ev| {
+            let value = event_target_value(&ev);
+            if let Some(shading) = TapeShading::all().iter().find(|s| s.label() == value) {
+                state.set_tape_shading(*shading);
+            }
+        }
+    };
+    let select_tape_collapse = {
+        let state = state.clone();
+        move |ev| {
+            let value = event_target_value(&ev);
+            if let Some(collapse) = TapeCollapse::all().iter().find(|c| c.label() == value) {
+                state.set_tape_collapse(*collapse);
+            }
+        }
+    };
+    let custom_panels = state.custom_panels.panels();
+    let select_custom_panel = {
+        let state = state.clone();
+        let ids: Vec<&'static str> = custom_panels.iter().map(|p| p.id()).collect();
+        move |ev| {
+            let value = event_target_value(&ev);
+            state.set_custom_panel(ids.iter().copied().find(|id| *id == value));
+        }
+    };
+    let chart_shading = state.shading;
+    let set_chart_shading = {
+        let state = state.clone();
+        move |ev| state.set_chart_shading(&event_target_value(&ev))
+    };
+    let share = state.share;
+    let set_watermark = {
+        let state = state.clone();
+        move |ev| state.set_watermark(&event_target_value(&ev))
+    };
+    let select_locale = {
+        let state = state.clone();
+        move |ev| {
+            if let Some(locale) = Locale::from_tag(&event_target_value(&ev)) {
+                state.set_locale(locale);
+            }
+        }
+    };
+    let book_price_levels = state.market.book_price_levels;
+    let select_book_levels = move |ev| {
+        if let Ok(levels) = event_target_value(&ev).parse::<usize>() {
+            book_price_levels.set(levels);
+        }
+    };
+    let toggle_book_prices = {
+        let state = state.clone();
+        move |_| state.toggle_book_prices()
+    };
+    let toggle_performance = move |_| state.toggle_performance_mode();
 
     view! {
         <div class="status-bar">
             <div class="sb-connection">
-                <span class="sb-label">"Status:"</span>
+                <span class="sb-label">{move || t(Msg::Status)}</span>
                 <span class=move || format!("sb-value {}", connection.get().css_class())>
-                    {move || connection.get().label()}
+                    {move || t(Msg::connection(connection.get()))}
                 </span>
             </div>
 
+            <div class="sb-feed">
+                <span class="sb-label">{move || t(Msg::Feed)}</span>
+                {move || {
+                    let (class, label, detail) = server_health.with(|report| match report {
+                        Some(r) => (r.status.css_class(), t(Msg::feed(r.status)), feed_detail(r)),
+                        None => ("feed-down", t(Msg::FeedUnknown), "Health endpoint unreachable".to_string()),
+                    });
+                    view! {
+                        <span class=format!("sb-value {}", class) title=detail>{label}</span>
+                    }
+                }}
+            </div>
+
+            <div class="sb-compression" title=move || decode_stats.with(parse_detail)>
+                <span class="sb-label">{move || t(Msg::Compression)}</span>
+                <span class="sb-value">
+                    {move || format!("{:.1}x", stats.get().compression_ratio())}
+                </span>
+                {move || {
+                    let failed = decode_stats.with(|s| s.failed());
+                    (failed > 0).then(|| view! { <span class="sb-parse-errors">"!"{failed}</span> })
+                }}
+            </div>
+
+            <div class="sb-render">
+                <span class="sb-label">{move || t(Msg::Render)}</span>
+                <select
+                    class="sb-select"
+                    aria-label="Chart frame rate"
+                    prop:disabled=move || ui.with(|ui| ui.performance_mode)
+                    on:change=select_fps
+                >
+                    {FrameRateCap::all()
+                        .iter()
+                        .map(|cap| {
+                            let cap = *cap;
+                            view! {
+                                <option
+                                    value=cap.label()
+                                    selected=move || ui.with(|ui| ui.frame_rate == cap)
+                                >
+                                    {cap.label()}
+                                </option>
+                            }
+                        })
+                        .collect_view()}
+                </select>
+                <label class="sb-toggle">
+                    <input
+                        type="checkbox"
+                        prop:checked=move || ui.with(|ui| ui.performance_mode)
+                        on:change=toggle_performance
+                    />
+                    {move || t(Msg::Performance)}
+                </label>
+            </div>
+
+            <div class="sb-book-prices">
+                <label class="sb-toggle">
+                    <input
+                        type="checkbox"
+                        prop:checked=move || ui.with(|ui| ui.book_prices)
+                        on:change=toggle_book_prices
+                    />
+                    {move || t(Msg::BookPrices)}
+                </label>
+                <select
+                    class="sb-select"
+                    aria-label="Book levels weighed"
+                    title="Levels per side weighed by the microprice and depth-weighted mid"
+                    prop:disabled=move || ui.with(|ui| !ui.book_prices)
+                    on:change=select_book_levels
+                >
+                    {BOOK_PRICE_LEVEL_CHOICES
+                        .iter()
+                        .map(|levels| {
+                            let levels = *levels;
+                            view! {
+                                <option
+                                    value=levels.to_string()
+                                    selected=move || book_price_levels.get() == levels
+                                >
+                                    {format!("L{}", levels)}
+                                </option>
+                            }
+                        })
+                        .collect_view()}
+                </select>
+            </div>
+
+            <div class="sb-announce">
+                <label class="sb-label" for="sb-announce-select">{move || t(Msg::Announce)}</label>
+                <select
+                    id="sb-announce-select"
+                    class="sb-select"
+                    title="How often screen readers hear the price"
+                    on:change=select_announce
+                >
+                    {AnnounceCadence::all()
+                        .iter()
+                        .map(|cadence| {
+                            let cadence = *cadence;
+                            view! {
+                                <option
+                                    value=cadence.label()
+                                    selected=move || ui.with(|ui| ui.announce == cadence)
+                                >
+                                    {cadence.label()}
+                                </option>
+                            }
+                        })
+                        .collect_view()}
+                </select>
+            </div>
+
+            <div class="sb-data-age">
+                <label class="sb-label" for="sb-data-age-select">{move || t(Msg::DataAge)}</label>
+                <select
+                    id="sb-data-age-select"
+                    class="sb-select"
+                    title="Panel data ages that turn the badges amber and red"
+                    on:change=select_stale_threshold
+                >
+                    {StaleThreshold::all()
+                        .iter()
+                        .map(|threshold| {
+                            let threshold = *threshold;
+                            view! {
+                                <option
+                                    value=threshold.label()
+                                    selected=move || ui.with(|ui| ui.stale_threshold == threshold)
+                                >
+                                    {threshold.label()}
+                                </option>
+                            }
+                        })
+                        .collect_view()}
+                </select>
+            </div>
+
+            <div class="sb-tape-shading">
+                <label class="sb-label" for="sb-tape-shading-select">
+                    {move || t(Msg::TapeShading)}
+                </label>
+                <select
+                    id="sb-tape-shading-select"
+                    class="sb-select"
+                    title="Shade trade tape rows by value, darker for larger prints"
+                    on:change=select_tape_shading
+                >
+                    {TapeShading::all()
+                        .iter()
+                        .map(|shading| {
+                            let shading = *shading;
+                            view! {
+                                <option
+                                    value=shading.label()
+                                    selected=move || ui.with(|ui| ui.tape_shading == shading)
+                                >
+                                    {shading.label()}
+                                </option>
+                            }
+                        })
+                        .collect_view()}
+                </select>
+            </div>
+
+            <div class="sb-tape-collapse">
+                <label class="sb-label" for="sb-tape-collapse-select">
+                    {move || t(Msg::Collapse)}
+                </label>
+                <select
+                    id="sb-tape-collapse-select"
+                    class="sb-select"
+                    title="Collapse repeat prints at one price and side into one tape row"
+                    on:change=select_tape_collapse
+                >
+                    {TapeCollapse::all()
+                        .iter()
+                        .map(|collapse| {
+                            let collapse = *collapse;
+                            view! {
+                                <option
+                                    value=collapse.label()
+                                    selected=move || ui.with(|ui| ui.tape_collapse == collapse)
+                                >
+                                    {collapse.label()}
+                                </option>
+                            }
+                        })
+                        .collect_view()}
+                </select>
+            </div>
+
+            {(!custom_panels.is_empty()).then(|| view! {
+                <div class="sb-custom-panel">
+                    <label class="sb-label" for="sb-custom-panel-select">
+                        {move || t(Msg::ExtraPanel)}
+                    </label>
+                    <select
+                        id="sb-custom-panel-select"
+                        class="sb-select"
+                        title="Panel from an extension, shown below the trade tape"
+                        on:change=select_custom_panel
+                    >
+                        <option value="" selected=move || ui.with(|ui| ui.custom_panel.is_none())>
+                            {move || t(Msg::NoPanel)}
+                        </option>
+                        {custom_panels
+                            .iter()
+                            .map(|panel| {
+                                let id = panel.id();
+                                view! {
+                                    <option
+                                        value=id
+                                        selected=move || ui.with(|ui| ui.custom_panel == Some(id))
+                                    >
+                                        {panel.title()}
+                                    </option>
+                                }
+                            })
+                            .collect_view()}
+                    </select>
+                </div>
+            })}
+
+            <div class="sb-shading">
+                <label class="sb-label" for="sb-shading-input">
+                    {move || t(Msg::SessionShading)}
+                </label>
+                <input
+                    id="sb-shading-input"
+                    class="sb-input"
+                    type="text"
+                    placeholder="US=Mon-Fri 14:30-21:00, Sat-Sun"
+                    title=format!(
+                        "Time ranges shaded on the chart, in UTC and comma separated (up to {})",
+                        MAX_SHADED_RANGES,
+                    )
+                    prop:value=move || chart_shading.with(|s| s.text())
+                    on:change=set_chart_shading
+                />
+            </div>
+
+            <div class="sb-watermark">
+                <label class="sb-label" for="sb-watermark-input">{move || t(Msg::Watermark)}</label>
+                <input
+                    id="sb-watermark-input"
+                    class="sb-input"
+                    type="text"
+                    maxlength=MAX_WATERMARK_CHARS.to_string()
+                    placeholder="none"
+                    title="Text stamped on shared chart snapshots; leave empty for none"
+                    prop:value=move || share.with(|s| s.watermark().unwrap_or_default().to_string())
+                    on:change=set_watermark
+                />
+            </div>
+
+            <SessionLogControls />
+            <LogToggle />
+
+            {move || {
+                source_status.get().filter(|s| s.is_degraded()).map(|s| {
+                    let label = if s.failover {
+                        format!("{} ({}: {})", t(Msg::DegradedData), t(Msg::Fallback), s.source)
+                    } else {
+                        format!("{} ({})", t(Msg::DegradedData), s.source)
+                    };
+                    view! {
+                        <div class="sb-degraded">
+                            <span class="error-icon">"⚠"</span>
+                            <span>{label}</span>
+                        </div>
+                    }
+                })
+            }}
+
             {move || {
                 error.get().map(|e| {
                     view! {
@@ -101,9 +1346,73 @@ fn StatusBar() -> impl IntoView {
                 })
             }}
 
+            <div class="sb-locale">
+                <select
+                    class="sb-select"
+                    aria-label=move || t(Msg::Language)
+                    title=move || t(Msg::Language)
+                    on:change=select_locale
+                >
+                    {Locale::all()
+                        .iter()
+                        .map(|option| {
+                            let option = *option;
+                            view! {
+                                <option value=option.tag() selected=move || locale.get() == option>
+                                    {option.native_name()}
+                                </option>
+                            }
+                        })
+                        .collect_view()}
+                </select>
+            </div>
+
             <div class="sb-version">
                 <span>"v0.1.0"</span>
             </div>
         </div>
     }
-}
\ No newline at end of file
+}
+
+/// Tooltip listing each adapter channel and its last message age
+/// Per message type decode counters for the status bar tooltip
+fn parse_detail(stats: &DecodeStats) -> String {
+    let now = Utc::now().timestamp_millis();
+    if stats.types.is_empty() {
+        return "No frames decoded".to_string();
+    }
+    stats
+        .types
+        .iter()
+        .map(|(kind, t)| {
+            let mut line = format!(
+                "{}: {} ok, {} repaired, {} failed",
+                kind, t.decoded, t.recovered, t.failed
+            );
+            if t.is_quarantined(now) {
+                line.push_str(&format!(", quarantined ({} dropped)", t.dropped));
+            }
+            if let Some(error) = &t.last_error {
+                line.push_str(&format!("\n  last error: {}", error));
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn feed_detail(report: &HealthReport) -> String {
+    report
+        .adapters
+        .iter()
+        .flat_map(|adapter| {
+            adapter.channels.iter().map(move |c| {
+                let age = c
+                    .last_message_age_ms
+                    .map_or("no data".to_string(), |ms| format!("{:.1}s ago", ms as f64 / 1000.0));
+                format!("{} {}: {}", adapter.name, c.channel.label(), age)
+            })
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
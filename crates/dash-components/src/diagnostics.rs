@@ -0,0 +1,244 @@
+//! Hidden diagnostics overlay for investigating performance in the field,
+//! toggled with Ctrl+Shift+D: signal update rates, frame and dispatch
+//! timings, the WebSocket command queue, buffer lengths and the latest parse
+//! errors. Update rates and frame times are measured only while it is open.
+
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use chrono::{Local, TimeZone};
+use dash_core::Msg;
+use dash_state::{
+    client_log, use_app_state, Timing, TimingWindow, UpdateCounts, MAX_CLIENT_LOG,
+    MAX_PARSE_ERRORS,
+};
+use leptos::prelude::*;
+
+/// How often the open overlay rereads its counters
+const DIAGNOSTICS_REFRESH: Duration = Duration::from_secs(1);
+
+/// High-resolution local time (ms)
+fn now_ms() -> f64 {
+    window().performance().map_or_else(js_sys::Date::now, |p| p.now())
+}
+
+/// Count the runs of an effect tracking `source`, one per update
+fn count_updates(
+    counts: StoredValue<UpdateCounts>,
+    name: &'static str,
+    source: impl Track + 'static,
+) {
+    Effect::new(move |_| {
+        source.track();
+        counts.update_value(|c| c.record(name, now_ms()));
+    });
+}
+
+/// Time every animation frame until `frames` is disposed
+fn watch_frames(frames: StoredValue<TimingWindow>, last: f64) {
+    request_animation_frame(move || {
+        let now = now_ms();
+        if frames.try_update_value(|f| f.record(now - last, now)).is_some() {
+            watch_frames(frames, now);
+        }
+    });
+}
+
+fn row(label: impl IntoView, value: String) -> impl IntoView {
+    view! {
+        <tr>
+            <td>{label}</td>
+            <td class="diag-num">{value}</td>
+        </tr>
+    }
+}
+
+fn timing_row(label: &'static str, timing: Timing) -> impl IntoView {
+    view! {
+        <tr>
+            <td>{label}</td>
+            <td class="diag-num">{timing.count}</td>
+            <td class="diag-num">{format!("{:.2}", timing.avg_ms)}</td>
+            <td class="diag-num">{format!("{:.2}", timing.max_ms)}</td>
+        </tr>
+    }
+}
+
+/// Ctrl+Shift+D listener; the overlay itself is mounted only while open
+#[component]
+pub fn DiagnosticsOverlay() -> impl IntoView {
+    let open = use_app_state().diagnostics_open;
+
+    let handle = window_event_listener(leptos::ev::keydown, move |ev| {
+        if (ev.ctrl_key() || ev.meta_key()) && ev.shift_key() && ev.key().eq_ignore_ascii_case("d")
+        {
+            ev.prevent_default();
+            open.update(|open| *open = !*open);
+        }
+    });
+    on_cleanup(move || handle.remove());
+
+    view! {
+        <Show when=move || open.get()>
+            <DiagnosticsPanel />
+        </Show>
+    }
+}
+
+#[component]
+fn DiagnosticsPanel() -> impl IntoView {
+    let state = use_app_state();
+    let market = &state.market;
+    let locale = state.locale();
+    let t = move |msg: Msg| locale.get().text(msg);
+
+    let counts = StoredValue::new(UpdateCounts::default());
+    count_updates(counts, "frames", state.stats);
+    count_updates(counts, "ticker", market.ticker);
+    count_updates(counts, "bbo", market.bbo);
+    count_updates(counts, "orderbook", market.orderbook);
+    count_updates(counts, "depth", market.depth);
+    count_updates(counts, "trades", market.trades);
+    count_updates(counts, "candles", market.candles);
+    count_updates(counts, "book_history", market.book_history);
+
+    let frames = StoredValue::new(TimingWindow::new(now_ms()));
+    watch_frames(frames, now_ms());
+
+    // Everything below is reread on this tick rather than tracked
+    let tick = RwSignal::new(0u64);
+    let timer = set_interval_with_handle(move || tick.update(|n| *n += 1), DIAGNOSTICS_REFRESH);
+    let timer = timer.ok();
+    on_cleanup(move || {
+        if let Some(handle) = timer {
+            handle.clear();
+        }
+    });
+
+    let updates = move || {
+        tick.track();
+        let rates = counts.try_update_value(|c| c.report(now_ms())).unwrap_or_default();
+        rates
+            .into_iter()
+            .map(|(name, rate)| row(name, format!("{:.1}", rate)))
+            .collect_view()
+    };
+
+    let diagnostics = state.diagnostics;
+    let timings = move || {
+        tick.track();
+        let now = now_ms();
+        let frame = frames.try_update_value(|f| f.report(now)).unwrap_or_default();
+        let dispatch = diagnostics.try_update_value(|d| d.dispatch.report(now)).unwrap_or_default();
+        view! {
+            {timing_row("frame", frame)}
+            {timing_row("dispatch", dispatch)}
+        }
+    };
+
+    let ws_queue = state.ws_queue.clone();
+    let (trades, candles, orderbook) = (market.trades, market.candles, market.orderbook);
+    let (book_history, session_log) = (market.book_history, state.session_log);
+    let buffers = move || {
+        tick.track();
+        let book = orderbook.with_untracked(|b| {
+            b.as_ref().map(|b| b.bids.len() + b.asks.len()).unwrap_or_default()
+        });
+        let log_lines = client_log().len();
+        view! {
+            {row(t(Msg::QueuedCommands), ws_queue.load(Ordering::Relaxed).to_string())}
+            {row("trades", trades.with_untracked(|b| format!("{}/{}", b.len(), b.capacity())))}
+            {row("candles", candles.with_untracked(|c| c.len().to_string()))}
+            {row("book levels", book.to_string())}
+            {row(
+                "book gaps",
+                diagnostics.with_value(|d| {
+                    let b = d.books;
+                    format!("{} · stale {} · resyncs {}", b.gaps, b.stale, b.resyncs)
+                }),
+            )}
+            {row(
+                "book_history",
+                book_history.with_untracked(|h| format!("{}/{}", h.len(), h.capacity())),
+            )}
+            {row("client log", format!("{}/{}", log_lines, MAX_CLIENT_LOG))}
+            {row("session log", session_log.with_untracked(|l| l.len().to_string()))}
+        }
+    };
+
+    let decode_stats = state.decode_stats;
+    let parse_errors = move || {
+        tick.track();
+        let errors: Vec<_> =
+            diagnostics.with_value(|d| d.parse_errors().cloned().collect::<Vec<_>>());
+        if errors.is_empty() {
+            return view! { <li class="diag-empty">{t(Msg::NoParseErrors)}</li> }.into_any();
+        }
+        errors
+            .into_iter()
+            .map(|error| {
+                let time = Local
+                    .timestamp_millis_opt(error.timestamp)
+                    .single()
+                    .map(|t| t.format("%H:%M:%S").to_string())
+                    .unwrap_or_default();
+                view! {
+                    <li>
+                        <span class="diag-muted">{time}</span>
+                        " "
+                        <span class="diag-kind">{error.kind}</span>
+                        " "
+                        {error.error}
+                    </li>
+                }
+            })
+            .collect_view()
+            .into_any()
+    };
+    let decode_totals = move || {
+        tick.track();
+        decode_stats.with_untracked(|s| {
+            let (failed, recovered, dropped) = (s.failed(), s.recovered(), s.dropped());
+            format!("failed {} · recovered {} · dropped {}", failed, recovered, dropped)
+        })
+    };
+
+    view! {
+        <section class="diag-overlay" aria-label=move || t(Msg::Diagnostics)>
+            <div class="panel-header">
+                <span class="panel-title">{move || t(Msg::Diagnostics)}</span>
+                <span class="diag-muted">"Ctrl+Shift+D"</span>
+            </div>
+            <div class="diag-grid">
+                <table class="diag-table">
+                    <caption>{move || t(Msg::SignalUpdates)}" / s"</caption>
+                    <tbody>{updates}</tbody>
+                </table>
+                <table class="diag-table">
+                    <caption>{move || t(Msg::Timings)}</caption>
+                    <thead>
+                        <tr>
+                            <th></th>
+                            <th class="diag-num">"/ s"</th>
+                            <th class="diag-num">"avg ms"</th>
+                            <th class="diag-num">"max ms"</th>
+                        </tr>
+                    </thead>
+                    <tbody>{timings}</tbody>
+                </table>
+                <table class="diag-table">
+                    <caption>{move || t(Msg::Buffers)}</caption>
+                    <tbody>{buffers}</tbody>
+                </table>
+            </div>
+            <div class="diag-errors">
+                <div class="diag-caption">
+                    {move || t(Msg::ParseErrors)}
+                    {format!(" (≤ {}) ", MAX_PARSE_ERRORS)}
+                    <span class="diag-muted">{decode_totals}</span>
+                </div>
+                <ol class="diag-error-list">{parse_errors}</ol>
+            </div>
+        </section>
+    }
+}
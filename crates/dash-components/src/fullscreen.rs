@@ -0,0 +1,228 @@
+//! Fullscreen panels: Fullscreen API wrapper, toggle button and the
+//! extended chart toolbar shown while the chart is expanded
+
+use dash_core::{ChartType, Msg, VolumeMode};
+use dash_state::{use_app_state, use_locale, AppState, DrawingTool, Overlay, Panel};
+use leptos::prelude::*;
+
+use crate::dashboard::indicator_menu;
+use crate::IntervalSelector;
+
+// ============================================================================
+// FULLSCREEN API
+// ============================================================================
+
+/// Whether the browser is currently showing an element fullscreen
+pub fn is_browser_fullscreen() -> bool {
+    document().fullscreen_element().is_some()
+}
+
+/// Ask the browser to show the whole page fullscreen. Refusals (no user
+/// gesture, iframe without permission) are logged and the panel stays
+/// expanded inside the window instead.
+pub fn request_browser_fullscreen() {
+    if is_browser_fullscreen() {
+        return;
+    }
+    if let Some(root) = document().document_element()
+        && let Err(err) = root.request_fullscreen()
+    {
+        tracing::debug!("fullscreen request refused: {:?}", err);
+    }
+}
+
+/// Leave browser fullscreen if it is active
+pub fn exit_browser_fullscreen() {
+    if is_browser_fullscreen() {
+        document().exit_fullscreen();
+    }
+}
+
+/// Keep browser fullscreen in step with `UiState::fullscreen`, in both
+/// directions: expanding a panel enters it, and leaving it from the browser
+/// (Esc) restores the regular layout.
+pub fn sync_fullscreen(state: &AppState) {
+    let ui = state.ui;
+    Effect::new(move |_| {
+        if ui.with(|ui| ui.fullscreen.is_some()) {
+            request_browser_fullscreen();
+        } else {
+            exit_browser_fullscreen();
+        }
+    });
+
+    let on_change = {
+        let state = state.clone();
+        window_event_listener(leptos::ev::fullscreenchange, move |_| {
+            if !is_browser_fullscreen() {
+                state.exit_fullscreen();
+            }
+        })
+    };
+    // Esc for panels expanded without browser fullscreen
+    let on_key = {
+        let state = state.clone();
+        window_event_listener(leptos::ev::keydown, move |ev| {
+            if ev.key() == "Escape" {
+                state.exit_fullscreen();
+            }
+        })
+    };
+    on_cleanup(move || {
+        on_change.remove();
+        on_key.remove();
+    });
+}
+
+// ============================================================================
+// COMPONENTS
+// ============================================================================
+
+/// Expand / restore button for a panel header
+#[component]
+pub fn FullscreenButton(panel: Panel) -> impl IntoView {
+    let state = use_app_state();
+    let ui = state.ui;
+    let locale = use_locale();
+    let active = move || ui.with(|ui| ui.fullscreen == Some(panel));
+    let label = move || {
+        let locale = locale.get();
+        if active() {
+            locale.text(Msg::ExitFullscreen).to_string()
+        } else {
+            format!("{}: {}", locale.text(Msg::Expand), locale.text(panel.msg()))
+        }
+    };
+
+    view! {
+        <button
+            class="fullscreen-btn"
+            title=label
+            aria-label=label
+            aria-pressed=move || active().to_string()
+            on:click=move |_| state.toggle_fullscreen(panel)
+        >
+            {move || if active() { "✕" } else { "⛶" }}
+        </button>
+    }
+}
+
+/// Interval, overlay and drawing controls for the expanded chart
+#[component]
+pub fn ChartToolbar() -> impl IntoView {
+    let state = use_app_state();
+    let ui = state.ui;
+    let drawings = state.drawings;
+    let locale = use_locale();
+    let t = move |msg: Msg| locale.get().text(msg);
+
+    let tool_active = move |tool: DrawingTool| drawings.with(|d| d.tool == tool);
+    let tool_class = move |tool| if tool_active(tool) { "toolbar-btn active" } else { "toolbar-btn" };
+    let toggle_tool = move |tool| drawings.update(|d| d.toggle_tool(tool));
+    let baseline_chart = move || ui.with(|ui| ui.chart_type == ChartType::Baseline);
+
+    view! {
+        <div class="chart-toolbar" role="toolbar" aria-label="Chart tools">
+            <IntervalSelector market=state.market.clone() />
+
+            <div class="toolbar-group" role="group" aria-label=move || t(Msg::ChartType)>
+                {ChartType::all()
+                    .iter()
+                    .map(|option| {
+                        let option = *option;
+                        let state = state.clone();
+                        let active = move || ui.with(|ui| ui.chart_type == option);
+                        view! {
+                            <button
+                                class=move || if active() { "toolbar-btn active" } else { "toolbar-btn" }
+                                aria-pressed=move || active().to_string()
+                                on:click=move |_| state.set_chart_type(option)
+                            >
+                                {move || t(option.msg())}
+                            </button>
+                        }
+                    })
+                    .collect_view()}
+            </div>
+
+            <div class="toolbar-group" role="group" aria-label="Volume">
+                {VolumeMode::all()
+                    .iter()
+                    .map(|mode| {
+                        let mode = *mode;
+                        let state = state.clone();
+                        let active = move || ui.with(|ui| ui.volume_mode == mode);
+                        view! {
+                            <button
+                                class=move || if active() { "toolbar-btn active" } else { "toolbar-btn" }
+                                aria-pressed=move || active().to_string()
+                                on:click=move |_| state.set_volume_mode(mode)
+                            >
+                                {move || t(mode.msg())}
+                            </button>
+                        }
+                    })
+                    .collect_view()}
+            </div>
+
+            <div class="toolbar-group" role="group" aria-label="Overlays">
+                {Overlay::all()
+                    .iter()
+                    .map(|overlay| {
+                        let overlay = *overlay;
+                        let state = state.clone();
+                        let on = move || ui.with(|ui| ui.overlays.is_shown(overlay));
+                        view! {
+                            <button
+                                class=move || if on() { "toolbar-btn active" } else { "toolbar-btn" }
+                                aria-pressed=move || on().to_string()
+                                on:click=move |_| state.toggle_overlay(overlay)
+                            >
+                                {move || t(overlay.msg())}
+                            </button>
+                        }
+                    })
+                    .collect_view()}
+            </div>
+
+            <div class="toolbar-group" role="group" aria-label="Drawings">
+                <button
+                    class=move || tool_class(DrawingTool::PriceLine)
+                    aria-pressed=move || tool_active(DrawingTool::PriceLine).to_string()
+                    title="Click the chart to place a horizontal line"
+                    on:click=move |_| toggle_tool(DrawingTool::PriceLine)
+                >
+                    {move || t(Msg::PriceLine)}
+                </button>
+                {move || baseline_chart().then(|| view! {
+                    <button
+                        class=move || tool_class(DrawingTool::BaselineLevel)
+                        aria-pressed=move || tool_active(DrawingTool::BaselineLevel).to_string()
+                        title="Click the chart to measure from that price, not the session open"
+                        on:click=move |_| toggle_tool(DrawingTool::BaselineLevel)
+                    >
+                        {move || t(Msg::BaselineLevel)}
+                    </button>
+                })}
+                <button
+                    class=move || tool_class(DrawingTool::Measure)
+                    aria-pressed=move || tool_active(DrawingTool::Measure).to_string()
+                    title="Drag across the chart to measure the price, time and volume in between"
+                    on:click=move |_| toggle_tool(DrawingTool::Measure)
+                >
+                    {move || t(Msg::Measure)}
+                </button>
+                <button
+                    class="toolbar-btn"
+                    disabled=move || drawings.with(|d| d.is_empty())
+                    on:click=move |_| drawings.update(|d| d.clear())
+                >
+                    {move || t(Msg::Clear)}
+                </button>
+            </div>
+
+            {indicator_menu()}
+            <FullscreenButton panel=Panel::CandleChart />
+        </div>
+    }
+}
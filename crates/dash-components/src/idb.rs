@@ -0,0 +1,49 @@
+//! Small IndexedDB helpers shared by the browser-side stores
+
+use leptos::prelude::window;
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{IdbDatabase, IdbRequest};
+
+/// Open database `name` at `version`, running `upgrade` to create its stores
+/// when the database is new or older than `version`
+pub(crate) async fn open_database(
+    name: &str,
+    version: u32,
+    upgrade: impl FnOnce(&IdbDatabase) -> Result<(), JsValue> + 'static,
+) -> Result<IdbDatabase, JsValue> {
+    let factory = window()
+        .indexed_db()?
+        .ok_or_else(|| JsValue::from_str("IndexedDB unavailable"))?;
+    let open = factory.open_with_u32(name, version)?;
+
+    let request = open.clone();
+    let db_name = name.to_string();
+    let on_upgrade = Closure::once_into_js(move || {
+        let Ok(db) = request.result().and_then(|r| r.dyn_into::<IdbDatabase>()) else {
+            return;
+        };
+        if let Err(err) = upgrade(&db) {
+            tracing::warn!(?err, db = %db_name, "failed to create object stores");
+        }
+    });
+    open.set_onupgradeneeded(Some(on_upgrade.unchecked_ref()));
+
+    request_result(&open).await?.dyn_into::<IdbDatabase>()
+}
+
+/// Resolve once `request` succeeds, with its result
+pub(crate) async fn request_result(request: &IdbRequest) -> Result<JsValue, JsValue> {
+    let promise = js_sys::Promise::new(&mut |resolve, reject| {
+        let done = request.clone();
+        let on_success = Closure::once_into_js(move || {
+            let _ = resolve.call1(&JsValue::NULL, &done.result().unwrap_or(JsValue::UNDEFINED));
+        });
+        let on_error = Closure::once_into_js(move || {
+            let _ = reject.call1(&JsValue::NULL, &JsValue::from_str("IndexedDB request failed"));
+        });
+        request.set_onsuccess(Some(on_success.unchecked_ref()));
+        request.set_onerror(Some(on_error.unchecked_ref()));
+    });
+    JsFuture::from(promise).await
+}
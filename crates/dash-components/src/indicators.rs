@@ -0,0 +1,235 @@
+//! Chart indicators: the toolbar menu for adding built-in and scripted
+//! indicators, and the lines they draw on the candle chart. The active
+//! indicators are kept in localStorage so they survive reloads.
+
+use dash_charts::IndicatorLine;
+use dash_core::{colors, IndicatorKind, Msg, Script};
+use dash_state::{use_app_state, AppState, MarketState};
+use leptos::prelude::*;
+
+use crate::alerts::expr_error_text;
+
+/// localStorage key holding the active indicators as a JSON array
+const INDICATORS_KEY: &str = "dash.indicators";
+
+/// Offered as one-click chips above the formula editor
+const PRESETS: [IndicatorKind; 4] = [
+    IndicatorKind::Sma(20),
+    IndicatorKind::Sma(50),
+    IndicatorKind::Ema(20),
+    IndicatorKind::Ema(50),
+];
+
+const FORMULA_PLACEHOLDER: &str = "(sma(close, 20) + ema(close, 50)) / 2";
+
+fn load_indicators() -> Option<Vec<IndicatorKind>> {
+    let storage = window().local_storage().ok().flatten()?;
+    let json = storage.get_item(INDICATORS_KEY).ok().flatten()?;
+    serde_json::from_str(&json).ok()
+}
+
+fn save_indicators(kinds: &[&IndicatorKind]) {
+    let Ok(Some(storage)) = window().local_storage() else { return };
+    let result = if kinds.is_empty() {
+        storage.remove_item(INDICATORS_KEY)
+    } else {
+        match serde_json::to_string(kinds) {
+            Ok(json) => storage.set_item(INDICATORS_KEY, &json),
+            Err(_) => return,
+        }
+    };
+    if let Err(err) = result {
+        tracing::warn!(?err, "failed to persist chart indicators");
+    }
+}
+
+/// Restore the indicators from earlier visits and write them back whenever
+/// one is added or removed
+pub fn track_indicators(state: &AppState) {
+    let market = state.market.clone();
+    for kind in load_indicators().unwrap_or_default() {
+        market.add_indicator(kind);
+    }
+    let kinds = Memo::new(move |_| {
+        market.indicators.with(|set| set.kinds().cloned().collect::<Vec<_>>())
+    });
+    Effect::new(move |previous: Option<()>| {
+        kinds.with(|kinds| {
+            if previous.is_some() {
+                save_indicators(&kinds.iter().collect::<Vec<_>>());
+            }
+        });
+    });
+}
+
+/// Each active indicator's line over the candle history, colored in the
+/// order they were added
+pub fn indicator_lines(market: &MarketState) -> Signal<Vec<IndicatorLine>> {
+    let (indicators, candles) = (market.indicators, market.candles);
+    Signal::derive(move || {
+        indicators.with(|set| {
+            candles.with(|history| {
+                set.series(&history.candles)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, (kind, points))| IndicatorLine {
+                        label: kind.label(),
+                        color: colors::indicator(i),
+                        points,
+                    })
+                    .collect()
+            })
+        })
+    })
+}
+
+/// Chip toggling a built-in indicator
+fn preset_chip(market: &MarketState, kind: &IndicatorKind) -> impl IntoView + use<> {
+    let market = market.clone();
+    let (chip, label) = (kind.clone(), kind.label());
+    let indicators = market.indicators;
+    let active = {
+        let kind = kind.clone();
+        Memo::new(move |_| indicators.with(|set| set.contains(&kind)))
+    };
+    view! {
+        <button
+            type="button"
+            class=move || if active.get() { "toolbar-btn active" } else { "toolbar-btn" }
+            aria-pressed=move || active.get().to_string()
+            on:click=move |_| {
+                if active.get_untracked() {
+                    market.remove_indicator(chip.clone());
+                } else {
+                    market.add_indicator(chip.clone());
+                }
+            }
+        >
+            {label}
+        </button>
+    }
+}
+
+/// Toolbar button opening the indicator editor: presets, a named formula
+/// over the candles, and the active indicators with a remove button each
+#[component]
+pub fn IndicatorMenu() -> impl IntoView {
+    let state = use_app_state();
+    let market = state.market.clone();
+    let indicators = market.indicators;
+    let locale = state.locale();
+    let t = move |msg: Msg| locale.get().text(msg);
+
+    let open = RwSignal::new(false);
+    let name = RwSignal::new(String::new());
+    let formula = RwSignal::new(String::new());
+    let error = RwSignal::new(None::<String>);
+
+    let add = {
+        let market = market.clone();
+        move |ev: leptos::ev::SubmitEvent| {
+            ev.prevent_default();
+            let compiled = name.with(|n| formula.with(|f| Script::compile(n.trim(), f)));
+            match compiled {
+                Ok(script) => {
+                    market.add_indicator(IndicatorKind::Script(script));
+                    name.set(String::new());
+                    formula.set(String::new());
+                    error.set(None);
+                }
+                Err(err) => error.set(Some(expr_error_text(locale.get_untracked(), &err))),
+            }
+        }
+    };
+
+    let presets = {
+        let market = market.clone();
+        move || PRESETS.iter().map(|kind| preset_chip(&market, kind)).collect_view()
+    };
+
+    let active_list = move || {
+        let market = market.clone();
+        indicators.with(|set| {
+            set.kinds()
+                .enumerate()
+                .map(|(i, kind)| {
+                    let market = market.clone();
+                    let remove = kind.clone();
+                    let title = match kind {
+                        IndicatorKind::Script(script) => script.source().to_string(),
+                        _ => kind.label(),
+                    };
+                    view! {
+                        <li class="indicator-item" title=title>
+                            <span
+                                class="indicator-swatch"
+                                style:background=colors::indicator(i)
+                            ></span>
+                            <span class="indicator-label">{kind.label()}</span>
+                            <button
+                                type="button"
+                                class="pt-cancel"
+                                aria-label=format!("Remove {}", kind.label())
+                                on:click=move |_| market.remove_indicator(remove.clone())
+                            >
+                                "×"
+                            </button>
+                        </li>
+                    }
+                })
+                .collect_view()
+        })
+    };
+
+    view! {
+        <div class="indicator-menu">
+            <button
+                class=move || if open.get() { "toolbar-btn active" } else { "toolbar-btn" }
+                aria-expanded=move || open.get().to_string()
+                on:click=move |_| open.update(|o| *o = !*o)
+            >
+                {move || t(Msg::Indicators)}
+            </button>
+            <Show when=move || open.get()>
+                <div class="indicator-popover" role="dialog" aria-label=move || t(Msg::Indicators)>
+                    <div class="indicator-presets">{presets.clone()}</div>
+                    <form class="indicator-form" on:submit=add.clone()>
+                        <label class="pt-field">
+                            <span class="pt-label">{move || t(Msg::IndicatorName)}</span>
+                            <input
+                                type="text"
+                                maxlength="32"
+                                prop:value=move || name.get()
+                                on:input=move |ev| name.set(event_target_value(&ev))
+                            />
+                        </label>
+                        <label class="pt-field">
+                            <span class="pt-label">{move || t(Msg::Formula)}</span>
+                            <input
+                                type="text"
+                                spellcheck="false"
+                                placeholder=FORMULA_PLACEHOLDER
+                                prop:value=move || formula.get()
+                                on:input=move |ev| {
+                                    formula.set(event_target_value(&ev));
+                                    error.set(None);
+                                }
+                            />
+                        </label>
+                        {move || error.get().map(|text| view! {
+                            <div class="pt-error" role="alert">{text}</div>
+                        })}
+                        <button
+                            type="submit"
+                            class="pt-submit"
+                            disabled=move || formula.with(|f| f.trim().is_empty())
+                        >
+                            {move || t(Msg::AddIndicator)}
+                        </button>
+                    </form>
+                    <ul class="indicator-list">{active_list.clone()}</ul>
+                </div>
+            </Show>
+        </div>
+    }
+}
@@ -0,0 +1,35 @@
+//! Candle interval selector
+
+use dash_core::CandleInterval;
+use dash_state::MarketState;
+use leptos::prelude::*;
+
+/// Row of interval buttons for the chart header
+#[component]
+pub fn IntervalSelector(market: MarketState) -> impl IntoView {
+    let interval = market.interval;
+
+    view! {
+        <div class="interval-selector" role="group" aria-label="Candle interval">
+            {CandleInterval::all()
+                .iter()
+                .map(|option| {
+                    let option = *option;
+                    let market = market.clone();
+                    let class = move || {
+                        if interval.get() == option { "interval-btn active" } else { "interval-btn" }
+                    };
+                    view! {
+                        <button
+                            class=class
+                            aria-pressed=move || (interval.get() == option).to_string()
+                            on:click=move |_| market.set_interval(option)
+                        >
+                            {option.label()}
+                        </button>
+                    }
+                })
+                .collect_view()}
+        </div>
+    }
+}
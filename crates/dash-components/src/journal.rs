@@ -0,0 +1,162 @@
+//! Trade journal panel: every paper fill with the PnL it closed, the running
+//! equity curve and a CSV export. The journal is kept in localStorage so it
+//! survives reloads.
+
+use chrono::{DateTime, Utc};
+use dash_charts::AreaSparkline;
+use dash_core::{colors, Fill, FillJournal, Msg};
+use dash_state::{use_app_state, AppState};
+use leptos::prelude::*;
+use wasm_bindgen::JsValue;
+
+use crate::paper::pnl_class;
+use crate::share::download_blob;
+
+/// localStorage key holding the journal as a JSON array of fills
+const JOURNAL_KEY: &str = "dash.journal";
+
+/// Fills listed in the panel, newest first
+const SHOWN_FILLS: usize = 50;
+
+fn load_journal() -> Option<Vec<Fill>> {
+    let storage = window().local_storage().ok().flatten()?;
+    let json = storage.get_item(JOURNAL_KEY).ok().flatten()?;
+    serde_json::from_str(&json).ok()
+}
+
+fn save_journal(journal: &FillJournal) {
+    let Ok(Some(storage)) = window().local_storage() else { return };
+    let result = if journal.is_empty() {
+        storage.remove_item(JOURNAL_KEY)
+    } else {
+        let fills: Vec<&Fill> = journal.fills().collect();
+        match serde_json::to_string(&fills) {
+            Ok(json) => storage.set_item(JOURNAL_KEY, &json),
+            Err(_) => return,
+        }
+    };
+    if let Err(err) = result {
+        tracing::warn!(?err, "failed to persist trade journal");
+    }
+}
+
+/// Restore the journal from earlier visits and write it back on every fill
+pub fn track_journal(state: &AppState) {
+    let journal = state.journal;
+    if let Some(fills) = load_journal() {
+        journal.update(|j| j.restore(fills));
+    }
+    Effect::new(move |previous: Option<()>| {
+        journal.with(|j| {
+            if previous.is_some() {
+                save_journal(j);
+            }
+        });
+    });
+}
+
+/// Save the journal as a timestamped CSV file
+fn download_journal(journal: RwSignal<FillJournal>) {
+    let csv = journal.with_untracked(FillJournal::to_csv);
+    let file_name = format!("trade-journal-{}.csv", Utc::now().format("%Y%m%d-%H%M%S"));
+    let parts = js_sys::Array::of1(&JsValue::from_str(&csv));
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type("text/csv");
+    let result = web_sys::Blob::new_with_str_sequence_and_options(&parts, &options)
+        .and_then(|blob| download_blob(&file_name, &blob));
+    if let Err(err) = result {
+        tracing::warn!(?err, "trade journal export failed");
+    }
+}
+
+/// Equity curve, realized total and recent fills, with export and clear
+#[component]
+pub fn TradeJournal() -> impl IntoView {
+    let state = use_app_state();
+    let journal = state.journal;
+    let locale = state.locale();
+    let t = move |msg: Msg| locale.get().text(msg);
+
+    let equity = Signal::derive(move || journal.with(FillJournal::equity_curve));
+    let realized = move || journal.with(FillJournal::realized_pnl);
+    let equity_color =
+        Memo::new(move |_| if realized() < 0.0 { colors::BEAR } else { colors::BULL });
+
+    let fills = move || {
+        journal.with(|j| j.fills().rev().take(SHOWN_FILLS).cloned().collect::<Vec<_>>())
+    };
+
+    let clear = move |_| state.clear_journal();
+    let export = move |_| download_journal(journal);
+
+    view! {
+        <div class="journal-summary">
+            <span class="pt-label">{move || t(Msg::Equity)}</span>
+            <span class=move || pnl_class(realized())>
+                {move || locale.get().format_decimal(realized(), 2)}
+            </span>
+            <button
+                class="sb-button"
+                prop:disabled=move || journal.with(FillJournal::is_empty)
+                on:click=export
+            >
+                {move || format!("{} CSV", t(Msg::Export))}
+            </button>
+            <button
+                class="sb-button"
+                prop:disabled=move || journal.with(FillJournal::is_empty)
+                on:click=clear
+            >
+                {move || t(Msg::Clear)}
+            </button>
+        </div>
+        // Re-mounted when the sign flips so the curve takes its color
+        {move || {
+            let color = equity_color.get();
+            view! {
+                <div class="journal-equity" aria-hidden="true">
+                    <AreaSparkline values=equity width=260.0 height=40.0 color=color />
+                </div>
+            }
+        }}
+        <ul class="journal-fills" aria-label=move || t(Msg::Journal)>
+            {move || {
+                let fills = fills();
+                if fills.is_empty() {
+                    return view! { <li class="pt-empty">{move || t(Msg::NoFills)}</li> }
+                        .into_any();
+                }
+                let locale = locale.get();
+                fills
+                    .into_iter()
+                    .map(|fill| {
+                        let time = DateTime::<Utc>::from_timestamp_millis(fill.timestamp)
+                            .unwrap_or_default()
+                            .format("%m-%d %H:%M:%S")
+                            .to_string();
+                        let pnl = fill.realized_pnl;
+                        let pnl_class = if pnl > 0.0 {
+                            "text-bull"
+                        } else if pnl < 0.0 {
+                            "text-bear"
+                        } else {
+                            ""
+                        };
+                        view! {
+                            <li class="journal-fill">
+                                <span class="journal-time">{time}</span>
+                                <span class=fill.side.css_class()>
+                                    {locale.text(Msg::side(fill.side))}
+                                </span>
+                                <span>{locale.format_decimal(fill.quantity, 4)}</span>
+                                <span>{locale.format_decimal(fill.price, 2)}</span>
+                                <span class=pnl_class>{locale.format_decimal(pnl, 2)}</span>
+                            </li>
+                        }
+                    })
+                    .collect_view()
+                    .into_any()
+            }}
+        </ul>
+    }
+}
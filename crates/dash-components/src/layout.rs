@@ -0,0 +1,54 @@
+//! Responsive layout helpers: viewport tracking and the mobile tab bar
+
+use dash_state::{use_app_state, use_locale, MobileTab, Viewport};
+use leptos::prelude::*;
+
+/// Keep `viewport` in step with the browser window size
+pub fn track_viewport(viewport: RwSignal<Viewport>) {
+    let measure = move || {
+        let window = window();
+        let width = window.inner_width().ok().and_then(|v| v.as_f64());
+        let height = window.inner_height().ok().and_then(|v| v.as_f64());
+        if let (Some(width), Some(height)) = (width, height) {
+            let next = Viewport::new(width, height);
+            if viewport.get_untracked() != next {
+                viewport.set(next);
+            }
+        }
+    };
+    measure();
+
+    let handle = window_event_listener(leptos::ev::resize, move |_| measure());
+    on_cleanup(move || handle.remove());
+}
+
+/// Chart / Book / Trades switcher for the single-panel layout
+#[component]
+pub fn MobileTabBar() -> impl IntoView {
+    let state = use_app_state();
+    let ui = state.ui;
+    let locale = use_locale();
+
+    view! {
+        <nav class="tab-headers mobile-tabs" role="tablist" aria-label="Dashboard panels">
+            {MobileTab::all()
+                .iter()
+                .map(|tab| {
+                    let tab = *tab;
+                    let state = state.clone();
+                    let active = move || ui.with(|ui| ui.mobile_tab == tab);
+                    view! {
+                        <button
+                            class=move || if active() { "tab-header active" } else { "tab-header" }
+                            role="tab"
+                            aria-selected=move || active().to_string()
+                            on:click=move |_| state.set_mobile_tab(tab)
+                        >
+                            {move || locale.get().text(tab.msg())}
+                        </button>
+                    }
+                })
+                .collect_view()}
+        </nav>
+    }
+}
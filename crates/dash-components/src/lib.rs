@@ -5,16 +5,98 @@
 //! ## Components
 //!
 //! - `order` - Order book ladder display
+//! - `interval` - Candle interval selector
 //! - `trade_history` - Recent trades tape
+//! - `replay` - Time-travel scrubber
+//! - `slippage` - Market impact / slippage calculator
 //! - `ticker_bar` - Header ticker with price/stats
+//! - `clock` - Session clock and market hours
+//! - `news` - News feed panel and alert keywords
+//! - `venue` - Consolidated / single-venue selector
+//! - `layout` - Viewport tracking and mobile tab bar
+//! - `fullscreen` - Fullscreen panels and the extended chart toolbar
+//! - `indicators` - Built-in and scripted chart indicators and their menu
+//! - `session_log` - Opt-in session log storage (IndexedDB) and export
+//! - `share` - Chart snapshot PNG with caption and watermark, copied or saved
+//! - `candle_cache` - Per symbol/interval candle history cache (IndexedDB)
+//! - `palette` - Ctrl+K command palette over the event bus's commands
+//! - `alerts` - Alert rule editor (conditions on live market values) and delivery
+//! - `account` - Sign-in menu and the stored session
+//! - `admin` - Server admin page for operators
+//! - `a11y` - Keyboard row navigation and screen-reader announcements
 //! - `dashboard` - Main dashboard layout
+//!
+//! ## Features
+//!
+//! - `indicators` (default) - Indicator menu and the indicator lines on the chart
+//! - `paper-trading` (default) - Paper trading, journal, risk and backtest panels
+//! - `recording-playback` (default) - Replay scrubber for buffered market data
 
+pub mod a11y;
+pub mod account;
+pub mod admin;
+pub mod alerts;
+#[cfg(feature = "paper-trading")]
+pub mod backtest;
+pub mod candle_cache;
+pub mod clock;
 pub mod dashboard;
+pub mod diagnostics;
+pub mod fullscreen;
+mod idb;
+#[cfg(feature = "indicators")]
+pub mod indicators;
+pub mod interval;
+#[cfg(feature = "paper-trading")]
+pub mod journal;
+pub mod layout;
+pub mod logs;
+pub mod news;
 pub mod order;
+#[cfg(feature = "paper-trading")]
+pub mod paper;
+pub mod palette;
+#[cfg(feature = "recording-playback")]
+pub mod replay;
+#[cfg(feature = "paper-trading")]
+pub mod risk;
+pub mod session_log;
+pub mod share;
+pub mod slippage;
 pub mod ticker_bar;
 pub mod trade_history;
+pub mod venue;
 
+pub use a11y::*;
+pub use account::*;
+pub use admin::*;
+pub use alerts::*;
+#[cfg(feature = "paper-trading")]
+pub use backtest::*;
+pub use candle_cache::*;
+pub use clock::*;
 pub use dashboard::*;
+pub use diagnostics::*;
+pub use fullscreen::*;
+#[cfg(feature = "indicators")]
+pub use indicators::*;
+pub use interval::*;
+#[cfg(feature = "paper-trading")]
+pub use journal::*;
+pub use layout::*;
+pub use logs::*;
+pub use news::*;
 pub use order::*;
+#[cfg(feature = "paper-trading")]
+pub use paper::*;
+pub use palette::*;
+#[cfg(feature = "recording-playback")]
+pub use replay::*;
+#[cfg(feature = "paper-trading")]
+pub use risk::*;
+pub use session_log::*;
+pub use share::*;
+pub use slippage::*;
 pub use ticker_bar::*;
 pub use trade_history::*;
+pub use venue::*;
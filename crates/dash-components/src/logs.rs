@@ -0,0 +1,159 @@
+//! Client log panel: the latest lines from the in-memory log ring with the
+//! spans they were logged in, opened from the status bar
+
+use std::time::Duration;
+
+use chrono::{Local, TimeZone};
+use dash_core::Msg;
+use dash_state::{client_log, use_app_state, LogEntry, LogLevel};
+use leptos::prelude::*;
+
+/// How often the open panel rereads the ring
+const LOG_REFRESH: Duration = Duration::from_secs(1);
+
+fn format_time(ms: i64) -> String {
+    Local
+        .timestamp_millis_opt(ms)
+        .single()
+        .map(|t| t.format("%H:%M:%S%.3f").to_string())
+        .unwrap_or_default()
+}
+
+fn log_line(entry: LogEntry) -> impl IntoView {
+    let class = format!("log-line log-{}", entry.level.label().to_lowercase());
+    let spans = (!entry.spans.is_empty())
+        .then(|| view! { <span class="log-spans">{entry.spans}</span> });
+    view! {
+        <li class=class>
+            <span class="log-time">{format_time(entry.timestamp)}</span>
+            <span class="log-level">{entry.level.label()}</span>
+            <span class="log-target">{entry.target}</span>
+            <span class="log-message">{entry.message}</span>
+            {spans}
+        </li>
+    }
+}
+
+/// Status bar button showing and hiding the log panel
+#[component]
+pub fn LogToggle() -> impl IntoView {
+    let state = use_app_state();
+    let open = state.log_open;
+    let locale = state.locale();
+    let t = move |msg: Msg| locale.get().text(msg);
+
+    view! {
+        <button
+            type="button"
+            class=move || if open.get() { "sb-button active" } else { "sb-button" }
+            aria-pressed=move || open.get().to_string()
+            title="Recent client log lines"
+            on:click=move |_| open.update(|open| *open = !*open)
+        >
+            {move || t(Msg::ClientLog)}
+        </button>
+    }
+}
+
+/// Latest client log lines, newest first, reread once a second while shown
+#[component]
+pub fn LogPanel() -> impl IntoView {
+    let state = use_app_state();
+    let open = state.log_open;
+    let locale = state.locale();
+    let t = move |msg: Msg| locale.get().text(msg);
+
+    let filter = RwSignal::new(LogLevel::Debug);
+    let lines = RwSignal::new(Vec::<LogEntry>::new());
+    // Ring state at the last read, so an idle log does not re-render
+    let seen = StoredValue::new(None::<(u64, usize, LogLevel)>);
+    let refresh = move || {
+        let level = filter.get_untracked();
+        let log = client_log();
+        let key = (log.total(), log.len(), level);
+        if seen.try_get_value().flatten() == Some(key) {
+            return;
+        }
+        seen.set_value(Some(key));
+        let entries = log.entries(level);
+        // Setting the signal may log; the ring must be free by then
+        drop(log);
+        lines.set(entries);
+    };
+
+    let timer = StoredValue::new(None::<IntervalHandle>);
+    let stop = move || {
+        if let Some(handle) = timer.try_get_value().flatten() {
+            handle.clear();
+        }
+    };
+    Effect::new(move |_| {
+        stop();
+        filter.track();
+        if !open.get() {
+            timer.set_value(None);
+            return;
+        }
+        refresh();
+        timer.set_value(set_interval_with_handle(refresh, LOG_REFRESH).ok());
+    });
+    on_cleanup(stop);
+
+    let clear = move |_| {
+        client_log().clear();
+        refresh();
+    };
+    let set_filter = move |ev| {
+        let value = event_target_value(&ev);
+        if let Some(level) = LogLevel::all().iter().find(|l| l.label() == value) {
+            filter.set(*level);
+        }
+    };
+
+    view! {
+        <Show when=move || open.get()>
+            <section class="log-panel" aria-label=move || t(Msg::ClientLogTitle)>
+                <div class="panel-header">
+                    <span class="panel-title">{move || t(Msg::ClientLogTitle)}</span>
+                    <select class="log-filter" on:change=set_filter>
+                        {LogLevel::all()
+                            .iter()
+                            .map(|level| {
+                                let level = *level;
+                                view! {
+                                    <option
+                                        value=level.label()
+                                        selected=move || filter.get() == level
+                                    >
+                                        {level.label()}
+                                    </option>
+                                }
+                            })
+                            .collect_view()}
+                    </select>
+                    <button type="button" class="sb-button" on:click=clear>
+                        {move || t(Msg::Clear)}
+                    </button>
+                    <button
+                        type="button"
+                        class="sb-button"
+                        aria-label="Close"
+                        on:click=move |_| open.set(false)
+                    >
+                        "×"
+                    </button>
+                </div>
+                <ol class="log-lines" role="log">
+                    {move || {
+                        let entries = lines.get();
+                        if entries.is_empty() {
+                            return view! { <li class="log-empty">{t(Msg::NoLogLines)}</li> }
+                                .into_any();
+                        }
+                        entries.into_iter().rev().map(log_line).collect_view().into_any()
+                    }}
+                </ol>
+            </section>
+        </Show>
+    }
+}
@@ -0,0 +1,105 @@
+//! News feed panel: server-pushed headlines with pair tags, filtered to the
+//! current pair on request, and the keywords that raise alerts
+
+use dash_core::Msg;
+use dash_state::{use_app_state, MAX_NEWS_KEYWORDS};
+use leptos::prelude::*;
+
+/// Headlines, newest first; items mentioning an alert keyword stand out
+#[component]
+pub fn NewsFeed(
+    /// Only items tagged with the current pair (and untagged ones)
+    #[prop(into)]
+    pair_only: Signal<bool>,
+) -> impl IntoView {
+    let state = use_app_state();
+    let news = state.news;
+    let symbol = state.market.symbol;
+    let locale = state.locale();
+
+    let rows = move || {
+        let symbol = pair_only.get().then(|| symbol.get());
+        news.with(|feed| {
+            feed.items_for(symbol.as_ref())
+                .into_iter()
+                .map(|item| (item.clone(), feed.alert_keyword(item).is_some()))
+                .collect::<Vec<_>>()
+        })
+    };
+
+    view! {
+        <ul class="news-feed" aria-live="polite">
+            {move || {
+                let rows = rows();
+                if rows.is_empty() {
+                    return view! {
+                        <li class="news-empty">{move || locale.get().text(Msg::NoNews)}</li>
+                    }
+                        .into_any();
+                }
+                rows.into_iter()
+                    .map(|(item, flagged)| {
+                        let time = locale.get_untracked().format_datetime(&item.timestamp, false);
+                        let headline = match item.url.clone() {
+                            Some(url) => view! {
+                                <a href=url target="_blank" rel="noopener noreferrer">
+                                    {item.headline.clone()}
+                                </a>
+                            }
+                                .into_any(),
+                            None => item.headline.clone().into_any(),
+                        };
+                        view! {
+                            <li class="news-item" class:flagged=flagged>
+                                <div class="news-meta">
+                                    <span class="news-source">{item.source.clone()}</span>
+                                    <span class="news-time">{time}</span>
+                                    {item
+                                        .symbols
+                                        .iter()
+                                        .map(|s| {
+                                            view! { <span class="news-tag">{s.to_string()}</span> }
+                                        })
+                                        .collect_view()}
+                                </div>
+                                <div class="news-headline">{headline}</div>
+                                {item
+                                    .body
+                                    .clone()
+                                    .map(|body| view! { <p class="news-body">{body}</p> })}
+                            </li>
+                        }
+                    })
+                    .collect_view()
+                    .into_any()
+            }}
+        </ul>
+    }
+}
+
+/// Comma-separated alert keywords for the news panel's settings
+#[component]
+pub fn NewsKeywords() -> impl IntoView {
+    let state = use_app_state();
+    let news = state.news;
+    let locale = state.locale();
+    let label = move || locale.get().text(Msg::AlertKeywords);
+    let set_keywords = {
+        let state = state.clone();
+        move |ev| state.set_news_keywords(&event_target_value(&ev))
+    };
+
+    view! {
+        <label class="news-keywords">
+            <span class="sb-label">{label}</span>
+            <input
+                class="sb-input"
+                type="text"
+                placeholder="halt, listing, upgrade"
+                title=format!("Up to {} words or phrases, comma separated", MAX_NEWS_KEYWORDS)
+                prop:value=move || news.with(|feed| feed.keywords_text())
+                on:change=set_keywords
+            />
+        </label>
+    }
+}
@@ -1,16 +1,107 @@
 //! Order book ladder display component
 
-use dash_core::{colors, OrderBookLevel, OrderSide};
-use dash_state::MarketState;
-use leptos::prelude::*;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use dash_core::{
+    colors, Locale, Msg, OrderBookLevel, OrderSide, SizeChange, WallTracker, DEPTH_LEVEL_CHOICES,
+};
+use dash_state::{use_locale, BookTotal, MarketState};
+use leptos::{html, prelude::*};
+
+use crate::{navigate_rows, navigate_rows_bottom_up};
+
+/// How long a level flashes after its size changes
+const FLASH_MS: u64 = 400;
+
+/// Quiet time after the user scrolls an anchored ladder before it recenters
+const RECENTER_IDLE_MS: u64 = 3_000;
+
+/// Live size of one ladder level. A row keeps its cell while its price
+/// stays on the book, so an update only touches the levels that changed.
+#[derive(Clone, Copy, PartialEq)]
+struct LevelCell {
+    quantity: RwSignal<f64>,
+    /// Size from the best price through this level
+    cumulative: RwSignal<f64>,
+    /// Latest change and its sequence number, cleared when the flash ends
+    flash: RwSignal<Option<(SizeChange, u32)>>,
+}
+
+impl LevelCell {
+    fn new(quantity: f64, cumulative: f64) -> Self {
+        Self {
+            quantity: RwSignal::new(quantity),
+            cumulative: RwSignal::new(cumulative),
+            flash: RwSignal::new(None),
+        }
+    }
+
+    fn set_cumulative(&self, cumulative: f64) {
+        if self.cumulative.get_untracked() != cumulative {
+            self.cumulative.set(cumulative);
+        }
+    }
+
+    fn set_quantity(&self, quantity: f64) {
+        let Some(change) = SizeChange::between(self.quantity.get_untracked(), quantity) else {
+            return;
+        };
+        self.quantity.set(quantity);
+        let seq = self.flash.get_untracked().map_or(0, |(_, seq)| seq.wrapping_add(1));
+        self.flash.set(Some((change, seq)));
+
+        // Only the latest change ends the flash; the row may be gone by then
+        let flash = self.flash;
+        set_timeout(
+            move || {
+                if flash.try_get_untracked() == Some(Some((change, seq))) {
+                    flash.set(None);
+                }
+            },
+            Duration::from_millis(FLASH_MS),
+        );
+    }
+}
+
+/// Cells of the rows on screen, by side and price bits
+type LevelCells = HashMap<(OrderSide, u64), LevelCell>;
+
+/// Cell for a new row, registered for updates until the row goes away
+fn track_level(
+    cells: StoredValue<LevelCells>,
+    side: OrderSide,
+    level: &OrderBookLevel,
+    cumulative: f64,
+) -> LevelCell {
+    let key = (side, level.price.as_f64().to_bits());
+    let cell = LevelCell::new(level.quantity.as_f64(), cumulative);
+    cells.update_value(|cells| {
+        cells.insert(key, cell);
+    });
+    on_cleanup(move || {
+        // A row re-created at the same price may have replaced this cell
+        cells.try_update_value(|cells| {
+            if cells.get(&key) == Some(&cell) {
+                cells.remove(&key);
+            }
+        });
+    });
+    cell
+}
 
 /// Order book configuration
 #[derive(Debug, Clone)]
 pub struct OrderBookConfig {
+    /// Rows per side when the level selector is hidden
     pub depth: usize,
     pub show_spread: bool,
     pub show_totals: bool,
     pub compact: bool,
+    /// Annotate levels flagged as liquidity walls
+    pub show_walls: bool,
+    /// Level-count selector; rows follow the depth negotiated with the server
+    pub level_selector: bool,
 }
 
 impl Default for OrderBookConfig {
@@ -20,6 +111,8 @@ impl Default for OrderBookConfig {
             show_spread: true,
             show_totals: true,
             compact: false,
+            show_walls: true,
+            level_selector: true,
         }
     }
 }
@@ -31,6 +124,8 @@ impl OrderBookConfig {
             show_spread: true,
             show_totals: false,
             compact: true,
+            show_walls: true,
+            level_selector: false,
         }
     }
 }
@@ -40,36 +135,131 @@ impl OrderBookConfig {
 pub fn OrderBook(
     #[prop(into)] market: MarketState,
     #[prop(optional)] config: Option<OrderBookConfig>,
+    /// Total column mode; notional when not given
+    #[prop(optional, into)] total_mode: Option<Signal<BookTotal>>,
+    /// One scroll area for both sides, kept centered on the spread
+    #[prop(optional, into)] anchored: Option<Signal<bool>>,
 ) -> impl IntoView {
     let config = config.unwrap_or_default();
-    let depth = config.depth;
+    let total_mode = total_mode.unwrap_or_else(|| Signal::stored(BookTotal::Notional));
+    let anchored = anchored.unwrap_or_else(|| Signal::stored(false));
+    let level_selector = config.level_selector;
+    let depth_levels = market.depth_levels;
+    let depth = move || if level_selector { depth_levels.get() } else { config.depth };
     let show_spread = config.show_spread;
     let show_totals = config.show_totals;
+    let show_walls = config.show_walls;
 
     let orderbook = market.orderbook;
+    let walls = market.walls;
+    let locale = use_locale();
+    let t = move |msg: Msg| locale.get().text(msg);
+
+    let max_qty = Memo::new(move |_| {
+        orderbook.with(|b| b.as_ref().map_or(1.0, |book| book.max_quantity().max(0.001)))
+    });
+    // Deepest running total on screen, which cumulative bars scale to
+    let max_cumulative = Memo::new(move |_| {
+        let depth = depth();
+        orderbook.with(|b| {
+            b.as_ref().map_or(1.0, |book| {
+                let side_total = |levels: &[OrderBookLevel]| -> f64 {
+                    levels.iter().take(depth).map(|l| l.quantity.as_f64()).sum()
+                };
+                side_total(&book.asks).max(side_total(&book.bids)).max(0.001)
+            })
+        })
+    });
+
+    // Rows are keyed by price; size changes go to the rows' own cells
+    // instead of re-creating them
+    let cells = StoredValue::new(LevelCells::new());
+    Effect::new(move |_| {
+        orderbook.with(|book| {
+            let Some(book) = book else { return };
+            cells.with_value(|cells| {
+                for (side, levels) in [(OrderSide::Ask, &book.asks), (OrderSide::Bid, &book.bids)] {
+                    let mut cumulative = 0.0;
+                    for level in levels {
+                        cumulative += level.quantity.as_f64();
+                        if let Some(cell) = cells.get(&(side, level.price.as_f64().to_bits())) {
+                            cell.set_quantity(level.quantity.as_f64());
+                            cell.set_cumulative(cumulative);
+                        }
+                    }
+                }
+            });
+        });
+    });
 
-    let max_qty = move || {
-        orderbook.get().map_or(1.0, |book| book.max_quantity().max(0.001))
+    // Visible levels, best first, with their running totals
+    let ladder = move |levels: &[OrderBookLevel]| -> Vec<(OrderBookLevel, f64)> {
+        levels
+            .iter()
+            .take(depth())
+            .scan(0.0, |cumulative, level| {
+                *cumulative += level.quantity.as_f64();
+                Some((level.clone(), *cumulative))
+            })
+            .collect()
     };
 
     let asks = move || {
-        orderbook.get().map_or(vec![], |book| {
-            let mut a: Vec<_> = book.asks.iter().take(depth).cloned().collect();
-            a.reverse();
-            a
-        })
+        // Best ask first; the column is laid out bottom-up in CSS
+        orderbook.with(|b| b.as_ref().map_or(vec![], |book| ladder(&book.asks)))
     };
 
-    let bids = move || {
-        orderbook.get().map_or(vec![], |book| {
-            book.bids.iter().take(depth).cloned().collect()
-        })
+    let bids = move || orderbook.with(|b| b.as_ref().map_or(vec![], |book| ladder(&book.bids)));
+
+    // Anchored: the shared scroll area follows the spread as the book moves,
+    // except while the user scrolls through deeper levels
+    let ladder_ref = NodeRef::<html::Div>::new();
+    let asks_ref = NodeRef::<html::Div>::new();
+    let bids_ref = NodeRef::<html::Div>::new();
+    let locked = RwSignal::new(false);
+    let activity = StoredValue::new(0u32);
+    let recenter = move || {
+        let (Some(ladder), Some(asks), Some(bids)) =
+            (ladder_ref.get_untracked(), asks_ref.get_untracked(), bids_ref.get_untracked())
+        else {
+            return;
+        };
+        // Midway between the best ask and the best bid: the spread row
+        let spread_mid = (asks.offset_top() + asks.offset_height() + bids.offset_top()) / 2;
+        ladder.set_scroll_top((spread_mid - ladder.client_height() / 2).max(0));
+    };
+    Effect::new(move |_| {
+        orderbook.track();
+        if anchored.get() && !locked.get() {
+            recenter();
+        }
+    });
+    let unlock = move || {
+        activity.update_value(|seq| *seq = seq.wrapping_add(1));
+        locked.set(false);
+    };
+    let inspect = move || {
+        if !anchored.get_untracked() {
+            return;
+        }
+        locked.set(true);
+        activity.update_value(|seq| *seq = seq.wrapping_add(1));
+        let seq = activity.get_value();
+        set_timeout(
+            move || {
+                if activity.try_get_value() == Some(seq) {
+                    locked.set(false);
+                }
+            },
+            Duration::from_millis(RECENTER_IDLE_MS),
+        );
     };
 
     let spread_info = move || {
         orderbook.get().and_then(|book| {
             book.spread().zip(book.spread_percent()).map(|(s, pct)| {
-                (format!("{:.2}", s), format!("{:.3}%", pct))
+                let locale = locale.get();
+                (locale.format_decimal(s, 2), format!("{}%", locale.format_decimal(pct, 3)))
             })
         })
     };
@@ -82,65 +272,125 @@ pub fn OrderBook(
 
     view! {
         <div class="orderbook">
-            <div class="ob-header">
-                <span class="ob-col price">"Price"</span>
-                <span class="ob-col size">"Size"</span>
-                <span class="ob-col total">"Total"</span>
+            {level_selector.then(|| view! { <DepthLevelSelector market=market.clone() /> })}
+            <div class="ob-header" aria-hidden="true">
+                <span class="ob-col price">{move || t(Msg::Price)}</span>
+                <span class="ob-col size">{move || t(Msg::Size)}</span>
+                <span class="ob-col total">
+                    {move || match total_mode.get() {
+                        BookTotal::Notional => t(Msg::Total),
+                        BookTotal::Cumulative => t(Msg::CumSize),
+                    }}
+                </span>
             </div>
 
-            <div class="ob-asks">
-                <For
-                    each=asks
-                    key=|level| format!("{:.8}", level.price.as_f64())
-                    children=move |level| {
-                        let mq = max_qty();
-                        view! { <OrderBookRow level=level side=OrderSide::Ask max_qty=mq /> }
-                    }
-                />
-            </div>
+            {move || (anchored.get() && locked.get()).then(|| view! {
+                <button class="ob-recenter" on:click=move |_| unlock()>
+                    {move || format!("⇕ {}", t(Msg::Recenter))}
+                </button>
+            })}
 
-            {move || {
-                if show_spread {
-                    spread_info().map(|(spread, pct)| {
-                        view! {
-                            <div class="ob-spread">
-                                <span class="spread-label">"Spread"</span>
-                                <span class="spread-value">{spread}</span>
-                                <span class="spread-pct">{pct}</span>
-                            </div>
+            <div
+                class=move || if anchored.get() { "ob-ladder anchored" } else { "ob-ladder" }
+                node_ref=ladder_ref
+                on:wheel=move |_| inspect()
+                on:touchmove=move |_| inspect()
+                on:pointerdown=move |_| inspect()
+                on:keydown=move |_| inspect()
+            >
+                <div
+                    class="ob-asks"
+                    role="list"
+                    aria-label="Asks, best price first"
+                    tabindex="0"
+                    on:keydown=navigate_rows_bottom_up
+                    node_ref=asks_ref
+                >
+                    <For
+                        each=asks
+                        key=|(level, _)| format!("{:.8}", level.price.as_f64())
+                        children=move |(level, cumulative)| {
+                            let cell = track_level(cells, OrderSide::Ask, &level, cumulative);
+                            view! {
+                                <OrderBookRow
+                                    level=level
+                                    cell=cell
+                                    side=OrderSide::Ask
+                                    max_qty=max_qty
+                                    max_cumulative=max_cumulative
+                                    total_mode=total_mode
+                                    walls=walls
+                                    show_walls=show_walls
+                                    locale=locale
+                                />
+                            }
                         }
-                    })
-                } else {
-                    None
-                }
-            }}
+                    />
+                </div>
 
-            <div class="ob-bids">
-                <For
-                    each=bids
-                    key=|level| format!("{:.8}", level.price.as_f64())
-                    children=move |level| {
-                        let mq = max_qty();
-                        view! { <OrderBookRow level=level side=OrderSide::Bid max_qty=mq /> }
+                {move || {
+                    if show_spread {
+                        spread_info().map(|(spread, pct)| {
+                            let label = format!("{} {} ({})", t(Msg::Spread), spread, pct);
+                            view! {
+                                <div class="ob-spread" aria-label=label>
+                                    <span class="spread-label">{t(Msg::Spread)}</span>
+                                    <span class="spread-value">{spread}</span>
+                                    <span class="spread-pct">{pct}</span>
+                                </div>
+                            }
+                        })
+                    } else {
+                        None
                     }
-                />
+                }}
+
+                <div
+                    class="ob-bids"
+                    role="list"
+                    aria-label="Bids, best price first"
+                    tabindex="0"
+                    on:keydown=navigate_rows
+                    node_ref=bids_ref
+                >
+                    <For
+                        each=bids
+                        key=|(level, _)| format!("{:.8}", level.price.as_f64())
+                        children=move |(level, cumulative)| {
+                            let cell = track_level(cells, OrderSide::Bid, &level, cumulative);
+                            view! {
+                                <OrderBookRow
+                                    level=level
+                                    cell=cell
+                                    side=OrderSide::Bid
+                                    max_qty=max_qty
+                                    max_cumulative=max_cumulative
+                                    total_mode=total_mode
+                                    walls=walls
+                                    show_walls=show_walls
+                                    locale=locale
+                                />
+                            }
+                        }
+                    />
+                </div>
             </div>
 
             {move || {
                 if show_totals {
                     totals().map(|(bid_total, ask_total)| {
                         view! {
-                            <div class="ob-totals">
+                            <div class="ob-totals" role="group" aria-label="Book totals">
                                 <div class="total-bid">
-                                    <span class="label">"Bid Total:"</span>
+                                    <span class="label">{t(Msg::BidTotal)}</span>
                                     <span class="value" style=format!("color: {}", colors::BULL)>
-                                        {format!("{:.4}", bid_total)}
+                                        {locale.get().format_decimal(bid_total, 4)}
                                     </span>
                                 </div>
                                 <div class="total-ask">
-                                    <span class="label">"Ask Total:"</span>
+                                    <span class="label">{t(Msg::AskTotal)}</span>
                                     <span class="value" style=format!("color: {}", colors::BEAR)>
-                                        {format!("{:.4}", ask_total)}
+                                        {locale.get().format_decimal(ask_total, 4)}
                                     </span>
                                 </div>
                             </div>
@@ -154,42 +404,136 @@ pub fn OrderBook(
     }
 }
 
+/// Book level count; changing it re-subscribes with the new `depth_levels`
+#[component]
+pub fn DepthLevelSelector(market: MarketState) -> impl IntoView {
+    let depth_levels = market.depth_levels;
+    let locale = use_locale();
+    let on_change = move |ev| {
+        if let Ok(levels) = event_target_value(&ev).parse::<usize>() {
+            depth_levels.set(levels);
+        }
+    };
+
+    view! {
+        <div class="ob-levels">
+            <label class="ob-levels-label" for="ob-levels-select">{move || locale.get().text(Msg::Levels)}</label>
+            <select id="ob-levels-select" class="ob-levels-select" on:change=on_change>
+                {DEPTH_LEVEL_CHOICES
+                    .iter()
+                    .map(|levels| {
+                        let levels = *levels;
+                        view! {
+                            <option value=levels.to_string() selected=move || depth_levels.get() == levels>
+                                {levels.to_string()}
+                            </option>
+                        }
+                    })
+                    .collect_view()}
+            </select>
+        </div>
+    }
+}
+
 #[component]
 fn OrderBookRow(
     level: OrderBookLevel,
+    cell: LevelCell,
     side: OrderSide,
-    max_qty: f64,
+    max_qty: Memo<f64>,
+    max_cumulative: Memo<f64>,
+    total_mode: Signal<BookTotal>,
+    walls: RwSignal<WallTracker>,
+    show_walls: bool,
+    locale: Signal<Locale>,
 ) -> impl IntoView {
     let price = level.price.as_f64();
-    let qty = level.quantity.as_f64();
-    let bar_pct = (qty / max_qty * 100.0).min(100.0);
+    let qty = cell.quantity;
 
-    let price_str = if price >= 1000.0 {
-        format!("{:.2}", price)
-    } else {
-        format!("{:.4}", price)
+    let price_decimals = if price >= 1000.0 { 2 } else { 4 };
+    let price_str = move || locale.get().format_decimal(price, price_decimals);
+    let qty_str = move || locale.get().format_decimal(qty.get(), 4);
+    let cumulative = cell.cumulative;
+    let total_str = move || match total_mode.get() {
+        BookTotal::Notional => locale.get().format_decimal(price * qty.get(), 2),
+        BookTotal::Cumulative => locale.get().format_decimal(cumulative.get(), 4),
+    };
+    let row_label = move || {
+        let t = |msg| locale.get().text(msg);
+        let side = if side == OrderSide::Bid { Msg::Bid } else { Msg::Ask };
+        let total = match total_mode.get() {
+            BookTotal::Notional => Msg::Total,
+            BookTotal::Cumulative => Msg::CumSize,
+        };
+        format!(
+            "{} {}, {} {}, {} {}",
+            t(side), price_str(), t(Msg::Size), qty_str(), t(total), total_str()
+        )
     };
-
-    let qty_str = format!("{:.4}", qty);
-    let value = price * qty;
-    let value_str = format!("{:.2}", value);
 
     let (bar_color, text_color) = match side {
         OrderSide::Bid => (colors::bull_alpha(0.2), colors::BULL),
         OrderSide::Ask => (colors::bear_alpha(0.2), colors::BEAR),
     };
 
-    let bg_style = format!(
-        "background: linear-gradient(to {}, {} {}%, transparent {}%)",
-        if side == OrderSide::Bid { "left" } else { "right" },
-        bar_color, bar_pct, bar_pct
-    );
+    let bg_style = move || {
+        let share = match total_mode.get() {
+            BookTotal::Notional => qty.get() / max_qty.get(),
+            BookTotal::Cumulative => cumulative.get() / max_cumulative.get(),
+        };
+        let bar_pct = (share * 100.0).min(100.0);
+        format!(
+            "background: linear-gradient(to {}, {} {}%, transparent {}%)",
+            if side == OrderSide::Bid { "left" } else { "right" },
+            bar_color, bar_pct, bar_pct
+        )
+    };
+
+    // Reactive so annotations follow the tracker without re-keying rows
+    let wall = move || {
+        if show_walls {
+            walls.with(|w| w.wall_at(side, price).cloned())
+        } else {
+            None
+        }
+    };
 
     view! {
-        <div class="ob-row" style=bg_style>
+        <div
+            class=move || {
+                let mut class = String::from("ob-row");
+                if wall().is_some() {
+                    class.push_str(" wall");
+                }
+                if let Some((change, _)) = cell.flash.get() {
+                    class.push(' ');
+                    class.push_str(change.css_class());
+                }
+                class
+            }
+            style=bg_style
+            role="listitem"
+            tabindex="-1"
+            aria-label=move || match wall() {
+                Some(w) => format!("{}, liquidity wall {:.1}x neighbors", row_label(), w.strength),
+                None => row_label(),
+            }
+        >
             <span class="ob-col price" style=format!("color: {}", text_color)>{price_str}</span>
-            <span class="ob-col size">{qty_str}</span>
-            <span class="ob-col total">{value_str}</span>
+            <span class="ob-col size">
+                {move || wall().map(|w| {
+                    view! {
+                        <span
+                            class="ob-wall-tag"
+                            title=format!("{:.1}x neighboring size", w.strength)
+                        >
+                            {format!("W {}", w.age_label())}
+                        </span>
+                    }
+                })}
+                {qty_str}
+            </span>
+            <span class="ob-col total">{total_str}</span>
         </div>
     }
 }
\ No newline at end of file
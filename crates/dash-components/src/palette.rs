@@ -0,0 +1,272 @@
+//! Command palette
+//!
+//! Ctrl+K (Cmd+K on macOS) opens a searchable list of the commands
+//! registered on the event bus. Everything works from the keyboard: type to
+//! filter, arrows to pick, Enter to run, Escape to close.
+
+use dash_core::{CandleInterval, Msg, Symbol};
+use dash_state::{
+    search_commands, use_app_state, AppState, Command, DashEvent, DashboardRoute, EventBus, Panel,
+};
+use leptos::{html, prelude::*};
+use wasm_bindgen::JsCast;
+use web_sys::HtmlElement;
+
+use crate::{download_session_log, save_chart_snapshot};
+
+/// Pairs offered without typing; any other symbol can be typed in full
+const PALETTE_SYMBOLS: [&str; 3] = ["BTC-USD", "ETH-USD", "SOL-USD"];
+
+/// Control focused by "Open settings"
+const SETTINGS_SELECTOR: &str = ".status-bar select, .status-bar input";
+
+fn switch_symbol(events: &EventBus, symbol: Symbol) -> Command {
+    let relay = events.clone();
+    let title = format!("Switch symbol: {}", symbol);
+    Command::new(format!("symbol-{}", symbol), title, move || {
+        relay.publish(DashEvent::SymbolSelected(symbol.clone()));
+    })
+}
+
+/// A symbol typed into the palette, e.g. "avax-usd"
+fn typed_symbol(query: &str) -> Option<Symbol> {
+    if !query.contains('-') {
+        return None;
+    }
+    DashboardRoute::from_params(Some(query), None, CandleInterval::default()).map(|r| r.symbol)
+}
+
+/// Register the dashboard's own commands on the event bus
+pub fn register_commands(state: &AppState) {
+    let events = &state.events;
+    for symbol in PALETTE_SYMBOLS {
+        events.register_command(switch_symbol(events, Symbol::new(symbol)));
+    }
+    for &interval in CandleInterval::all() {
+        let market = state.market.clone();
+        let id = format!("interval-{}", interval);
+        let title = format!("Interval: {}", interval);
+        events.register_command(Command::new(id, title, move || market.set_interval(interval)));
+    }
+    for &panel in Panel::all() {
+        let state = state.clone();
+        let id = format!("expand-{:?}", panel).to_lowercase();
+        let title = format!("Toggle panel: {}", panel.label());
+        events.register_command(Command::new(id, title, move || state.toggle_fullscreen(panel)));
+    }
+    for panel in state.custom_panels.panels() {
+        let state = state.clone();
+        let id = panel.id();
+        let title = format!("Show panel: {}", panel.title());
+        events.register_command(Command::new(format!("custom-panel-{}", id), title, move || {
+            state.set_custom_panel(Some(id))
+        }));
+    }
+
+    let log = state.session_log;
+    events.register_command(Command::new("export-session-log", "Export session log", move || {
+        download_session_log(log)
+    }));
+    let snapshot = state.clone();
+    events.register_command(Command::new("export-chart", "Export chart snapshot", move || {
+        save_chart_snapshot(&snapshot)
+    }));
+    let theme = state.clone();
+    events.register_command(Command::new("toggle-theme", "Toggle theme", move || {
+        theme.toggle_theme()
+    }));
+    let performance = state.clone();
+    events.register_command(Command::new(
+        "toggle-performance",
+        "Toggle performance mode",
+        move || performance.toggle_performance_mode(),
+    ));
+    events.register_command(Command::new("open-settings", "Open settings", || {
+        let control = document().query_selector(SETTINGS_SELECTOR).ok().flatten();
+        if let Some(control) = control.and_then(|c| c.dyn_into::<HtmlElement>().ok()) {
+            let _ = control.focus();
+        }
+    }));
+}
+
+/// Ctrl+K command palette
+#[component]
+pub fn CommandPalette() -> impl IntoView {
+    let state = use_app_state();
+    let locale = state.locale();
+    let t = move |msg: Msg| locale.get().text(msg);
+    let events = StoredValue::new(state.events.clone());
+
+    let open = RwSignal::new(false);
+    let query = RwSignal::new(String::new());
+    let selected = RwSignal::new(0usize);
+    // Snapshot of the registered commands, taken on open
+    let commands = RwSignal::new(Vec::<Command>::new());
+    let input_ref = NodeRef::<html::Input>::new();
+    let list_ref = NodeRef::<html::Ul>::new();
+    // Where focus goes back to on close
+    let return_focus = StoredValue::new_local(None::<HtmlElement>);
+
+    let matches = move || -> Vec<Command> {
+        query.with(|q| {
+            let mut found: Vec<Command> =
+                commands.with(|c| search_commands(q, c).into_iter().cloned().collect());
+            if let Some(symbol) = typed_symbol(q)
+                && !found.iter().any(|c| c.id == format!("symbol-{}", symbol))
+            {
+                found.insert(0, events.with_value(|e| switch_symbol(e, symbol)));
+            }
+            found
+        })
+    };
+
+    let show = move || {
+        let active = document().active_element().and_then(|e| e.dyn_into::<HtmlElement>().ok());
+        return_focus.set_value(active);
+        commands.set(events.with_value(EventBus::commands));
+        query.set(String::new());
+        selected.set(0);
+        open.set(true);
+    };
+    let close = move || {
+        open.set(false);
+        if let Some(element) = return_focus.get_value() {
+            let _ = element.focus();
+        }
+    };
+    // Close first so a command that moves focus keeps it
+    let run = move |command: &Command| {
+        close();
+        command.run();
+    };
+
+    let handle = window_event_listener(leptos::ev::keydown, move |ev| {
+        if (ev.ctrl_key() || ev.meta_key()) && ev.key().eq_ignore_ascii_case("k") {
+            ev.prevent_default();
+            if open.get_untracked() { close() } else { show() }
+        }
+    });
+    on_cleanup(move || handle.remove());
+
+    Effect::new(move |_| {
+        if open.get()
+            && let Some(input) = input_ref.get()
+        {
+            let _ = input.focus();
+        }
+    });
+
+    // Keep the picked option in view while arrowing through a long list
+    Effect::new(move |_| {
+        let id = format!("palette-opt-{}", selected.get());
+        let option = document().get_element_by_id(&id);
+        if let Some(list) = list_ref.get()
+            && let Some(option) = option.and_then(|o| o.dyn_into::<HtmlElement>().ok())
+        {
+            let top = option.offset_top();
+            let bottom = top + option.offset_height();
+            if top < list.scroll_top() {
+                list.set_scroll_top(top);
+            } else if bottom > list.scroll_top() + list.client_height() {
+                list.set_scroll_top(bottom - list.client_height());
+            }
+        }
+    });
+
+    let on_keydown = move |ev: leptos::ev::KeyboardEvent| {
+        let count = matches().len();
+        match ev.key().as_str() {
+            "ArrowDown" if count > 0 => selected.update(|s| *s = (*s + 1) % count),
+            "ArrowUp" if count > 0 => selected.update(|s| *s = (*s + count - 1) % count),
+            "Enter" => {
+                if let Some(command) = matches().get(selected.get_untracked()) {
+                    run(command);
+                }
+            }
+            "Escape" => {
+                // Not also leaving a fullscreen panel
+                ev.stop_propagation();
+                close();
+            }
+            // Focus stays in the palette while it is open
+            "Tab" => {}
+            _ => return,
+        }
+        ev.prevent_default();
+    };
+
+    view! {
+        <Show when=move || open.get()>
+            <div class="palette-backdrop" on:click=move |_| close()>
+                <div
+                    class="palette"
+                    role="dialog"
+                    aria-modal="true"
+                    aria-label=move || t(Msg::CommandPalette)
+                    on:click=|ev| ev.stop_propagation()
+                >
+                    <input
+                        class="palette-input"
+                        type="text"
+                        role="combobox"
+                        aria-expanded="true"
+                        aria-controls="palette-list"
+                        aria-autocomplete="list"
+                        aria-activedescendant=move || format!("palette-opt-{}", selected.get())
+                        placeholder=move || t(Msg::PaletteHint)
+                        node_ref=input_ref
+                        prop:value=move || query.get()
+                        on:input=move |ev| {
+                            query.set(event_target_value(&ev));
+                            selected.set(0);
+                        }
+                        on:keydown=on_keydown
+                    />
+                    <ul
+                        id="palette-list"
+                        class="palette-list"
+                        role="listbox"
+                        node_ref=list_ref
+                    >
+                        {move || {
+                            let found = matches();
+                            if found.is_empty() {
+                                return view! {
+                                    <li class="palette-empty">{move || t(Msg::NoMatches)}</li>
+                                }
+                                    .into_any();
+                            }
+                            found
+                                .into_iter()
+                                .enumerate()
+                                .map(|(i, command)| {
+                                    let active = move || selected.get() == i;
+                                    let title = command.title.clone();
+                                    view! {
+                                        <li
+                                            id=format!("palette-opt-{}", i)
+                                            class=move || {
+                                                if active() {
+                                                    "palette-option active"
+                                                } else {
+                                                    "palette-option"
+                                                }
+                                            }
+                                            role="option"
+                                            aria-selected=move || active().to_string()
+                                            on:mousemove=move |_| selected.set(i)
+                                            on:click=move |_| run(&command)
+                                        >
+                                            {title}
+                                        </li>
+                                    }
+                                })
+                                .collect_view()
+                                .into_any()
+                        }}
+                    </ul>
+                </div>
+            </div>
+        </Show>
+    }
+}
@@ -0,0 +1,395 @@
+//! Paper trading panel: order ticket with a take-profit/stop-loss bracket and
+//! its projected outcome, the position in the current pair and its working
+//! orders
+
+use dash_charts::{ChartBadge, ChartLevel};
+use dash_core::{
+    colors, Bracket, BracketLeg, Leverage, Locale, Msg, OrderTicket, OrderType, PaperEngine,
+    Symbol, TradeSide,
+};
+use dash_state::use_app_state;
+use leptos::prelude::*;
+
+/// Default order size shown before the user types anything
+const DEFAULT_SIZE: &str = "0.1";
+
+/// Positive number typed into a ticket field
+fn parse_input(text: &str) -> Option<f64> {
+    text.trim().parse::<f64>().ok().filter(|v| v.is_finite() && *v > 0.0)
+}
+
+/// PnL with its sign, e.g. "+12.50"
+fn format_pnl(locale: Locale, pnl: f64) -> String {
+    let sign = if pnl > 0.0 { "+" } else if pnl < 0.0 { "-" } else { "" };
+    format!("{}{}", sign, locale.format_decimal(pnl.abs(), 2))
+}
+
+/// Value cell colored by the sign of `pnl`
+pub(crate) fn pnl_class(pnl: f64) -> &'static str {
+    if pnl > 0.0 {
+        "pt-value text-bull"
+    } else if pnl < 0.0 {
+        "pt-value text-bear"
+    } else {
+        "pt-value"
+    }
+}
+
+/// Id of the position lines, which are never dragged; order ids start at 1
+const POSITION_LEVEL: u64 = 0;
+
+/// Chart levels for the paper position and orders in `symbol`. Working
+/// orders, including the TP/SL legs of an open position, can be dragged; the
+/// bracket of an unfilled entry is shown but moves with the ticket only.
+pub fn paper_levels(engine: &PaperEngine, symbol: &Symbol, locale: Locale) -> Vec<ChartLevel> {
+    let leg_color = |leg: BracketLeg| match leg {
+        BracketLeg::TakeProfit => colors::BULL,
+        BracketLeg::StopLoss => colors::BEAR,
+    };
+    let mut levels = Vec::new();
+    let position = engine.position(symbol);
+    if !position.is_flat() {
+        let side = if position.quantity > 0.0 { TradeSide::Buy } else { TradeSide::Sell };
+        let size = locale.format_decimal(position.quantity, 4);
+        levels.push(ChartLevel {
+            id: POSITION_LEVEL,
+            price: position.avg_price,
+            label: format!("{} {}", locale.text(Msg::Position), size),
+            color: side.color(),
+            draggable: false,
+        });
+        if let Some(price) = position.liquidation_price(engine.leverage) {
+            let label = format!("{} {}", locale.text(Msg::Liquidation), engine.leverage.label());
+            levels.push(ChartLevel {
+                id: POSITION_LEVEL,
+                price,
+                label,
+                color: colors::WARN,
+                draggable: false,
+            });
+        }
+    }
+    for order in engine.working(symbol) {
+        let Some(price) = order.price else { continue };
+        let (label, color) = match order.parent {
+            Some((_, leg)) => (locale.text(leg.msg()).to_string(), leg_color(leg)),
+            None => {
+                let kind = locale.text(order.order_type.msg());
+                (format!("{} {}", locale.text(Msg::side(order.side)), kind), order.side.color())
+            }
+        };
+        levels.push(ChartLevel { id: order.id, price, label, color, draggable: true });
+        let pending = [
+            (BracketLeg::TakeProfit, order.bracket.take_profit),
+            (BracketLeg::StopLoss, order.bracket.stop_loss),
+        ];
+        for (leg, level) in pending {
+            let Some(price) = level else { continue };
+            let label = locale.text(leg.msg()).to_string();
+            let color = leg_color(leg);
+            levels.push(ChartLevel { id: order.id, price, label, color, draggable: false });
+        }
+    }
+    levels
+}
+
+/// Open PnL of the position in `symbol` at `last`, with its return on margin,
+/// tagged at `last`
+pub fn position_badge(
+    engine: &PaperEngine,
+    symbol: &Symbol,
+    last: f64,
+    locale: Locale,
+) -> Option<ChartBadge> {
+    let position = engine.position(symbol);
+    if position.is_flat() {
+        return None;
+    }
+    let pnl = position.unrealized_pnl(last);
+    let roe = position.return_on_margin(last, engine.leverage);
+    let color = if pnl > 0.0 {
+        colors::BULL
+    } else if pnl < 0.0 {
+        colors::BEAR
+    } else {
+        colors::TEXT_MUTED
+    };
+    let text = format!("{} ({}%)", format_pnl(locale, pnl), format_pnl(locale, roe));
+    Some(ChartBadge { price: last, text, color })
+}
+
+/// Order entry for the current pair; the bracket outcome is projected from
+/// the entry before the order is sent
+#[component]
+pub fn PaperTicket() -> impl IntoView {
+    let state = use_app_state();
+    let symbol = state.market.symbol;
+    let trades = state.market.trades;
+    let locale = state.locale();
+    let t = move |msg: Msg| locale.get().text(msg);
+
+    let side = RwSignal::new(TradeSide::Buy);
+    let order_type = RwSignal::new(OrderType::Market);
+    let size = RwSignal::new(DEFAULT_SIZE.to_string());
+    let price = RwSignal::new(String::new());
+    let take_profit = RwSignal::new(String::new());
+    let stop_loss = RwSignal::new(String::new());
+    let error = RwSignal::new(None::<Msg>);
+    let paper = state.paper;
+    let leverage = move || paper.with(|engine| engine.leverage);
+
+    let ticket = move || {
+        let mut ticket = OrderTicket::new(
+            symbol.get(),
+            side.get(),
+            size.with(|s| s.trim().parse().unwrap_or(0.0)),
+        );
+        ticket.order_type = order_type.get();
+        ticket.price = price.with(|p| parse_input(p));
+        ticket.bracket = Bracket {
+            take_profit: take_profit.with(|p| parse_input(p)),
+            stop_loss: stop_loss.with(|p| parse_input(p)),
+        };
+        ticket
+    };
+    let last = move || trades.with(|trades| trades.latest().map(|t| t.price.as_f64()));
+
+    // Entry, TP/SL outcome and reward:risk, once a leg is set
+    let projection = move || {
+        let ticket = ticket();
+        if ticket.bracket.is_empty() {
+            return None;
+        }
+        let projection = ticket.projection(last())?;
+        let locale = locale.get();
+        let mut parts = vec![format!("@ {}", locale.format_decimal(projection.entry, 2))];
+        if let Some(pnl) = projection.take_profit {
+            parts.push(format!("{} {}", locale.text(Msg::TakeProfit), format_pnl(locale, pnl)));
+        }
+        if let Some(pnl) = projection.stop_loss {
+            parts.push(format!("{} {}", locale.text(Msg::StopLoss), format_pnl(locale, pnl)));
+        }
+        if let Some(ratio) = projection.reward_risk() {
+            let ratio = locale.format_decimal(ratio, 2);
+            parts.push(format!("{} {}", locale.text(Msg::RewardRisk), ratio));
+        }
+        Some(parts.join(" · "))
+    };
+
+    let set_leverage = {
+        let state = state.clone();
+        move |ev| {
+            let value = event_target_value(&ev);
+            if let Some(leverage) = Leverage::all().iter().find(|l| l.label() == value) {
+                state.set_paper_leverage(*leverage);
+            }
+        }
+    };
+
+    let submit = {
+        let state = state.clone();
+        move |ev: leptos::ev::SubmitEvent| {
+            ev.prevent_default();
+            match state.place_paper_order(&ticket()) {
+                Ok(_) => {
+                    error.set(None);
+                    take_profit.set(String::new());
+                    stop_loss.set(String::new());
+                }
+                Err(e) => error.set(Some(e.msg())),
+            }
+        }
+    };
+
+    let side_button = move |button: TradeSide| {
+        view! {
+            <button
+                type="button"
+                class=move || {
+                    let active = if side.get() == button { " active" } else { "" };
+                    format!("pt-side {}{}", button.css_class(), active)
+                }
+                aria-pressed=move || (side.get() == button).to_string()
+                on:click=move |_| side.set(button)
+            >
+                {move || t(Msg::side(button))}
+            </button>
+        }
+    };
+
+    let input = move |id: &'static str, msg: Msg, value: RwSignal<String>| {
+        view! {
+            <label class="pt-field" for=id>
+                <span class="pt-label">{move || t(msg)}</span>
+                <input
+                    id=id
+                    type="number"
+                    min="0"
+                    step="any"
+                    prop:value=move || value.get()
+                    on:input=move |ev| {
+                        value.set(event_target_value(&ev));
+                        error.set(None);
+                    }
+                />
+            </label>
+        }
+    };
+
+    view! {
+        <form class="paper-ticket" on:submit=submit>
+            <div class="pt-sides" role="group" aria-label=move || t(Msg::Side)>
+                {side_button(TradeSide::Buy)}
+                {side_button(TradeSide::Sell)}
+            </div>
+            <select
+                class="sb-select pt-type"
+                aria-label="Order type"
+                on:change=move |ev| {
+                    let value = event_target_value(&ev);
+                    if let Some(kind) = OrderType::all().iter().find(|k| k.label() == value) {
+                        order_type.set(*kind);
+                    }
+                }
+            >
+                {OrderType::all()
+                    .iter()
+                    .map(|kind| {
+                        let kind = *kind;
+                        view! {
+                            <option value=kind.label() selected=move || order_type.get() == kind>
+                                {move || t(kind.msg())}
+                            </option>
+                        }
+                    })
+                    .collect_view()}
+            </select>
+            <select
+                class="sb-select pt-leverage"
+                aria-label=move || t(Msg::Leverage)
+                title=move || t(Msg::Leverage)
+                on:change=set_leverage
+            >
+                {Leverage::all()
+                    .iter()
+                    .map(|l| {
+                        let l = *l;
+                        view! {
+                            <option value=l.label() selected=move || leverage() == l>
+                                {l.label()}
+                            </option>
+                        }
+                    })
+                    .collect_view()}
+            </select>
+            {input("pt-size", Msg::Size, size)}
+            <Show when=move || order_type.get() != OrderType::Market>
+                {input("pt-price", Msg::Price, price)}
+            </Show>
+            {input("pt-take-profit", Msg::TakeProfit, take_profit)}
+            {input("pt-stop-loss", Msg::StopLoss, stop_loss)}
+            <div class="pt-projection" aria-live="polite">{projection}</div>
+            {move || {
+                error.get().map(|msg| view! { <div class="pt-error" role="alert">{t(msg)}</div> })
+            }}
+            <button type="submit" class=move || format!("pt-submit {}", side.get().css_class())>
+                {move || format!("{} {}", t(Msg::PlaceOrder), t(Msg::side(side.get())))}
+            </button>
+        </form>
+    }
+}
+
+/// Position in the current pair and its working orders, each cancellable
+#[component]
+pub fn PaperOrders() -> impl IntoView {
+    let state = use_app_state();
+    let paper = state.paper;
+    let symbol = state.market.symbol;
+    let trades = state.market.trades;
+    let locale = state.locale();
+    let t = move |msg: Msg| locale.get().text(msg);
+
+    let position = move || {
+        let position = paper.with(|engine| symbol.with(|s| engine.position(s)));
+        let locale = locale.get();
+        let open = trades
+            .with(|trades| trades.latest().map(|t| position.unrealized_pnl(t.price.as_f64())))
+            .unwrap_or(0.0);
+        let size = if position.is_flat() {
+            "—".to_string()
+        } else {
+            let avg = locale.format_decimal(position.avg_price, 2);
+            format!("{} @ {}", locale.format_decimal(position.quantity, 4), avg)
+        };
+        view! {
+            <div class="pt-position">
+                <span class="pt-label">{locale.text(Msg::Position)}</span>
+                <span class="pt-value">{size}</span>
+                <span class="pt-label">{locale.text(Msg::OpenPnl)}</span>
+                <span class=pnl_class(open)>
+                    {format_pnl(locale, open)}
+                </span>
+                <span class="pt-label">{locale.text(Msg::RealizedPnl)}</span>
+                <span class=pnl_class(position.realized_pnl)>
+                    {format_pnl(locale, position.realized_pnl)}
+                </span>
+            </div>
+        }
+    };
+
+    let orders = move || {
+        paper.with(|engine| {
+            symbol.with(|s| engine.working(s).into_iter().cloned().collect::<Vec<_>>())
+        })
+    };
+
+    view! {
+        {position}
+        <ul class="pt-orders" aria-label=move || t(Msg::WorkingOrders)>
+            {move || {
+                let orders = orders();
+                if orders.is_empty() {
+                    return view! { <li class="pt-empty">{move || t(Msg::NoOrders)}</li> }
+                        .into_any();
+                }
+                orders
+                    .into_iter()
+                    .map(|order| {
+                        let state = state.clone();
+                        let locale = locale.get_untracked();
+                        let kind = match order.parent {
+                            Some((_, leg)) => locale.text(leg.msg()),
+                            None => locale.text(order.order_type.msg()),
+                        };
+                        let level = order
+                            .price
+                            .map(|p| locale.format_decimal(p, 2))
+                            .unwrap_or_default();
+                        let id = order.id;
+                        view! {
+                            <li class="pt-order">
+                                <span class=order.side.css_class()>
+                                    {locale.text(Msg::side(order.side))}
+                                </span>
+                                <span>{kind}</span>
+                                <span>{locale.format_decimal(order.quantity, 4)}</span>
+                                <span>{level}</span>
+                                <button
+                                    class="pt-cancel"
+                                    title=locale.text(Msg::CancelOrder)
+                                    aria-label=locale.text(Msg::CancelOrder)
+                                    on:click=move |_| {
+                                        let _ = state.cancel_paper_order(id);
+                                    }
+                                >
+                                    "×"
+                                </button>
+                            </li>
+                        }
+                    })
+                    .collect_view()
+                    .into_any()
+            }}
+        </ul>
+    }
+}
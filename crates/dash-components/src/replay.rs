@@ -0,0 +1,92 @@
+//! Time-travel scrubber for reviewing buffered market data
+
+use chrono::{TimeZone, Utc};
+use dash_core::{Locale, Msg};
+use dash_state::{use_locale, MarketState};
+use leptos::prelude::*;
+
+/// Timeline slider that pauses live updates and scrubs through history
+#[component]
+pub fn ReplayScrubber(#[prop(into)] market: MarketState) -> impl IntoView {
+    let replay = market.replay;
+    let history = market.book_history;
+    let locale = use_locale();
+
+    let time_range = move || history.with(|h| h.time_range());
+    let position = move || {
+        replay
+            .get()
+            .position()
+            .or_else(|| time_range().map(|(_, newest)| newest))
+    };
+
+    let toggle = {
+        let market = market.clone();
+        move |_| {
+            if replay.get_untracked().is_live() {
+                market.pause_live();
+            } else {
+                market.resume_live();
+            }
+        }
+    };
+
+    let scrub = move |ev| {
+        if let Ok(ts) = event_target_value(&ev).parse::<i64>() {
+            market.scrub_to(ts);
+        }
+    };
+
+    view! {
+        <div class=move || {
+            if replay.get().is_live() { "replay-bar live" } else { "replay-bar paused" }
+        }>
+            <button class="replay-toggle" on:click=toggle>
+                {move || {
+                    let locale = locale.get();
+                    if replay.get().is_live() {
+                        format!("⏸ {}", locale.text(Msg::Live))
+                    } else {
+                        format!("▶ {}", locale.text(Msg::Resume))
+                    }
+                }}
+            </button>
+
+            <input
+                class="replay-slider"
+                type="range"
+                step="1"
+                min=move || time_range().map_or(0, |(oldest, _)| oldest)
+                max=move || time_range().map_or(0, |(_, newest)| newest)
+                prop:value=move || position().unwrap_or_default()
+                disabled=move || time_range().is_none()
+                on:input=scrub
+            />
+
+            <span
+                class="replay-time"
+                title=move || position().map(|ts| format_replay_date(locale.get(), ts)).unwrap_or_default()
+            >
+                {move || position().map(format_replay_time).unwrap_or_else(|| "--:--:--".into())}
+            </span>
+            <span class="replay-mode">
+                {move || locale.get().text(if replay.get().is_live() { Msg::Live } else { Msg::Replay })}
+            </span>
+        </div>
+    }
+}
+
+/// Full date and time for the tooltip
+fn format_replay_date(locale: Locale, ts: i64) -> String {
+    Utc.timestamp_millis_opt(ts)
+        .single()
+        .map(|t| locale.format_datetime(&t, true))
+        .unwrap_or_default()
+}
+
+fn format_replay_time(ts: i64) -> String {
+    Utc.timestamp_millis_opt(ts)
+        .single()
+        .map(|t| t.format("%H:%M:%S%.3f").to_string())
+        .unwrap_or_default()
+}
@@ -0,0 +1,68 @@
+//! Risk panel: exposure of the open paper positions and the journal's
+//! drawdown, Sharpe, win rate and average R
+
+use dash_core::{Locale, Msg};
+use dash_state::use_app_state;
+use leptos::prelude::*;
+
+use crate::paper::pnl_class;
+
+/// `value` with `decimals` places and `suffix`, or a dash until there is one
+fn format_metric(locale: Locale, value: Option<f64>, decimals: usize, suffix: &str) -> String {
+    match value {
+        Some(v) => format!("{}{}", locale.format_decimal(v, decimals), suffix),
+        None => "—".to_string(),
+    }
+}
+
+/// Exposure, max drawdown, Sharpe, win rate, average R and trade count
+#[component]
+pub fn RiskSummary() -> impl IntoView {
+    let state = use_app_state();
+    let paper = state.paper;
+    let journal = state.journal;
+    let symbol = state.market.symbol;
+    let trades = state.market.trades;
+    let locale = state.locale();
+    let t = move |msg: Msg| locale.get().text(msg);
+
+    // Current pair at its last print, others at cost
+    let exposure = move || {
+        let last = trades.with(|trades| trades.latest().map(|t| t.price.as_f64()));
+        let exposure = paper.with(|engine| {
+            symbol.with(|current| engine.exposure(|s| if s == current { last } else { None }))
+        });
+        locale.get().format_decimal(exposure, 2)
+    };
+
+    // Only changes when a fill is journaled
+    let metrics = Memo::new(move |_| journal.with(|j| *j.metrics()));
+    let drawdown = move || metrics.get().max_drawdown();
+    let average_r = move || metrics.get().average_r();
+    let metric = move |value: Option<f64>, decimals: usize, suffix: &str| {
+        format_metric(locale.get(), value, decimals, suffix)
+    };
+
+    view! {
+        <div class="risk-metrics">
+            <span class="pt-label">{move || t(Msg::Exposure)}</span>
+            <span class="pt-value">{exposure}</span>
+            <span class="pt-label">{move || t(Msg::MaxDrawdown)}</span>
+            <span class=move || pnl_class(-drawdown())>
+                {move || metric(Some(drawdown()), 2, "")}
+            </span>
+            <span class="pt-label">{move || t(Msg::Sharpe)}</span>
+            <span class="pt-value">{move || metric(metrics.get().sharpe(), 2, "")}</span>
+            <span class="pt-label">{move || t(Msg::WinRate)}</span>
+            <span class="pt-value">
+                {move || metric(metrics.get().win_rate().map(|r| r * 100.0), 1, "%")}
+            </span>
+            <span class="pt-label">{move || t(Msg::AverageR)}</span>
+            <span class=move || pnl_class(average_r().unwrap_or(0.0))>
+                {move || metric(average_r(), 2, "R")}
+            </span>
+            <span class="pt-label">{move || t(Msg::Trades)}</span>
+            <span class="pt-value">{move || metrics.get().trades()}</span>
+        </div>
+    }
+}
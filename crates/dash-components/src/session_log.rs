@@ -0,0 +1,250 @@
+//! Session log persistence and export
+//!
+//! Stores the opt-in session log in IndexedDB so it survives reloads, and
+//! exports it as a JSON file users can attach to issue reports. The opt-in
+//! itself is kept in localStorage so recording resumes on the next visit.
+
+use chrono::Utc;
+use dash_core::{ConnectionState, Msg, Symbol};
+use dash_state::{
+    export_session_log, use_app_state, AppState, DashEvent, DashEventKind, SessionEvent,
+    SessionLog, SessionLogEntry,
+};
+use leptos::{prelude::*, task::spawn_local};
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{IdbDatabase, IdbObjectStore, IdbObjectStoreParameters, IdbTransactionMode};
+
+use crate::idb::{open_database, request_result};
+use crate::share::download_blob;
+
+const DB_NAME: &str = "dash-session-log";
+const DB_VERSION: u32 = 1;
+const STORE_NAME: &str = "events";
+
+/// localStorage key holding the opt-in
+const OPT_IN_KEY: &str = "dash.session-log";
+
+/// IndexedDB object store of serialized `SessionLogEntry` values
+#[derive(Clone)]
+struct SessionStore {
+    db: IdbDatabase,
+}
+
+impl SessionStore {
+    async fn open() -> Result<Self, JsValue> {
+        let db = open_database(DB_NAME, DB_VERSION, |db| {
+            let params = IdbObjectStoreParameters::new();
+            params.set_auto_increment(true);
+            db.create_object_store_with_optional_parameters(STORE_NAME, &params)?;
+            Ok(())
+        })
+        .await?;
+        Ok(Self { db })
+    }
+
+    fn store(&self, mode: IdbTransactionMode) -> Result<IdbObjectStore, JsValue> {
+        self.db.transaction_with_str_and_mode(STORE_NAME, mode)?.object_store(STORE_NAME)
+    }
+
+    fn append(&self, entries: &[SessionLogEntry]) -> Result<(), JsValue> {
+        let store = self.store(IdbTransactionMode::Readwrite)?;
+        for entry in entries {
+            if let Ok(json) = serde_json::to_string(entry) {
+                store.add(&JsValue::from_str(&json))?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn load(&self) -> Result<Vec<SessionLogEntry>, JsValue> {
+        let request = self.store(IdbTransactionMode::Readonly)?.get_all()?;
+        let values: js_sys::Array = request_result(&request).await?.dyn_into()?;
+        Ok(values
+            .iter()
+            .filter_map(|value| value.as_string())
+            .filter_map(|json| serde_json::from_str(&json).ok())
+            .collect())
+    }
+
+    fn clear(&self) -> Result<(), JsValue> {
+        self.store(IdbTransactionMode::Readwrite)?.clear()?;
+        Ok(())
+    }
+}
+
+fn opted_in() -> bool {
+    let storage = window().local_storage().ok().flatten();
+    storage.and_then(|s| s.get_item(OPT_IN_KEY).ok().flatten()).as_deref() == Some("1")
+}
+
+fn remember_opt_in(enabled: bool) {
+    if let Ok(Some(storage)) = window().local_storage() {
+        let _ = if enabled {
+            storage.set_item(OPT_IN_KEY, "1")
+        } else {
+            storage.remove_item(OPT_IN_KEY)
+        };
+    }
+}
+
+/// Record symbol switches and connection drops, and persist the session log
+/// while the user has opted in
+pub fn track_session_log(state: &AppState) {
+    let log = state.session_log;
+    let store = StoredValue::new_local(None::<SessionStore>);
+
+    if opted_in() {
+        state.set_session_logging(true);
+    }
+
+    // Open the database on opt-in, pull in earlier sessions, and wipe it on opt-out
+    let enabled = Memo::new(move |_| log.with(|l| l.is_enabled()));
+    Effect::new(move |previous: Option<bool>| {
+        let enabled = enabled.get();
+        if previous.is_some() {
+            remember_opt_in(enabled);
+        }
+        if !enabled {
+            if let Some(db) = store.get_value() {
+                let _ = db.clear();
+            }
+            return enabled;
+        }
+        if store.with_value(|s| s.is_none()) {
+            spawn_local(async move {
+                match SessionStore::open().await {
+                    Ok(db) => {
+                        let stored = db.load().await.unwrap_or_default();
+                        store.set_value(Some(db));
+                        log.update(|l| l.restore(stored));
+                    }
+                    Err(err) => tracing::warn!(?err, "session log storage unavailable"),
+                }
+            });
+        }
+        enabled
+    });
+
+    // Write new entries through once the database is open
+    Effect::new(move |_| {
+        if !log.with(|l| l.has_pending()) {
+            return;
+        }
+        let Some(db) = store.get_value() else { return };
+        let entries = log.try_update_untracked(|l| l.take_pending()).unwrap_or_default();
+        if let Err(err) = db.append(&entries) {
+            tracing::warn!(?err, "failed to persist session log");
+        }
+    });
+
+    let symbol = state.market.symbol;
+    let logger = state.clone();
+    Effect::new(move |previous: Option<Symbol>| {
+        let current = symbol.get();
+        if let Some(previous) = previous
+            && previous != current
+        {
+            logger.log_session_event(SessionEvent::SymbolSwitch {
+                from: previous.to_string(),
+                to: current.to_string(),
+            });
+        }
+        current
+    });
+
+    let logger = state.clone();
+    state.events.subscribe(Some(DashEventKind::AlertTriggered), move |event| {
+        if let DashEvent::AlertTriggered { message, .. } = event {
+            logger.log_session_event(SessionEvent::Alert { message: message.clone() });
+        }
+    });
+
+    // (state, when the connection dropped)
+    let connection = state.connection;
+    let logger = state.clone();
+    Effect::new(move |previous: Option<(ConnectionState, Option<i64>)>| {
+        let current = connection.get();
+        let now = Utc::now().timestamp_millis();
+        let (was, down_since) = previous.unwrap_or((current, None));
+        let down_since = match (was.is_connected(), current) {
+            (true, ConnectionState::Disconnected | ConnectionState::Reconnecting) => {
+                logger.log_session_event(SessionEvent::ConnectionDrop {
+                    state: current.label().to_string(),
+                });
+                Some(now)
+            }
+            (false, ConnectionState::Connected) => {
+                if let Some(since) = down_since {
+                    logger.log_session_event(SessionEvent::Reconnected { down_ms: now - since });
+                }
+                None
+            }
+            _ => down_since,
+        };
+        (current, down_since)
+    });
+
+    on_cleanup(move || {
+        if let Some(db) = store.get_value() {
+            db.db.close();
+        }
+    });
+}
+
+/// Download `contents` as a JSON file named `file_name`
+fn download_json(file_name: &str, contents: &str) -> Result<(), JsValue> {
+    let parts = js_sys::Array::of1(&JsValue::from_str(contents));
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type("application/json");
+    let blob = web_sys::Blob::new_with_str_sequence_and_options(&parts, &options)?;
+    download_blob(file_name, &blob)
+}
+
+/// Save the session log as a timestamped JSON file
+pub fn download_session_log(log: RwSignal<SessionLog>) {
+    let entries: Vec<SessionLogEntry> = log.with_untracked(|l| l.entries().cloned().collect());
+    let user_agent = window().navigator().user_agent().ok();
+    let now = Utc::now();
+    let json = export_session_log(&entries, now.timestamp_millis(), user_agent.as_deref());
+    let file_name = format!("session-log-{}.json", now.format("%Y%m%d-%H%M%S"));
+    if let Err(err) = download_json(&file_name, &json) {
+        tracing::warn!(?err, "session log export failed");
+    }
+}
+
+/// Opt-in checkbox and export button for the status bar
+#[component]
+pub fn SessionLogControls() -> impl IntoView {
+    let state = use_app_state();
+    let log = state.session_log;
+    let locale = state.locale();
+    let t = move |msg: Msg| locale.get().text(msg);
+
+    let toggle = move |_| {
+        let enabled = log.with_untracked(|l| l.is_enabled());
+        state.set_session_logging(!enabled);
+    };
+    let export = move |_| download_session_log(log);
+
+    let hint = "Keep a local timeline of connection drops, symbol switches and latency spikes";
+
+    view! {
+        <div class="sb-session-log">
+            <label class="sb-toggle" title=hint>
+                <input
+                    type="checkbox"
+                    prop:checked=move || log.with(|l| l.is_enabled())
+                    on:change=toggle
+                />
+                {move || t(Msg::SessionLog)}
+            </label>
+            <button
+                class="sb-button"
+                prop:disabled=move || log.with(|l| l.is_empty())
+                on:click=export
+            >
+                {move || format!("{} ({})", t(Msg::Export), log.with(|l| l.len()))}
+            </button>
+        </div>
+    }
+}
@@ -0,0 +1,292 @@
+//! Chart snapshot sharing
+//!
+//! Composes the candle chart and a ticker caption into a PNG off-screen:
+//! the live chart SVG is serialized into an image, drawn onto a detached
+//! canvas under the caption, and stamped with the user's watermark. The
+//! result is copied to the clipboard, or downloaded where the clipboard
+//! refuses images.
+
+use chrono::Utc;
+use dash_core::{colors, Msg};
+use dash_state::{snapshot_file_name, use_app_state, AppState, SnapshotCaption};
+use leptos::{prelude::*, task::spawn_local};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::{future_to_promise, JsFuture};
+use web_sys::{Blob, CanvasRenderingContext2d, Element, HtmlCanvasElement, HtmlImageElement};
+
+/// Chart SVG captured by a snapshot
+const CHART_SELECTOR: &str = ".chart-container svg.candlestick-chart";
+
+/// Caption band above the chart (CSS px)
+const CAPTION_HEIGHT: f64 = 46.0;
+
+/// Device pixels per CSS pixel in the image, whatever the screen
+const SNAPSHOT_SCALE: f64 = 2.0;
+
+/// How long the "Copied" / "Saved" note stays (ms)
+const NOTE_MS: u64 = 2_000;
+
+const FONT: &str = "JetBrains Mono, monospace";
+
+/// Where a snapshot goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ShareTarget {
+    Clipboard,
+    Download,
+}
+
+/// Settles once `image` loads or fails to
+fn image_loaded(image: &HtmlImageElement) -> js_sys::Promise {
+    js_sys::Promise::new(&mut |resolve, reject| {
+        image.set_onload(Some(&resolve));
+        image.set_onerror(Some(&reject));
+    })
+}
+
+/// Rasterizable copy of the chart SVG at its on-screen size
+async fn chart_image(svg: &Element, width: f64, height: f64) -> Result<HtmlImageElement, JsValue> {
+    let copy: Element = svg.clone_node_with_deep(true)?.dyn_into()?;
+    copy.set_attribute("xmlns", "http://www.w3.org/2000/svg")?;
+    copy.set_attribute("width", &width.to_string())?;
+    copy.set_attribute("height", &height.to_string())?;
+    copy.remove_attribute("style")?;
+    let markup = web_sys::XmlSerializer::new()?.serialize_to_string(&copy)?;
+
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type("image/svg+xml;charset=utf-8");
+    let parts = js_sys::Array::of1(&JsValue::from_str(&markup));
+    let blob = Blob::new_with_str_sequence_and_options(&parts, &options)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob)?;
+
+    let image = HtmlImageElement::new()?;
+    let loaded = image_loaded(&image);
+    image.set_src(&url);
+    let result = JsFuture::from(loaded).await;
+    web_sys::Url::revoke_object_url(&url)?;
+    result.map(|_| image)
+}
+
+/// Caption band: title and time on top, ticker stats below
+fn draw_caption(ctx: &CanvasRenderingContext2d, caption: &SnapshotCaption, width: f64) {
+    ctx.set_text_baseline("middle");
+    ctx.set_text_align("left");
+    ctx.set_fill_style_str(colors::TEXT_PRIMARY);
+    ctx.set_font(&format!("600 14px {}", FONT));
+    let _ = ctx.fill_text(&caption.title, 12.0, 15.0);
+
+    ctx.set_text_align("right");
+    ctx.set_fill_style_str(colors::TEXT_MUTED);
+    ctx.set_font(&format!("11px {}", FONT));
+    let _ = ctx.fill_text(&caption.taken_at, width - 12.0, 15.0);
+
+    ctx.set_text_align("left");
+    let mut x = 12.0;
+    for (i, (label, value)) in caption.stats.iter().enumerate() {
+        ctx.set_fill_style_str(colors::TEXT_MUTED);
+        let _ = ctx.fill_text(label, x, 34.0);
+        x += ctx.measure_text(label).map(|m| m.width()).unwrap_or(0.0) + 6.0;
+        // The second stat is the 24h change
+        let color = match i {
+            1 if caption.up => colors::BULL,
+            1 => colors::BEAR,
+            _ => colors::TEXT_PRIMARY,
+        };
+        ctx.set_fill_style_str(color);
+        let _ = ctx.fill_text(value, x, 34.0);
+        x += ctx.measure_text(value).map(|m| m.width()).unwrap_or(0.0) + 16.0;
+    }
+}
+
+/// Encode `canvas` as PNG
+async fn png_blob(canvas: &HtmlCanvasElement) -> Result<Blob, JsValue> {
+    let encoded = js_sys::Promise::new(&mut |resolve, reject| {
+        if let Err(err) = canvas.to_blob(&resolve) {
+            let _ = reject.call1(&JsValue::NULL, &err);
+        }
+    });
+    // toBlob hands over null when encoding fails
+    JsFuture::from(encoded).await?.dyn_into()
+}
+
+/// Compose the chart under `caption`, stamped with `watermark`, as a PNG
+pub async fn compose_snapshot(
+    svg: Element,
+    caption: SnapshotCaption,
+    watermark: Option<String>,
+) -> Result<Blob, JsValue> {
+    let rect = svg.get_bounding_client_rect();
+    let (width, chart_height) = (rect.width().round(), rect.height().round());
+    if width <= 0.0 || chart_height <= 0.0 {
+        return Err(JsValue::from_str("chart is not laid out"));
+    }
+    let image = chart_image(&svg, width, chart_height).await?;
+
+    let canvas: HtmlCanvasElement = document().create_element("canvas")?.dyn_into()?;
+    let height = CAPTION_HEIGHT + chart_height;
+    canvas.set_width((width * SNAPSHOT_SCALE) as u32);
+    canvas.set_height((height * SNAPSHOT_SCALE) as u32);
+    let ctx: CanvasRenderingContext2d = canvas
+        .get_context("2d")?
+        .ok_or_else(|| JsValue::from_str("no 2d context"))?
+        .dyn_into()?;
+    ctx.scale(SNAPSHOT_SCALE, SNAPSHOT_SCALE)?;
+
+    ctx.set_fill_style_str(colors::BG_PANEL);
+    ctx.fill_rect(0.0, 0.0, width, height);
+    draw_caption(&ctx, &caption, width);
+    ctx.draw_image_with_html_image_element_and_dw_and_dh(
+        &image,
+        0.0,
+        CAPTION_HEIGHT,
+        width,
+        chart_height,
+    )?;
+
+    if let Some(watermark) = watermark {
+        ctx.set_global_alpha(0.35);
+        ctx.set_fill_style_str(colors::TEXT_PRIMARY);
+        ctx.set_font(&format!("600 13px {}", FONT));
+        ctx.set_text_align("right");
+        ctx.set_text_baseline("bottom");
+        let _ = ctx.fill_text(&watermark, width - 12.0, height - 10.0);
+        ctx.set_global_alpha(1.0);
+    }
+    png_blob(&canvas).await
+}
+
+/// Put a PNG (a promise of a `Blob`) on the clipboard. Browsers only allow
+/// the write during a user gesture, hence the promise rather than a
+/// finished image. `ClipboardItem` is not in web-sys' stable API, so it is
+/// reached through the global object.
+fn copy_png(png: &js_sys::Promise) -> Result<js_sys::Promise, JsValue> {
+    let navigator = window().navigator();
+    let clipboard = js_sys::Reflect::get(&navigator, &JsValue::from_str("clipboard"))?;
+    let item_class = js_sys::Reflect::get(&js_sys::global(), &JsValue::from_str("ClipboardItem"))?;
+    if clipboard.is_undefined() || item_class.is_undefined() {
+        return Err(JsValue::from_str("clipboard images unsupported"));
+    }
+    let parts = js_sys::Object::new();
+    js_sys::Reflect::set(&parts, &JsValue::from_str("image/png"), png)?;
+    let item = js_sys::Reflect::construct(item_class.unchecked_ref(), &js_sys::Array::of1(&parts))?;
+    let clipboard: web_sys::Clipboard = clipboard.unchecked_into();
+    Ok(clipboard.write(&js_sys::Array::of1(&item)))
+}
+
+/// Save `blob` as a file named `file_name`
+pub fn download_blob(file_name: &str, blob: &Blob) -> Result<(), JsValue> {
+    let url = web_sys::Url::create_object_url_with_blob(blob)?;
+    let link: web_sys::HtmlAnchorElement = document().create_element("a")?.dyn_into()?;
+    link.set_href(&url);
+    link.set_download(file_name);
+    link.click();
+    web_sys::Url::revoke_object_url(&url)
+}
+
+/// Start a snapshot of the chart on screen bound for `target`. Composing
+/// and any clipboard write begin before this returns, inside the click that
+/// asked for them; the future resolves to where the image actually went.
+fn share_snapshot(
+    state: &AppState,
+    target: ShareTarget,
+) -> impl Future<Output = Result<ShareTarget, JsValue>> + use<> {
+    let market = &state.market;
+    let (symbol, interval) = (market.symbol.get_untracked(), market.interval.get_untracked());
+    let taken_at = Utc::now();
+    let locale = state.ui.with_untracked(|ui| ui.locale);
+    let caption = market.ticker.with_untracked(|ticker| {
+        SnapshotCaption::new(locale, &symbol, interval, ticker.as_ref(), taken_at)
+    });
+    let watermark = state.share.with_untracked(|s| s.watermark().map(str::to_string));
+    let file_name = snapshot_file_name(&symbol, interval, taken_at);
+
+    let started = document().query_selector(CHART_SELECTOR).and_then(|svg| {
+        let svg = svg.ok_or_else(|| JsValue::from_str("no chart on screen"))?;
+        let png = future_to_promise(async move {
+            compose_snapshot(svg, caption, watermark).await.map(JsValue::from)
+        });
+        let written = (target == ShareTarget::Clipboard).then(|| copy_png(&png));
+        Ok((png, written))
+    });
+
+    async move {
+        let (png, written) = started?;
+        match written {
+            Some(Ok(written)) => match JsFuture::from(written).await {
+                Ok(_) => return Ok(ShareTarget::Clipboard),
+                Err(err) => tracing::debug!(?err, "clipboard refused the snapshot"),
+            },
+            Some(Err(err)) => tracing::debug!(?err, "clipboard unavailable"),
+            None => {}
+        }
+        let blob: Blob = JsFuture::from(png).await?.dyn_into()?;
+        download_blob(&file_name, &blob)?;
+        Ok(ShareTarget::Download)
+    }
+}
+
+/// Save a snapshot of the chart on screen as PNG, without the button's note
+pub fn save_chart_snapshot(state: &AppState) {
+    let saved = share_snapshot(state, ShareTarget::Download);
+    spawn_local(async move {
+        if let Err(err) = saved.await {
+            tracing::warn!(?err, "chart snapshot failed");
+        }
+    });
+}
+
+/// Copy / save buttons for the chart panel header
+#[component]
+pub fn ShareSnapshot() -> impl IntoView {
+    let state = use_app_state();
+    let locale = state.locale();
+    let note = RwSignal::new(None::<Msg>);
+    let busy = RwSignal::new(false);
+
+    let share = move |target: ShareTarget| {
+        if busy.get_untracked() {
+            return;
+        }
+        busy.set(true);
+        let shared = share_snapshot(&state, target);
+        spawn_local(async move {
+            match shared.await {
+                Ok(ShareTarget::Clipboard) => note.set(Some(Msg::Copied)),
+                Ok(ShareTarget::Download) => note.set(Some(Msg::Saved)),
+                Err(err) => tracing::warn!(?err, "chart snapshot failed"),
+            }
+            busy.set(false);
+            set_timeout(move || note.set(None), std::time::Duration::from_millis(NOTE_MS));
+        });
+    };
+    let copy = {
+        let share = share.clone();
+        move |_| share(ShareTarget::Clipboard)
+    };
+    let save = move |_| share(ShareTarget::Download);
+
+    view! {
+        <div class="share-snapshot">
+            {move || note.get().map(|msg| view! {
+                <span class="share-note" role="status">{move || locale.get().text(msg)}</span>
+            })}
+            <button
+                class="share-btn"
+                title="Copy a snapshot of the chart to the clipboard"
+                aria-label="Copy chart snapshot"
+                prop:disabled=move || busy.get()
+                on:click=copy
+            >
+                "⧉"
+            </button>
+            <button
+                class="share-btn"
+                title="Save a snapshot of the chart as PNG"
+                aria-label="Save chart snapshot"
+                prop:disabled=move || busy.get()
+                on:click=save
+            >
+                "⤓"
+            </button>
+        </div>
+    }
+}
@@ -0,0 +1,82 @@
+//! Slippage calculator widget
+//!
+//! Simulates a market order of user-entered size against the live book.
+
+use dash_core::{Locale, MarketImpact, Msg, TradeSide};
+use dash_state::{use_locale, MarketState};
+use leptos::prelude::*;
+
+/// Default order size shown before the user types anything
+const DEFAULT_SIZE: &str = "1.0";
+
+/// Market impact widget showing simulated fills on both sides
+#[component]
+pub fn SlippageCalculator(#[prop(into)] market: MarketState) -> impl IntoView {
+    let orderbook = market.orderbook;
+    let locale = use_locale();
+    let t = move |msg: Msg| locale.get().text(msg);
+    let (size_input, set_size_input) = signal(DEFAULT_SIZE.to_string());
+
+    let size = move || {
+        size_input
+            .get()
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|s| s.is_finite() && *s > 0.0)
+    };
+
+    let impact = move |side: TradeSide| {
+        size().and_then(|qty| orderbook.get().and_then(|book| book.market_impact(side, qty)))
+    };
+
+    view! {
+        <div class="slippage-calc">
+            <div class="sc-input">
+                <label class="sc-label" for="sc-size">{move || t(Msg::Size)}</label>
+                <input
+                    id="sc-size"
+                    type="number"
+                    min="0"
+                    step="any"
+                    prop:value=move || size_input.get()
+                    on:input=move |ev| set_size_input.set(event_target_value(&ev))
+                />
+            </div>
+
+            <div class="sc-header">
+                <span class="sc-col">{move || t(Msg::Side)}</span>
+                <span class="sc-col">{move || t(Msg::AvgPrice)}</span>
+                <span class="sc-col">{move || t(Msg::Slippage)}</span>
+                <span class="sc-col">{move || t(Msg::Levels)}</span>
+            </div>
+
+            {move || view! { <ImpactRow side=TradeSide::Buy impact=impact(TradeSide::Buy) locale=locale.get() /> }}
+            {move || view! { <ImpactRow side=TradeSide::Sell impact=impact(TradeSide::Sell) locale=locale.get() /> }}
+        </div>
+    }
+}
+
+#[component]
+fn ImpactRow(side: TradeSide, impact: Option<MarketImpact>, locale: Locale) -> impl IntoView {
+    let (avg, slippage, levels, partial) = match impact {
+        Some(i) => (
+            locale.format_decimal(i.avg_price, 2),
+            format!("{} bps", locale.format_decimal(i.slippage_bps, 1)),
+            i.levels_consumed.to_string(),
+            !i.fully_filled,
+        ),
+        None => ("—".to_string(), "—".to_string(), "—".to_string(), false),
+    };
+
+    view! {
+        <div class=move || if partial { "sc-row partial" } else { "sc-row" }>
+            <span class="sc-col" style=format!("color: {}", side.color())>{locale.text(Msg::side(side))}</span>
+            <span class="sc-col">{avg}</span>
+            <span class="sc-col">{slippage}</span>
+            <span class="sc-col" title=if partial { locale.text(Msg::BookDepthExhausted) } else { "" }>
+                {levels}
+            </span>
+        </div>
+    }
+}
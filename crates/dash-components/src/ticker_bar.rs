@@ -1,7 +1,7 @@
 //! Ticker bar component for dashboard header
 
-use dash_core::{colors, ConnectionState, Ticker};
-use dash_state::MarketState;
+use dash_core::{colors, format_countdown, ConnectionState, Locale, Msg};
+use dash_state::{use_locale, MarketComputed, MarketState, TickerFields};
 use leptos::prelude::*;
 
 #[derive(Debug, Clone)]
@@ -28,14 +28,27 @@ pub fn TickerBar(
     #[prop(into)] market: MarketState,
     #[prop(into)] connection: Signal<ConnectionState>,
     #[prop(optional)] config: Option<TickerBarConfig>,
+    /// Server time for the candle-close countdown (hidden when absent)
+    #[prop(optional, into)] now_ms: Option<Signal<i64>>,
+    /// Show the mid, microprice and depth-weighted mid (hidden when absent)
+    #[prop(optional, into)] book_prices: Option<Signal<bool>>,
 ) -> impl IntoView {
     let config = config.unwrap_or_default();
     let show_volume = config.show_volume;
     let show_high_low = config.show_high_low;
     let show_spread = config.show_spread;
 
-    let ticker = market.ticker;
+    let fields = TickerFields::new(&market);
+    let computed = book_prices.map(|shown| (shown, MarketComputed::new(&market)));
     let symbol = market.symbol;
+    let interval = market.interval;
+    let locale = use_locale();
+    let t = move |msg: Msg| locale.get().text(msg);
+    let price = move |p: f64| locale.get().format_decimal(p, 2);
+
+    // Direction styling shared by every change-dependent node
+    let direction_color = move || if fields.is_up.get() { colors::BULL } else { colors::BEAR };
+    let direction_style = move || format!("color: {}", direction_color());
 
     view! {
         <div class="ticker-bar">
@@ -45,115 +58,158 @@ pub fn TickerBar(
             </div>
 
             <div class="tb-price">
-                {move || {
-                    ticker.get().map(|t| {
-                        let color = t.color();
-                        let arrow = t.arrow();
-                        view! {
-                            <span class="price-value" style=format!("color: {}", color)>
-                                {format!("{:.2}", t.last_price.as_f64())}
-                            </span>
-                            <span class="price-change" style=format!("color: {}", color)>
-                                {arrow} " " {t.change_percent_str()}
-                            </span>
-                        }
-                    })
-                }}
+                <span class="price-value" style=direction_style>
+                    {move || fmt_field(fields.last_price.get(), price)}
+                </span>
+                <span class="price-change" style=direction_style>
+                    {move || if fields.is_up.get() { "▲" } else { "▼" }}
+                    " "
+                    {move || fmt_field(fields.change_percent_24h.get(), |p| format!("{}{}%", sign(p), price(p)))}
+                </span>
             </div>
 
             <div class="tb-stats">
-                {move || {
-                    let t = ticker.get()?;
-                    let color = t.color();
-                    Some(view! {
+                <div class="tb-stat">
+                    <span class="stat-label">{move || t(Msg::Change24h)}</span>
+                    <span class="stat-value" style=direction_style>
+                        {move || fmt_field(fields.change_24h.get(), |c| format!("{}{}", sign(c), price(c)))}
+                    </span>
+                </div>
+
+                {show_high_low.then(|| view! {
+                    <div class="tb-stat">
+                        <span class="stat-label">{move || t(Msg::High24h)}</span>
+                        <span class="stat-value" style=format!("color: {}", colors::BULL)>
+                            {move || fmt_field(fields.high_24h.get(), price)}
+                        </span>
+                    </div>
+                    <div class="tb-stat">
+                        <span class="stat-label">{move || t(Msg::Low24h)}</span>
+                        <span class="stat-value" style=format!("color: {}", colors::BEAR)>
+                            {move || fmt_field(fields.low_24h.get(), price)}
+                        </span>
+                    </div>
+                })}
+
+                {show_volume.then(|| view! {
+                    <div class="tb-stat">
+                        <span class="stat-label">{move || t(Msg::Volume24h)}</span>
+                        <span class="stat-value">
+                            {move || fmt_field(fields.volume_24h.get(), |v| format_volume(locale.get(), v))}
+                        </span>
+                    </div>
+                })}
+
+                {now_ms.map(|now_ms| view! {
+                    <div class="tb-stat">
+                        <span class="stat-label">
+                            {move || format!("{} {}", t(Msg::CandleClose), interval.get().label())}
+                        </span>
+                        <span class="stat-value candle-countdown">
+                            {move || {
+                                let now = now_ms.get();
+                                format_countdown(interval.get().next_close(now) - now)
+                            }}
+                        </span>
+                    </div>
+                })}
+
+                {computed.map(|(shown, computed)| {
+                    // Distance from the plain mid, in basis points
+                    let vs_mid = move |value: Option<f64>| {
+                        let locale = locale.get();
+                        match (value, computed.mid_price.get()) {
+                            (Some(v), Some(mid)) if mid > 0.0 => format!(
+                                "{} ({}{} bp)",
+                                locale.format_decimal(v, 2),
+                                sign(v - mid),
+                                locale.format_decimal((v - mid) / mid * 10_000.0, 1),
+                            ),
+                            (v, _) => fmt_field(v, price),
+                        }
+                    };
+                    move || shown.get().then(|| view! {
+                        <div class="tb-stat">
+                            <span class="stat-label">{move || t(Msg::Mid)}</span>
+                            <span class="stat-value">
+                                {move || fmt_field(computed.mid_price.get(), price)}
+                            </span>
+                        </div>
                         <div class="tb-stat">
-                            <span class="stat-label">"24h Change"</span>
-                            <span class="stat-value" style=format!("color: {}", color)>{t.change_str()}</span>
+                            <span class="stat-label">{move || t(Msg::Microprice)}</span>
+                            <span class="stat-value">
+                                {move || vs_mid(computed.microprice.get())}
+                            </span>
+                        </div>
+                        <div class="tb-stat">
+                            <span class="stat-label">{move || t(Msg::WeightedMid)}</span>
+                            <span class="stat-value">
+                                {move || vs_mid(computed.weighted_mid.get())}
+                            </span>
                         </div>
                     })
-                }}
-
-                {move || {
-                    if show_high_low {
-                        ticker.get().map(|t| view! {
-                            <div class="tb-stat">
-                                <span class="stat-label">"24h High"</span>
-                                <span class="stat-value" style=format!("color: {}", colors::BULL)>
-                                    {format!("{:.2}", t.high_24h.as_f64())}
-                                </span>
-                            </div>
-                            <div class="tb-stat">
-                                <span class="stat-label">"24h Low"</span>
-                                <span class="stat-value" style=format!("color: {}", colors::BEAR)>
-                                    {format!("{:.2}", t.low_24h.as_f64())}
-                                </span>
-                            </div>
-                        })
-                    } else {
-                        None
-                    }
-                }}
-
-                {move || {
-                    if show_volume {
-                        ticker.get().map(|t| {
-                            let vol = t.volume_24h.as_f64();
-                            let vol_str = if vol >= 1_000_000.0 {
-                                format!("{:.2}M", vol / 1_000_000.0)
-                            } else if vol >= 1_000.0 {
-                                format!("{:.2}K", vol / 1_000.0)
-                            } else {
-                                format!("{:.4}", vol)
-                            };
-                            view! {
-                                <div class="tb-stat">
-                                    <span class="stat-label">"24h Volume"</span>
-                                    <span class="stat-value">{vol_str}</span>
-                                </div>
-                            }
-                        })
-                    } else {
-                        None
-                    }
-                }}
-
-                {move || {
-                    if show_spread {
-                        ticker.get().map(|t| view! {
-                            <div class="tb-stat">
-                                <span class="stat-label">"Spread"</span>
-                                <span class="stat-value" style=format!("color: {}", colors::WARN)>
-                                    {format!("{:.2} ({:.3}%)", t.spread(), t.spread_percent())}
-                                </span>
-                            </div>
-                        })
-                    } else {
-                        None
-                    }
-                }}
+                })}
+
+                {show_spread.then(|| view! {
+                    <div class="tb-stat">
+                        <span class="stat-label">{move || t(Msg::Spread)}</span>
+                        <span class="stat-value" style=format!("color: {}", colors::WARN)>
+                            {move || {
+                                fields.spread.get().map_or_else(
+                                    || PLACEHOLDER.to_string(),
+                                    |(abs, pct)| {
+                                        let locale = locale.get();
+                                        format!("{} ({}%)", locale.format_decimal(abs, 2), locale.format_decimal(pct, 3))
+                                    },
+                                )
+                            }}
+                        </span>
+                    </div>
+                })}
             </div>
         </div>
     }
 }
 
+/// Shown until the first ticker arrives
+const PLACEHOLDER: &str = "—";
+
+fn fmt_field(value: Option<f64>, f: impl Fn(f64) -> String) -> String {
+    value.map_or_else(|| PLACEHOLDER.to_string(), f)
+}
+
+fn sign(value: f64) -> &'static str {
+    if value >= 0.0 { "+" } else { "" }
+}
+
+fn format_volume(locale: Locale, vol: f64) -> String {
+    if vol >= 1_000.0 {
+        locale.format_compact(vol)
+    } else {
+        locale.format_decimal(vol, 4)
+    }
+}
+
 #[component]
 pub fn ConnectionIndicator(
     #[prop(into)] state: Signal<ConnectionState>,
 ) -> impl IntoView {
+    let locale = use_locale();
+    let label = move || locale.get().text(Msg::connection(state.get()));
     let indicator_style = move || {
         let s = state.get();
         let color = match s {
             ConnectionState::Connected => colors::BULL,
             ConnectionState::Connecting | ConnectionState::Reconnecting => colors::WARN,
-            ConnectionState::Disconnected => colors::BEAR,
+            ConnectionState::Disconnected | ConnectionState::UpdateRequired => colors::BEAR,
         };
         format!("background-color: {}", color)
     };
 
     view! {
-        <div class="connection-indicator" title=move || state.get().label()>
+        <div class="connection-indicator" title=label>
             <span class="indicator-dot" style=indicator_style />
-            <span class="indicator-label">{move || state.get().label()}</span>
+            <span class="indicator-label">{label}</span>
         </div>
     }
 }
\ No newline at end of file
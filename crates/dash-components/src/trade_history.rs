@@ -1,15 +1,26 @@
 //! Trade history (tape) component
 
-use dash_core::{colors, Trade, TradeSide, TradeClassification, ValueThresholdClassifier, TradeClassifier};
-use dash_state::MarketState;
+use chrono::Utc;
+use dash_core::{
+    colors, CandleInterval, Locale, Msg, TradeClassification, TradeClassifier, TradeGroup,
+    ValueThresholdClassifier,
+};
+use dash_state::{
+    trades_after, use_locale, MarketComputed, MarketState, TapeCollapse, TapeGroups, TapeShading,
+    TapeVolume,
+};
 use leptos::prelude::*;
 
+use crate::navigate_rows;
+
 #[derive(Debug, Clone)]
 pub struct TradeHistoryConfig {
     pub max_visible: usize,
     pub show_value: bool,
     pub highlight_whales: bool,
     pub compact: bool,
+    /// Venue badge column when the tape mixes exchanges
+    pub show_venue: bool,
 }
 
 impl Default for TradeHistoryConfig {
@@ -19,6 +30,7 @@ impl Default for TradeHistoryConfig {
             show_value: true,
             highlight_whales: true,
             compact: false,
+            show_venue: true,
         }
     }
 }
@@ -30,58 +42,213 @@ impl TradeHistoryConfig {
             show_value: false,
             highlight_whales: true,
             compact: true,
+            show_venue: false,
         }
     }
 }
 
+/// A tape row: a print (or run of repeats) and its shading intensity
+type TapeRow = (TradeGroup, Option<f64>);
+
+/// Rows held on screen while the tape is paused, with the newest trade
+/// they include
+type HeldRows = (Vec<TapeRow>, Option<String>);
+
 #[component]
 pub fn TradeHistory(
     #[prop(into)] market: MarketState,
     #[prop(optional)] config: Option<TradeHistoryConfig>,
+    /// Row shading by trade value; unshaded when not given
+    #[prop(optional, into)] shading: Option<Signal<TapeShading>>,
+    /// Show the running volume since the tape was opened
+    #[prop(optional, into)] running_volume: Option<Signal<bool>>,
+    /// Merge window for repeat prints; one row per print when not given
+    #[prop(optional, into)] collapse: Option<Signal<TapeCollapse>>,
 ) -> impl IntoView {
     let config = config.unwrap_or_default();
     let max_visible = config.max_visible;
     let show_value = config.show_value;
     let highlight_whales = config.highlight_whales;
     let compact = config.compact;
+    let show_venue = config.show_venue;
 
     let trades = market.trades;
+    let venues = market.venues;
+    let locale = use_locale();
+    let t = move |msg: Msg| locale.get().text(msg);
     let classifier = ValueThresholdClassifier::default();
+    let shading = shading.unwrap_or_else(|| Signal::stored(TapeShading::Off));
+    let collapse = collapse.unwrap_or_else(|| Signal::stored(TapeCollapse::Off));
 
+    // Repeat prints coalesced as they stream in, while collapsing is on
+    let groups = RwSignal::new(None::<TapeGroups>);
+    Effect::new(move |_| {
+        let window = collapse.get().window_ms();
+        trades.with(|t| {
+            groups.maybe_update(|groups| {
+                let Some(window) = window else {
+                    return groups.take().is_some();
+                };
+                match groups {
+                    Some(groups) if groups.window_ms() == window => groups.update(t.as_slice()),
+                    _ => {
+                        let mut fresh = TapeGroups::new(window, t.capacity());
+                        fresh.update(t.as_slice());
+                        *groups = Some(fresh);
+                        true
+                    }
+                }
+            })
+        });
+    });
+
+    // Each row with its shading intensity; the tape is the reference for
+    // relative shading
+    let live_rows = move || -> Vec<TapeRow> {
+        let shading = shading.get();
+        trades.with(|t| {
+            let bucketer = shading.bucketer(t.as_slice());
+            let intensity =
+                |group: &TradeGroup| bucketer.as_ref().map(|b| b.intensity(&group.trade));
+            groups.with(|groups| match groups {
+                Some(groups) => groups
+                    .groups()
+                    .recent(max_visible)
+                    .map(|group| (group.clone(), intensity(group)))
+                    .collect(),
+                None => t
+                    .recent(max_visible)
+                    .map(TradeGroup::from)
+                    .map(|group| {
+                        let intensity = intensity(&group);
+                        (group, intensity)
+                    })
+                    .collect(),
+            })
+        })
+    };
+
+    // Hovering or scrolling the tape holds its rows still so a fast feed
+    // stays readable; trades arriving meanwhile wait behind a pill
+    let held = RwSignal::new(None::<HeldRows>);
+    let hovering = StoredValue::new(false);
+    let hold = move || {
+        if held.with_untracked(Option::is_none) {
+            let newest = trades.with_untracked(|t| t.latest().map(|trade| trade.id.clone()));
+            held.set(Some((untrack(live_rows), newest)));
+        }
+    };
+    let release = move || held.set(None);
+    let pending = move || {
+        held.with(|held| {
+            held.as_ref().map_or(0, |(_, newest)| {
+                trades.with(|t| trades_after(t.as_slice(), newest.as_deref()))
+            })
+        })
+    };
     let visible_trades = move || {
-        trades.get().into_iter().take(max_visible).collect::<Vec<_>>()
+        held.with(|held| held.as_ref().map(|(rows, _)| rows.clone())).unwrap_or_else(live_rows)
+    };
+    let enter = move || {
+        hovering.set_value(true);
+        hold();
+    };
+    let on_leave = move |_| {
+        hovering.set_value(false);
+        release();
+    };
+    // Show what arrived, staying held while the pointer is still on the tape
+    let flush = move |_| {
+        release();
+        if hovering.get_value() {
+            hold();
+        }
+    };
+
+    // Running volume counts from when this tape was opened
+    let volume = RwSignal::new(TapeVolume::new(Utc::now().timestamp_millis()));
+    Effect::new(move |_| {
+        trades.with(|t| volume.maybe_update(|v| v.update(t.as_slice())));
+    });
+    let volume_column = Signal::derive(move || running_volume.is_some_and(|r| r.get()));
+
+    // Only consolidated feeds carry more than one venue
+    let venue_column = Signal::derive(move || show_venue && venues.with(|v| v.len() > 1));
+
+    let class = move || {
+        let mut class = String::from("trade-history");
+        if venue_column.get() {
+            class.push_str(" with-venue");
+        }
+        if volume_column.get() {
+            class.push_str(" with-running");
+        }
+        class
     };
 
     view! {
-        <div class="trade-history">
-            <div class="th-header">
-                <span class="th-col time">"Time"</span>
-                <span class="th-col side">"Side"</span>
-                <span class="th-col price">"Price"</span>
-                <span class="th-col size">"Size"</span>
+        <div
+            class=class
+            on:mouseenter=move |_| enter()
+            on:mouseleave=on_leave
+            on:wheel=move |_| enter()
+        >
+            <div class="th-header" aria-hidden="true">
+                <span class="th-col time">{move || t(Msg::Time)}</span>
+                {move || venue_column.get().then(|| view! { <span class="th-col venue">{t(Msg::Venue)}</span> })}
+                <span class="th-col side">{move || t(Msg::Side)}</span>
+                <span class="th-col price">{move || t(Msg::Price)}</span>
+                <span class="th-col size">{move || t(Msg::Size)}</span>
                 {if show_value {
-                    Some(view! { <span class="th-col value">"Value"</span> })
+                    Some(view! { <span class="th-col value">{move || t(Msg::Value)}</span> })
                 } else {
                     None
                 }}
+                {move || volume_column.get().then(|| view! {
+                    <span class="th-col running" title=t(Msg::RunningVolume)>
+                        {t(Msg::CumVolume)}
+                    </span>
+                })}
             </div>
 
-            <div class="th-list">
+            {move || {
+                let pending = pending();
+                (pending > 0).then(|| view! {
+                    <button class="th-pill" aria-live="polite" on:click=flush>
+                        {format!("{} {}", pending, t(Msg::NewTrades))}
+                    </button>
+                })
+            }}
+
+            <div
+                class="th-list"
+                role="list"
+                aria-label="Recent trades, newest first"
+                tabindex="0"
+                on:keydown=navigate_rows
+            >
                 <For
                     each=visible_trades
-                    key=|trade| trade.id.clone()
-                    children=move |trade| {
+                    key=|(group, intensity)| {
+                        (group.trade.id.clone(), group.count, intensity.map(f64::to_bits))
+                    }
+                    children=move |(group, intensity)| {
                         let classification = if highlight_whales {
-                            Some(classifier.classify(&trade))
+                            Some(classifier.classify(&group.trade))
                         } else {
                             None
                         };
                         view! {
                             <TradeRow
-                                trade=trade
+                                group=group
                                 show_value=show_value
                                 classification=classification
                                 compact=compact
+                                venue_column=venue_column
+                                intensity=intensity
+                                volume=volume
+                                volume_column=volume_column
+                                locale=locale
                             />
                         }
                     }
@@ -93,25 +260,27 @@ pub fn TradeHistory(
 
 #[component]
 fn TradeRow(
-    trade: Trade,
+    group: TradeGroup,
     show_value: bool,
     classification: Option<TradeClassification>,
     compact: bool,
+    venue_column: Signal<bool>,
+    /// Value shading from 0.0 to 1.0, `None` when shading is off
+    intensity: Option<f64>,
+    volume: RwSignal<TapeVolume>,
+    volume_column: Signal<bool>,
+    locale: Signal<Locale>,
 ) -> impl IntoView {
+    let TradeGroup { trade, count, last_id } = group;
     let time_str = if compact { trade.time_short() } else { trade.time_str() };
     let price = trade.price.as_f64();
     let qty = trade.quantity.as_f64();
     let value = trade.value();
 
-    let price_str = if price >= 1000.0 { format!("{:.2}", price) } else { format!("{:.4}", price) };
-    let qty_str = format!("{:.4}", qty);
-    let value_str = if value >= 1_000_000.0 {
-        format!("{:.2}M", value / 1_000_000.0)
-    } else if value >= 1_000.0 {
-        format!("{:.2}K", value / 1_000.0)
-    } else {
-        format!("{:.2}", value)
-    };
+    let price_decimals = if price >= 1000.0 { 2 } else { 4 };
+    let price_str = move || locale.get().format_decimal(price, price_decimals);
+    let qty_str = move || locale.get().format_decimal(qty, 4);
+    let value_str = move || locale.get().format_compact(value);
 
     let side_color = trade.side.color();
     let side_arrow = trade.side.arrow();
@@ -122,17 +291,129 @@ fn TradeRow(
         _ => "th-row",
     };
 
+    let side = trade.side;
+    let size_class = match classification {
+        Some(TradeClassification::Whale) => Some(Msg::Whale),
+        Some(TradeClassification::Large) => Some(Msg::Large),
+        _ => None,
+    };
+    let row_label = {
+        let venue = trade.venue.clone();
+        let time_str = time_str.clone();
+        move || {
+            let locale = locale.get();
+            let mut label = locale.text(Msg::side(side)).to_string();
+            if let Some(class) = size_class {
+                label = format!("{} ({})", label, locale.text(class));
+            }
+            label = format!("{} {} @ {}", label, qty_str(), price_str());
+            if count > 1 {
+                label = format!("{} ×{}", label, count);
+            }
+            if let Some(venue) = &venue {
+                label = format!("{}, {}", label, venue);
+            }
+            format!("{}, {}", label, time_str)
+        }
+    };
+
+    // Venue attribution when the tape mixes exchanges
+    let title = trade.venue.clone().unwrap_or_default();
+    let venue = trade.venue.clone();
+    let venue_cell = move || {
+        venue_column.get().then(|| {
+            let badge = venue.clone().map(|venue| {
+                let style = format!("color: {0}; border-color: {0}", colors::venue(&venue));
+                view! { <span class="venue-badge" style=style>{venue}</span> }
+            });
+            view! { <span class="th-col venue">{badge}</span> }
+        })
+    };
+
+    // Larger buckets shade darker; the smallest is left plain
+    let shade = intensity.filter(|i| *i > 0.0).map(|i| {
+        let percent = 6.0 + 24.0 * i;
+        format!("background: color-mix(in srgb, {} {:.0}%, transparent)", side_color, percent)
+    });
+
+    // Running volume as of this row's newest print; "–" for trades from
+    // before opening
+    let running_cell = move || {
+        volume_column.get().then(|| {
+            let running = volume.with(|v| v.at(&last_id));
+            let text =
+                running.map_or_else(|| "–".to_string(), |v| locale.get().format_compact(v));
+            view! { <span class="th-col running">{text}</span> }
+        })
+    };
+
     view! {
-        <div class=row_class>
+        <div
+            class=row_class
+            style=shade
+            title=title
+            role="listitem"
+            tabindex="-1"
+            aria-label=row_label
+        >
             <span class="th-col time">{time_str}</span>
+            {venue_cell}
             <span class="th-col side" style=format!("color: {}", side_color)>{side_arrow}</span>
             <span class="th-col price" style=format!("color: {}", side_color)>{price_str}</span>
-            <span class="th-col size">{qty_str}</span>
+            <span class="th-col size">
+                {qty_str}
+                {(count > 1).then(|| {
+                    view! { <span class="th-count">{format!("×{}", count)}</span> }
+                })}
+            </span>
             {if show_value {
                 Some(view! { <span class="th-col value">{value_str}</span> })
             } else {
                 None
             }}
+            {running_cell}
+        </div>
+    }
+}
+
+/// Buy share and net volume over the rolling 1m/5m/15m trade windows
+#[component]
+pub fn TradeFlow(computed: MarketComputed) -> impl IntoView {
+    let locale = use_locale();
+    let windows = [
+        (CandleInterval::M1, computed.flow_1m),
+        (CandleInterval::M5, computed.flow_5m),
+        (CandleInterval::M15, computed.flow_15m),
+    ];
+
+    view! {
+        <div class="trade-flow" role="group" aria-label=move || locale.get().text(Msg::TradeFlow)>
+            {windows.into_iter().map(|(span, flow)| {
+                let buy_share = move || flow.with(|f| {
+                    let total = f.buy_volume + f.sell_volume;
+                    if total > 0.0 { f.buy_volume / total } else { 0.5 }
+                });
+                let delta = move || flow.with(|f| f.delta());
+                let color = move || if delta() >= 0.0 { colors::BULL } else { colors::BEAR };
+                view! {
+                    <div class="tf-row" title=move || format!("{} trades", flow.with(|f| f.count))>
+                        <span class="tf-span">{span.label()}</span>
+                        <div class="tf-bar" aria-hidden="true">
+                            <div
+                                class="tf-buy"
+                                style=move || format!("width: {:.1}%", buy_share() * 100.0)
+                            ></div>
+                        </div>
+                        <span class="tf-delta" style=move || format!("color: {}", color())>
+                            {move || {
+                                let delta = delta();
+                                let sign = if delta > 0.0 { "+" } else { "" };
+                                format!("{}{}", sign, locale.get().format_decimal(delta, 3))
+                            }}
+                        </span>
+                    </div>
+                }
+            }).collect_view()}
         </div>
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,63 @@
+//! Consolidated vs single-venue view selector
+
+use dash_core::{Msg, VenueQuote};
+use dash_state::{use_locale, MarketState, VenueView};
+use leptos::prelude::*;
+
+const CONSOLIDATED_VALUE: &str = "";
+
+fn quote_label(quote: Option<&VenueQuote>) -> String {
+    quote.map_or("-".to_string(), |q| format!("{:.2} @{}", q.price.as_f64(), q.venue))
+}
+
+/// Venue dropdown for the order book header, with the consolidated BBO
+#[component]
+pub fn VenueSelector(market: MarketState) -> impl IntoView {
+    let venue_view = market.venue_view;
+    let venues = market.venues;
+    let bbo = market.bbo;
+    let locale = use_locale();
+
+    let on_change = move |ev| {
+        let value = event_target_value(&ev);
+        let view = if value == CONSOLIDATED_VALUE {
+            VenueView::Consolidated
+        } else {
+            VenueView::Venue(value)
+        };
+        market.set_venue_view(view);
+    };
+
+    let bbo_label = move || {
+        bbo.with(|bbo| {
+            bbo.as_ref()
+                .filter(|_| venue_view.with(|v| *v == VenueView::Consolidated))
+                .map(|bbo| format!("{} / {}", quote_label(bbo.bid.as_ref()), quote_label(bbo.ask.as_ref())))
+        })
+    };
+
+    view! {
+        <div class="venue-selector">
+            {move || bbo_label().map(|label| view! { <span class="venue-bbo" title="Consolidated best bid / offer">{label}</span> })}
+            <select class="venue-select" aria-label=move || locale.get().text(Msg::Venue) on:change=on_change>
+                <option
+                    value=CONSOLIDATED_VALUE
+                    selected=move || venue_view.with(|v| *v == VenueView::Consolidated)
+                >
+                    {move || locale.get().text(Msg::Consolidated)}
+                </option>
+                <For
+                    each=move || venues.get()
+                    key=|venue| venue.clone()
+                    children=move |venue| {
+                        let selected = {
+                            let venue = venue.clone();
+                            move || venue_view.with(|v| v.label() == venue && *v != VenueView::Consolidated)
+                        };
+                        view! { <option value=venue.clone() selected=selected>{venue.clone()}</option> }
+                    }
+                />
+            </select>
+        </div>
+    }
+}
@@ -0,0 +1,83 @@
+//! DOM rendering of the market panels against fixture data
+//!
+//! Browser only: `wasm-pack test --headless --firefox crates/dash-components`
+
+#![cfg(target_arch = "wasm32")]
+
+use dash_components::{OrderBook, TickerBar, TradeHistory};
+use dash_core::ConnectionState;
+use dash_state::{MarketFixture, FIXTURE_SYMBOL};
+use leptos::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::*;
+use web_sys::HtmlElement;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// Mount `view` into a fresh container on the page
+fn mount<V: IntoView + 'static>(view: impl FnOnce() -> V + 'static) -> HtmlElement {
+    let container: HtmlElement = document().create_element("div").unwrap().unchecked_into();
+    document().body().unwrap().append_child(&container).unwrap();
+    leptos::mount::mount_to(container.clone(), view).forget();
+    container
+}
+
+fn count(container: &HtmlElement, selector: &str) -> u32 {
+    container.query_selector_all(selector).unwrap().length()
+}
+
+fn text(container: &HtmlElement, selector: &str) -> String {
+    container
+        .query_selector(selector)
+        .unwrap()
+        .and_then(|element| element.text_content())
+        .unwrap_or_default()
+}
+
+#[wasm_bindgen_test]
+fn order_book_renders_fixture_levels() {
+    let container = mount(|| {
+        let market = MarketFixture::new().with_book_levels(5).market();
+        view! { <OrderBook market=market /> }
+    });
+
+    assert_eq!(count(&container, ".ob-asks [role=listitem]"), 5);
+    assert_eq!(count(&container, ".ob-bids [role=listitem]"), 5);
+    // Best ask is one tick above the 50,000 mid
+    assert!(text(&container, ".ob-asks").contains("50,000.50"));
+    assert!(!text(&container, ".spread-value").is_empty());
+}
+
+#[wasm_bindgen_test]
+fn order_book_without_data_has_no_rows() {
+    let container = mount(|| {
+        let market = MarketFixture::new().with_book_levels(0).market();
+        view! { <OrderBook market=market /> }
+    });
+
+    assert_eq!(count(&container, "[role=listitem]"), 0);
+}
+
+#[wasm_bindgen_test]
+fn trade_history_lists_newest_first() {
+    let container = mount(|| {
+        let market = MarketFixture::new().with_trades(12).market();
+        view! { <TradeHistory market=market /> }
+    });
+
+    assert_eq!(count(&container, ".th-list [role=listitem]"), 12);
+    // The last fixture trade prints 11 above the market price
+    assert!(text(&container, ".th-list [role=listitem] .price").contains("50,011"));
+}
+
+#[wasm_bindgen_test]
+fn ticker_bar_shows_fixture_ticker() {
+    let container = mount(|| {
+        let market = MarketFixture::new().market();
+        view! { <TickerBar market=market connection=Signal::stored(ConnectionState::Connected) /> }
+    });
+
+    assert_eq!(text(&container, ".symbol-name"), FIXTURE_SYMBOL);
+    assert!(text(&container, ".price-value").contains("50,000.00"));
+    assert!(text(&container, ".price-change").contains("+2.00%"));
+}
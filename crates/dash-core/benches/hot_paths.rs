@@ -0,0 +1,204 @@
+//! Benchmarks for per-message hot paths
+//!
+//! Run with `cargo bench -p dash-core`.
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use dash_core::*;
+
+const TRADES: usize = 10_000;
+const BOOK_LEVELS: usize = 500;
+/// Visible candle window on the chart
+const CHART_CANDLES: usize = 200;
+
+fn symbol() -> Symbol {
+    Symbol::new("BTC-USD")
+}
+
+/// Deterministic random walk of (timestamp, price, quantity) ticks, 100 ms apart
+fn ticks(count: usize) -> Vec<(i64, f64, f64)> {
+    let mut price = 50_000.0;
+    (0..count)
+        .map(|i| {
+            price += ((i * 7919) % 21) as f64 - 10.0;
+            (i as i64 * 100, price, 0.01 + (i % 13) as f64 * 0.05)
+        })
+        .collect()
+}
+
+fn sample_book(levels: usize) -> OrderBookSnapshot {
+    let mut book = OrderBookSnapshot::new(symbol());
+    book.bids = (0..levels)
+        .map(|i| OrderBookLevel::new(50_000.0 - i as f64, 0.5 + (i % 17) as f64 * 0.3, 1 + (i % 5) as u32))
+        .collect();
+    book.asks = (0..levels)
+        .map(|i| OrderBookLevel::new(50_001.0 + i as f64, 0.5 + (i % 11) as f64 * 0.4, 1 + (i % 7) as u32))
+        .collect();
+    book.timestamp = 0;
+    book.sequence = 1;
+    book
+}
+
+fn sample_candles(count: usize) -> CandleHistory {
+    let mut history = CandleHistory::new(symbol(), CandleInterval::M1);
+    for (i, (_, price, quantity)) in ticks(count).into_iter().enumerate() {
+        let mut candle = Candle::new(symbol(), CandleInterval::M1, i as i64 * 60_000, price);
+        candle.update(price + 15.0, quantity);
+        candle.update(price - 12.0, quantity);
+        candle.update(price + 3.0, quantity);
+        candle.close_candle();
+        history.push(candle);
+    }
+    history
+}
+
+// ============================================================================
+// CANDLE AGGREGATION
+// ============================================================================
+
+/// Fold a trade stream into 1m candles the way the feed does
+fn aggregate(ticks: &[(i64, f64, f64)]) -> CandleHistory {
+    let interval = CandleInterval::M1;
+    let bucket = interval.as_millis();
+    let mut history = CandleHistory::new(symbol(), interval);
+
+    for &(timestamp, price, quantity) in ticks {
+        let start = timestamp - timestamp.rem_euclid(bucket);
+        match history.latest_mut() {
+            Some(candle) if candle.timestamp == start => candle.update(price, quantity),
+            _ => {
+                if let Some(candle) = history.latest_mut() {
+                    candle.close_candle();
+                }
+                let mut candle = Candle::new(symbol(), interval, start, price);
+                candle.update(price, quantity);
+                history.push(candle);
+            }
+        }
+    }
+    history
+}
+
+fn bench_candles(c: &mut Criterion) {
+    let mut group = c.benchmark_group("candles");
+    let trades = ticks(TRADES);
+    group.throughput(Throughput::Elements(TRADES as u64));
+    group.bench_function("aggregate_trades", |b| b.iter(|| aggregate(black_box(&trades))));
+    group.finish();
+
+    let history = sample_candles(1_000);
+    let mut group = c.benchmark_group("candles");
+    group.bench_function("resample_1m_to_15m", |b| {
+        b.iter(|| history.resample(black_box(CandleInterval::M15)))
+    });
+    group.bench_function("integrity_report", |b| b.iter(|| black_box(&history).integrity_report()));
+    group.finish();
+}
+
+// ============================================================================
+// ORDER BOOK
+// ============================================================================
+
+fn deltas(count: usize) -> Vec<OrderBookDelta> {
+    (0..count)
+        .map(|i| {
+            let side = if i % 2 == 0 { OrderSide::Bid } else { OrderSide::Ask };
+            let offset = ((i * 31) % (BOOK_LEVELS * 2)) as f64 * 0.5;
+            let price = match side {
+                OrderSide::Bid => 50_000.0 - offset,
+                OrderSide::Ask => 50_001.0 + offset,
+            };
+            // Every fifth update removes the level
+            let quantity = if i % 5 == 0 { 0.0 } else { 0.1 + (i % 9) as f64 * 0.2 };
+            OrderBookDelta {
+                symbol: symbol(),
+                side,
+                price: Price::new(price),
+                quantity: Quantity::new(quantity),
+                sequence: i as u64 + 2,
+            }
+        })
+        .collect()
+}
+
+fn bench_orderbook(c: &mut Criterion) {
+    let mut group = c.benchmark_group("orderbook");
+    let book = sample_book(BOOK_LEVELS);
+    let updates = deltas(1_000);
+
+    group.throughput(Throughput::Elements(updates.len() as u64));
+    group.bench_function("apply_deltas", |b| {
+        b.iter_batched(
+            || book.clone(),
+            |mut book| {
+                for delta in &updates {
+                    book.apply_delta(delta);
+                }
+                book
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("orderbook");
+    group.bench_function("market_depth", |b| b.iter(|| MarketDepth::from_orderbook(black_box(&book))));
+    group.finish();
+}
+
+// ============================================================================
+// ANALYTICS
+// ============================================================================
+
+fn bench_analytics(c: &mut Criterion) {
+    let mut group = c.benchmark_group("analytics");
+    let book = sample_book(BOOK_LEVELS);
+    let history = sample_candles(CHART_CANDLES);
+
+    let patterns = BasicPatternDetector::new();
+    group.bench_function("candle_patterns", |b| {
+        b.iter(|| black_box(&history).detect_patterns_with(&patterns))
+    });
+
+    let walls = NeighborRatioDetector::default();
+    group.bench_function("wall_detection", |b| b.iter(|| walls.detect(black_box(&book))));
+
+    group.bench_function("market_impact", |b| {
+        b.iter(|| black_box(&book).market_impact(TradeSide::Buy, black_box(25.0)))
+    });
+    group.finish();
+}
+
+// ============================================================================
+// WIRE CODECS
+// ============================================================================
+
+fn bench_codecs(c: &mut Criterion) {
+    let messages = [
+        ("orderbook", WsMessage::OrderBook(sample_book(50))),
+        ("trade", WsMessage::Trade(Trade::new(symbol(), 50_000.0, 0.25, TradeSide::Buy))),
+    ];
+
+    let mut group = c.benchmark_group("codec");
+    for (name, message) in &messages {
+        let json = serde_json::to_vec(message).unwrap();
+        let gzip = gzip_encode(&json).unwrap();
+        group.throughput(Throughput::Bytes(json.len() as u64));
+
+        group.bench_function(format!("{name}/json_encode"), |b| {
+            b.iter(|| serde_json::to_vec(black_box(message)).unwrap())
+        });
+        group.bench_function(format!("{name}/json_decode"), |b| {
+            b.iter(|| serde_json::from_slice::<WsMessage>(black_box(&json)).unwrap())
+        });
+        group.bench_function(format!("{name}/gzip_encode"), |b| {
+            b.iter(|| gzip_encode(&serde_json::to_vec(black_box(message)).unwrap()).unwrap())
+        });
+        group.bench_function(format!("{name}/gzip_decode"), |b| {
+            b.iter(|| serde_json::from_slice::<WsMessage>(&gzip_decode(black_box(&gzip)).unwrap()).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_candles, bench_orderbook, bench_analytics, bench_codecs);
+criterion_main!(benches);
@@ -0,0 +1,169 @@
+//! User accounts shared by the server and the dashboard
+//!
+//! Credentials, the session a sign-in answers with, and the documents a
+//! signed-in user keeps on the server. Documents are JSON the dashboard
+//! owns; the server stores them as given.
+
+use serde::{Deserialize, Serialize};
+
+use crate::Msg;
+
+/// Shortest password accepted at signup
+pub const MIN_PASSWORD_CHARS: usize = 8;
+
+/// Longest username accepted at signup
+pub const MAX_USERNAME_CHARS: usize = 32;
+
+/// Largest document the server stores (bytes of JSON)
+pub const MAX_DOCUMENT_BYTES: usize = 256 * 1024;
+
+/// Body of `POST /api/auth/signup` and `/api/auth/login`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+}
+
+impl Credentials {
+    /// Why these credentials cannot open an account, if anything
+    pub fn signup_error(&self) -> Option<AccountError> {
+        let name = self.username.trim();
+        let valid_name = (3..=MAX_USERNAME_CHARS).contains(&name.chars().count())
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+        if !valid_name {
+            Some(AccountError::InvalidUsername)
+        } else if self.password.chars().count() < MIN_PASSWORD_CHARS {
+            Some(AccountError::PasswordTooShort)
+        } else {
+            None
+        }
+    }
+}
+
+/// A signed-in user, as answered by signup and login
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Session {
+    pub username: String,
+    /// Bearer token for the `/api/me` endpoints
+    pub token: String,
+    /// Unix millis after which the token is refused
+    pub expires_at: i64,
+}
+
+impl Session {
+    pub fn is_expired(&self, now_ms: i64) -> bool {
+        now_ms >= self.expires_at
+    }
+}
+
+/// What a user may do, as answered by `GET /api/auth/role`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// Market data panels only
+    Viewer,
+    /// Also paper trading and the server's control endpoints
+    Operator,
+}
+
+impl Role {
+    pub fn is_operator(&self) -> bool {
+        matches!(self, Self::Operator)
+    }
+
+    pub fn msg(&self) -> Msg {
+        match self {
+            Self::Viewer => Msg::Viewer,
+            Self::Operator => Msg::Operator,
+        }
+    }
+}
+
+/// What a user keeps on the server (`/api/me/:kind`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UserDocument {
+    /// Theme, language and display toggles
+    Settings,
+    /// Visible panels and chart indicators
+    Layout,
+    /// Symbols the user follows
+    Watchlist,
+    /// Alert rules
+    Alerts,
+}
+
+impl UserDocument {
+    pub fn all() -> &'static [Self] {
+        &[Self::Settings, Self::Layout, Self::Watchlist, Self::Alerts]
+    }
+
+    /// Path segment and storage key
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Settings => "settings",
+            Self::Layout => "layout",
+            Self::Watchlist => "watchlist",
+            Self::Alerts => "alerts",
+        }
+    }
+
+    pub fn parse(kind: &str) -> Option<Self> {
+        Self::all().iter().copied().find(|doc| doc.as_str() == kind)
+    }
+}
+
+/// Why a sign-in or sync failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountError {
+    /// 3 to 32 letters, digits, `_`, `-` or `.`
+    InvalidUsername,
+    PasswordTooShort,
+    UsernameTaken,
+    WrongPassword,
+    /// The session ran out; sign in again
+    Expired,
+    /// The server has no account storage
+    Unavailable,
+    /// Network error or unexpected answer
+    Failed,
+}
+
+impl AccountError {
+    pub fn msg(&self) -> Msg {
+        match self {
+            Self::InvalidUsername => Msg::InvalidUsername,
+            Self::PasswordTooShort => Msg::PasswordTooShort,
+            Self::UsernameTaken => Msg::UsernameTaken,
+            Self::WrongPassword => Msg::WrongPassword,
+            Self::Expired => Msg::SessionExpired,
+            Self::Unavailable => Msg::AccountsUnavailable,
+            Self::Failed => Msg::AccountFailed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_signup_rules() {
+        let creds = |username: &str, password: &str| Credentials {
+            username: username.into(),
+            password: password.into(),
+        };
+        let invalid = Some(AccountError::InvalidUsername);
+        assert_eq!(creds("trader_1", "correct horse").signup_error(), None);
+        assert_eq!(creds("ab", "correct horse").signup_error(), invalid);
+        assert_eq!(creds("a b c", "correct horse").signup_error(), invalid);
+        assert_eq!(creds("trader", "short").signup_error(), Some(AccountError::PasswordTooShort));
+
+        assert_eq!(UserDocument::parse("layout"), Some(UserDocument::Layout));
+        assert_eq!(UserDocument::parse("secrets"), None);
+        let json = serde_json::to_string(&UserDocument::Watchlist).unwrap();
+        assert_eq!(json, r#""watchlist""#);
+        let role: Role = serde_json::from_str(r#""viewer""#).unwrap();
+        assert!(!role.is_operator());
+    }
+}
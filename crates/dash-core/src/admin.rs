@@ -0,0 +1,217 @@
+//! Server administration shared by the server and the admin page
+//!
+//! `GET /api/control/status` answers with an [`AdminStatus`]; the admin
+//! page's controls send [`SourceRequest`] and [`ScenarioRequest`] bodies.
+//! `/api/control/chaos` reads and replaces the [`ChaosConfig`].
+//! Every `/api/control` endpoint is for operators only.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{FeedChannel, HealthReport, Msg};
+
+/// How long a triggered scenario lasts unless the request says otherwise
+pub const SCENARIO_SECS: u64 = 60;
+
+/// Longest scenario an operator can trigger
+pub const MAX_SCENARIO_SECS: u64 = 3_600;
+
+/// Most delay chaos mode adds to a frame (ms)
+pub const MAX_CHAOS_LATENCY_MS: u64 = 10_000;
+
+/// Market conditions the mock engine can be pushed into for a while
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MockScenario {
+    /// Steady climb
+    Rally,
+    /// Sharp sell-off
+    Crash,
+    /// Much wider swings around the trend
+    Volatile,
+    /// No messages at all, as if the upstream stalled
+    Stall,
+}
+
+impl MockScenario {
+    pub fn all() -> &'static [Self] {
+        &[Self::Rally, Self::Crash, Self::Volatile, Self::Stall]
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Rally => "rally",
+            Self::Crash => "crash",
+            Self::Volatile => "volatile",
+            Self::Stall => "stall",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::all().iter().copied().find(|s| s.as_str() == name)
+    }
+
+    pub fn msg(&self) -> Msg {
+        match self {
+            Self::Rally => Msg::ScenarioRally,
+            Self::Crash => Msg::ScenarioCrash,
+            Self::Volatile => Msg::ScenarioVolatile,
+            Self::Stall => Msg::ScenarioStall,
+        }
+    }
+}
+
+/// Network faults injected into every client connection, to exercise
+/// reconnects, gap handling and conflation on purpose; all off by default
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChaosConfig {
+    /// Delay before each frame (ms)
+    pub latency_ms: u64,
+    /// Random extra delay of up to this much (ms)
+    pub jitter_ms: u64,
+    /// Share of frames dropped (0.0 to 1.0)
+    pub drop_rate: f64,
+    /// Share of frames sent twice
+    pub duplicate_rate: f64,
+    /// Share of frames held back and sent after the next one
+    pub reorder_rate: f64,
+    /// Close each connection this long after it opened; 0 never does
+    pub disconnect_secs: u64,
+}
+
+impl ChaosConfig {
+    pub fn is_active(&self) -> bool {
+        *self != Self::default()
+    }
+
+    /// Rates within 0 to 1 and delays within [`MAX_CHAOS_LATENCY_MS`]
+    pub fn clamped(self) -> Self {
+        let rate = |r: f64| if r.is_finite() { r.clamp(0.0, 1.0) } else { 0.0 };
+        Self {
+            latency_ms: self.latency_ms.min(MAX_CHAOS_LATENCY_MS),
+            jitter_ms: self.jitter_ms.min(MAX_CHAOS_LATENCY_MS),
+            drop_rate: rate(self.drop_rate),
+            duplicate_rate: rate(self.duplicate_rate),
+            reorder_rate: rate(self.reorder_rate),
+            disconnect_secs: self.disconnect_secs,
+        }
+    }
+}
+
+/// Open WebSocket connections from one client address
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClientConnections {
+    pub ip: String,
+    pub connections: usize,
+}
+
+/// Messages broadcast to clients on one channel
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ChannelThroughput {
+    pub channel: FeedChannel,
+    /// Average over the last few seconds
+    pub per_sec: f64,
+    /// Since the server started
+    pub total: u64,
+}
+
+/// Body of `GET /api/control/status`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AdminStatus {
+    /// Connected clients, most connections first
+    pub clients: Vec<ClientConnections>,
+    pub throughput: Vec<ChannelThroughput>,
+    /// Upstream adapter health, as on `/healthz`
+    pub health: HealthReport,
+    /// Upstream sources in failover order
+    pub sources: Vec<String>,
+    /// Source feeding the clients now
+    pub active_source: String,
+    /// Scenario the mock engine is running, if any
+    pub scenario: Option<MockScenario>,
+    /// Whether scenarios can be triggered (the mock engine is configured)
+    pub scenarios: bool,
+    /// Whether history is being recorded; `None` without storage
+    pub recording: Option<bool>,
+    /// Faults injected into client connections
+    #[serde(default)]
+    pub chaos: ChaosConfig,
+}
+
+impl AdminStatus {
+    /// Open WebSocket connections across all clients
+    pub fn connections(&self) -> usize {
+        self.clients.iter().map(|c| c.connections).sum()
+    }
+}
+
+/// Body of `PUT /api/control/source`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceRequest {
+    /// One of [`AdminStatus::sources`]; the active source is restarted
+    pub source: String,
+}
+
+/// Body of `PUT /api/control/scenario`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScenarioRequest {
+    /// Scenario to start; `None` ends the running one
+    pub scenario: Option<MockScenario>,
+    /// Length in seconds ([`SCENARIO_SECS`] when absent)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secs: Option<u64>,
+}
+
+/// Why the admin page cannot show the server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdminError {
+    /// No session; the endpoints need an operator's
+    SignedOut,
+    /// Signed in as a viewer
+    Forbidden,
+    /// Network error or unexpected answer
+    Failed,
+}
+
+impl AdminError {
+    /// Error for an HTTP status from `/api/control`
+    pub fn from_status(status: u16) -> Self {
+        match status {
+            401 => Self::SignedOut,
+            403 => Self::Forbidden,
+            _ => Self::Failed,
+        }
+    }
+
+    pub fn msg(&self) -> Msg {
+        match self {
+            Self::SignedOut => Msg::AdminSignIn,
+            Self::Forbidden => Msg::AdminForbidden,
+            Self::Failed => Msg::AdminFailed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scenario_requests() {
+        assert_eq!(MockScenario::parse("crash"), Some(MockScenario::Crash));
+        assert_eq!(MockScenario::parse("meltdown"), None);
+
+        let request: ScenarioRequest = serde_json::from_str(r#"{"scenario":"stall"}"#).unwrap();
+        assert_eq!(request, ScenarioRequest { scenario: Some(MockScenario::Stall), secs: None });
+        let end: ScenarioRequest = serde_json::from_str(r#"{"scenario":null}"#).unwrap();
+        assert_eq!(end.scenario, None);
+
+        let chaos: ChaosConfig = serde_json::from_str(r#"{"drop_rate":1.5}"#).unwrap();
+        assert!(chaos.is_active());
+        assert_eq!(chaos.clamped(), ChaosConfig { drop_rate: 1.0, ..ChaosConfig::default() });
+        assert!(!ChaosConfig::default().is_active());
+
+        assert_eq!(AdminError::from_status(403), AdminError::Forbidden);
+        assert_eq!(AdminError::from_status(500), AdminError::Failed);
+    }
+}
@@ -0,0 +1,463 @@
+//! Alert rules
+//!
+//! Conditions on live market values written in the expression syntax, e.g.
+//! `price > 100000 && rsi(14) < 30` or `spread_pct > 0.1`. Candle functions
+//! (`rsi`, `sma`, `ema`, ...) take the close by default, or any candle
+//! series as in indicator scripts: `sma(high, 20)`. A rule fires when its
+//! condition turns true and re-arms once it is false again.
+
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{parse_expr, BinOp, Candle, Expr, ExprError, ExprErrorKind, Script, Symbol};
+
+/// Live values a rule reads; a rule reading a missing value is undecided
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AlertInputs<'a> {
+    /// Last traded price
+    pub price: Option<f64>,
+    pub bid: Option<f64>,
+    pub ask: Option<f64>,
+    /// 24h change in percent
+    pub change_pct: Option<f64>,
+    pub high_24h: Option<f64>,
+    pub low_24h: Option<f64>,
+    pub volume_24h: Option<f64>,
+    /// Candle history, oldest first
+    pub candles: &'a [Candle],
+}
+
+/// Names a rule can read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Live {
+    Price,
+    Bid,
+    Ask,
+    Mid,
+    Spread,
+    /// Spread as a percentage of the mid
+    SpreadPct,
+    ChangePct,
+    High24h,
+    Low24h,
+    Volume24h,
+}
+
+impl Live {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "price" | "last" => Self::Price,
+            "bid" => Self::Bid,
+            "ask" => Self::Ask,
+            "mid" => Self::Mid,
+            "spread" => Self::Spread,
+            "spread_pct" => Self::SpreadPct,
+            "change_pct" => Self::ChangePct,
+            "high_24h" => Self::High24h,
+            "low_24h" => Self::Low24h,
+            "volume_24h" => Self::Volume24h,
+            _ => return None,
+        })
+    }
+
+    fn read(&self, inputs: &AlertInputs<'_>) -> Option<f64> {
+        let quote = || Some((inputs.bid?, inputs.ask?));
+        match self {
+            Self::Price => inputs.price,
+            Self::Bid => inputs.bid,
+            Self::Ask => inputs.ask,
+            Self::Mid => quote().map(|(bid, ask)| (bid + ask) / 2.0),
+            Self::Spread => quote().map(|(bid, ask)| ask - bid),
+            Self::SpreadPct => {
+                let (bid, ask) = quote()?;
+                let mid = (bid + ask) / 2.0;
+                (mid != 0.0).then(|| (ask - bid) / mid * 100.0)
+            }
+            Self::ChangePct => inputs.change_pct,
+            Self::High24h => inputs.high_24h,
+            Self::Low24h => inputs.low_24h,
+            Self::Volume24h => inputs.volume_24h,
+        }
+    }
+}
+
+/// A compiled rule condition
+#[derive(Debug, Clone)]
+enum Node {
+    Num(f64),
+    Live(Live),
+    Neg(Box<Node>),
+    Not(Box<Node>),
+    Binary(BinOp, Box<Node>, Box<Node>),
+    Abs(Box<Node>),
+    Min(Box<Node>, Box<Node>),
+    Max(Box<Node>, Box<Node>),
+    /// Candle function or field, at the newest candle
+    Candles(Script),
+}
+
+impl Node {
+    fn eval(&self, inputs: &AlertInputs<'_>) -> Option<f64> {
+        let value = match self {
+            Self::Num(value) => *value,
+            Self::Live(live) => live.read(inputs)?,
+            Self::Neg(inner) => -inner.eval(inputs)?,
+            Self::Not(inner) => BinOp::Eq.apply(inner.eval(inputs)?, 0.0),
+            Self::Binary(op, a, b) => op.apply(a.eval(inputs)?, b.eval(inputs)?),
+            Self::Abs(inner) => inner.eval(inputs)?.abs(),
+            Self::Min(a, b) => a.eval(inputs)?.min(b.eval(inputs)?),
+            Self::Max(a, b) => a.eval(inputs)?.max(b.eval(inputs)?),
+            Self::Candles(script) => script.eval_last(inputs.candles)?,
+        };
+        (!value.is_nan()).then_some(value)
+    }
+}
+
+/// Resolve names and calls in a parsed rule
+fn compile(expr: &Expr) -> Result<Node, ExprErrorKind> {
+    let boxed = |expr: &Expr| compile(expr).map(Box::new);
+    Ok(match expr {
+        Expr::Num(value) => Node::Num(*value),
+        Expr::Name(name) => match Live::parse(name) {
+            Some(live) => Node::Live(live),
+            // `close`, `volume` and the other candle fields
+            None => Node::Candles(Script::from_expr(name, name, expr)?),
+        },
+        Expr::Neg(inner) => Node::Neg(boxed(inner)?),
+        Expr::Not(inner) => Node::Not(boxed(inner)?),
+        Expr::Binary(op, a, b) => Node::Binary(*op, boxed(a)?, boxed(b)?),
+        Expr::Call(name, args) => match (name.as_str(), args.as_slice()) {
+            ("abs", [x]) => Node::Abs(boxed(x)?),
+            ("min", [a, b]) => Node::Min(boxed(a)?, boxed(b)?),
+            ("max", [a, b]) => Node::Max(boxed(a)?, boxed(b)?),
+            ("abs" | "min" | "max", _) => return Err(ExprErrorKind::Arguments(name.clone())),
+            // `rsi(14)` is `rsi(close, 14)`
+            (_, [period]) => {
+                let close = Expr::Name("close".into());
+                let call = Expr::Call(name.clone(), vec![close, period.clone()]);
+                Node::Candles(Script::from_expr(name, name, &call)?)
+            }
+            _ => Node::Candles(Script::from_expr(name, name, expr)?),
+        },
+    })
+}
+
+/// Whether `expr` reads as true or false rather than a number
+fn is_condition(expr: &Expr) -> bool {
+    match expr {
+        Expr::Not(_) => true,
+        Expr::Binary(op, ..) => !matches!(
+            op,
+            BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div | BinOp::Rem | BinOp::Pow
+        ),
+        _ => false,
+    }
+}
+
+/// What a rule is saved as
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedAlert {
+    pub id: u64,
+    pub symbol: Symbol,
+    pub source: String,
+}
+
+/// A compiled alert condition on one symbol
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "SavedAlert", into = "SavedAlert")]
+pub struct AlertRule {
+    id: u64,
+    symbol: Symbol,
+    source: String,
+    node: Arc<Node>,
+    /// Cleared when the rule fires, set again once the condition is false
+    armed: bool,
+}
+
+impl AlertRule {
+    /// Compile `source`; errors carry the character offset they refer to
+    pub fn compile(id: u64, symbol: Symbol, source: &str) -> Result<Self, ExprError> {
+        let expr = parse_expr(source)?;
+        if !is_condition(&expr) {
+            return Err(ExprError::new(0, ExprErrorKind::NotCondition));
+        }
+        let node = compile(&expr).map_err(|kind| ExprError::new(0, kind))?;
+        let source = source.trim().to_string();
+        Ok(Self { id, symbol, source, node: Arc::new(node), armed: true })
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    pub fn symbol(&self) -> &Symbol {
+        &self.symbol
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Whether the condition holds; `None` while a value it reads is missing
+    pub fn holds(&self, inputs: &AlertInputs<'_>) -> Option<bool> {
+        self.node.eval(inputs).map(|value| value != 0.0)
+    }
+
+    /// Evaluate against `inputs`; true when the rule fires
+    pub fn check(&mut self, inputs: &AlertInputs<'_>) -> bool {
+        match self.holds(inputs) {
+            Some(true) if self.armed => {
+                self.armed = false;
+                true
+            }
+            Some(false) => {
+                self.armed = true;
+                false
+            }
+            _ => false,
+        }
+    }
+}
+
+impl TryFrom<SavedAlert> for AlertRule {
+    type Error = String;
+
+    fn try_from(saved: SavedAlert) -> Result<Self, Self::Error> {
+        Self::compile(saved.id, saved.symbol, &saved.source).map_err(|e| format!("{:?}", e))
+    }
+}
+
+impl From<AlertRule> for SavedAlert {
+    fn from(rule: AlertRule) -> Self {
+        Self { id: rule.id, symbol: rule.symbol, source: rule.source }
+    }
+}
+
+/// The user's alert rules, in the order added
+#[derive(Debug, Clone, Default)]
+pub struct AlertBook {
+    rules: Vec<AlertRule>,
+    next_id: u64,
+}
+
+impl AlertBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compile and add a rule on `symbol`, returning its id
+    pub fn add(&mut self, symbol: Symbol, source: &str) -> Result<u64, ExprError> {
+        let rule = AlertRule::compile(self.next_id, symbol, source)?;
+        self.next_id += 1;
+        self.rules.push(rule);
+        Ok(self.next_id - 1)
+    }
+
+    pub fn remove(&mut self, id: u64) -> bool {
+        let before = self.rules.len();
+        self.rules.retain(|rule| rule.id != id);
+        self.rules.len() != before
+    }
+
+    /// Replace the rules with ones saved earlier
+    pub fn restore(&mut self, rules: Vec<AlertRule>) {
+        self.next_id = rules.iter().map(|rule| rule.id + 1).max().unwrap_or(0);
+        self.rules = rules;
+    }
+
+    pub fn rules(&self) -> &[AlertRule] {
+        &self.rules
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Check the rules on `symbol` against its live values; returns the
+    /// ones that fired
+    pub fn check(&mut self, symbol: &Symbol, inputs: &AlertInputs<'_>) -> Vec<AlertRule> {
+        self.rules
+            .iter_mut()
+            .filter(|rule| rule.symbol == *symbol)
+            .filter_map(|rule| rule.check(inputs).then(|| rule.clone()))
+            .collect()
+    }
+}
+
+// ============================================================================
+// DELIVERY
+// ============================================================================
+
+/// Message format of a webhook the server posts fired alerts to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookFormat {
+    /// `{"content": ...}`
+    #[default]
+    Discord,
+    /// `{"text": ...}`
+    Slack,
+    /// The notice's fields as they are
+    Json,
+}
+
+impl WebhookFormat {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Discord => "Discord",
+            Self::Slack => "Slack",
+            Self::Json => "JSON",
+        }
+    }
+
+    pub fn all() -> &'static [Self] {
+        &[Self::Discord, Self::Slack, Self::Json]
+    }
+}
+
+/// Where the server delivers fired alerts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Webhook {
+    pub url: String,
+    #[serde(default)]
+    pub format: WebhookFormat,
+}
+
+impl Webhook {
+    /// Only https URLs with a host are accepted
+    pub fn is_valid(&self) -> bool {
+        self.url
+            .strip_prefix("https://")
+            .and_then(|rest| rest.split(['/', '?', '#']).next())
+            .is_some_and(|host| !host.is_empty() && !host.contains(char::is_whitespace))
+    }
+}
+
+/// Body of `PUT /api/alerts`: the rules the server checks while the
+/// dashboard is closed, and where it delivers them
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ServerAlerts {
+    pub webhook: Option<Webhook>,
+    #[serde(default)]
+    pub rules: Vec<AlertRule>,
+}
+
+/// A fired rule, as delivered
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertNotice {
+    /// Id of the rule in the book that fired it
+    pub rule_id: u64,
+    pub symbol: Symbol,
+    pub rule: String,
+    /// Last price when it fired
+    pub price: Option<f64>,
+    /// Unix millis
+    pub timestamp: i64,
+}
+
+impl AlertNotice {
+    pub fn new(rule: &AlertRule, price: Option<f64>, timestamp: i64) -> Self {
+        let (rule_id, symbol) = (rule.id, rule.symbol.clone());
+        Self { rule_id, symbol, rule: rule.source.clone(), price, timestamp }
+    }
+
+    /// One-line text for chat messages and notifications
+    pub fn message(&self) -> String {
+        match self.price {
+            Some(price) => format!("{}: {} (last {})", self.symbol, self.rule, price),
+            None => format!("{}: {}", self.symbol, self.rule),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CandleInterval, Msg};
+
+    fn candles(closes: &[f64]) -> Vec<Candle> {
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, &close)| Candle::new(Symbol::default(), CandleInterval::M1, i as i64, close))
+            .collect()
+    }
+
+    #[test]
+    fn test_alert_rules() {
+        let history = candles(&[105.0, 104.0, 103.0, 102.0]);
+        let inputs = AlertInputs {
+            price: Some(102.0),
+            bid: Some(99.9),
+            ask: Some(100.1),
+            candles: &history,
+            ..Default::default()
+        };
+        let holds = |source: &str| {
+            AlertRule::compile(0, Symbol::default(), source).unwrap().holds(&inputs)
+        };
+        assert_eq!(holds("price > 100 && rsi(3) < 30"), Some(true));
+        assert_eq!(holds("spread_pct > 0.1"), Some(true));
+        assert_eq!(holds("spread_pct > 0.25"), Some(false));
+        assert_eq!(holds("close < sma(high, 2) || abs(mid - 100) > 1"), Some(true));
+        // Undecided without a 24h change or enough candles
+        assert_eq!(holds("change_pct < -5"), None);
+        assert_eq!(holds("!(sma(10) > price)"), None);
+
+        let err = |source: &str| AlertRule::compile(0, Symbol::default(), source).unwrap_err();
+        assert_eq!(err("price > 1 &&").kind, ExprErrorKind::Syntax);
+        assert_eq!(err("price").msg(), Msg::ExprNotCondition);
+        assert_eq!(err("prise > 1").name(), Some("prise"));
+        assert_eq!(err("max(price) > 1").kind, ExprErrorKind::Arguments("max".into()));
+        assert_eq!(err("rsi(price) < 30").kind, ExprErrorKind::Period);
+    }
+
+    #[test]
+    fn test_alert_book() {
+        let btc = Symbol::new("BTC-USD");
+        let mut book = AlertBook::new();
+        let id = book.add(btc.clone(), " price >= 100 ").unwrap();
+        book.add(Symbol::new("ETH-USD"), "price > 0").unwrap();
+        assert!(book.add(btc.clone(), "price >").is_err());
+
+        let at = |price: f64| AlertInputs { price: Some(price), ..Default::default() };
+        let fired = |book: &mut AlertBook, price: f64| {
+            book.check(&btc, &at(price)).iter().map(AlertRule::id).collect::<Vec<_>>()
+        };
+        // Fires once, re-arms below the level and fires again
+        assert_eq!(fired(&mut book, 101.0), vec![id]);
+        assert!(fired(&mut book, 102.0).is_empty());
+        assert!(fired(&mut book, 99.0).is_empty());
+        assert_eq!(fired(&mut book, 100.0), vec![id]);
+
+        let json = serde_json::to_string(book.rules()).unwrap();
+        assert!(json.starts_with(r#"[{"id":0,"symbol":"BTC-USD","source":"price >= 100"}"#));
+        let mut restored = AlertBook::new();
+        restored.restore(serde_json::from_str(&json).unwrap());
+        assert_eq!(restored.add(btc, "bid < 1"), Ok(2));
+        assert!(restored.remove(id));
+        assert!(!restored.remove(id));
+    }
+
+    #[test]
+    fn test_webhook_delivery() {
+        let hook = |url: &str| Webhook { url: url.into(), format: WebhookFormat::Slack };
+        assert!(hook("https://hooks.slack.com/services/T0/B0/x").is_valid());
+        assert!(!hook("http://hooks.slack.com/services").is_valid());
+        assert!(!hook("https:///path").is_valid());
+        assert!(!hook("file:///etc/passwd").is_valid());
+
+        let body: ServerAlerts = serde_json::from_str(
+            r#"{"webhook":{"url":"https://discord.com/api/webhooks/1/a"},
+                "rules":[{"id":3,"symbol":"BTC-USD","source":"price > 100000"}]}"#,
+        )
+        .unwrap();
+        assert_eq!(body.webhook.unwrap().format, WebhookFormat::Discord);
+        let notice = AlertNotice::new(&body.rules[0], Some(100_001.5), 0);
+        assert_eq!(notice.message(), "BTC-USD: price > 100000 (last 100001.5)");
+        // Rules are compiled as they are read
+        let bad = r#"{"webhook":null,"rules":[{"id":0,"symbol":"BTC-USD","source":"price"}]}"#;
+        assert!(serde_json::from_str::<ServerAlerts>(bad).is_err());
+    }
+}
@@ -0,0 +1,292 @@
+//! Strategy backtesting
+//!
+//! Runs a strategy over recorded candles and trades through the paper
+//! engine, so simulated fills follow the same rules as live paper trading.
+//! Prints come from the trades when there are any; otherwise each candle is
+//! walked open, nearer extreme, further extreme, close before the strategy
+//! sees it closed.
+
+use crate::{
+    Candle, FillJournal, Indicator, OrderId, OrderTicket, PaperEngine, PaperOrder, Position, Sma,
+    Symbol, TicketError, Trade, TradeSide,
+};
+
+/// Hooks called as the recorded data plays back; both default to doing nothing
+pub trait Strategy {
+    /// A candle closed
+    fn on_candle(&mut self, ctx: &mut BacktestContext<'_>, candle: &Candle) {
+        let _ = (ctx, candle);
+    }
+
+    /// A trade printed in the backtested symbol
+    fn on_trade(&mut self, ctx: &mut BacktestContext<'_>, trade: &Trade) {
+        let _ = (ctx, trade);
+    }
+}
+
+/// A closure run on every closed candle is a strategy
+impl<F> Strategy for F
+where
+    F: FnMut(&mut BacktestContext<'_>, &Candle),
+{
+    fn on_candle(&mut self, ctx: &mut BacktestContext<'_>, candle: &Candle) {
+        self(ctx, candle)
+    }
+}
+
+/// What a strategy can see and do at the current point of the replay
+pub struct BacktestContext<'a> {
+    engine: &'a mut PaperEngine,
+    symbol: &'a Symbol,
+    last: Option<f64>,
+    now_ms: i64,
+}
+
+impl BacktestContext<'_> {
+    pub fn symbol(&self) -> &Symbol {
+        self.symbol
+    }
+
+    /// Replay time (Unix millis)
+    pub fn now_ms(&self) -> i64 {
+        self.now_ms
+    }
+
+    pub fn last_price(&self) -> Option<f64> {
+        self.last
+    }
+
+    pub fn position(&self) -> Position {
+        self.engine.position(self.symbol)
+    }
+
+    pub fn working(&self) -> Vec<&PaperOrder> {
+        self.engine.working(self.symbol)
+    }
+
+    /// Place an order; market orders fill at the last price
+    pub fn submit(&mut self, ticket: &OrderTicket) -> Result<OrderId, TicketError> {
+        self.engine.submit(ticket, self.last, self.now_ms)
+    }
+
+    pub fn cancel(&mut self, id: OrderId) -> Result<(), TicketError> {
+        self.engine.cancel(id)
+    }
+
+    pub fn move_order(&mut self, id: OrderId, price: f64) -> Result<(), TicketError> {
+        self.engine.move_order(id, price)
+    }
+}
+
+/// Fills, equity and risk metrics of a finished run
+#[derive(Debug, Clone, Default)]
+pub struct BacktestReport {
+    /// Every fill, with the equity curve and risk metrics over them
+    pub journal: FillJournal,
+    /// Left open at the end of the data
+    pub position: Position,
+    /// Last price replayed
+    pub last_price: Option<f64>,
+}
+
+impl BacktestReport {
+    /// PnL of the position left open, marked at the last price
+    pub fn open_pnl(&self) -> f64 {
+        self.last_price.map_or(0.0, |price| self.position.unrealized_pnl(price))
+    }
+}
+
+/// Recorded data for one symbol and the engine it plays through
+pub struct Backtest<'a> {
+    symbol: Symbol,
+    candles: &'a [Candle],
+    trades: &'a [Trade],
+    engine: PaperEngine,
+}
+
+impl<'a> Backtest<'a> {
+    pub fn new(symbol: Symbol) -> Self {
+        Self { symbol, candles: &[], trades: &[], engine: PaperEngine::new() }
+    }
+
+    /// Closed candles, oldest first
+    pub fn with_candles(mut self, candles: &'a [Candle]) -> Self {
+        self.candles = candles;
+        self
+    }
+
+    /// Trades, oldest first; trades in other symbols are skipped
+    pub fn with_trades(mut self, trades: &'a [Trade]) -> Self {
+        self.trades = trades;
+        self
+    }
+
+    /// Start from an engine set up beforehand, e.g. with a leverage
+    pub fn with_engine(mut self, engine: PaperEngine) -> Self {
+        self.engine = engine;
+        self
+    }
+
+    pub fn run(mut self, strategy: &mut impl Strategy) -> BacktestReport {
+        let symbol = self.symbol.clone();
+        let mut journal = FillJournal::new();
+        let mut last = None;
+        let mut trades = self.trades.iter().filter(|t| t.symbol == symbol).peekable();
+
+        for candle in self.candles {
+            let close_at = candle.timestamp + candle.interval.as_millis();
+            while let Some(trade) = trades.next_if(|t| t.timestamp.timestamp_millis() < close_at) {
+                self.print(&mut journal, &mut last, trade, strategy);
+            }
+            if self.trades.is_empty() {
+                for (offset, price) in candle_path(candle) {
+                    let now = candle.timestamp + offset * candle.interval.as_millis() / 4;
+                    for fill in self.engine.on_trade(&symbol, price, now) {
+                        journal.record(fill);
+                    }
+                    last = Some(price);
+                }
+            }
+            let before = self.engine.fills().len();
+            let mut ctx = BacktestContext {
+                engine: &mut self.engine,
+                symbol: &symbol,
+                last,
+                now_ms: close_at,
+            };
+            strategy.on_candle(&mut ctx, candle);
+            self.engine.fills()[before..].iter().for_each(|f| journal.record(f.clone()));
+        }
+        for trade in trades {
+            self.print(&mut journal, &mut last, trade, strategy);
+        }
+
+        BacktestReport { journal, position: self.engine.position(&symbol), last_price: last }
+    }
+
+    /// Fill what `trade` reaches, then hand it to the strategy
+    fn print(
+        &mut self,
+        journal: &mut FillJournal,
+        last: &mut Option<f64>,
+        trade: &Trade,
+        strategy: &mut impl Strategy,
+    ) {
+        let price = trade.price.as_f64();
+        let now = trade.timestamp.timestamp_millis();
+        let before = self.engine.fills().len();
+        self.engine.on_trade(&self.symbol, price, now);
+        *last = Some(price);
+        let mut ctx = BacktestContext {
+            engine: &mut self.engine,
+            symbol: &self.symbol,
+            last: *last,
+            now_ms: now,
+        };
+        strategy.on_trade(&mut ctx, trade);
+        self.engine.fills()[before..].iter().for_each(|f| journal.record(f.clone()));
+    }
+}
+
+/// Prints a candle is assumed to have traded through, as (quarter of the
+/// candle, price): down first on an up candle, up first on a down candle
+fn candle_path(candle: &Candle) -> [(i64, f64); 4] {
+    let (open, close) = (candle.open.as_f64(), candle.close.as_f64());
+    let (high, low) = (candle.high.as_f64(), candle.low.as_f64());
+    let (first, second) = if close >= open { (low, high) } else { (high, low) };
+    [(0, open), (1, first), (2, second), (3, close)]
+}
+
+/// Moving-average crossover: long when the fast SMA crosses above the slow
+/// one, short when it crosses below
+pub struct SmaCross {
+    fast: Sma,
+    slow: Sma,
+    size: f64,
+    /// Whether fast was above slow on the previous candle
+    above: Option<bool>,
+}
+
+impl SmaCross {
+    pub fn new(fast: usize, slow: usize, size: f64) -> Self {
+        Self { fast: Sma::new(fast), slow: Sma::new(slow), size, above: None }
+    }
+}
+
+impl Strategy for SmaCross {
+    fn on_candle(&mut self, ctx: &mut BacktestContext<'_>, candle: &Candle) {
+        self.fast.push(candle);
+        self.slow.push(candle);
+        let (Some(fast), Some(slow)) = (self.fast.value(), self.slow.value()) else {
+            return;
+        };
+        let above = fast > slow;
+        let crossed = self.above.is_some_and(|was| was != above);
+        self.above = Some(above);
+        if !crossed {
+            return;
+        }
+        // Close out whatever is open and take the new side
+        let side = if above { TradeSide::Buy } else { TradeSide::Sell };
+        let quantity = self.size + ctx.position().quantity.abs();
+        let _ = ctx.submit(&OrderTicket::new(ctx.symbol().clone(), side, quantity));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Bracket, CandleInterval};
+
+    fn candles(closes: &[f64]) -> Vec<Candle> {
+        let interval = CandleInterval::M1;
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, close)| {
+                let ts = i as i64 * interval.as_millis();
+                let mut candle = Candle::new(Symbol::new("BTC-USD"), interval, ts, *close);
+                candle.update(*close + 1.0, 1.0);
+                candle.update(*close - 1.0, 1.0);
+                candle.update(*close, 1.0);
+                candle
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_bracket_strategy() {
+        let history = candles(&[100.0, 100.0, 103.0, 106.0, 110.0]);
+        let mut entered = false;
+        let mut strategy = |ctx: &mut BacktestContext<'_>, _: &Candle| {
+            if !entered {
+                let mut ticket = OrderTicket::new(ctx.symbol().clone(), TradeSide::Buy, 1.0);
+                ticket.bracket = Bracket { take_profit: Some(105.0), stop_loss: Some(95.0) };
+                entered = ctx.submit(&ticket).is_ok();
+            }
+        };
+        let report = Backtest::new(Symbol::new("BTC-USD"))
+            .with_candles(&history)
+            .run(&mut strategy);
+
+        // In at 100 on the first close, out when the market gaps up to 106
+        let fills: Vec<_> = report.journal.fills().map(|f| (f.price, f.realized_pnl)).collect();
+        assert_eq!(fills, vec![(100.0, 0.0), (106.0, 6.0)]);
+        assert!(report.position.is_flat());
+        assert_eq!(report.journal.metrics().average_r(), Some(1.2));
+        assert_eq!(report.last_price, Some(110.0));
+    }
+
+    #[test]
+    fn test_sma_cross() {
+        let history = candles(&[10.0, 10.0, 10.0, 12.0, 14.0, 12.0, 9.0, 8.0]);
+        let report = Backtest::new(Symbol::new("BTC-USD"))
+            .with_candles(&history)
+            .run(&mut SmaCross::new(1, 3, 1.0));
+        let sides: Vec<_> = report.journal.fills().map(|f| f.side).collect();
+        // Long at 12, flipped short at 12, marked at 8
+        assert_eq!(sides, vec![TradeSide::Buy, TradeSide::Sell]);
+        assert_eq!(report.position.quantity, -1.0);
+        assert_eq!(report.open_pnl(), 4.0);
+    }
+}
@@ -0,0 +1,103 @@
+//! Economic calendar: scheduled events (rate decisions, data releases,
+//! exchange maintenance) counted down in the session clock and marked on
+//! the candle chart
+
+use serde::{Deserialize, Serialize};
+
+/// Longest marker label drawn on the chart; longer titles are cut
+pub const MAX_MARKER_CHARS: usize = 12;
+
+/// What kind of event is scheduled
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    /// Central bank decisions and economic data (FOMC, CPI)
+    #[default]
+    Macro,
+    /// Exchange or venue downtime
+    Maintenance,
+    Other,
+}
+
+impl EventKind {
+    /// Marker color
+    pub fn color(&self) -> &'static str {
+        match self {
+            Self::Macro => crate::colors::WARN,
+            Self::Maintenance => crate::colors::BEAR,
+            Self::Other => crate::colors::NEUTRAL,
+        }
+    }
+}
+
+/// One scheduled event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledEvent {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub kind: EventKind,
+    /// Start, Unix millis
+    pub timestamp: i64,
+}
+
+impl ScheduledEvent {
+    pub fn new(id: impl Into<String>, title: impl Into<String>, kind: EventKind, ts: i64) -> Self {
+        Self { id: id.into(), title: title.into(), kind, timestamp: ts }
+    }
+
+    /// Title cut to `MAX_MARKER_CHARS` for the chart
+    pub fn marker_label(&self) -> String {
+        if self.title.chars().count() <= MAX_MARKER_CHARS {
+            return self.title.clone();
+        }
+        let cut: String = self.title.chars().take(MAX_MARKER_CHARS - 1).collect();
+        format!("{}…", cut.trim_end())
+    }
+}
+
+/// Up to `limit` events starting after `now_ms`, soonest first
+pub fn upcoming_events(
+    events: &[ScheduledEvent],
+    now_ms: i64,
+    limit: usize,
+) -> Vec<&ScheduledEvent> {
+    let mut upcoming: Vec<&ScheduledEvent> =
+        events.iter().filter(|e| e.timestamp > now_ms).collect();
+    upcoming.sort_by_key(|e| e.timestamp);
+    upcoming.truncate(limit);
+    upcoming
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upcoming_events() {
+        let events = vec![
+            ScheduledEvent::new("fomc", "FOMC rate decision", EventKind::Macro, 3_000),
+            ScheduledEvent::new("cpi", "CPI", EventKind::Macro, 2_000),
+            ScheduledEvent::new("old", "PPI", EventKind::Macro, 500),
+            ScheduledEvent::new("mx", "Maintenance", EventKind::Maintenance, 4_000),
+        ];
+        let ids = |now, limit| -> Vec<&str> {
+            upcoming_events(&events, now, limit).iter().map(|e| e.id.as_str()).collect()
+        };
+        assert_eq!(ids(1_000, 2), vec!["cpi", "fomc"]);
+        assert!(ids(4_000, 5).is_empty());
+    }
+
+    #[test]
+    fn test_scheduled_event() {
+        let event = ScheduledEvent::new("fomc", "FOMC rate decision", EventKind::Macro, 0);
+        assert_eq!(event.marker_label(), "FOMC rate d…");
+        assert_eq!(event.marker_label().chars().count(), MAX_MARKER_CHARS);
+        assert_eq!(ScheduledEvent::new("cpi", "CPI", EventKind::Macro, 0).marker_label(), "CPI");
+
+        // Kind defaults to macro
+        let parsed: ScheduledEvent =
+            serde_json::from_str(r#"{"id":"x","title":"NFP","timestamp":5}"#).unwrap();
+        assert_eq!(parsed.kind, EventKind::Macro);
+    }
+}
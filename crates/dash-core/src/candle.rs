@@ -1,6 +1,6 @@
 //! Candlestick (OHLCV) types for charting
 
-use crate::{colors, Price, Quantity, Symbol};
+use crate::{colors, Msg, Price, Quantity, RingBuffer, Symbol, Trade, TradeSide};
 use serde::{Deserialize, Serialize};
 
 // ============================================================================
@@ -103,9 +103,13 @@ impl CandlePatternDetector for BasicPatternDetector {
 // CORE TYPES
 // ============================================================================
 
+/// Time from Monday 00:00 UTC to the Unix epoch, which fell on a Thursday
+const WEEK_OPEN_OFFSET_MS: i64 = 259_200_000;
+
 /// Time interval for candlesticks
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
 pub enum CandleInterval {
+    #[default]
     #[serde(rename = "1m")]
     M1,
     #[serde(rename = "5m")]
@@ -144,6 +148,20 @@ impl CandleInterval {
         self.as_secs() * 1000
     }
 
+    /// Open time of the candle containing `timestamp`. Buckets are aligned to
+    /// the Unix epoch, except weeks, which open on Monday 00:00 UTC as on
+    /// the exchanges (the epoch fell on a Thursday).
+    pub fn open_time(&self, timestamp: i64) -> i64 {
+        let step = self.as_millis();
+        let offset = if *self == Self::W1 { WEEK_OPEN_OFFSET_MS } else { 0 };
+        (timestamp + offset).div_euclid(step) * step - offset
+    }
+
+    /// Close time of the candle forming at `now_ms`
+    pub fn next_close(&self, now_ms: i64) -> i64 {
+        self.open_time(now_ms) + self.as_millis()
+    }
+
     /// Display label
     pub fn label(&self) -> &'static str {
         match self {
@@ -158,6 +176,42 @@ impl CandleInterval {
         }
     }
 
+    /// Wire/URL code (matches the serde name, e.g. "15m", "1h")
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::M1 => "1m",
+            Self::M5 => "5m",
+            Self::M15 => "15m",
+            Self::M30 => "30m",
+            Self::H1 => "1h",
+            Self::H4 => "4h",
+            Self::D1 => "1d",
+            Self::W1 => "1w",
+        }
+    }
+
+    /// Parse a code or label, case-insensitively ("15m", "1H")
+    pub fn from_code(value: &str) -> Option<Self> {
+        let value = value.trim();
+        Self::all()
+            .iter()
+            .copied()
+            .find(|interval| interval.code().eq_ignore_ascii_case(value))
+    }
+
+    /// Finest interval at or above `self` that covers `span_ms` in at most
+    /// `max_points` candles and can be resampled from `self`
+    pub fn coarsen_for(&self, span_ms: i64, max_points: usize) -> Self {
+        let max_points = max_points.max(1) as i64;
+        let base = self.as_millis();
+        Self::all()
+            .iter()
+            .copied()
+            .filter(|i| i.as_millis() >= base && i.as_millis() % base == 0)
+            .find(|i| span_ms / i.as_millis() < max_points)
+            .unwrap_or(Self::W1)
+    }
+
     /// All intervals
     pub fn all() -> &'static [Self] {
         &[
@@ -167,18 +221,69 @@ impl CandleInterval {
     }
 }
 
-impl Default for CandleInterval {
-    fn default() -> Self {
-        Self::M1
-    }
-}
-
 impl std::fmt::Display for CandleInterval {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.label())
     }
 }
 
+/// How the price series is drawn on the candle chart
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ChartType {
+    #[default]
+    Candles,
+    /// Open/high/low/close bars with side ticks
+    Ohlc,
+    /// Close prices joined by a line
+    Line,
+    /// Close line over a gradient fill
+    Area,
+    /// Close line filled green above and red below a reference price
+    Baseline,
+}
+
+impl ChartType {
+    pub fn all() -> &'static [Self] {
+        &[Self::Candles, Self::Ohlc, Self::Line, Self::Area, Self::Baseline]
+    }
+
+    pub fn msg(&self) -> Msg {
+        match self {
+            Self::Candles => Msg::Candles,
+            Self::Ohlc => Msg::OhlcBars,
+            Self::Line => Msg::LineSeries,
+            Self::Area => Msg::AreaSeries,
+            Self::Baseline => Msg::BaselineSeries,
+        }
+    }
+}
+
+/// How the volume pane draws each candle's volume
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum VolumeMode {
+    /// One bar colored by the candle's direction
+    #[default]
+    Total,
+    /// Buy volume stacked under sell volume
+    Split,
+    /// Buy minus sell volume, colored by sign
+    Delta,
+}
+
+impl VolumeMode {
+    pub fn all() -> &'static [Self] {
+        &[Self::Total, Self::Split, Self::Delta]
+    }
+
+    pub fn msg(&self) -> Msg {
+        match self {
+            Self::Total => Msg::VolumeTotal,
+            Self::Split => Msg::VolumeSplit,
+            Self::Delta => Msg::VolumeDelta,
+        }
+    }
+}
+
 /// Single OHLCV candlestick
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Candle {
@@ -195,8 +300,16 @@ pub struct Candle {
     pub quote_volume: f64,
     /// Number of trades in this candle
     pub trade_count: u32,
+    /// Taker buy / sell volume, when built from trades with a side
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub buy_volume: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sell_volume: Option<f64>,
     /// Is this candle still forming?
     pub is_closed: bool,
+    /// Flat placeholder injected for a missing interval
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub synthetic: bool,
 }
 
 impl Candle {
@@ -213,7 +326,19 @@ impl Candle {
             volume: Quantity::ZERO,
             quote_volume: 0.0,
             trade_count: 0,
+            buy_volume: None,
+            sell_volume: None,
             is_closed: false,
+            synthetic: false,
+        }
+    }
+
+    /// Flat, closed, zero-volume placeholder carrying the previous close forward
+    pub fn synthetic(symbol: Symbol, interval: CandleInterval, timestamp: i64, price: f64) -> Self {
+        Self {
+            is_closed: true,
+            synthetic: true,
+            ..Self::new(symbol, interval, timestamp, price)
         }
     }
 
@@ -231,6 +356,28 @@ impl Candle {
         self.trade_count += 1;
     }
 
+    /// Update candle with a trade, splitting its volume by taker side
+    pub fn add_trade(&mut self, trade: &Trade) {
+        let qty = trade.quantity.as_f64();
+        self.update(trade.price.as_f64(), qty);
+        let buy = self.buy_volume.get_or_insert(0.0);
+        let sell = self.sell_volume.get_or_insert(0.0);
+        match trade.side {
+            TradeSide::Buy => *buy += qty,
+            TradeSide::Sell => *sell += qty,
+        }
+    }
+
+    /// (buy, sell) volume, if the source reported sides
+    pub fn volume_split(&self) -> Option<(f64, f64)> {
+        self.buy_volume.zip(self.sell_volume)
+    }
+
+    /// Buy minus sell volume
+    pub fn volume_delta(&self) -> Option<f64> {
+        self.volume_split().map(|(buy, sell)| buy - sell)
+    }
+
     /// Close the candle
     pub fn close_candle(&mut self) {
         self.is_closed = true;
@@ -312,16 +459,30 @@ impl Candle {
 
     /// Detect patterns using given strategy
     pub fn detect_patterns_with<D: CandlePatternDetector>(&self, detector: &D) -> Vec<CandlePattern> {
-        detector.detect(&[self.clone()])
+        detector.detect(std::slice::from_ref(self))
     }
 }
 
+/// How an update changed the candle history
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CandleDelta {
+    /// A new candle was appended
+    Appended,
+    /// The forming candle was revised
+    ReplacedLast,
+    /// Candles were inserted or replaced wholesale (gap fill, bulk load)
+    Reloaded,
+    /// The update was dropped (out of order)
+    Rejected,
+}
+
 /// Collection of candles for charting
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CandleHistory {
     pub symbol: Symbol,
     pub interval: CandleInterval,
-    pub candles: Vec<Candle>,
+    /// Oldest first
+    pub candles: RingBuffer<Candle>,
 }
 
 impl CandleHistory {
@@ -329,15 +490,16 @@ impl CandleHistory {
         Self {
             symbol,
             interval,
-            candles: Vec::new(),
+            candles: RingBuffer::unbounded(),
         }
     }
 
+    /// History that keeps only the newest `capacity` candles
     pub fn with_capacity(symbol: Symbol, interval: CandleInterval, capacity: usize) -> Self {
         Self {
             symbol,
             interval,
-            candles: Vec::with_capacity(capacity),
+            candles: RingBuffer::new(capacity),
         }
     }
 
@@ -416,12 +578,241 @@ impl CandleHistory {
     pub fn detect_patterns_with<D: CandlePatternDetector>(&self, detector: &D) -> Vec<CandlePattern> {
         detector.detect(&self.candles)
     }
+
+    /// Scan for missing intervals, ordering problems and misaligned timestamps
+    pub fn integrity_report(&self) -> CandleIntegrityReport {
+        let step = self.interval.as_millis();
+        let mut report = CandleIntegrityReport {
+            candles: self.candles.len(),
+            ..Default::default()
+        };
+
+        for candle in &self.candles {
+            if self.interval.open_time(candle.timestamp) != candle.timestamp {
+                report.misaligned += 1;
+            }
+            if candle.synthetic {
+                report.synthetic += 1;
+            }
+        }
+
+        for pair in self.candles.windows(2) {
+            let (prev, next) = (pair[0].timestamp, pair[1].timestamp);
+            if next < prev {
+                report.out_of_order += 1;
+            } else if next == prev {
+                report.duplicates += 1;
+            } else if next - prev > step {
+                let missing = ((next - prev) / step - 1) as usize;
+                if missing > 0 {
+                    report.missing += missing;
+                    report.gaps.push(CandleGap {
+                        from: prev + step,
+                        missing,
+                    });
+                }
+            }
+        }
+
+        report
+    }
+
+    /// Sort by timestamp and drop duplicates (keeping the later entry).
+    /// Returns the number of candles removed.
+    pub fn sanitize(&mut self) -> usize {
+        self.candles.update_vec(|candles| {
+            let before = candles.len();
+            // Stable sort keeps arrival order within equal timestamps
+            candles.sort_by_key(|c| c.timestamp);
+            candles.reverse();
+            candles.dedup_by_key(|c| c.timestamp);
+            candles.reverse();
+            before - candles.len()
+        })
+    }
+
+    /// Merge `fresh` candles (oldest first) over this history: candles inside
+    /// the fresh time range are replaced, newer ones are kept (they arrived
+    /// live after the fetch) and older ones stay as history the fetch did
+    /// not cover. Returns the number of candles replaced.
+    pub fn reconcile(&mut self, fresh: Vec<Candle>) -> usize {
+        let (Some(start), Some(end)) = (
+            fresh.iter().map(|c| c.timestamp).min(),
+            fresh.iter().map(|c| c.timestamp).max(),
+        ) else {
+            return 0;
+        };
+        let replaced = self.candles.update_vec(|candles| {
+            let before = candles.len();
+            candles.retain(|c| c.timestamp < start || c.timestamp > end);
+            let replaced = before - candles.len();
+            candles.extend(fresh);
+            candles.sort_by_key(|c| c.timestamp);
+            replaced
+        });
+        self.sanitize();
+        replaced
+    }
+
+    /// Inject synthetic flat candles into gaps of up to `MAX_GAP_FILL` intervals.
+    /// Larger gaps are left for the chart to mark. Returns candles inserted.
+    pub fn fill_gaps(&mut self) -> usize {
+        let step = self.interval.as_millis();
+        let needs_fill = self.candles.windows(2).any(|pair| {
+            let missing = (pair[1].timestamp - pair[0].timestamp) / step - 1;
+            missing > 0 && missing as usize <= MAX_GAP_FILL
+        });
+        if !needs_fill {
+            return 0;
+        }
+
+        let capacity = self.candles.capacity();
+        let candles = std::mem::replace(&mut self.candles, RingBuffer::new(capacity)).into_vec();
+        let filled = &mut self.candles;
+        let mut inserted = 0;
+
+        for candle in candles {
+            if let Some(prev) = filled.last() {
+                let (prev_ts, price) = (prev.timestamp, prev.close.as_f64());
+                let missing = (candle.timestamp - prev_ts) / step - 1;
+                if missing > 0 && missing as usize <= MAX_GAP_FILL {
+                    for n in 1..=missing {
+                        filled.push(Candle::synthetic(
+                            self.symbol.clone(),
+                            self.interval,
+                            prev_ts + n * step,
+                            price,
+                        ));
+                    }
+                    inserted += missing as usize;
+                }
+            }
+            filled.push(candle);
+        }
+
+        inserted
+    }
+
+    /// Merge candles into a coarser interval (e.g. 1m -> 5m/15m/1h).
+    ///
+    /// Buckets open at `CandleInterval::open_time`. Returns `None` when the
+    /// target is not a whole multiple of the current interval.
+    pub fn resample(&self, target: CandleInterval) -> Option<CandleHistory> {
+        let source_ms = self.interval.as_millis();
+        let target_ms = target.as_millis();
+        if target_ms < source_ms || target_ms % source_ms != 0 {
+            return None;
+        }
+
+        let mut out = CandleHistory::with_capacity(self.symbol.clone(), target, self.candles.capacity());
+
+        for candle in &self.candles {
+            let bucket = target.open_time(candle.timestamp);
+            // A bucket is only closed once its final source candle has closed
+            let closes_bucket = candle.is_closed && candle.timestamp == bucket + target_ms - source_ms;
+
+            match out.candles.last_mut() {
+                Some(agg) if agg.timestamp == bucket => {
+                    agg.high = Price::new(agg.high.as_f64().max(candle.high.as_f64()));
+                    agg.low = Price::new(agg.low.as_f64().min(candle.low.as_f64()));
+                    agg.close = candle.close;
+                    agg.volume = Quantity::new(agg.volume.as_f64() + candle.volume.as_f64());
+                    agg.quote_volume += candle.quote_volume;
+                    agg.trade_count += candle.trade_count;
+                    // The split survives only if every source candle has one
+                    agg.buy_volume = agg.buy_volume.zip(candle.buy_volume).map(|(a, b)| a + b);
+                    agg.sell_volume = agg.sell_volume.zip(candle.sell_volume).map(|(a, b)| a + b);
+                    agg.is_closed = closes_bucket;
+                }
+                _ => out.candles.push(Candle {
+                    interval: target,
+                    timestamp: bucket,
+                    is_closed: closes_bucket,
+                    ..candle.clone()
+                }),
+            }
+        }
+
+        Some(out)
+    }
+}
+
+// ============================================================================
+// INTEGRITY
+// ============================================================================
+
+/// Largest gap (in intervals) filled with synthetic candles
+pub const MAX_GAP_FILL: usize = 60;
+
+/// Run of missing candles
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CandleGap {
+    /// Open time of the first missing candle
+    pub from: i64,
+    /// Number of consecutive intervals missing
+    pub missing: usize,
+}
+
+/// Result of `CandleHistory::integrity_report`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CandleIntegrityReport {
+    pub candles: usize,
+    pub gaps: Vec<CandleGap>,
+    /// Total missing intervals across all gaps
+    pub missing: usize,
+    pub out_of_order: usize,
+    pub duplicates: usize,
+    /// Timestamps not aligned to the interval boundary
+    pub misaligned: usize,
+    /// Placeholders injected by `fill_gaps`
+    pub synthetic: usize,
+}
+
+impl CandleIntegrityReport {
+    /// No gaps, ordering problems or misaligned candles
+    pub fn is_clean(&self) -> bool {
+        self.gaps.is_empty() && self.out_of_order == 0 && self.duplicates == 0 && self.misaligned == 0
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn history_at(timestamps: &[i64]) -> CandleHistory {
+        let mut history = CandleHistory::new(Symbol::default(), CandleInterval::M1);
+        for &ts in timestamps {
+            history.push(Candle::new(Symbol::default(), CandleInterval::M1, ts, 100.0));
+        }
+        history
+    }
+
+    #[test]
+    fn test_interval_code() {
+        for interval in CandleInterval::all() {
+            assert_eq!(CandleInterval::from_code(interval.code()), Some(*interval));
+            assert_eq!(CandleInterval::from_code(interval.label()), Some(*interval));
+        }
+        assert_eq!(CandleInterval::from_code("7x"), None);
+    }
+
+    #[test]
+    fn test_interval_coarsen_for() {
+        let day = 86_400_000;
+        assert_eq!(CandleInterval::M1.coarsen_for(60 * 60_000, 500), CandleInterval::M1);
+        assert_eq!(CandleInterval::M1.coarsen_for(day, 500), CandleInterval::M5);
+        assert_eq!(CandleInterval::M1.coarsen_for(60 * day, 500), CandleInterval::H4);
+        assert_eq!(CandleInterval::H1.coarsen_for(60_000, 500), CandleInterval::H1);
+    }
+
+    #[test]
+    fn test_interval_next_close() {
+        assert_eq!(CandleInterval::M1.next_close(0), 60_000);
+        assert_eq!(CandleInterval::M1.next_close(59_999), 60_000);
+        assert_eq!(CandleInterval::M1.next_close(60_000), 120_000);
+        assert_eq!(CandleInterval::H1.next_close(1_000), 3_600_000);
+    }
+
     #[test]
     fn test_candle_update() {
         let mut candle = Candle::new(Symbol::default(), CandleInterval::M1, 1700000000000, 50000.0);
@@ -448,6 +839,149 @@ mod tests {
         assert!(bearish.is_bearish());
     }
 
+    #[test]
+    fn test_reconcile() {
+        let candle = |ts: i64, close: f64| {
+            let mut c = Candle::new(Symbol::default(), CandleInterval::M1, ts, 100.0);
+            c.update(close, 1.0);
+            c
+        };
+        // Cached 0..=3 min, with the last one still forming when stored
+        let mut history = CandleHistory::new(Symbol::default(), CandleInterval::M1);
+        for i in 0..4 {
+            history.push(candle(i * 60_000, 100.0));
+        }
+        // The server covers 2..=5 min: 2 and 3 are replaced, 4 and 5 appended
+        let fresh = (2..6).map(|i| candle(i * 60_000, 200.0)).collect();
+        assert_eq!(history.reconcile(fresh), 2);
+        assert_eq!(history.len(), 6);
+        let closes: Vec<f64> = history.candles.iter().map(|c| c.close.as_f64()).collect();
+        assert_eq!(closes, vec![100.0, 100.0, 200.0, 200.0, 200.0, 200.0]);
+        assert_eq!(history.reconcile(Vec::new()), 0);
+    }
+
+    #[test]
+    fn test_volume_split() {
+        let trade = |qty, side| Trade::new(Symbol::default(), 100.0, qty, side);
+        let mut candle = Candle::new(Symbol::default(), CandleInterval::M1, 0, 100.0);
+        assert_eq!(candle.volume_delta(), None);
+        candle.add_trade(&trade(2.0, TradeSide::Buy));
+        candle.add_trade(&trade(0.5, TradeSide::Sell));
+        assert_eq!(candle.volume_split(), Some((2.0, 0.5)));
+        assert_eq!(candle.volume_delta(), Some(1.5));
+
+        // Resampled buckets keep the split only if every part had one
+        let mut history = CandleHistory::new(Symbol::default(), CandleInterval::M1);
+        history.push(candle.clone());
+        history.push(Candle::new(Symbol::default(), CandleInterval::M1, 60_000, 100.0));
+        candle.timestamp = 300_000;
+        history.push(candle);
+        let m5 = history.resample(CandleInterval::M5).unwrap();
+        assert_eq!(m5.candles[0].volume_split(), None);
+        assert_eq!(m5.candles[1].volume_split(), Some((2.0, 0.5)));
+
+        let plain = Candle::new(Symbol::default(), CandleInterval::M1, 0, 1.0);
+        assert!(!serde_json::to_string(&plain).unwrap().contains("buy_volume"));
+    }
+
+    #[test]
+    fn test_resample() {
+        let mut history = CandleHistory::new(Symbol::default(), CandleInterval::M1);
+        for (i, (open, high, low, close)) in [
+            (100.0, 105.0, 99.0, 104.0),
+            (104.0, 110.0, 103.0, 108.0),
+            (108.0, 109.0, 95.0, 96.0),
+            (96.0, 98.0, 94.0, 97.0),
+            (97.0, 101.0, 96.0, 100.0),
+            (100.0, 102.0, 99.0, 101.0),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let mut candle = Candle::new(Symbol::default(), CandleInterval::M1, i as i64 * 60_000, open);
+            candle.high = Price::new(high);
+            candle.low = Price::new(low);
+            candle.close = Price::new(close);
+            candle.volume = Quantity::new(1.0);
+            candle.quote_volume = close;
+            candle.trade_count = 2;
+            candle.is_closed = i < 5;
+            history.push(candle);
+        }
+
+        let m5 = history.resample(CandleInterval::M5).unwrap();
+        assert_eq!(m5.interval, CandleInterval::M5);
+        assert_eq!(m5.len(), 2);
+
+        let first = &m5.candles[0];
+        assert_eq!(first.timestamp, 0);
+        assert_eq!(first.open.as_f64(), 100.0);
+        assert_eq!(first.high.as_f64(), 110.0);
+        assert_eq!(first.low.as_f64(), 94.0);
+        assert_eq!(first.close.as_f64(), 100.0);
+        assert_eq!(first.volume.as_f64(), 5.0);
+        assert_eq!(first.trade_count, 10);
+        assert!(first.is_closed);
+
+        // Second bucket only has its first minute, still forming
+        let second = &m5.candles[1];
+        assert_eq!(second.timestamp, 300_000);
+        assert!(!second.is_closed);
+
+        assert!(history.resample(CandleInterval::M1).is_some());
+        assert!(m5.resample(CandleInterval::M1).is_none());
+
+        // Weeks open on Monday: Thu 1 - Sun 4 Jan 1970 belong to the week of Mon 29 Dec
+        let day = CandleInterval::D1.as_millis();
+        let mut daily = CandleHistory::new(Symbol::default(), CandleInterval::D1);
+        for i in 0..12 {
+            let mut candle = Candle::new(Symbol::default(), CandleInterval::D1, i * day, 100.0);
+            candle.is_closed = true;
+            daily.push(candle);
+        }
+        let w1 = daily.resample(CandleInterval::W1).unwrap();
+        let opens: Vec<i64> = w1.candles.iter().map(|c| c.timestamp).collect();
+        assert_eq!(opens, vec![-3 * day, 4 * day, 11 * day]);
+        assert!(w1.candles[0].is_closed && w1.candles[1].is_closed);
+        assert!(!w1.candles[2].is_closed);
+        assert_eq!(CandleInterval::W1.next_close(4 * day), 11 * day);
+    }
+
+    #[test]
+    fn test_integrity_report() {
+        let clean = history_at(&[0, 60_000, 120_000]);
+        assert!(clean.integrity_report().is_clean());
+
+        let report = history_at(&[0, 60_000, 240_000, 180_000, 180_000, 200_000]).integrity_report();
+        assert_eq!(report.gaps, vec![CandleGap { from: 120_000, missing: 2 }]);
+        assert_eq!(report.missing, 2);
+        assert_eq!(report.out_of_order, 1);
+        assert_eq!(report.duplicates, 1);
+        assert_eq!(report.misaligned, 1);
+        assert!(!report.is_clean());
+    }
+
+    #[test]
+    fn test_sanitize_and_fill_gaps() {
+        let mut history = history_at(&[120_000, 0, 0, 300_000]);
+        assert_eq!(history.sanitize(), 1);
+
+        assert_eq!(history.fill_gaps(), 3);
+        let timestamps: Vec<i64> = history.candles.iter().map(|c| c.timestamp).collect();
+        assert_eq!(timestamps, vec![0, 60_000, 120_000, 180_000, 240_000, 300_000]);
+        assert!(history.candles[1].synthetic);
+        assert_eq!(history.candles[1].volume.as_f64(), 0.0);
+
+        let report = history.integrity_report();
+        assert!(report.is_clean());
+        assert_eq!(report.synthetic, 3);
+
+        // Oversized gaps are left for markers
+        let mut sparse = history_at(&[0, (MAX_GAP_FILL as i64 + 2) * 60_000]);
+        assert_eq!(sparse.fill_gaps(), 0);
+        assert_eq!(sparse.integrity_report().gaps.len(), 1);
+    }
+
     #[test]
     fn test_doji_detection() {
         let detector = BasicPatternDetector::new();
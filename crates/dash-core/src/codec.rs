@@ -0,0 +1,93 @@
+//! Wire encodings for server → client frames
+//!
+//! Frames are JSON `WsMessage`s, sent either as plain text or as gzip-compressed
+//! binary frames. The encoding is negotiated with a query parameter on the
+//! WebSocket URL (`?encoding=gzip`); clients accept both forms regardless.
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::io::{Read, Write};
+
+/// Query parameter used to request a wire encoding
+pub const ENCODING_PARAM: &str = "encoding";
+
+/// Frames smaller than this are sent as text even when gzip is negotiated
+pub const GZIP_MIN_BYTES: usize = 256;
+
+/// gzip magic header
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Negotiated frame encoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WireEncoding {
+    /// Plain JSON text frames
+    #[default]
+    Text,
+    /// gzip-compressed JSON in binary frames
+    Gzip,
+}
+
+impl WireEncoding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Gzip => "gzip",
+        }
+    }
+
+    /// Parse the `encoding` query value (unknown values fall back to text)
+    pub fn from_param(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "gzip" => Self::Gzip,
+            _ => Self::Text,
+        }
+    }
+}
+
+impl std::fmt::Display for WireEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// Compress a payload with gzip
+pub fn gzip_encode(bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::with_capacity(bytes.len() / 4), Compression::fast());
+    encoder.write_all(bytes)?;
+    encoder.finish()
+}
+
+/// Decompress a gzip payload
+pub fn gzip_decode(bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut out = Vec::with_capacity(bytes.len() * 4);
+    GzDecoder::new(bytes).read_to_end(&mut out)?;
+    Ok(out)
+}
+
+/// Does this payload start with the gzip header?
+pub fn is_gzip(bytes: &[u8]) -> bool {
+    bytes.starts_with(&GZIP_MAGIC)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gzip_roundtrip() {
+        let json = r#"{"type":"heartbeat","data":{"timestamp":1}}"#.repeat(20);
+        let compressed = gzip_encode(json.as_bytes()).unwrap();
+
+        assert!(is_gzip(&compressed));
+        assert!(compressed.len() < json.len());
+        assert_eq!(gzip_decode(&compressed).unwrap(), json.as_bytes());
+    }
+
+    #[test]
+    fn test_encoding_param() {
+        assert_eq!(WireEncoding::from_param("GZIP"), WireEncoding::Gzip);
+        assert_eq!(WireEncoding::from_param("brotli"), WireEncoding::Text);
+        assert!(!is_gzip(b"{\"type\":\"trade\"}"));
+    }
+}
@@ -0,0 +1,295 @@
+//! Bounded collections for hot-path market data buffers
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::ops::{Deref, DerefMut};
+
+/// Bounded FIFO buffer stored oldest-first in one contiguous slice.
+///
+/// Pushing past capacity evicts the oldest item in amortized O(1): the head
+/// index advances and evicted slots are compacted away once they outnumber
+/// the live items. Derefs to `[T]`, so slice methods (`last`, `windows`,
+/// `partition_point`, ...) work directly on the live items.
+pub struct RingBuffer<T> {
+    items: Vec<T>,
+    /// Index of the oldest live item; slots before it are evicted
+    head: usize,
+    capacity: usize,
+}
+
+impl<T> RingBuffer<T> {
+    /// Buffer holding at most `capacity` items (minimum 1)
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            items: Vec::with_capacity(capacity.min(1024)),
+            head: 0,
+            capacity,
+        }
+    }
+
+    /// Buffer that never evicts
+    pub fn unbounded() -> Self {
+        Self {
+            items: Vec::new(),
+            head: 0,
+            capacity: usize::MAX,
+        }
+    }
+
+    /// Build from oldest-first items, keeping the newest `capacity`
+    pub fn from_vec(items: Vec<T>, capacity: usize) -> Self {
+        let mut buffer = Self {
+            items,
+            head: 0,
+            capacity: capacity.max(1),
+        };
+        buffer.trim();
+        buffer
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len() >= self.capacity
+    }
+
+    /// Append as newest, evicting the oldest when full
+    pub fn push(&mut self, item: T) {
+        if self.is_full() {
+            self.head += 1;
+        }
+        self.items.push(item);
+        self.compact_if_sparse();
+    }
+
+    /// Change the bound, evicting the oldest items if needed
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        self.trim();
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.head = 0;
+    }
+
+    // ========================================================================
+    // Iteration order helpers
+    // ========================================================================
+
+    /// Most recently pushed item
+    pub fn latest(&self) -> Option<&T> {
+        self.as_slice().last()
+    }
+
+    /// Oldest retained item
+    pub fn oldest(&self) -> Option<&T> {
+        self.as_slice().first()
+    }
+
+    /// Iterate newest to oldest (tape / ladder order)
+    pub fn newest_first(&self) -> std::iter::Rev<std::slice::Iter<'_, T>> {
+        self.as_slice().iter().rev()
+    }
+
+    /// Newest `n` items, newest first
+    pub fn recent(&self, n: usize) -> impl Iterator<Item = &T> {
+        self.newest_first().take(n)
+    }
+
+    pub fn as_slice(&self) -> &[T] {
+        &self.items[self.head..]
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        &mut self.items[self.head..]
+    }
+
+    /// Live items as an oldest-first `Vec`
+    pub fn into_vec(mut self) -> Vec<T> {
+        self.items.drain(..self.head);
+        self.items
+    }
+
+    /// Edit the live items as a `Vec` (sort, dedup, splice, ...), then re-apply the bound
+    pub fn update_vec<R>(&mut self, f: impl FnOnce(&mut Vec<T>) -> R) -> R {
+        self.compact();
+        let result = f(&mut self.items);
+        self.trim();
+        result
+    }
+
+    /// Keep only items matching the predicate
+    pub fn retain(&mut self, f: impl FnMut(&T) -> bool) {
+        self.update_vec(|items| items.retain(f));
+    }
+
+    /// Drop evicted slots so the live items start at index 0
+    fn compact(&mut self) {
+        if self.head > 0 {
+            self.items.drain(..self.head);
+            self.head = 0;
+        }
+    }
+
+    /// Compact once evicted slots outnumber live items (amortized O(1) per push)
+    fn compact_if_sparse(&mut self) {
+        if self.head > 0 && self.head >= self.items.len() - self.head {
+            self.compact();
+        }
+    }
+
+    /// Evict oldest items beyond capacity
+    fn trim(&mut self) {
+        let excess = self.len().saturating_sub(self.capacity);
+        if excess > 0 {
+            self.head += excess;
+            self.compact();
+        }
+    }
+}
+
+impl<T> Default for RingBuffer<T> {
+    fn default() -> Self {
+        Self::unbounded()
+    }
+}
+
+impl<T> From<Vec<T>> for RingBuffer<T> {
+    /// Unbounded buffer over oldest-first items
+    fn from(items: Vec<T>) -> Self {
+        Self {
+            items,
+            head: 0,
+            capacity: usize::MAX,
+        }
+    }
+}
+
+impl<T> Deref for RingBuffer<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        self.as_slice()
+    }
+}
+
+impl<T> DerefMut for RingBuffer<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T: Clone> Clone for RingBuffer<T> {
+    fn clone(&self) -> Self {
+        // Only live items are copied
+        Self {
+            items: self.as_slice().to_vec(),
+            head: 0,
+            capacity: self.capacity,
+        }
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for RingBuffer<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RingBuffer")
+            .field("capacity", &self.capacity)
+            .field("items", &self.as_slice())
+            .finish()
+    }
+}
+
+impl<T: PartialEq> PartialEq for RingBuffer<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl<T> Extend<T> for RingBuffer<T> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.push(item);
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for &'a RingBuffer<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.as_slice().iter()
+    }
+}
+
+/// Serialized as a plain oldest-first sequence
+impl<T: Serialize> Serialize for RingBuffer<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.as_slice().serialize(serializer)
+    }
+}
+
+/// Deserialized buffers are unbounded; callers re-apply a bound with `set_capacity`
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for RingBuffer<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<T>::deserialize(deserializer).map(Self::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_buffer_eviction() {
+        let mut buffer = RingBuffer::new(3);
+        buffer.extend(1..=5);
+
+        assert_eq!(buffer.as_slice(), &[3, 4, 5]);
+        assert_eq!(buffer.latest(), Some(&5));
+        assert_eq!(buffer.oldest(), Some(&3));
+        assert!(buffer.is_full());
+
+        let newest: Vec<_> = buffer.newest_first().copied().collect();
+        assert_eq!(newest, vec![5, 4, 3]);
+        assert_eq!(buffer.recent(2).count(), 2);
+    }
+
+    #[test]
+    fn test_ring_buffer_compaction() {
+        let mut buffer = RingBuffer::new(4);
+        buffer.extend(0..1_000);
+
+        assert_eq!(buffer.as_slice(), &[996, 997, 998, 999]);
+        // Evicted slots never exceed the live items
+        assert!(buffer.items.len() <= 2 * buffer.capacity());
+    }
+
+    #[test]
+    fn test_ring_buffer_update_vec() {
+        let mut buffer = RingBuffer::from_vec(vec![5, 1, 4, 2, 3], 4);
+        assert_eq!(buffer.as_slice(), &[1, 4, 2, 3]);
+
+        buffer.update_vec(|items| {
+            items.sort();
+            items.push(9);
+        });
+        assert_eq!(buffer.as_slice(), &[2, 3, 4, 9]);
+
+        buffer.retain(|x| x % 2 == 0);
+        assert_eq!(buffer.into_vec(), vec![2, 4]);
+    }
+
+    #[test]
+    fn test_ring_buffer_serde() {
+        let buffer = RingBuffer::from_vec(vec![1, 2, 3], 2);
+        let json = serde_json::to_string(&buffer).unwrap();
+        assert_eq!(json, "[2,3]");
+
+        let back: RingBuffer<i32> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, buffer);
+    }
+}
@@ -0,0 +1,196 @@
+//! Cross-venue consolidation: merged order book and best bid/offer (BBO)
+
+use serde::{Deserialize, Serialize};
+
+use crate::{OrderBookLevel, OrderBookSnapshot, Price, Quantity, Symbol};
+
+/// Venue label used for merged books
+pub const CONSOLIDATED_VENUE: &str = "consolidated";
+
+/// One venue's quantity at a price
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VenueQuote {
+    pub venue: String,
+    pub price: Price,
+    pub quantity: Quantity,
+    /// Orders at this price on the venue
+    pub orders: u32,
+}
+
+/// Consolidated price level with per-venue attribution
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsolidatedLevel {
+    pub price: Price,
+    /// Total across venues
+    pub quantity: Quantity,
+    pub venues: Vec<VenueQuote>,
+}
+
+/// Best bid and offer across venues
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Bbo {
+    pub bid: Option<VenueQuote>,
+    pub ask: Option<VenueQuote>,
+}
+
+impl Bbo {
+    pub fn spread(&self) -> Option<f64> {
+        Some(self.ask.as_ref()?.price.as_f64() - self.bid.as_ref()?.price.as_f64())
+    }
+
+    /// Best bid at or above best ask (an arbitrage window or stale venue)
+    pub fn is_crossed(&self) -> bool {
+        self.spread().is_some_and(|s| s <= 0.0)
+    }
+}
+
+/// Order book merged from several venues for one pair
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsolidatedBook {
+    pub symbol: Symbol,
+    /// Sorted by price descending
+    pub bids: Vec<ConsolidatedLevel>,
+    /// Sorted by price ascending
+    pub asks: Vec<ConsolidatedLevel>,
+    pub bbo: Bbo,
+    /// Venues contributing to this book
+    pub venues: Vec<String>,
+    pub timestamp: i64,
+}
+
+impl ConsolidatedBook {
+    /// Keep only the best `levels` per side (the BBO is unaffected)
+    pub fn truncate(&mut self, levels: usize) {
+        self.bids.truncate(levels);
+        self.asks.truncate(levels);
+    }
+
+    /// Merge venue books (each tagged with `venue`) for the same symbol.
+    /// Untagged books are attributed to `"unknown"`.
+    pub fn merge(symbol: Symbol, books: &[&OrderBookSnapshot]) -> Self {
+        let venue = |book: &OrderBookSnapshot| book.venue.clone().unwrap_or_else(|| "unknown".into());
+        let side = |levels: fn(&OrderBookSnapshot) -> &[OrderBookLevel], descending: bool| {
+            let mut quotes: Vec<VenueQuote> = books
+                .iter()
+                .flat_map(|book| {
+                    levels(book).iter().map(|level| VenueQuote {
+                        venue: venue(book),
+                        price: level.price,
+                        quantity: level.quantity,
+                        orders: level.order_count,
+                    })
+                })
+                .collect();
+            quotes.sort_by(|a, b| {
+                let ord = a.price.as_f64().total_cmp(&b.price.as_f64());
+                if descending { ord.reverse() } else { ord }
+            });
+            merge_levels(quotes)
+        };
+
+        let bids = side(|b| &b.bids, true);
+        let asks = side(|b| &b.asks, false);
+        let best = |levels: &[ConsolidatedLevel]| {
+            // Largest venue quote at the top price
+            levels.first().and_then(|level| {
+                level
+                    .venues
+                    .iter()
+                    .max_by(|a, b| a.quantity.as_f64().total_cmp(&b.quantity.as_f64()))
+                    .cloned()
+            })
+        };
+
+        let mut venues: Vec<String> = books.iter().map(|b| venue(b)).collect();
+        venues.sort();
+        venues.dedup();
+
+        Self {
+            symbol,
+            bbo: Bbo {
+                bid: best(&bids),
+                ask: best(&asks),
+            },
+            bids,
+            asks,
+            venues,
+            timestamp: books.iter().map(|b| b.timestamp).max().unwrap_or_default(),
+        }
+    }
+
+    /// Plain snapshot for components that render a single book
+    pub fn to_snapshot(&self) -> OrderBookSnapshot {
+        let flatten = |levels: &[ConsolidatedLevel]| {
+            levels
+                .iter()
+                .map(|l| {
+                    let orders = l.venues.iter().map(|v| v.orders).sum();
+                    OrderBookLevel::new(l.price.as_f64(), l.quantity.as_f64(), orders)
+                })
+                .collect()
+        };
+        OrderBookSnapshot {
+            symbol: self.symbol.clone(),
+            bids: flatten(&self.bids),
+            asks: flatten(&self.asks),
+            timestamp: self.timestamp,
+            sequence: 0,
+            venue: Some(CONSOLIDATED_VENUE.to_string()),
+        }
+    }
+}
+
+/// Group sorted quotes into levels with identical prices
+fn merge_levels(quotes: Vec<VenueQuote>) -> Vec<ConsolidatedLevel> {
+    let mut levels: Vec<ConsolidatedLevel> = Vec::new();
+    for quote in quotes {
+        match levels.last_mut() {
+            Some(level) if level.price == quote.price => {
+                level.quantity = Quantity::new(level.quantity.as_f64() + quote.quantity.as_f64());
+                level.venues.push(quote);
+            }
+            _ => levels.push(ConsolidatedLevel {
+                price: quote.price,
+                quantity: quote.quantity,
+                venues: vec![quote],
+            }),
+        }
+    }
+    levels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn book(venue: &str, bids: &[(f64, f64)], asks: &[(f64, f64)]) -> OrderBookSnapshot {
+        let mut book = OrderBookSnapshot::new(Symbol::new("BTC-USD"));
+        book.bids = bids.iter().map(|(p, q)| OrderBookLevel::new(*p, *q, 1)).collect();
+        book.asks = asks.iter().map(|(p, q)| OrderBookLevel::new(*p, *q, 1)).collect();
+        book.venue = Some(venue.to_string());
+        book
+    }
+
+    #[test]
+    fn test_merge_attributes_venues() {
+        let a = book("kraken", &[(100.0, 1.0), (99.0, 2.0)], &[(101.0, 1.0)]);
+        let b = book("okx", &[(100.0, 0.5), (99.5, 1.0)], &[(100.5, 3.0)]);
+        let merged = ConsolidatedBook::merge(Symbol::new("BTC-USD"), &[&a, &b]);
+
+        assert_eq!(merged.venues, vec!["kraken", "okx"]);
+        let prices: Vec<f64> = merged.bids.iter().map(|l| l.price.as_f64()).collect();
+        assert_eq!(prices, vec![100.0, 99.5, 99.0]);
+        assert_eq!(merged.bids[0].quantity.as_f64(), 1.5);
+        assert_eq!(merged.bids[0].venues.len(), 2);
+
+        assert_eq!(merged.bbo.bid.as_ref().unwrap().venue, "kraken");
+        assert_eq!(merged.bbo.ask.as_ref().unwrap().venue, "okx");
+        assert_eq!(merged.bbo.spread(), Some(0.5));
+        assert!(!merged.bbo.is_crossed());
+
+        let snapshot = merged.to_snapshot();
+        assert_eq!(snapshot.best_ask().unwrap().price.as_f64(), 100.5);
+        assert_eq!(snapshot.best_bid().unwrap().order_count, 2);
+        assert_eq!(snapshot.venue.as_deref(), Some(CONSOLIDATED_VENUE));
+    }
+}
@@ -0,0 +1,356 @@
+//! Tolerant decoding of inbound frames
+//!
+//! A frame that fails strict decoding gets a second attempt with per-field
+//! fallbacks: numbers sent as strings are coerced and missing auxiliary
+//! fields take a neutral default. A message type that keeps failing is
+//! quarantined for a while, so a broken feed does not pay for a repair
+//! attempt (and a log line) on every frame. Every outcome is counted per
+//! message type for metrics.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::{decode_frame, WsEnvelope, WsMessage};
+
+/// Consecutive failures of one message type before it is quarantined
+pub const QUARANTINE_AFTER: u32 = 5;
+
+/// How long a quarantined message type is dropped unread
+pub const QUARANTINE_MS: i64 = 30_000;
+
+/// Type key for frames that are not JSON objects with a `type`
+pub const UNTYPED: &str = "untyped";
+
+impl WsMessage {
+    /// Wire `type` tag
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Trade(_) => "trade",
+            Self::OrderBook(_) => "orderbook",
+            Self::Ticker(_) => "ticker",
+            Self::Candle(_) => "candle",
+            Self::Depth(_) => "depth",
+            Self::Heartbeat { .. } => "heartbeat",
+            Self::SourceStatus(_) => "source_status",
+            Self::ConsolidatedBook(_) => "consolidated_book",
+            Self::News(_) => "news",
+            Self::AlertTriggered(_) => "alert_triggered",
+        }
+    }
+}
+
+/// Value for a field missing from a payload
+#[derive(Debug, Clone, Copy)]
+enum Fallback {
+    Zero,
+    False,
+}
+
+/// Fields of one message type that may be repaired
+struct FieldRules {
+    kind: &'static str,
+    /// Accepted as JSON strings holding a number
+    numeric: &'static [&'static str],
+    /// Filled in when missing or null
+    defaults: &'static [(&'static str, Fallback)],
+}
+
+const FIELD_RULES: &[FieldRules] = &[
+    FieldRules { kind: "trade", numeric: &["price", "quantity"], defaults: &[] },
+    FieldRules {
+        kind: "ticker",
+        numeric: &[
+            "last_price",
+            "bid_price",
+            "bid_qty",
+            "ask_price",
+            "ask_qty",
+            "high_24h",
+            "low_24h",
+            "volume_24h",
+            "quote_volume_24h",
+            "change_24h",
+            "change_percent_24h",
+            "open_24h",
+            "trade_count_24h",
+            "timestamp",
+        ],
+        defaults: &[
+            ("bid_qty", Fallback::Zero),
+            ("ask_qty", Fallback::Zero),
+            ("volume_24h", Fallback::Zero),
+            ("quote_volume_24h", Fallback::Zero),
+            ("change_24h", Fallback::Zero),
+            ("change_percent_24h", Fallback::Zero),
+            ("trade_count_24h", Fallback::Zero),
+        ],
+    },
+    FieldRules {
+        kind: "candle",
+        numeric: &[
+            "timestamp",
+            "open",
+            "high",
+            "low",
+            "close",
+            "volume",
+            "quote_volume",
+            "trade_count",
+        ],
+        defaults: &[
+            ("quote_volume", Fallback::Zero),
+            ("trade_count", Fallback::Zero),
+            ("is_closed", Fallback::False),
+        ],
+    },
+    FieldRules { kind: "heartbeat", numeric: &["timestamp"], defaults: &[] },
+];
+
+/// Number held in a JSON string, as an integer when it is one
+fn coerce_number(text: &str) -> Option<Value> {
+    let text = text.trim();
+    if let Ok(int) = text.parse::<i64>() {
+        return Some(Value::from(int));
+    }
+    text.parse::<f64>().ok().and_then(serde_json::Number::from_f64).map(Value::Number)
+}
+
+/// Apply the fallbacks for `kind` to a payload; false when nothing changed
+fn repair(kind: &str, data: &mut Map<String, Value>) -> bool {
+    let Some(rules) = FIELD_RULES.iter().find(|r| r.kind == kind) else {
+        return false;
+    };
+    let mut changed = false;
+    for field in rules.numeric {
+        if let Some(value) = data.get_mut(*field)
+            && let Some(number) = value.as_str().and_then(coerce_number)
+        {
+            *value = number;
+            changed = true;
+        }
+    }
+    for (field, fallback) in rules.defaults {
+        if data.get(*field).is_none_or(Value::is_null) {
+            let value = match fallback {
+                Fallback::Zero => Value::from(0),
+                Fallback::False => Value::Bool(false),
+            };
+            data.insert(field.to_string(), value);
+            changed = true;
+        }
+    }
+    changed
+}
+
+/// Decode counters for one message type
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TypeStats {
+    /// Decoded as sent
+    pub decoded: u64,
+    /// Decoded after field fallbacks
+    pub recovered: u64,
+    pub failed: u64,
+    /// Skipped while quarantined
+    pub dropped: u64,
+    pub consecutive_failures: u32,
+    /// Local millis until which the type is skipped
+    pub quarantined_until: Option<i64>,
+    pub last_error: Option<String>,
+}
+
+impl TypeStats {
+    pub fn is_quarantined(&self, now_ms: i64) -> bool {
+        self.quarantined_until.is_some_and(|until| now_ms < until)
+    }
+}
+
+/// Why a frame produced no message
+#[derive(Debug, Clone, PartialEq)]
+pub enum Rejected {
+    /// Unusable even with fallbacks
+    Failed { kind: String, error: String },
+    /// Failed, and that quarantined its type
+    Quarantined { kind: String, error: String },
+    /// Skipped because its type is quarantined
+    Dropped { kind: String },
+}
+
+/// Per message type decode metrics
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DecodeStats {
+    pub types: BTreeMap<String, TypeStats>,
+}
+
+impl DecodeStats {
+    fn entry(&mut self, kind: &str) -> &mut TypeStats {
+        self.types.entry(kind.to_string()).or_default()
+    }
+
+    pub fn failed(&self) -> u64 {
+        self.types.values().map(|t| t.failed).sum()
+    }
+
+    pub fn recovered(&self) -> u64 {
+        self.types.values().map(|t| t.recovered).sum()
+    }
+
+    pub fn dropped(&self) -> u64 {
+        self.types.values().map(|t| t.dropped).sum()
+    }
+
+    /// Message types currently skipped
+    pub fn quarantined(&self, now_ms: i64) -> impl Iterator<Item = &str> {
+        self.types
+            .iter()
+            .filter(move |(_, stats)| stats.is_quarantined(now_ms))
+            .map(|(kind, _)| kind.as_str())
+    }
+}
+
+/// Frame decoder with field fallbacks, quarantine and metrics
+#[derive(Debug, Clone)]
+pub struct TolerantDecoder {
+    pub stats: DecodeStats,
+    quarantine_after: u32,
+    quarantine_ms: i64,
+}
+
+impl Default for TolerantDecoder {
+    fn default() -> Self {
+        Self::new(QUARANTINE_AFTER, QUARANTINE_MS)
+    }
+}
+
+impl TolerantDecoder {
+    pub fn new(quarantine_after: u32, quarantine_ms: i64) -> Self {
+        Self {
+            stats: DecodeStats::default(),
+            quarantine_after: quarantine_after.max(1),
+            quarantine_ms,
+        }
+    }
+
+    /// Decode one frame received at `now_ms`
+    pub fn decode(&mut self, text: &str, now_ms: i64) -> Result<WsEnvelope, Rejected> {
+        let error = match decode_frame(text) {
+            Ok(frame) => {
+                let stats = self.stats.entry(frame.message.kind());
+                stats.decoded += 1;
+                stats.consecutive_failures = 0;
+                stats.quarantined_until = None;
+                return Ok(frame);
+            }
+            Err(e) => e,
+        };
+
+        let Ok(Value::Object(mut frame)) = serde_json::from_str::<Value>(text) else {
+            return Err(self.fail(UNTYPED, error.to_string(), now_ms));
+        };
+        let kind = frame.get("type").and_then(Value::as_str).unwrap_or(UNTYPED).to_string();
+        if self.stats.entry(&kind).is_quarantined(now_ms) {
+            self.stats.entry(&kind).dropped += 1;
+            return Err(Rejected::Dropped { kind });
+        }
+
+        let repaired = match frame.get_mut("data") {
+            Some(Value::Object(data)) => repair(&kind, data),
+            _ => false,
+        };
+        let recovered = repaired
+            .then(|| serde_json::from_value::<WsEnvelope>(Value::Object(frame)).ok())
+            .flatten();
+        match recovered {
+            Some(envelope) => {
+                let stats = self.stats.entry(&kind);
+                stats.recovered += 1;
+                stats.consecutive_failures = 0;
+                Ok(envelope)
+            }
+            None => Err(self.fail(&kind, error.to_string(), now_ms)),
+        }
+    }
+
+    fn fail(&mut self, kind: &str, error: String, now_ms: i64) -> Rejected {
+        let stats = self.stats.entry(kind);
+        stats.failed += 1;
+        stats.consecutive_failures += 1;
+        stats.last_error = Some(error.clone());
+        let kind = kind.to_string();
+        if stats.consecutive_failures < self.quarantine_after {
+            return Rejected::Failed { kind, error };
+        }
+        stats.consecutive_failures = 0;
+        stats.quarantined_until = Some(now_ms + self.quarantine_ms);
+        Rejected::Quarantined { kind, error }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CANDLE: &str = r#"{"v":2,"type":"candle","data":{"symbol":"BTC-USD","interval":"1m",
+        "timestamp":60000,"open":1.0,"high":2.0,"low":0.5,"close":1.5,"volume":3.0,
+        "quote_volume":4.5,"trade_count":7,"is_closed":false}}"#;
+
+    #[test]
+    fn test_field_fallbacks() {
+        let mut decoder = TolerantDecoder::default();
+        assert!(decoder.decode(CANDLE, 0).is_ok());
+
+        // Venue-style string numbers and a missing trade count
+        let sloppy = CANDLE
+            .replace(r#""close":1.5"#, r#""close":"1.5""#)
+            .replace(r#""timestamp":60000"#, r#""timestamp":"60000""#)
+            .replace(r#","trade_count":7"#, "");
+        let frame = decoder.decode(&sloppy, 0).expect("recovered");
+        match frame.message {
+            WsMessage::Candle(candle) => {
+                assert_eq!((candle.close.as_f64(), candle.timestamp), (1.5, 60_000));
+                assert_eq!(candle.trade_count, 0);
+            }
+            other => panic!("expected candle, got {:?}", other),
+        }
+        let stats = &decoder.stats.types["candle"];
+        assert_eq!((stats.decoded, stats.recovered, stats.failed), (1, 1, 0));
+    }
+
+    #[test]
+    fn test_quarantine() {
+        let mut decoder = TolerantDecoder::new(2, 1_000);
+        let broken = r#"{"type":"trade","data":{"price":"abc"}}"#;
+        assert!(matches!(decoder.decode(broken, 0), Err(Rejected::Failed { .. })));
+        assert!(matches!(decoder.decode(broken, 1), Err(Rejected::Quarantined { .. })));
+        assert_eq!(decoder.stats.quarantined(2).collect::<Vec<_>>(), vec!["trade"]);
+
+        assert!(matches!(decoder.decode(broken, 3), Err(Rejected::Dropped { .. })));
+        let stats = &decoder.stats.types["trade"];
+        assert_eq!((stats.failed, stats.dropped), (2, 1));
+        assert!(stats.last_error.is_some());
+
+        // Expired: failures are counted again
+        assert!(matches!(decoder.decode(broken, 1_001), Err(Rejected::Failed { .. })));
+        assert_eq!(decoder.stats.failed(), 3);
+        assert!(decoder.decode("not json", 1_002).is_err());
+        assert_eq!(decoder.stats.types[UNTYPED].failed, 1);
+    }
+
+    #[test]
+    fn test_mangled_frames_never_panic() {
+        let mut decoder = TolerantDecoder::default();
+        let heartbeat = r#"{"type":"heartbeat","data":{"timestamp":1}}"#;
+        for frame in [CANDLE, heartbeat] {
+            for cut in 0..frame.len() {
+                let _ = decoder.decode(&frame[..cut], 0);
+                let mut bytes = frame.as_bytes().to_vec();
+                bytes[cut] = bytes[cut].wrapping_add(1);
+                if let Ok(text) = String::from_utf8(bytes) {
+                    let _ = decoder.decode(&text, 0);
+                }
+            }
+        }
+        assert!(decoder.stats.failed() > 0);
+    }
+}
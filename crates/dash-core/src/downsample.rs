@@ -0,0 +1,107 @@
+//! Downsampling for long line series
+
+use serde::{Deserialize, Serialize};
+
+/// Timestamped value of a line series
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LinePoint {
+    /// Unix timestamp in milliseconds
+    pub timestamp: i64,
+    pub value: f64,
+}
+
+impl LinePoint {
+    pub fn new(timestamp: i64, value: f64) -> Self {
+        Self { timestamp, value }
+    }
+}
+
+// ============================================================================
+// STRATEGY PATTERN: Line Downsampling
+// ============================================================================
+
+/// Strategy trait for reducing a line series to at most `threshold` points
+pub trait LineDownsampler: Send + Sync {
+    fn downsample(&self, points: &[LinePoint], threshold: usize) -> Vec<LinePoint>;
+}
+
+/// Largest-Triangle-Three-Buckets: keeps the points that preserve the visual
+/// shape of the series. First and last points are always kept.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Lttb;
+
+impl LineDownsampler for Lttb {
+    fn downsample(&self, points: &[LinePoint], threshold: usize) -> Vec<LinePoint> {
+        if threshold >= points.len() || threshold < 3 {
+            return points.to_vec();
+        }
+
+        let mut sampled = Vec::with_capacity(threshold);
+        sampled.push(points[0]);
+
+        // Interior points split into threshold - 2 buckets
+        let bucket_size = (points.len() - 2) as f64 / (threshold - 2) as f64;
+        let bucket = |i: usize| {
+            let start = (i as f64 * bucket_size) as usize + 1;
+            let end = (((i + 1) as f64 * bucket_size) as usize + 1).min(points.len() - 1);
+            start..end
+        };
+
+        let mut anchor = points[0];
+        for i in 0..threshold - 2 {
+            // Average of the next bucket (or the last point for the final bucket)
+            let next = if i + 1 < threshold - 2 { &points[bucket(i + 1)] } else { &points[points.len() - 1..] };
+            let (sum_t, sum_v) = next
+                .iter()
+                .fold((0.0, 0.0), |(t, v), p| (t + p.timestamp as f64, v + p.value));
+            let (avg_t, avg_v) = (sum_t / next.len() as f64, sum_v / next.len() as f64);
+
+            let (anchor_t, anchor_v) = (anchor.timestamp as f64, anchor.value);
+            let chosen = points[bucket(i)]
+                .iter()
+                .copied()
+                .max_by(|a, b| {
+                    let area = |p: &LinePoint| {
+                        ((anchor_t - avg_t) * (p.value - anchor_v)
+                            - (anchor_t - p.timestamp as f64) * (avg_v - anchor_v))
+                            .abs()
+                    };
+                    area(a).total_cmp(&area(b))
+                })
+                .unwrap_or(anchor);
+
+            sampled.push(chosen);
+            anchor = chosen;
+        }
+
+        sampled.push(points[points.len() - 1]);
+        sampled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lttb_keeps_shape() {
+        // Flat series with a single spike
+        let points: Vec<LinePoint> = (0..1_000)
+            .map(|i| LinePoint::new(i, if i == 500 { 100.0 } else { 1.0 }))
+            .collect();
+
+        let sampled = Lttb.downsample(&points, 50);
+        assert_eq!(sampled.len(), 50);
+        assert_eq!(sampled.first(), points.first());
+        assert_eq!(sampled.last(), points.last());
+        assert!(sampled.iter().any(|p| p.value == 100.0));
+        assert!(sampled.windows(2).all(|w| w[0].timestamp < w[1].timestamp));
+    }
+
+    #[test]
+    fn test_lttb_small_input_untouched() {
+        let points = vec![LinePoint::new(0, 1.0), LinePoint::new(1, 2.0)];
+        assert_eq!(Lttb.downsample(&points, 10), points);
+        assert_eq!(Lttb.downsample(&points, 2).len(), 2);
+    }
+}
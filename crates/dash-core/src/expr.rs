@@ -0,0 +1,355 @@
+//! Expression syntax shared by indicator scripts and alert rules
+//!
+//! Numbers, names, calls, arithmetic (`+ - * / % ^`), comparisons
+//! (`< <= > >= == !=`) and logic (`&& || !`). Names are not resolved here;
+//! each language decides what `close` or `rsi(14)` means when it compiles
+//! the tree.
+
+use crate::Msg;
+
+/// Longest source accepted, in characters
+pub const MAX_EXPR_LEN: usize = 500;
+
+/// Deepest nesting of parentheses, calls and operators
+const MAX_DEPTH: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Pow,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+    And,
+    Or,
+}
+
+impl BinOp {
+    /// Apply to two values; comparisons and logic give 1 or 0
+    pub fn apply(&self, a: f64, b: f64) -> f64 {
+        let truth = |t: bool| if t { 1.0 } else { 0.0 };
+        match self {
+            Self::Add => a + b,
+            Self::Sub => a - b,
+            Self::Mul => a * b,
+            Self::Div => a / b,
+            Self::Rem => a % b,
+            Self::Pow => a.powf(b),
+            Self::Lt => truth(a < b),
+            Self::Le => truth(a <= b),
+            Self::Gt => truth(a > b),
+            Self::Ge => truth(a >= b),
+            Self::Eq => truth(a == b),
+            Self::Ne => truth(a != b),
+            Self::And => truth(a != 0.0 && b != 0.0),
+            Self::Or => truth(a != 0.0 || b != 0.0),
+        }
+    }
+}
+
+/// Parsed expression; names are resolved by the language using it
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Num(f64),
+    Name(String),
+    Neg(Box<Expr>),
+    Not(Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+    Call(String, Vec<Expr>),
+}
+
+/// What is wrong with an expression
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExprErrorKind {
+    /// Unexpected character, token or end of input
+    Syntax,
+    UnknownName(String),
+    /// A function was called with the wrong number of arguments
+    Arguments(String),
+    /// A lookback is not a whole number in range
+    Period,
+    /// Too long, nested too deeply, or too costly to evaluate
+    TooComplex,
+    /// A number where a true/false condition is needed
+    NotCondition,
+}
+
+/// An error and the character offset it was found at
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExprError {
+    pub at: usize,
+    pub kind: ExprErrorKind,
+}
+
+impl ExprError {
+    pub fn new(at: usize, kind: ExprErrorKind) -> Self {
+        Self { at, kind }
+    }
+
+    pub fn msg(&self) -> Msg {
+        match self.kind {
+            ExprErrorKind::Syntax => Msg::ExprSyntax,
+            ExprErrorKind::UnknownName(_) => Msg::ExprUnknownName,
+            ExprErrorKind::Arguments(_) => Msg::ExprArguments,
+            ExprErrorKind::Period => Msg::ExprPeriod,
+            ExprErrorKind::TooComplex => Msg::ExprTooComplex,
+            ExprErrorKind::NotCondition => Msg::ExprNotCondition,
+        }
+    }
+
+    /// The name the error is about, if any
+    pub fn name(&self) -> Option<&str> {
+        match &self.kind {
+            ExprErrorKind::UnknownName(name) | ExprErrorKind::Arguments(name) => Some(name),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Num(f64),
+    Name(String),
+    Op(&'static str),
+    Open,
+    Close,
+    Comma,
+}
+
+/// Operators, longest first so `<=` wins over `<`
+const OPERATORS: [&str; 16] =
+    ["<=", ">=", "==", "!=", "&&", "||", "+", "-", "*", "/", "%", "^", "<", ">", "!", "="];
+
+fn tokenize(src: &str) -> Result<Vec<(usize, Token)>, ExprError> {
+    let chars: Vec<char> = src.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        let token = if c.is_ascii_digit() || c == '.' {
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            let value = text.parse().map_err(|_| ExprError::new(start, ExprErrorKind::Syntax))?;
+            Token::Num(value)
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            Token::Name(chars[start..i].iter().collect::<String>().to_ascii_lowercase())
+        } else {
+            i += 1;
+            match c {
+                '(' => Token::Open,
+                ')' => Token::Close,
+                ',' => Token::Comma,
+                _ => {
+                    let rest: String = chars[start..chars.len().min(start + 2)].iter().collect();
+                    let op = OPERATORS
+                        .iter()
+                        .find(|op| rest.starts_with(**op))
+                        .ok_or(ExprError::new(start, ExprErrorKind::Syntax))?;
+                    i = start + op.len();
+                    // A lone `=` is almost always a mistyped `==`
+                    Token::Op(if *op == "=" { "==" } else { op })
+                }
+            }
+        };
+        tokens.push((start, token));
+    }
+    Ok(tokens)
+}
+
+/// Binding strength of a binary operator; higher binds tighter
+fn precedence(op: &str) -> Option<(u8, BinOp)> {
+    Some(match op {
+        "||" => (1, BinOp::Or),
+        "&&" => (2, BinOp::And),
+        "<" => (3, BinOp::Lt),
+        "<=" => (3, BinOp::Le),
+        ">" => (3, BinOp::Gt),
+        ">=" => (3, BinOp::Ge),
+        "==" => (3, BinOp::Eq),
+        "!=" => (3, BinOp::Ne),
+        "+" => (4, BinOp::Add),
+        "-" => (4, BinOp::Sub),
+        "*" => (5, BinOp::Mul),
+        "/" => (5, BinOp::Div),
+        "%" => (5, BinOp::Rem),
+        "^" => (7, BinOp::Pow),
+        _ => return None,
+    })
+}
+
+/// Unary minus and not bind tighter than `*` but looser than `^`
+const UNARY_PRECEDENCE: u8 = 6;
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    /// Offset reported for errors at the end of input
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, t)| t)
+    }
+
+    fn offset(&self) -> usize {
+        self.tokens.get(self.pos).map_or(self.end, |(at, _)| *at)
+    }
+
+    fn error(&self, kind: ExprErrorKind) -> ExprError {
+        ExprError::new(self.offset(), kind)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).map(|(_, t)| t.clone());
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, token: Token) -> Result<(), ExprError> {
+        if self.peek() == Some(&token) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(ExprErrorKind::Syntax))
+        }
+    }
+
+    /// Precedence climbing over binary operators at least as strong as `min`
+    fn expr(&mut self, min: u8, depth: usize) -> Result<Expr, ExprError> {
+        if depth > MAX_DEPTH {
+            return Err(self.error(ExprErrorKind::TooComplex));
+        }
+        let mut lhs = self.unary(depth)?;
+        while let Some(Token::Op(op)) = self.peek() {
+            let Some((prec, bin)) = precedence(op) else { break };
+            if prec < min {
+                break;
+            }
+            self.pos += 1;
+            // `^` is right-associative, the rest left-associative
+            let next_min = if bin == BinOp::Pow { prec } else { prec + 1 };
+            let rhs = self.expr(next_min, depth + 1)?;
+            lhs = Expr::Binary(bin, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self, depth: usize) -> Result<Expr, ExprError> {
+        match self.peek() {
+            Some(Token::Op("-")) => {
+                self.pos += 1;
+                Ok(Expr::Neg(Box::new(self.expr(UNARY_PRECEDENCE, depth + 1)?)))
+            }
+            Some(Token::Op("!")) => {
+                self.pos += 1;
+                Ok(Expr::Not(Box::new(self.expr(UNARY_PRECEDENCE, depth + 1)?)))
+            }
+            _ => self.atom(depth),
+        }
+    }
+
+    fn atom(&mut self, depth: usize) -> Result<Expr, ExprError> {
+        let at = self.offset();
+        match self.next() {
+            Some(Token::Num(value)) => Ok(Expr::Num(value)),
+            Some(Token::Open) => {
+                let inner = self.expr(0, depth + 1)?;
+                self.expect(Token::Close)?;
+                Ok(inner)
+            }
+            Some(Token::Name(name)) => {
+                if self.peek() != Some(&Token::Open) {
+                    return Ok(Expr::Name(name));
+                }
+                self.pos += 1;
+                let mut args = Vec::new();
+                if self.peek() != Some(&Token::Close) {
+                    loop {
+                        args.push(self.expr(0, depth + 1)?);
+                        if self.peek() != Some(&Token::Comma) {
+                            break;
+                        }
+                        self.pos += 1;
+                    }
+                }
+                self.expect(Token::Close)?;
+                Ok(Expr::Call(name, args))
+            }
+            _ => Err(ExprError::new(at, ExprErrorKind::Syntax)),
+        }
+    }
+}
+
+/// Parse `src` into an expression tree
+pub fn parse_expr(src: &str) -> Result<Expr, ExprError> {
+    let len = src.chars().count();
+    if len > MAX_EXPR_LEN {
+        return Err(ExprError::new(MAX_EXPR_LEN, ExprErrorKind::TooComplex));
+    }
+    let mut parser = Parser { tokens: tokenize(src)?, pos: 0, end: len };
+    let expr = parser.expr(0, 0)?;
+    if parser.pos < parser.tokens.len() {
+        return Err(parser.error(ExprErrorKind::Syntax));
+    }
+    Ok(expr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn name(n: &str) -> Box<Expr> {
+        Box::new(Expr::Name(n.into()))
+    }
+
+    #[test]
+    fn test_parse_precedence() {
+        let expr = parse_expr("price > 100000 && rsi(14) < 30").unwrap();
+        let Expr::Binary(BinOp::And, lhs, rhs) = expr else { panic!("{:?}", expr) };
+        assert_eq!(*lhs, Expr::Binary(BinOp::Gt, name("price"), Box::new(Expr::Num(100000.0))));
+        let call = Expr::Call("rsi".into(), vec![Expr::Num(14.0)]);
+        assert_eq!(*rhs, Expr::Binary(BinOp::Lt, Box::new(call), Box::new(Expr::Num(30.0))));
+
+        // -2^2 is -(2^2); 2^3^2 is 2^(3^2)
+        let eval = |src: &str| match parse_expr(src).unwrap() {
+            Expr::Neg(inner) => match *inner {
+                Expr::Binary(BinOp::Pow, ..) => "neg pow",
+                _ => "neg",
+            },
+            Expr::Binary(BinOp::Pow, _, rhs) if matches!(*rhs, Expr::Binary(..)) => "pow pow",
+            _ => "other",
+        };
+        assert_eq!(eval("-2^2"), "neg pow");
+        assert_eq!(eval("2^3^2"), "pow pow");
+        assert_eq!(parse_expr("Close = 1").unwrap(), parse_expr("close == 1").unwrap());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let kind = |src: &str| parse_expr(src).unwrap_err();
+        assert_eq!(kind("close +"), ExprError::new(7, ExprErrorKind::Syntax));
+        assert_eq!(kind("sma(close, 3"), ExprError::new(12, ExprErrorKind::Syntax));
+        assert_eq!(kind("close # 2"), ExprError::new(6, ExprErrorKind::Syntax));
+        assert_eq!(kind("1 2"), ExprError::new(2, ExprErrorKind::Syntax));
+        assert_eq!(kind(&"(".repeat(40)).kind, ExprErrorKind::TooComplex);
+        assert_eq!(kind(&"1+".repeat(300)).kind, ExprErrorKind::TooComplex);
+    }
+}
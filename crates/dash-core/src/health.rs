@@ -0,0 +1,208 @@
+//! Upstream feed health reported by the server (`/healthz`, `/readyz`)
+
+use serde::{Deserialize, Serialize};
+
+use crate::WsMessage;
+
+/// A channel with no messages for this long is considered stale (ms)
+pub const STALE_AFTER_MS: u64 = 10_000;
+
+/// Market data channel tracked for liveness
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedChannel {
+    Trades,
+    OrderBook,
+    Ticker,
+    Candles,
+}
+
+impl FeedChannel {
+    pub fn all() -> &'static [Self] {
+        &[Self::Trades, Self::OrderBook, Self::Ticker, Self::Candles]
+    }
+
+    /// Channel a message counts towards (None for heartbeats)
+    pub fn for_message(msg: &WsMessage) -> Option<Self> {
+        match msg {
+            WsMessage::Trade(_) => Some(Self::Trades),
+            WsMessage::OrderBook(_) | WsMessage::Depth(_) => Some(Self::OrderBook),
+            WsMessage::Ticker(_) => Some(Self::Ticker),
+            WsMessage::Candle(_) => Some(Self::Candles),
+            WsMessage::Heartbeat { .. }
+            | WsMessage::SourceStatus(_)
+            | WsMessage::ConsolidatedBook(_)
+            | WsMessage::News(_)
+            | WsMessage::AlertTriggered(_) => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Trades => "Trades",
+            Self::OrderBook => "Book",
+            Self::Ticker => "Ticker",
+            Self::Candles => "Candles",
+        }
+    }
+}
+
+/// Overall state of an upstream feed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedStatus {
+    /// Every channel is fresh
+    Up,
+    /// Some channels are stale
+    Degraded,
+    /// No channel is fresh
+    #[default]
+    Down,
+}
+
+impl FeedStatus {
+    /// Status from the number of fresh channels out of `total`
+    pub fn from_fresh(fresh: usize, total: usize) -> Self {
+        match fresh {
+            0 => Self::Down,
+            n if n >= total => Self::Up,
+            _ => Self::Degraded,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Up => "Up",
+            Self::Degraded => "Degraded",
+            Self::Down => "Down",
+        }
+    }
+
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            Self::Up => "feed-up",
+            Self::Degraded => "feed-degraded",
+            Self::Down => "feed-down",
+        }
+    }
+}
+
+/// Liveness of one channel
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChannelHealth {
+    pub channel: FeedChannel,
+    /// Time since the last message (None = nothing received yet)
+    pub last_message_age_ms: Option<u64>,
+    pub stale: bool,
+}
+
+impl ChannelHealth {
+    pub fn new(channel: FeedChannel, last_message_age_ms: Option<u64>) -> Self {
+        Self {
+            channel,
+            last_message_age_ms,
+            stale: last_message_age_ms.is_none_or(|age| age > STALE_AFTER_MS),
+        }
+    }
+}
+
+/// Connectivity of one upstream adapter
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AdapterHealth {
+    /// Adapter name (e.g. `mock`)
+    pub name: String,
+    pub status: FeedStatus,
+    pub channels: Vec<ChannelHealth>,
+}
+
+impl AdapterHealth {
+    pub fn new(name: impl Into<String>, channels: Vec<ChannelHealth>) -> Self {
+        let fresh = channels.iter().filter(|c| !c.stale).count();
+        Self {
+            name: name.into(),
+            status: FeedStatus::from_fresh(fresh, channels.len()),
+            channels,
+        }
+    }
+}
+
+/// Upstream source change pushed to clients (`WsMessage::SourceStatus`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceStatus {
+    /// Adapter currently feeding the stream
+    pub source: String,
+    pub status: FeedStatus,
+    /// Channels past their staleness threshold
+    #[serde(default)]
+    pub stale_channels: Vec<FeedChannel>,
+    /// Serving from a fallback instead of the primary adapter
+    #[serde(default)]
+    pub failover: bool,
+    /// Unix timestamp in milliseconds
+    pub timestamp: i64,
+}
+
+impl SourceStatus {
+    /// Data shown to users may be missing or synthetic
+    pub fn is_degraded(&self) -> bool {
+        self.failover || self.status != FeedStatus::Up
+    }
+}
+
+/// Body of `/healthz` and `/readyz`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HealthReport {
+    /// Serving market data (some adapter is not down)
+    pub ready: bool,
+    /// Best status across adapters
+    pub status: FeedStatus,
+    pub uptime_secs: u64,
+    pub adapters: Vec<AdapterHealth>,
+}
+
+impl HealthReport {
+    pub fn new(uptime_secs: u64, adapters: Vec<AdapterHealth>) -> Self {
+        let status = adapters
+            .iter()
+            .map(|a| a.status)
+            .min_by_key(|s| match s {
+                FeedStatus::Up => 0,
+                FeedStatus::Degraded => 1,
+                FeedStatus::Down => 2,
+            })
+            .unwrap_or_default();
+        Self {
+            ready: status != FeedStatus::Down,
+            status,
+            uptime_secs,
+            adapters,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adapter_status() {
+        let fresh = |c| ChannelHealth::new(c, Some(100));
+        let up = AdapterHealth::new("mock", FeedChannel::all().iter().map(|c| fresh(*c)).collect());
+        assert_eq!(up.status, FeedStatus::Up);
+
+        let degraded = AdapterHealth::new(
+            "mock",
+            vec![fresh(FeedChannel::Trades), ChannelHealth::new(FeedChannel::Ticker, Some(STALE_AFTER_MS + 1))],
+        );
+        assert_eq!(degraded.status, FeedStatus::Degraded);
+
+        let down = AdapterHealth::new("mock", vec![ChannelHealth::new(FeedChannel::Trades, None)]);
+        assert_eq!(down.status, FeedStatus::Down);
+
+        let report = HealthReport::new(5, vec![down, degraded]);
+        assert_eq!(report.status, FeedStatus::Degraded);
+        assert!(report.ready);
+        assert!(!HealthReport::new(0, vec![]).ready);
+        assert!(HealthReport::new(0, vec![up]).ready);
+    }
+}
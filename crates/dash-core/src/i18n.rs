@@ -0,0 +1,1425 @@
+//! Localized UI strings and locale-aware number/date formatting
+//!
+//! Every label the dashboard shows is a `Msg`; each locale bundle is an
+//! exhaustive match, so adding a message without translating it fails to
+//! compile rather than silently falling back.
+
+use chrono::{DateTime, Utc};
+
+use crate::{ConnectionState, FeedStatus, TradeSide};
+
+// ============================================================================
+// LOCALES
+// ============================================================================
+
+/// Supported UI languages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Locale {
+    #[default]
+    En,
+    De,
+    Ja,
+    Zh,
+}
+
+impl Locale {
+    pub fn all() -> &'static [Self] {
+        &[Self::En, Self::De, Self::Ja, Self::Zh]
+    }
+
+    /// BCP 47 primary language subtag
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Self::En => "en",
+            Self::De => "de",
+            Self::Ja => "ja",
+            Self::Zh => "zh",
+        }
+    }
+
+    /// Name of the language in that language, for the picker
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Self::En => "English",
+            Self::De => "Deutsch",
+            Self::Ja => "日本語",
+            Self::Zh => "中文",
+        }
+    }
+
+    /// Match a language tag such as `de-AT` or `zh_Hans` on its primary subtag
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag.split(['-', '_']).next()?.trim().to_ascii_lowercase();
+        Self::all().iter().copied().find(|l| l.tag() == primary)
+    }
+
+    /// Translated text for a message
+    pub fn text(&self, msg: Msg) -> &'static str {
+        match self {
+            Self::En => en(msg),
+            Self::De => de(msg),
+            Self::Ja => ja(msg),
+            Self::Zh => zh(msg),
+        }
+    }
+
+    // ========================================================================
+    // Number / date formatting
+    // ========================================================================
+
+    pub fn decimal_separator(&self) -> char {
+        match self {
+            Self::De => ',',
+            Self::En | Self::Ja | Self::Zh => '.',
+        }
+    }
+
+    pub fn group_separator(&self) -> char {
+        match self {
+            Self::De => '.',
+            Self::En | Self::Ja | Self::Zh => ',',
+        }
+    }
+
+    /// Fixed-point number with this locale's grouping, e.g. `64,210.50` / `64.210,50`
+    pub fn format_decimal(&self, value: f64, decimals: usize) -> String {
+        if !value.is_finite() {
+            return value.to_string();
+        }
+        let fixed = format!("{:.prec$}", value.abs(), prec = decimals);
+        let (int, frac) = fixed.split_once('.').unwrap_or((&fixed, ""));
+
+        let mut out = String::with_capacity(fixed.len() + int.len() / 3 + 1);
+        // "-0.00" reads oddly; only sign values that survive rounding
+        if value < 0.0 && fixed.bytes().any(|b| b.is_ascii_digit() && b != b'0') {
+            out.push('-');
+        }
+        for (i, digit) in int.chars().enumerate() {
+            if i > 0 && (int.len() - i).is_multiple_of(3) {
+                out.push(self.group_separator());
+            }
+            out.push(digit);
+        }
+        if !frac.is_empty() {
+            out.push(self.decimal_separator());
+            out.push_str(frac);
+        }
+        out
+    }
+
+    /// Large values with a K/M/B suffix, e.g. `1.25M` / `1,25M`
+    pub fn format_compact(&self, value: f64) -> String {
+        let abs = value.abs();
+        let (scaled, suffix) = if abs >= 1_000_000_000.0 {
+            (value / 1_000_000_000.0, "B")
+        } else if abs >= 1_000_000.0 {
+            (value / 1_000_000.0, "M")
+        } else if abs >= 1_000.0 {
+            (value / 1_000.0, "K")
+        } else {
+            (value, "")
+        };
+        format!("{}{}", self.format_decimal(scaled, 2), suffix)
+    }
+
+    /// Calendar date in the locale's usual order
+    pub fn format_date(&self, time: &DateTime<Utc>) -> String {
+        let pattern = match self {
+            Self::En => "%b %-d, %Y",
+            Self::De => "%d.%m.%Y",
+            Self::Ja => "%Y/%m/%d",
+            Self::Zh => "%Y年%-m月%-d日",
+        };
+        time.format(pattern).to_string()
+    }
+
+    /// Date and 24-hour time, with milliseconds when `millis` is set
+    pub fn format_datetime(&self, time: &DateTime<Utc>, millis: bool) -> String {
+        let clock = if millis { "%H:%M:%S%.3f" } else { "%H:%M:%S" };
+        format!("{} {} UTC", self.format_date(time), time.format(clock))
+    }
+}
+
+// ============================================================================
+// MESSAGES
+// ============================================================================
+
+/// UI string keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Msg {
+    // Panels
+    OrderBook,
+    MarketImpact,
+    Chart,
+    MarketDepth,
+    NotionalDepth,
+    DepthPerSide,
+    RecentTrades,
+    TradeFlow,
+    Book,
+    Trades,
+    News,
+    NewsPairOnly,
+    AlertKeywords,
+    NoNews,
+    // Paper trading
+    PaperTrading,
+    MarketOrder,
+    LimitOrder,
+    StopOrder,
+    TakeProfit,
+    StopLoss,
+    PlaceOrder,
+    CancelOrder,
+    WorkingOrders,
+    NoOrders,
+    Position,
+    OpenPnl,
+    RealizedPnl,
+    RewardRisk,
+    Leverage,
+    Liquidation,
+    Journal,
+    Equity,
+    NoFills,
+    Risk,
+    Exposure,
+    MaxDrawdown,
+    Sharpe,
+    WinRate,
+    AverageR,
+    Backtest,
+    RunBacktest,
+    FastSma,
+    SlowSma,
+    Indicators,
+    AddIndicator,
+    IndicatorName,
+    Formula,
+    ExprSyntax,
+    ExprUnknownName,
+    ExprArguments,
+    ExprPeriod,
+    ExprTooComplex,
+    ExprNotCondition,
+    Alerts,
+    AddAlert,
+    AlertCondition,
+    NoAlerts,
+    Notifications,
+    NotificationsBlocked,
+    ServerDelivery,
+    WebhookUrl,
+    WebhookFormat,
+    ServerToken,
+    AlertSyncPending,
+    AlertSynced,
+    AlertSyncUnauthorized,
+    AlertSyncUnavailable,
+    AlertSyncInvalidWebhook,
+    AlertSyncFailed,
+    Account,
+    SignIn,
+    SignUp,
+    SignOut,
+    Username,
+    Password,
+    SignedInAs,
+    AccountSyncing,
+    AccountSynced,
+    InvalidUsername,
+    PasswordTooShort,
+    UsernameTaken,
+    WrongPassword,
+    SessionExpired,
+    AccountsUnavailable,
+    AccountFailed,
+    Viewer,
+    Operator,
+    ReadOnly,
+    Admin,
+    ServerAdmin,
+    BackToDashboard,
+    AdminLoading,
+    AdminSignIn,
+    AdminForbidden,
+    AdminFailed,
+    Clients,
+    NoClients,
+    Connections,
+    Throughput,
+    PerSecond,
+    Upstream,
+    DataSource,
+    MockScenarios,
+    ScenarioRally,
+    ScenarioCrash,
+    ScenarioVolatile,
+    ScenarioStall,
+    EndScenario,
+    Recording,
+    PauseRecording,
+    ResumeRecording,
+    NoStorage,
+    ClientLog,
+    ClientLogTitle,
+    NoLogLines,
+    Diagnostics,
+    SignalUpdates,
+    Timings,
+    QueuedCommands,
+    Buffers,
+    ParseErrors,
+    NoParseErrors,
+    InvalidQuantity,
+    PriceRequired,
+    NoMarketPrice,
+    StopSide,
+    TakeProfitSide,
+    StopLossSide,
+    NotWorking,
+    // Columns
+    Price,
+    Size,
+    Total,
+    Time,
+    Venue,
+    Side,
+    Value,
+    Levels,
+    AvgPrice,
+    Slippage,
+    CumVolume,
+    RunningVolume,
+    CumSize,
+    CumulativeTotals,
+    AnchorBook,
+    Recenter,
+    NewTrades,
+    // Ticker
+    Spread,
+    Change24h,
+    High24h,
+    Low24h,
+    Volume24h,
+    // Order book
+    Bid,
+    Ask,
+    BidTotal,
+    AskTotal,
+    BookDepthExhausted,
+    Consolidated,
+    ImbalanceTint,
+    // Sides
+    Buy,
+    Sell,
+    // Trade size classes
+    Whale,
+    Large,
+    // Connection
+    Connected,
+    Connecting,
+    Reconnecting,
+    Disconnected,
+    UpdateRequired,
+    StaleData,
+    // Status bar
+    Status,
+    Feed,
+    FeedUp,
+    FeedDegraded,
+    FeedDown,
+    FeedUnknown,
+    DegradedData,
+    Fallback,
+    Compression,
+    Render,
+    Performance,
+    Announce,
+    DataAge,
+    Watermark,
+    TapeShading,
+    Collapse,
+    Old,
+    Language,
+    ExtraPanel,
+    NoPanel,
+    PanelSettings,
+    // Replay
+    Live,
+    Replay,
+    Resume,
+    // Chart tools
+    Volume,
+    Grid,
+    LastPrice,
+    HighLow,
+    Legend,
+    VolumeTotal,
+    VolumeSplit,
+    VolumeDelta,
+    CumulativeDelta,
+    ScheduledEvents,
+    SessionShading,
+    Mid,
+    Microprice,
+    WeightedMid,
+    BookPrices,
+    SessionLog,
+    Export,
+    Copied,
+    Saved,
+    ChartType,
+    Candles,
+    OhlcBars,
+    LineSeries,
+    AreaSeries,
+    BaselineSeries,
+    PriceLine,
+    BaselineLevel,
+    Measure,
+    Clear,
+    ExitFullscreen,
+    Expand,
+    // Session clock
+    LocalTime,
+    CandleClose,
+    Sessions,
+    SessionAsia,
+    SessionEurope,
+    SessionUs,
+    SessionOpen,
+    OpensIn,
+    // Command palette
+    CommandPalette,
+    PaletteHint,
+    NoMatches,
+}
+
+impl Msg {
+    pub fn side(side: TradeSide) -> Self {
+        match side {
+            TradeSide::Buy => Self::Buy,
+            TradeSide::Sell => Self::Sell,
+        }
+    }
+
+    pub fn feed(status: FeedStatus) -> Self {
+        match status {
+            FeedStatus::Up => Self::FeedUp,
+            FeedStatus::Degraded => Self::FeedDegraded,
+            FeedStatus::Down => Self::FeedDown,
+        }
+    }
+
+    pub fn connection(state: ConnectionState) -> Self {
+        match state {
+            ConnectionState::Connected => Self::Connected,
+            ConnectionState::Connecting => Self::Connecting,
+            ConnectionState::Reconnecting => Self::Reconnecting,
+            ConnectionState::Disconnected => Self::Disconnected,
+            ConnectionState::UpdateRequired => Self::UpdateRequired,
+        }
+    }
+}
+
+// ============================================================================
+// BUNDLES
+// ============================================================================
+
+fn en(msg: Msg) -> &'static str {
+    match msg {
+        Msg::OrderBook => "Order Book",
+        Msg::MarketImpact => "Market Impact",
+        Msg::News => "News",
+        Msg::NewsPairOnly => "Only news tagged with this pair",
+        Msg::AlertKeywords => "Alert keywords",
+        Msg::NoNews => "No news yet",
+        Msg::PaperTrading => "Paper trading",
+        Msg::MarketOrder => "Market",
+        Msg::LimitOrder => "Limit",
+        Msg::StopOrder => "Stop",
+        Msg::TakeProfit => "Take profit",
+        Msg::StopLoss => "Stop loss",
+        Msg::PlaceOrder => "Place order",
+        Msg::CancelOrder => "Cancel order",
+        Msg::WorkingOrders => "Working orders",
+        Msg::NoOrders => "No working orders",
+        Msg::Position => "Position",
+        Msg::OpenPnl => "Open PnL",
+        Msg::RealizedPnl => "Realized PnL",
+        Msg::RewardRisk => "Reward/risk",
+        Msg::Leverage => "Leverage",
+        Msg::Liquidation => "Liquidation",
+        Msg::Journal => "Trade journal",
+        Msg::Equity => "Equity",
+        Msg::NoFills => "No fills yet",
+        Msg::Risk => "Risk",
+        Msg::Exposure => "Exposure",
+        Msg::MaxDrawdown => "Max drawdown",
+        Msg::Sharpe => "Sharpe (per trade)",
+        Msg::WinRate => "Win rate",
+        Msg::AverageR => "Average R",
+        Msg::Backtest => "Backtest",
+        Msg::RunBacktest => "Run backtest",
+        Msg::FastSma => "Fast SMA",
+        Msg::SlowSma => "Slow SMA",
+        Msg::Indicators => "Indicators",
+        Msg::AddIndicator => "Add indicator",
+        Msg::IndicatorName => "Name",
+        Msg::Formula => "Formula",
+        Msg::ExprSyntax => "Syntax error",
+        Msg::ExprUnknownName => "Unknown name",
+        Msg::ExprArguments => "Wrong number of arguments",
+        Msg::ExprPeriod => "Lookback must be a whole number from 1 to 500",
+        Msg::ExprTooComplex => "Too long or too costly to evaluate",
+        Msg::ExprNotCondition => "Needs a condition, e.g. price > 100000",
+        Msg::Alerts => "Alerts",
+        Msg::AddAlert => "Add alert",
+        Msg::AlertCondition => "Condition",
+        Msg::NoAlerts => "No alerts",
+        Msg::Notifications => "Notifications",
+        Msg::NotificationsBlocked => "Notifications are blocked in this browser's site settings",
+        Msg::ServerDelivery => "Server delivery",
+        Msg::WebhookUrl => "Webhook URL",
+        Msg::WebhookFormat => "Format",
+        Msg::ServerToken => "Server token",
+        Msg::AlertSyncPending => "Syncing…",
+        Msg::AlertSynced => "Checked by the server",
+        Msg::AlertSyncUnauthorized => "Token rejected",
+        Msg::AlertSyncUnavailable => "Server alerts are disabled",
+        Msg::AlertSyncInvalidWebhook => "Webhook must be an https URL",
+        Msg::AlertSyncFailed => "Server unreachable",
+        Msg::Account => "Account",
+        Msg::SignIn => "Sign in",
+        Msg::SignUp => "Create account",
+        Msg::SignOut => "Sign out",
+        Msg::Username => "Username",
+        Msg::Password => "Password",
+        Msg::SignedInAs => "Signed in as",
+        Msg::AccountSyncing => "Syncing…",
+        Msg::AccountSynced => "Settings synced",
+        Msg::InvalidUsername => "3-32 letters, digits, _ - or .",
+        Msg::PasswordTooShort => "Password needs at least 8 characters",
+        Msg::UsernameTaken => "Username is taken",
+        Msg::WrongPassword => "Wrong username or password",
+        Msg::SessionExpired => "Session expired; sign in again",
+        Msg::AccountsUnavailable => "Accounts are not enabled on this server",
+        Msg::AccountFailed => "Server unreachable",
+        Msg::Viewer => "Viewer",
+        Msg::Operator => "Operator",
+        Msg::ReadOnly => "Read-only",
+        Msg::Admin => "Admin",
+        Msg::ServerAdmin => "Server admin",
+        Msg::BackToDashboard => "Dashboard",
+        Msg::AdminLoading => "Loading…",
+        Msg::AdminSignIn => "Sign in as an operator to manage the server",
+        Msg::AdminForbidden => "Operators only",
+        Msg::AdminFailed => "Server unreachable",
+        Msg::Clients => "Clients",
+        Msg::NoClients => "No clients connected",
+        Msg::Connections => "Connections",
+        Msg::Throughput => "Throughput",
+        Msg::PerSecond => "msg/s",
+        Msg::Upstream => "Upstream",
+        Msg::DataSource => "Data source",
+        Msg::MockScenarios => "Mock scenarios",
+        Msg::ScenarioRally => "Rally",
+        Msg::ScenarioCrash => "Crash",
+        Msg::ScenarioVolatile => "Volatile",
+        Msg::ScenarioStall => "Stall",
+        Msg::EndScenario => "End scenario",
+        Msg::Recording => "History recording",
+        Msg::PauseRecording => "Pause",
+        Msg::ResumeRecording => "Resume",
+        Msg::NoStorage => "No history storage",
+        Msg::ClientLog => "Log",
+        Msg::ClientLogTitle => "Client log",
+        Msg::NoLogLines => "Nothing logged",
+        Msg::Diagnostics => "Diagnostics",
+        Msg::SignalUpdates => "Signal updates",
+        Msg::Timings => "Timings",
+        Msg::QueuedCommands => "Queued commands",
+        Msg::Buffers => "Buffers",
+        Msg::ParseErrors => "Parse errors",
+        Msg::NoParseErrors => "No parse errors",
+        Msg::InvalidQuantity => "Enter a size above zero",
+        Msg::PriceRequired => "Enter a price",
+        Msg::NoMarketPrice => "No trades yet to price a market order",
+        Msg::StopSide => "Buy stops go above the market, sell stops below",
+        Msg::TakeProfitSide => "Take profit must be beyond the entry in the trade's favor",
+        Msg::StopLossSide => "Stop loss must be beyond the entry against the trade",
+        Msg::NotWorking => "Order is no longer working",
+        Msg::Chart => "Chart",
+        Msg::MarketDepth => "Market Depth",
+        Msg::NotionalDepth => "Plot depth as notional value",
+        Msg::DepthPerSide => "Scale each side to its own total",
+        Msg::RecentTrades => "Recent Trades",
+        Msg::TradeFlow => "Trade flow",
+        Msg::Book => "Book",
+        Msg::Trades => "Trades",
+        Msg::Price => "Price",
+        Msg::Size => "Size",
+        Msg::Total => "Total",
+        Msg::Time => "Time",
+        Msg::Venue => "Venue",
+        Msg::Side => "Side",
+        Msg::Value => "Value",
+        Msg::Levels => "Levels",
+        Msg::AvgPrice => "Avg Price",
+        Msg::Slippage => "Slippage",
+        Msg::CumVolume => "Σ Vol",
+        Msg::RunningVolume => "Volume since this panel opened",
+        Msg::CumSize => "Cum. size",
+        Msg::CumulativeTotals => "Show size summed from the best price",
+        Msg::AnchorBook => "Keep the book centered on the spread",
+        Msg::Recenter => "Recenter",
+        Msg::NewTrades => "new trades",
+        Msg::Spread => "Spread",
+        Msg::Change24h => "24h Change",
+        Msg::High24h => "24h High",
+        Msg::Low24h => "24h Low",
+        Msg::Volume24h => "24h Volume",
+        Msg::Bid => "Bid",
+        Msg::Ask => "Ask",
+        Msg::BidTotal => "Bid Total:",
+        Msg::AskTotal => "Ask Total:",
+        Msg::BookDepthExhausted => "Book depth exhausted",
+        Msg::Consolidated => "Consolidated",
+        Msg::ImbalanceTint => "Imbalance tint",
+        Msg::Buy => "BUY",
+        Msg::Sell => "SELL",
+        Msg::Whale => "whale",
+        Msg::Large => "large",
+        Msg::Connected => "Connected",
+        Msg::Connecting => "Connecting...",
+        Msg::Reconnecting => "Reconnecting...",
+        Msg::Disconnected => "Disconnected",
+        Msg::UpdateRequired => "Update required",
+        Msg::StaleData => "Offline, data as of",
+        Msg::Status => "Status:",
+        Msg::Feed => "Feed:",
+        Msg::FeedUp => "Up",
+        Msg::FeedDegraded => "Degraded",
+        Msg::FeedDown => "Down",
+        Msg::FeedUnknown => "Unknown",
+        Msg::DegradedData => "Degraded data",
+        Msg::Fallback => "fallback",
+        Msg::Compression => "Compression:",
+        Msg::Render => "Render:",
+        Msg::Performance => "Performance",
+        Msg::Announce => "Announce:",
+        Msg::DataAge => "Data age:",
+        Msg::Watermark => "Watermark:",
+        Msg::TapeShading => "Tape shading:",
+        Msg::Collapse => "Collapse:",
+        Msg::Old => "old",
+        Msg::Language => "Language",
+        Msg::ExtraPanel => "Extra panel",
+        Msg::NoPanel => "None",
+        Msg::PanelSettings => "Panel settings",
+        Msg::Live => "LIVE",
+        Msg::Replay => "REPLAY",
+        Msg::Resume => "RESUME",
+        Msg::Volume => "Volume",
+        Msg::Grid => "Grid",
+        Msg::LastPrice => "Last price",
+        Msg::HighLow => "High/Low",
+        Msg::VolumeTotal => "Total",
+        Msg::VolumeSplit => "Buy/Sell",
+        Msg::VolumeDelta => "Delta",
+        Msg::CumulativeDelta => "Cumulative delta",
+        Msg::ScheduledEvents => "Events",
+        Msg::SessionShading => "Shading",
+        Msg::Mid => "Mid",
+        Msg::Microprice => "Micro",
+        Msg::WeightedMid => "W-Mid",
+        Msg::BookPrices => "Book prices",
+        Msg::SessionLog => "Session log",
+        Msg::Export => "Export",
+        Msg::Copied => "Copied",
+        Msg::Saved => "Saved",
+        Msg::Legend => "Legend",
+        Msg::ChartType => "Chart type",
+        Msg::Candles => "Candles",
+        Msg::OhlcBars => "OHLC",
+        Msg::LineSeries => "Line",
+        Msg::AreaSeries => "Area",
+        Msg::BaselineSeries => "Baseline",
+        Msg::PriceLine => "H-Line",
+        Msg::BaselineLevel => "Set base",
+        Msg::Measure => "Measure",
+        Msg::Clear => "Clear",
+        Msg::ExitFullscreen => "Exit fullscreen",
+        Msg::Expand => "Expand",
+        Msg::LocalTime => "Local",
+        Msg::CandleClose => "Candle close",
+        Msg::Sessions => "Sessions",
+        Msg::SessionAsia => "Asia",
+        Msg::SessionEurope => "Europe",
+        Msg::SessionUs => "US",
+        Msg::SessionOpen => "Open",
+        Msg::OpensIn => "Opens in",
+        Msg::CommandPalette => "Command palette",
+        Msg::PaletteHint => "Type a command…",
+        Msg::NoMatches => "No matching commands",
+    }
+}
+
+fn de(msg: Msg) -> &'static str {
+    match msg {
+        Msg::OrderBook => "Orderbuch",
+        Msg::MarketImpact => "Marktauswirkung",
+        Msg::News => "Nachrichten",
+        Msg::NewsPairOnly => "Nur Nachrichten zu diesem Paar",
+        Msg::AlertKeywords => "Alarm-Stichwörter",
+        Msg::NoNews => "Noch keine Nachrichten",
+        Msg::PaperTrading => "Papierhandel",
+        Msg::MarketOrder => "Markt",
+        Msg::LimitOrder => "Limit",
+        Msg::StopOrder => "Stop",
+        Msg::TakeProfit => "Gewinnmitnahme",
+        Msg::StopLoss => "Stop-Loss",
+        Msg::PlaceOrder => "Order aufgeben",
+        Msg::CancelOrder => "Order stornieren",
+        Msg::WorkingOrders => "Offene Orders",
+        Msg::NoOrders => "Keine offenen Orders",
+        Msg::Position => "Position",
+        Msg::OpenPnl => "Offener GuV",
+        Msg::RealizedPnl => "Realisierter GuV",
+        Msg::RewardRisk => "Chance/Risiko",
+        Msg::Leverage => "Hebel",
+        Msg::Liquidation => "Liquidation",
+        Msg::Journal => "Handelsjournal",
+        Msg::Equity => "Kapital",
+        Msg::NoFills => "Noch keine Ausführungen",
+        Msg::Risk => "Risiko",
+        Msg::Exposure => "Exposure",
+        Msg::MaxDrawdown => "Max. Drawdown",
+        Msg::Sharpe => "Sharpe (pro Trade)",
+        Msg::WinRate => "Trefferquote",
+        Msg::AverageR => "Durchschn. R",
+        Msg::Backtest => "Backtest",
+        Msg::RunBacktest => "Backtest starten",
+        Msg::FastSma => "Schneller SMA",
+        Msg::SlowSma => "Langsamer SMA",
+        Msg::Indicators => "Indikatoren",
+        Msg::AddIndicator => "Indikator hinzufügen",
+        Msg::IndicatorName => "Name",
+        Msg::Formula => "Formel",
+        Msg::ExprSyntax => "Syntaxfehler",
+        Msg::ExprUnknownName => "Unbekannter Name",
+        Msg::ExprArguments => "Falsche Anzahl an Argumenten",
+        Msg::ExprPeriod => "Rückblick muss eine ganze Zahl von 1 bis 500 sein",
+        Msg::ExprTooComplex => "Zu lang oder zu aufwendig",
+        Msg::ExprNotCondition => "Bedingung nötig, z. B. price > 100000",
+        Msg::Alerts => "Alarme",
+        Msg::AddAlert => "Alarm hinzufügen",
+        Msg::AlertCondition => "Bedingung",
+        Msg::NoAlerts => "Keine Alarme",
+        Msg::Notifications => "Benachrichtigungen",
+        Msg::NotificationsBlocked => "Benachrichtigungen sind in den Seiteneinstellungen blockiert",
+        Msg::ServerDelivery => "Zustellung über Server",
+        Msg::WebhookUrl => "Webhook-URL",
+        Msg::WebhookFormat => "Format",
+        Msg::ServerToken => "Server-Token",
+        Msg::AlertSyncPending => "Synchronisiere…",
+        Msg::AlertSynced => "Vom Server geprüft",
+        Msg::AlertSyncUnauthorized => "Token abgelehnt",
+        Msg::AlertSyncUnavailable => "Server-Alarme sind deaktiviert",
+        Msg::AlertSyncInvalidWebhook => "Webhook muss eine https-URL sein",
+        Msg::AlertSyncFailed => "Server nicht erreichbar",
+        Msg::Account => "Konto",
+        Msg::SignIn => "Anmelden",
+        Msg::SignUp => "Konto erstellen",
+        Msg::SignOut => "Abmelden",
+        Msg::Username => "Benutzername",
+        Msg::Password => "Passwort",
+        Msg::SignedInAs => "Angemeldet als",
+        Msg::AccountSyncing => "Synchronisiere…",
+        Msg::AccountSynced => "Einstellungen synchronisiert",
+        Msg::InvalidUsername => "3-32 Buchstaben, Ziffern, _ - oder .",
+        Msg::PasswordTooShort => "Passwort braucht mindestens 8 Zeichen",
+        Msg::UsernameTaken => "Benutzername ist vergeben",
+        Msg::WrongPassword => "Falscher Benutzername oder Passwort",
+        Msg::SessionExpired => "Sitzung abgelaufen; bitte neu anmelden",
+        Msg::AccountsUnavailable => "Konten sind auf diesem Server nicht aktiviert",
+        Msg::AccountFailed => "Server nicht erreichbar",
+        Msg::Viewer => "Betrachter",
+        Msg::Operator => "Operator",
+        Msg::ReadOnly => "Nur Lesen",
+        Msg::Admin => "Admin",
+        Msg::ServerAdmin => "Serververwaltung",
+        Msg::BackToDashboard => "Dashboard",
+        Msg::AdminLoading => "Wird geladen…",
+        Msg::AdminSignIn => "Als Operator anmelden, um den Server zu verwalten",
+        Msg::AdminForbidden => "Nur für Operatoren",
+        Msg::AdminFailed => "Server nicht erreichbar",
+        Msg::Clients => "Clients",
+        Msg::NoClients => "Keine Clients verbunden",
+        Msg::Connections => "Verbindungen",
+        Msg::Throughput => "Durchsatz",
+        Msg::PerSecond => "Nachr./s",
+        Msg::Upstream => "Upstream",
+        Msg::DataSource => "Datenquelle",
+        Msg::MockScenarios => "Mock-Szenarien",
+        Msg::ScenarioRally => "Rally",
+        Msg::ScenarioCrash => "Crash",
+        Msg::ScenarioVolatile => "Volatil",
+        Msg::ScenarioStall => "Stillstand",
+        Msg::EndScenario => "Szenario beenden",
+        Msg::Recording => "Verlaufsaufzeichnung",
+        Msg::PauseRecording => "Pausieren",
+        Msg::ResumeRecording => "Fortsetzen",
+        Msg::NoStorage => "Kein Verlaufsspeicher",
+        Msg::ClientLog => "Log",
+        Msg::ClientLogTitle => "Client-Protokoll",
+        Msg::NoLogLines => "Nichts protokolliert",
+        Msg::Diagnostics => "Diagnose",
+        Msg::SignalUpdates => "Signal-Updates",
+        Msg::Timings => "Zeiten",
+        Msg::QueuedCommands => "Wartende Befehle",
+        Msg::Buffers => "Puffer",
+        Msg::ParseErrors => "Parse-Fehler",
+        Msg::NoParseErrors => "Keine Parse-Fehler",
+        Msg::InvalidQuantity => "Größe über null eingeben",
+        Msg::PriceRequired => "Preis eingeben",
+        Msg::NoMarketPrice => "Noch keine Trades für einen Marktpreis",
+        Msg::StopSide => "Kauf-Stops liegen über dem Markt, Verkaufs-Stops darunter",
+        Msg::TakeProfitSide => "Gewinnmitnahme muss im Gewinn jenseits des Einstiegs liegen",
+        Msg::StopLossSide => "Stop-Loss muss im Verlust jenseits des Einstiegs liegen",
+        Msg::NotWorking => "Order ist nicht mehr offen",
+        Msg::Chart => "Chart",
+        Msg::MarketDepth => "Markttiefe",
+        Msg::NotionalDepth => "Tiefe als Nominalwert zeigen",
+        Msg::DepthPerSide => "Jede Seite auf ihre eigene Summe skalieren",
+        Msg::RecentTrades => "Letzte Trades",
+        Msg::TradeFlow => "Orderfluss",
+        Msg::Book => "Buch",
+        Msg::Trades => "Trades",
+        Msg::Price => "Preis",
+        Msg::Size => "Menge",
+        Msg::Total => "Gesamt",
+        Msg::Time => "Zeit",
+        Msg::Venue => "Börse",
+        Msg::Side => "Seite",
+        Msg::Value => "Wert",
+        Msg::Levels => "Stufen",
+        Msg::AvgPrice => "Ø Preis",
+        Msg::Slippage => "Slippage",
+        Msg::CumVolume => "Σ Vol.",
+        Msg::RunningVolume => "Volumen seit Öffnen des Panels",
+        Msg::CumSize => "Kum. Größe",
+        Msg::CumulativeTotals => "Größe ab bestem Preis summiert zeigen",
+        Msg::AnchorBook => "Buch um den Spread zentriert halten",
+        Msg::Recenter => "Zentrieren",
+        Msg::NewTrades => "neue Trades",
+        Msg::Spread => "Spread",
+        Msg::Change24h => "24h Änderung",
+        Msg::High24h => "24h Hoch",
+        Msg::Low24h => "24h Tief",
+        Msg::Volume24h => "24h Volumen",
+        Msg::Bid => "Geld",
+        Msg::Ask => "Brief",
+        Msg::BidTotal => "Geld gesamt:",
+        Msg::AskTotal => "Brief gesamt:",
+        Msg::BookDepthExhausted => "Buchtiefe erschöpft",
+        Msg::Consolidated => "Konsolidiert",
+        Msg::ImbalanceTint => "Ungleichgewicht einfärben",
+        Msg::Buy => "KAUF",
+        Msg::Sell => "VERKAUF",
+        Msg::Whale => "Wal",
+        Msg::Large => "groß",
+        Msg::Connected => "Verbunden",
+        Msg::Connecting => "Verbinde...",
+        Msg::Reconnecting => "Neuverbindung...",
+        Msg::Disconnected => "Getrennt",
+        Msg::UpdateRequired => "Update erforderlich",
+        Msg::StaleData => "Offline, Stand",
+        Msg::Status => "Status:",
+        Msg::Feed => "Feed:",
+        Msg::FeedUp => "Aktiv",
+        Msg::FeedDegraded => "Eingeschränkt",
+        Msg::FeedDown => "Ausgefallen",
+        Msg::FeedUnknown => "Unbekannt",
+        Msg::DegradedData => "Eingeschränkte Daten",
+        Msg::Fallback => "Ersatz",
+        Msg::Compression => "Kompression:",
+        Msg::Render => "Rendering:",
+        Msg::Performance => "Sparmodus",
+        Msg::Announce => "Ansage:",
+        Msg::DataAge => "Datenalter:",
+        Msg::Watermark => "Wasserzeichen:",
+        Msg::TapeShading => "Tape-Tönung:",
+        Msg::Collapse => "Bündeln:",
+        Msg::Old => "alt",
+        Msg::Language => "Sprache",
+        Msg::ExtraPanel => "Zusatzpanel",
+        Msg::NoPanel => "Keines",
+        Msg::PanelSettings => "Panel-Einstellungen",
+        Msg::Live => "LIVE",
+        Msg::Replay => "WIEDERGABE",
+        Msg::Resume => "FORTSETZEN",
+        Msg::Volume => "Volumen",
+        Msg::Grid => "Raster",
+        Msg::LastPrice => "Letzter Kurs",
+        Msg::HighLow => "Hoch/Tief",
+        Msg::VolumeTotal => "Gesamt",
+        Msg::VolumeSplit => "Kauf/Verkauf",
+        Msg::VolumeDelta => "Delta",
+        Msg::CumulativeDelta => "Kumuliertes Delta",
+        Msg::ScheduledEvents => "Termine",
+        Msg::SessionShading => "Schattierung",
+        Msg::Mid => "Mitte",
+        Msg::Microprice => "Mikro",
+        Msg::WeightedMid => "Gew. Mitte",
+        Msg::BookPrices => "Buchpreise",
+        Msg::SessionLog => "Sitzungsprotokoll",
+        Msg::Export => "Exportieren",
+        Msg::Copied => "Kopiert",
+        Msg::Saved => "Gespeichert",
+        Msg::Legend => "Legende",
+        Msg::ChartType => "Diagrammtyp",
+        Msg::Candles => "Kerzen",
+        Msg::OhlcBars => "OHLC",
+        Msg::LineSeries => "Linie",
+        Msg::AreaSeries => "Fläche",
+        Msg::BaselineSeries => "Basislinie",
+        Msg::PriceLine => "H-Linie",
+        Msg::BaselineLevel => "Basis setzen",
+        Msg::Measure => "Messen",
+        Msg::Clear => "Löschen",
+        Msg::ExitFullscreen => "Vollbild beenden",
+        Msg::Expand => "Vergrößern",
+        Msg::LocalTime => "Lokal",
+        Msg::CandleClose => "Kerzenschluss",
+        Msg::Sessions => "Handelszeiten",
+        Msg::SessionAsia => "Asien",
+        Msg::SessionEurope => "Europa",
+        Msg::SessionUs => "USA",
+        Msg::SessionOpen => "Geöffnet",
+        Msg::OpensIn => "Öffnet in",
+        Msg::CommandPalette => "Befehlspalette",
+        Msg::PaletteHint => "Befehl eingeben…",
+        Msg::NoMatches => "Keine passenden Befehle",
+    }
+}
+
+fn ja(msg: Msg) -> &'static str {
+    match msg {
+        Msg::OrderBook => "板情報",
+        Msg::MarketImpact => "マーケットインパクト",
+        Msg::News => "ニュース",
+        Msg::NewsPairOnly => "この銘柄のニュースのみ",
+        Msg::AlertKeywords => "アラートキーワード",
+        Msg::NoNews => "ニュースはまだありません",
+        Msg::PaperTrading => "ペーパートレード",
+        Msg::MarketOrder => "成行",
+        Msg::LimitOrder => "指値",
+        Msg::StopOrder => "逆指値",
+        Msg::TakeProfit => "利確",
+        Msg::StopLoss => "損切り",
+        Msg::PlaceOrder => "注文する",
+        Msg::CancelOrder => "注文を取消",
+        Msg::WorkingOrders => "未約定の注文",
+        Msg::NoOrders => "未約定の注文はありません",
+        Msg::Position => "ポジション",
+        Msg::OpenPnl => "含み損益",
+        Msg::RealizedPnl => "確定損益",
+        Msg::RewardRisk => "リスクリワード",
+        Msg::Leverage => "レバレッジ",
+        Msg::Liquidation => "清算",
+        Msg::Journal => "取引履歴",
+        Msg::Equity => "資産",
+        Msg::NoFills => "約定はまだありません",
+        Msg::Risk => "リスク",
+        Msg::Exposure => "エクスポージャー",
+        Msg::MaxDrawdown => "最大ドローダウン",
+        Msg::Sharpe => "シャープ（取引毎）",
+        Msg::WinRate => "勝率",
+        Msg::AverageR => "平均R",
+        Msg::Backtest => "バックテスト",
+        Msg::RunBacktest => "バックテスト実行",
+        Msg::FastSma => "短期SMA",
+        Msg::SlowSma => "長期SMA",
+        Msg::Indicators => "インジケーター",
+        Msg::AddIndicator => "インジケーターを追加",
+        Msg::IndicatorName => "名前",
+        Msg::Formula => "数式",
+        Msg::ExprSyntax => "構文エラー",
+        Msg::ExprUnknownName => "不明な名前",
+        Msg::ExprArguments => "引数の数が正しくありません",
+        Msg::ExprPeriod => "期間は1〜500の整数で指定してください",
+        Msg::ExprTooComplex => "長すぎるか計算量が多すぎます",
+        Msg::ExprNotCondition => "条件式が必要です（例: price > 100000）",
+        Msg::Alerts => "アラート",
+        Msg::AddAlert => "アラートを追加",
+        Msg::AlertCondition => "条件",
+        Msg::NoAlerts => "アラートなし",
+        Msg::Notifications => "通知",
+        Msg::NotificationsBlocked => "通知はブラウザのサイト設定でブロックされています",
+        Msg::ServerDelivery => "サーバー配信",
+        Msg::WebhookUrl => "Webhook URL",
+        Msg::WebhookFormat => "形式",
+        Msg::ServerToken => "サーバートークン",
+        Msg::AlertSyncPending => "同期中…",
+        Msg::AlertSynced => "サーバーで監視中",
+        Msg::AlertSyncUnauthorized => "トークンが拒否されました",
+        Msg::AlertSyncUnavailable => "サーバーアラートは無効です",
+        Msg::AlertSyncInvalidWebhook => "Webhook は https URL である必要があります",
+        Msg::AlertSyncFailed => "サーバーに接続できません",
+        Msg::Account => "アカウント",
+        Msg::SignIn => "ログイン",
+        Msg::SignUp => "アカウント作成",
+        Msg::SignOut => "ログアウト",
+        Msg::Username => "ユーザー名",
+        Msg::Password => "パスワード",
+        Msg::SignedInAs => "ログイン中:",
+        Msg::AccountSyncing => "同期中…",
+        Msg::AccountSynced => "設定を同期しました",
+        Msg::InvalidUsername => "3〜32文字の英数字、_ - .",
+        Msg::PasswordTooShort => "パスワードは8文字以上必要です",
+        Msg::UsernameTaken => "このユーザー名は使用されています",
+        Msg::WrongPassword => "ユーザー名またはパスワードが違います",
+        Msg::SessionExpired => "セッションが切れました。再度ログインしてください",
+        Msg::AccountsUnavailable => "このサーバーではアカウントが無効です",
+        Msg::AccountFailed => "サーバーに接続できません",
+        Msg::Viewer => "閲覧者",
+        Msg::Operator => "オペレーター",
+        Msg::ReadOnly => "閲覧のみ",
+        Msg::Admin => "管理",
+        Msg::ServerAdmin => "サーバー管理",
+        Msg::BackToDashboard => "ダッシュボード",
+        Msg::AdminLoading => "読み込み中…",
+        Msg::AdminSignIn => "サーバーを管理するにはオペレーターとしてサインイン",
+        Msg::AdminForbidden => "オペレーターのみ",
+        Msg::AdminFailed => "サーバーに接続できません",
+        Msg::Clients => "クライアント",
+        Msg::NoClients => "接続中のクライアントはありません",
+        Msg::Connections => "接続数",
+        Msg::Throughput => "スループット",
+        Msg::PerSecond => "件/秒",
+        Msg::Upstream => "アップストリーム",
+        Msg::DataSource => "データソース",
+        Msg::MockScenarios => "モックシナリオ",
+        Msg::ScenarioRally => "上昇",
+        Msg::ScenarioCrash => "暴落",
+        Msg::ScenarioVolatile => "高ボラティリティ",
+        Msg::ScenarioStall => "停止",
+        Msg::EndScenario => "シナリオを終了",
+        Msg::Recording => "履歴の記録",
+        Msg::PauseRecording => "一時停止",
+        Msg::ResumeRecording => "再開",
+        Msg::NoStorage => "履歴ストレージなし",
+        Msg::ClientLog => "ログ",
+        Msg::ClientLogTitle => "クライアントログ",
+        Msg::NoLogLines => "ログはありません",
+        Msg::Diagnostics => "診断",
+        Msg::SignalUpdates => "シグナル更新",
+        Msg::Timings => "処理時間",
+        Msg::QueuedCommands => "送信待ちコマンド",
+        Msg::Buffers => "バッファ",
+        Msg::ParseErrors => "解析エラー",
+        Msg::NoParseErrors => "解析エラーなし",
+        Msg::InvalidQuantity => "0より大きい数量を入力してください",
+        Msg::PriceRequired => "価格を入力してください",
+        Msg::NoMarketPrice => "成行注文の価格となる約定がまだありません",
+        Msg::StopSide => "買いの逆指値は市場価格より上、売りは下に置きます",
+        Msg::TakeProfitSide => "利確は建値より有利な側に置いてください",
+        Msg::StopLossSide => "損切りは建値より不利な側に置いてください",
+        Msg::NotWorking => "注文は既に有効ではありません",
+        Msg::Chart => "チャート",
+        Msg::MarketDepth => "市場の厚み",
+        Msg::NotionalDepth => "厚みを想定元本で表示",
+        Msg::DepthPerSide => "各サイドを自身の合計で正規化",
+        Msg::RecentTrades => "約定履歴",
+        Msg::TradeFlow => "約定フロー",
+        Msg::Book => "板",
+        Msg::Trades => "約定",
+        Msg::Price => "価格",
+        Msg::Size => "数量",
+        Msg::Total => "合計",
+        Msg::Time => "時刻",
+        Msg::Venue => "取引所",
+        Msg::Side => "売買",
+        Msg::Value => "金額",
+        Msg::Levels => "段数",
+        Msg::AvgPrice => "平均価格",
+        Msg::Slippage => "スリッページ",
+        Msg::CumVolume => "累計",
+        Msg::RunningVolume => "パネルを開いてからの出来高",
+        Msg::CumSize => "累計数量",
+        Msg::CumulativeTotals => "最良気配からの累計数量を表示",
+        Msg::AnchorBook => "板をスプレッド中心に保つ",
+        Msg::Recenter => "中央に戻す",
+        Msg::NewTrades => "件の新しい約定",
+        Msg::Spread => "スプレッド",
+        Msg::Change24h => "24時間変動",
+        Msg::High24h => "24時間高値",
+        Msg::Low24h => "24時間安値",
+        Msg::Volume24h => "24時間出来高",
+        Msg::Bid => "買い気配",
+        Msg::Ask => "売り気配",
+        Msg::BidTotal => "買い合計:",
+        Msg::AskTotal => "売り合計:",
+        Msg::BookDepthExhausted => "板の厚みが不足",
+        Msg::Consolidated => "統合",
+        Msg::ImbalanceTint => "不均衡を色で表示",
+        Msg::Buy => "買い",
+        Msg::Sell => "売り",
+        Msg::Whale => "大口",
+        Msg::Large => "大きめ",
+        Msg::Connected => "接続済み",
+        Msg::Connecting => "接続中...",
+        Msg::Reconnecting => "再接続中...",
+        Msg::Disconnected => "切断",
+        Msg::UpdateRequired => "更新が必要",
+        Msg::StaleData => "オフライン・データ時刻",
+        Msg::Status => "状態:",
+        Msg::Feed => "フィード:",
+        Msg::FeedUp => "正常",
+        Msg::FeedDegraded => "低下",
+        Msg::FeedDown => "停止",
+        Msg::FeedUnknown => "不明",
+        Msg::DegradedData => "データ品質低下",
+        Msg::Fallback => "代替",
+        Msg::Compression => "圧縮:",
+        Msg::Render => "描画:",
+        Msg::Performance => "省電力",
+        Msg::Announce => "読み上げ:",
+        Msg::DataAge => "データ鮮度:",
+        Msg::Watermark => "透かし:",
+        Msg::TapeShading => "歩み値の濃淡:",
+        Msg::Collapse => "集約:",
+        Msg::Old => "経過",
+        Msg::Language => "言語",
+        Msg::ExtraPanel => "追加パネル",
+        Msg::NoPanel => "なし",
+        Msg::PanelSettings => "パネル設定",
+        Msg::Live => "ライブ",
+        Msg::Replay => "リプレイ",
+        Msg::Resume => "再開",
+        Msg::Volume => "出来高",
+        Msg::Grid => "グリッド",
+        Msg::LastPrice => "現在値",
+        Msg::HighLow => "高値/安値",
+        Msg::VolumeTotal => "合計",
+        Msg::VolumeSplit => "売買",
+        Msg::VolumeDelta => "デルタ",
+        Msg::CumulativeDelta => "累積デルタ",
+        Msg::ScheduledEvents => "イベント",
+        Msg::SessionShading => "時間帯の網掛け",
+        Msg::Mid => "仲値",
+        Msg::Microprice => "マイクロ",
+        Msg::WeightedMid => "加重仲値",
+        Msg::BookPrices => "板価格",
+        Msg::SessionLog => "セッションログ",
+        Msg::Export => "エクスポート",
+        Msg::Copied => "コピーしました",
+        Msg::Saved => "保存しました",
+        Msg::Legend => "凡例",
+        Msg::ChartType => "チャート種類",
+        Msg::Candles => "ローソク足",
+        Msg::OhlcBars => "OHLCバー",
+        Msg::LineSeries => "ライン",
+        Msg::AreaSeries => "エリア",
+        Msg::BaselineSeries => "ベースライン",
+        Msg::PriceLine => "水平線",
+        Msg::BaselineLevel => "基準設定",
+        Msg::Measure => "計測",
+        Msg::Clear => "消去",
+        Msg::ExitFullscreen => "全画面を終了",
+        Msg::Expand => "拡大",
+        Msg::LocalTime => "ローカル",
+        Msg::CandleClose => "足確定まで",
+        Msg::Sessions => "取引時間",
+        Msg::SessionAsia => "アジア",
+        Msg::SessionEurope => "欧州",
+        Msg::SessionUs => "米国",
+        Msg::SessionOpen => "取引中",
+        Msg::OpensIn => "開始まで",
+        Msg::CommandPalette => "コマンドパレット",
+        Msg::PaletteHint => "コマンドを入力…",
+        Msg::NoMatches => "一致するコマンドはありません",
+    }
+}
+
+fn zh(msg: Msg) -> &'static str {
+    match msg {
+        Msg::OrderBook => "订单簿",
+        Msg::MarketImpact => "市场冲击",
+        Msg::News => "新闻",
+        Msg::NewsPairOnly => "仅显示此交易对的新闻",
+        Msg::AlertKeywords => "提醒关键词",
+        Msg::NoNews => "暂无新闻",
+        Msg::PaperTrading => "模拟交易",
+        Msg::MarketOrder => "市价",
+        Msg::LimitOrder => "限价",
+        Msg::StopOrder => "止损单",
+        Msg::TakeProfit => "止盈",
+        Msg::StopLoss => "止损",
+        Msg::PlaceOrder => "下单",
+        Msg::CancelOrder => "撤单",
+        Msg::WorkingOrders => "挂单",
+        Msg::NoOrders => "暂无挂单",
+        Msg::Position => "持仓",
+        Msg::OpenPnl => "浮动盈亏",
+        Msg::RealizedPnl => "已实现盈亏",
+        Msg::RewardRisk => "盈亏比",
+        Msg::Leverage => "杠杆",
+        Msg::Liquidation => "强平",
+        Msg::Journal => "交易日志",
+        Msg::Equity => "权益",
+        Msg::NoFills => "暂无成交",
+        Msg::Risk => "风险",
+        Msg::Exposure => "敞口",
+        Msg::MaxDrawdown => "最大回撤",
+        Msg::Sharpe => "夏普（每笔）",
+        Msg::WinRate => "胜率",
+        Msg::AverageR => "平均R",
+        Msg::Backtest => "回测",
+        Msg::RunBacktest => "运行回测",
+        Msg::FastSma => "快速SMA",
+        Msg::SlowSma => "慢速SMA",
+        Msg::Indicators => "指标",
+        Msg::AddIndicator => "添加指标",
+        Msg::IndicatorName => "名称",
+        Msg::Formula => "公式",
+        Msg::ExprSyntax => "语法错误",
+        Msg::ExprUnknownName => "未知名称",
+        Msg::ExprArguments => "参数数量错误",
+        Msg::ExprPeriod => "回溯期须为1到500的整数",
+        Msg::ExprTooComplex => "过长或计算量过大",
+        Msg::ExprNotCondition => "需要条件表达式，例如 price > 100000",
+        Msg::Alerts => "提醒",
+        Msg::AddAlert => "添加提醒",
+        Msg::AlertCondition => "条件",
+        Msg::NoAlerts => "暂无提醒",
+        Msg::Notifications => "通知",
+        Msg::NotificationsBlocked => "通知已在浏览器的网站设置中被阻止",
+        Msg::ServerDelivery => "服务器推送",
+        Msg::WebhookUrl => "Webhook 地址",
+        Msg::WebhookFormat => "格式",
+        Msg::ServerToken => "服务器令牌",
+        Msg::AlertSyncPending => "同步中…",
+        Msg::AlertSynced => "由服务器监控",
+        Msg::AlertSyncUnauthorized => "令牌被拒绝",
+        Msg::AlertSyncUnavailable => "服务器提醒未启用",
+        Msg::AlertSyncInvalidWebhook => "Webhook 必须是 https 地址",
+        Msg::AlertSyncFailed => "无法连接服务器",
+        Msg::Account => "账户",
+        Msg::SignIn => "登录",
+        Msg::SignUp => "创建账户",
+        Msg::SignOut => "退出登录",
+        Msg::Username => "用户名",
+        Msg::Password => "密码",
+        Msg::SignedInAs => "已登录：",
+        Msg::AccountSyncing => "同步中…",
+        Msg::AccountSynced => "设置已同步",
+        Msg::InvalidUsername => "3-32 个字母、数字、_ - 或 .",
+        Msg::PasswordTooShort => "密码至少需要 8 个字符",
+        Msg::UsernameTaken => "用户名已被使用",
+        Msg::WrongPassword => "用户名或密码错误",
+        Msg::SessionExpired => "会话已过期，请重新登录",
+        Msg::AccountsUnavailable => "此服务器未启用账户",
+        Msg::AccountFailed => "无法连接服务器",
+        Msg::Viewer => "查看者",
+        Msg::Operator => "操作员",
+        Msg::ReadOnly => "只读",
+        Msg::Admin => "管理",
+        Msg::ServerAdmin => "服务器管理",
+        Msg::BackToDashboard => "仪表盘",
+        Msg::AdminLoading => "加载中…",
+        Msg::AdminSignIn => "以操作员身份登录以管理服务器",
+        Msg::AdminForbidden => "仅限操作员",
+        Msg::AdminFailed => "无法连接服务器",
+        Msg::Clients => "客户端",
+        Msg::NoClients => "没有已连接的客户端",
+        Msg::Connections => "连接数",
+        Msg::Throughput => "吞吐量",
+        Msg::PerSecond => "条/秒",
+        Msg::Upstream => "上游",
+        Msg::DataSource => "数据源",
+        Msg::MockScenarios => "模拟场景",
+        Msg::ScenarioRally => "上涨",
+        Msg::ScenarioCrash => "暴跌",
+        Msg::ScenarioVolatile => "高波动",
+        Msg::ScenarioStall => "停滞",
+        Msg::EndScenario => "结束场景",
+        Msg::Recording => "历史记录",
+        Msg::PauseRecording => "暂停",
+        Msg::ResumeRecording => "恢复",
+        Msg::NoStorage => "无历史存储",
+        Msg::ClientLog => "日志",
+        Msg::ClientLogTitle => "客户端日志",
+        Msg::NoLogLines => "暂无日志",
+        Msg::Diagnostics => "诊断",
+        Msg::SignalUpdates => "信号更新",
+        Msg::Timings => "耗时",
+        Msg::QueuedCommands => "排队命令",
+        Msg::Buffers => "缓冲区",
+        Msg::ParseErrors => "解析错误",
+        Msg::NoParseErrors => "无解析错误",
+        Msg::InvalidQuantity => "请输入大于零的数量",
+        Msg::PriceRequired => "请输入价格",
+        Msg::NoMarketPrice => "尚无成交价可用于市价单",
+        Msg::StopSide => "买入止损单须高于市价，卖出止损单须低于市价",
+        Msg::TakeProfitSide => "止盈须位于入场价的盈利一侧",
+        Msg::StopLossSide => "止损须位于入场价的亏损一侧",
+        Msg::NotWorking => "订单已不再有效",
+        Msg::Chart => "图表",
+        Msg::MarketDepth => "市场深度",
+        Msg::NotionalDepth => "按名义价值显示深度",
+        Msg::DepthPerSide => "按各自总量缩放买卖两侧",
+        Msg::RecentTrades => "最新成交",
+        Msg::TradeFlow => "成交流向",
+        Msg::Book => "盘口",
+        Msg::Trades => "成交",
+        Msg::Price => "价格",
+        Msg::Size => "数量",
+        Msg::Total => "总额",
+        Msg::Time => "时间",
+        Msg::Venue => "交易所",
+        Msg::Side => "方向",
+        Msg::Value => "金额",
+        Msg::Levels => "档位",
+        Msg::AvgPrice => "均价",
+        Msg::Slippage => "滑点",
+        Msg::CumVolume => "累计",
+        Msg::RunningVolume => "打开面板以来的成交量",
+        Msg::CumSize => "累计数量",
+        Msg::CumulativeTotals => "显示自最优价起的累计数量",
+        Msg::AnchorBook => "使订单簿保持以价差为中心",
+        Msg::Recenter => "回到中间",
+        Msg::NewTrades => "条新成交",
+        Msg::Spread => "价差",
+        Msg::Change24h => "24小时涨跌",
+        Msg::High24h => "24小时最高",
+        Msg::Low24h => "24小时最低",
+        Msg::Volume24h => "24小时成交量",
+        Msg::Bid => "买盘",
+        Msg::Ask => "卖盘",
+        Msg::BidTotal => "买单总量:",
+        Msg::AskTotal => "卖单总量:",
+        Msg::BookDepthExhausted => "盘口深度不足",
+        Msg::Consolidated => "综合",
+        Msg::ImbalanceTint => "失衡着色",
+        Msg::Buy => "买入",
+        Msg::Sell => "卖出",
+        Msg::Whale => "巨鲸",
+        Msg::Large => "大单",
+        Msg::Connected => "已连接",
+        Msg::Connecting => "连接中...",
+        Msg::Reconnecting => "重新连接中...",
+        Msg::Disconnected => "已断开",
+        Msg::UpdateRequired => "需要更新",
+        Msg::StaleData => "离线，数据截至",
+        Msg::Status => "状态:",
+        Msg::Feed => "数据源:",
+        Msg::FeedUp => "正常",
+        Msg::FeedDegraded => "降级",
+        Msg::FeedDown => "中断",
+        Msg::FeedUnknown => "未知",
+        Msg::DegradedData => "数据降级",
+        Msg::Fallback => "备用",
+        Msg::Compression => "压缩:",
+        Msg::Render => "渲染:",
+        Msg::Performance => "省电模式",
+        Msg::Announce => "播报:",
+        Msg::DataAge => "数据时效:",
+        Msg::Watermark => "水印:",
+        Msg::TapeShading => "成交着色:",
+        Msg::Collapse => "合并:",
+        Msg::Old => "前",
+        Msg::Language => "语言",
+        Msg::ExtraPanel => "附加面板",
+        Msg::NoPanel => "无",
+        Msg::PanelSettings => "面板设置",
+        Msg::Live => "实时",
+        Msg::Replay => "回放",
+        Msg::Resume => "恢复",
+        Msg::Volume => "成交量",
+        Msg::Grid => "网格",
+        Msg::LastPrice => "最新价",
+        Msg::HighLow => "高/低",
+        Msg::VolumeTotal => "总量",
+        Msg::VolumeSplit => "买/卖",
+        Msg::VolumeDelta => "差额",
+        Msg::CumulativeDelta => "累计差额",
+        Msg::ScheduledEvents => "事件",
+        Msg::SessionShading => "时段底色",
+        Msg::Mid => "中间价",
+        Msg::Microprice => "微观价",
+        Msg::WeightedMid => "加权中间价",
+        Msg::BookPrices => "盘口价格",
+        Msg::SessionLog => "会话日志",
+        Msg::Export => "导出",
+        Msg::Copied => "已复制",
+        Msg::Saved => "已保存",
+        Msg::Legend => "图例",
+        Msg::ChartType => "图表类型",
+        Msg::Candles => "K线",
+        Msg::OhlcBars => "美国线",
+        Msg::LineSeries => "折线",
+        Msg::AreaSeries => "面积",
+        Msg::BaselineSeries => "基准线",
+        Msg::PriceLine => "水平线",
+        Msg::BaselineLevel => "设基准",
+        Msg::Measure => "测量",
+        Msg::Clear => "清除",
+        Msg::ExitFullscreen => "退出全屏",
+        Msg::Expand => "放大",
+        Msg::LocalTime => "本地",
+        Msg::CandleClose => "K线收盘",
+        Msg::Sessions => "交易时段",
+        Msg::SessionAsia => "亚洲",
+        Msg::SessionEurope => "欧洲",
+        Msg::SessionUs => "美国",
+        Msg::SessionOpen => "交易中",
+        Msg::OpensIn => "距开盘",
+        Msg::CommandPalette => "命令面板",
+        Msg::PaletteHint => "输入命令…",
+        Msg::NoMatches => "没有匹配的命令",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_from_tag() {
+        assert_eq!(Locale::from_tag("de-AT"), Some(Locale::De));
+        assert_eq!(Locale::from_tag("zh_Hans_CN"), Some(Locale::Zh));
+        assert_eq!(Locale::from_tag("JA"), Some(Locale::Ja));
+        assert_eq!(Locale::from_tag("fr-FR"), None);
+    }
+
+    #[test]
+    fn test_format_decimal() {
+        assert_eq!(Locale::En.format_decimal(64210.5, 2), "64,210.50");
+        assert_eq!(Locale::De.format_decimal(1234567.891, 2), "1.234.567,89");
+        assert_eq!(Locale::Ja.format_decimal(999.0, 0), "999");
+        assert_eq!(Locale::En.format_decimal(-1500.0, 1), "-1,500.0");
+        assert_eq!(Locale::En.format_decimal(-0.001, 2), "0.00");
+        assert_eq!(Locale::De.format_compact(2_500_000.0), "2,50M");
+    }
+
+    #[test]
+    fn test_format_date() {
+        let t = Utc.with_ymd_and_hms(2025, 3, 7, 14, 5, 9).unwrap();
+        assert_eq!(Locale::En.format_date(&t), "Mar 7, 2025");
+        assert_eq!(Locale::De.format_date(&t), "07.03.2025");
+        assert_eq!(Locale::Zh.format_datetime(&t, false), "2025年3月7日 14:05:09 UTC");
+    }
+
+    #[test]
+    fn test_bundles_translated() {
+        // Untranslated entries would show up as English text in another bundle
+        for msg in [Msg::OrderBook, Msg::MarketDepth, Msg::Spread, Msg::High24h] {
+            assert_ne!(Locale::Ja.text(msg), Locale::En.text(msg));
+            assert_ne!(Locale::Zh.text(msg), Locale::En.text(msg));
+        }
+    }
+}
@@ -0,0 +1,498 @@
+//! Technical indicators over candle closes
+//!
+//! Indicators update incrementally: a new candle is pushed, and each tick of
+//! the forming candle replaces the newest sample instead of recomputing the
+//! whole history.
+//!
+//! A full recompute (a new indicator, a reloaded history) can be deferred:
+//! the set primes each indicator's state and its newest value at once, and
+//! hands out an `IndicatorJob` for the values at the older candles. The job
+//! is serializable, so it can run in a Web Worker; `IndicatorSet::complete`
+//! fills its output in.
+
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Candle, CandleDelta, Ewma, RollingStats, Script};
+
+/// An indicator fed one candle at a time
+pub trait Indicator: Send + Sync {
+    /// A new candle opened
+    fn push(&mut self, candle: &Candle);
+
+    /// The newest candle changed
+    fn replace_last(&mut self, candle: &Candle);
+
+    /// Current value (`None` until warmed up)
+    fn value(&self) -> Option<f64>;
+
+    fn reset(&mut self);
+
+    /// Recompute from a full history
+    fn rebuild(&mut self, candles: &[Candle]) {
+        self.reset();
+        for candle in candles {
+            self.push(candle);
+        }
+    }
+}
+
+/// Indicators the chart can compute
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum IndicatorKind {
+    /// Simple moving average of the last n closes
+    Sma(usize),
+    /// Exponential moving average with the usual 2 / (n + 1) weight
+    Ema(usize),
+    /// User formula over the candles
+    Script(Script),
+}
+
+impl IndicatorKind {
+    pub fn label(&self) -> String {
+        match self {
+            Self::Sma(period) => format!("SMA {}", period),
+            Self::Ema(period) => format!("EMA {}", period),
+            Self::Script(script) => script.name().to_string(),
+        }
+    }
+
+    pub fn build(&self) -> Box<dyn Indicator> {
+        match self {
+            Self::Sma(period) => Box::new(Sma::new(*period)),
+            Self::Ema(period) => Box::new(Ema::new(*period)),
+            Self::Script(script) => Box::new(ScriptIndicator::new(script.clone())),
+        }
+    }
+}
+
+/// Simple moving average of closes
+#[derive(Debug, Clone)]
+pub struct Sma {
+    stats: RollingStats,
+}
+
+impl Sma {
+    pub fn new(period: usize) -> Self {
+        Self {
+            stats: RollingStats::new(period),
+        }
+    }
+}
+
+impl Indicator for Sma {
+    fn push(&mut self, candle: &Candle) {
+        self.stats.push(candle.close.as_f64());
+    }
+
+    fn replace_last(&mut self, candle: &Candle) {
+        self.stats.replace_last(candle.close.as_f64());
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.stats.mean().filter(|_| self.stats.is_full())
+    }
+
+    fn reset(&mut self) {
+        self.stats.clear();
+    }
+}
+
+/// Exponential moving average of closes
+#[derive(Debug, Clone)]
+pub struct Ema {
+    ewma: Ewma,
+    period: usize,
+    /// Candles seen, for warm-up
+    count: usize,
+}
+
+impl Ema {
+    pub fn new(period: usize) -> Self {
+        let period = period.max(1);
+        Self {
+            ewma: Ewma::new(2.0 / (period as f64 + 1.0)),
+            period,
+            count: 0,
+        }
+    }
+}
+
+impl Indicator for Ema {
+    fn push(&mut self, candle: &Candle) {
+        self.ewma.update(candle.close.as_f64());
+        self.count += 1;
+    }
+
+    fn replace_last(&mut self, candle: &Candle) {
+        if self.count == 0 {
+            self.push(candle);
+        } else {
+            self.ewma.replace_last(candle.close.as_f64());
+        }
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.ewma.value().filter(|_| self.count >= self.period)
+    }
+
+    fn reset(&mut self) {
+        self.ewma.reset();
+        self.count = 0;
+    }
+}
+
+/// A script fed one candle at a time, keeping only the candles it reads
+#[derive(Debug, Clone)]
+pub struct ScriptIndicator {
+    script: Script,
+    /// The newest `lookback + 1` candles, oldest first
+    window: Vec<Candle>,
+}
+
+impl ScriptIndicator {
+    pub fn new(script: Script) -> Self {
+        Self { window: Vec::with_capacity(script.lookback() + 1), script }
+    }
+}
+
+impl Indicator for ScriptIndicator {
+    fn push(&mut self, candle: &Candle) {
+        if self.window.len() > self.script.lookback() {
+            self.window.remove(0);
+        }
+        self.window.push(candle.clone());
+    }
+
+    fn replace_last(&mut self, candle: &Candle) {
+        match self.window.last_mut() {
+            Some(last) => *last = candle.clone(),
+            None => self.push(candle),
+        }
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.script.eval_last(&self.window)
+    }
+
+    fn reset(&mut self) {
+        self.window.clear();
+    }
+}
+
+/// Values of indicators at each candle of a history, computed away from the
+/// set that asked for them
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndicatorJob {
+    /// Matches the output to the rebuild that is waiting for it
+    pub id: u64,
+    pub kinds: Vec<IndicatorKind>,
+    /// The history up to, but not including, its newest candle
+    pub candles: Vec<Candle>,
+}
+
+impl IndicatorJob {
+    pub fn run(&self) -> IndicatorJobOutput {
+        let values = self
+            .kinds
+            .iter()
+            .map(|kind| {
+                let mut indicator = kind.build();
+                let values = self
+                    .candles
+                    .iter()
+                    .map(|candle| {
+                        indicator.push(candle);
+                        indicator.value()
+                    })
+                    .collect();
+                (kind.clone(), values)
+            })
+            .collect();
+        IndicatorJobOutput { id: self.id, values }
+    }
+}
+
+/// Each indicator's value at every candle of an `IndicatorJob`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndicatorJobOutput {
+    pub id: u64,
+    pub values: Vec<(IndicatorKind, Vec<Option<f64>>)>,
+}
+
+/// An active indicator and its value at each candle of the history
+struct Tracked {
+    kind: IndicatorKind,
+    indicator: Box<dyn Indicator>,
+    /// Aligned with the newest candles, oldest first
+    values: VecDeque<Option<f64>>,
+    /// Job the older values wait for, and how many of the oldest values
+    /// were dropped since it was handed out
+    pending: Option<(u64, usize)>,
+}
+
+impl Tracked {
+    /// Rebuild the indicator's state and newest value; the older values
+    /// stay empty until `job` completes
+    fn prime(&mut self, candles: &[Candle], job: u64) {
+        self.indicator.rebuild(candles);
+        self.values = VecDeque::from(vec![None; candles.len()]);
+        if let Some(newest) = self.values.back_mut() {
+            *newest = self.indicator.value();
+        }
+        self.pending = (candles.len() > 1).then_some((job, 0));
+    }
+}
+
+/// The active indicators, kept in step with a candle history
+#[derive(Default)]
+pub struct IndicatorSet {
+    indicators: Vec<Tracked>,
+    next_job: u64,
+}
+
+impl IndicatorSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `kind` computed over `candles`; no-op if already active
+    pub fn add(&mut self, kind: IndicatorKind, candles: &[Candle]) {
+        if let Some(job) = self.add_deferred(kind, candles) {
+            self.complete(job.run());
+        }
+    }
+
+    /// Add `kind` with only its newest value; the job computes the rest
+    pub fn add_deferred(
+        &mut self,
+        kind: IndicatorKind,
+        candles: &[Candle],
+    ) -> Option<IndicatorJob> {
+        if self.contains(&kind) {
+            return None;
+        }
+        let indicator = kind.build();
+        let mut tracked = Tracked { kind, indicator, values: VecDeque::new(), pending: None };
+        tracked.prime(candles, self.next_job);
+        let kinds = vec![tracked.kind.clone()];
+        self.indicators.push(tracked);
+        self.job(kinds, candles)
+    }
+
+    pub fn remove(&mut self, kind: &IndicatorKind) {
+        self.indicators.retain(|t| t.kind != *kind);
+    }
+
+    pub fn contains(&self, kind: &IndicatorKind) -> bool {
+        self.indicators.iter().any(|t| t.kind == *kind)
+    }
+
+    pub fn kinds(&self) -> impl Iterator<Item = &IndicatorKind> {
+        self.indicators.iter().map(|t| &t.kind)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indicators.is_empty()
+    }
+
+    /// Follow a change to `candles`, the history after the update
+    pub fn apply(&mut self, delta: CandleDelta, candles: &[Candle]) {
+        if let Some(job) = self.apply_deferred(delta, candles) {
+            self.complete(job.run());
+        }
+    }
+
+    /// Follow a change to `candles`; a full recompute only primes the
+    /// newest values and returns the job for the rest
+    pub fn apply_deferred(
+        &mut self,
+        delta: CandleDelta,
+        candles: &[Candle],
+    ) -> Option<IndicatorJob> {
+        let mut rebuilt = Vec::new();
+        for tracked in &mut self.indicators {
+            match (delta, candles.last()) {
+                (CandleDelta::Appended, Some(last)) => {
+                    tracked.indicator.push(last);
+                    tracked.values.push_back(tracked.indicator.value());
+                    // The history dropped its oldest candle to make room
+                    if tracked.values.len() > candles.len() {
+                        tracked.values.pop_front();
+                        if let Some((_, dropped)) = &mut tracked.pending {
+                            *dropped += 1;
+                        }
+                    }
+                }
+                (CandleDelta::ReplacedLast, Some(last)) => {
+                    tracked.indicator.replace_last(last);
+                    let value = tracked.indicator.value();
+                    match tracked.values.back_mut() {
+                        Some(newest) => *newest = value,
+                        None => tracked.values.push_back(value),
+                    }
+                }
+                (CandleDelta::Rejected, _) => {}
+                _ => {
+                    tracked.prime(candles, self.next_job);
+                    rebuilt.push(tracked.kind.clone());
+                }
+            }
+        }
+        self.job(rebuilt, candles)
+    }
+
+    /// Job for the values of `kinds` before the newest of `candles`
+    fn job(&mut self, kinds: Vec<IndicatorKind>, candles: &[Candle]) -> Option<IndicatorJob> {
+        if kinds.is_empty() || candles.len() < 2 {
+            return None;
+        }
+        let id = self.next_job;
+        self.next_job += 1;
+        Some(IndicatorJob { id, kinds, candles: candles[..candles.len() - 1].to_vec() })
+    }
+
+    /// Fill in the values a job computed. Output of a job superseded by a
+    /// later rebuild, or for a removed indicator, is ignored.
+    pub fn complete(&mut self, output: IndicatorJobOutput) {
+        for (kind, values) in output.values {
+            let Some(tracked) = self.indicators.iter_mut().find(|t| t.kind == kind) else {
+                continue;
+            };
+            let Some((_, dropped)) = tracked.pending.filter(|(job, _)| *job == output.id) else {
+                continue;
+            };
+            for (slot, value) in tracked.values.iter_mut().zip(values.into_iter().skip(dropped)) {
+                *slot = value;
+            }
+            tracked.pending = None;
+        }
+    }
+
+    /// Current value of each indicator, in the order added
+    pub fn values(&self) -> Vec<(IndicatorKind, Option<f64>)> {
+        self.indicators.iter().map(|t| (t.kind.clone(), t.indicator.value())).collect()
+    }
+
+    /// Each indicator's values as (candle open time, value) over `candles`,
+    /// the history the set follows
+    pub fn series(&self, candles: &[Candle]) -> Vec<(IndicatorKind, Vec<(i64, f64)>)> {
+        self.indicators
+            .iter()
+            .map(|t| {
+                let points = candles
+                    .iter()
+                    .rev()
+                    .zip(t.values.iter().rev())
+                    .filter_map(|(candle, value)| value.map(|v| (candle.timestamp, v)))
+                    .collect::<Vec<_>>()
+                    .into_iter()
+                    .rev()
+                    .collect();
+                (t.kind.clone(), points)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CandleInterval, Symbol};
+
+    fn candle(i: i64, close: f64) -> Candle {
+        let mut c = Candle::new(Symbol::default(), CandleInterval::M1, i * 60_000, close);
+        c.update(close, 1.0);
+        c
+    }
+
+    #[test]
+    fn test_incremental_matches_rebuild() {
+        let closes = [10.0, 12.0, 11.0, 15.0, 14.0];
+        let candles: Vec<Candle> =
+            closes.iter().enumerate().map(|(i, &c)| candle(i as i64, c)).collect();
+
+        let mut set = IndicatorSet::new();
+        set.add(IndicatorKind::Sma(3), &candles[..1]);
+        set.add(IndicatorKind::Ema(3), &candles[..1]);
+        for n in 2..=candles.len() {
+            set.apply(CandleDelta::Appended, &candles[..n]);
+            // Ticks of the forming candle before its close
+            let mut forming = candles[..n].to_vec();
+            for tick in [1.0, 30.0, closes[n - 1]] {
+                forming[n - 1] = candle(n as i64 - 1, tick);
+                set.apply(CandleDelta::ReplacedLast, &forming);
+            }
+        }
+
+        let mut rebuilt = IndicatorSet::new();
+        rebuilt.add(IndicatorKind::Sma(3), &candles);
+        rebuilt.add(IndicatorKind::Ema(3), &candles);
+        for ((kind, a), (_, b)) in set.values().into_iter().zip(rebuilt.values()) {
+            assert!((a.unwrap() - b.unwrap()).abs() < 1e-9, "{:?}", kind);
+        }
+        assert_eq!(set.values()[0].1, Some((11.0 + 15.0 + 14.0) / 3.0));
+
+        // The series follows the forming candle and lines up with the history
+        let sma = &set.series(&candles)[0].1;
+        assert_eq!(sma.len(), 3);
+        assert_eq!(sma[0], (2 * 60_000, 11.0));
+        let script = Script::compile("mid", "(high + low) / 2").unwrap();
+        set.add(IndicatorKind::Script(script.clone()), &candles);
+        assert!(set.contains(&IndicatorKind::Script(script)));
+        assert_eq!(set.series(&candles)[2].1.last(), Some(&(4 * 60_000, 14.0)));
+    }
+
+    #[test]
+    fn test_script_indicator() {
+        let history: Vec<Candle> = (1..=5).map(|i| candle(i, i as f64)).collect();
+        let script = Script::compile("avg", "sma(close, 2)").unwrap();
+        let mut indicator = ScriptIndicator::new(script);
+        indicator.rebuild(&history);
+        assert_eq!(indicator.value(), Some(4.5));
+        indicator.replace_last(&candle(5, 7.0));
+        assert_eq!(indicator.value(), Some(5.5));
+    }
+
+    #[test]
+    fn test_deferred_rebuild() {
+        let candles: Vec<Candle> = (0..7).map(|i| candle(i, (i * i % 5) as f64)).collect();
+        let script = IndicatorKind::Script(Script::compile("mid", "sma(close, 2)").unwrap());
+        let mut set = IndicatorSet::new();
+        let job = set.add_deferred(script.clone(), &candles[..5]).unwrap();
+        assert_eq!(job.candles.len(), 4);
+        assert_eq!(set.series(&candles[..5])[0].1.len(), 1);
+
+        // The history moves on while the job is out: a candle in, the oldest out
+        set.apply(CandleDelta::Appended, &candles[1..6]);
+        let json = serde_json::to_string(&job).unwrap();
+        let job: IndicatorJob = serde_json::from_str(&json).unwrap();
+        let output = job.run();
+        set.complete(output.clone());
+
+        let mut full = IndicatorSet::new();
+        full.add(script.clone(), &candles[..6]);
+        assert_eq!(set.series(&candles[1..6]), full.series(&candles[1..6]));
+
+        // A later rebuild supersedes the job
+        let rebuild = set.apply_deferred(CandleDelta::Reloaded, &candles[1..7]).unwrap();
+        set.complete(output);
+        assert_eq!(set.series(&candles[1..7])[0].1.len(), 1);
+        set.complete(rebuild.run());
+        let mut rebuilt = IndicatorSet::new();
+        rebuilt.add(script, &candles[1..7]);
+        assert_eq!(set.series(&candles[1..7]), rebuilt.series(&candles[1..7]));
+    }
+
+    #[test]
+    fn test_warm_up() {
+        let mut sma = Sma::new(3);
+        sma.push(&candle(0, 1.0));
+        sma.push(&candle(1, 2.0));
+        assert_eq!(sma.value(), None);
+        sma.push(&candle(2, 3.0));
+        assert_eq!(sma.value(), Some(2.0));
+        assert_eq!(IndicatorKind::Ema(20).label(), "EMA 20");
+    }
+}
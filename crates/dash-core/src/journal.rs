@@ -0,0 +1,153 @@
+//! Trade journal
+//!
+//! Every paper fill with the PnL it closed, kept across reloads. The running
+//! sum of realized PnL is the account's equity curve, and risk metrics are
+//! kept up to date as fills are recorded.
+
+use std::collections::VecDeque;
+use std::fmt::Write;
+
+use chrono::{DateTime, Utc};
+
+use crate::{Fill, RiskMetrics};
+
+/// Fills kept; the oldest are dropped first
+pub const MAX_JOURNAL_FILLS: usize = 1_000;
+
+/// Header row of the CSV export
+const CSV_HEADER: &str = "time,symbol,side,quantity,price,realized_pnl,equity";
+
+/// Paper fills, oldest first
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FillJournal {
+    fills: VecDeque<Fill>,
+    /// Over every fill recorded since the journal was loaded or cleared
+    metrics: RiskMetrics,
+}
+
+impl FillJournal {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, fill: Fill) {
+        self.metrics.record(&fill);
+        if self.fills.len() == MAX_JOURNAL_FILLS {
+            self.fills.pop_front();
+        }
+        self.fills.push_back(fill);
+    }
+
+    /// Put fills loaded from storage (oldest first) ahead of the ones
+    /// recorded since
+    pub fn restore(&mut self, fills: Vec<Fill>) {
+        let mut restored = VecDeque::from(fills);
+        restored.extend(self.fills.drain(..));
+        let excess = restored.len().saturating_sub(MAX_JOURNAL_FILLS);
+        restored.drain(..excess);
+        self.fills = restored;
+        self.metrics = RiskMetrics::from_fills(&self.fills);
+    }
+
+    pub fn fills(&self) -> impl DoubleEndedIterator<Item = &Fill> {
+        self.fills.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.fills.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fills.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.fills.clear();
+        self.metrics = RiskMetrics::new();
+    }
+
+    pub fn metrics(&self) -> &RiskMetrics {
+        &self.metrics
+    }
+
+    /// Sum of realized PnL over the kept fills
+    pub fn realized_pnl(&self) -> f64 {
+        self.fills.iter().map(|f| f.realized_pnl).sum()
+    }
+
+    /// Equity after each fill, starting from zero before the first
+    pub fn equity_curve(&self) -> Vec<f64> {
+        let mut equity = 0.0;
+        std::iter::once(0.0)
+            .chain(self.fills.iter().map(|fill| {
+                equity += fill.realized_pnl;
+                equity
+            }))
+            .collect()
+    }
+
+    /// One row per fill with the running equity
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from(CSV_HEADER);
+        csv.push('\n');
+        let mut equity = 0.0;
+        for fill in &self.fills {
+            equity += fill.realized_pnl;
+            let time = DateTime::<Utc>::from_timestamp_millis(fill.timestamp)
+                .map(|t| t.to_rfc3339())
+                .unwrap_or_default();
+            let _ = writeln!(
+                csv,
+                "{},{},{},{},{},{},{}",
+                time,
+                fill.symbol,
+                fill.side.label(),
+                fill.quantity,
+                fill.price,
+                fill.realized_pnl,
+                equity
+            );
+        }
+        csv
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Symbol, TradeSide};
+
+    fn fill(timestamp: i64, realized_pnl: f64) -> Fill {
+        Fill {
+            order_id: 1,
+            symbol: Symbol::new("BTC-USD"),
+            side: TradeSide::Sell,
+            quantity: 0.5,
+            price: 100.0,
+            timestamp,
+            realized_pnl,
+            risk: None,
+        }
+    }
+
+    #[test]
+    fn test_fill_journal() {
+        let mut journal = FillJournal::new();
+        journal.record(fill(0, 0.0));
+        journal.record(fill(1_000, 5.0));
+        journal.record(fill(2_000, -2.0));
+        assert_eq!(journal.equity_curve(), vec![0.0, 0.0, 5.0, 3.0]);
+        assert_eq!(journal.realized_pnl(), 3.0);
+
+        let csv = journal.to_csv();
+        let mut rows = csv.lines();
+        assert_eq!(rows.next(), Some(CSV_HEADER));
+        assert_eq!(rows.nth(1), Some("1970-01-01T00:00:01+00:00,BTC-USD,SELL,0.5,100,5,5"));
+
+        journal.restore(vec![fill(-1, 1.0)]);
+        assert_eq!(journal.len(), 4);
+        assert_eq!(journal.equity_curve().last(), Some(&4.0));
+        assert_eq!(journal.metrics().trades(), 3);
+        assert_eq!(journal.metrics().max_drawdown(), 2.0);
+    }
+}
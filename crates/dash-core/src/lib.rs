@@ -3,15 +3,73 @@
 //! Core domain types for the BTC Exchange Dashboard.
 //! Implements Strategy pattern for formatting and validation.
 
+pub mod account;
+pub mod admin;
+pub mod alert;
+#[cfg(feature = "paper-trading")]
+pub mod backtest;
+pub mod calendar;
 pub mod candle;
+pub mod codec;
+pub mod collections;
+pub mod consolidated;
+pub mod decode;
+pub mod downsample;
+pub mod expr;
+pub mod health;
+pub mod i18n;
+#[cfg(feature = "indicators")]
+pub mod indicator;
+#[cfg(feature = "paper-trading")]
+pub mod journal;
+pub mod news;
 pub mod order;
+#[cfg(feature = "paper-trading")]
+pub mod paper;
+pub mod protocol;
+#[cfg(feature = "paper-trading")]
+pub mod risk;
+pub mod script;
+pub mod session;
+pub mod stats;
 pub mod ticker;
 pub mod trade;
-
+pub mod wall;
+
+pub use account::*;
+pub use admin::*;
+pub use alert::*;
+#[cfg(feature = "paper-trading")]
+pub use backtest::*;
+pub use calendar::*;
 pub use candle::*;
+pub use codec::*;
+pub use collections::*;
+pub use consolidated::*;
+pub use decode::*;
+pub use downsample::*;
+pub use expr::*;
+pub use health::*;
+pub use i18n::*;
+#[cfg(feature = "indicators")]
+pub use indicator::*;
+#[cfg(feature = "paper-trading")]
+pub use journal::*;
+pub use news::*;
 pub use order::*;
+#[cfg(feature = "paper-trading")]
+pub use paper::*;
+pub use protocol::*;
+#[cfg(feature = "paper-trading")]
+pub use risk::*;
+pub use script::*;
+pub use session::*;
+pub use stats::*;
 pub use ticker::*;
 pub use trade::*;
+pub use wall::*;
+
+use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
@@ -151,6 +209,38 @@ impl From<&str> for Symbol {
     }
 }
 
+/// Canonical symbol with the instrument id each venue lists it under
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SymbolInfo {
+    pub symbol: Symbol,
+    pub base: String,
+    pub quote: String,
+    /// Venue name → venue instrument id (e.g. `binance` → `BTCUSDT`)
+    pub instruments: BTreeMap<String, String>,
+}
+
+impl SymbolInfo {
+    pub fn new(symbol: Symbol) -> Self {
+        Self {
+            base: symbol.base().to_string(),
+            quote: symbol.quote().to_string(),
+            symbol,
+            instruments: BTreeMap::new(),
+        }
+    }
+
+    /// Record a venue's instrument id
+    pub fn with_instrument(mut self, venue: impl Into<String>, instrument: impl Into<String>) -> Self {
+        self.instruments.insert(venue.into(), instrument.into());
+        self
+    }
+
+    /// Instrument id on a venue, if listed there
+    pub fn instrument(&self, venue: &str) -> Option<&str> {
+        self.instruments.get(venue).map(String::as_str)
+    }
+}
+
 /// Decimal price representation
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct Price(pub f64);
@@ -252,6 +342,116 @@ pub enum WsMessage {
     Depth(MarketDepth),
     #[serde(rename = "heartbeat")]
     Heartbeat { timestamp: i64 },
+    #[serde(rename = "source_status")]
+    SourceStatus(SourceStatus),
+    #[serde(rename = "consolidated_book")]
+    ConsolidatedBook(ConsolidatedBook),
+    #[serde(rename = "news")]
+    News(NewsItem),
+    /// A rule registered on the server fired
+    #[serde(rename = "alert_triggered")]
+    AlertTriggered(AlertNotice),
+}
+
+impl WsMessage {
+    /// Venue attribution for books and trades
+    pub fn venue(&self) -> Option<&str> {
+        match self {
+            Self::Trade(trade) => trade.venue.as_deref(),
+            Self::OrderBook(book) => book.venue.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// Book level counts offered by the level selector
+pub const DEPTH_LEVEL_CHOICES: [usize; 4] = [10, 25, 50, 100];
+
+/// Book levels requested until the user picks another count
+pub const DEFAULT_DEPTH_LEVELS: usize = 25;
+
+/// Level counts offered for the microprice and depth-weighted mid
+pub const BOOK_PRICE_LEVEL_CHOICES: [usize; 4] = [1, 3, 5, 10];
+
+/// Levels per side weighed by the microprice and depth-weighted mid
+pub const DEFAULT_BOOK_PRICE_LEVELS: usize = 5;
+
+/// Market data stream parameters for a single symbol
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Subscription {
+    pub symbol: Symbol,
+    /// Candle interval to stream
+    #[serde(default)]
+    pub interval: CandleInterval,
+    /// Order book levels per side; the server truncates books to this
+    /// (None = everything the feed has). `depth` is the pre-negotiation name.
+    #[serde(default, alias = "depth", skip_serializing_if = "Option::is_none")]
+    pub depth_levels: Option<usize>,
+    /// Only deliver trades worth at least this much in quote currency
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_trade_value: Option<f64>,
+}
+
+impl Subscription {
+    pub fn new(symbol: impl Into<Symbol>) -> Self {
+        Self {
+            symbol: symbol.into(),
+            interval: CandleInterval::default(),
+            depth_levels: None,
+            min_trade_value: None,
+        }
+    }
+
+    pub fn interval(mut self, interval: CandleInterval) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn depth_levels(mut self, levels: usize) -> Self {
+        self.depth_levels = Some(levels);
+        self
+    }
+
+    /// Drop trades below a notional value (e.g. `10_000.0` for $10k+ prints)
+    pub fn min_trade_value(mut self, value: f64) -> Self {
+        self.min_trade_value = Some(value);
+        self
+    }
+}
+
+/// Client → server command envelope
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ClientCommand {
+    #[serde(rename = "subscribe")]
+    Subscribe(Subscription),
+    #[serde(rename = "unsubscribe")]
+    Unsubscribe { symbol: Symbol },
+    /// Request fresh snapshots for a symbol (e.g., after a sequence gap)
+    #[serde(rename = "resync")]
+    Resync { symbol: Symbol },
+    #[serde(rename = "auth")]
+    Auth { token: String },
+    #[serde(rename = "ping")]
+    Ping,
+}
+
+impl ClientCommand {
+    pub fn subscribe(symbol: impl Into<Symbol>) -> Self {
+        Self::Subscribe(Subscription::new(symbol))
+    }
+
+    pub fn unsubscribe(symbol: impl Into<Symbol>) -> Self {
+        Self::Unsubscribe { symbol: symbol.into() }
+    }
+
+    pub fn resync(symbol: impl Into<Symbol>) -> Self {
+        Self::Resync { symbol: symbol.into() }
+    }
+
+    pub fn auth(token: impl Into<String>) -> Self {
+        Self::Auth { token: token.into() }
+    }
 }
 
 /// Connection state FSM
@@ -262,6 +462,8 @@ pub enum ConnectionState {
     Connecting,
     Connected,
     Reconnecting,
+    /// The server speaks a protocol major this build cannot read
+    UpdateRequired,
 }
 
 impl ConnectionState {
@@ -275,6 +477,7 @@ impl ConnectionState {
             Self::Connecting => "Connecting...",
             Self::Connected => "Connected",
             Self::Reconnecting => "Reconnecting...",
+            Self::UpdateRequired => "Update required",
         }
     }
 
@@ -284,6 +487,7 @@ impl ConnectionState {
             Self::Connecting => "conn-connecting",
             Self::Connected => "conn-connected",
             Self::Reconnecting => "conn-reconnecting",
+            Self::UpdateRequired => "conn-update-required",
         }
     }
 }
@@ -316,12 +520,70 @@ pub mod colors {
     pub fn warn_alpha(alpha: f64) -> String {
         format!("rgba(251, 191, 36, {:.2})", alpha)
     }
+
+    /// Line colors for chart indicators, cycled in the order they were added
+    pub const INDICATOR_PALETTE: [&str; 6] =
+        ["#60a5fa", "#f472b6", "#a78bfa", "#2dd4bf", "#fb923c", "#e879f9"];
+
+    pub fn indicator(index: usize) -> &'static str {
+        INDICATOR_PALETTE[index % INDICATOR_PALETTE.len()]
+    }
+
+    /// Strongest tint either end of the imbalance gradient reaches
+    const IMBALANCE_TINT_MAX: f64 = 0.25;
+
+    /// Red (asks, top) to green (bids, bottom) background whose balance
+    /// follows a book imbalance in -1..=1
+    pub fn imbalance_gradient(imbalance: f64) -> String {
+        let bid_share = (imbalance.clamp(-1.0, 1.0) + 1.0) / 2.0;
+        format!(
+            "linear-gradient(to bottom, {}, {})",
+            bear_alpha(IMBALANCE_TINT_MAX * (1.0 - bid_share)),
+            bull_alpha(IMBALANCE_TINT_MAX * bid_share)
+        )
+    }
+
+    /// Palette for venues without a house color
+    const VENUE_PALETTE: [&str; 6] = ["#38bdf8", "#a78bfa", "#f472b6", "#2dd4bf", "#fb923c", "#a3e635"];
+
+    /// Badge color for a venue: house colors for known exchanges, otherwise
+    /// a stable pick from the palette
+    pub fn venue(name: &str) -> &'static str {
+        match name.to_ascii_lowercase().as_str() {
+            "binance" => "#f0b90b",
+            "coinbase" => "#0052ff",
+            "kraken" => "#7132f5",
+            "okx" => "#d4d4d4",
+            "bybit" => "#f7a600",
+            "mock" => NEUTRAL,
+            other => {
+                let hash = other.bytes().fold(0usize, |h, b| h.wrapping_mul(31).wrapping_add(b as usize));
+                VENUE_PALETTE[hash % VENUE_PALETTE.len()]
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_venue_colors() {
+        assert_eq!(colors::venue("Binance"), "#f0b90b");
+        assert_eq!(colors::venue("mock-2"), colors::venue("mock-2"));
+        assert_ne!(colors::venue("mock-2"), colors::venue("mock-3"));
+    }
+
+    #[test]
+    fn test_imbalance_gradient() {
+        assert_eq!(
+            colors::imbalance_gradient(1.0),
+            "linear-gradient(to bottom, rgba(239, 68, 68, 0.00), rgba(34, 197, 94, 0.25))"
+        );
+        assert_eq!(colors::imbalance_gradient(-3.0), colors::imbalance_gradient(-1.0));
+    }
+
     #[test]
     fn test_symbol_parsing() {
         let sym = Symbol::new("ETH-BTC");
@@ -336,6 +598,39 @@ mod tests {
         assert_eq!(price.format_with(&formatter), "42.5678");
     }
 
+    #[test]
+    fn test_client_command_wire_format() {
+        let cmd = ClientCommand::subscribe("ETH-USD");
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert_eq!(json, r#"{"type":"subscribe","symbol":"ETH-USD","interval":"1m"}"#);
+
+        let sub = Subscription::new("BTC-USD").interval(CandleInterval::H1).depth_levels(25);
+        let json = serde_json::to_string(&ClientCommand::Subscribe(sub)).unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"subscribe","symbol":"BTC-USD","interval":"1h","depth_levels":25}"#
+        );
+
+        let sub = Subscription::new("BTC-USD").depth_levels(10).min_trade_value(10_000.0);
+        let json = serde_json::to_string(&ClientCommand::Subscribe(sub)).unwrap();
+        assert_eq!(
+            json,
+            r#"{"type":"subscribe","symbol":"BTC-USD","interval":"1m","depth_levels":10,"min_trade_value":10000.0}"#
+        );
+
+        // Older clients omit the stream parameters
+        let parsed: ClientCommand =
+            serde_json::from_str(r#"{"type":"subscribe","symbol":"BTC-USD"}"#).unwrap();
+        assert_eq!(parsed, ClientCommand::subscribe("BTC-USD"));
+
+        let parsed: ClientCommand =
+            serde_json::from_str(r#"{"type":"subscribe","symbol":"BTC-USD","depth":50}"#).unwrap();
+        assert_eq!(parsed, ClientCommand::Subscribe(Subscription::new("BTC-USD").depth_levels(50)));
+
+        let ping: ClientCommand = serde_json::from_str(r#"{"type":"ping"}"#).unwrap();
+        assert_eq!(ping, ClientCommand::Ping);
+    }
+
     #[test]
     fn test_compact_formatter() {
         let formatter = CompactNumberFormatter;
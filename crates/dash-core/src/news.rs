@@ -0,0 +1,85 @@
+//! News and exchange announcements streamed alongside market data
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::Symbol;
+
+/// A headline pushed to clients as `WsMessage::News`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewsItem {
+    pub id: String,
+    pub headline: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// Publisher or feed name, e.g. "Exchange status"
+    pub source: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Pairs the item is tagged with; empty for market-wide news
+    #[serde(default)]
+    pub symbols: Vec<Symbol>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl NewsItem {
+    pub fn new(source: impl Into<String>, headline: impl Into<String>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            headline: headline.into(),
+            body: None,
+            source: source.into(),
+            url: None,
+            symbols: Vec::new(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    pub fn with_symbols(mut self, symbols: Vec<Symbol>) -> Self {
+        self.symbols = symbols;
+        self
+    }
+
+    /// Whether the item belongs on `symbol`'s feed; untagged news is
+    /// shown everywhere
+    pub fn concerns(&self, symbol: &Symbol) -> bool {
+        self.symbols.is_empty() || self.symbols.contains(symbol)
+    }
+
+    /// First of `keywords` found in the headline or body, ignoring case
+    pub fn matched_keyword<'a>(&self, keywords: &'a [String]) -> Option<&'a str> {
+        let text = format!("{} {}", self.headline, self.body.as_deref().unwrap_or_default())
+            .to_lowercase();
+        keywords
+            .iter()
+            .map(|k| k.trim())
+            .find(|k| !k.is_empty() && text.contains(&k.to_lowercase()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_news_item() {
+        let mut item = NewsItem::new("Exchange status", "ETH deposits paused for upgrade")
+            .with_symbols(vec![Symbol::new("ETH-USD")]);
+        assert!(item.concerns(&Symbol::new("ETH-USD")));
+        assert!(!item.concerns(&Symbol::new("BTC-USD")));
+
+        let keywords = vec!["halt".to_string(), " Upgrade ".to_string(), String::new()];
+        assert_eq!(item.matched_keyword(&keywords), Some("Upgrade"));
+        item.headline = "ETH deposits resumed".into();
+        assert_eq!(item.matched_keyword(&keywords), None);
+        item.body = Some("Trading halt lifted".into());
+        assert_eq!(item.matched_keyword(&keywords), Some("halt"));
+
+        // Untagged items parse and apply to every pair
+        let json = r#"{"id":"1","headline":"Maintenance tonight","source":"ops",
+            "timestamp":"2024-01-01T00:00:00Z"}"#;
+        let parsed: NewsItem = serde_json::from_str(json).unwrap();
+        assert!(parsed.concerns(&Symbol::new("SOL-USD")));
+    }
+}
@@ -1,6 +1,6 @@
 //! Order book types and market depth visualization
 
-use crate::{colors, Price, Quantity, Symbol};
+use crate::{colors, Price, Quantity, Symbol, TradeSide};
 use serde::{Deserialize, Serialize};
 
 // ============================================================================
@@ -99,7 +99,7 @@ impl OrderBookLevel {
 }
 
 /// Order book side (bids or asks)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum OrderSide {
     Bid,
@@ -136,6 +136,33 @@ impl OrderSide {
     }
 }
 
+/// How the resting size at a price level moved between two books
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeChange {
+    Grew,
+    Shrank,
+}
+
+impl SizeChange {
+    /// Change from `old` to `new` size, `None` when unchanged
+    pub fn between(old: f64, new: f64) -> Option<Self> {
+        if new > old {
+            Some(Self::Grew)
+        } else if new < old {
+            Some(Self::Shrank)
+        } else {
+            None
+        }
+    }
+
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            Self::Grew => "flash-up",
+            Self::Shrank => "flash-down",
+        }
+    }
+}
+
 /// Complete order book snapshot
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderBookSnapshot {
@@ -146,6 +173,9 @@ pub struct OrderBookSnapshot {
     pub asks: Vec<OrderBookLevel>,
     pub timestamp: i64,
     pub sequence: u64,
+    /// Exchange the book came from (None = single-venue feed)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub venue: Option<String>,
 }
 
 impl OrderBookSnapshot {
@@ -156,9 +186,16 @@ impl OrderBookSnapshot {
             asks: Vec::new(),
             timestamp: chrono::Utc::now().timestamp_millis(),
             sequence: 0,
+            venue: None,
         }
     }
 
+    /// Keep only the best `levels` per side
+    pub fn truncate(&mut self, levels: usize) {
+        self.bids.truncate(levels);
+        self.asks.truncate(levels);
+    }
+
     /// Best bid price (highest buy order)
     pub fn best_bid(&self) -> Option<&OrderBookLevel> {
         self.bids.first()
@@ -200,6 +237,35 @@ impl OrderBookSnapshot {
         }
     }
 
+    /// Size-weighted mid over the best `levels` per side: the best bid and
+    /// ask weighted by the opposite side's depth, so it leans toward the
+    /// side more likely to trade through. `levels = 1` is the classic
+    /// top-of-book microprice.
+    pub fn microprice(&self, levels: usize) -> Option<f64> {
+        let (bid, ask) = (self.best_bid()?.price.as_f64(), self.best_ask()?.price.as_f64());
+        let depth = |side: &[OrderBookLevel]| -> f64 {
+            side.iter().take(levels.max(1)).map(|l| l.quantity.as_f64()).sum()
+        };
+        let (bid_depth, ask_depth) = (depth(&self.bids), depth(&self.asks));
+        let total = bid_depth + ask_depth;
+        if total <= 0.0 {
+            return self.mid_price();
+        }
+        Some((bid * ask_depth + ask * bid_depth) / total)
+    }
+
+    /// Average of the bid and ask VWAPs over the best `levels` per side
+    pub fn depth_weighted_mid(&self, levels: usize) -> Option<f64> {
+        let vwap = |side: &[OrderBookLevel]| {
+            let (value, quantity) = side
+                .iter()
+                .take(levels.max(1))
+                .fold((0.0, 0.0), |(v, q), l| (v + l.value(), q + l.quantity.as_f64()));
+            (quantity > 0.0).then(|| value / quantity)
+        };
+        Some((vwap(&self.bids)? + vwap(&self.asks)?) / 2.0)
+    }
+
     /// Total bid depth (sum of all bid quantities)
     pub fn total_bid_depth(&self) -> f64 {
         self.bids.iter().map(|l| l.quantity.as_f64()).sum()
@@ -256,6 +322,123 @@ impl OrderBookSnapshot {
     pub fn aggregate_with<A: DepthAggregator>(&self, aggregator: &A) -> (Vec<AggregatedLevel>, Vec<AggregatedLevel>) {
         (aggregator.aggregate(&self.bids), aggregator.aggregate(&self.asks))
     }
+
+    /// Simulate a market order of `quantity` (base units) against the book
+    pub fn market_impact(&self, side: TradeSide, quantity: f64) -> Option<MarketImpact> {
+        self.walk_book(side, ImpactBudget::Quantity(quantity))
+    }
+
+    /// Simulate a market order spending `value` (quote units) against the book
+    pub fn quantity_for_value(&self, side: TradeSide, value: f64) -> Option<MarketImpact> {
+        self.walk_book(side, ImpactBudget::Value(value))
+    }
+
+    /// Walk the opposing side level by level until the budget is exhausted
+    fn walk_book(&self, side: TradeSide, budget: ImpactBudget) -> Option<MarketImpact> {
+        // Buys lift asks, sells hit bids
+        let levels = match side {
+            TradeSide::Buy => &self.asks,
+            TradeSide::Sell => &self.bids,
+        };
+        let best_price = levels.first()?.price.as_f64();
+
+        let mut remaining = match budget {
+            ImpactBudget::Quantity(q) | ImpactBudget::Value(q) => q,
+        };
+        if remaining <= 0.0 {
+            return None;
+        }
+
+        let mut filled = 0.0;
+        let mut cost = 0.0;
+        let mut levels_consumed = 0;
+        let mut worst_price = best_price;
+
+        for level in levels {
+            if remaining <= 0.0 {
+                break;
+            }
+            let price = level.price.as_f64();
+            let available = level.quantity.as_f64();
+            let take = match budget {
+                ImpactBudget::Quantity(_) => available.min(remaining),
+                ImpactBudget::Value(_) if price > 0.0 => available.min(remaining / price),
+                ImpactBudget::Value(_) => available,
+            };
+            if take <= 0.0 {
+                continue;
+            }
+
+            filled += take;
+            cost += take * price;
+            remaining -= match budget {
+                ImpactBudget::Quantity(_) => take,
+                ImpactBudget::Value(_) => take * price,
+            };
+            levels_consumed += 1;
+            worst_price = price;
+        }
+
+        if filled <= 0.0 {
+            return None;
+        }
+
+        let avg_price = cost / filled;
+        let slippage_bps = if best_price > 0.0 {
+            match side {
+                TradeSide::Buy => (avg_price - best_price) / best_price * 10_000.0,
+                TradeSide::Sell => (best_price - avg_price) / best_price * 10_000.0,
+            }
+        } else {
+            0.0
+        };
+
+        Some(MarketImpact {
+            side,
+            filled_quantity: filled,
+            total_value: cost,
+            avg_price,
+            best_price,
+            worst_price,
+            slippage_bps,
+            levels_consumed,
+            // Tolerate float dust left over from the last partial level
+            fully_filled: remaining <= 1e-9,
+        })
+    }
+}
+
+// ============================================================================
+// LIQUIDITY METRICS
+// ============================================================================
+
+/// Amount a simulated market order is allowed to consume
+#[derive(Debug, Clone, Copy)]
+enum ImpactBudget {
+    /// Base asset quantity
+    Quantity(f64),
+    /// Quote asset notional
+    Value(f64),
+}
+
+/// Result of walking the book with a simulated market order
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarketImpact {
+    pub side: TradeSide,
+    pub filled_quantity: f64,
+    /// Quote value spent (buy) or received (sell)
+    pub total_value: f64,
+    /// Volume-weighted average fill price
+    pub avg_price: f64,
+    /// Top-of-book price on the consumed side
+    pub best_price: f64,
+    /// Price of the deepest level touched
+    pub worst_price: f64,
+    /// Adverse move of the average fill versus the best price
+    pub slippage_bps: f64,
+    pub levels_consumed: usize,
+    /// False when the visible book ran out before the order was filled
+    pub fully_filled: bool,
 }
 
 // ============================================================================
@@ -272,6 +455,52 @@ pub struct DepthPoint {
     pub cumulative_value: f64,
 }
 
+/// What the depth chart's y-axis measures
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DepthAxis {
+    /// Cumulative base quantity
+    #[default]
+    Quantity,
+    /// Cumulative quote value (price × size), comparable across price levels
+    Notional,
+}
+
+impl DepthAxis {
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::Quantity => Self::Notional,
+            Self::Notional => Self::Quantity,
+        }
+    }
+
+    /// Cumulative total of `point` on this axis
+    pub fn value(self, point: &DepthPoint) -> f64 {
+        match self {
+            Self::Quantity => point.cumulative_quantity,
+            Self::Notional => point.cumulative_value,
+        }
+    }
+}
+
+/// How the depth chart scales its two sides
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DepthScale {
+    /// One y-axis for both sides, comparing magnitude
+    #[default]
+    Shared,
+    /// Each side as a percentage of its own total, comparing shape
+    PerSide,
+}
+
+impl DepthScale {
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::Shared => Self::PerSide,
+            Self::PerSide => Self::Shared,
+        }
+    }
+}
+
 /// Aggregated market depth for visualization
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MarketDepth {
@@ -321,6 +550,13 @@ impl MarketDepth {
         }
     }
 
+    /// Keep the `levels` points nearest the mid per side (cumulative
+    /// totals of the kept points are unchanged)
+    pub fn truncate(&mut self, levels: usize) {
+        self.bid_depth.truncate(levels);
+        self.ask_depth.truncate(levels);
+    }
+
     /// Price range for depth chart
     pub fn price_range(&self) -> Option<(f64, f64)> {
         let bid_prices: Vec<f64> = self.bid_depth.iter().map(|p| p.price).collect();
@@ -344,6 +580,18 @@ impl MarketDepth {
         bid_max.max(ask_max)
     }
 
+    /// Maximum cumulative total on `axis`
+    pub fn max_on(&self, axis: DepthAxis) -> f64 {
+        let (bid_max, ask_max) = self.side_totals(axis);
+        bid_max.max(ask_max)
+    }
+
+    /// Bid and ask side totals on `axis`
+    pub fn side_totals(&self, axis: DepthAxis) -> (f64, f64) {
+        let total = |side: &[DepthPoint]| side.last().map_or(0.0, |p| axis.value(p));
+        (total(&self.bid_depth), total(&self.ask_depth))
+    }
+
     /// Mid price from depth
     pub fn mid_price(&self) -> Option<f64> {
         let best_bid = self.bid_depth.first().map(|p| p.price);
@@ -377,6 +625,39 @@ impl OrderBookDelta {
     }
 }
 
+impl OrderBookSnapshot {
+    /// Apply an incremental level update, keeping both sides sorted.
+    /// Returns false (and leaves the book untouched) for stale sequences.
+    pub fn apply_delta(&mut self, delta: &OrderBookDelta) -> bool {
+        if delta.sequence <= self.sequence && self.sequence != 0 {
+            return false;
+        }
+
+        let price = delta.price.as_f64();
+        let (levels, descending) = match delta.side {
+            OrderSide::Bid => (&mut self.bids, true),
+            OrderSide::Ask => (&mut self.asks, false),
+        };
+
+        let position = levels.binary_search_by(|level| {
+            let ord = level.price.as_f64().total_cmp(&price);
+            if descending { ord.reverse() } else { ord }
+        });
+
+        match (position, delta.is_removal()) {
+            (Ok(i), true) => {
+                levels.remove(i);
+            }
+            (Ok(i), false) => levels[i].quantity = delta.quantity,
+            (Err(i), false) => levels.insert(i, OrderBookLevel::new(price, delta.quantity.as_f64(), 1)),
+            (Err(_), true) => {}
+        }
+
+        self.sequence = delta.sequence;
+        true
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -396,6 +677,13 @@ mod tests {
         book
     }
 
+    #[test]
+    fn test_size_change() {
+        assert_eq!(SizeChange::between(1.0, 1.5), Some(SizeChange::Grew));
+        assert_eq!(SizeChange::between(1.5, 0.2), Some(SizeChange::Shrank));
+        assert_eq!(SizeChange::between(0.7, 0.7), None);
+    }
+
     #[test]
     fn test_spread() {
         let book = sample_orderbook();
@@ -408,6 +696,20 @@ mod tests {
         assert_eq!(book.mid_price(), Some(50005.0));
     }
 
+    #[test]
+    fn test_microprice() {
+        let book = sample_orderbook();
+        // More size on the bid pulls the microprice above the mid
+        assert!((book.microprice(1).unwrap() - 90010.0 / 1.8).abs() < 1e-6);
+        assert!((book.microprice(3).unwrap() - 425045.0 / 8.5).abs() < 1e-6);
+        assert_eq!(book.microprice(0), book.microprice(1));
+
+        let expected = (224950.0 / 4.5 + 50023.0) / 2.0;
+        assert!((book.depth_weighted_mid(3).unwrap() - expected).abs() < 1e-6);
+        assert_eq!(book.depth_weighted_mid(1), book.mid_price());
+        assert_eq!(OrderBookSnapshot::new(Symbol::new("BTC-USD")).microprice(5), None);
+    }
+
     #[test]
     fn test_imbalance() {
         let book = sample_orderbook();
@@ -415,6 +717,33 @@ mod tests {
         assert!(imb > 0.0); // More bids than asks (4.5 vs 4.0)
     }
 
+    #[test]
+    fn test_apply_delta() {
+        let mut book = sample_orderbook();
+        book.sequence = 10;
+        let delta = |side, price, quantity, sequence| OrderBookDelta {
+            symbol: Symbol::new("BTC-USD"),
+            side,
+            price: Price::new(price),
+            quantity: Quantity::new(quantity),
+            sequence,
+        };
+
+        // Insert between levels, update in place, remove
+        assert!(book.apply_delta(&delta(OrderSide::Bid, 49995.0, 3.0, 11)));
+        assert!(book.apply_delta(&delta(OrderSide::Ask, 50020.0, 5.0, 12)));
+        assert!(book.apply_delta(&delta(OrderSide::Ask, 50010.0, 0.0, 13)));
+
+        let bids: Vec<f64> = book.bids.iter().map(|l| l.price.as_f64()).collect();
+        assert_eq!(bids, vec![50000.0, 49995.0, 49990.0, 49980.0]);
+        assert_eq!(book.best_ask().unwrap().quantity.as_f64(), 5.0);
+        assert_eq!(book.sequence, 13);
+
+        // Stale sequence is ignored
+        assert!(!book.apply_delta(&delta(OrderSide::Bid, 49000.0, 1.0, 12)));
+        assert_eq!(book.bids.len(), 4);
+    }
+
     #[test]
     fn test_market_depth() {
         let book = sample_orderbook();
@@ -427,6 +756,48 @@ mod tests {
         assert_eq!(depth.bid_depth[0].cumulative_quantity, 1.0);
         // Last bid cumulative should be 4.5
         assert_eq!(depth.bid_depth[2].cumulative_quantity, 4.5);
+
+        assert_eq!(depth.max_on(DepthAxis::Quantity), 4.5);
+        // Bids hold more value too: 50,000 + 2 × 49,990 + 1.5 × 49,980
+        assert_eq!(depth.max_on(DepthAxis::Notional), 224_950.0);
+        assert_eq!(depth.side_totals(DepthAxis::Quantity), (4.5, 4.0));
+    }
+
+    #[test]
+    fn test_market_impact_buy() {
+        let book = sample_orderbook();
+        let impact = book.market_impact(TradeSide::Buy, 1.5).unwrap();
+
+        // 0.8 @ 50010 + 0.7 @ 50020
+        assert_eq!(impact.levels_consumed, 2);
+        assert!((impact.filled_quantity - 1.5).abs() < 1e-9);
+        assert!((impact.total_value - 75_022.0).abs() < 1e-6);
+        assert_eq!(impact.worst_price, 50020.0);
+        assert!(impact.slippage_bps > 0.0);
+        assert!(impact.fully_filled);
+    }
+
+    #[test]
+    fn test_market_impact_exhausts_book() {
+        let book = sample_orderbook();
+        let impact = book.market_impact(TradeSide::Sell, 10.0).unwrap();
+
+        assert_eq!(impact.levels_consumed, 3);
+        assert!((impact.filled_quantity - 4.5).abs() < 1e-9);
+        assert!(!impact.fully_filled);
+        assert!(book.market_impact(TradeSide::Sell, 0.0).is_none());
+    }
+
+    #[test]
+    fn test_quantity_for_value() {
+        let book = sample_orderbook();
+        let impact = book.quantity_for_value(TradeSide::Sell, 50_000.0).unwrap();
+
+        // Whole best bid level is worth exactly 50_000
+        assert_eq!(impact.levels_consumed, 1);
+        assert!((impact.filled_quantity - 1.0).abs() < 1e-9);
+        assert_eq!(impact.slippage_bps, 0.0);
+        assert!(impact.fully_filled);
     }
 
     #[test]
@@ -0,0 +1,707 @@
+//! Paper trading
+//!
+//! Simulated orders filled against the live trade feed. An entry may carry a
+//! bracket: once it fills, a take-profit limit and a stop-loss stop for the
+//! same size are placed on the other side as a one-cancels-other pair.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Msg, Symbol, TradeSide};
+
+/// Sizes below this count as zero
+const QTY_EPSILON: f64 = 1e-9;
+
+/// Filled and cancelled orders kept for display
+pub const MAX_CLOSED_ORDERS: usize = 200;
+
+pub type OrderId = u64;
+
+/// How an order executes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderType {
+    /// At the last price, straight away
+    #[default]
+    Market,
+    /// At the limit price or better
+    Limit,
+    /// At the market once a trade reaches the stop price
+    Stop,
+}
+
+impl OrderType {
+    pub fn all() -> &'static [Self] {
+        &[Self::Market, Self::Limit, Self::Stop]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Market => "Market",
+            Self::Limit => "Limit",
+            Self::Stop => "Stop",
+        }
+    }
+
+    pub fn msg(&self) -> Msg {
+        match self {
+            Self::Market => Msg::MarketOrder,
+            Self::Limit => Msg::LimitOrder,
+            Self::Stop => Msg::StopOrder,
+        }
+    }
+}
+
+/// Leverage the paper account trades at. Margin is not simulated; it sets
+/// where an isolated position would be liquidated and scales return on margin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum Leverage {
+    #[default]
+    X1,
+    X2,
+    X5,
+    X10,
+    X20,
+}
+
+impl Leverage {
+    pub fn all() -> &'static [Self] {
+        &[Self::X1, Self::X2, Self::X5, Self::X10, Self::X20]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::X1 => "1x",
+            Self::X2 => "2x",
+            Self::X5 => "5x",
+            Self::X10 => "10x",
+            Self::X20 => "20x",
+        }
+    }
+
+    pub fn factor(&self) -> f64 {
+        match self {
+            Self::X1 => 1.0,
+            Self::X2 => 2.0,
+            Self::X5 => 5.0,
+            Self::X10 => 10.0,
+            Self::X20 => 20.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OrderStatus {
+    Working,
+    Filled,
+    Cancelled,
+}
+
+/// Take-profit and stop-loss prices attached to an entry
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Bracket {
+    pub take_profit: Option<f64>,
+    pub stop_loss: Option<f64>,
+}
+
+impl Bracket {
+    pub fn is_empty(&self) -> bool {
+        self.take_profit.is_none() && self.stop_loss.is_none()
+    }
+}
+
+/// Which side of a bracket an order is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BracketLeg {
+    TakeProfit,
+    StopLoss,
+}
+
+impl BracketLeg {
+    pub fn msg(&self) -> Msg {
+        match self {
+            Self::TakeProfit => Msg::TakeProfit,
+            Self::StopLoss => Msg::StopLoss,
+        }
+    }
+}
+
+/// A simulated order
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PaperOrder {
+    pub id: OrderId,
+    pub symbol: Symbol,
+    pub side: TradeSide,
+    pub order_type: OrderType,
+    pub quantity: f64,
+    /// Limit or stop price
+    pub price: Option<f64>,
+    pub status: OrderStatus,
+    /// Exit levels placed once this order fills
+    pub bracket: Bracket,
+    /// Entry whose position this order closes, and which leg it is
+    pub parent: Option<(OrderId, BracketLeg)>,
+    /// For bracket exits, what the entry stood to lose at its stop-loss
+    pub risk: Option<f64>,
+    /// Unix millis
+    pub created: i64,
+}
+
+impl PaperOrder {
+    pub fn is_working(&self) -> bool {
+        self.status == OrderStatus::Working
+    }
+
+    /// Whether a trade at `price` executes the order
+    fn triggers(&self, price: f64) -> bool {
+        let Some(level) = self.price else {
+            return true;
+        };
+        match (self.order_type, self.side) {
+            (OrderType::Market, _) => true,
+            (OrderType::Limit, TradeSide::Buy) | (OrderType::Stop, TradeSide::Sell) => {
+                price <= level
+            }
+            (OrderType::Limit, TradeSide::Sell) | (OrderType::Stop, TradeSide::Buy) => {
+                price >= level
+            }
+        }
+    }
+}
+
+/// Why an order was refused
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TicketError {
+    /// Size is not a positive number
+    InvalidQuantity,
+    /// Limit and stop orders need a positive price
+    PriceRequired,
+    /// Nothing has traded yet to price a market order against
+    NoMarketPrice,
+    /// A buy stop must sit above the market, a sell stop below it
+    StopSide,
+    /// The take-profit must be beyond the entry in the trade's favor
+    TakeProfitSide,
+    /// The stop-loss must be beyond the entry against the trade
+    StopLossSide,
+    /// No working order with that id
+    NotWorking,
+    /// Viewers cannot trade
+    ReadOnly,
+}
+
+impl TicketError {
+    pub fn msg(&self) -> Msg {
+        match self {
+            Self::InvalidQuantity => Msg::InvalidQuantity,
+            Self::PriceRequired => Msg::PriceRequired,
+            Self::NoMarketPrice => Msg::NoMarketPrice,
+            Self::StopSide => Msg::StopSide,
+            Self::TakeProfitSide => Msg::TakeProfitSide,
+            Self::StopLossSide => Msg::StopLossSide,
+            Self::NotWorking => Msg::NotWorking,
+            Self::ReadOnly => Msg::ReadOnly,
+        }
+    }
+}
+
+/// An order as entered on the ticket
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderTicket {
+    pub symbol: Symbol,
+    pub side: TradeSide,
+    pub order_type: OrderType,
+    pub quantity: f64,
+    /// Limit or stop price; ignored for market orders
+    pub price: Option<f64>,
+    pub bracket: Bracket,
+}
+
+impl OrderTicket {
+    pub fn new(symbol: Symbol, side: TradeSide, quantity: f64) -> Self {
+        Self {
+            symbol,
+            side,
+            order_type: OrderType::Market,
+            quantity,
+            price: None,
+            bracket: Bracket::default(),
+        }
+    }
+
+    /// Expected fill price given the last traded price
+    pub fn entry_price(&self, last: Option<f64>) -> Option<f64> {
+        match self.order_type {
+            OrderType::Market => last,
+            OrderType::Limit | OrderType::Stop => self.price,
+        }
+    }
+
+    /// Check the ticket against the last traded price; returns the expected
+    /// fill price
+    pub fn validate(&self, last: Option<f64>) -> Result<f64, TicketError> {
+        if !(self.quantity.is_finite() && self.quantity > 0.0) {
+            return Err(TicketError::InvalidQuantity);
+        }
+        let valid_price = |p: Option<f64>| p.filter(|p| p.is_finite() && *p > 0.0);
+        let entry = match self.order_type {
+            OrderType::Market => valid_price(last).ok_or(TicketError::NoMarketPrice)?,
+            OrderType::Limit | OrderType::Stop => {
+                valid_price(self.price).ok_or(TicketError::PriceRequired)?
+            }
+        };
+        if self.order_type == OrderType::Stop
+            && let Some(last) = last
+            && direction(self.side) * (entry - last) <= 0.0
+        {
+            return Err(TicketError::StopSide);
+        }
+        let dir = direction(self.side);
+        if let Some(tp) = self.bracket.take_profit
+            && !(tp.is_finite() && dir * (tp - entry) > 0.0)
+        {
+            return Err(TicketError::TakeProfitSide);
+        }
+        if let Some(sl) = self.bracket.stop_loss
+            && !(sl.is_finite() && sl > 0.0 && dir * (entry - sl) > 0.0)
+        {
+            return Err(TicketError::StopLossSide);
+        }
+        Ok(entry)
+    }
+
+    /// Profit at the take-profit and loss at the stop-loss if the entry fills
+    /// where expected
+    pub fn projection(&self, last: Option<f64>) -> Option<BracketProjection> {
+        let entry = self.entry_price(last)?;
+        let pnl = |exit: f64| direction(self.side) * (exit - entry) * self.quantity;
+        Some(BracketProjection {
+            entry,
+            take_profit: self.bracket.take_profit.map(pnl),
+            stop_loss: self.bracket.stop_loss.map(pnl),
+        })
+    }
+}
+
+/// Projected bracket outcome in quote currency
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BracketProjection {
+    pub entry: f64,
+    /// PnL if the take-profit fills
+    pub take_profit: Option<f64>,
+    /// PnL if the stop-loss fills (negative for a loss)
+    pub stop_loss: Option<f64>,
+}
+
+impl BracketProjection {
+    /// Reward over risk, when both legs are set
+    pub fn reward_risk(&self) -> Option<f64> {
+        let (reward, risk) = (self.take_profit?, -self.stop_loss?);
+        (risk > 0.0).then(|| reward / risk)
+    }
+}
+
+/// +1 for buys, -1 for sells
+fn direction(side: TradeSide) -> f64 {
+    if side.is_buy() { 1.0 } else { -1.0 }
+}
+
+/// Net position in one symbol
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Position {
+    /// Positive long, negative short
+    pub quantity: f64,
+    /// Average entry of the open size
+    pub avg_price: f64,
+    /// Closed PnL so far
+    pub realized_pnl: f64,
+}
+
+impl Position {
+    pub fn is_flat(&self) -> bool {
+        self.quantity.abs() < QTY_EPSILON
+    }
+
+    /// Open PnL at `price`
+    pub fn unrealized_pnl(&self, price: f64) -> f64 {
+        if self.is_flat() { 0.0 } else { (price - self.avg_price) * self.quantity }
+    }
+
+    /// Open PnL at `price` as a percentage of the margin posted at `leverage`
+    pub fn return_on_margin(&self, price: f64, leverage: Leverage) -> f64 {
+        let notional = self.avg_price * self.quantity.abs();
+        if notional > 0.0 {
+            self.unrealized_pnl(price) / notional * leverage.factor() * 100.0
+        } else {
+            0.0
+        }
+    }
+
+    /// Price at which the margin posted at `leverage` is lost; `None` when
+    /// flat or for an unlevered long
+    pub fn liquidation_price(&self, leverage: Leverage) -> Option<f64> {
+        if self.is_flat() {
+            return None;
+        }
+        let price = self.avg_price * (1.0 - self.quantity.signum() / leverage.factor());
+        (price > 0.0).then_some(price)
+    }
+
+    /// Apply a fill; returns the PnL it realized
+    fn apply(&mut self, side: TradeSide, quantity: f64, price: f64) -> f64 {
+        let signed = direction(side) * quantity;
+        if self.is_flat() || self.quantity.signum() == signed.signum() {
+            let size = self.quantity.abs() + quantity;
+            self.avg_price = (self.avg_price * self.quantity.abs() + price * quantity) / size;
+            self.quantity += signed;
+            return 0.0;
+        }
+        let closed = quantity.min(self.quantity.abs());
+        let realized = closed * (price - self.avg_price) * self.quantity.signum();
+        self.realized_pnl += realized;
+        self.quantity += signed;
+        if self.is_flat() {
+            self.quantity = 0.0;
+            self.avg_price = 0.0;
+        } else if self.quantity.signum() == signed.signum() {
+            // Flipped: the rest opens a new position at the fill price
+            self.avg_price = price;
+        }
+        realized
+    }
+}
+
+/// One simulated execution
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fill {
+    pub order_id: OrderId,
+    pub symbol: Symbol,
+    pub side: TradeSide,
+    pub quantity: f64,
+    pub price: f64,
+    /// Unix millis
+    pub timestamp: i64,
+    /// PnL closed by this fill
+    pub realized_pnl: f64,
+    /// Initial risk of the trade a bracket exit closed, for R multiples
+    #[serde(default)]
+    pub risk: Option<f64>,
+}
+
+/// Orders, positions and fills of the paper account
+#[derive(Debug, Clone, Default)]
+pub struct PaperEngine {
+    /// Oldest first
+    orders: Vec<PaperOrder>,
+    positions: HashMap<Symbol, Position>,
+    /// Oldest first
+    fills: Vec<Fill>,
+    next_id: OrderId,
+    pub leverage: Leverage,
+}
+
+impl PaperEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Place `ticket`; a market order fills at `last` straight away
+    pub fn submit(
+        &mut self,
+        ticket: &OrderTicket,
+        last: Option<f64>,
+        now_ms: i64,
+    ) -> Result<OrderId, TicketError> {
+        let entry = ticket.validate(last)?;
+        let id = self.push_order(PaperOrder {
+            id: 0,
+            symbol: ticket.symbol.clone(),
+            side: ticket.side,
+            order_type: ticket.order_type,
+            quantity: ticket.quantity,
+            price: (ticket.order_type != OrderType::Market).then_some(entry),
+            status: OrderStatus::Working,
+            bracket: ticket.bracket,
+            parent: None,
+            risk: None,
+            created: now_ms,
+        });
+        if ticket.order_type == OrderType::Market {
+            self.fill(id, entry, now_ms);
+        }
+        Ok(id)
+    }
+
+    /// Execute working orders a trade at `price` reaches; returns the fills.
+    /// Bracket legs placed by these fills wait for the next trade.
+    pub fn on_trade(&mut self, symbol: &Symbol, price: f64, now_ms: i64) -> Vec<Fill> {
+        let triggered: Vec<OrderId> = self
+            .orders
+            .iter()
+            .filter(|o| o.is_working() && &o.symbol == symbol && o.triggers(price))
+            .map(|o| o.id)
+            .collect();
+        let mut fills = Vec::new();
+        for id in triggered {
+            // An earlier fill may have cancelled this one (OCO)
+            if self.order(id).is_some_and(PaperOrder::is_working) {
+                fills.extend(self.fill(id, price, now_ms));
+            }
+        }
+        fills
+    }
+
+    /// Cancel a working order; its bracket is never placed
+    pub fn cancel(&mut self, id: OrderId) -> Result<(), TicketError> {
+        let order = self.working_mut(id)?;
+        order.status = OrderStatus::Cancelled;
+        self.prune();
+        Ok(())
+    }
+
+    /// Move a working limit, stop or bracket leg to `price`
+    pub fn move_order(&mut self, id: OrderId, price: f64) -> Result<(), TicketError> {
+        if !(price.is_finite() && price > 0.0) {
+            return Err(TicketError::PriceRequired);
+        }
+        let order = self.working_mut(id)?;
+        if order.order_type == OrderType::Market {
+            return Err(TicketError::NotWorking);
+        }
+        order.price = Some(price);
+        Ok(())
+    }
+
+    pub fn order(&self, id: OrderId) -> Option<&PaperOrder> {
+        self.orders.iter().find(|o| o.id == id)
+    }
+
+    /// All kept orders, oldest first
+    pub fn orders(&self) -> &[PaperOrder] {
+        &self.orders
+    }
+
+    /// Working orders for `symbol`, oldest first
+    pub fn working(&self, symbol: &Symbol) -> Vec<&PaperOrder> {
+        self.orders.iter().filter(|o| o.is_working() && &o.symbol == symbol).collect()
+    }
+
+    pub fn position(&self, symbol: &Symbol) -> Position {
+        self.positions.get(symbol).copied().unwrap_or_default()
+    }
+
+    /// Gross notional of the open positions, marked at `mark` where it has a
+    /// price for the symbol and at cost otherwise
+    pub fn exposure(&self, mark: impl Fn(&Symbol) -> Option<f64>) -> f64 {
+        self.positions
+            .iter()
+            .filter(|(_, p)| !p.is_flat())
+            .map(|(symbol, p)| p.quantity.abs() * mark(symbol).unwrap_or(p.avg_price))
+            .sum()
+    }
+
+    /// Every fill, oldest first
+    pub fn fills(&self) -> &[Fill] {
+        &self.fills
+    }
+
+    fn push_order(&mut self, mut order: PaperOrder) -> OrderId {
+        self.next_id += 1;
+        order.id = self.next_id;
+        self.orders.push(order);
+        self.next_id
+    }
+
+    fn working_mut(&mut self, id: OrderId) -> Result<&mut PaperOrder, TicketError> {
+        self.orders
+            .iter_mut()
+            .find(|o| o.id == id && o.is_working())
+            .ok_or(TicketError::NotWorking)
+    }
+
+    /// Fill order `id` at `price`, place its bracket or cancel its OCO sibling
+    fn fill(&mut self, id: OrderId, price: f64, now_ms: i64) -> Option<Fill> {
+        let order = self.orders.iter_mut().find(|o| o.id == id)?;
+        order.status = OrderStatus::Filled;
+        let order = order.clone();
+
+        let realized = self
+            .positions
+            .entry(order.symbol.clone())
+            .or_default()
+            .apply(order.side, order.quantity, price);
+        let fill = Fill {
+            order_id: id,
+            symbol: order.symbol.clone(),
+            side: order.side,
+            quantity: order.quantity,
+            price,
+            timestamp: now_ms,
+            realized_pnl: realized,
+            risk: order.risk,
+        };
+        self.fills.push(fill.clone());
+
+        match order.parent {
+            // One leg out, the other is cancelled
+            Some((parent, _)) => {
+                for sibling in &mut self.orders {
+                    if sibling.is_working() && sibling.parent.is_some_and(|(p, _)| p == parent) {
+                        sibling.status = OrderStatus::Cancelled;
+                    }
+                }
+            }
+            None => {
+                let exit = match order.side {
+                    TradeSide::Buy => TradeSide::Sell,
+                    TradeSide::Sell => TradeSide::Buy,
+                };
+                let risk =
+                    order.bracket.stop_loss.map(|stop| (price - stop).abs() * order.quantity);
+                let legs = [
+                    (BracketLeg::TakeProfit, OrderType::Limit, order.bracket.take_profit),
+                    (BracketLeg::StopLoss, OrderType::Stop, order.bracket.stop_loss),
+                ];
+                for (leg, order_type, level) in legs {
+                    let Some(level) = level else { continue };
+                    self.push_order(PaperOrder {
+                        id: 0,
+                        symbol: order.symbol.clone(),
+                        side: exit,
+                        order_type,
+                        quantity: order.quantity,
+                        price: Some(level),
+                        status: OrderStatus::Working,
+                        bracket: Bracket::default(),
+                        parent: Some((id, leg)),
+                        risk,
+                        created: now_ms,
+                    });
+                }
+            }
+        }
+        self.prune();
+        Some(fill)
+    }
+
+    /// Drop the oldest finished orders beyond `MAX_CLOSED_ORDERS`
+    fn prune(&mut self) {
+        let closed = self.orders.iter().filter(|o| !o.is_working()).count();
+        let mut excess = closed.saturating_sub(MAX_CLOSED_ORDERS);
+        self.orders.retain(|o| {
+            let drop = excess > 0 && !o.is_working();
+            excess -= usize::from(drop);
+            !drop
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn btc() -> Symbol {
+        Symbol::new("BTC-USD")
+    }
+
+    fn bracket_buy(take_profit: f64, stop_loss: f64) -> OrderTicket {
+        let mut ticket = OrderTicket::new(btc(), TradeSide::Buy, 2.0);
+        ticket.bracket = Bracket { take_profit: Some(take_profit), stop_loss: Some(stop_loss) };
+        ticket
+    }
+
+    #[test]
+    fn test_ticket_validation() {
+        let ticket = bracket_buy(110.0, 95.0);
+        assert_eq!(ticket.validate(None), Err(TicketError::NoMarketPrice));
+        assert_eq!(ticket.validate(Some(100.0)), Ok(100.0));
+        assert_eq!(bracket_buy(99.0, 95.0).validate(Some(100.0)), Err(TicketError::TakeProfitSide));
+        assert_eq!(bracket_buy(110.0, 101.0).validate(Some(100.0)), Err(TicketError::StopLossSide));
+
+        let mut stop = OrderTicket::new(btc(), TradeSide::Buy, 1.0);
+        stop.order_type = OrderType::Stop;
+        assert_eq!(stop.validate(Some(100.0)), Err(TicketError::PriceRequired));
+        stop.price = Some(99.0);
+        assert_eq!(stop.validate(Some(100.0)), Err(TicketError::StopSide));
+        stop.quantity = 0.0;
+        assert_eq!(stop.validate(Some(100.0)), Err(TicketError::InvalidQuantity));
+
+        let projection = ticket.projection(Some(100.0)).unwrap();
+        assert_eq!((projection.take_profit, projection.stop_loss), (Some(20.0), Some(-10.0)));
+        assert_eq!(projection.reward_risk(), Some(2.0));
+    }
+
+    #[test]
+    fn test_bracket_oco() {
+        let mut engine = PaperEngine::new();
+        let entry = engine.submit(&bracket_buy(110.0, 95.0), Some(100.0), 0).unwrap();
+        assert_eq!(engine.position(&btc()).quantity, 2.0);
+        assert_eq!(engine.exposure(|_| Some(105.0)), 210.0);
+        assert_eq!(engine.exposure(|_| None), 200.0);
+        let legs = engine.working(&btc());
+        assert_eq!(legs.len(), 2);
+        assert!(legs.iter().all(|o| o.side == TradeSide::Sell && o.parent.unwrap().0 == entry));
+
+        // Other symbols and prices short of either leg fill nothing
+        assert!(engine.on_trade(&Symbol::new("ETH-USD"), 50.0, 1).is_empty());
+        assert!(engine.on_trade(&btc(), 105.0, 1).is_empty());
+
+        let fills = engine.on_trade(&btc(), 111.0, 2);
+        assert_eq!(fills.len(), 1);
+        assert_eq!(fills[0].realized_pnl, 22.0);
+        assert_eq!(fills[0].risk, Some(10.0));
+        assert!(engine.working(&btc()).is_empty());
+        assert!(engine.position(&btc()).is_flat());
+        assert_eq!(engine.position(&btc()).realized_pnl, 22.0);
+        assert_eq!(engine.fills().len(), 2);
+    }
+
+    #[test]
+    fn test_resting_entry() {
+        let mut engine = PaperEngine::new();
+        let mut ticket = OrderTicket::new(btc(), TradeSide::Sell, 1.0);
+        ticket.order_type = OrderType::Limit;
+        ticket.price = Some(120.0);
+        ticket.bracket.stop_loss = Some(130.0);
+        let id = engine.submit(&ticket, Some(100.0), 0).unwrap();
+        assert!(engine.on_trade(&btc(), 119.0, 1).is_empty());
+
+        // Dragged closer, then filled; the stop waits for the next trade
+        engine.move_order(id, 110.0).unwrap();
+        assert_eq!(engine.on_trade(&btc(), 112.0, 2).len(), 1);
+        assert_eq!(engine.position(&btc()).quantity, -1.0);
+        let stop = engine.working(&btc())[0].id;
+        engine.move_order(stop, 125.0).unwrap();
+        let fills = engine.on_trade(&btc(), 126.0, 3);
+        assert_eq!(fills[0].realized_pnl, -14.0);
+
+        assert_eq!(engine.cancel(stop), Err(TicketError::NotWorking));
+    }
+
+    #[test]
+    fn test_liquidation_price() {
+        let long = Position { quantity: 2.0, avg_price: 100.0, realized_pnl: 0.0 };
+        assert_eq!(long.liquidation_price(Leverage::X1), None);
+        assert_eq!(long.liquidation_price(Leverage::X10), Some(90.0));
+        assert_eq!(long.return_on_margin(101.0, Leverage::X10), 10.0);
+
+        let short = Position { quantity: -1.0, ..long };
+        assert_eq!(short.liquidation_price(Leverage::X1), Some(200.0));
+        assert_eq!(short.liquidation_price(Leverage::X20), Some(105.0));
+        assert_eq!(Position::default().liquidation_price(Leverage::X5), None);
+    }
+
+    #[test]
+    fn test_position_flip() {
+        let mut position = Position::default();
+        assert_eq!(position.apply(TradeSide::Buy, 1.0, 100.0), 0.0);
+        assert_eq!(position.apply(TradeSide::Buy, 1.0, 110.0), 0.0);
+        assert_eq!(position.avg_price, 105.0);
+        assert_eq!(position.unrealized_pnl(115.0), 20.0);
+        assert_eq!(position.apply(TradeSide::Sell, 3.0, 120.0), 30.0);
+        assert_eq!((position.quantity, position.avg_price), (-1.0, 120.0));
+    }
+}
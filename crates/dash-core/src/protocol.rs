@@ -0,0 +1,165 @@
+//! Wire protocol versioning
+//!
+//! Clients name the protocol they speak on the WebSocket URL
+//! (`?protocol=2.0`); clients that send nothing are treated as 1.0. Version 1
+//! frames are bare `WsMessage`s, later majors wrap them in an envelope that
+//! carries the major version (`{"v":2,"type":"trade","data":{..}}`). The
+//! server down-converts to the client's version and leaves out message types
+//! the client predates; a client that receives a major it cannot read must
+//! be updated.
+
+use serde::{Deserialize, Serialize};
+
+use crate::WsMessage;
+
+/// Query parameter carrying the client's protocol version
+pub const PROTOCOL_PARAM: &str = "protocol";
+
+/// Protocol spoken by this build
+pub const PROTOCOL_VERSION: ProtocolVersion = ProtocolVersion::new(2, 2);
+
+/// Oldest major the server still down-converts to
+pub const MIN_PROTOCOL_MAJOR: u16 = 1;
+
+/// `major.minor`; minors only add message types, majors change the framing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ProtocolVersion {
+    pub major: u16,
+    pub minor: u16,
+}
+
+impl ProtocolVersion {
+    /// Unversioned clients: bare frames
+    pub const LEGACY: Self = Self::new(1, 0);
+
+    pub const fn new(major: u16, minor: u16) -> Self {
+        Self { major, minor }
+    }
+
+    /// Parse the `protocol` query value ("2" or "2.1")
+    pub fn parse(value: &str) -> Option<Self> {
+        let (major, minor) = value.trim().split_once('.').unwrap_or((value.trim(), "0"));
+        Some(Self::new(major.parse().ok()?, minor.parse().ok()?))
+    }
+
+    /// Version the server speaks to a client that asked for `requested`.
+    /// Clients too old to serve get the current version, which they will
+    /// recognise as one they cannot read.
+    pub fn negotiate(requested: Option<Self>) -> Self {
+        match requested {
+            None => Self::LEGACY,
+            Some(v) if v.major < MIN_PROTOCOL_MAJOR => PROTOCOL_VERSION,
+            Some(v) => v.min(PROTOCOL_VERSION),
+        }
+    }
+
+    /// Can a client speaking this version read frames of major `major`?
+    pub fn reads(&self, major: u16) -> bool {
+        major <= self.major
+    }
+}
+
+impl std::fmt::Display for ProtocolVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+impl WsMessage {
+    /// First protocol version carrying this message type
+    pub fn since(&self) -> ProtocolVersion {
+        match self {
+            Self::Trade(_)
+            | Self::OrderBook(_)
+            | Self::Ticker(_)
+            | Self::Candle(_)
+            | Self::Depth(_)
+            | Self::Heartbeat { .. }
+            | Self::SourceStatus(_)
+            | Self::ConsolidatedBook(_) => ProtocolVersion::LEGACY,
+            Self::News(_) => ProtocolVersion::new(2, 1),
+            Self::AlertTriggered(_) => ProtocolVersion::new(2, 2),
+        }
+    }
+}
+
+fn legacy_major() -> u16 {
+    ProtocolVersion::LEGACY.major
+}
+
+/// A frame of any supported version; bare (v1) frames parse with `v = 1`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WsEnvelope {
+    #[serde(default = "legacy_major")]
+    pub v: u16,
+    #[serde(flatten)]
+    pub message: WsMessage,
+}
+
+/// JSON for a client speaking `version`, or `None` when the message type
+/// is newer than the client
+pub fn encode_message(
+    msg: &WsMessage,
+    version: ProtocolVersion,
+) -> Option<serde_json::Result<String>> {
+    if msg.since() > version {
+        return None;
+    }
+    Some(if version.major <= ProtocolVersion::LEGACY.major {
+        serde_json::to_string(msg)
+    } else {
+        serde_json::to_string(&WsEnvelope { v: version.major, message: msg.clone() })
+    })
+}
+
+/// Parse a frame of any version
+pub fn decode_frame(text: &str) -> serde_json::Result<WsEnvelope> {
+    serde_json::from_str(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiate_protocol() {
+        assert_eq!(ProtocolVersion::parse("2.1"), Some(ProtocolVersion::new(2, 1)));
+        assert_eq!(ProtocolVersion::parse("3"), Some(ProtocolVersion::new(3, 0)));
+        assert_eq!(ProtocolVersion::parse("x.1"), None);
+
+        assert_eq!(ProtocolVersion::negotiate(None), ProtocolVersion::LEGACY);
+        let newer = ProtocolVersion::new(PROTOCOL_VERSION.major + 1, 0);
+        assert_eq!(ProtocolVersion::negotiate(Some(newer)), PROTOCOL_VERSION);
+        let ancient = ProtocolVersion::new(0, 9);
+        assert_eq!(ProtocolVersion::negotiate(Some(ancient)), PROTOCOL_VERSION);
+        assert!(!ancient.reads(PROTOCOL_VERSION.major));
+        assert!(PROTOCOL_VERSION.reads(ProtocolVersion::LEGACY.major));
+    }
+
+    #[test]
+    fn test_envelope_versions() {
+        let msg = WsMessage::Heartbeat { timestamp: 7 };
+
+        let legacy = encode_message(&msg, ProtocolVersion::LEGACY).unwrap().unwrap();
+        assert_eq!(legacy, r#"{"type":"heartbeat","data":{"timestamp":7}}"#);
+        let current = encode_message(&msg, PROTOCOL_VERSION).unwrap().unwrap();
+        assert_eq!(current, r#"{"v":2,"type":"heartbeat","data":{"timestamp":7}}"#);
+
+        for (text, v) in [(legacy, 1), (current, 2)] {
+            let frame = decode_frame(&text).unwrap();
+            assert_eq!(frame.v, v);
+            assert!(matches!(frame.message, WsMessage::Heartbeat { timestamp: 7 }));
+        }
+
+        // News arrived in 2.1; 2.0 clients never see it
+        let news = WsMessage::News(crate::NewsItem::new("ops", "Maintenance tonight"));
+        assert!(encode_message(&news, ProtocolVersion::new(2, 0)).is_none());
+        assert!(encode_message(&news, PROTOCOL_VERSION).is_some());
+        // Server-side alerts arrived in 2.2
+        let symbol = crate::Symbol::new("BTC-USD");
+        let rule = crate::AlertRule::compile(1, symbol, "price > 1").unwrap();
+        let alert = WsMessage::AlertTriggered(crate::AlertNotice::new(&rule, Some(2.0), 0));
+        assert!(encode_message(&alert, ProtocolVersion::new(2, 1)).is_none());
+        assert!(encode_message(&alert, PROTOCOL_VERSION).is_some());
+    }
+}
@@ -0,0 +1,135 @@
+//! Risk metrics for the paper account
+//!
+//! Updated one fill at a time so the panel never rescans the journal. A
+//! trade is a fill that realized PnL or exited a bracket; entries only open
+//! risk and are not counted.
+
+use crate::Fill;
+
+/// Drawdown, per-trade Sharpe, win rate and average R over recorded fills
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RiskMetrics {
+    trades: usize,
+    wins: usize,
+    equity: f64,
+    peak: f64,
+    max_drawdown: f64,
+    /// Running mean and sum of squared deviations of trade PnL (Welford)
+    mean: f64,
+    m2: f64,
+    r_sum: f64,
+    r_trades: usize,
+}
+
+impl RiskMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Metrics over `fills`, oldest first
+    pub fn from_fills<'a>(fills: impl IntoIterator<Item = &'a Fill>) -> Self {
+        let mut metrics = Self::new();
+        fills.into_iter().for_each(|fill| metrics.record(fill));
+        metrics
+    }
+
+    pub fn record(&mut self, fill: &Fill) {
+        let pnl = fill.realized_pnl;
+        if pnl == 0.0 && fill.risk.is_none() {
+            return;
+        }
+        self.trades += 1;
+        if pnl > 0.0 {
+            self.wins += 1;
+        }
+
+        self.equity += pnl;
+        self.peak = self.peak.max(self.equity);
+        self.max_drawdown = self.max_drawdown.max(self.peak - self.equity);
+
+        let delta = pnl - self.mean;
+        self.mean += delta / self.trades as f64;
+        self.m2 += delta * (pnl - self.mean);
+
+        if let Some(risk) = fill.risk.filter(|r| *r > 0.0) {
+            self.r_sum += pnl / risk;
+            self.r_trades += 1;
+        }
+    }
+
+    /// Closing fills counted
+    pub fn trades(&self) -> usize {
+        self.trades
+    }
+
+    /// Realized PnL over the counted trades
+    pub fn equity(&self) -> f64 {
+        self.equity
+    }
+
+    /// Largest fall of equity from a previous high, in quote currency
+    pub fn max_drawdown(&self) -> f64 {
+        self.max_drawdown
+    }
+
+    /// Share of trades closed in profit, 0–1
+    pub fn win_rate(&self) -> Option<f64> {
+        (self.trades > 0).then(|| self.wins as f64 / self.trades as f64)
+    }
+
+    /// Mean trade PnL over its standard deviation; not annualized
+    pub fn sharpe(&self) -> Option<f64> {
+        if self.trades < 2 {
+            return None;
+        }
+        let std_dev = (self.m2 / (self.trades - 1) as f64).sqrt();
+        (std_dev > 0.0).then(|| self.mean / std_dev)
+    }
+
+    /// Mean PnL in multiples of initial risk, over bracket exits
+    pub fn average_r(&self) -> Option<f64> {
+        (self.r_trades > 0).then(|| self.r_sum / self.r_trades as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Symbol, TradeSide};
+
+    fn fill(realized_pnl: f64, risk: Option<f64>) -> Fill {
+        Fill {
+            order_id: 1,
+            symbol: Symbol::new("BTC-USD"),
+            side: TradeSide::Sell,
+            quantity: 1.0,
+            price: 100.0,
+            timestamp: 0,
+            realized_pnl,
+            risk,
+        }
+    }
+
+    #[test]
+    fn test_risk_metrics() {
+        let fills = [
+            fill(0.0, None),
+            fill(10.0, Some(5.0)),
+            fill(-5.0, Some(5.0)),
+            fill(-5.0, None),
+            fill(20.0, None),
+        ];
+        let metrics = RiskMetrics::from_fills(&fills);
+        assert_eq!(metrics.trades(), 4);
+        assert_eq!(metrics.equity(), 20.0);
+        assert_eq!(metrics.max_drawdown(), 10.0);
+        assert_eq!(metrics.win_rate(), Some(0.5));
+        assert_eq!(metrics.average_r(), Some(0.5));
+        // Trade PnL 10, -5, -5, 20: mean 5, sample std dev 12.25
+        let sharpe = metrics.sharpe().unwrap();
+        assert!((sharpe - 5.0 / 150.0_f64.sqrt()).abs() < 1e-9);
+
+        let empty = RiskMetrics::new();
+        assert_eq!((empty.win_rate(), empty.sharpe(), empty.average_r()), (None, None, None));
+    }
+}
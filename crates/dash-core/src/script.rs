@@ -0,0 +1,407 @@
+//! Indicator scripts
+//!
+//! Custom indicators written as an expression over each candle, e.g.
+//! `(high + low + close) / 3` or `sma(close, 20) + 2 * stdev(close, 20)`.
+//! Scripts are sandboxed by construction: they can only read the candles they
+//! are given, have no variables, loops or I/O, and every lookback is a literal,
+//! so the work per candle is known and capped when the script compiles.
+//!
+//! Alert conditions compile to scripts as well; plotting one on the chart
+//! (`ScriptIndicator`) comes with the `indicators` feature.
+
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{parse_expr, BinOp, Candle, Expr, ExprError, ExprErrorKind};
+
+/// Longest lookback a window function may take
+pub const MAX_LOOKBACK: usize = 500;
+
+/// Values a script may read to compute one candle
+const MAX_COST: usize = 20_000;
+
+/// `ema(x, n)` weights this many multiples of `n` values, seeded with the
+/// oldest; the remaining weight is under 0.1%
+const EMA_SPAN: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Open,
+    High,
+    Low,
+    Close,
+    Volume,
+}
+
+impl Field {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "open" | "o" => Self::Open,
+            "high" | "h" => Self::High,
+            "low" | "l" => Self::Low,
+            "close" | "c" => Self::Close,
+            "volume" | "v" => Self::Volume,
+            _ => return None,
+        })
+    }
+
+    fn read(&self, candle: &Candle) -> f64 {
+        match self {
+            Self::Open => candle.open.as_f64(),
+            Self::High => candle.high.as_f64(),
+            Self::Low => candle.low.as_f64(),
+            Self::Close => candle.close.as_f64(),
+            Self::Volume => candle.volume.as_f64(),
+        }
+    }
+}
+
+/// Functions of values at one candle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Math {
+    Abs,
+    Sqrt,
+    Ln,
+    Min,
+    Max,
+}
+
+/// Functions over the last `n` values of a series
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Window {
+    Sma,
+    Ema,
+    Stdev,
+    Highest,
+    Lowest,
+    Sum,
+    Rsi,
+    /// The value `n` candles back
+    Prev,
+}
+
+impl Window {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "sma" => Self::Sma,
+            "ema" => Self::Ema,
+            "stdev" => Self::Stdev,
+            "highest" => Self::Highest,
+            "lowest" => Self::Lowest,
+            "sum" => Self::Sum,
+            "rsi" => Self::Rsi,
+            "prev" => Self::Prev,
+            _ => return None,
+        })
+    }
+
+    /// Candles before the current one the function reads for period `n`
+    fn lookback(&self, n: usize) -> usize {
+        match self {
+            Self::Ema => EMA_SPAN * n - 1,
+            Self::Rsi | Self::Prev => n,
+            _ => n - 1,
+        }
+    }
+}
+
+/// A compiled script tree
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Num(f64),
+    Field(Field),
+    Neg(Box<Node>),
+    Not(Box<Node>),
+    Binary(BinOp, Box<Node>, Box<Node>),
+    Math(Math, Vec<Node>),
+    Window(Window, Box<Node>, usize),
+}
+
+impl Node {
+    /// (candles looked back, values read) for one evaluation
+    fn needs(&self) -> (usize, usize) {
+        match self {
+            Self::Num(_) | Self::Field(_) => (0, 1),
+            Self::Neg(inner) | Self::Not(inner) => inner.needs(),
+            Self::Binary(_, a, b) => {
+                let ((la, ca), (lb, cb)) = (a.needs(), b.needs());
+                (la.max(lb), ca + cb)
+            }
+            Self::Math(_, args) => args.iter().map(Node::needs).fold((0, 0), |(l, c), (al, ac)| {
+                (l.max(al), c + ac)
+            }),
+            Self::Window(window, inner, n) => {
+                let (lookback, cost) = inner.needs();
+                let span = match window {
+                    Window::Prev => 1,
+                    _ => window.lookback(*n) + 1,
+                };
+                (lookback + window.lookback(*n), cost.saturating_mul(span))
+            }
+        }
+    }
+
+    /// Value at `candles[i]`; NaN where there is not enough history
+    fn eval(&self, candles: &[Candle], i: usize) -> f64 {
+        match self {
+            Self::Num(value) => *value,
+            Self::Field(field) => field.read(&candles[i]),
+            Self::Neg(inner) => -inner.eval(candles, i),
+            Self::Not(inner) => BinOp::Eq.apply(inner.eval(candles, i), 0.0),
+            Self::Binary(op, a, b) => op.apply(a.eval(candles, i), b.eval(candles, i)),
+            Self::Math(math, args) => {
+                let arg = |k: usize| args[k].eval(candles, i);
+                match math {
+                    Math::Abs => arg(0).abs(),
+                    Math::Sqrt => arg(0).sqrt(),
+                    Math::Ln => arg(0).ln(),
+                    Math::Min => arg(0).min(arg(1)),
+                    Math::Max => arg(0).max(arg(1)),
+                }
+            }
+            Self::Window(window, inner, n) => {
+                let lookback = window.lookback(*n);
+                if i < lookback {
+                    return f64::NAN;
+                }
+                if *window == Window::Prev {
+                    return inner.eval(candles, i - n);
+                }
+                let values = (i - lookback..=i).map(|j| inner.eval(candles, j));
+                window_value(*window, values, *n)
+            }
+        }
+    }
+}
+
+/// `window` over `values`, oldest first
+fn window_value(window: Window, values: impl Iterator<Item = f64>, n: usize) -> f64 {
+    match window {
+        Window::Sma => values.sum::<f64>() / n as f64,
+        Window::Sum => values.sum(),
+        Window::Highest => values.fold(f64::NEG_INFINITY, f64::max),
+        Window::Lowest => values.fold(f64::INFINITY, f64::min),
+        Window::Stdev => {
+            let values: Vec<f64> = values.collect();
+            let mean = values.iter().sum::<f64>() / n as f64;
+            (values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n as f64).sqrt()
+        }
+        Window::Ema => {
+            let alpha = 2.0 / (n as f64 + 1.0);
+            let mut values = values;
+            let seed = values.next().unwrap_or(f64::NAN);
+            values.fold(seed, |ema, v| ema + alpha * (v - ema))
+        }
+        // Simple-average RSI over the last n changes
+        Window::Rsi => {
+            let values: Vec<f64> = values.collect();
+            let (gain, loss) = values.windows(2).fold((0.0, 0.0), |(gain, loss), pair| {
+                let change = pair[1] - pair[0];
+                (gain + change.max(0.0), loss + (-change).max(0.0))
+            });
+            if loss == 0.0 {
+                if gain == 0.0 { 50.0 } else { 100.0 }
+            } else {
+                100.0 - 100.0 / (1.0 + gain / loss)
+            }
+        }
+        Window::Prev => f64::NAN,
+    }
+}
+
+/// Resolve names and check calls in a parsed script
+fn compile(expr: &Expr) -> Result<Node, ExprErrorKind> {
+    Ok(match expr {
+        Expr::Num(value) => Node::Num(*value),
+        Expr::Name(name) => {
+            Node::Field(Field::parse(name).ok_or_else(|| ExprErrorKind::UnknownName(name.clone()))?)
+        }
+        Expr::Neg(inner) => Node::Neg(Box::new(compile(inner)?)),
+        Expr::Not(inner) => Node::Not(Box::new(compile(inner)?)),
+        Expr::Binary(op, a, b) => Node::Binary(*op, Box::new(compile(a)?), Box::new(compile(b)?)),
+        Expr::Call(name, args) => {
+            let arity = |n: usize| {
+                if args.len() == n { Ok(()) } else { Err(ExprErrorKind::Arguments(name.clone())) }
+            };
+            let math = match name.as_str() {
+                "abs" => Some((Math::Abs, 1)),
+                "sqrt" => Some((Math::Sqrt, 1)),
+                "ln" => Some((Math::Ln, 1)),
+                "min" => Some((Math::Min, 2)),
+                "max" => Some((Math::Max, 2)),
+                _ => None,
+            };
+            if let Some((math, n)) = math {
+                arity(n)?;
+                return Ok(Node::Math(math, args.iter().map(compile).collect::<Result<_, _>>()?));
+            }
+            let window =
+                Window::parse(name).ok_or_else(|| ExprErrorKind::UnknownName(name.clone()))?;
+            arity(2)?;
+            let period = match args[1] {
+                Expr::Num(n) if n.fract() == 0.0 && n >= 1.0 => n as usize,
+                _ => return Err(ExprErrorKind::Period),
+            };
+            if period > MAX_LOOKBACK || window.lookback(period) > MAX_LOOKBACK {
+                return Err(ExprErrorKind::Period);
+            }
+            Node::Window(window, Box::new(compile(&args[0])?), period)
+        }
+    })
+}
+
+/// Name and source a script is saved as
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScriptSource {
+    pub name: String,
+    pub source: String,
+}
+
+/// A compiled indicator script; equal when the name and source are
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "ScriptSource", into = "ScriptSource")]
+pub struct Script {
+    name: String,
+    source: String,
+    node: Arc<Node>,
+    lookback: usize,
+}
+
+impl Script {
+    /// Compile `source`; errors carry the character offset they refer to
+    pub fn compile(name: &str, source: &str) -> Result<Self, ExprError> {
+        let expr = parse_expr(source)?;
+        Self::from_expr(name, source, &expr).map_err(|kind| ExprError::new(0, kind))
+    }
+
+    /// Compile an already parsed `expr`, e.g. part of an alert rule
+    pub(crate) fn from_expr(name: &str, source: &str, expr: &Expr) -> Result<Self, ExprErrorKind> {
+        let node = compile(expr)?;
+        let (lookback, cost) = node.needs();
+        if lookback > MAX_LOOKBACK || cost > MAX_COST {
+            return Err(ExprErrorKind::TooComplex);
+        }
+        let name = match name.trim() {
+            "" => source.trim().to_string(),
+            name => name.to_string(),
+        };
+        Ok(Self { name, source: source.to_string(), node: Arc::new(node), lookback })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Candles before the current one a value needs
+    pub fn lookback(&self) -> usize {
+        self.lookback
+    }
+
+    /// Value at the last of `candles`; `None` while warming up
+    pub fn eval_last(&self, candles: &[Candle]) -> Option<f64> {
+        let i = candles.len().checked_sub(1).filter(|i| *i >= self.lookback)?;
+        Some(self.node.eval(candles, i)).filter(|v| v.is_finite())
+    }
+}
+
+impl PartialEq for Script {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.source == other.source
+    }
+}
+
+impl Eq for Script {}
+
+impl Hash for Script {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.source.hash(state);
+    }
+}
+
+impl TryFrom<ScriptSource> for Script {
+    type Error = String;
+
+    fn try_from(saved: ScriptSource) -> Result<Self, Self::Error> {
+        Self::compile(&saved.name, &saved.source).map_err(|e| format!("{:?}", e))
+    }
+}
+
+impl From<Script> for ScriptSource {
+    fn from(script: Script) -> Self {
+        Self { name: script.name, source: script.source }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CandleInterval, Symbol};
+
+    fn candles(closes: &[f64]) -> Vec<Candle> {
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, &close)| {
+                let mut c = Candle::new(Symbol::default(), CandleInterval::M1, i as i64, close);
+                c.update(close + 2.0, 1.0);
+                c.update(close, 1.0);
+                c
+            })
+            .collect()
+    }
+
+    fn eval(source: &str, closes: &[f64]) -> Option<f64> {
+        Script::compile("", source).unwrap().eval_last(&candles(closes))
+    }
+
+    #[test]
+    fn test_script_values() {
+        let closes = [1.0, 2.0, 3.0, 4.0];
+        assert_eq!(eval("(high + low + close) / 3", &closes), Some(4.0 + 2.0 / 3.0));
+        assert_eq!(eval("sma(close, 3)", &closes), Some(3.0));
+        assert_eq!(eval("close - prev(close, 3)", &closes), Some(3.0));
+        assert_eq!(eval("highest(high, 2) - lowest(low, 4)", &closes), Some(5.0));
+        assert_eq!(eval("rsi(close, 3)", &closes), Some(100.0));
+        assert_eq!(eval("rsi(close, 2)", &[1.0, 3.0, 2.0]), Some(100.0 - 100.0 / 3.0));
+        assert_eq!(eval("stdev(close, 2)", &closes), Some(0.5));
+        assert_eq!(eval("close > 3 && volume >= 2", &closes), Some(1.0));
+        // Warming up, and division by zero
+        assert_eq!(eval("sma(close, 5)", &closes), None);
+        assert_eq!(eval("close / (close - close)", &closes), None);
+
+        let ema = eval("ema(close, 2)", &[10.0; 8]).unwrap();
+        assert!((ema - 10.0).abs() < 1e-9);
+        assert_eq!(Script::compile("", "sma(sma(close, 3), 2)").unwrap().lookback(), 3);
+    }
+
+    #[test]
+    fn test_script_errors() {
+        let kind = |source: &str| Script::compile("x", source).unwrap_err().kind;
+        assert_eq!(kind("closes"), ExprErrorKind::UnknownName("closes".into()));
+        assert_eq!(kind("sma(close)"), ExprErrorKind::Arguments("sma".into()));
+        assert_eq!(kind("sma(close, 2.5)"), ExprErrorKind::Period);
+        assert_eq!(kind("sma(close, close)"), ExprErrorKind::Period);
+        assert_eq!(kind("sma(close, 501)"), ExprErrorKind::Period);
+        assert_eq!(kind("sma(sma(sma(close, 100), 100), 100)"), ExprErrorKind::TooComplex);
+
+        let err = Script::compile(" ", "hlc").unwrap_err();
+        assert_eq!((err.name(), err.msg()), (Some("hlc"), crate::Msg::ExprUnknownName));
+        let script = Script::compile("", " close ").unwrap();
+        assert_eq!(script.name(), "close");
+    }
+
+    #[test]
+    fn test_script_serde() {
+        let script = Script::compile("avg", "sma(close, 2)").unwrap();
+        let json = serde_json::to_string(&script).unwrap();
+        assert_eq!(json, r#"{"name":"avg","source":"sma(close, 2)"}"#);
+        assert_eq!(serde_json::from_str::<Script>(&json).unwrap(), script);
+    }
+}
@@ -0,0 +1,322 @@
+//! Regional trading sessions, recurring trading hours and countdown
+//! formatting for the session clock
+//!
+//! Sessions use fixed UTC hours on weekdays; daylight-saving shifts are not
+//! applied, so the markers are approximate by up to an hour in summer.
+
+use std::fmt;
+
+use crate::Msg;
+
+const DAY_MS: i64 = 86_400_000;
+const HOUR_MS: i64 = 3_600_000;
+const MINUTE_MS: i64 = 60_000;
+
+const DAY_NAMES: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// Days of the week a range recurs on, Monday in bit 0; never empty
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Weekdays(u8);
+
+impl Weekdays {
+    pub const MON_FRI: Self = Self(0b001_1111);
+    pub const WEEKEND: Self = Self(0b110_0000);
+    pub const ALL: Self = Self(0b111_1111);
+
+    /// Whether the range recurs on `day` (days since the epoch)
+    pub fn has_day(&self, day: i64) -> bool {
+        // The epoch fell on a Thursday
+        self.0 & (1 << (day + 3).rem_euclid(7)) != 0
+    }
+
+    /// "Sat", "Mon-Fri" or a run wrapping past Sunday such as "Fri-Mon"
+    pub fn parse(text: &str) -> Option<Self> {
+        let index =
+            |name: &str| DAY_NAMES.iter().position(|d| d.eq_ignore_ascii_case(name.trim()));
+        let (first, last) = match text.split_once('-') {
+            Some((first, last)) => (index(first)?, index(last)?),
+            None => (index(text)?, index(text)?),
+        };
+        let mut mask = 0;
+        let mut day = first;
+        loop {
+            mask |= 1 << day;
+            if day == last {
+                return Some(Self(mask));
+            }
+            day = (day + 1) % 7;
+        }
+    }
+}
+
+impl fmt::Display for Weekdays {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let has = |d: usize| self.0 & (1 << (d % 7)) != 0;
+        // First day of the run (parsed days are always one run)
+        let first = (0..7).find(|d| has(*d) && !has(d + 6)).unwrap_or(0);
+        let last = (first + self.0.count_ones() as usize + 6) % 7;
+        if first == last {
+            write!(f, "{}", DAY_NAMES[first])
+        } else {
+            write!(f, "{}-{}", DAY_NAMES[first], DAY_NAMES[last])
+        }
+    }
+}
+
+/// A recurring UTC time range on some days of the week, e.g. US market hours
+/// or weekends; shaded on the chart and counted down by the session clock
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TradingHours {
+    pub days: Weekdays,
+    /// Milliseconds after UTC midnight
+    pub start_ms: i64,
+    /// Milliseconds after UTC midnight, after `start_ms`; at most a day
+    pub end_ms: i64,
+}
+
+impl TradingHours {
+    pub const fn new(days: Weekdays, start_ms: i64, end_ms: i64) -> Self {
+        Self { days, start_ms, end_ms }
+    }
+
+    /// The whole of each day in `days`
+    pub const fn all_day(days: Weekdays) -> Self {
+        Self::new(days, 0, DAY_MS)
+    }
+
+    /// Whether `now_ms` (Unix millis) falls inside the range
+    pub fn contains(&self, now_ms: i64) -> bool {
+        let day = now_ms.div_euclid(DAY_MS);
+        let offset = now_ms.rem_euclid(DAY_MS);
+        self.days.has_day(day) && (self.start_ms..self.end_ms).contains(&offset)
+    }
+
+    /// Next start strictly after `now_ms`
+    pub fn next_start(&self, now_ms: i64) -> i64 {
+        let mut day = now_ms.div_euclid(DAY_MS);
+        loop {
+            let start = day * DAY_MS + self.start_ms;
+            if start > now_ms && self.days.has_day(day) {
+                return start;
+            }
+            day += 1;
+        }
+    }
+
+    /// Starts falling in `[start_ms, end_ms)`, oldest first
+    pub fn starts_between(&self, start_ms: i64, end_ms: i64) -> Vec<i64> {
+        self.spans(start_ms, end_ms)
+            .map(|(start, _)| start)
+            .filter(|start| (start_ms..end_ms).contains(start))
+            .collect()
+    }
+
+    /// Occurrences overlapping `[start_ms, end_ms)` as `(start, end)` clipped
+    /// to it, oldest first; back-to-back days (weekends) are joined
+    pub fn spans_between(&self, start_ms: i64, end_ms: i64) -> Vec<(i64, i64)> {
+        let mut spans: Vec<(i64, i64)> = Vec::new();
+        for (start, end) in self.spans(start_ms, end_ms) {
+            let (start, end) = (start.max(start_ms), end.min(end_ms));
+            if start >= end {
+                continue;
+            }
+            match spans.last_mut() {
+                Some(last) if last.1 == start => last.1 = end,
+                _ => spans.push((start, end)),
+            }
+        }
+        spans
+    }
+
+    /// Unclipped occurrences on the days touching `[start_ms, end_ms]`
+    fn spans(&self, start_ms: i64, end_ms: i64) -> impl Iterator<Item = (i64, i64)> + '_ {
+        (start_ms.div_euclid(DAY_MS)..=end_ms.div_euclid(DAY_MS))
+            .filter(|day| self.days.has_day(*day))
+            .map(|day| (day * DAY_MS + self.start_ms, day * DAY_MS + self.end_ms))
+    }
+
+    /// "Mon-Fri 14:30-21:00", "Sat-Sun" (whole days) or "22:00-24:00"
+    /// (every day)
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut parts = text.split_whitespace();
+        let (days, times) = match (parts.next()?, parts.next(), parts.next()) {
+            (first, None, _) if first.contains(':') => (Weekdays::ALL, Some(first)),
+            (first, None, _) => (Weekdays::parse(first)?, None),
+            (first, Some(times), None) => (Weekdays::parse(first)?, Some(times)),
+            _ => return None,
+        };
+        let Some(times) = times else {
+            return Some(Self::all_day(days));
+        };
+        let (start, end) = times.split_once('-')?;
+        let (start_ms, end_ms) = (parse_time(start)?, parse_time(end)?);
+        (start_ms < end_ms && end_ms <= DAY_MS).then_some(Self::new(days, start_ms, end_ms))
+    }
+}
+
+impl fmt::Display for TradingHours {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let time = |ms: i64| format!("{:02}:{:02}", ms / HOUR_MS, ms % HOUR_MS / MINUTE_MS);
+        let times = format!("{}-{}", time(self.start_ms), time(self.end_ms));
+        match (self.days, self.start_ms == 0 && self.end_ms == DAY_MS) {
+            (days, true) => write!(f, "{}", days),
+            (Weekdays::ALL, false) => write!(f, "{}", times),
+            (days, false) => write!(f, "{} {}", days, times),
+        }
+    }
+}
+
+/// "HH:MM" as milliseconds after midnight; "24:00" is the end of the day
+fn parse_time(text: &str) -> Option<i64> {
+    let (hours, minutes) = text.split_once(':')?;
+    let (hours, minutes): (i64, i64) = (hours.parse().ok()?, minutes.parse().ok()?);
+    let ms = hours * HOUR_MS + minutes * MINUTE_MS;
+    (hours >= 0 && (0..60).contains(&minutes) && ms <= DAY_MS).then_some(ms)
+}
+
+/// Regional equity/FX session whose open is marked on the chart
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MarketSession {
+    /// Tokyo
+    Asia,
+    /// London
+    Europe,
+    /// New York
+    Us,
+}
+
+impl MarketSession {
+    pub fn all() -> &'static [Self] {
+        &[Self::Asia, Self::Europe, Self::Us]
+    }
+
+    /// Open time as milliseconds after UTC midnight
+    pub fn open_ms(&self) -> i64 {
+        match self {
+            Self::Asia => 0,
+            Self::Europe => 8 * HOUR_MS,
+            Self::Us => 14 * HOUR_MS + 30 * MINUTE_MS,
+        }
+    }
+
+    /// Close time as milliseconds after UTC midnight
+    pub fn close_ms(&self) -> i64 {
+        match self {
+            Self::Asia => 6 * HOUR_MS,
+            Self::Europe => 16 * HOUR_MS + 30 * MINUTE_MS,
+            Self::Us => 21 * HOUR_MS,
+        }
+    }
+
+    /// Short marker label drawn on the chart
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Asia => "ASIA",
+            Self::Europe => "EU",
+            Self::Us => "US",
+        }
+    }
+
+    pub fn msg(&self) -> Msg {
+        match self {
+            Self::Asia => Msg::SessionAsia,
+            Self::Europe => Msg::SessionEurope,
+            Self::Us => Msg::SessionUs,
+        }
+    }
+
+    /// Weekday trading hours
+    pub fn hours(&self) -> TradingHours {
+        TradingHours::new(Weekdays::MON_FRI, self.open_ms(), self.close_ms())
+    }
+
+    /// Whether the session is trading at `now_ms` (Unix millis)
+    pub fn is_open(&self, now_ms: i64) -> bool {
+        self.hours().contains(now_ms)
+    }
+
+    /// Next open strictly after `now_ms`
+    pub fn next_open(&self, now_ms: i64) -> i64 {
+        self.hours().next_start(now_ms)
+    }
+
+    /// Opens falling in `[start_ms, end_ms)`, oldest first
+    pub fn opens_between(&self, start_ms: i64, end_ms: i64) -> Vec<i64> {
+        self.hours().starts_between(start_ms, end_ms)
+    }
+}
+
+/// Remaining time as "mm:ss", "h:mm:ss" or "2d 04:05:06"
+pub fn format_countdown(millis: i64) -> String {
+    // Round up so the display reaches 00:00 exactly at the deadline
+    let secs = (millis.max(0) + 999) / 1000;
+    let (days, hours, minutes, seconds) = (secs / 86_400, secs / 3600 % 24, secs / 60 % 60, secs % 60);
+    if days > 0 {
+        format!("{}d {:02}:{:02}:{:02}", days, hours, minutes, seconds)
+    } else if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{:02}:{:02}", minutes, seconds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Monday 2024-01-01 00:00:00 UTC
+    const MONDAY: i64 = 1_704_067_200_000;
+
+    #[test]
+    fn test_session_hours() {
+        assert!(MarketSession::Asia.is_open(MONDAY));
+        assert!(!MarketSession::Us.is_open(MONDAY));
+        assert!(MarketSession::Us.is_open(MONDAY + 15 * HOUR_MS));
+        // Saturday
+        assert!(!MarketSession::Europe.is_open(MONDAY - 2 * DAY_MS + 9 * HOUR_MS));
+
+        assert_eq!(MarketSession::Europe.next_open(MONDAY), MONDAY + 8 * HOUR_MS);
+        // Friday evening rolls over the weekend to Monday
+        let friday_night = MONDAY + 4 * DAY_MS + 22 * HOUR_MS;
+        assert_eq!(MarketSession::Asia.next_open(friday_night), MONDAY + 7 * DAY_MS);
+    }
+
+    #[test]
+    fn test_opens_between() {
+        let opens = MarketSession::Asia.opens_between(MONDAY - 3 * DAY_MS, MONDAY + DAY_MS);
+        assert_eq!(opens, vec![MONDAY - 3 * DAY_MS, MONDAY]);
+        assert!(MarketSession::Us.opens_between(MONDAY, MONDAY + HOUR_MS).is_empty());
+    }
+
+    #[test]
+    fn test_trading_hours() {
+        let weekend = TradingHours::parse("sat-SUN").unwrap();
+        assert_eq!(weekend, TradingHours::all_day(Weekdays::WEEKEND));
+        assert!(weekend.contains(MONDAY - HOUR_MS));
+        assert!(!weekend.contains(MONDAY));
+        // Saturday and Sunday join into one span, clipped to the range
+        let spans = weekend.spans_between(MONDAY - 3 * DAY_MS, MONDAY + DAY_MS);
+        assert_eq!(spans, vec![(MONDAY - 2 * DAY_MS, MONDAY)]);
+
+        let us = TradingHours::parse("Mon-Fri 14:30-21:00").unwrap();
+        assert_eq!(us, MarketSession::Us.hours());
+        assert_eq!(us.to_string(), "Mon-Fri 14:30-21:00");
+        assert_eq!(weekend.to_string(), "Sat-Sun");
+        assert_eq!(TradingHours::parse("22:00-24:00").unwrap().to_string(), "22:00-24:00");
+        assert_eq!(Weekdays::parse("Fri-Mon").unwrap().to_string(), "Fri-Mon");
+        assert!(Weekdays::parse("Fri-Mon").unwrap().has_day(MONDAY));
+
+        for bad in ["", "Mon-Fri 21:00-14:30", "Funday", "10:00-25:00", "Mon 9:75-10:00"] {
+            assert_eq!(TradingHours::parse(bad), None, "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_format_countdown() {
+        assert_eq!(format_countdown(42_001), "00:43");
+        assert_eq!(format_countdown(0), "00:00");
+        assert_eq!(format_countdown(-5), "00:00");
+        assert_eq!(format_countdown(HOUR_MS + 61_000), "1:01:01");
+        assert_eq!(format_countdown(2 * DAY_MS + 4 * HOUR_MS + 5 * MINUTE_MS + 6_000), "2d 04:05:06");
+    }
+}
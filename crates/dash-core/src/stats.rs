@@ -0,0 +1,292 @@
+//! Incremental smoothing and rolling statistics
+//!
+//! Each primitive takes one sample at a time so it can sit behind a memo or
+//! a stream handler without re-scanning history. `replace_last` revises the
+//! newest sample in place, for values that keep changing until they close
+//! (the forming candle).
+
+use std::collections::VecDeque;
+
+// ============================================================================
+// EWMA
+// ============================================================================
+
+/// Exponentially weighted moving average
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ewma {
+    alpha: f64,
+    value: Option<f64>,
+    /// Average before the newest sample, for `replace_last`
+    previous: Option<f64>,
+}
+
+impl Ewma {
+    /// `alpha` is the weight of each new sample, clamped to (0, 1]
+    pub fn new(alpha: f64) -> Self {
+        Self {
+            alpha: alpha.clamp(f64::EPSILON, 1.0),
+            value: None,
+            previous: None,
+        }
+    }
+
+    /// Average where a sample's weight halves every `samples` updates
+    pub fn with_half_life(samples: f64) -> Self {
+        Self::new(1.0 - 0.5f64.powf(1.0 / samples.max(f64::EPSILON)))
+    }
+
+    /// Fold in a sample and return the new average; the first sample seeds it
+    pub fn update(&mut self, sample: f64) -> f64 {
+        let next = match self.value {
+            Some(value) => value + self.alpha * (sample - value),
+            None => sample,
+        };
+        self.previous = self.value.replace(next);
+        next
+    }
+
+    /// Swap the newest sample for `sample` (an update if there is none)
+    pub fn replace_last(&mut self, sample: f64) -> f64 {
+        self.value = self.previous;
+        self.update(sample)
+    }
+
+    /// Current average (`None` before the first sample)
+    pub fn value(&self) -> Option<f64> {
+        self.value
+    }
+
+    pub fn alpha(&self) -> f64 {
+        self.alpha
+    }
+
+    pub fn reset(&mut self) {
+        self.value = None;
+        self.previous = None;
+    }
+}
+
+// ============================================================================
+// ROLLING MEAN / STD
+// ============================================================================
+
+/// Mean and standard deviation over the last `window` samples
+#[derive(Debug, Clone)]
+pub struct RollingStats {
+    window: usize,
+    samples: VecDeque<f64>,
+    sum: f64,
+    sum_sq: f64,
+}
+
+impl RollingStats {
+    /// Stats over at most `window` samples (minimum 1)
+    pub fn new(window: usize) -> Self {
+        let window = window.max(1);
+        Self {
+            window,
+            samples: VecDeque::with_capacity(window),
+            sum: 0.0,
+            sum_sq: 0.0,
+        }
+    }
+
+    /// Add a sample, evicting the oldest once the window is full
+    pub fn push(&mut self, sample: f64) {
+        if self.samples.len() == self.window
+            && let Some(old) = self.samples.pop_front()
+        {
+            self.sum -= old;
+            self.sum_sq -= old * old;
+        }
+        self.samples.push_back(sample);
+        self.sum += sample;
+        self.sum_sq += sample * sample;
+    }
+
+    /// Swap the newest sample for `sample` (a push if there is none)
+    pub fn replace_last(&mut self, sample: f64) {
+        match self.samples.back_mut() {
+            Some(last) => {
+                self.sum += sample - *last;
+                self.sum_sq += sample * sample - *last * *last;
+                *last = sample;
+            }
+            None => self.push(sample),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.samples.len() == self.window
+    }
+
+    pub fn mean(&self) -> Option<f64> {
+        (!self.is_empty()).then(|| self.sum / self.len() as f64)
+    }
+
+    /// Population variance (floored at zero against rounding drift)
+    pub fn variance(&self) -> Option<f64> {
+        let mean = self.mean()?;
+        Some((self.sum_sq / self.len() as f64 - mean * mean).max(0.0))
+    }
+
+    pub fn std_dev(&self) -> Option<f64> {
+        self.variance().map(f64::sqrt)
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+        self.sum = 0.0;
+        self.sum_sq = 0.0;
+    }
+}
+
+// ============================================================================
+// MEDIAN FILTER
+// ============================================================================
+
+/// Running median over the last `window` samples; robust to the outliers
+/// that drag a mean (a single whale print, a bad tick)
+#[derive(Debug, Clone)]
+pub struct MedianFilter {
+    window: usize,
+    /// Arrival order, for eviction
+    samples: VecDeque<f64>,
+    /// Same samples kept sorted
+    sorted: Vec<f64>,
+}
+
+impl MedianFilter {
+    /// Median over at most `window` samples (minimum 1)
+    pub fn new(window: usize) -> Self {
+        let window = window.max(1);
+        Self {
+            window,
+            samples: VecDeque::with_capacity(window),
+            sorted: Vec::with_capacity(window),
+        }
+    }
+
+    /// Add a sample and return the new median; NaN samples are ignored
+    pub fn push(&mut self, sample: f64) -> Option<f64> {
+        if sample.is_nan() {
+            return self.median();
+        }
+        if self.samples.len() == self.window
+            && let Some(old) = self.samples.pop_front()
+            && let Ok(i) = self.sorted.binary_search_by(|v| v.total_cmp(&old))
+        {
+            self.sorted.remove(i);
+        }
+        self.samples.push_back(sample);
+        let i = self.sorted.partition_point(|v| v.total_cmp(&sample).is_lt());
+        self.sorted.insert(i, sample);
+        self.median()
+    }
+
+    /// Swap the newest sample for `sample` (a push if there is none)
+    pub fn replace_last(&mut self, sample: f64) -> Option<f64> {
+        if sample.is_nan() {
+            return self.median();
+        }
+        if let Some(old) = self.samples.pop_back()
+            && let Ok(i) = self.sorted.binary_search_by(|v| v.total_cmp(&old))
+        {
+            self.sorted.remove(i);
+        }
+        self.push(sample)
+    }
+
+    /// Middle sample (mean of the middle two for an even count)
+    pub fn median(&self) -> Option<f64> {
+        let n = self.sorted.len();
+        match n {
+            0 => None,
+            _ if !n.is_multiple_of(2) => Some(self.sorted[n / 2]),
+            _ => Some((self.sorted[n / 2 - 1] + self.sorted[n / 2]) / 2.0),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.samples.len() == self.window
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+        self.sorted.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ewma() {
+        let mut ewma = Ewma::new(0.5);
+        assert_eq!(ewma.value(), None);
+        assert_eq!(ewma.update(10.0), 10.0);
+        assert_eq!(ewma.update(20.0), 15.0);
+        ewma.reset();
+        assert_eq!(ewma.update(4.0), 4.0);
+
+        // Revising the newest sample leaves earlier ones in place
+        ewma.update(8.0);
+        assert_eq!(ewma.replace_last(12.0), 8.0);
+        assert_eq!(ewma.replace_last(4.0), 4.0);
+
+        assert!((Ewma::with_half_life(1.0).alpha() - 0.5).abs() < 1e-12);
+        assert_eq!(Ewma::new(7.0).alpha(), 1.0);
+    }
+
+    #[test]
+    fn test_rolling_stats() {
+        let mut stats = RollingStats::new(3);
+        assert_eq!(stats.mean(), None);
+        for x in [1.0, 2.0, 3.0, 4.0] {
+            stats.push(x);
+        }
+        assert!(stats.is_full());
+        assert_eq!(stats.mean(), Some(3.0));
+        assert!((stats.std_dev().unwrap() - (2.0f64 / 3.0).sqrt()).abs() < 1e-12);
+
+        stats.replace_last(7.0);
+        assert_eq!(stats.mean(), Some(4.0));
+        assert_eq!(stats.len(), 3);
+
+        stats.clear();
+        stats.push(5.0);
+        assert_eq!(stats.variance(), Some(0.0));
+    }
+
+    #[test]
+    fn test_median_filter() {
+        let mut filter = MedianFilter::new(3);
+        assert_eq!(filter.push(5.0), Some(5.0));
+        assert_eq!(filter.push(1.0), Some(3.0));
+        assert_eq!(filter.push(100.0), Some(5.0));
+        // 5.0 falls out of the window
+        assert_eq!(filter.push(2.0), Some(2.0));
+        assert_eq!(filter.push(f64::NAN), Some(2.0));
+        assert_eq!(filter.len(), 3);
+        // [1, 100, 2] -> [1, 100, 50]
+        assert_eq!(filter.replace_last(50.0), Some(50.0));
+        assert_eq!(filter.len(), 3);
+    }
+}
@@ -1,6 +1,6 @@
 //! Trade execution types with Strategy pattern for classification
 
-use crate::{colors, Price, Quantity, Symbol};
+use crate::{colors, MedianFilter, Price, Quantity, RingBuffer, Symbol};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -75,14 +75,169 @@ impl TradeClassifier for ValueThresholdClassifier {
     }
 }
 
+/// Classifier relative to the median value of recent prints, so "large"
+/// tracks the market instead of fixed dollar thresholds
+#[derive(Debug, Clone)]
+pub struct RelativeSizeClassifier {
+    baseline: MedianFilter,
+    /// Value / baseline at or above which a trade is a whale
+    pub whale_multiple: f64,
+    /// Value / baseline at or above which a trade is large
+    pub large_multiple: f64,
+    /// Value / baseline below which a trade is micro
+    pub micro_fraction: f64,
+    /// Used until the baseline window has filled
+    pub fallback: ValueThresholdClassifier,
+}
+
+impl RelativeSizeClassifier {
+    /// Baseline over the last `window` trades
+    pub fn new(window: usize) -> Self {
+        Self {
+            baseline: MedianFilter::new(window),
+            whale_multiple: 50.0,
+            large_multiple: 10.0,
+            micro_fraction: 0.05,
+            fallback: ValueThresholdClassifier::default(),
+        }
+    }
+
+    /// Add a trade to the baseline
+    pub fn observe(&mut self, trade: &Trade) {
+        self.baseline.push(trade.value());
+    }
+
+    /// Median trade value, once the window has filled
+    pub fn baseline(&self) -> Option<f64> {
+        self.baseline.median().filter(|_| self.baseline.is_full())
+    }
+}
+
+impl TradeClassifier for RelativeSizeClassifier {
+    fn classify(&self, trade: &Trade) -> TradeClassification {
+        let Some(baseline) = self.baseline().filter(|b| *b > 0.0) else {
+            return self.fallback.classify(trade);
+        };
+        let ratio = trade.value() / baseline;
+        if ratio >= self.whale_multiple {
+            TradeClassification::Whale
+        } else if ratio >= self.large_multiple {
+            TradeClassification::Large
+        } else if ratio < self.micro_fraction {
+            TradeClassification::MicroTrade
+        } else {
+            TradeClassification::Normal
+        }
+    }
+}
+
+// ============================================================================
+// STRATEGY PATTERN: Value Bucketing
+// ============================================================================
+
+/// Strategy trait for grading trades into value buckets, e.g. to shade the
+/// tape; bucket 0 holds the smallest prints
+pub trait ValueBucketer: Send + Sync {
+    fn bucket_count(&self) -> usize;
+
+    fn bucket(&self, trade: &Trade) -> usize;
+
+    /// Shading strength from 0.0 (smallest bucket) to 1.0 (largest)
+    fn intensity(&self, trade: &Trade) -> f64 {
+        let top = self.bucket_count().saturating_sub(1);
+        if top == 0 {
+            return 0.0;
+        }
+        self.bucket(trade).min(top) as f64 / top as f64
+    }
+}
+
+/// Buckets between fixed USD value bounds
+#[derive(Debug, Clone)]
+pub struct NotionalBuckets {
+    /// Ascending lower bounds of every bucket but the first
+    pub bounds: Vec<f64>,
+}
+
+impl Default for NotionalBuckets {
+    fn default() -> Self {
+        Self { bounds: vec![1_000.0, 10_000.0, 100_000.0, 1_000_000.0] }
+    }
+}
+
+impl ValueBucketer for NotionalBuckets {
+    fn bucket_count(&self) -> usize {
+        self.bounds.len() + 1
+    }
+
+    fn bucket(&self, trade: &Trade) -> usize {
+        let value = trade.value();
+        self.bounds.partition_point(|bound| *bound <= value)
+    }
+}
+
+/// Buckets by multiples of the median value of a set of prints, so the
+/// shading tracks the market instead of fixed dollar bounds
+#[derive(Debug, Clone)]
+pub struct RelativeBuckets {
+    baseline: Option<f64>,
+    /// Ascending lower bounds (× baseline) of every bucket but the first
+    pub multiples: Vec<f64>,
+    /// Used while there are too few prints for a baseline
+    pub fallback: NotionalBuckets,
+}
+
+impl RelativeBuckets {
+    /// Prints needed before the median is trusted
+    pub const MIN_BASELINE_TRADES: usize = 20;
+
+    /// Baseline from the median value of `trades`
+    pub fn over<'a>(trades: impl IntoIterator<Item = &'a Trade>) -> Self {
+        let values: Vec<f64> = trades.into_iter().map(Trade::value).collect();
+        let mut median = MedianFilter::new(values.len());
+        for value in values {
+            median.push(value);
+        }
+        Self {
+            baseline: median
+                .median()
+                .filter(|b| median.len() >= Self::MIN_BASELINE_TRADES && *b > 0.0),
+            multiples: vec![0.5, 2.0, 10.0, 50.0],
+            fallback: NotionalBuckets::default(),
+        }
+    }
+
+    pub fn baseline(&self) -> Option<f64> {
+        self.baseline
+    }
+}
+
+impl ValueBucketer for RelativeBuckets {
+    fn bucket_count(&self) -> usize {
+        match self.baseline {
+            Some(_) => self.multiples.len() + 1,
+            None => self.fallback.bucket_count(),
+        }
+    }
+
+    fn bucket(&self, trade: &Trade) -> usize {
+        let Some(baseline) = self.baseline else {
+            return self.fallback.bucket(trade);
+        };
+        let ratio = trade.value() / baseline;
+        self.multiples.partition_point(|multiple| *multiple <= ratio)
+    }
+}
+
 // ============================================================================
 // CORE TYPES
 // ============================================================================
 
 /// Direction of a trade
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum TradeSide {
+    #[default]
     Buy,
     Sell,
 }
@@ -132,12 +287,6 @@ impl TradeSide {
     }
 }
 
-impl Default for TradeSide {
-    fn default() -> Self {
-        Self::Buy
-    }
-}
-
 /// Individual trade execution
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Trade {
@@ -153,6 +302,9 @@ pub struct Trade {
     /// Optional taker order ID
     #[serde(skip_serializing_if = "Option::is_none")]
     pub taker_order_id: Option<String>,
+    /// Exchange the trade printed on (None = single-venue feed)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub venue: Option<String>,
 }
 
 impl Trade {
@@ -167,6 +319,7 @@ impl Trade {
             timestamp: Utc::now(),
             maker_order_id: None,
             taker_order_id: None,
+            venue: None,
         }
     }
 
@@ -215,7 +368,7 @@ impl Trade {
 }
 
 /// Aggregated trade statistics over a time window
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct TradeAggregation {
     pub symbol: Symbol,
     pub count: u64,
@@ -282,6 +435,42 @@ impl TradeAggregation {
         self.last_price = price;
     }
 
+    /// Take a previously added trade back out of the counts, volumes and
+    /// VWAP. High, low and first price are left alone; rolling windows
+    /// track those themselves.
+    pub fn remove(&mut self, trade: &Trade) {
+        let qty = trade.quantity.as_f64();
+        let value = trade.value();
+
+        self.count = self.count.saturating_sub(1);
+        self.total_volume = (self.total_volume - qty).max(0.0);
+        self.total_value = (self.total_value - value).max(0.0);
+
+        match trade.side {
+            TradeSide::Buy => {
+                self.buy_count = self.buy_count.saturating_sub(1);
+                self.buy_volume = (self.buy_volume - qty).max(0.0);
+                self.buy_value = (self.buy_value - value).max(0.0);
+            }
+            TradeSide::Sell => {
+                self.sell_count = self.sell_count.saturating_sub(1);
+                self.sell_volume = (self.sell_volume - qty).max(0.0);
+                self.sell_value = (self.sell_value - value).max(0.0);
+            }
+        }
+
+        if self.count == 0 {
+            *self = Self::new(std::mem::take(&mut self.symbol));
+        } else if self.total_volume > 0.0 {
+            self.vwap = self.total_value / self.total_volume;
+        }
+    }
+
+    /// Buy volume minus sell volume
+    pub fn delta(&self) -> f64 {
+        self.buy_volume - self.sell_volume
+    }
+
     /// Buy/sell imbalance ratio (-1 to +1)
     pub fn imbalance(&self) -> f64 {
         let total = self.buy_volume + self.sell_volume;
@@ -351,10 +540,112 @@ impl TradeBatch {
     }
 }
 
+// ============================================================================
+// Repeat-trade coalescing
+// ============================================================================
+
+/// Consecutive prints at one price and side shown as one, like an exchange
+/// aggregate trade
+#[derive(Debug, Clone)]
+pub struct TradeGroup {
+    /// First print of the run, carrying the run's summed quantity
+    pub trade: Trade,
+    /// Prints in the run
+    pub count: usize,
+    /// ID of the newest print in the run
+    pub last_id: String,
+}
+
+impl From<&Trade> for TradeGroup {
+    fn from(trade: &Trade) -> Self {
+        Self { trade: trade.clone(), count: 1, last_id: trade.id.clone() }
+    }
+}
+
+impl TradeGroup {
+    /// Whether `trade` continues this run: same price, side and venue, and
+    /// printed within `window_ms` of the run's first print
+    pub fn accepts(&self, trade: &Trade, window_ms: i64) -> bool {
+        let first = &self.trade;
+        let elapsed = trade.timestamp.timestamp_millis() - first.timestamp.timestamp_millis();
+        trade.price == first.price
+            && trade.side == first.side
+            && trade.venue == first.venue
+            && (0..=window_ms).contains(&elapsed)
+    }
+
+    fn merge(&mut self, trade: &Trade) {
+        self.trade.quantity = self.trade.quantity + trade.quantity;
+        self.count += 1;
+        self.last_id.clone_from(&trade.id);
+    }
+}
+
+/// Folds a stream of prints into runs of repeats as they arrive
+#[derive(Debug, Clone)]
+pub struct TradeCoalescer {
+    window_ms: i64,
+    groups: RingBuffer<TradeGroup>,
+}
+
+impl TradeCoalescer {
+    /// Merge repeats within `window_ms`, keeping the newest `capacity` groups
+    pub fn new(window_ms: i64, capacity: usize) -> Self {
+        Self { window_ms, groups: RingBuffer::new(capacity) }
+    }
+
+    pub fn window_ms(&self) -> i64 {
+        self.window_ms
+    }
+
+    /// Add the next print; returns whether it merged into the newest group
+    pub fn push(&mut self, trade: &Trade) -> bool {
+        if let Some(group) = self.groups.last_mut()
+            && group.accepts(trade, self.window_ms)
+        {
+            group.merge(trade);
+            return true;
+        }
+        self.groups.push(TradeGroup::from(trade));
+        false
+    }
+
+    /// Groups, oldest first
+    pub fn groups(&self) -> &RingBuffer<TradeGroup> {
+        &self.groups
+    }
+
+    pub fn clear(&mut self) {
+        self.groups.clear();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_trade_coalescer() {
+        let print = |price: f64, qty: f64, side: TradeSide, ms: i64| {
+            let mut trade = Trade::new(Symbol::default(), price, qty, side);
+            trade.timestamp = DateTime::from_timestamp_millis(ms).unwrap();
+            trade
+        };
+        let mut coalescer = TradeCoalescer::new(100, 10);
+        assert!(!coalescer.push(&print(100.0, 1.0, TradeSide::Buy, 1_000)));
+        let last = print(100.0, 0.5, TradeSide::Buy, 1_080);
+        assert!(coalescer.push(&last));
+        // Different side, then the same price past the window
+        assert!(!coalescer.push(&print(100.0, 2.0, TradeSide::Sell, 1_090)));
+        assert!(!coalescer.push(&print(100.0, 1.0, TradeSide::Sell, 1_200)));
+
+        let groups = coalescer.groups();
+        assert_eq!(groups.len(), 3);
+        assert_eq!((groups[0].count, groups[0].trade.quantity.as_f64()), (2, 1.5));
+        assert_eq!(groups[0].last_id, last.id);
+        assert_eq!(groups[1].count, 1);
+    }
+
     #[test]
     fn test_trade_value() {
         let trade = Trade::new(Symbol::new("BTC-USD"), 50000.0, 0.5, TradeSide::Buy);
@@ -372,6 +663,47 @@ mod tests {
         assert_eq!(normal.classify_with(&classifier), TradeClassification::Normal);
     }
 
+    #[test]
+    fn test_relative_size_classifier() {
+        let mut classifier = RelativeSizeClassifier::new(3);
+        let trade = |qty| Trade::new(Symbol::default(), 100.0, qty, TradeSide::Buy);
+
+        // Dollar thresholds until the baseline fills
+        assert_eq!(trade(20_000.0).classify_with(&classifier), TradeClassification::Whale);
+        for qty in [1.0, 2.0, 3.0] {
+            classifier.observe(&trade(qty));
+        }
+        assert_eq!(classifier.baseline(), Some(200.0));
+        assert_eq!(trade(100.0).classify_with(&classifier), TradeClassification::Whale);
+        assert_eq!(trade(25.0).classify_with(&classifier), TradeClassification::Large);
+        assert_eq!(trade(2.0).classify_with(&classifier), TradeClassification::Normal);
+        assert_eq!(trade(0.05).classify_with(&classifier), TradeClassification::MicroTrade);
+    }
+
+    #[test]
+    fn test_value_buckets() {
+        let trade = |qty| Trade::new(Symbol::default(), 100.0, qty, TradeSide::Buy);
+        let notional = NotionalBuckets::default();
+        assert_eq!(notional.bucket_count(), 5);
+        assert_eq!(notional.bucket(&trade(5.0)), 0);
+        assert_eq!(notional.bucket(&trade(10.0)), 1);
+        assert_eq!(notional.intensity(&trade(50_000.0)), 1.0);
+        assert_eq!(notional.intensity(&trade(1_000.0)), 0.75);
+
+        // Too few prints: dollar bounds
+        let few: Vec<_> = (0..5).map(|_| trade(1.0)).collect();
+        assert_eq!(RelativeBuckets::over(&few).baseline(), None);
+        assert_eq!(RelativeBuckets::over(&few).bucket(&trade(10.0)), 1);
+
+        let tape: Vec<_> = (0..RelativeBuckets::MIN_BASELINE_TRADES).map(|_| trade(1.0)).collect();
+        let relative = RelativeBuckets::over(&tape);
+        assert_eq!(relative.baseline(), Some(100.0));
+        assert_eq!(relative.bucket(&trade(0.1)), 0);
+        assert_eq!(relative.bucket(&trade(1.0)), 1);
+        assert_eq!(relative.bucket(&trade(20.0)), 3);
+        assert_eq!(relative.intensity(&trade(100.0)), 1.0);
+    }
+
     #[test]
     fn test_aggregation() {
         let mut agg = TradeAggregation::new(Symbol::default());
@@ -384,5 +716,11 @@ mod tests {
         assert_eq!(agg.buy_count, 2);
         assert_eq!(agg.sell_count, 1);
         assert_eq!(agg.total_volume, 4.0);
+        assert_eq!(agg.delta(), 0.0);
+
+        agg.remove(&Trade::new(Symbol::default(), 102.0, 2.0, TradeSide::Sell));
+        assert_eq!((agg.count, agg.sell_count), (2, 0));
+        assert_eq!(agg.vwap, 100.5);
+        assert_eq!(agg.delta(), 2.0);
     }
 }
\ No newline at end of file
@@ -0,0 +1,245 @@
+//! Liquidity wall detection and lifetime tracking
+
+use crate::{OrderBookLevel, OrderBookSnapshot, OrderSide};
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// STRATEGY PATTERN: Wall Detection
+// ============================================================================
+
+/// Strategy trait for flagging unusually large resting levels
+pub trait WallDetector: Send + Sync {
+    fn detect(&self, book: &OrderBookSnapshot) -> Vec<Wall>;
+}
+
+/// Level flagged as a wall in a single snapshot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Wall {
+    pub side: OrderSide,
+    pub price: f64,
+    pub quantity: f64,
+    /// Level quantity relative to neighboring liquidity
+    pub strength: f64,
+}
+
+/// Flags levels that dwarf the average of their neighbors
+#[derive(Debug, Clone)]
+pub struct NeighborRatioDetector {
+    /// Levels on each side of the candidate used as the baseline
+    pub window: usize,
+    /// Minimum quantity / neighbor average ratio to count as a wall
+    pub min_ratio: f64,
+}
+
+impl Default for NeighborRatioDetector {
+    fn default() -> Self {
+        Self {
+            window: 5,
+            min_ratio: 4.0,
+        }
+    }
+}
+
+impl NeighborRatioDetector {
+    fn detect_side(&self, levels: &[OrderBookLevel], side: OrderSide, walls: &mut Vec<Wall>) {
+        for (i, level) in levels.iter().enumerate() {
+            let quantity = level.quantity.as_f64();
+            if quantity <= 0.0 {
+                continue;
+            }
+
+            let start = i.saturating_sub(self.window);
+            let end = (i + self.window + 1).min(levels.len());
+            let (sum, count) = levels[start..end]
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| start + j != i)
+                .fold((0.0, 0usize), |(sum, count), (_, l)| (sum + l.quantity.as_f64(), count + 1));
+
+            if count == 0 || sum <= 0.0 {
+                continue;
+            }
+
+            let strength = quantity / (sum / count as f64);
+            if strength >= self.min_ratio {
+                walls.push(Wall {
+                    side,
+                    price: level.price.as_f64(),
+                    quantity,
+                    strength,
+                });
+            }
+        }
+    }
+}
+
+impl WallDetector for NeighborRatioDetector {
+    fn detect(&self, book: &OrderBookSnapshot) -> Vec<Wall> {
+        let mut walls = Vec::new();
+        self.detect_side(&book.bids, OrderSide::Bid, &mut walls);
+        self.detect_side(&book.asks, OrderSide::Ask, &mut walls);
+        walls
+    }
+}
+
+// ============================================================================
+// LIFETIME TRACKING
+// ============================================================================
+
+/// Wall observed across one or more consecutive snapshots
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrackedWall {
+    pub side: OrderSide,
+    pub price: f64,
+    /// Quantity in the latest snapshot
+    pub quantity: f64,
+    /// Largest quantity seen while the wall was alive
+    pub peak_quantity: f64,
+    pub strength: f64,
+    /// Snapshot timestamp when first flagged (ms)
+    pub first_seen: i64,
+    /// Snapshot timestamp when last flagged (ms)
+    pub last_seen: i64,
+    /// Number of snapshots the wall appeared in
+    pub snapshots: u32,
+}
+
+impl TrackedWall {
+    /// How long the wall has been resting (ms)
+    pub fn age_ms(&self) -> i64 {
+        self.last_seen - self.first_seen
+    }
+
+    /// Short age label for annotations (e.g. "45s", "3m")
+    pub fn age_label(&self) -> String {
+        let secs = self.age_ms() / 1000;
+        if secs < 60 {
+            format!("{}s", secs)
+        } else if secs < 3600 {
+            format!("{}m", secs / 60)
+        } else {
+            format!("{}h", secs / 3600)
+        }
+    }
+
+    fn matches(&self, wall: &Wall) -> bool {
+        self.side == wall.side && price_key(self.price) == price_key(wall.price)
+    }
+}
+
+/// Tracks wall lifetimes across successive order book snapshots
+#[derive(Debug, Clone, Default)]
+pub struct WallTracker {
+    walls: Vec<TrackedWall>,
+}
+
+impl WallTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run the detector on a new snapshot and update lifetimes.
+    /// Walls missing from the snapshot are dropped.
+    pub fn update<D: WallDetector>(&mut self, book: &OrderBookSnapshot, detector: &D) {
+        let detected = detector.detect(book);
+        let previous = std::mem::take(&mut self.walls);
+
+        self.walls = detected
+            .into_iter()
+            .map(|wall| match previous.iter().find(|t| t.matches(&wall)) {
+                Some(existing) => TrackedWall {
+                    quantity: wall.quantity,
+                    peak_quantity: existing.peak_quantity.max(wall.quantity),
+                    strength: wall.strength,
+                    last_seen: book.timestamp,
+                    snapshots: existing.snapshots + 1,
+                    ..existing.clone()
+                },
+                None => TrackedWall {
+                    side: wall.side,
+                    price: wall.price,
+                    quantity: wall.quantity,
+                    peak_quantity: wall.quantity,
+                    strength: wall.strength,
+                    first_seen: book.timestamp,
+                    last_seen: book.timestamp,
+                    snapshots: 1,
+                },
+            })
+            .collect();
+    }
+
+    /// Currently active walls
+    pub fn walls(&self) -> &[TrackedWall] {
+        &self.walls
+    }
+
+    /// Active wall at a given price on a side, if any
+    pub fn wall_at(&self, side: OrderSide, price: f64) -> Option<&TrackedWall> {
+        self.walls
+            .iter()
+            .find(|w| w.side == side && price_key(w.price) == price_key(price))
+    }
+
+    pub fn clear(&mut self) {
+        self.walls.clear();
+    }
+}
+
+/// Stable key for comparing float prices across snapshots
+fn price_key(price: f64) -> i64 {
+    (price * 1e8).round() as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Symbol;
+
+    fn book_with_wall(timestamp: i64, wall_qty: f64) -> OrderBookSnapshot {
+        let mut book = OrderBookSnapshot::new(Symbol::new("BTC-USD"));
+        book.bids = (0..10)
+            .map(|i| {
+                let qty = if i == 4 { wall_qty } else { 1.0 };
+                OrderBookLevel::new(50000.0 - i as f64 * 10.0, qty, 1)
+            })
+            .collect();
+        book.asks = (0..10)
+            .map(|i| OrderBookLevel::new(50010.0 + i as f64 * 10.0, 1.0, 1))
+            .collect();
+        book.timestamp = timestamp;
+        book
+    }
+
+    #[test]
+    fn test_neighbor_ratio_detector() {
+        let detector = NeighborRatioDetector::default();
+        let walls = detector.detect(&book_with_wall(0, 20.0));
+
+        assert_eq!(walls.len(), 1);
+        assert_eq!(walls[0].side, OrderSide::Bid);
+        assert_eq!(walls[0].price, 49960.0);
+        assert!(walls[0].strength >= 20.0);
+
+        assert!(detector.detect(&book_with_wall(0, 2.0)).is_empty());
+    }
+
+    #[test]
+    fn test_wall_tracker_lifetime() {
+        let detector = NeighborRatioDetector::default();
+        let mut tracker = WallTracker::new();
+
+        tracker.update(&book_with_wall(1_000, 20.0), &detector);
+        tracker.update(&book_with_wall(6_000, 30.0), &detector);
+
+        let wall = tracker.wall_at(OrderSide::Bid, 49960.0).unwrap();
+        assert_eq!(wall.age_ms(), 5_000);
+        assert_eq!(wall.age_label(), "5s");
+        assert_eq!(wall.snapshots, 2);
+        assert_eq!(wall.peak_quantity, 30.0);
+
+        // Wall pulled
+        tracker.update(&book_with_wall(7_000, 1.0), &detector);
+        assert!(tracker.walls().is_empty());
+    }
+}
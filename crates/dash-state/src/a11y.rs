@@ -0,0 +1,151 @@
+//! Accessibility state: screen-reader announcement cadence, spoken price
+//! summaries and keyboard row navigation
+
+use std::time::Duration;
+
+/// How often the live region re-announces the price
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AnnounceCadence {
+    Off,
+    Secs5,
+    #[default]
+    Secs15,
+    Secs60,
+}
+
+impl AnnounceCadence {
+    /// Announcement period (`None` = silent)
+    pub fn period(&self) -> Option<Duration> {
+        match self {
+            Self::Off => None,
+            Self::Secs5 => Some(Duration::from_secs(5)),
+            Self::Secs15 => Some(Duration::from_secs(15)),
+            Self::Secs60 => Some(Duration::from_secs(60)),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Off => "Off",
+            Self::Secs5 => "5s",
+            Self::Secs15 => "15s",
+            Self::Secs60 => "60s",
+        }
+    }
+
+    pub fn all() -> &'static [Self] {
+        &[Self::Off, Self::Secs5, Self::Secs15, Self::Secs60]
+    }
+}
+
+/// Spoken price update, e.g. "BTC-USD 64210.50, up 12.30 since last update,
+/// up 1.25% today". `previous` is the price from the last announcement.
+pub fn price_summary(symbol: &str, price: f64, previous: Option<f64>, change_percent_24h: Option<f64>) -> String {
+    let mut summary = format!("{} {:.2}", symbol, price);
+    match previous.map(|p| price - p) {
+        Some(delta) if delta.abs() >= 0.005 => {
+            summary.push_str(&format!(", {} {:.2} since last update", direction(delta), delta.abs()));
+        }
+        Some(_) => summary.push_str(", unchanged since last update"),
+        None => {}
+    }
+    if let Some(pct) = change_percent_24h {
+        summary.push_str(&format!(", {} {:.2}% today", direction(pct), pct.abs()));
+    }
+    summary
+}
+
+fn direction(delta: f64) -> &'static str {
+    if delta < 0.0 { "down" } else { "up" }
+}
+
+/// Keyboard movement within a list of rows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowMove {
+    Prev,
+    Next,
+    PageUp,
+    PageDown,
+    First,
+    Last,
+}
+
+impl RowMove {
+    /// Rows skipped by PageUp / PageDown
+    pub const PAGE: usize = 10;
+
+    /// Map a `KeyboardEvent.key` value
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "ArrowUp" | "k" => Some(Self::Prev),
+            "ArrowDown" | "j" => Some(Self::Next),
+            "PageUp" => Some(Self::PageUp),
+            "PageDown" => Some(Self::PageDown),
+            "Home" => Some(Self::First),
+            "End" => Some(Self::Last),
+            _ => None,
+        }
+    }
+
+    /// Same key on a list laid out bottom-up (first row at the bottom)
+    pub fn reversed(self) -> Self {
+        match self {
+            Self::Prev => Self::Next,
+            Self::Next => Self::Prev,
+            Self::PageUp => Self::PageDown,
+            Self::PageDown => Self::PageUp,
+            Self::First => Self::Last,
+            Self::Last => Self::First,
+        }
+    }
+
+    /// Row to focus after this move; with nothing focused yet, any move
+    /// lands on the first row (or the last, for `Last`)
+    pub fn apply(self, current: Option<usize>, len: usize) -> Option<usize> {
+        let last = len.checked_sub(1)?;
+        let Some(current) = current.map(|i| i.min(last)) else {
+            return Some(if self == Self::Last { last } else { 0 });
+        };
+        Some(match self {
+            Self::Prev => current.saturating_sub(1),
+            Self::Next => (current + 1).min(last),
+            Self::PageUp => current.saturating_sub(Self::PAGE),
+            Self::PageDown => (current + Self::PAGE).min(last),
+            Self::First => 0,
+            Self::Last => last,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_price_summary() {
+        assert_eq!(price_summary("BTC-USD", 100.0, None, None), "BTC-USD 100.00");
+        assert_eq!(
+            price_summary("BTC-USD", 98.5, Some(100.0), Some(1.25)),
+            "BTC-USD 98.50, down 1.50 since last update, up 1.25% today"
+        );
+        assert_eq!(
+            price_summary("ETH-USD", 10.0, Some(10.0), None),
+            "ETH-USD 10.00, unchanged since last update"
+        );
+    }
+
+    #[test]
+    fn test_row_moves() {
+        assert_eq!(RowMove::Next.apply(None, 5), Some(0));
+        assert_eq!(RowMove::Last.apply(None, 5), Some(4));
+        assert_eq!(RowMove::Next.apply(Some(4), 5), Some(4));
+        assert_eq!(RowMove::Prev.apply(Some(0), 5), Some(0));
+        assert_eq!(RowMove::PageDown.apply(Some(2), 30), Some(12));
+        assert_eq!(RowMove::PageUp.apply(Some(12), 30), Some(2));
+        // Focused row vanished past the end of a shrunken list
+        assert_eq!(RowMove::Prev.apply(Some(9), 3), Some(1));
+        assert_eq!(RowMove::Next.apply(None, 0), None);
+        assert_eq!(RowMove::from_key("Tab"), None);
+        assert_eq!(RowMove::from_key("ArrowUp").map(RowMove::reversed), Some(RowMove::Next));
+    }
+}
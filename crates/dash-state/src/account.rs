@@ -0,0 +1,204 @@
+//! User account state
+//!
+//! The signed-in session and the parts of the dashboard that follow a user
+//! between browsers: display settings, panel layout with chart indicators,
+//! and alert rules. Each is one JSON document on the server; the requests
+//! live in `dash-websocket`. The dashboard has no watchlist yet, so that
+//! document is only reachable through the REST API.
+
+use dash_core::{AccountError, AlertRule, Credentials, Locale, Msg, UserDocument};
+#[cfg(feature = "indicators")]
+use dash_core::IndicatorKind;
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{AppState, PanelVisibility, Theme, UiState};
+
+/// Display settings kept on the account
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UserSettings {
+    pub light_theme: bool,
+    /// BCP 47 tag; tags this build cannot show are ignored
+    pub locale: String,
+    pub compact_mode: bool,
+    pub performance_mode: bool,
+    pub imbalance_tint: bool,
+    pub book_anchored: bool,
+    pub tape_volume: bool,
+    pub book_prices: bool,
+    pub news_pair_only: bool,
+}
+
+impl UserSettings {
+    pub fn capture(ui: &UiState) -> Self {
+        Self {
+            light_theme: ui.theme == Theme::Light,
+            locale: ui.locale.tag().to_string(),
+            compact_mode: ui.compact_mode,
+            performance_mode: ui.performance_mode,
+            imbalance_tint: ui.imbalance_tint,
+            book_anchored: ui.book_anchored,
+            tape_volume: ui.tape_volume,
+            book_prices: ui.book_prices,
+            news_pair_only: ui.news_pair_only,
+        }
+    }
+
+    pub fn apply(&self, ui: &mut UiState) {
+        ui.theme = if self.light_theme { Theme::Light } else { Theme::Dark };
+        if let Some(locale) = Locale::from_tag(&self.locale) {
+            ui.locale = locale;
+        }
+        ui.compact_mode = self.compact_mode;
+        ui.performance_mode = self.performance_mode;
+        ui.imbalance_tint = self.imbalance_tint;
+        ui.book_anchored = self.book_anchored;
+        ui.tape_volume = self.tape_volume;
+        ui.book_prices = self.book_prices;
+        ui.news_pair_only = self.news_pair_only;
+    }
+}
+
+impl Default for UserSettings {
+    fn default() -> Self {
+        Self::capture(&UiState::default())
+    }
+}
+
+/// Panel layout kept on the account
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UserLayout {
+    pub panels: PanelVisibility,
+    /// Chart indicators, in the order they were added
+    #[cfg(feature = "indicators")]
+    pub indicators: Vec<IndicatorKind>,
+}
+
+/// A sign-in the account menu asks for; `dash-websocket` sends it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignInRequest {
+    pub credentials: Credentials,
+    /// Open a new account instead of signing in to one
+    pub signup: bool,
+}
+
+/// State of the account sync
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AccountSync {
+    #[default]
+    SignedOut,
+    /// Waiting for the server to answer a sign-in
+    SigningIn,
+    /// Pulling the account's documents or pushing a change
+    Syncing,
+    /// The server holds the current documents
+    Synced,
+    Failed(AccountError),
+}
+
+impl AccountSync {
+    /// Status line in the account menu
+    pub fn msg(&self) -> Option<Msg> {
+        match self {
+            Self::SignedOut | Self::SigningIn => None,
+            Self::Syncing => Some(Msg::AccountSyncing),
+            Self::Synced => Some(Msg::AccountSynced),
+            Self::Failed(err) => Some(err.msg()),
+        }
+    }
+}
+
+impl AppState {
+    /// A document as it would be stored on the account, or `None` for
+    /// documents the dashboard does not keep. Tracks what it reads.
+    pub fn user_document(&self, kind: UserDocument) -> Option<Value> {
+        let value = match kind {
+            UserDocument::Settings => {
+                self.ui.with(|ui| serde_json::to_value(UserSettings::capture(ui)))
+            }
+            UserDocument::Layout => {
+                let layout = UserLayout {
+                    panels: self.ui.with(|ui| ui.panels),
+                    #[cfg(feature = "indicators")]
+                    indicators: self.market.indicators.with(|set| set.kinds().cloned().collect()),
+                };
+                serde_json::to_value(layout)
+            }
+            UserDocument::Alerts => self.alerts.with(|book| serde_json::to_value(book.rules())),
+            UserDocument::Watchlist => return None,
+        };
+        value.ok()
+    }
+
+    /// Replace the dashboard's state with a document from the account;
+    /// false when the document does not parse
+    pub fn apply_user_document(&self, kind: UserDocument, value: Value) -> bool {
+        match kind {
+            UserDocument::Settings => {
+                let Ok(settings) = serde_json::from_value::<UserSettings>(value) else {
+                    return false;
+                };
+                self.ui.update(|ui| settings.apply(ui));
+            }
+            UserDocument::Layout => {
+                let Ok(layout) = serde_json::from_value::<UserLayout>(value) else { return false };
+                self.ui.update(|ui| ui.panels = layout.panels);
+                #[cfg(feature = "indicators")]
+                self.apply_layout_indicators(layout.indicators);
+            }
+            UserDocument::Alerts => {
+                let Ok(rules) = serde_json::from_value::<Vec<AlertRule>>(value) else {
+                    return false;
+                };
+                self.alerts.update(|book| book.restore(rules));
+            }
+            UserDocument::Watchlist => return false,
+        }
+        true
+    }
+
+    /// Add and remove chart indicators to match a stored layout
+    #[cfg(feature = "indicators")]
+    fn apply_layout_indicators(&self, indicators: Vec<IndicatorKind>) {
+        let current: Vec<IndicatorKind> =
+            self.market.indicators.with_untracked(|set| set.kinds().cloned().collect());
+        for kind in current.iter().filter(|kind| !indicators.contains(kind)) {
+            self.market.remove_indicator(kind.clone());
+        }
+        for kind in indicators.into_iter().filter(|kind| !current.contains(kind)) {
+            self.market.add_indicator(kind);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_roundtrip() {
+        let mut ui = UiState { theme: Theme::Light, locale: Locale::De, ..UiState::default() };
+        ui.book_prices = true;
+        let settings = UserSettings::capture(&ui);
+        let json = serde_json::to_string(&settings).unwrap();
+
+        let mut restored = UiState::default();
+        serde_json::from_str::<UserSettings>(&json).unwrap().apply(&mut restored);
+        assert_eq!(UserSettings::capture(&restored), settings);
+
+        // Unknown locales and missing fields leave the defaults
+        let saved: UserSettings = serde_json::from_str(r#"{"locale":"fr","tape_volume":true}"#)
+            .unwrap();
+        let mut ui = UiState::default();
+        saved.apply(&mut ui);
+        assert_eq!((ui.locale, ui.tape_volume, ui.theme), (Locale::default(), true, Theme::Dark));
+
+        let layout: UserLayout = serde_json::from_str(r#"{"panels":{"trades":false}}"#).unwrap();
+        assert!(!layout.panels.trades && layout.panels.orderbook);
+        #[cfg(feature = "indicators")]
+        assert!(layout.indicators.is_empty());
+    }
+}
@@ -0,0 +1,27 @@
+//! Admin page state
+//!
+//! The server status the admin page shows and the controls it sends; the
+//! requests live in `dash-websocket`.
+
+use dash_core::{AdminError, AdminStatus, MockScenario};
+
+/// What the admin page shows
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum AdminView {
+    /// Waiting for the first status
+    #[default]
+    Loading,
+    Ready(AdminStatus),
+    Failed(AdminError),
+}
+
+/// A control on the admin page; `dash-websocket` sends it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AdminCommand {
+    /// Feed clients from this source
+    Source(String),
+    /// Start a mock scenario, or end the running one
+    Scenario(Option<MockScenario>),
+    /// Resume or pause history recording
+    Recording(bool),
+}
@@ -0,0 +1,202 @@
+//! Rolling trade aggregation windows
+//!
+//! Each window adds trades as they print and drops them as they age out, so
+//! totals stay current without re-scanning the tape (which only keeps the
+//! last `MAX_TRADES` anyway).
+
+use std::collections::VecDeque;
+
+use dash_core::{CandleInterval, Symbol, Trade, TradeAggregation};
+
+/// Window lengths kept live
+pub const TRADE_WINDOWS: [CandleInterval; 3] =
+    [CandleInterval::M1, CandleInterval::M5, CandleInterval::M15];
+
+/// Trades within the last `span` of the newest time seen
+#[derive(Debug, Clone)]
+pub struct TradeWindow {
+    span: CandleInterval,
+    /// (sequence, trade), oldest first
+    trades: VecDeque<(u64, Trade)>,
+    /// Decreasing prices with their sequence: the front is the window high
+    highs: VecDeque<(u64, f64)>,
+    /// Increasing prices: the front is the window low
+    lows: VecDeque<(u64, f64)>,
+    next_seq: u64,
+    aggregation: TradeAggregation,
+}
+
+impl TradeWindow {
+    pub fn new(symbol: Symbol, span: CandleInterval) -> Self {
+        Self {
+            span,
+            trades: VecDeque::new(),
+            highs: VecDeque::new(),
+            lows: VecDeque::new(),
+            next_seq: 0,
+            aggregation: TradeAggregation::new(symbol),
+        }
+    }
+
+    pub fn span(&self) -> CandleInterval {
+        self.span
+    }
+
+    pub fn aggregation(&self) -> &TradeAggregation {
+        &self.aggregation
+    }
+
+    pub fn push(&mut self, trade: Trade) {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        let price = trade.price.as_f64();
+
+        while self.highs.back().is_some_and(|(_, p)| *p <= price) {
+            self.highs.pop_back();
+        }
+        self.highs.push_back((seq, price));
+        while self.lows.back().is_some_and(|(_, p)| *p >= price) {
+            self.lows.pop_back();
+        }
+        self.lows.push_back((seq, price));
+
+        self.aggregation.add(&trade);
+        let now = trade.timestamp.timestamp_millis();
+        self.trades.push_back((seq, trade));
+        self.expire(now);
+    }
+
+    /// Drop trades older than `span` before `now_ms`; true if any were
+    pub fn expire(&mut self, now_ms: i64) -> bool {
+        let cutoff = now_ms - self.span.as_millis();
+        let mut expired = false;
+        while let Some((seq, trade)) = self.trades.front()
+            && trade.timestamp.timestamp_millis() <= cutoff
+        {
+            let seq = *seq;
+            self.aggregation.remove(trade);
+            if self.highs.front().is_some_and(|(s, _)| *s == seq) {
+                self.highs.pop_front();
+            }
+            if self.lows.front().is_some_and(|(s, _)| *s == seq) {
+                self.lows.pop_front();
+            }
+            self.trades.pop_front();
+            expired = true;
+        }
+        if expired {
+            self.sync_prices();
+        }
+        expired
+    }
+
+    /// Restore the price fields `TradeAggregation::remove` leaves alone
+    fn sync_prices(&mut self) {
+        let agg = &mut self.aggregation;
+        if let (Some((_, first)), Some((_, high)), Some((_, low))) =
+            (self.trades.front(), self.highs.front(), self.lows.front())
+        {
+            agg.first_price = first.price.as_f64();
+            agg.high = *high;
+            agg.low = *low;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.trades.clear();
+        self.highs.clear();
+        self.lows.clear();
+        self.aggregation = TradeAggregation::new(self.aggregation.symbol.clone());
+    }
+}
+
+/// One window per `TRADE_WINDOWS` length
+#[derive(Debug, Clone)]
+pub struct TradeWindows {
+    windows: Vec<TradeWindow>,
+}
+
+impl TradeWindows {
+    pub fn new(symbol: Symbol) -> Self {
+        Self {
+            windows: TRADE_WINDOWS
+                .iter()
+                .map(|span| TradeWindow::new(symbol.clone(), *span))
+                .collect(),
+        }
+    }
+
+    pub fn push(&mut self, trade: &Trade) {
+        for window in &mut self.windows {
+            window.push(trade.clone());
+        }
+    }
+
+    /// True if any window dropped trades
+    pub fn expire(&mut self, now_ms: i64) -> bool {
+        let mut expired = false;
+        for window in &mut self.windows {
+            expired |= window.expire(now_ms);
+        }
+        expired
+    }
+
+    /// Aggregation over `span`, if it is one of `TRADE_WINDOWS`
+    pub fn get(&self, span: CandleInterval) -> Option<&TradeAggregation> {
+        self.windows.iter().find(|w| w.span == span).map(|w| w.aggregation())
+    }
+
+    pub fn clear(&mut self) {
+        for window in &mut self.windows {
+            window.clear();
+        }
+    }
+}
+
+impl Default for TradeWindows {
+    fn default() -> Self {
+        Self::new(Symbol::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+    use dash_core::TradeSide;
+
+    fn trade(secs: i64, price: f64, side: TradeSide) -> Trade {
+        let mut t = Trade::new(Symbol::default(), price, 1.0, side);
+        t.timestamp = DateTime::from_timestamp(secs, 0).unwrap();
+        t
+    }
+
+    #[test]
+    fn test_trade_window_expiry() {
+        let mut window = TradeWindow::new(Symbol::default(), CandleInterval::M1);
+        window.push(trade(0, 105.0, TradeSide::Buy));
+        window.push(trade(20, 100.0, TradeSide::Sell));
+        window.push(trade(40, 102.0, TradeSide::Buy));
+        assert_eq!(window.aggregation().count, 3);
+        assert_eq!((window.aggregation().high, window.aggregation().low), (105.0, 100.0));
+
+        // The 105 print ages out
+        window.push(trade(61, 101.0, TradeSide::Buy));
+        let agg = window.aggregation();
+        assert_eq!((agg.count, agg.buy_count), (3, 2));
+        assert_eq!((agg.high, agg.low, agg.first_price), (102.0, 100.0, 100.0));
+
+        window.expire(200_000);
+        assert_eq!(window.aggregation(), &TradeAggregation::new(Symbol::default()));
+    }
+
+    #[test]
+    fn test_trade_windows() {
+        let mut windows = TradeWindows::default();
+        windows.push(&trade(0, 100.0, TradeSide::Buy));
+        windows.push(&trade(120, 100.0, TradeSide::Sell));
+        assert_eq!(windows.get(CandleInterval::M1).map(|a| a.count), Some(1));
+        assert_eq!(windows.get(CandleInterval::M5).map(|a| a.delta()), Some(0.0));
+        assert!(windows.get(CandleInterval::H1).is_none());
+    }
+}
@@ -0,0 +1,396 @@
+//! Candle chart tools: overlay toggles, session markers, background shading
+//! and user drawings
+
+use dash_core::{colors, MarketSession, Msg, TradingHours, Weekdays};
+
+/// Most horizontal lines kept per chart; the oldest is dropped beyond this
+pub const MAX_PRICE_LINES: usize = 16;
+
+/// Optional layers drawn over the price pane
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChartOverlays {
+    pub volume: bool,
+    pub grid: bool,
+    /// Dashed line and axis tag at the current price
+    pub last_price: bool,
+    /// Visible-range and 24h high/low annotations
+    pub high_low: bool,
+    /// Per-candle buy − sell volume pane under the chart
+    pub delta: bool,
+    /// Running total of the delta, over the delta pane
+    pub cumulative_delta: bool,
+    /// Scheduled events from the economic calendar
+    pub events: bool,
+    /// Background shading for the ranges in `ChartShading`
+    pub shading: bool,
+}
+
+impl Default for ChartOverlays {
+    fn default() -> Self {
+        Self {
+            volume: true,
+            grid: true,
+            last_price: true,
+            high_low: true,
+            delta: false,
+            cumulative_delta: false,
+            events: true,
+            shading: true,
+        }
+    }
+}
+
+/// A toggleable layer of the candle chart
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Overlay {
+    Volume,
+    Grid,
+    LastPrice,
+    HighLow,
+    Delta,
+    CumulativeDelta,
+    Events,
+    Shading,
+}
+
+impl Overlay {
+    pub fn all() -> &'static [Overlay] {
+        &[
+            Self::Volume,
+            Self::Grid,
+            Self::LastPrice,
+            Self::HighLow,
+            Self::Delta,
+            Self::CumulativeDelta,
+            Self::Events,
+            Self::Shading,
+        ]
+    }
+
+    /// Stable key, e.g. for legend items
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Volume => "volume",
+            Self::Grid => "grid",
+            Self::LastPrice => "last-price",
+            Self::HighLow => "high-low",
+            Self::Delta => "delta",
+            Self::CumulativeDelta => "cumulative-delta",
+            Self::Events => "events",
+            Self::Shading => "shading",
+        }
+    }
+
+    pub fn msg(&self) -> Msg {
+        match self {
+            Self::Volume => Msg::Volume,
+            Self::Grid => Msg::Grid,
+            Self::LastPrice => Msg::LastPrice,
+            Self::HighLow => Msg::HighLow,
+            Self::Delta => Msg::VolumeDelta,
+            Self::CumulativeDelta => Msg::CumulativeDelta,
+            Self::Events => Msg::ScheduledEvents,
+            Self::Shading => Msg::SessionShading,
+        }
+    }
+
+    /// Legend swatch color
+    pub fn color(&self) -> &'static str {
+        match self {
+            Self::Volume => colors::NEUTRAL,
+            Self::Grid => colors::BORDER,
+            Self::LastPrice => colors::WARN,
+            Self::HighLow => colors::BULL,
+            Self::Delta => colors::BEAR,
+            Self::CumulativeDelta => colors::TEXT_PRIMARY,
+            Self::Events => colors::WARN,
+            Self::Shading => colors::TEXT_MUTED,
+        }
+    }
+}
+
+impl ChartOverlays {
+    pub fn is_shown(&self, overlay: Overlay) -> bool {
+        match overlay {
+            Overlay::Volume => self.volume,
+            Overlay::Grid => self.grid,
+            Overlay::LastPrice => self.last_price,
+            Overlay::HighLow => self.high_low,
+            Overlay::Delta => self.delta,
+            Overlay::CumulativeDelta => self.cumulative_delta,
+            Overlay::Events => self.events,
+            Overlay::Shading => self.shading,
+        }
+    }
+
+    pub fn toggle(&mut self, overlay: Overlay) {
+        let shown = match overlay {
+            Overlay::Volume => &mut self.volume,
+            Overlay::Grid => &mut self.grid,
+            Overlay::LastPrice => &mut self.last_price,
+            Overlay::HighLow => &mut self.high_low,
+            Overlay::Delta => &mut self.delta,
+            Overlay::CumulativeDelta => &mut self.cumulative_delta,
+            Overlay::Events => &mut self.events,
+            Overlay::Shading => &mut self.shading,
+        };
+        *shown = !*shown;
+    }
+}
+
+/// Which session opens are drawn as vertical lines on the chart
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionMarkers {
+    pub asia: bool,
+    pub europe: bool,
+    pub us: bool,
+}
+
+impl Default for SessionMarkers {
+    fn default() -> Self {
+        Self {
+            asia: true,
+            europe: true,
+            us: true,
+        }
+    }
+}
+
+impl SessionMarkers {
+    pub fn is_shown(&self, session: MarketSession) -> bool {
+        match session {
+            MarketSession::Asia => self.asia,
+            MarketSession::Europe => self.europe,
+            MarketSession::Us => self.us,
+        }
+    }
+
+    pub fn toggle(&mut self, session: MarketSession) {
+        let shown = match session {
+            MarketSession::Asia => &mut self.asia,
+            MarketSession::Europe => &mut self.europe,
+            MarketSession::Us => &mut self.us,
+        };
+        *shown = !*shown;
+    }
+
+    /// Sessions to mark, in `MarketSession::all` order
+    pub fn shown(&self) -> Vec<MarketSession> {
+        MarketSession::all().iter().copied().filter(|s| self.is_shown(*s)).collect()
+    }
+}
+
+/// Most ranges kept in `ChartShading`
+pub const MAX_SHADED_RANGES: usize = 8;
+
+/// A shaded time range, with the name the session clock shows for it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShadedRange {
+    pub label: Option<String>,
+    pub hours: TradingHours,
+}
+
+impl ShadedRange {
+    /// "US=Mon-Fri 14:30-21:00" or just "Sat-Sun"
+    pub fn parse(text: &str) -> Option<Self> {
+        let (label, hours) = match text.split_once('=') {
+            Some((label, hours)) => (Some(label.trim()).filter(|l| !l.is_empty()), hours),
+            None => (None, text),
+        };
+        Some(Self { label: label.map(str::to_string), hours: TradingHours::parse(hours)? })
+    }
+
+    /// Label, or the hours when unnamed
+    pub fn name(&self) -> String {
+        self.label.clone().unwrap_or_else(|| self.hours.to_string())
+    }
+}
+
+/// Time ranges shaded behind the candles and listed by the session clock
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartShading {
+    ranges: Vec<ShadedRange>,
+}
+
+impl Default for ChartShading {
+    /// US market hours and weekends
+    fn default() -> Self {
+        let range = |label: &str, hours| ShadedRange { label: Some(label.to_string()), hours };
+        Self {
+            ranges: vec![
+                range("US", MarketSession::Us.hours()),
+                range("Weekend", TradingHours::all_day(Weekdays::WEEKEND)),
+            ],
+        }
+    }
+}
+
+impl ChartShading {
+    pub fn ranges(&self) -> &[ShadedRange] {
+        &self.ranges
+    }
+
+    /// Hours of every range, for the chart
+    pub fn hours(&self) -> Vec<TradingHours> {
+        self.ranges.iter().map(|r| r.hours).collect()
+    }
+
+    /// Replace the ranges from comma-separated text; entries that do not
+    /// parse are dropped, so the settings field shows what was kept
+    pub fn set_text(&mut self, text: &str) {
+        self.ranges = text
+            .split(',')
+            .filter_map(ShadedRange::parse)
+            .take(MAX_SHADED_RANGES)
+            .collect();
+    }
+
+    /// Ranges as the user edits them
+    pub fn text(&self) -> String {
+        self.ranges
+            .iter()
+            .map(|r| match &r.label {
+                Some(label) => format!("{}={}", label, r.hours),
+                None => r.hours.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Active drawing tool; clicks on the chart go to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DrawingTool {
+    #[default]
+    None,
+    /// Horizontal line at the clicked price
+    PriceLine,
+    /// Baseline chart reference at the clicked price (one shot)
+    BaselineLevel,
+    /// Drag across the chart to read the price, time and volume spanned;
+    /// draws nothing that is kept
+    Measure,
+}
+
+/// Drawings placed on the candle chart
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChartDrawings {
+    pub tool: DrawingTool,
+    pub price_lines: Vec<f64>,
+    /// Custom baseline chart reference; `None` measures from the session open
+    pub baseline: Option<f64>,
+}
+
+impl ChartDrawings {
+    /// Select a tool; choosing the active one again drops back to the cursor
+    pub fn toggle_tool(&mut self, tool: DrawingTool) {
+        self.tool = if self.tool == tool { DrawingTool::None } else { tool };
+    }
+
+    /// Apply the active tool at a clicked price; returns whether anything was drawn
+    pub fn click(&mut self, price: f64) -> bool {
+        match self.tool {
+            DrawingTool::None => false,
+            DrawingTool::PriceLine if price.is_finite() => {
+                if self.price_lines.len() == MAX_PRICE_LINES {
+                    self.price_lines.remove(0);
+                }
+                self.price_lines.push(price);
+                true
+            }
+            DrawingTool::BaselineLevel if price.is_finite() => {
+                self.baseline = Some(price);
+                self.tool = DrawingTool::None;
+                true
+            }
+            DrawingTool::PriceLine | DrawingTool::BaselineLevel | DrawingTool::Measure => false,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.price_lines.is_empty() && self.baseline.is_none()
+    }
+
+    pub fn clear(&mut self) {
+        self.price_lines.clear();
+        self.baseline = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_price_line_tool() {
+        let mut drawings = ChartDrawings::default();
+        assert!(!drawings.click(100.0));
+
+        drawings.toggle_tool(DrawingTool::PriceLine);
+        for i in 0..=MAX_PRICE_LINES {
+            assert!(drawings.click(i as f64));
+        }
+        assert!(!drawings.click(f64::NAN));
+        assert_eq!(drawings.price_lines.len(), MAX_PRICE_LINES);
+        assert_eq!(drawings.price_lines[0], 1.0);
+
+        drawings.toggle_tool(DrawingTool::PriceLine);
+        assert_eq!(drawings.tool, DrawingTool::None);
+        drawings.clear();
+        assert!(drawings.price_lines.is_empty());
+
+        // Measuring leaves nothing behind
+        drawings.toggle_tool(DrawingTool::Measure);
+        assert!(!drawings.click(100.0));
+        assert!(drawings.is_empty());
+    }
+
+    #[test]
+    fn test_baseline_level_tool() {
+        let mut drawings = ChartDrawings::default();
+        drawings.toggle_tool(DrawingTool::BaselineLevel);
+        assert!(drawings.click(64_000.0));
+        assert_eq!(drawings.baseline, Some(64_000.0));
+        assert_eq!(drawings.tool, DrawingTool::None);
+        assert!(!drawings.is_empty());
+
+        drawings.clear();
+        assert!(drawings.is_empty());
+    }
+
+    #[test]
+    fn test_overlay_toggle() {
+        let mut overlays = ChartOverlays::default();
+        let price_layers = [Overlay::Volume, Overlay::Grid, Overlay::LastPrice, Overlay::HighLow];
+        assert!(price_layers.iter().all(|o| overlays.is_shown(*o)));
+        assert!(!overlays.is_shown(Overlay::Delta) && !overlays.cumulative_delta);
+        overlays.toggle(Overlay::LastPrice);
+        assert!(!overlays.last_price);
+        assert!(!overlays.is_shown(Overlay::LastPrice));
+        overlays.toggle(Overlay::LastPrice);
+        assert_eq!(overlays, ChartOverlays::default());
+    }
+
+    #[test]
+    fn test_session_markers() {
+        let mut markers = SessionMarkers::default();
+        markers.toggle(MarketSession::Europe);
+        assert!(!markers.is_shown(MarketSession::Europe));
+        assert_eq!(markers.shown(), vec![MarketSession::Asia, MarketSession::Us]);
+    }
+
+    #[test]
+    fn test_chart_shading() {
+        let mut shading = ChartShading::default();
+        assert_eq!(shading.text(), "US=Mon-Fri 14:30-21:00, Weekend=Sat-Sun");
+
+        shading.set_text(" Asia = Mon-Fri 00:00-06:00, 25:00-26:00,22:00-24:00 ");
+        assert_eq!(shading.text(), "Asia=Mon-Fri 00:00-06:00, 22:00-24:00");
+        assert_eq!(shading.ranges()[0].name(), "Asia");
+        assert_eq!(shading.ranges()[1].name(), "22:00-24:00");
+        assert_eq!(shading.hours()[0], MarketSession::Asia.hours());
+
+        shading.set_text("");
+        assert!(shading.ranges().is_empty());
+    }
+}
@@ -0,0 +1,78 @@
+//! Server-synced wall clock for countdowns
+//!
+//! The browser clock can be minutes off; candle closes happen on the
+//! server's clock, so countdowns run on local time shifted by an offset
+//! learned from server heartbeats.
+
+/// Weight of each new heartbeat in the smoothed offset
+const OFFSET_SMOOTHING: f64 = 0.2;
+
+/// Offsets further than this from the estimate replace it outright
+/// (the server or browser clock was stepped)
+const OFFSET_RESET_MS: f64 = 5_000.0;
+
+/// Local time plus the estimated server offset
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ServerClock {
+    /// Local Unix millis at the last tick
+    local_ms: i64,
+    /// Server minus local time; `None` until the first heartbeat
+    offset_ms: Option<f64>,
+}
+
+impl ServerClock {
+    /// Advance to the current local time
+    pub fn tick(&mut self, local_ms: i64) {
+        self.local_ms = local_ms;
+    }
+
+    /// Fold in a server timestamp received at `local_ms`
+    pub fn sync(&mut self, server_ms: i64, local_ms: i64) {
+        let sample = (server_ms - local_ms) as f64;
+        self.offset_ms = Some(match self.offset_ms {
+            Some(offset) if (sample - offset).abs() < OFFSET_RESET_MS => {
+                offset + OFFSET_SMOOTHING * (sample - offset)
+            }
+            _ => sample,
+        });
+        self.local_ms = local_ms;
+    }
+
+    pub fn is_synced(&self) -> bool {
+        self.offset_ms.is_some()
+    }
+
+    /// Estimated server minus local time in milliseconds
+    pub fn offset_ms(&self) -> i64 {
+        self.offset_ms.unwrap_or(0.0).round() as i64
+    }
+
+    /// Server time at the last tick (local time until synced)
+    pub fn now_ms(&self) -> i64 {
+        self.local_ms + self.offset_ms()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_server_clock_offset() {
+        let mut clock = ServerClock::default();
+        clock.tick(1_000);
+        assert!(!clock.is_synced());
+        assert_eq!(clock.now_ms(), 1_000);
+
+        clock.sync(3_000, 1_000);
+        assert_eq!(clock.offset_ms(), 2_000);
+        clock.tick(2_000);
+        assert_eq!(clock.now_ms(), 4_000);
+
+        // Jitter is smoothed, a stepped clock is taken as-is
+        clock.sync(5_100, 3_000);
+        assert_eq!(clock.offset_ms(), 2_020);
+        clock.sync(60_000, 4_000);
+        assert_eq!(clock.offset_ms(), 56_000);
+    }
+}
@@ -0,0 +1,174 @@
+//! Worker pool for expensive derived data
+//!
+//! A request is answered by a `ComputeWorker` in a Web Worker (the
+//! `dash-worker` binary of dash-app), so recomputing indicators over a
+//! whole history keeps the main thread free. Messages cross as JSON, like
+//! the wire protocol. Until the app installs a pool (and in tests and
+//! native clients) requests run inline. Indicators are the only requests
+//! so far, so the pool comes with the `indicators` feature.
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use dash_core::{IndicatorJob, IndicatorJobOutput};
+use gloo_worker::{Codec, HandlerId, Registrable, Spawnable, Worker, WorkerBridge, WorkerScope};
+use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsValue;
+
+/// Where the app serves the compute worker's JS shim
+pub const COMPUTE_WORKER_PATH: &str = "/dash-worker.js";
+
+/// Most workers a pool is started with
+pub const MAX_COMPUTE_WORKERS: usize = 4;
+
+/// Work a compute worker takes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ComputeRequest {
+    /// Indicator values over a candle history
+    Indicators(IndicatorJob),
+}
+
+/// Answer to a `ComputeRequest`, of the matching variant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ComputeResponse {
+    Indicators(IndicatorJobOutput),
+}
+
+impl ComputeRequest {
+    pub fn run(self) -> ComputeResponse {
+        match self {
+            Self::Indicators(job) => ComputeResponse::Indicators(job.run()),
+        }
+    }
+}
+
+/// Worker messages as JSON text; bincode cannot decode the fields the
+/// market data types skip when empty
+#[derive(Debug)]
+pub struct JsonCodec;
+
+impl Codec for JsonCodec {
+    fn encode<I>(input: I) -> JsValue
+    where
+        I: Serialize,
+    {
+        JsValue::from_str(&serde_json::to_string(&input).expect("compute message serializes"))
+    }
+
+    fn decode<O>(input: JsValue) -> O
+    where
+        O: for<'de> Deserialize<'de>,
+    {
+        let json = input.as_string().unwrap_or_default();
+        serde_json::from_str(&json).expect("compute message from the other side decodes")
+    }
+}
+
+/// Answers compute requests inside a Web Worker; requests are tagged with
+/// the id the pool matches the answer by
+pub struct ComputeWorker;
+
+impl ComputeWorker {
+    /// Serve requests from the pool; the worker binary's entry point
+    pub fn register() {
+        Self::registrar().encoding::<JsonCodec>().register();
+    }
+}
+
+impl Worker for ComputeWorker {
+    type Message = ();
+    type Input = (u64, ComputeRequest);
+    type Output = (u64, ComputeResponse);
+
+    fn create(_scope: &WorkerScope<Self>) -> Self {
+        Self
+    }
+
+    fn update(&mut self, _scope: &WorkerScope<Self>, _msg: Self::Message) {}
+
+    fn received(&mut self, scope: &WorkerScope<Self>, (id, request): Self::Input, who: HandlerId) {
+        scope.respond(who, (id, request.run()));
+    }
+}
+
+type Callbacks = Rc<RefCell<HashMap<u64, Box<dyn FnOnce(ComputeResponse)>>>>;
+
+/// Web Workers running `ComputeWorker`, handed requests in turn
+pub struct ComputePool {
+    workers: Vec<WorkerBridge<ComputeWorker>>,
+    next_worker: Cell<usize>,
+    next_id: Cell<u64>,
+    /// What to do with each answer still outstanding, by request id
+    callbacks: Callbacks,
+}
+
+impl ComputePool {
+    /// Start `size` workers (at least one) from the shim at `path`
+    pub fn spawn(path: &str, size: usize) -> Self {
+        let callbacks = Callbacks::default();
+        let workers = (0..size.max(1))
+            .map(|_| {
+                let callbacks = callbacks.clone();
+                ComputeWorker::spawner()
+                    .callback(move |(id, response)| {
+                        let done = callbacks.borrow_mut().remove(&id);
+                        if let Some(done) = done {
+                            done(response);
+                        }
+                    })
+                    .encoding::<JsonCodec>()
+                    .spawn(path)
+            })
+            .collect::<Vec<_>>();
+        tracing::info!(workers = workers.len(), "compute pool started");
+        Self { workers, next_worker: Cell::new(0), next_id: Cell::new(0), callbacks }
+    }
+
+    fn submit(&self, request: ComputeRequest, done: Box<dyn FnOnce(ComputeResponse)>) {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        self.callbacks.borrow_mut().insert(id, done);
+        let worker = self.next_worker.get();
+        self.next_worker.set((worker + 1) % self.workers.len());
+        self.workers[worker].send((id, request));
+    }
+}
+
+/// Where expensive derived data is computed: on the installed pool's
+/// workers, or inline without one
+#[derive(Clone, Copy)]
+pub struct Compute {
+    pool: StoredValue<Option<Rc<ComputePool>>, LocalStorage>,
+}
+
+impl Default for Compute {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Compute {
+    pub fn new() -> Self {
+        Self { pool: StoredValue::new_local(None) }
+    }
+
+    /// Send requests to `pool` from now on
+    pub fn install(&self, pool: ComputePool) {
+        self.pool.set_value(Some(Rc::new(pool)));
+    }
+
+    pub fn is_offloaded(&self) -> bool {
+        self.pool.with_value(Option::is_some)
+    }
+
+    /// Answer `request` with `done`: once a worker has, or right away
+    /// without a pool
+    pub fn run(&self, request: ComputeRequest, done: impl FnOnce(ComputeResponse) + 'static) {
+        match self.pool.try_get_value().flatten() {
+            Some(pool) => pool.submit(request, Box::new(done)),
+            None => done(request.run()),
+        }
+    }
+}
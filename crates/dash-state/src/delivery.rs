@@ -0,0 +1,107 @@
+//! Alert delivery settings
+//!
+//! Where fired alert rules go besides the dashboard's own alert toasts: a
+//! browser notification, and the server, which checks the rules itself and
+//! POSTs firings to a webhook while the dashboard is closed. Browser APIs
+//! stay in the components crate; the push lives in `dash-websocket`.
+
+use dash_core::{AlertBook, Msg, ServerAlerts, Webhook, WebhookFormat};
+use serde::{Deserialize, Serialize};
+
+/// How fired alerts are delivered
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AlertDelivery {
+    /// Raise a browser notification when a rule fires
+    pub notify: bool,
+    /// Webhook the server POSTs firings to; blank for none
+    pub webhook_url: String,
+    pub format: WebhookFormat,
+    /// Bearer token for `/api/alerts`; rules stay in the browser without one
+    pub token: String,
+}
+
+impl AlertDelivery {
+    /// Whether the rules are pushed to the server
+    pub fn server_enabled(&self) -> bool {
+        !self.token.trim().is_empty()
+    }
+
+    pub fn webhook(&self) -> Option<Webhook> {
+        let url = self.webhook_url.trim();
+        (!url.is_empty()).then(|| Webhook { url: url.to_string(), format: self.format })
+    }
+
+    /// What to push to the server, or `None` when server delivery is off
+    pub fn server_alerts(&self, book: &AlertBook) -> Option<ServerAlerts> {
+        self.server_enabled()
+            .then(|| ServerAlerts { webhook: self.webhook(), rules: book.rules().to_vec() })
+    }
+}
+
+/// State of the last push to the server
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AlertSync {
+    /// No token, so nothing is pushed
+    #[default]
+    Off,
+    Pending,
+    /// The server holds the current rules
+    Synced,
+    /// The server rejected the token
+    Unauthorized,
+    /// The server has no alerts token configured
+    Unavailable,
+    /// The server refused the webhook URL
+    InvalidWebhook,
+    /// Network error or unexpected answer
+    Failed,
+}
+
+impl AlertSync {
+    /// Status line under the server delivery settings
+    pub fn msg(&self) -> Option<Msg> {
+        match self {
+            Self::Off => None,
+            Self::Pending => Some(Msg::AlertSyncPending),
+            Self::Synced => Some(Msg::AlertSynced),
+            Self::Unauthorized => Some(Msg::AlertSyncUnauthorized),
+            Self::Unavailable => Some(Msg::AlertSyncUnavailable),
+            Self::InvalidWebhook => Some(Msg::AlertSyncInvalidWebhook),
+            Self::Failed => Some(Msg::AlertSyncFailed),
+        }
+    }
+
+    /// Whether the status is a problem the user has to fix
+    pub fn is_error(&self) -> bool {
+        !matches!(self, Self::Off | Self::Pending | Self::Synced)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dash_core::Symbol;
+
+    #[test]
+    fn test_server_alerts() {
+        let mut book = AlertBook::new();
+        book.add(Symbol::new("BTC-USD"), "price > 100000").unwrap();
+        let mut delivery = AlertDelivery::default();
+        assert!(delivery.server_alerts(&book).is_none());
+
+        delivery.token = "secret".into();
+        let pushed = delivery.server_alerts(&book).unwrap();
+        assert_eq!((pushed.webhook, pushed.rules.len()), (None, 1));
+
+        delivery.webhook_url = " https://hooks.slack.com/services/x ".into();
+        delivery.format = WebhookFormat::Slack;
+        let hook = delivery.server_alerts(&book).unwrap().webhook.unwrap();
+        assert_eq!(hook.url, "https://hooks.slack.com/services/x");
+        assert_eq!(hook.format, WebhookFormat::Slack);
+
+        // Settings saved before a field existed still load
+        let saved: AlertDelivery = serde_json::from_str(r#"{"notify":true}"#).unwrap();
+        assert!(saved.notify && !saved.server_enabled());
+    }
+}
@@ -0,0 +1,205 @@
+//! Counters behind the hidden diagnostics overlay
+//!
+//! Rates and timings are reported over the last complete one-second window,
+//! so the overlay can read them once a second without smoothing. Times are
+//! high-resolution milliseconds (`performance.now()`), supplied by the
+//! caller to keep this module free of browser APIs.
+
+use std::collections::VecDeque;
+
+/// Parse errors kept for the overlay
+pub const MAX_PARSE_ERRORS: usize = 10;
+
+/// Length of a reporting window (ms)
+pub const DIAGNOSTICS_WINDOW_MS: f64 = 1_000.0;
+
+/// Events per second
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct RateCounter {
+    start: f64,
+    count: u64,
+    /// Over the last complete window
+    rate: f64,
+}
+
+impl RateCounter {
+    pub fn new(now: f64) -> Self {
+        Self { start: now, count: 0, rate: 0.0 }
+    }
+
+    pub fn record(&mut self, now: f64) {
+        self.roll(now);
+        self.count += 1;
+    }
+
+    /// Close the window once it is long enough; a quiet one averages to zero
+    fn roll(&mut self, now: f64) {
+        let elapsed = now - self.start;
+        if elapsed < DIAGNOSTICS_WINDOW_MS {
+            return;
+        }
+        self.rate = self.count as f64 * 1_000.0 / elapsed;
+        self.count = 0;
+        self.start = now;
+    }
+
+    pub fn per_sec(&mut self, now: f64) -> f64 {
+        self.roll(now);
+        self.rate
+    }
+}
+
+/// Update rates of named signals, in the order they were first seen
+#[derive(Debug, Clone, Default)]
+pub struct UpdateCounts {
+    counters: Vec<(&'static str, RateCounter)>,
+}
+
+impl UpdateCounts {
+    pub fn record(&mut self, name: &'static str, now: f64) {
+        match self.counters.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, counter)) => counter.record(now),
+            None => {
+                let mut counter = RateCounter::new(now);
+                counter.record(now);
+                self.counters.push((name, counter));
+            }
+        }
+    }
+
+    pub fn report(&mut self, now: f64) -> Vec<(&'static str, f64)> {
+        self.counters.iter_mut().map(|(name, c)| (*name, c.per_sec(now))).collect()
+    }
+}
+
+/// Durations of one kind of work over a window
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Timing {
+    pub count: u32,
+    pub avg_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Collects durations, reporting the last complete window
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TimingWindow {
+    start: f64,
+    count: u32,
+    total_ms: f64,
+    max_ms: f64,
+    last: Timing,
+}
+
+impl TimingWindow {
+    pub fn new(now: f64) -> Self {
+        Self { start: now, ..Self::default() }
+    }
+
+    pub fn record(&mut self, ms: f64, now: f64) {
+        self.roll(now);
+        self.count += 1;
+        self.total_ms += ms;
+        self.max_ms = self.max_ms.max(ms);
+    }
+
+    fn roll(&mut self, now: f64) {
+        if now - self.start < DIAGNOSTICS_WINDOW_MS {
+            return;
+        }
+        let avg_ms = if self.count == 0 { 0.0 } else { self.total_ms / self.count as f64 };
+        self.last = Timing { count: self.count, avg_ms, max_ms: self.max_ms };
+        *self = Self { start: now, last: self.last, ..Self::default() };
+    }
+
+    pub fn report(&mut self, now: f64) -> Timing {
+        self.roll(now);
+        self.last
+    }
+}
+
+/// A frame the client could not decode
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// Local Unix millis
+    pub timestamp: i64,
+    /// Message type, as far as it could be read
+    pub kind: String,
+    pub error: String,
+}
+
+/// Order book sequence problems seen by the WebSocket client
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BookGaps {
+    /// Books that skipped sequence numbers
+    pub gaps: u64,
+    /// Sequence numbers skipped in total
+    pub missed: u64,
+    /// Duplicate or late books dropped
+    pub stale: u64,
+    /// Resync requests sent to the server
+    pub resyncs: u64,
+}
+
+/// Measurements taken by the WebSocket client for the overlay
+#[derive(Debug, Clone, Default)]
+pub struct Diagnostics {
+    /// Time spent decoding and applying each frame
+    pub dispatch: TimingWindow,
+    /// Book sequence gaps and resyncs since the page loaded
+    pub books: BookGaps,
+    /// Newest last
+    parse_errors: VecDeque<ParseError>,
+}
+
+impl Diagnostics {
+    pub fn record_parse_error(&mut self, error: ParseError) {
+        if self.parse_errors.len() >= MAX_PARSE_ERRORS {
+            self.parse_errors.pop_front();
+        }
+        self.parse_errors.push_back(error);
+    }
+
+    /// Newest first
+    pub fn parse_errors(&self) -> impl Iterator<Item = &ParseError> {
+        self.parse_errors.iter().rev()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rates_and_timings() {
+        let mut counts = UpdateCounts::default();
+        for n in 0..5 {
+            counts.record("ticker", n as f64 * 100.0);
+        }
+        counts.record("trades", 0.0);
+        assert_eq!(counts.report(500.0), vec![("ticker", 0.0), ("trades", 0.0)]);
+        assert_eq!(counts.report(1_000.0), vec![("ticker", 5.0), ("trades", 1.0)]);
+        // Nothing in the next window
+        assert_eq!(counts.report(2_000.0), vec![("ticker", 0.0), ("trades", 0.0)]);
+
+        let mut window = TimingWindow::new(0.0);
+        window.record(2.0, 10.0);
+        window.record(6.0, 20.0);
+        assert_eq!(window.report(500.0), Timing::default());
+        assert_eq!(window.report(1_000.0), Timing { count: 2, avg_ms: 4.0, max_ms: 6.0 });
+    }
+
+    #[test]
+    fn test_parse_errors_keep_newest() {
+        let mut diagnostics = Diagnostics::default();
+        for n in 0..MAX_PARSE_ERRORS + 2 {
+            diagnostics.record_parse_error(ParseError {
+                timestamp: n as i64,
+                kind: "ticker".into(),
+                error: "missing field".into(),
+            });
+        }
+        let timestamps: Vec<i64> = diagnostics.parse_errors().map(|e| e.timestamp).collect();
+        assert_eq!(timestamps.len(), MAX_PARSE_ERRORS);
+        assert_eq!(timestamps[0], MAX_PARSE_ERRORS as i64 + 1);
+    }
+}
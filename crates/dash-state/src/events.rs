@@ -0,0 +1,255 @@
+//! Dashboard event bus
+//!
+//! Components announce what happened (a symbol picked, an alert fired, the
+//! chart scrolled, an order sent) without reaching into each other's state.
+//! Listeners subscribe to the kinds of event they care about and are called
+//! synchronously on publish, so bursts of events are never coalesced the
+//! way signal updates are. Components also register the commands they
+//! offer here, for the command palette.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use dash_core::{Symbol, TradeSide};
+use leptos::prelude::*;
+
+use crate::{use_app_state, Command};
+
+/// Something one component tells the rest of the dashboard
+#[derive(Debug, Clone, PartialEq)]
+pub enum DashEvent {
+    /// A symbol was picked (watchlist, search, link)
+    SymbolSelected(Symbol),
+    /// A price or data alert fired
+    AlertTriggered { message: String, price: Option<f64> },
+    /// The candle chart now shows this time range (ms)
+    ChartRangeChanged { from_ms: i64, to_ms: i64 },
+    /// An order was sent; `price` is `None` for market orders
+    OrderPlaced { symbol: Symbol, side: TradeSide, quantity: f64, price: Option<f64> },
+}
+
+/// Payload-free tag of a `DashEvent`, for subscribing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DashEventKind {
+    SymbolSelected,
+    AlertTriggered,
+    ChartRangeChanged,
+    OrderPlaced,
+}
+
+impl DashEvent {
+    pub fn kind(&self) -> DashEventKind {
+        match self {
+            Self::SymbolSelected(_) => DashEventKind::SymbolSelected,
+            Self::AlertTriggered { .. } => DashEventKind::AlertTriggered,
+            Self::ChartRangeChanged { .. } => DashEventKind::ChartRangeChanged,
+            Self::OrderPlaced { .. } => DashEventKind::OrderPlaced,
+        }
+    }
+}
+
+/// Handle for removing a listener
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SubscriptionId(u64);
+
+type Listener = Arc<dyn Fn(&DashEvent) + Send + Sync>;
+
+/// Listeners with their kind filter, in subscription order
+type Listeners = Vec<(SubscriptionId, Option<DashEventKind>, Listener)>;
+
+/// Publish/subscribe hub shared by every clone
+#[derive(Clone, Default)]
+pub struct EventBus {
+    listeners: Arc<Mutex<Listeners>>,
+    next_id: Arc<AtomicU64>,
+    commands: Arc<Mutex<Vec<Command>>>,
+}
+
+impl std::fmt::Debug for EventBus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventBus").field("listeners", &self.listener_count()).finish()
+    }
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Call `listener` for every event of `kind`, or for all events when
+    /// `kind` is `None`
+    pub fn subscribe(
+        &self,
+        kind: Option<DashEventKind>,
+        listener: impl Fn(&DashEvent) + Send + Sync + 'static,
+    ) -> SubscriptionId {
+        let id = SubscriptionId(self.next_id.fetch_add(1, Ordering::Relaxed));
+        self.lock().push((id, kind, Arc::new(listener)));
+        id
+    }
+
+    /// Remove a listener; returns whether it was subscribed
+    pub fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        let mut listeners = self.lock();
+        let before = listeners.len();
+        listeners.retain(|(listener, _, _)| *listener != id);
+        listeners.len() < before
+    }
+
+    /// Deliver `event` to its listeners, returning how many were called.
+    /// Listeners may publish or subscribe in turn; those added during
+    /// delivery hear from the next event on.
+    pub fn publish(&self, event: DashEvent) -> usize {
+        let kind = event.kind();
+        let listeners: Vec<Listener> = self
+            .lock()
+            .iter()
+            .filter(|(_, filter, _)| filter.is_none_or(|k| k == kind))
+            .map(|(_, _, listener)| Arc::clone(listener))
+            .collect();
+        for listener in &listeners {
+            listener(&event);
+        }
+        listeners.len()
+    }
+
+    pub fn listener_count(&self) -> usize {
+        self.lock().len()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Listeners> {
+        // A listener that panicked leaves the list itself intact
+        self.listeners.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // ========================================================================
+    // Commands
+    // ========================================================================
+
+    /// Offer `command` in the palette, replacing one with the same id
+    pub fn register_command(&self, command: Command) {
+        let mut commands = self.lock_commands();
+        match commands.iter_mut().find(|c| c.id == command.id) {
+            Some(existing) => *existing = command,
+            None => commands.push(command),
+        }
+    }
+
+    pub fn unregister_command(&self, id: &str) {
+        self.lock_commands().retain(|c| c.id != id);
+    }
+
+    /// Registered commands, in registration order
+    pub fn commands(&self) -> Vec<Command> {
+        self.lock_commands().clone()
+    }
+
+    /// Run the command `id`; returns whether it is registered
+    pub fn run_command(&self, id: &str) -> bool {
+        let command = self.lock_commands().iter().find(|c| c.id == id).cloned();
+        command.map(|c| c.run()).is_some()
+    }
+
+    fn lock_commands(&self) -> MutexGuard<'_, Vec<Command>> {
+        self.commands.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Listen for events of `kind` while the calling component is mounted
+pub fn use_dash_event(
+    kind: DashEventKind,
+    listener: impl Fn(&DashEvent) + Send + Sync + 'static,
+) -> SubscriptionId {
+    let events = use_app_state().events;
+    let id = events.subscribe(Some(kind), listener);
+    on_cleanup(move || {
+        events.unsubscribe(id);
+    });
+    id
+}
+
+/// Publish through the app state in context
+pub fn publish_dash_event(event: DashEvent) -> usize {
+    use_app_state().events.publish(event)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_bus() {
+        let bus = EventBus::new();
+        let heard = Arc::new(Mutex::new(Vec::new()));
+        let alerts = {
+            let heard = Arc::clone(&heard);
+            bus.subscribe(Some(DashEventKind::AlertTriggered), move |event| {
+                heard.lock().unwrap().push(event.clone());
+            })
+        };
+        let all = Arc::new(AtomicU64::new(0));
+        {
+            let all = Arc::clone(&all);
+            bus.subscribe(None, move |_| {
+                all.fetch_add(1, Ordering::Relaxed);
+            });
+        }
+
+        let alert = DashEvent::AlertTriggered { message: "BTC above 70k".into(), price: Some(7e4) };
+        assert_eq!(bus.publish(alert.clone()), 2);
+        assert_eq!(bus.publish(DashEvent::SymbolSelected(Symbol::new("ETH-USD"))), 1);
+        assert_eq!(*heard.lock().unwrap(), vec![alert]);
+        assert_eq!(all.load(Ordering::Relaxed), 2);
+
+        assert!(bus.unsubscribe(alerts));
+        assert!(!bus.unsubscribe(alerts));
+        assert_eq!(bus.publish(DashEvent::ChartRangeChanged { from_ms: 0, to_ms: 60_000 }), 1);
+    }
+
+    #[test]
+    fn test_commands() {
+        let bus = EventBus::new();
+        let relay = bus.clone();
+        bus.register_command(Command::new("eth", "Symbol: ETH-USD", move || {
+            relay.publish(DashEvent::SymbolSelected(Symbol::new("ETH-USD")));
+        }));
+        bus.register_command(Command::new("theme", "Toggle theme", || {}));
+        bus.register_command(Command::new("theme", "Switch theme", || {}));
+        let titles: Vec<String> = bus.commands().into_iter().map(|c| c.title).collect();
+        assert_eq!(titles, vec!["Symbol: ETH-USD", "Switch theme"]);
+
+        let picked = Arc::new(Mutex::new(None));
+        {
+            let picked = Arc::clone(&picked);
+            bus.subscribe(Some(DashEventKind::SymbolSelected), move |event| {
+                *picked.lock().unwrap() = Some(event.clone());
+            });
+        }
+        assert!(bus.run_command("eth"));
+        assert_eq!(
+            *picked.lock().unwrap(),
+            Some(DashEvent::SymbolSelected(Symbol::new("ETH-USD")))
+        );
+
+        bus.unregister_command("eth");
+        assert!(!bus.run_command("eth"));
+    }
+
+    #[test]
+    fn test_publish_from_listener() {
+        let bus = EventBus::new();
+        let relay = bus.clone();
+        bus.subscribe(Some(DashEventKind::SymbolSelected), move |_| {
+            relay.publish(DashEvent::ChartRangeChanged { from_ms: 0, to_ms: 0 });
+        });
+        let ranges = Arc::new(AtomicU64::new(0));
+        {
+            let ranges = Arc::clone(&ranges);
+            bus.subscribe(Some(DashEventKind::ChartRangeChanged), move |_| {
+                ranges.fetch_add(1, Ordering::Relaxed);
+            });
+        }
+        bus.publish(DashEvent::SymbolSelected(Symbol::default()));
+        assert_eq!(ranges.load(Ordering::Relaxed), 1);
+    }
+}
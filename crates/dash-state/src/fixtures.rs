@@ -0,0 +1,220 @@
+//! Populated state for tests
+//!
+//! Deterministic candles, books and trades, and a [`MarketFixture`] builder
+//! loading them into a [`MarketState`] or [`AppState`]. Used by the unit
+//! tests here and, through the `test-support` feature, by component tests
+//! downstream.
+
+use chrono::{DateTime, Utc};
+use dash_core::{
+    Candle, CandleInterval, OrderBookLevel, OrderBookSnapshot, Price, Quantity, Symbol, Ticker,
+    Trade, TradeSide,
+};
+
+use crate::{AppState, MarketState};
+
+pub const FIXTURE_SYMBOL: &str = "BTC-USD";
+
+/// Last price of the fixture market
+pub const FIXTURE_PRICE: f64 = 50_000.0;
+
+/// Open time of the newest fixture candle and time of the newest trade
+/// (2024-01-01 00:00 UTC)
+pub const FIXTURE_TIME_MS: i64 = 1_704_067_200_000;
+
+/// Book levels are this far apart
+pub const FIXTURE_TICK: f64 = 0.5;
+
+/// Ticker at `price` with 24h stats around it (+2% on the day)
+pub fn fixture_ticker(symbol: &Symbol, price: f64) -> Ticker {
+    let mut ticker = Ticker::new(symbol.clone(), price);
+    let open = price / 1.02;
+    ticker.bid_price = Price::new(price - FIXTURE_TICK);
+    ticker.ask_price = Price::new(price + FIXTURE_TICK);
+    ticker.high_24h = Price::new(price * 1.01);
+    ticker.low_24h = Price::new(open * 0.99);
+    ticker.open_24h = Price::new(open);
+    ticker.change_24h = price - open;
+    ticker.change_percent_24h = 2.0;
+    ticker.volume_24h = Quantity::new(1_234.5);
+    ticker.quote_volume_24h = 1_234.5 * price;
+    ticker.timestamp = FIXTURE_TIME_MS;
+    ticker
+}
+
+/// Book with `levels` per side around `mid`; sizes grow away from it
+/// (1.0, 1.5, 2.0, ...)
+pub fn fixture_book(symbol: &Symbol, mid: f64, levels: usize) -> OrderBookSnapshot {
+    let level = |i: usize, price: f64| OrderBookLevel::new(price, 1.0 + i as f64 * 0.5, 1);
+    let mut book = OrderBookSnapshot::new(symbol.clone());
+    book.bids = (0..levels).map(|i| level(i, mid - FIXTURE_TICK * (i + 1) as f64)).collect();
+    book.asks = (0..levels).map(|i| level(i, mid + FIXTURE_TICK * (i + 1) as f64)).collect();
+    book.timestamp = FIXTURE_TIME_MS;
+    book.sequence = 1;
+    book
+}
+
+/// `n` trades a second apart ending at [`FIXTURE_TIME_MS`], oldest first,
+/// alternating buy and sell with ids `t0`, `t1`, ...
+pub fn fixture_trades(symbol: &Symbol, price: f64, n: usize) -> Vec<Trade> {
+    (0..n)
+        .map(|i| {
+            let side = if i % 2 == 0 { TradeSide::Buy } else { TradeSide::Sell };
+            let mut trade = Trade::new(symbol.clone(), price + i as f64, 0.1 * (i + 1) as f64, side);
+            trade.id = format!("t{}", i);
+            let ms = FIXTURE_TIME_MS - (n - 1 - i) as i64 * 1_000;
+            trade.timestamp = DateTime::<Utc>::from_timestamp_millis(ms).unwrap_or_default();
+            trade
+        })
+        .collect()
+}
+
+/// `n` closed one-minute candles, the newest opening at [`FIXTURE_TIME_MS`]
+/// and closing at `close`; each closes 10 above the one before
+pub fn fixture_candles(symbol: &Symbol, close: f64, n: usize) -> Vec<Candle> {
+    let interval = CandleInterval::M1;
+    let step_ms = interval.as_secs() * 1_000;
+    (0..n)
+        .map(|i| {
+            let back = (n - 1 - i) as f64;
+            let close = close - back * 10.0;
+            let timestamp = FIXTURE_TIME_MS - (n - 1 - i) as i64 * step_ms;
+            let mut candle = Candle::new(symbol.clone(), interval, timestamp, close - 10.0);
+            candle.high = Price::new(close + 5.0);
+            candle.low = Price::new(close - 15.0);
+            candle.close = Price::new(close);
+            candle.volume = Quantity::new(2.0);
+            candle.quote_volume = 2.0 * close;
+            candle.trade_count = 20;
+            candle.is_closed = true;
+            candle
+        })
+        .collect()
+}
+
+/// Builder for a populated market
+#[derive(Debug, Clone)]
+pub struct MarketFixture {
+    symbol: Symbol,
+    price: f64,
+    book_levels: usize,
+    trades: usize,
+    candles: usize,
+    ticker: bool,
+}
+
+impl Default for MarketFixture {
+    fn default() -> Self {
+        Self {
+            symbol: Symbol::new(FIXTURE_SYMBOL),
+            price: FIXTURE_PRICE,
+            book_levels: 10,
+            trades: 20,
+            candles: 60,
+            ticker: true,
+        }
+    }
+}
+
+impl MarketFixture {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_symbol(mut self, symbol: impl Into<Symbol>) -> Self {
+        self.symbol = symbol.into();
+        self
+    }
+
+    pub fn with_price(mut self, price: f64) -> Self {
+        self.price = price;
+        self
+    }
+
+    /// Levels per book side; none leaves the book empty
+    pub fn with_book_levels(mut self, levels: usize) -> Self {
+        self.book_levels = levels;
+        self
+    }
+
+    pub fn with_trades(mut self, n: usize) -> Self {
+        self.trades = n;
+        self
+    }
+
+    pub fn with_candles(mut self, n: usize) -> Self {
+        self.candles = n;
+        self
+    }
+
+    /// Leave the ticker empty, as before the first message
+    pub fn without_ticker(mut self) -> Self {
+        self.ticker = false;
+        self
+    }
+
+    /// Load the fixture data into `market`
+    pub fn apply(&self, market: &MarketState) {
+        let symbol = &self.symbol;
+        market.set_symbol(symbol.clone());
+        market.set_interval(CandleInterval::M1);
+        if self.ticker {
+            market.update_ticker(fixture_ticker(symbol, self.price));
+        }
+        if self.book_levels > 0 {
+            market.update_orderbook(fixture_book(symbol, self.price, self.book_levels));
+        }
+        market.add_trades(fixture_trades(symbol, self.price, self.trades));
+        if self.candles > 0 {
+            market.set_candles(fixture_candles(symbol, self.price, self.candles));
+        }
+    }
+
+    pub fn market(&self) -> MarketState {
+        let market = MarketState::new();
+        self.apply(&market);
+        market
+    }
+
+    /// App state around the fixture market, connected
+    pub fn app_state(&self) -> AppState {
+        let state = AppState::new();
+        self.apply(&state.market);
+        state.set_connected();
+        state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use leptos::prelude::*;
+
+    #[test]
+    fn test_market_fixture() {
+        let market = MarketFixture::new().with_trades(5).with_candles(3).market();
+
+        assert_eq!(market.current_price(), Some(FIXTURE_PRICE));
+        assert_eq!(market.mid_price(), Some(FIXTURE_PRICE));
+        assert_eq!(market.spread(), Some(2.0 * FIXTURE_TICK));
+
+        let trades = market.recent_trades(10);
+        assert_eq!(trades.len(), 5);
+        assert!(trades.iter().any(|t| t.id == "t4"));
+
+        let candles = market.candles.with_untracked(|h| h.candles.to_vec());
+        assert_eq!(candles.len(), 3);
+        assert_eq!(candles.last().map(|c| c.close.as_f64()), Some(FIXTURE_PRICE));
+        assert_eq!(candles.last().map(|c| c.timestamp), Some(FIXTURE_TIME_MS));
+    }
+
+    #[test]
+    fn test_empty_fixture() {
+        let state =
+            MarketFixture::new().without_ticker().with_book_levels(0).with_trades(0).app_state();
+        assert!(state.is_connected());
+        assert_eq!(state.market.current_price(), None);
+        assert!(state.market.orderbook.with_untracked(Option::is_none));
+        assert!(state.market.trades.with_untracked(|t| t.is_empty()));
+    }
+}
@@ -0,0 +1,124 @@
+//! Bounded, time-indexed history of order book snapshots
+
+use std::sync::Arc;
+
+use dash_core::{OrderBookSnapshot, RingBuffer};
+
+/// Recent order book snapshots ordered by timestamp (oldest first).
+/// Snapshots are shared with the live `orderbook` signal, not copied.
+#[derive(Debug, Clone)]
+pub struct BookHistory {
+    snapshots: RingBuffer<Arc<OrderBookSnapshot>>,
+}
+
+impl BookHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            snapshots: RingBuffer::new(capacity),
+        }
+    }
+
+    /// Record a snapshot, evicting the oldest when full.
+    /// Snapshots older than the newest one are ignored; equal timestamps replace.
+    pub fn push(&mut self, book: Arc<OrderBookSnapshot>) {
+        if let Some(last) = self.snapshots.last_mut() {
+            if book.timestamp < last.timestamp {
+                return;
+            }
+            if book.timestamp == last.timestamp {
+                *last = book;
+                return;
+            }
+        }
+        self.snapshots.push(book);
+    }
+
+    /// Book as of `timestamp` (latest snapshot taken at or before it)
+    pub fn at(&self, timestamp: i64) -> Option<&Arc<OrderBookSnapshot>> {
+        let idx = self.snapshots.partition_point(|b| b.timestamp <= timestamp);
+        idx.checked_sub(1).and_then(|i| self.snapshots.get(i))
+    }
+
+    /// Snapshots with `from <= timestamp <= to`, oldest first
+    pub fn range(&self, from: i64, to: i64) -> impl Iterator<Item = &Arc<OrderBookSnapshot>> {
+        let start = self.snapshots.partition_point(|b| b.timestamp < from);
+        let end = self.snapshots.partition_point(|b| b.timestamp <= to);
+        self.snapshots[start..end.max(start)].iter()
+    }
+
+    /// Timestamps covered by the buffer (oldest, newest)
+    pub fn time_range(&self) -> Option<(i64, i64)> {
+        self.snapshots
+            .oldest()
+            .zip(self.snapshots.latest())
+            .map(|(first, last)| (first.timestamp, last.timestamp))
+    }
+
+    pub fn latest(&self) -> Option<&Arc<OrderBookSnapshot>> {
+        self.snapshots.latest()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Arc<OrderBookSnapshot>> {
+        self.snapshots.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.snapshots.capacity()
+    }
+
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dash_core::Symbol;
+
+    fn book(timestamp: i64) -> Arc<OrderBookSnapshot> {
+        let mut book = OrderBookSnapshot::new(Symbol::new("BTC-USD"));
+        book.timestamp = timestamp;
+        Arc::new(book)
+    }
+
+    #[test]
+    fn test_book_history_eviction() {
+        let mut history = BookHistory::new(3);
+        for ts in [100, 200, 300, 400] {
+            history.push(book(ts));
+        }
+
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.time_range(), Some((200, 400)));
+
+        // Out-of-order snapshot is dropped
+        history.push(book(350));
+        assert_eq!(history.len(), 3);
+    }
+
+    #[test]
+    fn test_book_history_time_index() {
+        let mut history = BookHistory::new(10);
+        for ts in [100, 200, 300] {
+            history.push(book(ts));
+        }
+
+        assert!(history.at(50).is_none());
+        assert_eq!(history.at(100).map(|b| b.timestamp), Some(100));
+        assert_eq!(history.at(250).map(|b| b.timestamp), Some(200));
+        assert_eq!(history.at(1_000).map(|b| b.timestamp), Some(300));
+
+        let ts: Vec<i64> = history.range(150, 300).map(|b| b.timestamp).collect();
+        assert_eq!(ts, vec![200, 300]);
+        assert_eq!(history.range(400, 500).count(), 0);
+    }
+}
@@ -0,0 +1,97 @@
+//! Responsive layout: viewport size, layout mode and the mobile tab
+
+use dash_core::Msg;
+
+/// Viewports narrower than this get the tabbed single-panel layout
+pub const MOBILE_BREAKPOINT_PX: f64 = 768.0;
+
+/// Browser viewport size in CSS pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Viewport {
+    pub fn new(width: f64, height: f64) -> Self {
+        Self { width, height }
+    }
+
+    pub fn layout_mode(&self) -> LayoutMode {
+        LayoutMode::for_width(self.width)
+    }
+}
+
+impl Default for Viewport {
+    /// Desktop-sized until the first measurement
+    fn default() -> Self {
+        Self::new(1280.0, 800.0)
+    }
+}
+
+/// Dashboard arrangement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LayoutMode {
+    /// Three columns: book, charts, trades
+    #[default]
+    Desktop,
+    /// One panel at a time behind a tab bar
+    Mobile,
+}
+
+impl LayoutMode {
+    pub fn for_width(width: f64) -> Self {
+        if width < MOBILE_BREAKPOINT_PX {
+            Self::Mobile
+        } else {
+            Self::Desktop
+        }
+    }
+
+    pub fn is_mobile(&self) -> bool {
+        matches!(self, Self::Mobile)
+    }
+
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            Self::Desktop => "layout-desktop",
+            Self::Mobile => "layout-mobile",
+        }
+    }
+}
+
+/// Panel shown in the mobile layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MobileTab {
+    #[default]
+    Chart,
+    Book,
+    Trades,
+}
+
+impl MobileTab {
+    /// Localized tab title
+    pub fn msg(&self) -> Msg {
+        match self {
+            Self::Chart => Msg::Chart,
+            Self::Book => Msg::Book,
+            Self::Trades => Msg::Trades,
+        }
+    }
+
+    pub fn all() -> &'static [Self] {
+        &[Self::Chart, Self::Book, Self::Trades]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breakpoint() {
+        assert_eq!(Viewport::new(390.0, 844.0).layout_mode(), LayoutMode::Mobile);
+        assert_eq!(Viewport::new(MOBILE_BREAKPOINT_PX, 1024.0).layout_mode(), LayoutMode::Desktop);
+        assert_eq!(Viewport::default().layout_mode(), LayoutMode::Desktop);
+    }
+}
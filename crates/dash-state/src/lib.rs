@@ -3,16 +3,81 @@
 //! Reactive state management for the BTC Exchange Dashboard.
 //! Uses Leptos signals for surgical DOM updates on market data changes.
 
+pub mod a11y;
+pub mod account;
+pub mod admin;
+pub mod aggregation;
+pub mod chart;
+pub mod clock;
+#[cfg(feature = "indicators")]
+pub mod compute;
+pub mod delivery;
+pub mod diagnostics;
+pub mod events;
+#[cfg(any(test, feature = "test-support"))]
+pub mod fixtures;
+pub mod history;
+pub mod layout;
+pub mod logging;
 pub mod market;
+pub mod news;
+pub mod palette;
+pub mod plugins;
+pub mod replay;
+pub mod route;
+pub mod session_log;
+pub mod share;
+pub mod staleness;
+pub mod subscription;
+pub mod tape;
 
+pub use a11y::*;
+pub use account::*;
+pub use admin::*;
+pub use aggregation::*;
+pub use chart::*;
+pub use clock::*;
+#[cfg(feature = "indicators")]
+pub use compute::*;
+pub use delivery::*;
+pub use diagnostics::*;
+pub use events::*;
+#[cfg(any(test, feature = "test-support"))]
+pub use fixtures::*;
+pub use history::*;
+pub use layout::*;
+pub use logging::*;
 pub use market::*;
+pub use news::*;
+pub use palette::*;
+pub use plugins::*;
+pub use replay::*;
+pub use route::*;
+pub use session_log::*;
+pub use share::*;
+pub use staleness::*;
+pub use subscription::*;
+pub use tape::*;
+
+use dash_core::{
+    AlertBook, AlertNotice, ChartType, ConnectionState, DecodeStats, DepthAxis, DepthScale,
+    ExprError, ExprErrorKind, HealthReport, Locale, MarketSession, Msg, NewsItem, Role,
+    ScheduledEvent, Session, SourceStatus, Subscription, VolumeMode,
+};
+#[cfg(feature = "paper-trading")]
+use dash_core::{
+    Fill, FillJournal, Leverage, OrderId, OrderTicket, OrderType, PaperEngine, TicketError, Trade,
+};
+use std::sync::atomic::AtomicUsize;
+use std::sync::Arc;
 
-use dash_core::ConnectionState;
 use leptos::prelude::*;
+use serde::{Deserialize, Serialize};
 
 /// Configuration constants
 pub const MAX_TRADES: usize = 100;
 pub const MAX_CANDLES: usize = 200;
+pub const MAX_BOOK_HISTORY: usize = 600;
 
 // ============================================================================
 // UI STATE
@@ -50,7 +115,8 @@ impl Theme {
 }
 
 /// Panel visibility state
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PanelVisibility {
     pub orderbook: bool,
     pub trades: bool,
@@ -69,12 +135,124 @@ impl Default for PanelVisibility {
     }
 }
 
+/// Chart redraw rate cap, independent of data arrival rate
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FrameRateCap {
+    Fps10,
+    Fps30,
+    #[default]
+    Fps60,
+    /// Redraw on every data change
+    Unlimited,
+}
+
+impl FrameRateCap {
+    /// Frames per second (`None` = uncapped)
+    pub fn fps(&self) -> Option<u32> {
+        match self {
+            Self::Fps10 => Some(10),
+            Self::Fps30 => Some(30),
+            Self::Fps60 => Some(60),
+            Self::Unlimited => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Fps10 => "10 fps",
+            Self::Fps30 => "30 fps",
+            Self::Fps60 => "60 fps",
+            Self::Unlimited => "Max",
+        }
+    }
+
+    pub fn all() -> &'static [Self] {
+        &[Self::Fps10, Self::Fps30, Self::Fps60, Self::Unlimited]
+    }
+}
+
+/// What the order book's total column shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BookTotal {
+    /// Price × size of each level
+    #[default]
+    Notional,
+    /// Size summed from the best price out, with depth bars to match
+    Cumulative,
+}
+
+impl BookTotal {
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::Notional => Self::Cumulative,
+            Self::Cumulative => Self::Notional,
+        }
+    }
+}
+
+/// Frame rate used for charts while performance mode is on
+pub const PERFORMANCE_MODE_FPS: u32 = 10;
+
 /// Global UI state
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct UiState {
     pub theme: Theme,
     pub panels: PanelVisibility,
     pub compact_mode: bool,
+    pub frame_rate: FrameRateCap,
+    /// Throttle chart redraws hard to save CPU/battery
+    pub performance_mode: bool,
+    /// Panel shown by the mobile layout
+    pub mobile_tab: MobileTab,
+    /// Panel expanded to fill the screen, if any
+    pub fullscreen: Option<Panel>,
+    /// How the candle chart draws prices
+    pub chart_type: ChartType,
+    /// What the candle chart's volume bars show
+    pub volume_mode: VolumeMode,
+    /// Candle chart layers
+    pub overlays: ChartOverlays,
+    /// Session opens marked on the candle chart
+    pub sessions: SessionMarkers,
+    /// Tint the order book by the smoothed bid/ask imbalance
+    pub imbalance_tint: bool,
+    /// Order book total column mode
+    pub book_total: BookTotal,
+    /// Asks and bids in one scroll area kept centered on the spread
+    pub book_anchored: bool,
+    /// What the depth chart's y-axis measures
+    pub depth_axis: DepthAxis,
+    /// Depth chart sides on one shared scale or each on its own
+    pub depth_scale: DepthScale,
+    /// How trade tape rows are shaded by value
+    pub tape_shading: TapeShading,
+    /// Running volume column on the trade tape
+    pub tape_volume: bool,
+    /// Repeat prints collapsed into one tape row
+    pub tape_collapse: TapeCollapse,
+    /// Microprice and depth-weighted mid in the ticker bar
+    pub book_prices: bool,
+    /// Screen-reader price announcements
+    pub announce: AnnounceCadence,
+    /// Ages at which panel data badges turn amber and red
+    pub stale_threshold: StaleThreshold,
+    /// UI language and number/date format
+    pub locale: Locale,
+    /// News panel lists only items tagged with the current pair
+    pub news_pair_only: bool,
+    /// Registered custom panel shown below the trade tape, by id
+    pub custom_panel: Option<&'static str>,
+}
+
+impl UiState {
+    /// Chart frame-rate cap after applying performance mode
+    pub fn effective_fps(&self) -> Option<u32> {
+        if self.performance_mode {
+            Some(PERFORMANCE_MODE_FPS)
+        } else {
+            self.frame_rate.fps()
+        }
+    }
 }
 
 impl Default for UiState {
@@ -83,6 +261,61 @@ impl Default for UiState {
             theme: Theme::Dark,
             panels: PanelVisibility::default(),
             compact_mode: false,
+            frame_rate: FrameRateCap::default(),
+            performance_mode: false,
+            mobile_tab: MobileTab::default(),
+            fullscreen: None,
+            chart_type: ChartType::default(),
+            volume_mode: VolumeMode::default(),
+            overlays: ChartOverlays::default(),
+            sessions: SessionMarkers::default(),
+            imbalance_tint: false,
+            book_total: BookTotal::default(),
+            book_anchored: false,
+            depth_axis: DepthAxis::default(),
+            depth_scale: DepthScale::default(),
+            tape_shading: TapeShading::default(),
+            tape_volume: false,
+            tape_collapse: TapeCollapse::default(),
+            book_prices: false,
+            announce: AnnounceCadence::default(),
+            stale_threshold: StaleThreshold::default(),
+            locale: Locale::default(),
+            news_pair_only: false,
+            custom_panel: None,
+        }
+    }
+}
+
+// ============================================================================
+// CONNECTION STATS
+// ============================================================================
+
+/// Byte counters for the market data stream
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ConnectionStats {
+    /// Frames received
+    pub messages: u64,
+    /// Bytes received on the wire
+    pub wire_bytes: u64,
+    /// Bytes after decompression
+    pub decoded_bytes: u64,
+}
+
+impl ConnectionStats {
+    /// Record one received frame
+    pub fn record(&mut self, wire_bytes: usize, decoded_bytes: usize) {
+        self.messages += 1;
+        self.wire_bytes += wire_bytes as u64;
+        self.decoded_bytes += decoded_bytes as u64;
+    }
+
+    /// Decoded / wire size (1.0 = uncompressed)
+    pub fn compression_ratio(&self) -> f64 {
+        if self.wire_bytes == 0 {
+            1.0
+        } else {
+            self.decoded_bytes as f64 / self.wire_bytes as f64
         }
     }
 }
@@ -104,6 +337,75 @@ pub struct AppState {
     pub error: RwSignal<Option<String>>,
     /// Loading state
     pub loading: RwSignal<bool>,
+    /// Streams to restore after every (re)connection
+    pub subscriptions: RwSignal<SubscriptionSet>,
+    /// Stream byte counters
+    pub stats: RwSignal<ConnectionStats>,
+    /// Inbound frame decode metrics, republished when a frame is repaired
+    /// or rejected
+    pub decode_stats: RwSignal<DecodeStats>,
+    /// Latest server feed health (None until the first poll answers)
+    pub server_health: RwSignal<Option<HealthReport>>,
+    /// Last upstream source change pushed by the server
+    pub source_status: RwSignal<Option<SourceStatus>>,
+    /// Browser viewport size, kept current by the layout
+    pub viewport: RwSignal<Viewport>,
+    /// Lines and tool selection for the candle chart
+    pub drawings: RwSignal<ChartDrawings>,
+    /// Time ranges shaded on the candle chart and listed by the session clock
+    pub shading: RwSignal<ChartShading>,
+    /// Wall clock aligned to server heartbeats, ticked once a second
+    pub clock: RwSignal<ServerClock>,
+    /// Opt-in timeline of session events for issue reports
+    pub session_log: RwSignal<SessionLog>,
+    /// Watermark and other options for shared chart snapshots
+    pub share: RwSignal<ShareSettings>,
+    /// Cross-component events (symbol picks, alerts, chart range, orders)
+    pub events: EventBus,
+    /// Scheduled events from the server's economic calendar
+    pub calendar: RwSignal<Vec<ScheduledEvent>>,
+    /// News pushed by the server and the user's alert keywords
+    pub news: RwSignal<NewsFeed>,
+    /// Simulated orders, positions and fills
+    #[cfg(feature = "paper-trading")]
+    pub paper: RwSignal<PaperEngine>,
+    /// Every paper fill, persisted across reloads
+    #[cfg(feature = "paper-trading")]
+    pub journal: RwSignal<FillJournal>,
+    /// The user's alert rules, checked as market data arrives
+    pub alerts: RwSignal<AlertBook>,
+    /// Browser notification and server webhook settings for the alerts
+    pub alert_delivery: RwSignal<AlertDelivery>,
+    /// Outcome of the last push of the alerts to the server
+    pub alert_sync: RwSignal<AlertSync>,
+    /// Signed-in user, if any
+    pub account: RwSignal<Option<Session>>,
+    /// Outcome of the last account sync
+    pub account_sync: RwSignal<AccountSync>,
+    /// Sign-in waiting to be sent
+    pub sign_in: RwSignal<Option<SignInRequest>>,
+    /// What the server lets this user do; viewers get the data panels only
+    pub role: RwSignal<Role>,
+    /// Set while the admin page is shown; the server status is polled then
+    pub admin_open: RwSignal<bool>,
+    /// Server status for the admin page
+    pub admin: RwSignal<AdminView>,
+    /// Admin control waiting to be sent
+    pub admin_command: RwSignal<Option<AdminCommand>>,
+    /// Set while the client log panel is shown
+    pub log_open: RwSignal<bool>,
+    /// Set while the diagnostics overlay is shown
+    pub diagnostics_open: RwSignal<bool>,
+    /// Dispatch timings and parse errors from the WebSocket client
+    pub diagnostics: StoredValue<Diagnostics>,
+    /// Commands queued for the WebSocket but not yet written; shared with
+    /// the connection's handle
+    pub ws_queue: Arc<AtomicUsize>,
+    /// Panels registered by downstream crates
+    pub custom_panels: PanelRegistry,
+    /// Set while a dropped stream is down: server time of the last data
+    /// shown. Panels keep their frozen state until the stream is back.
+    pub stale_since: RwSignal<Option<i64>>,
 }
 
 impl AppState {
@@ -115,6 +417,41 @@ impl AppState {
             ui: RwSignal::new(UiState::default()),
             error: RwSignal::new(None),
             loading: RwSignal::new(false),
+            subscriptions: RwSignal::new(SubscriptionSet::new()),
+            stats: RwSignal::new(ConnectionStats::default()),
+            decode_stats: RwSignal::new(DecodeStats::default()),
+            server_health: RwSignal::new(None),
+            source_status: RwSignal::new(None),
+            viewport: RwSignal::new(Viewport::default()),
+            drawings: RwSignal::new(ChartDrawings::default()),
+            shading: RwSignal::new(ChartShading::default()),
+            clock: RwSignal::new(ServerClock::default()),
+            session_log: RwSignal::new(SessionLog::default()),
+            share: RwSignal::new(ShareSettings::default()),
+            events: EventBus::new(),
+            calendar: RwSignal::new(Vec::new()),
+            news: RwSignal::new(NewsFeed::default()),
+            #[cfg(feature = "paper-trading")]
+            paper: RwSignal::new(PaperEngine::new()),
+            #[cfg(feature = "paper-trading")]
+            journal: RwSignal::new(FillJournal::new()),
+            alerts: RwSignal::new(AlertBook::new()),
+            alert_delivery: RwSignal::new(AlertDelivery::default()),
+            alert_sync: RwSignal::new(AlertSync::default()),
+            account: RwSignal::new(None),
+            account_sync: RwSignal::new(AccountSync::default()),
+            sign_in: RwSignal::new(None),
+            // Until the server says otherwise
+            role: RwSignal::new(Role::Viewer),
+            admin_open: RwSignal::new(false),
+            admin: RwSignal::new(AdminView::default()),
+            admin_command: RwSignal::new(None),
+            log_open: RwSignal::new(false),
+            diagnostics_open: RwSignal::new(false),
+            diagnostics: StoredValue::new(Diagnostics::default()),
+            ws_queue: Arc::default(),
+            custom_panels: PanelRegistry::new(),
+            stale_since: RwSignal::new(None),
         }
     }
 
@@ -126,10 +463,12 @@ impl AppState {
     pub fn set_connected(&self) {
         self.connection.set(ConnectionState::Connected);
         self.error.set(None);
+        self.stale_since.set(None);
     }
 
     /// Set disconnected state
     pub fn set_disconnected(&self) {
+        self.mark_stale();
         self.connection.set(ConnectionState::Disconnected);
     }
 
@@ -140,14 +479,241 @@ impl AppState {
 
     /// Set reconnecting state
     pub fn set_reconnecting(&self) {
+        self.mark_stale();
         self.connection.set(ConnectionState::Reconnecting);
     }
 
+    /// The server sent protocol major `server_major`, which this build cannot
+    /// read; the page has to be reloaded to pick up a newer client
+    pub fn set_update_required(&self, server_major: u16) {
+        self.mark_stale();
+        self.connection.set(ConnectionState::UpdateRequired);
+        self.error.set(Some(format!(
+            "Server protocol v{} is newer than this dashboard (v{}); reload to update",
+            server_major,
+            dash_core::PROTOCOL_VERSION.major
+        )));
+    }
+
+    /// Freeze on the last data when a live stream drops
+    fn mark_stale(&self) {
+        let was_live = self.connection.get_untracked().is_connected();
+        if was_live && self.stale_since.get_untracked().is_none() {
+            self.stale_since.set(Some(self.market.last_data_at()));
+        }
+    }
+
     /// Check if connected
     pub fn is_connected(&self) -> bool {
         self.connection.get().is_connected()
     }
 
+    /// Align the clock to a server heartbeat received at `local_ms`
+    pub fn record_server_time(&self, server_ms: i64, local_ms: i64) {
+        let expected =
+            self.clock.with_untracked(|c| c.is_synced().then(|| local_ms + c.offset_ms()));
+        self.clock.update(|clock| clock.sync(server_ms, local_ms));
+        if let Some(late_ms) = expected.map(|expected| expected - server_ms)
+            && late_ms >= LATENCY_SPIKE_MS
+        {
+            self.log_session_event(SessionEvent::LatencySpike { ms: late_ms });
+        }
+    }
+
+    /// Reactive server time in Unix millis, updated on every clock tick
+    pub fn server_now(&self) -> Signal<i64> {
+        let clock = self.clock;
+        Memo::new(move |_| clock.with(|c| c.now_ms())).into()
+    }
+
+    /// Subscription for the currently displayed symbol, interval and book depth
+    pub fn current_subscription(&self) -> Subscription {
+        Subscription::new(self.market.symbol.get_untracked())
+            .interval(self.market.interval.get_untracked())
+            .depth_levels(self.market.depth_levels.get_untracked())
+    }
+
+    // ========================================================================
+    // Events
+    // ========================================================================
+
+    /// Tell subscribed components about `event`
+    pub fn publish(&self, event: DashEvent) -> usize {
+        self.events.publish(event)
+    }
+
+    // ========================================================================
+    // News
+    // ========================================================================
+
+    /// Add a pushed news item, raising an alert when it mentions one of
+    /// the user's keywords
+    pub fn add_news(&self, item: NewsItem) {
+        let keyword = self.news.try_maybe_update(|feed| {
+            let keyword = feed.alert_keyword(&item).map(str::to_string);
+            let added = feed.push(item.clone());
+            (added, keyword.filter(|_| added))
+        });
+        if let Some(keyword) = keyword.flatten() {
+            let message = format!("News ({}): {}", keyword, item.headline);
+            self.publish(DashEvent::AlertTriggered { message, price: None });
+        }
+    }
+
+    /// Set the news alert keywords from comma-separated text
+    pub fn set_news_keywords(&self, text: &str) {
+        self.news.update(|feed| feed.set_keywords(text));
+    }
+
+    /// Show only news tagged with the current pair, or everything
+    pub fn toggle_news_pair_only(&self) {
+        self.ui.update(|ui| ui.news_pair_only = !ui.news_pair_only);
+    }
+
+    // ========================================================================
+    // Alerts
+    // ========================================================================
+
+    /// Add an alert rule on the current pair
+    pub fn add_alert(&self, source: &str) -> Result<u64, ExprError> {
+        let symbol = self.market.symbol.get_untracked();
+        self.alerts
+            .try_update(|book| book.add(symbol, source))
+            .unwrap_or(Err(ExprError::new(0, ExprErrorKind::Syntax)))
+    }
+
+    pub fn remove_alert(&self, id: u64) {
+        self.alerts.maybe_update(|book| book.remove(id));
+    }
+
+    /// Check the current pair's rules against its live values, raising an
+    /// alert for each one that fires
+    pub fn check_alerts(&self) {
+        if self.alerts.with_untracked(AlertBook::is_empty) {
+            return;
+        }
+        let symbol = self.market.symbol.get_untracked();
+        let (fired, price) = untrack(|| {
+            self.market.with_alert_inputs(|inputs| {
+                // Arming changes are bookkeeping; only a firing is news
+                let fired = self.alerts.try_maybe_update(|book| {
+                    let fired = book.check(&symbol, inputs);
+                    (!fired.is_empty(), fired)
+                });
+                (fired.unwrap_or_default(), inputs.price)
+            })
+        });
+        for rule in fired {
+            let message = format!("{}: {}", symbol, rule.source());
+            self.publish(DashEvent::AlertTriggered { message, price });
+        }
+    }
+
+    /// Raise an alert for a rule registered on the server
+    pub fn raise_server_alert(&self, notice: &AlertNotice) {
+        let message = format!("{}: {}", notice.symbol, notice.rule);
+        self.publish(DashEvent::AlertTriggered { message, price: notice.price });
+    }
+
+}
+
+#[cfg(feature = "paper-trading")]
+impl AppState {
+    // ========================================================================
+    // Paper Trading
+    // ========================================================================
+
+    /// Whether paper trading is open to this user (tracked)
+    pub fn can_trade(&self) -> bool {
+        self.role.with(Role::is_operator)
+    }
+
+    /// Last traded price of the current pair
+    pub fn last_trade_price(&self) -> Option<f64> {
+        self.market.trades.with_untracked(|trades| trades.latest().map(|t| t.price.as_f64()))
+    }
+
+    /// Place a paper order against the last traded price and announce it
+    pub fn place_paper_order(&self, ticket: &OrderTicket) -> Result<OrderId, TicketError> {
+        if !self.can_trade() {
+            return Err(TicketError::ReadOnly);
+        }
+        let last = self.last_trade_price();
+        let now = self.clock.with_untracked(ServerClock::now_ms);
+        let (id, fills) = self
+            .paper
+            .try_update(|engine| {
+                let before = engine.fills().len();
+                let id = engine.submit(ticket, last, now)?;
+                Ok((id, engine.fills()[before..].to_vec()))
+            })
+            .unwrap_or(Err(TicketError::NotWorking))?;
+        self.journal_fills(fills);
+        self.publish(DashEvent::OrderPlaced {
+            symbol: ticket.symbol.clone(),
+            side: ticket.side,
+            quantity: ticket.quantity,
+            price: (ticket.order_type != OrderType::Market).then_some(ticket.price).flatten(),
+        });
+        Ok(id)
+    }
+
+    /// Fill the paper orders a live trade reaches
+    pub fn fill_paper_orders(&self, trade: &Trade) {
+        let now = trade.timestamp.timestamp_millis();
+        let fills = self.paper.try_maybe_update(|engine| {
+            let fills = engine.on_trade(&trade.symbol, trade.price.as_f64(), now);
+            (!fills.is_empty(), fills)
+        });
+        self.journal_fills(fills.unwrap_or_default());
+    }
+
+    fn journal_fills(&self, fills: Vec<Fill>) {
+        if !fills.is_empty() {
+            self.journal.update(|journal| fills.into_iter().for_each(|f| journal.record(f)));
+        }
+    }
+
+    pub fn clear_journal(&self) {
+        self.journal.update(FillJournal::clear);
+    }
+
+    pub fn cancel_paper_order(&self, id: OrderId) -> Result<(), TicketError> {
+        self.paper.try_update(|engine| engine.cancel(id)).unwrap_or(Err(TicketError::NotWorking))
+    }
+
+    /// Leverage the paper positions are marked at
+    pub fn set_paper_leverage(&self, leverage: Leverage) {
+        self.paper.update(|engine| engine.leverage = leverage);
+    }
+
+    /// Move a working limit, stop or bracket leg, e.g. dragged on the chart
+    pub fn move_paper_order(&self, id: OrderId, price: f64) -> Result<(), TicketError> {
+        if !self.can_trade() {
+            return Err(TicketError::ReadOnly);
+        }
+        self.paper
+            .try_update(|engine| engine.move_order(id, price))
+            .unwrap_or(Err(TicketError::NotWorking))
+    }
+}
+
+impl AppState {
+    // ========================================================================
+    // Session Log
+    // ========================================================================
+
+    /// Add `event` to the session log (no-op unless the user opted in)
+    pub fn log_session_event(&self, event: SessionEvent) {
+        let now = chrono::Utc::now().timestamp_millis();
+        self.session_log.maybe_update(|log| log.record(now, event));
+    }
+
+    /// Opt in to or out of session logging
+    pub fn set_session_logging(&self, enabled: bool) {
+        self.session_log.update(|log| log.set_enabled(enabled));
+    }
+
     // ========================================================================
     // Error Handling
     // ========================================================================
@@ -215,6 +781,158 @@ impl AppState {
         });
     }
 
+    /// Set the chart frame-rate cap
+    pub fn set_frame_rate(&self, frame_rate: FrameRateCap) {
+        self.ui.update(|ui| {
+            ui.frame_rate = frame_rate;
+        });
+    }
+
+    /// Toggle performance mode
+    pub fn toggle_performance_mode(&self) {
+        self.ui.update(|ui| {
+            ui.performance_mode = !ui.performance_mode;
+        });
+    }
+
+    /// Reactive frame-rate cap for chart render schedulers
+    pub fn chart_fps(&self) -> Signal<Option<u32>> {
+        let ui = self.ui;
+        Memo::new(move |_| ui.with(|ui| ui.effective_fps())).into()
+    }
+
+    /// Reactive layout mode; only changes when the breakpoint is crossed
+    pub fn layout_mode(&self) -> Signal<LayoutMode> {
+        let viewport = self.viewport;
+        Memo::new(move |_| viewport.with(|v| v.layout_mode())).into()
+    }
+
+    /// Switch the panel shown by the mobile layout
+    pub fn set_mobile_tab(&self, tab: MobileTab) {
+        self.ui.update(|ui| ui.mobile_tab = tab);
+    }
+
+    /// Expand a panel to fill the screen, or restore the layout if it
+    /// already is
+    pub fn toggle_fullscreen(&self, panel: Panel) {
+        self.ui.update(|ui| {
+            ui.fullscreen = if ui.fullscreen == Some(panel) { None } else { Some(panel) };
+        });
+    }
+
+    /// Return to the regular layout
+    pub fn exit_fullscreen(&self) {
+        if self.ui.with_untracked(|ui| ui.fullscreen.is_some()) {
+            self.ui.update(|ui| ui.fullscreen = None);
+        }
+    }
+
+    /// Switch the UI language
+    pub fn set_locale(&self, locale: Locale) {
+        self.ui.update(|ui| ui.locale = locale);
+    }
+
+    /// Reactive UI language; only changes when the locale does
+    pub fn locale(&self) -> Signal<Locale> {
+        let ui = self.ui;
+        Memo::new(move |_| ui.with(|ui| ui.locale)).into()
+    }
+
+    /// Set how often the price is announced to screen readers
+    pub fn set_announce_cadence(&self, cadence: AnnounceCadence) {
+        self.ui.update(|ui| ui.announce = cadence);
+    }
+
+    /// Text stamped on shared chart snapshots; blank turns it off
+    pub fn set_watermark(&self, text: &str) {
+        self.share.update(|s| s.set_watermark(text));
+    }
+
+    /// Set when panel data age badges escalate
+    pub fn set_stale_threshold(&self, threshold: StaleThreshold) {
+        self.ui.update(|ui| ui.stale_threshold = threshold);
+    }
+
+    /// Switch how the candle chart draws prices
+    pub fn set_chart_type(&self, chart_type: ChartType) {
+        self.ui.update(|ui| ui.chart_type = chart_type);
+    }
+
+    /// Switch between total, buy/sell split and delta volume bars
+    pub fn set_volume_mode(&self, volume_mode: VolumeMode) {
+        self.ui.update(|ui| ui.volume_mode = volume_mode);
+    }
+
+    /// Set which candle chart layers are drawn
+    pub fn set_overlays(&self, overlays: ChartOverlays) {
+        self.ui.update(|ui| ui.overlays = overlays);
+    }
+
+    /// Show or hide one candle chart layer
+    pub fn toggle_overlay(&self, overlay: Overlay) {
+        self.ui.update(|ui| ui.overlays.toggle(overlay));
+    }
+
+    /// Toggle the order book imbalance tint
+    pub fn toggle_imbalance_tint(&self) {
+        self.ui.update(|ui| ui.imbalance_tint = !ui.imbalance_tint);
+    }
+
+    /// Switch the order book total column between notional and cumulative
+    pub fn toggle_book_total(&self) {
+        self.ui.update(|ui| ui.book_total = ui.book_total.toggled());
+    }
+
+    /// Keep the order book centered on the spread, or scroll each side alone
+    pub fn toggle_book_anchor(&self) {
+        self.ui.update(|ui| ui.book_anchored = !ui.book_anchored);
+    }
+
+    /// Switch the depth chart's y-axis between quantity and notional
+    pub fn toggle_depth_axis(&self) {
+        self.ui.update(|ui| ui.depth_axis = ui.depth_axis.toggled());
+    }
+
+    /// Switch the depth chart between a shared scale and per-side percentages
+    pub fn toggle_depth_scale(&self) {
+        self.ui.update(|ui| ui.depth_scale = ui.depth_scale.toggled());
+    }
+
+    /// Choose how trade tape rows are shaded by value
+    pub fn set_tape_shading(&self, shading: TapeShading) {
+        self.ui.update(|ui| ui.tape_shading = shading);
+    }
+
+    /// Show or hide the running volume column on the trade tape
+    pub fn toggle_tape_volume(&self) {
+        self.ui.update(|ui| ui.tape_volume = !ui.tape_volume);
+    }
+
+    /// Choose the window within which repeat prints collapse on the tape
+    pub fn set_tape_collapse(&self, collapse: TapeCollapse) {
+        self.ui.update(|ui| ui.tape_collapse = collapse);
+    }
+
+    /// Show the registered custom panel `id`, or none
+    pub fn set_custom_panel(&self, id: Option<&'static str>) {
+        self.ui.update(|ui| ui.custom_panel = id);
+    }
+
+    /// Show or hide the microprice and depth-weighted mid in the ticker bar
+    pub fn toggle_book_prices(&self) {
+        self.ui.update(|ui| ui.book_prices = !ui.book_prices);
+    }
+
+    /// Show or hide a session's open markers on the candle chart
+    pub fn toggle_session_marker(&self, session: MarketSession) {
+        self.ui.update(|ui| ui.sessions.toggle(session));
+    }
+
+    /// Replace the shaded chart ranges from comma-separated text
+    pub fn set_chart_shading(&self, text: &str) {
+        self.shading.update(|s| s.set_text(text));
+    }
+
     // ========================================================================
     // Loading State
     // ========================================================================
@@ -255,6 +973,16 @@ impl Panel {
         }
     }
 
+    /// Localized panel title
+    pub fn msg(&self) -> Msg {
+        match self {
+            Self::OrderBook => Msg::OrderBook,
+            Self::Trades => Msg::RecentTrades,
+            Self::DepthChart => Msg::MarketDepth,
+            Self::CandleChart => Msg::Chart,
+        }
+    }
+
     pub fn all() -> &'static [Self] {
         &[Self::OrderBook, Self::Trades, Self::DepthChart, Self::CandleChart]
     }
@@ -268,6 +996,16 @@ impl Panel {
 pub fn provide_app_state() -> AppState {
     let state = AppState::new();
     provide_context(state.clone());
+
+    // Symbol pickers publish a selection; the market follows it
+    let market = state.market.clone();
+    state.events.subscribe(Some(DashEventKind::SymbolSelected), move |event| {
+        if let DashEvent::SymbolSelected(symbol) = event
+            && market.symbol.get_untracked() != *symbol
+        {
+            market.set_symbol(symbol.clone());
+        }
+    });
     state
 }
 
@@ -279,4 +1017,9 @@ pub fn use_app_state() -> AppState {
 /// Try to get app state from context (returns None if not provided)
 pub fn try_use_app_state() -> Option<AppState> {
     use_context::<AppState>()
+}
+
+/// UI locale from context; components mounted without app state use the default
+pub fn use_locale() -> Signal<Locale> {
+    try_use_app_state().map_or_else(|| Signal::stored(Locale::default()), |state| state.locale())
 }
\ No newline at end of file
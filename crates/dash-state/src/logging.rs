@@ -0,0 +1,295 @@
+//! Client log ring buffer
+//!
+//! [`RingLayer`] keeps the latest log lines in memory so the log panel can
+//! show them without the browser console. Each line carries the spans it was
+//! logged in (the WebSocket connection attempt, message dispatch, compute
+//! jobs) with their fields.
+
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::sync::{Mutex, MutexGuard};
+
+use tracing::field::{Field, Visit};
+use tracing::{span, Event, Level, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Lines kept; older ones are dropped
+pub const MAX_CLIENT_LOG: usize = 500;
+
+/// Severity of a log line, most severe first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Error => "ERROR",
+            Self::Warn => "WARN",
+            Self::Info => "INFO",
+            Self::Debug => "DEBUG",
+            Self::Trace => "TRACE",
+        }
+    }
+
+    pub fn all() -> &'static [Self] {
+        &[Self::Error, Self::Warn, Self::Info, Self::Debug, Self::Trace]
+    }
+
+    pub fn from_level(level: &Level) -> Self {
+        match *level {
+            Level::ERROR => Self::Error,
+            Level::WARN => Self::Warn,
+            Level::INFO => Self::Info,
+            Level::DEBUG => Self::Debug,
+            _ => Self::Trace,
+        }
+    }
+
+    /// Whether a line at `self` shows when filtering for `filter` and above
+    pub fn passes(&self, filter: LogLevel) -> bool {
+        *self <= filter
+    }
+}
+
+/// One log line
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    /// Local Unix millis
+    pub timestamp: i64,
+    pub level: LogLevel,
+    pub target: String,
+    /// Message followed by the event's fields as `name=value`
+    pub message: String,
+    /// Enclosing spans, outermost first: `name{fields}>name`
+    pub spans: String,
+}
+
+/// Most recent log lines
+#[derive(Debug)]
+pub struct LogRing {
+    entries: VecDeque<LogEntry>,
+    capacity: usize,
+    /// Lines ever pushed, so readers can tell when something changed
+    total: u64,
+}
+
+impl LogRing {
+    pub const fn new(capacity: usize) -> Self {
+        Self { entries: VecDeque::new(), capacity, total: 0 }
+    }
+
+    pub fn push(&mut self, entry: LogEntry) {
+        if self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+        self.total += 1;
+    }
+
+    /// Lines at `filter` or more severe, oldest first
+    pub fn entries(&self, filter: LogLevel) -> Vec<LogEntry> {
+        self.entries.iter().filter(|e| e.level.passes(filter)).cloned().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+static CLIENT_LOG: Mutex<LogRing> = Mutex::new(LogRing::new(MAX_CLIENT_LOG));
+
+/// The ring [`RingLayer::new`] writes to
+pub fn client_log() -> MutexGuard<'static, LogRing> {
+    CLIENT_LOG.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+// ============================================================================
+// LAYER
+// ============================================================================
+
+/// Formatted fields of a span, kept in its extensions
+struct SpanFields(String);
+
+/// Writes fields as `name=value`, keeping the message apart
+#[derive(Default)]
+struct FieldWriter {
+    message: String,
+    fields: String,
+}
+
+impl FieldWriter {
+    fn field(&mut self, field: &Field, value: std::fmt::Arguments<'_>) {
+        if field.name() == "message" {
+            let _ = self.message.write_fmt(value);
+            return;
+        }
+        if !self.fields.is_empty() {
+            self.fields.push(' ');
+        }
+        let _ = write!(self.fields, "{}={}", field.name(), value);
+    }
+}
+
+impl Visit for FieldWriter {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.field(field, format_args!("{}", value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.field(field, format_args!("{:?}", value));
+    }
+}
+
+/// Tracing layer feeding a [`LogRing`]
+pub struct RingLayer {
+    ring: &'static Mutex<LogRing>,
+    max_level: LogLevel,
+}
+
+impl RingLayer {
+    /// Keep lines at `max_level` and above in [`client_log`]
+    pub fn new(max_level: LogLevel) -> Self {
+        Self::with_ring(&CLIENT_LOG, max_level)
+    }
+
+    pub fn with_ring(ring: &'static Mutex<LogRing>, max_level: LogLevel) -> Self {
+        Self { ring, max_level }
+    }
+}
+
+impl<S> Layer<S> for RingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let mut writer = FieldWriter::default();
+        attrs.record(&mut writer);
+        span.extensions_mut().insert(SpanFields(writer.fields));
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let mut writer = FieldWriter::default();
+        values.record(&mut writer);
+        if let Some(fields) = span.extensions_mut().get_mut::<SpanFields>() {
+            if !fields.0.is_empty() && !writer.fields.is_empty() {
+                fields.0.push(' ');
+            }
+            fields.0.push_str(&writer.fields);
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let meta = event.metadata();
+        let level = LogLevel::from_level(meta.level());
+        if !level.passes(self.max_level) {
+            return;
+        }
+
+        let mut writer = FieldWriter::default();
+        event.record(&mut writer);
+        let mut message = writer.message;
+        if !writer.fields.is_empty() {
+            if !message.is_empty() {
+                message.push(' ');
+            }
+            message.push_str(&writer.fields);
+        }
+
+        let mut spans = String::new();
+        for span in ctx.event_scope(event).into_iter().flat_map(|scope| scope.from_root()) {
+            if !spans.is_empty() {
+                spans.push('>');
+            }
+            spans.push_str(span.name());
+            if let Some(fields) = span.extensions().get::<SpanFields>()
+                && !fields.0.is_empty()
+            {
+                let _ = write!(spans, "{{{}}}", fields.0);
+            }
+        }
+
+        let entry = LogEntry {
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            level,
+            target: meta.target().to_string(),
+            message,
+            spans,
+        };
+        self.ring.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).push(entry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    fn entry(level: LogLevel, message: &str) -> LogEntry {
+        LogEntry {
+            timestamp: 0,
+            level,
+            target: "test".into(),
+            message: message.into(),
+            spans: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_ring_drops_oldest() {
+        let mut ring = LogRing::new(2);
+        ring.push(entry(LogLevel::Info, "a"));
+        ring.push(entry(LogLevel::Warn, "b"));
+        ring.push(entry(LogLevel::Debug, "c"));
+        assert_eq!(ring.len(), 2);
+        assert_eq!(ring.total(), 3);
+
+        let messages = |filter| -> Vec<String> {
+            ring.entries(filter).into_iter().map(|e| e.message).collect()
+        };
+        assert_eq!(messages(LogLevel::Trace), vec!["b", "c"]);
+        assert_eq!(messages(LogLevel::Info), vec!["b"]);
+    }
+
+    #[test]
+    fn test_layer_records_spans_and_fields() {
+        static RING: Mutex<LogRing> = Mutex::new(LogRing::new(10));
+        let subscriber =
+            tracing_subscriber::registry().with(RingLayer::with_ring(&RING, LogLevel::Debug));
+        tracing::subscriber::with_default(subscriber, || {
+            let _conn = tracing::info_span!("ws_connection", attempt = 2).entered();
+            let _dispatch = tracing::debug_span!("dispatch").entered();
+            tracing::warn!(kind = "ticker", "Failed to parse message");
+            tracing::trace!("too verbose");
+        });
+
+        let ring = RING.lock().unwrap();
+        let entries = ring.entries(LogLevel::Trace);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].level, LogLevel::Warn);
+        assert_eq!(entries[0].message, "Failed to parse message kind=ticker");
+        assert_eq!(entries[0].spans, "ws_connection{attempt=2}>dispatch");
+    }
+}
@@ -1,11 +1,58 @@
 //! Reactive market data state with fine-grained signal updates
 
-use crate::{MAX_CANDLES, MAX_TRADES};
+use crate::{
+    BookHistory, Feed, PendingUpdate, ReplayBuffer, ReplayMode, TradeWindows, MAX_BOOK_HISTORY,
+    MAX_CANDLES, MAX_TRADES,
+};
+#[cfg(feature = "indicators")]
+use crate::{Compute, ComputeRequest, ComputeResponse};
 use dash_core::{
-    Candle, CandleHistory, CandleInterval, MarketDepth, OrderBookSnapshot,
-    Symbol, Ticker, Trade, TradeSide,
+    AlertInputs, Bbo, Candle, CandleDelta, CandleHistory, CandleIntegrityReport, CandleInterval,
+    ConsolidatedBook, Ewma, MarketDepth, NeighborRatioDetector, OrderBookSnapshot, RingBuffer,
+    RollingStats, Symbol, Ticker, Trade, TradeAggregation, TradeSide, WallTracker,
+    DEFAULT_BOOK_PRICE_LEVELS, DEFAULT_DEPTH_LEVELS,
 };
+#[cfg(feature = "indicators")]
+use dash_core::{IndicatorJob, IndicatorKind, IndicatorSet};
 use leptos::prelude::*;
+use std::sync::Arc;
+
+/// Which venue's book and tape the dashboard shows
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum VenueView {
+    /// Merged book across venues; trades from every venue
+    #[default]
+    Consolidated,
+    /// A single exchange
+    Venue(String),
+}
+
+impl VenueView {
+    /// Whether a venue-attributed book or trade belongs in this view.
+    /// Unattributed data (single-venue servers, snapshots) is always shown.
+    pub fn shows(&self, venue: Option<&str>) -> bool {
+        match (self, venue) {
+            (_, None) | (Self::Consolidated, _) => true,
+            (Self::Venue(selected), Some(venue)) => selected == venue,
+        }
+    }
+
+    /// Venue-attributed books replace the book in this view
+    /// (the consolidated view is fed by merged books instead)
+    pub fn shows_book(&self, venue: Option<&str>) -> bool {
+        match self {
+            Self::Consolidated => venue.is_none(),
+            Self::Venue(_) => self.shows(venue),
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            Self::Consolidated => "Consolidated",
+            Self::Venue(venue) => venue,
+        }
+    }
+}
 
 /// Reactive market state for a single symbol
 #[derive(Clone)]
@@ -15,15 +62,42 @@ pub struct MarketState {
     /// Current ticker data
     pub ticker: RwSignal<Option<Ticker>>,
     /// Order book snapshot
-    pub orderbook: RwSignal<Option<OrderBookSnapshot>>,
+    pub orderbook: RwSignal<Option<Arc<OrderBookSnapshot>>>,
     /// Market depth (derived from orderbook)
-    pub depth: RwSignal<Option<MarketDepth>>,
-    /// Recent trades (most recent first)
-    pub trades: RwSignal<Vec<Trade>>,
+    pub depth: RwSignal<Option<Arc<MarketDepth>>>,
+    /// Liquidity walls tracked across orderbook snapshots
+    pub walls: RwSignal<WallTracker>,
+    /// Recent orderbook snapshots for time-indexed lookups
+    pub book_history: RwSignal<BookHistory>,
+    /// Live or paused on a buffered moment
+    pub replay: RwSignal<ReplayMode>,
+    /// Data frozen / queued while replay is paused
+    replay_buffer: StoredValue<ReplayBuffer>,
+    /// Recent trades (oldest first; use `newest_first()` for tape order)
+    pub trades: RwSignal<RingBuffer<Trade>>,
+    /// Rolling 1m/5m/15m aggregations of live trades (frozen while paused)
+    pub trade_windows: RwSignal<TradeWindows>,
     /// Candlestick history
     pub candles: RwSignal<CandleHistory>,
+    /// Indicators over the candles, updated incrementally as candles tick;
+    /// full recomputes fill in from `compute`
+    #[cfg(feature = "indicators")]
+    pub indicators: RwSignal<IndicatorSet>,
+    /// Worker pool (or inline fallback) for expensive derived data
+    #[cfg(feature = "indicators")]
+    pub compute: Compute,
     /// Current candle interval
     pub interval: RwSignal<CandleInterval>,
+    /// Book levels per side negotiated with the server
+    pub depth_levels: RwSignal<usize>,
+    /// Levels per side weighed by the microprice and depth-weighted mid
+    pub book_price_levels: RwSignal<usize>,
+    /// Consolidated or single-venue view
+    pub venue_view: RwSignal<VenueView>,
+    /// Venues seen in consolidated books (sorted)
+    pub venues: RwSignal<Vec<String>>,
+    /// Best bid/offer across venues from the latest consolidated book
+    pub bbo: RwSignal<Option<Bbo>>,
     /// Last update timestamps
     pub last_update: LastUpdateSignals,
 }
@@ -57,9 +131,23 @@ impl MarketState {
             ticker: RwSignal::new(None),
             orderbook: RwSignal::new(None),
             depth: RwSignal::new(None),
-            trades: RwSignal::new(Vec::with_capacity(MAX_TRADES)),
-            candles: RwSignal::new(CandleHistory::new(symbol, CandleInterval::M1)),
+            walls: RwSignal::new(WallTracker::new()),
+            book_history: RwSignal::new(BookHistory::new(MAX_BOOK_HISTORY)),
+            replay: RwSignal::new(ReplayMode::Live),
+            replay_buffer: StoredValue::new(ReplayBuffer::default()),
+            trades: RwSignal::new(RingBuffer::new(MAX_TRADES)),
+            trade_windows: RwSignal::new(TradeWindows::new(symbol.clone())),
+            candles: RwSignal::new(CandleHistory::with_capacity(symbol, CandleInterval::M1, MAX_CANDLES)),
+            #[cfg(feature = "indicators")]
+            indicators: RwSignal::new(IndicatorSet::new()),
+            #[cfg(feature = "indicators")]
+            compute: Compute::new(),
             interval: RwSignal::new(CandleInterval::M1),
+            depth_levels: RwSignal::new(DEFAULT_DEPTH_LEVELS),
+            book_price_levels: RwSignal::new(DEFAULT_BOOK_PRICE_LEVELS),
+            venue_view: RwSignal::new(VenueView::default()),
+            venues: RwSignal::new(Vec::new()),
+            bbo: RwSignal::new(None),
             last_update: LastUpdateSignals::new(),
         }
     }
@@ -70,6 +158,9 @@ impl MarketState {
 
     /// Update ticker data
     pub fn update_ticker(&self, ticker: Ticker) {
+        if self.queue_if_paused(|| PendingUpdate::Ticker(ticker.clone())) {
+            return;
+        }
         self.last_update.ticker.set(ticker.timestamp);
         self.ticker.set(Some(ticker));
     }
@@ -85,26 +176,72 @@ impl MarketState {
 
     /// Update order book snapshot
     pub fn update_orderbook(&self, book: OrderBookSnapshot) {
+        // Shared by the signal and the history buffer; readers never deep-clone it
+        let book = Arc::new(book);
+        if !self.replay.get_untracked().is_live() {
+            // Keep extending the timeline; displayed book stays frozen
+            self.book_history.update(|history| history.push(book));
+            return;
+        }
+        self.apply_orderbook(book);
+    }
+
+    /// Merged cross-venue book; displayed when the consolidated view is active
+    pub fn update_consolidated(&self, book: ConsolidatedBook) {
+        if self.venues.with_untracked(|venues| *venues != book.venues) {
+            self.venues.set(book.venues.clone());
+        }
+        self.bbo.set(Some(book.bbo.clone()));
+        if self.venue_view.with_untracked(|view| *view == VenueView::Consolidated) {
+            self.update_orderbook(book.to_snapshot());
+        }
+    }
+
+    /// Switch between the consolidated and single-venue views
+    pub fn set_venue_view(&self, view: VenueView) {
+        if self.venue_view.get_untracked() == view {
+            return;
+        }
+        // Drop data from the previous view; the next update refills it
+        self.trades.update(|trades| trades.retain(|t| view.shows(t.venue.as_deref())));
+        self.venue_view.set(view);
+    }
+
+    /// Update server-computed depth (ignored while replay is paused)
+    pub fn update_depth(&self, depth: MarketDepth) {
+        if self.replay.get_untracked().is_live() {
+            self.depth.set(Some(Arc::new(depth)));
+        }
+    }
+
+    fn apply_orderbook(&self, book: Arc<OrderBookSnapshot>) {
         // Derive market depth from order book
         let depth = MarketDepth::from_orderbook(&book);
         self.last_update.orderbook.set(book.timestamp);
-        self.depth.set(Some(depth));
+        self.depth.set(Some(Arc::new(depth)));
+        self.walls.update(|walls| walls.update(&book, &NeighborRatioDetector::default()));
+        self.book_history.update(|history| history.push(Arc::clone(&book)));
         self.orderbook.set(Some(book));
     }
 
+    /// Order book as it was at `timestamp` (ms), if still buffered
+    pub fn orderbook_at(&self, timestamp: i64) -> Option<Arc<OrderBookSnapshot>> {
+        self.book_history.with(|history| history.at(timestamp).cloned())
+    }
+
     /// Get current mid price (from orderbook)
     pub fn mid_price(&self) -> Option<f64> {
-        self.orderbook.get().as_ref().and_then(|b| b.mid_price())
+        self.orderbook.with(|b| b.as_ref().and_then(|b| b.mid_price()))
     }
 
     /// Get current spread (from orderbook)
     pub fn spread(&self) -> Option<f64> {
-        self.orderbook.get().as_ref().and_then(|b| b.spread())
+        self.orderbook.with(|b| b.as_ref().and_then(|b| b.spread()))
     }
 
     /// Get order book imbalance
     pub fn imbalance(&self) -> f64 {
-        self.orderbook.get().map_or(0.0, |b| b.imbalance())
+        self.orderbook.with(|b| b.as_ref().map_or(0.0, |b| b.imbalance()))
     }
 
     // ========================================================================
@@ -113,13 +250,12 @@ impl MarketState {
 
     /// Add single trade to history
     pub fn add_trade(&self, trade: Trade) {
+        if self.queue_if_paused(|| PendingUpdate::Trade(trade.clone())) {
+            return;
+        }
         self.last_update.trade.set(trade.timestamp.timestamp_millis());
-        self.trades.update(|trades| {
-            trades.insert(0, trade);
-            if trades.len() > MAX_TRADES {
-                trades.pop();
-            }
-        });
+        self.trade_windows.update(|windows| windows.push(&trade));
+        self.trades.update(|trades| trades.push(trade));
     }
 
     /// Add batch of trades
@@ -128,26 +264,42 @@ impl MarketState {
             return;
         }
 
+        if !self.replay.get_untracked().is_live() {
+            self.replay_buffer.update_value(|buffer| {
+                for trade in new_trades {
+                    buffer.push(PendingUpdate::Trade(trade));
+                }
+            });
+            return;
+        }
+
         if let Some(first) = new_trades.first() {
             self.last_update.trade.set(first.timestamp.timestamp_millis());
         }
 
-        self.trades.update(|trades| {
-            for trade in new_trades {
-                trades.insert(0, trade);
+        self.trade_windows.update(|windows| {
+            for trade in &new_trades {
+                windows.push(trade);
             }
-            trades.truncate(MAX_TRADES);
         });
+        self.trades.update(|trades| trades.extend(new_trades));
+    }
+
+    /// Age trades out of the rolling windows when no new ones arrive
+    pub fn expire_trade_windows(&self, now_ms: i64) {
+        if self.replay.get_untracked().is_live() {
+            self.trade_windows.maybe_update(|windows| windows.expire(now_ms));
+        }
     }
 
     /// Get latest trade
     pub fn latest_trade(&self) -> Option<Trade> {
-        self.trades.get().first().cloned()
+        self.trades.with(|trades| trades.latest().cloned())
     }
 
     /// Get recent N trades
     pub fn recent_trades(&self, n: usize) -> Vec<Trade> {
-        self.trades.get().iter().take(n).cloned().collect()
+        self.trades.with(|trades| trades.recent(n).cloned().collect())
     }
 
     // ========================================================================
@@ -156,27 +308,20 @@ impl MarketState {
 
     /// Update or add candle
     pub fn update_candle(&self, candle: Candle) {
+        if self.queue_if_paused(|| PendingUpdate::Candle(candle.clone())) {
+            return;
+        }
         self.last_update.candle.set(candle.timestamp);
-        self.candles.update(|history| {
-            // Check if we should update existing candle or add new one
-            if let Some(last) = history.candles.last_mut() {
-                if last.timestamp == candle.timestamp && !last.is_closed {
-                    // Update existing candle
-                    *last = candle;
-                    return;
-                }
-            }
-            // Add new candle
-            history.candles.push(candle);
-            // Maintain max size
-            if history.candles.len() > MAX_CANDLES {
-                history.candles.remove(0);
-            }
-        });
+        let mut delta = CandleDelta::Rejected;
+        self.candles.update(|history| delta = merge_candle(history, candle));
+        self.feed_indicators(delta);
     }
 
     /// Set full candle history (bulk load)
     pub fn set_candles(&self, candles: Vec<Candle>) {
+        if self.queue_if_paused(|| PendingUpdate::Candles(candles.clone())) {
+            return;
+        }
         if let Some(last) = candles.last() {
             self.last_update.candle.set(last.timestamp);
         }
@@ -187,8 +332,145 @@ impl MarketState {
         self.candles.update(|history| {
             history.symbol = symbol;
             history.interval = interval;
-            history.candles = candles;
+            history.candles = candles.into();
+            let dropped = history.sanitize();
+            if dropped > 0 {
+                tracing::warn!("Dropped {} duplicate candles from bulk load", dropped);
+            }
+            history.fill_gaps();
+            history.candles.set_capacity(MAX_CANDLES);
         });
+        self.feed_indicators(CandleDelta::Reloaded);
+    }
+
+    /// Merge candle history fetched from the server over the displayed
+    /// candles (see `CandleHistory::reconcile`)
+    pub fn reconcile_candles(&self, fresh: Vec<Candle>) {
+        if self.queue_if_paused(|| PendingUpdate::FetchedCandles(fresh.clone())) {
+            return;
+        }
+        if let Some(last) = fresh.last() {
+            self.last_update.candle.update(|ts| *ts = (*ts).max(last.timestamp));
+        }
+        self.candles.update(|history| {
+            let replaced = history.reconcile(fresh);
+            tracing::debug!(replaced, "reconciled candle history");
+            history.fill_gaps();
+        });
+        self.feed_indicators(CandleDelta::Reloaded);
+    }
+
+    /// Show cached candles for the current symbol and interval; candles that
+    /// already arrived live take precedence
+    pub fn hydrate_candles(&self, cached: Vec<Candle>) {
+        if cached.is_empty() || !self.replay.get_untracked().is_live() {
+            return;
+        }
+        self.candles.update(|history| {
+            let live: Vec<Candle> = history.candles.iter().cloned().collect();
+            history.candles.update_vec(|candles| *candles = cached);
+            history.sanitize();
+            history.reconcile(live);
+            history.fill_gaps();
+        });
+        self.feed_indicators(CandleDelta::Reloaded);
+    }
+
+    /// Newest server timestamp across the feeds (0 before any data)
+    pub fn last_data_at(&self) -> i64 {
+        let updates = &self.last_update;
+        [updates.ticker, updates.orderbook, updates.trade, updates.candle]
+            .iter()
+            .map(|signal| signal.get_untracked())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Millis since `feed` last delivered at `now_ms` (`None` before its first
+    /// update). Candles are stamped with their open time, so the current one
+    /// counts as fresh until its interval has passed.
+    pub fn feed_age(&self, feed: Feed, now_ms: i64) -> Option<i64> {
+        let updates = &self.last_update;
+        let (updated, allowance) = match feed {
+            Feed::Ticker => (updates.ticker.get(), 0),
+            Feed::OrderBook => (updates.orderbook.get(), 0),
+            Feed::Trades => (updates.trade.get(), 0),
+            Feed::Candles => (updates.candle.get(), self.interval.get().as_millis()),
+        };
+        (updated > 0).then(|| (now_ms - updated - allowance).max(0))
+    }
+
+    /// Compute `kind` over the candles from now on
+    #[cfg(feature = "indicators")]
+    pub fn add_indicator(&self, kind: IndicatorKind) {
+        let mut job = None;
+        self.candles.with_untracked(|history| {
+            self.indicators.update(|set| job = set.add_deferred(kind, &history.candles[..]));
+        });
+        self.compute_indicators(job);
+    }
+
+    #[cfg(feature = "indicators")]
+    pub fn remove_indicator(&self, kind: IndicatorKind) {
+        self.indicators.update(|set| set.remove(&kind));
+    }
+
+    /// Bring the indicators in line with a candle history change
+    #[cfg(feature = "indicators")]
+    fn feed_indicators(&self, delta: CandleDelta) {
+        if delta == CandleDelta::Rejected || self.indicators.with_untracked(|set| set.is_empty()) {
+            return;
+        }
+        let mut job = None;
+        self.candles.with_untracked(|history| {
+            self.indicators.update(|set| job = set.apply_deferred(delta, &history.candles[..]));
+        });
+        self.compute_indicators(job);
+    }
+
+    /// Fill in the indicator values at older candles once `job` is done
+    #[cfg(feature = "indicators")]
+    fn compute_indicators(&self, job: Option<IndicatorJob>) {
+        let Some(job) = job else { return };
+        let indicators = self.indicators;
+        self.compute.run(ComputeRequest::Indicators(job), move |response| {
+            let ComputeResponse::Indicators(output) = response;
+            indicators.try_update(|set| set.complete(output));
+        });
+    }
+
+    #[cfg(not(feature = "indicators"))]
+    fn feed_indicators(&self, _delta: CandleDelta) {}
+
+    /// Run `f` on the live values alert rules read: the last print (or the
+    /// ticker's last price), the top of the book (or the ticker's quote) and
+    /// the candle history
+    pub fn with_alert_inputs<R>(&self, f: impl FnOnce(&AlertInputs<'_>) -> R) -> R {
+        let ticker = self.ticker.get();
+        let price = self.trades.with(|trades| trades.latest().map(|t| t.price.as_f64()));
+        let book = self.orderbook.with(|book| {
+            let book = book.as_ref()?;
+            Some((book.best_bid()?.price.as_f64(), book.best_ask()?.price.as_f64()))
+        });
+        let quote =
+            book.or_else(|| ticker.as_ref().map(|t| (t.bid_price.as_f64(), t.ask_price.as_f64())));
+        self.candles.with(|history| {
+            f(&AlertInputs {
+                price: price.or_else(|| ticker.as_ref().map(|t| t.last_price.as_f64())),
+                bid: quote.map(|(bid, _)| bid),
+                ask: quote.map(|(_, ask)| ask),
+                change_pct: ticker.as_ref().map(|t| t.change_percent_24h),
+                high_24h: ticker.as_ref().map(|t| t.high_24h.as_f64()),
+                low_24h: ticker.as_ref().map(|t| t.low_24h.as_f64()),
+                volume_24h: ticker.as_ref().map(|t| t.volume_24h.as_f64()),
+                candles: &history.candles[..],
+            })
+        })
+    }
+
+    /// Gap / ordering report for the displayed candle history
+    pub fn candle_integrity(&self) -> CandleIntegrityReport {
+        self.candles.with(|history| history.integrity_report())
     }
 
     // ========================================================================
@@ -197,19 +479,31 @@ impl MarketState {
 
     /// Change trading symbol (clears all data)
     pub fn set_symbol(&self, symbol: Symbol) {
+        self.discard_replay();
         self.symbol.set(symbol.clone());
         // Clear all market data
         self.ticker.set(None);
         self.orderbook.set(None);
         self.depth.set(None);
-        self.trades.set(Vec::new());
-        self.candles.set(CandleHistory::new(symbol, self.interval.get()));
+        self.walls.update(|walls| walls.clear());
+        self.book_history.update(|history| history.clear());
+        self.trades.update(|trades| trades.clear());
+        self.trade_windows.set(TradeWindows::new(symbol.clone()));
+        self.candles.set(CandleHistory::with_capacity(symbol, self.interval.get(), MAX_CANDLES));
+        self.feed_indicators(CandleDelta::Reloaded);
     }
 
-    /// Change candle interval (clears candle history)
+    /// Change candle interval (resamples cached candles when possible)
     pub fn set_interval(&self, interval: CandleInterval) {
+        self.discard_replay();
         self.interval.set(interval);
-        self.candles.set(CandleHistory::new(self.symbol.get(), interval));
+        // Coarser intervals render instantly from cached data until the server catches up
+        let resampled = self.candles.with_untracked(|history| history.resample(interval));
+        self.candles
+            .set(resampled.unwrap_or_else(|| {
+                CandleHistory::with_capacity(self.symbol.get(), interval, MAX_CANDLES)
+            }));
+        self.feed_indicators(CandleDelta::Reloaded);
     }
 
     // ========================================================================
@@ -218,17 +512,137 @@ impl MarketState {
 
     /// Clear all market data
     pub fn clear(&self) {
+        self.discard_replay();
         let symbol = self.symbol.get();
         let interval = self.interval.get();
 
         self.ticker.set(None);
         self.orderbook.set(None);
         self.depth.set(None);
-        self.trades.set(Vec::new());
-        self.candles.set(CandleHistory::new(symbol, interval));
+        self.walls.update(|walls| walls.clear());
+        self.book_history.update(|history| history.clear());
+        self.trades.update(|trades| trades.clear());
+        self.trade_windows.update(|windows| windows.clear());
+        self.candles.set(CandleHistory::with_capacity(symbol, interval, MAX_CANDLES));
+        self.feed_indicators(CandleDelta::Reloaded);
+    }
+
+    // ========================================================================
+    // Replay (time travel)
+    // ========================================================================
+
+    /// Freeze the display at the latest buffered moment
+    pub fn pause_live(&self) {
+        if !self.replay.get_untracked().is_live() {
+            return;
+        }
+        let at = self
+            .book_history
+            .with_untracked(|h| h.time_range().map(|(_, newest)| newest))
+            .unwrap_or_else(|| self.last_update.orderbook.get_untracked());
+
+        self.replay_buffer.set_value(ReplayBuffer::freeze(
+            self.trades.get_untracked(),
+            self.candles.get_untracked(),
+        ));
+        self.replay.set(ReplayMode::Paused { at });
+    }
+
+    /// Show book, trades and candles as of `timestamp` (ms), pausing if live
+    pub fn scrub_to(&self, timestamp: i64) {
+        self.pause_live();
+
+        if let Some(book) = self.book_history.with_untracked(|h| h.at(timestamp).cloned()) {
+            self.depth.set(Some(Arc::new(MarketDepth::from_orderbook(&book))));
+            self.orderbook.set(Some(book));
+        }
+        let (trades, candles) = self
+            .replay_buffer
+            .with_value(|b| (b.trades_at(timestamp), b.candles_at(timestamp)));
+        self.trades.set(trades);
+        if let Some(candles) = candles {
+            self.candles.set(candles);
+            self.feed_indicators(CandleDelta::Reloaded);
+        }
+        self.replay.set(ReplayMode::Paused { at: timestamp });
+    }
+
+    /// Leave replay and catch up on everything received while paused
+    pub fn resume_live(&self) {
+        if self.replay.get_untracked().is_live() {
+            return;
+        }
+        let (trades, candles, pending) = self
+            .replay_buffer
+            .try_update_value(|buffer| buffer.drain())
+            .unwrap_or_default();
+        self.replay.set(ReplayMode::Live);
+
+        self.trades.set(trades);
+        if let Some(candles) = candles {
+            self.candles.set(candles);
+            self.feed_indicators(CandleDelta::Reloaded);
+        }
+        if let Some(book) = self.book_history.with_untracked(|h| h.latest().cloned()) {
+            self.apply_orderbook(book);
+        }
+        for update in pending {
+            match update {
+                PendingUpdate::Ticker(t) => self.update_ticker(t),
+                PendingUpdate::Trade(t) => self.add_trade(t),
+                PendingUpdate::Candle(c) => self.update_candle(c),
+                PendingUpdate::Candles(c) => self.set_candles(c),
+                PendingUpdate::FetchedCandles(c) => self.reconcile_candles(c),
+            }
+        }
+    }
+
+    /// Queue an update instead of displaying it when replay is paused
+    fn queue_if_paused(&self, update: impl FnOnce() -> PendingUpdate) -> bool {
+        if self.replay.get_untracked().is_live() {
+            return false;
+        }
+        let update = update();
+        self.replay_buffer.update_value(|buffer| buffer.push(update));
+        true
+    }
+
+    /// Drop replay state without restoring (data is about to be reset)
+    fn discard_replay(&self) {
+        self.replay_buffer.set_value(ReplayBuffer::default());
+        self.replay.set(ReplayMode::Live);
     }
 }
 
+/// Merge a candle update into history (replace forming candle or append)
+pub(crate) fn merge_candle(history: &mut CandleHistory, candle: Candle) -> CandleDelta {
+    let mut gap = false;
+    if let Some(last) = history.candles.last_mut() {
+        // Check if we should update existing candle or add new one
+        if last.timestamp == candle.timestamp && !last.is_closed {
+            // Update existing candle
+            *last = candle;
+            return CandleDelta::ReplacedLast;
+        }
+        if candle.timestamp <= last.timestamp {
+            tracing::warn!(
+                "Rejected out-of-order candle {} (latest {})",
+                candle.timestamp,
+                last.timestamp
+            );
+            return CandleDelta::Rejected;
+        }
+        gap = candle.timestamp - last.timestamp > history.interval.as_millis();
+    }
+    // Add new candle (history capacity evicts the oldest)
+    history.candles.push(candle);
+    if gap {
+        history.fill_gaps();
+        return CandleDelta::Reloaded;
+    }
+    CandleDelta::Appended
+}
+
 impl Default for MarketState {
     fn default() -> Self {
         Self::new()
@@ -239,6 +653,12 @@ impl Default for MarketState {
 // COMPUTED SIGNALS
 // ============================================================================
 
+/// Weight of each new book in the smoothed imbalance
+pub const IMBALANCE_EWMA_ALPHA: f64 = 0.1;
+
+/// Trade-to-trade returns in the realized volatility estimate
+pub const VOLATILITY_WINDOW: usize = 50;
+
 /// Price direction indicator
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum PriceDirection {
@@ -274,6 +694,65 @@ impl PriceDirection {
     }
 }
 
+/// Most recent price from the ticker or the trade tape, with its tick direction
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LastPrice {
+    pub price: f64,
+    pub direction: PriceDirection,
+}
+
+impl LastPrice {
+    /// Follow `previous` to a new print; an unchanged price keeps the
+    /// previous direction
+    pub fn next(previous: Option<Self>, price: f64) -> Self {
+        let direction = match previous {
+            Some(p) if price > p.price => PriceDirection::Up,
+            Some(p) if price < p.price => PriceDirection::Down,
+            Some(p) => p.direction,
+            None => PriceDirection::Unchanged,
+        };
+        Self { price, direction }
+    }
+}
+
+/// Per-field ticker memos so views only re-render values that changed
+#[derive(Clone, Copy)]
+pub struct TickerFields {
+    pub last_price: Memo<Option<f64>>,
+    pub change_24h: Memo<Option<f64>>,
+    pub change_percent_24h: Memo<Option<f64>>,
+    pub high_24h: Memo<Option<f64>>,
+    pub low_24h: Memo<Option<f64>>,
+    pub volume_24h: Memo<Option<f64>>,
+    /// Absolute spread and spread as percent of mid
+    pub spread: Memo<Option<(f64, f64)>>,
+    /// Price direction over 24h
+    pub is_up: Memo<bool>,
+}
+
+impl TickerFields {
+    /// Create field memos from market state
+    pub fn new(state: &MarketState) -> Self {
+        let ticker = state.ticker;
+        let field = move |f: fn(&Ticker) -> f64| {
+            Memo::new(move |_| ticker.with(|t| t.as_ref().map(f)))
+        };
+
+        Self {
+            last_price: field(|t| t.last_price.as_f64()),
+            change_24h: field(|t| t.change_24h),
+            change_percent_24h: field(|t| t.change_percent_24h),
+            high_24h: field(|t| t.high_24h.as_f64()),
+            low_24h: field(|t| t.low_24h.as_f64()),
+            volume_24h: field(|t| t.volume_24h.as_f64()),
+            spread: Memo::new(move |_| {
+                ticker.with(|t| t.as_ref().map(|t| (t.spread(), t.spread_percent())))
+            }),
+            is_up: Memo::new(move |_| ticker.with(|t| t.as_ref().is_none_or(|t| t.is_up()))),
+        }
+    }
+}
+
 /// Computed market signals (derived from raw data)
 #[derive(Clone)]
 pub struct MarketComputed {
@@ -281,10 +760,26 @@ pub struct MarketComputed {
     pub price_direction: Memo<PriceDirection>,
     /// Order book imbalance (-1 to +1)
     pub imbalance: Memo<f64>,
+    /// Imbalance smoothed across book updates (EWMA)
+    pub imbalance_ewma: Memo<f64>,
     /// VWAP from recent trades
     pub vwap: Memo<f64>,
     /// Buy volume ratio (0 to 1)
     pub buy_ratio: Memo<f64>,
+    /// Newer of the ticker price and the last trade
+    pub last_price: Memo<Option<LastPrice>>,
+    /// Std dev of log returns across the last `VOLATILITY_WINDOW` trades
+    pub volatility: Memo<Option<f64>>,
+    /// Rolling trade aggregations over 1m, 5m and 15m
+    pub flow_1m: Memo<TradeAggregation>,
+    pub flow_5m: Memo<TradeAggregation>,
+    pub flow_15m: Memo<TradeAggregation>,
+    /// Plain mid of the best bid and ask
+    pub mid_price: Memo<Option<f64>>,
+    /// Size-weighted mid over `book_price_levels`
+    pub microprice: Memo<Option<f64>>,
+    /// Average of the bid and ask VWAPs over `book_price_levels`
+    pub weighted_mid: Memo<Option<f64>>,
 }
 
 impl MarketComputed {
@@ -293,6 +788,24 @@ impl MarketComputed {
         let ticker_signal = state.ticker;
         let orderbook_signal = state.orderbook;
         let trades_signal = state.trades;
+        let windows = state.trade_windows;
+        let levels = state.book_price_levels;
+        let book_price = move |f: fn(&OrderBookSnapshot, usize) -> Option<f64>| {
+            Memo::new(move |_| {
+                let levels = levels.get();
+                orderbook_signal.with(|b| b.as_ref().and_then(|b| f(b, levels)))
+            })
+        };
+        let flow = move |span: CandleInterval| {
+            Memo::new(move |_| windows.with(|w| w.get(span).cloned().unwrap_or_default()))
+        };
+
+        // Smoother state lives in the memo value so each book folds in once
+        let smoothed_imbalance = Memo::new(move |previous: Option<&Ewma>| {
+            let mut ewma = previous.copied().unwrap_or_else(|| Ewma::new(IMBALANCE_EWMA_ALPHA));
+            ewma.update(orderbook_signal.with(|b| b.as_ref().map_or(0.0, |b| b.imbalance())));
+            ewma
+        });
 
         Self {
             price_direction: Memo::new(move |_| {
@@ -308,9 +821,11 @@ impl MarketComputed {
             }),
 
             imbalance: Memo::new(move |_| {
-                orderbook_signal.get().map_or(0.0, |b| b.imbalance())
+                orderbook_signal.with(|b| b.as_ref().map_or(0.0, |b| b.imbalance()))
             }),
 
+            imbalance_ewma: Memo::new(move |_| smoothed_imbalance.with(|e| e.value().unwrap_or(0.0))),
+
             vwap: Memo::new(move |_| {
                 let trades = trades_signal.get();
                 if trades.is_empty() {
@@ -320,7 +835,7 @@ impl MarketComputed {
                 let mut total_value = 0.0;
                 let mut total_volume = 0.0;
 
-                for trade in trades.iter().take(50) {
+                for trade in trades.recent(50) {
                     total_value += trade.value();
                     total_volume += trade.quantity.as_f64();
                 }
@@ -338,11 +853,43 @@ impl MarketComputed {
                     return 0.5;
                 }
 
-                let recent: Vec<_> = trades.iter().take(50).collect();
+                let recent: Vec<_> = trades.recent(50).collect();
                 let buy_count = recent.iter().filter(|t| t.side == TradeSide::Buy).count();
 
                 buy_count as f64 / recent.len() as f64
             }),
+
+            last_price: Memo::new(move |previous: Option<&Option<LastPrice>>| {
+                let ticker = ticker_signal.with(|t| t.as_ref().map(|t| (t.timestamp, t.last_price.as_f64())));
+                let trade = trades_signal
+                    .with(|t| t.latest().map(|t| (t.timestamp.timestamp_millis(), t.price.as_f64())));
+                let (_, price) = ticker.into_iter().chain(trade).max_by_key(|(ts, _)| *ts)?;
+                Some(LastPrice::next(previous.copied().flatten(), price))
+            }),
+
+            volatility: Memo::new(move |_| {
+                trades_signal.with(|trades| {
+                    let mut stats = RollingStats::new(VOLATILITY_WINDOW);
+                    let recent = &trades[trades.len().saturating_sub(VOLATILITY_WINDOW + 1)..];
+                    for pair in recent.windows(2) {
+                        let (prev, next) = (pair[0].price.as_f64(), pair[1].price.as_f64());
+                        if prev > 0.0 && next > 0.0 {
+                            stats.push((next / prev).ln());
+                        }
+                    }
+                    stats.std_dev().filter(|_| stats.len() >= 2)
+                })
+            }),
+
+            flow_1m: flow(CandleInterval::M1),
+            flow_5m: flow(CandleInterval::M5),
+            flow_15m: flow(CandleInterval::M15),
+
+            mid_price: Memo::new(move |_| {
+                orderbook_signal.with(|b| b.as_ref().and_then(|b| b.mid_price()))
+            }),
+            microprice: book_price(OrderBookSnapshot::microprice),
+            weighted_mid: book_price(OrderBookSnapshot::depth_weighted_mid),
         }
     }
 }
@@ -351,6 +898,175 @@ impl MarketComputed {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_ticker_fields() {
+        let state = MarketState::new();
+        let fields = TickerFields::new(&state);
+        assert_eq!(fields.last_price.get_untracked(), None);
+
+        let mut ticker = Ticker::new(Symbol::new("BTC-USD"), 50_000.0);
+        ticker.high_24h = dash_core::Price::new(51_000.0);
+        state.update_ticker(ticker);
+
+        assert_eq!(fields.last_price.get_untracked(), Some(50_000.0));
+        assert_eq!(fields.high_24h.get_untracked(), Some(51_000.0));
+    }
+
+    #[test]
+    fn test_venue_view_filters() {
+        let venue = VenueView::Venue("kraken".into());
+        assert!(venue.shows(Some("kraken")));
+        assert!(!venue.shows(Some("okx")));
+        assert!(venue.shows(None));
+        assert!(venue.shows_book(Some("kraken")));
+
+        let consolidated = VenueView::Consolidated;
+        assert!(consolidated.shows(Some("okx")));
+        assert!(!consolidated.shows_book(Some("okx")));
+        assert!(consolidated.shows_book(None));
+    }
+
+    #[test]
+    fn test_imbalance_ewma() {
+        let state = MarketState::new();
+        let computed = MarketComputed::new(&state);
+        let book = |bid: f64, ask: f64| {
+            let mut book = OrderBookSnapshot::new(Symbol::new("BTC-USD"));
+            book.bids = vec![dash_core::OrderBookLevel::new(99.0, bid, 1)];
+            book.asks = vec![dash_core::OrderBookLevel::new(101.0, ask, 1)];
+            book
+        };
+
+        // The first book seeds the average, later ones are smoothed in
+        state.update_orderbook(book(3.0, 1.0));
+        assert_eq!(computed.imbalance_ewma.get_untracked(), 0.5);
+        state.update_orderbook(book(1.0, 3.0));
+        assert!((computed.imbalance_ewma.get_untracked() - 0.4).abs() < 1e-9);
+        assert_eq!(computed.imbalance.get_untracked(), -0.5);
+    }
+
+    #[test]
+    fn test_book_prices_follow_levels() {
+        let state = MarketState::new();
+        let computed = MarketComputed::new(&state);
+        let level = dash_core::OrderBookLevel::new;
+        let mut book = OrderBookSnapshot::new(Symbol::new("BTC-USD"));
+        book.bids = vec![level(99.0, 3.0, 1), level(98.0, 1.0, 1)];
+        book.asks = vec![level(101.0, 1.0, 1), level(102.0, 3.0, 1)];
+        state.update_orderbook(book);
+
+        state.book_price_levels.set(1);
+        assert_eq!(computed.mid_price.get_untracked(), Some(100.0));
+        assert_eq!(computed.microprice.get_untracked(), Some(100.5));
+        // Both sides hold 4 over two levels: balanced again
+        state.book_price_levels.set(2);
+        assert_eq!(computed.microprice.get_untracked(), Some(100.0));
+        assert_eq!(computed.weighted_mid.get_untracked(), Some((98.75 + 101.75) / 2.0));
+    }
+
+    #[test]
+    fn test_hydrate_and_reconcile_candles() {
+        let state = MarketState::new();
+        let candle = |i: i64, close: f64| {
+            let mut c = Candle::new(Symbol::default(), CandleInterval::M1, i * 60_000, close);
+            c.update(close, 1.0);
+            c
+        };
+        let closes = || -> Vec<f64> {
+            state.candles.with_untracked(|h| h.candles.iter().map(|c| c.close.as_f64()).collect())
+        };
+
+        // A live candle beat the cache: it wins over the cached copy
+        state.update_candle(candle(2, 9.0));
+        state.hydrate_candles(vec![candle(0, 1.0), candle(1, 1.0), candle(2, 1.0)]);
+        assert_eq!(closes(), vec![1.0, 1.0, 9.0]);
+
+        state.reconcile_candles(vec![candle(1, 2.0), candle(2, 2.0), candle(3, 2.0)]);
+        assert_eq!(closes(), vec![1.0, 2.0, 2.0, 2.0]);
+    }
+
+    #[test]
+    #[cfg(feature = "indicators")]
+    fn test_indicators_follow_candles() {
+        let state = MarketState::new();
+        let candle = |i: i64, close: f64| {
+            let mut c = Candle::new(Symbol::default(), CandleInterval::M1, i * 60_000, close);
+            c.update(close, 1.0);
+            c
+        };
+        let sma = || state.indicators.with_untracked(|set| set.values()[0].1);
+
+        state.add_indicator(IndicatorKind::Sma(2));
+        state.update_candle(candle(0, 10.0));
+        assert_eq!(sma(), None);
+        state.update_candle(candle(1, 20.0));
+        assert_eq!(sma(), Some(15.0));
+        // Ticks of the forming candle replace its close
+        state.update_candle(candle(1, 30.0));
+        assert_eq!(sma(), Some(20.0));
+        state.update_candle(candle(2, 40.0));
+        assert_eq!(sma(), Some(35.0));
+
+        state.set_candles(vec![candle(0, 1.0), candle(1, 3.0)]);
+        assert_eq!(sma(), Some(2.0));
+
+        // Without a worker pool the older values of a rebuild are filled in at once
+        assert!(!state.compute.is_offloaded());
+        state.set_candles(vec![candle(0, 1.0), candle(1, 3.0), candle(2, 5.0)]);
+        let series = state.candles.with_untracked(|history| {
+            state.indicators.with_untracked(|set| set.series(&history.candles[..]))
+        });
+        assert_eq!(series[0].1, vec![(60_000, 2.0), (120_000, 4.0)]);
+    }
+
+    #[test]
+    fn test_volatility() {
+        let state = MarketState::new();
+        let computed = MarketComputed::new(&state);
+        let trade = |price| Trade::new(Symbol::new("BTC-USD"), price, 1.0, TradeSide::Buy);
+
+        state.add_trade(trade(100.0));
+        state.add_trade(trade(100.0));
+        assert_eq!(computed.volatility.get_untracked(), None);
+
+        // Returns 0, +r, -r, +r
+        for price in [110.0, 100.0, 110.0] {
+            state.add_trade(trade(price));
+        }
+        let r = 1.1f64.ln();
+        let expected = (3.0 * r * r / 4.0 - r * r / 16.0).sqrt();
+        assert!((computed.volatility.get_untracked().unwrap() - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_last_price() {
+        let state = MarketState::new();
+        let computed = MarketComputed::new(&state);
+        assert_eq!(computed.last_price.get_untracked(), None);
+        assert_eq!(state.last_data_at(), 0);
+
+        let mut ticker = Ticker::new(Symbol::new("BTC-USD"), 100.0);
+        ticker.timestamp = 1_000;
+        state.update_ticker(ticker.clone());
+        let last = computed.last_price.get_untracked().unwrap();
+        assert_eq!((last.price, last.direction), (100.0, PriceDirection::Unchanged));
+
+        // A newer trade wins over the ticker
+        let mut trade = Trade::new(Symbol::new("BTC-USD"), 99.0, 1.0, TradeSide::Sell);
+        trade.timestamp = chrono::DateTime::from_timestamp_millis(2_000).unwrap();
+        state.add_trade(trade);
+        let last = computed.last_price.get_untracked().unwrap();
+        assert_eq!((last.price, last.direction), (99.0, PriceDirection::Down));
+
+        ticker.timestamp = 3_000;
+        ticker.last_price = dash_core::Price::new(99.0);
+        state.update_ticker(ticker);
+        assert_eq!(computed.last_price.get_untracked().unwrap().direction, PriceDirection::Down);
+        assert_eq!(state.last_data_at(), 3_000);
+        assert_eq!(state.feed_age(Feed::Ticker, 4_500), Some(1_500));
+        assert_eq!(state.feed_age(Feed::OrderBook, 4_500), None);
+    }
+
     #[test]
     fn test_price_direction() {
         assert_eq!(PriceDirection::Up.arrow(), "▲");
@@ -0,0 +1,122 @@
+//! News feed
+//!
+//! Items pushed by the server, newest kept, with the user's alert keywords.
+//! An item mentioning a keyword raises a dashboard alert when it arrives.
+
+use dash_core::{NewsItem, RingBuffer, Symbol};
+
+/// Items kept in the feed
+pub const MAX_NEWS: usize = 100;
+
+/// Longest keyword list kept
+pub const MAX_NEWS_KEYWORDS: usize = 20;
+
+/// Recent news and the keywords that raise alerts
+#[derive(Debug, Clone)]
+pub struct NewsFeed {
+    /// Oldest first
+    items: RingBuffer<NewsItem>,
+    keywords: Vec<String>,
+}
+
+impl Default for NewsFeed {
+    fn default() -> Self {
+        Self { items: RingBuffer::new(MAX_NEWS), keywords: Vec::new() }
+    }
+}
+
+impl NewsFeed {
+    /// Add `item` unless it is already in the feed (the server resends
+    /// recent news on reconnect); returns whether it was new
+    pub fn push(&mut self, item: NewsItem) -> bool {
+        if self.items.iter().any(|n| n.id == item.id) {
+            return false;
+        }
+        self.items.push(item);
+        true
+    }
+
+    /// Items for `symbol` (every item when `None`), newest first
+    pub fn items_for(&self, symbol: Option<&Symbol>) -> Vec<&NewsItem> {
+        self.items
+            .recent(self.items.len())
+            .filter(|item| symbol.is_none_or(|s| item.concerns(s)))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn keywords(&self) -> &[String] {
+        &self.keywords
+    }
+
+    /// Set the alert keywords from comma-separated text; blanks and repeats
+    /// are dropped
+    pub fn set_keywords(&mut self, text: &str) {
+        self.keywords.clear();
+        for keyword in text.split(',').map(str::trim).filter(|k| !k.is_empty()) {
+            let repeat = self.keywords.iter().any(|k| k.eq_ignore_ascii_case(keyword));
+            if !repeat && self.keywords.len() < MAX_NEWS_KEYWORDS {
+                self.keywords.push(keyword.to_string());
+            }
+        }
+    }
+
+    /// Keywords as the user edits them
+    pub fn keywords_text(&self) -> String {
+        self.keywords.join(", ")
+    }
+
+    /// Keyword `item` mentions, if any
+    pub fn alert_keyword(&self, item: &NewsItem) -> Option<&str> {
+        item.matched_keyword(&self.keywords)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: &str, headline: &str, symbols: &[&str]) -> NewsItem {
+        let mut item = NewsItem::new("test", headline)
+            .with_symbols(symbols.iter().map(|s| Symbol::new(*s)).collect());
+        item.id = id.to_string();
+        item
+    }
+
+    #[test]
+    fn test_news_feed() {
+        let mut feed = NewsFeed::default();
+        assert!(feed.push(item("1", "Maintenance tonight", &[])));
+        assert!(feed.push(item("2", "ETH deposits paused", &["ETH-USD"])));
+        assert!(!feed.push(item("2", "ETH deposits paused", &["ETH-USD"])));
+        assert_eq!(feed.len(), 2);
+
+        let ids = |symbol: Option<&str>| -> Vec<String> {
+            let symbol = symbol.map(Symbol::new);
+            feed.items_for(symbol.as_ref()).iter().map(|n| n.id.clone()).collect()
+        };
+        assert_eq!(ids(None), vec!["2", "1"]);
+        assert_eq!(ids(Some("BTC-USD")), vec!["1"]);
+        assert_eq!(ids(Some("ETH-USD")), vec!["2", "1"]);
+    }
+
+    #[test]
+    fn test_news_keywords() {
+        let mut feed = NewsFeed::default();
+        feed.set_keywords(" halt, , Paused,HALT ");
+        assert_eq!(feed.keywords(), ["halt", "Paused"]);
+        assert_eq!(feed.keywords_text(), "halt, Paused");
+        assert_eq!(feed.alert_keyword(&item("1", "ETH deposits paused", &[])), Some("Paused"));
+        assert_eq!(feed.alert_keyword(&item("2", "New listing", &[])), None);
+
+        feed.set_keywords("");
+        assert!(feed.keywords().is_empty());
+    }
+}
@@ -0,0 +1,108 @@
+//! Command palette
+//!
+//! Named actions registered on the event bus, and the fuzzy matching the
+//! palette uses to find them as the user types.
+
+use std::sync::Arc;
+
+/// An action the palette can run
+#[derive(Clone)]
+pub struct Command {
+    /// Stable identifier; registering the same id again replaces it
+    pub id: String,
+    /// What the palette lists, e.g. "Interval: 15m"
+    pub title: String,
+    action: Arc<dyn Fn() + Send + Sync>,
+}
+
+impl std::fmt::Debug for Command {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Command").field("id", &self.id).field("title", &self.title).finish()
+    }
+}
+
+impl Command {
+    pub fn new(
+        id: impl Into<String>,
+        title: impl Into<String>,
+        action: impl Fn() + Send + Sync + 'static,
+    ) -> Self {
+        Self { id: id.into(), title: title.into(), action: Arc::new(action) }
+    }
+
+    pub fn run(&self) {
+        (self.action)()
+    }
+}
+
+/// How well `query` matches `text`: every query character must appear in
+/// order (case-insensitive). Runs of consecutive characters and matches at
+/// word starts score higher. `None` when `query` does not match.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+    let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous: Option<usize> = None;
+    for wanted in query.chars().flat_map(char::to_lowercase).filter(|c| !c.is_whitespace()) {
+        let at = next + text[next..].iter().position(|c| *c == wanted)?;
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == at) {
+            score += 4;
+        }
+        if at == 0 || !text[at - 1].is_alphanumeric() {
+            score += 3;
+        }
+        previous = Some(at);
+        next = at + 1;
+    }
+    Some(score)
+}
+
+/// Commands matching `query`, best first; ties keep registration order.
+/// An empty query lists everything.
+pub fn search_commands<'a>(query: &str, commands: &'a [Command]) -> Vec<&'a Command> {
+    let mut matches: Vec<(u32, &Command)> = commands
+        .iter()
+        .filter_map(|command| fuzzy_score(query, &command.title).map(|score| (score, command)))
+        .collect();
+    matches.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    matches.into_iter().map(|(_, command)| command).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("", "Interval: 15m"), Some(0));
+        assert!(fuzzy_score("int15", "Interval: 15m").is_some());
+        assert!(fuzzy_score("INT", "interval").is_some());
+        assert_eq!(fuzzy_score("15x", "Interval: 15m"), None);
+        // Out of order
+        assert_eq!(fuzzy_score("mi", "Interval: 15m"), None);
+
+        // Consecutive and word-start matches rank higher
+        let run = fuzzy_score("exp", "Export session log").unwrap();
+        let scattered = fuzzy_score("exp", "Expand panel: Depth").unwrap();
+        let loose = fuzzy_score("exp", "Next symbol: ETH-USD").unwrap_or(0);
+        assert_eq!(run, scattered);
+        assert!(run > loose);
+    }
+
+    #[test]
+    fn test_search_commands() {
+        let commands = vec![
+            Command::new("interval-1m", "Interval: 1m", || {}),
+            Command::new("theme", "Toggle theme", || {}),
+            Command::new("interval-1h", "Interval: 1H", || {}),
+        ];
+        let ids = |query| -> Vec<&str> {
+            search_commands(query, &commands).iter().map(|c| c.id.as_str()).collect()
+        };
+        assert_eq!(ids(""), vec!["interval-1m", "theme", "interval-1h"]);
+        assert_eq!(ids("1h"), vec!["interval-1h"]);
+        assert_eq!(ids("tt"), vec!["theme"]);
+        assert!(ids("zz").is_empty());
+    }
+}
@@ -0,0 +1,120 @@
+//! Custom panels
+//!
+//! Downstream crates implement `DashPanel` and register it on the app state
+//! before the dashboard mounts. Registered panels are offered in the status
+//! bar's panel picker and the command palette, so indicator or news panels
+//! can be added without forking the dashboard.
+
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use leptos::prelude::*;
+
+use crate::AppState;
+
+/// A panel supplied from outside the dashboard
+pub trait DashPanel: Send + Sync {
+    /// Stable identifier, remembered as the layout choice
+    fn id(&self) -> &'static str;
+
+    /// Panel header text
+    fn title(&self) -> String;
+
+    /// Panel body
+    fn render(&self, state: &AppState) -> AnyView;
+
+    /// Settings shown from the panel header, if the panel has any
+    fn settings(&self, _state: &AppState) -> Option<AnyView> {
+        None
+    }
+}
+
+/// Registered custom panels, shared by every clone
+#[derive(Clone, Default)]
+pub struct PanelRegistry {
+    panels: Arc<Mutex<Vec<Arc<dyn DashPanel>>>>,
+}
+
+impl std::fmt::Debug for PanelRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let ids: Vec<&str> = self.lock().iter().map(|p| p.id()).collect();
+        f.debug_struct("PanelRegistry").field("panels", &ids).finish()
+    }
+}
+
+impl PanelRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `panel`, replacing one with the same id
+    pub fn register(&self, panel: impl DashPanel + 'static) {
+        let panel: Arc<dyn DashPanel> = Arc::new(panel);
+        let mut panels = self.lock();
+        match panels.iter_mut().find(|p| p.id() == panel.id()) {
+            Some(existing) => *existing = panel,
+            None => panels.push(panel),
+        }
+    }
+
+    /// Remove a panel; returns whether it was registered
+    pub fn unregister(&self, id: &str) -> bool {
+        let mut panels = self.lock();
+        let before = panels.len();
+        panels.retain(|p| p.id() != id);
+        panels.len() < before
+    }
+
+    pub fn get(&self, id: &str) -> Option<Arc<dyn DashPanel>> {
+        self.lock().iter().find(|p| p.id() == id).cloned()
+    }
+
+    /// Registered panels, in registration order
+    pub fn panels(&self) -> Vec<Arc<dyn DashPanel>> {
+        self.lock().clone()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Arc<dyn DashPanel>>> {
+        self.panels.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Notes(&'static str);
+
+    impl DashPanel for Notes {
+        fn id(&self) -> &'static str {
+            "notes"
+        }
+
+        fn title(&self) -> String {
+            self.0.to_string()
+        }
+
+        fn render(&self, _state: &AppState) -> AnyView {
+            ().into_any()
+        }
+    }
+
+    #[test]
+    fn test_panel_registry() {
+        let registry = PanelRegistry::new();
+        assert!(registry.is_empty());
+
+        registry.register(Notes("Notes"));
+        registry.clone().register(Notes("Desk notes"));
+        assert_eq!(registry.panels().len(), 1);
+        assert_eq!(registry.get("notes").map(|p| p.title()), Some("Desk notes".to_string()));
+        assert!(registry.get("news").is_none());
+
+        assert!(registry.unregister("notes"));
+        assert!(!registry.unregister("notes"));
+        assert!(registry.is_empty());
+    }
+}
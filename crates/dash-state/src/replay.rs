@@ -0,0 +1,184 @@
+//! Time-travel replay: freeze live updates and scrub through buffered market data
+
+use dash_core::{Candle, CandleHistory, RingBuffer, Ticker, Trade};
+
+use crate::{market::merge_candle, MAX_TRADES};
+
+/// Maximum live updates queued while paused before the oldest are dropped
+pub const MAX_REPLAY_PENDING: usize = 5_000;
+
+/// Whether the dashboard shows live data or a buffered moment
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReplayMode {
+    #[default]
+    Live,
+    /// Frozen at a scrub position (ms)
+    Paused { at: i64 },
+}
+
+impl ReplayMode {
+    pub fn is_live(&self) -> bool {
+        matches!(self, Self::Live)
+    }
+
+    /// Current scrub position, if paused
+    pub fn position(&self) -> Option<i64> {
+        match self {
+            Self::Live => None,
+            Self::Paused { at } => Some(*at),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Live => "LIVE",
+            Self::Paused { .. } => "REPLAY",
+        }
+    }
+}
+
+/// Live update that arrived while paused, applied on resume.
+/// Order books are not queued; they keep flowing into `BookHistory`.
+#[derive(Debug, Clone)]
+pub enum PendingUpdate {
+    Ticker(Ticker),
+    Trade(Trade),
+    Candle(Candle),
+    /// Bulk candle load
+    Candles(Vec<Candle>),
+    /// Candle history fetched from the server, merged over what is shown
+    FetchedCandles(Vec<Candle>),
+}
+
+/// Market data captured when replay started plus everything received since
+#[derive(Debug, Clone, Default)]
+pub struct ReplayBuffer {
+    /// Trades at pause time (oldest first)
+    frozen_trades: RingBuffer<Trade>,
+    /// Candle history at pause time
+    frozen_candles: Option<CandleHistory>,
+    /// Updates received while paused (oldest first)
+    pending: RingBuffer<PendingUpdate>,
+}
+
+impl ReplayBuffer {
+    /// Start a replay session from the currently displayed data
+    pub fn freeze(trades: RingBuffer<Trade>, candles: CandleHistory) -> Self {
+        Self {
+            frozen_trades: trades,
+            frozen_candles: Some(candles),
+            pending: RingBuffer::new(MAX_REPLAY_PENDING),
+        }
+    }
+
+    /// Queue a live update (the oldest is dropped once the queue is full)
+    pub fn push(&mut self, update: PendingUpdate) {
+        self.pending.push(update);
+    }
+
+    /// Take queued updates for replaying into live state
+    pub fn drain(&mut self) -> (RingBuffer<Trade>, Option<CandleHistory>, Vec<PendingUpdate>) {
+        let buffer = std::mem::take(self);
+        (buffer.frozen_trades, buffer.frozen_candles, buffer.pending.into_vec())
+    }
+
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Trades executed at or before `timestamp` (oldest first)
+    pub fn trades_at(&self, timestamp: i64) -> RingBuffer<Trade> {
+        let queued = self.pending.iter().filter_map(|u| match u {
+            PendingUpdate::Trade(t) => Some(t),
+            _ => None,
+        });
+
+        let mut trades = RingBuffer::new(MAX_TRADES);
+        trades.extend(
+            self.frozen_trades
+                .iter()
+                .chain(queued)
+                .filter(|t| t.timestamp.timestamp_millis() <= timestamp)
+                .cloned(),
+        );
+        trades
+    }
+
+    /// Candle history cut at the candle containing `timestamp`
+    pub fn candles_at(&self, timestamp: i64) -> Option<CandleHistory> {
+        let mut history = self.frozen_candles.clone()?;
+
+        for update in &self.pending {
+            match update {
+                PendingUpdate::Candle(c) if c.timestamp <= timestamp => {
+                    merge_candle(&mut history, c.clone());
+                }
+                PendingUpdate::Candles(candles) => {
+                    history.candles = RingBuffer::from_vec(candles.clone(), history.candles.capacity());
+                }
+                PendingUpdate::FetchedCandles(candles) => {
+                    history.reconcile(candles.clone());
+                }
+                _ => {}
+            }
+        }
+
+        history.candles.retain(|c| c.timestamp <= timestamp);
+        Some(history)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use dash_core::{CandleInterval, Symbol, TradeSide};
+
+    fn trade(id: &str, ts: i64) -> Trade {
+        let mut trade = Trade::new(Symbol::new("BTC-USD"), 50_000.0, 0.1, TradeSide::Buy);
+        trade.id = id.to_string();
+        trade.timestamp = chrono::Utc.timestamp_millis_opt(ts).unwrap();
+        trade
+    }
+
+    fn candle(ts: i64) -> Candle {
+        Candle::new(Symbol::new("BTC-USD"), CandleInterval::M1, ts, 50_000.0)
+    }
+
+    #[test]
+    fn test_replay_mode() {
+        assert!(ReplayMode::default().is_live());
+        assert_eq!(ReplayMode::Paused { at: 42 }.position(), Some(42));
+    }
+
+    #[test]
+    fn test_trades_at() {
+        let mut buffer = ReplayBuffer::freeze(
+            vec![trade("a", 1_000), trade("b", 2_000)].into(),
+            CandleHistory::new(Symbol::new("BTC-USD"), CandleInterval::M1),
+        );
+        buffer.push(PendingUpdate::Trade(trade("c", 3_000)));
+
+        let ids = |ts| buffer.trades_at(ts).newest_first().map(|t| t.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(3_000), vec!["c", "b", "a"]);
+        assert_eq!(ids(1_500), vec!["a"]);
+    }
+
+    #[test]
+    fn test_candles_at() {
+        let mut history = CandleHistory::new(Symbol::new("BTC-USD"), CandleInterval::M1);
+        history.push(candle(0));
+        history.push(candle(60_000));
+
+        let mut buffer = ReplayBuffer::freeze(RingBuffer::default(), history);
+        buffer.push(PendingUpdate::Candle(candle(120_000)));
+
+        assert_eq!(buffer.candles_at(130_000).unwrap().len(), 3);
+        assert_eq!(buffer.candles_at(30_000).unwrap().len(), 1);
+
+        let (_, candles, pending) = buffer.drain();
+        assert_eq!(candles.unwrap().len(), 2);
+        assert_eq!(pending.len(), 1);
+        assert_eq!(buffer.pending_len(), 0);
+    }
+}
@@ -0,0 +1,115 @@
+//! Shareable dashboard URLs (`/d/BTC-USD/15m`)
+
+use dash_core::{CandleInterval, Symbol};
+use leptos::prelude::*;
+
+use crate::MarketState;
+
+/// Path prefix for dashboard deep links
+pub const DASHBOARD_ROUTE_PREFIX: &str = "/d";
+
+/// Longest symbol accepted from a URL
+const MAX_SYMBOL_LEN: usize = 24;
+
+/// Symbol and interval selected by a dashboard URL
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DashboardRoute {
+    pub symbol: Symbol,
+    pub interval: CandleInterval,
+}
+
+impl DashboardRoute {
+    pub fn new(symbol: Symbol, interval: CandleInterval) -> Self {
+        Self { symbol, interval }
+    }
+
+    /// Route for what the dashboard is showing now
+    pub fn current(market: &MarketState) -> Self {
+        Self::new(market.symbol.get(), market.interval.get())
+    }
+
+    /// Build from route params. A missing or unknown interval falls back to
+    /// `default_interval`; an invalid symbol yields `None`.
+    pub fn from_params(
+        symbol: Option<&str>,
+        interval: Option<&str>,
+        default_interval: CandleInterval,
+    ) -> Option<Self> {
+        let symbol = parse_symbol(symbol?)?;
+        let interval = interval
+            .and_then(CandleInterval::from_code)
+            .unwrap_or(default_interval);
+        Some(Self::new(symbol, interval))
+    }
+
+    /// Parse a complete, canonical-form path (`/d/{symbol}/{interval}`)
+    pub fn from_path(path: &str) -> Option<Self> {
+        let rest = path.strip_prefix(DASHBOARD_ROUTE_PREFIX)?.strip_prefix('/')?;
+        let mut segments = rest.trim_end_matches('/').split('/');
+        let symbol = parse_symbol(segments.next()?)?;
+        let interval = CandleInterval::from_code(segments.next()?)?;
+        segments
+            .next()
+            .is_none()
+            .then(|| Self::new(symbol, interval))
+    }
+
+    /// Canonical URL path for this view
+    pub fn path(&self) -> String {
+        format!(
+            "{}/{}/{}",
+            DASHBOARD_ROUTE_PREFIX,
+            self.symbol.as_str(),
+            self.interval.code()
+        )
+    }
+
+    /// Switch the market to this route's symbol and interval (no-op if already shown)
+    pub fn apply(&self, market: &MarketState) {
+        if market.symbol.get_untracked() != self.symbol {
+            market.set_symbol(self.symbol.clone());
+        }
+        if market.interval.get_untracked() != self.interval {
+            market.set_interval(self.interval);
+        }
+    }
+}
+
+/// Normalize a URL symbol segment ("btc-usd" -> "BTC-USD")
+fn parse_symbol(value: &str) -> Option<Symbol> {
+    let value = value.trim();
+    let valid = !value.is_empty()
+        && value.len() <= MAX_SYMBOL_LEN
+        && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    valid.then(|| Symbol::new(value.to_ascii_uppercase()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route_path_roundtrip() {
+        let route = DashboardRoute::new(Symbol::new("ETH-USD"), CandleInterval::H4);
+        assert_eq!(route.path(), "/d/ETH-USD/4h");
+        assert_eq!(DashboardRoute::from_path(&route.path()), Some(route));
+
+        assert_eq!(
+            DashboardRoute::from_path("/d/btc-usd/15M/").map(|r| r.path()),
+            Some("/d/BTC-USD/15m".to_string())
+        );
+        assert!(DashboardRoute::from_path("/").is_none());
+        assert!(DashboardRoute::from_path("/d/BTC-USD").is_none());
+        assert!(DashboardRoute::from_path("/d/BTC-USD/1m/extra").is_none());
+    }
+
+    #[test]
+    fn test_route_from_params() {
+        let route = DashboardRoute::from_params(Some("sol-usd"), Some("bogus"), CandleInterval::M5).unwrap();
+        assert_eq!(route.symbol, Symbol::new("SOL-USD"));
+        assert_eq!(route.interval, CandleInterval::M5);
+
+        assert!(DashboardRoute::from_params(None, Some("1m"), CandleInterval::M1).is_none());
+        assert!(DashboardRoute::from_params(Some("BTC<USD"), None, CandleInterval::M1).is_none());
+    }
+}
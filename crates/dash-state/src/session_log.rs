@@ -0,0 +1,179 @@
+//! Opt-in session log for issue reports
+//!
+//! Records what happened to the user's session (symbol switches, alerts,
+//! connection drops, latency spikes) with timestamps. Nothing is recorded
+//! until the user enables it. Entries wait in `pending` until the storage
+//! layer persists them, so this module stays free of browser APIs.
+
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+/// Entries kept in memory; older ones remain only in storage
+pub const MAX_SESSION_LOG: usize = 1_000;
+
+/// Heartbeats arriving later than this after the clock estimate are logged
+pub const LATENCY_SPIKE_MS: i64 = 1_000;
+
+/// Something worth putting on a session timeline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SessionEvent {
+    SymbolSwitch { from: String, to: String },
+    Alert { message: String },
+    /// The stream was lost; `state` is what the client moved to
+    ConnectionDrop { state: String },
+    /// Connected again after a drop
+    Reconnected { down_ms: i64 },
+    /// A server heartbeat arrived `ms` later than expected
+    LatencySpike { ms: i64 },
+}
+
+/// One timestamped event
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionLogEntry {
+    /// Local Unix millis
+    pub timestamp: i64,
+    #[serde(flatten)]
+    pub event: SessionEvent,
+}
+
+/// Exported log file contents
+#[derive(Debug, Serialize)]
+struct SessionLogExport<'a> {
+    exported_at: i64,
+    user_agent: Option<&'a str>,
+    entries: &'a [SessionLogEntry],
+}
+
+/// Recent session events and those not yet persisted
+#[derive(Debug, Clone, Default)]
+pub struct SessionLog {
+    enabled: bool,
+    entries: VecDeque<SessionLogEntry>,
+    pending: Vec<SessionLogEntry>,
+}
+
+impl SessionLog {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Turn recording on or off; opting out forgets everything recorded
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.clear();
+        }
+    }
+
+    /// Record `event`; false (and nothing kept) while disabled
+    pub fn record(&mut self, timestamp: i64, event: SessionEvent) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        let entry = SessionLogEntry { timestamp, event };
+        tracing::debug!(?entry, "session event");
+        if self.entries.len() == MAX_SESSION_LOG {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry.clone());
+        self.pending.push(entry);
+        true
+    }
+
+    /// Put entries loaded from storage (oldest first) ahead of the ones
+    /// recorded since
+    pub fn restore(&mut self, entries: Vec<SessionLogEntry>) {
+        let mut restored = VecDeque::from(entries);
+        restored.extend(self.entries.drain(..));
+        let excess = restored.len().saturating_sub(MAX_SESSION_LOG);
+        restored.drain(..excess);
+        self.entries = restored;
+    }
+
+    /// Entries to persist, oldest first
+    pub fn take_pending(&mut self) -> Vec<SessionLogEntry> {
+        std::mem::take(&mut self.pending)
+    }
+
+    pub fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &SessionLogEntry> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.pending.clear();
+    }
+}
+
+/// Pretty-printed JSON for an exported log
+pub fn export_session_log(
+    entries: &[SessionLogEntry],
+    exported_at: i64,
+    user_agent: Option<&str>,
+) -> String {
+    let export = SessionLogExport {
+        exported_at,
+        user_agent,
+        entries,
+    };
+    serde_json::to_string_pretty(&export).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_log_opt_in() {
+        let mut log = SessionLog::default();
+        let drop = || SessionEvent::ConnectionDrop { state: "Reconnecting...".into() };
+        assert!(!log.record(1, drop()));
+        assert!(log.is_empty());
+
+        log.set_enabled(true);
+        assert!(log.record(2, drop()));
+        assert!(log.record(3, SessionEvent::Reconnected { down_ms: 1 }));
+        assert_eq!(log.take_pending().len(), 2);
+        assert!(!log.has_pending());
+        assert_eq!(log.len(), 2);
+
+        // Stored history goes before what was recorded while loading
+        let stored = SessionLogEntry { timestamp: 0, event: drop() };
+        log.restore(vec![stored.clone()]);
+        assert_eq!(log.entries().next(), Some(&stored));
+        assert_eq!(log.len(), 3);
+
+        log.record(4, SessionEvent::LatencySpike { ms: 1_500 });
+        log.set_enabled(false);
+        assert!(!log.has_pending() && log.is_empty());
+    }
+
+    #[test]
+    fn test_session_log_export() {
+        let entry = SessionLogEntry {
+            timestamp: 10,
+            event: SessionEvent::SymbolSwitch { from: "BTC-USD".into(), to: "ETH-USD".into() },
+        };
+        let json = serde_json::to_string(&entry).unwrap();
+        let expected = r#"{"timestamp":10,"type":"symbol_switch","from":"BTC-USD","to":"ETH-USD"}"#;
+        assert_eq!(json, expected);
+        assert_eq!(serde_json::from_str::<SessionLogEntry>(&json).unwrap(), entry);
+
+        let export = export_session_log(&[entry], 20, None);
+        assert!(export.contains("\"exported_at\": 20") && export.contains("symbol_switch"));
+    }
+}
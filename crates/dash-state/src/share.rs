@@ -0,0 +1,129 @@
+//! Chart snapshot sharing
+//!
+//! Text that goes on a shared chart image: a caption with the market, time
+//! and ticker stats, and the user's watermark. The image itself is composed
+//! by the components crate, so this module stays free of browser APIs.
+
+use chrono::{DateTime, Utc};
+use dash_core::{CandleInterval, Locale, Msg, Symbol, Ticker};
+
+/// Watermark on new installs
+pub const DEFAULT_WATERMARK: &str = "BTC Exchange Dash";
+
+/// Longest watermark kept; longer input is cut
+pub const MAX_WATERMARK_CHARS: usize = 48;
+
+/// Options for shared snapshots
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShareSettings {
+    watermark: String,
+}
+
+impl Default for ShareSettings {
+    fn default() -> Self {
+        Self { watermark: DEFAULT_WATERMARK.to_string() }
+    }
+}
+
+impl ShareSettings {
+    /// Watermark text, or `None` when turned off
+    pub fn watermark(&self) -> Option<&str> {
+        (!self.watermark.is_empty()).then_some(self.watermark.as_str())
+    }
+
+    /// Set the watermark, trimmed and cut to `MAX_WATERMARK_CHARS`; blank
+    /// text turns it off
+    pub fn set_watermark(&mut self, text: &str) {
+        self.watermark = text.trim().chars().take(MAX_WATERMARK_CHARS).collect();
+    }
+}
+
+/// Header of a snapshot image
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnapshotCaption {
+    /// Symbol and interval, e.g. "BTC-USD · 1m"
+    pub title: String,
+    /// When the snapshot was taken, in UTC
+    pub taken_at: String,
+    /// Ticker stats as (label, value), empty without a ticker
+    pub stats: Vec<(&'static str, String)>,
+    /// Whether the 24h change is up, for coloring
+    pub up: bool,
+}
+
+impl SnapshotCaption {
+    pub fn new(
+        locale: Locale,
+        symbol: &Symbol,
+        interval: CandleInterval,
+        ticker: Option<&Ticker>,
+        taken_at: DateTime<Utc>,
+    ) -> Self {
+        let stats = ticker
+            .map(|t| {
+                vec![
+                    (locale.text(Msg::Price), locale.format_decimal(t.last_price.as_f64(), 2)),
+                    (locale.text(Msg::Change24h), format!("{:+.2}%", t.change_percent_24h)),
+                    (locale.text(Msg::High24h), locale.format_decimal(t.high_24h.as_f64(), 2)),
+                    (locale.text(Msg::Low24h), locale.format_decimal(t.low_24h.as_f64(), 2)),
+                    (locale.text(Msg::Volume24h), locale.format_compact(t.volume_24h.as_f64())),
+                ]
+            })
+            .unwrap_or_default();
+        Self {
+            title: format!("{} · {}", symbol, interval),
+            taken_at: locale.format_datetime(&taken_at, false),
+            stats,
+            up: ticker.is_none_or(|t| t.change_percent_24h >= 0.0),
+        }
+    }
+}
+
+/// PNG file name for a snapshot, e.g. `BTC-USD_1m_20240101-000000.png`
+pub fn snapshot_file_name(
+    symbol: &Symbol,
+    interval: CandleInterval,
+    taken_at: DateTime<Utc>,
+) -> String {
+    format!("{}_{}_{}.png", symbol, interval, taken_at.format("%Y%m%d-%H%M%S"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_watermark_setting() {
+        let mut settings = ShareSettings::default();
+        assert_eq!(settings.watermark(), Some(DEFAULT_WATERMARK));
+
+        settings.set_watermark("  @desk  ");
+        assert_eq!(settings.watermark(), Some("@desk"));
+        settings.set_watermark(&"x".repeat(100));
+        assert_eq!(settings.watermark().map(|w| w.chars().count()), Some(MAX_WATERMARK_CHARS));
+        settings.set_watermark("   ");
+        assert_eq!(settings.watermark(), None);
+    }
+
+    #[test]
+    fn test_snapshot_caption() {
+        let symbol = Symbol::new("BTC-USD");
+        let taken_at = Utc.with_ymd_and_hms(2024, 1, 1, 9, 30, 5).unwrap();
+        let caption = SnapshotCaption::new(Locale::En, &symbol, CandleInterval::M1, None, taken_at);
+        assert_eq!(caption.title, "BTC-USD · 1m");
+        assert_eq!(caption.taken_at, "Jan 1, 2024 09:30:05 UTC");
+        assert!(caption.stats.is_empty());
+
+        let ticker = Ticker::new(symbol.clone(), 64_210.5);
+        let caption =
+            SnapshotCaption::new(Locale::En, &symbol, CandleInterval::M1, Some(&ticker), taken_at);
+        assert_eq!(caption.stats[0], ("Price", "64,210.50".to_string()));
+        assert_eq!(caption.stats.len(), 5);
+
+        assert_eq!(
+            snapshot_file_name(&symbol, CandleInterval::H4, taken_at),
+            "BTC-USD_4H_20240101-093005.png"
+        );
+    }
+}
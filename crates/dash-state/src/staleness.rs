@@ -0,0 +1,117 @@
+//! Per-feed data age
+//!
+//! Each panel watches the feed it draws from and escalates an age badge from
+//! muted to amber to red as that feed goes quiet, so a stuck channel shows up
+//! even while the socket itself looks healthy.
+
+/// A stream a panel draws from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Feed {
+    Ticker,
+    OrderBook,
+    Trades,
+    Candles,
+}
+
+impl Feed {
+    /// Short name for the age badge
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Ticker => "ticker",
+            Self::OrderBook => "book",
+            Self::Trades => "trades",
+            Self::Candles => "candles",
+        }
+    }
+}
+
+/// How worrying a feed's age is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Staleness {
+    Fresh,
+    Warn,
+    Alert,
+}
+
+impl Staleness {
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            Self::Fresh => "age-fresh",
+            Self::Warn => "age-warn",
+            Self::Alert => "age-alert",
+        }
+    }
+}
+
+/// Age thresholds for the staleness badges
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StaleThreshold {
+    Tight,
+    #[default]
+    Normal,
+    Relaxed,
+}
+
+impl StaleThreshold {
+    /// (amber, red) ages in millis
+    pub fn limits_ms(&self) -> (i64, i64) {
+        match self {
+            Self::Tight => (2_000, 5_000),
+            Self::Normal => (5_000, 15_000),
+            Self::Relaxed => (15_000, 60_000),
+        }
+    }
+
+    pub fn classify(&self, age_ms: i64) -> Staleness {
+        let (warn, alert) = self.limits_ms();
+        if age_ms >= alert {
+            Staleness::Alert
+        } else if age_ms >= warn {
+            Staleness::Warn
+        } else {
+            Staleness::Fresh
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Tight => "2s/5s",
+            Self::Normal => "5s/15s",
+            Self::Relaxed => "15s/60s",
+        }
+    }
+
+    pub fn all() -> &'static [Self] {
+        &[Self::Tight, Self::Normal, Self::Relaxed]
+    }
+}
+
+/// Compact age: "4s", "3m", "2h"
+pub fn format_age(age_ms: i64) -> String {
+    let secs = age_ms.max(0) / 1_000;
+    match secs {
+        0..60 => format!("{}s", secs),
+        60..3_600 => format!("{}m", secs / 60),
+        _ => format!("{}h", secs / 3_600),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stale_threshold() {
+        let threshold = StaleThreshold::Normal;
+        assert_eq!(threshold.classify(0), Staleness::Fresh);
+        assert_eq!(threshold.classify(4_999), Staleness::Fresh);
+        assert_eq!(threshold.classify(5_000), Staleness::Warn);
+        assert_eq!(threshold.classify(15_000), Staleness::Alert);
+        assert_eq!(StaleThreshold::Tight.classify(5_000), Staleness::Alert);
+
+        assert_eq!(format_age(4_200), "4s");
+        assert_eq!(format_age(150_000), "2m");
+        assert_eq!(format_age(7_200_000), "2h");
+        assert_eq!(format_age(-50), "0s");
+    }
+}
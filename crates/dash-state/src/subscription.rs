@@ -0,0 +1,88 @@
+//! Desired market data subscriptions (replayed by the WebSocket client on reconnect)
+
+use dash_core::{ClientCommand, Subscription, Symbol};
+
+/// Set of streams the dashboard wants, keyed by symbol
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SubscriptionSet {
+    subscriptions: Vec<Subscription>,
+}
+
+impl SubscriptionSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add or replace the subscription for its symbol
+    pub fn upsert(&mut self, sub: Subscription) {
+        match self.subscriptions.iter_mut().find(|s| s.symbol == sub.symbol) {
+            Some(existing) => *existing = sub,
+            None => self.subscriptions.push(sub),
+        }
+    }
+
+    /// Remove the subscription for a symbol
+    pub fn remove(&mut self, symbol: &Symbol) {
+        self.subscriptions.retain(|s| &s.symbol != symbol);
+    }
+
+    /// Apply a command that was delivered to the server
+    pub fn apply(&mut self, cmd: &ClientCommand) {
+        match cmd {
+            ClientCommand::Subscribe(sub) => self.upsert(sub.clone()),
+            ClientCommand::Unsubscribe { symbol } => self.remove(symbol),
+            _ => {}
+        }
+    }
+
+    pub fn get(&self, symbol: &Symbol) -> Option<&Subscription> {
+        self.subscriptions.iter().find(|s| &s.symbol == symbol)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Subscription> {
+        self.subscriptions.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.subscriptions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.subscriptions.is_empty()
+    }
+
+    /// Subscribe commands that restore every stream in the set
+    pub fn replay_commands(&self) -> Vec<ClientCommand> {
+        self.subscriptions.iter().cloned().map(ClientCommand::Subscribe).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dash_core::CandleInterval;
+
+    #[test]
+    fn test_upsert_replaces_parameters() {
+        let mut set = SubscriptionSet::new();
+        set.upsert(Subscription::new("BTC-USD"));
+        set.upsert(Subscription::new("ETH-USD"));
+        set.upsert(Subscription::new("BTC-USD").interval(CandleInterval::M5).depth_levels(50));
+
+        assert_eq!(set.len(), 2);
+        let btc = set.get(&Symbol::new("BTC-USD")).unwrap();
+        assert_eq!(btc.interval, CandleInterval::M5);
+        assert_eq!(btc.depth_levels, Some(50));
+    }
+
+    #[test]
+    fn test_apply_and_replay() {
+        let mut set = SubscriptionSet::new();
+        set.apply(&ClientCommand::subscribe("BTC-USD"));
+        set.apply(&ClientCommand::subscribe("ETH-USD"));
+        set.apply(&ClientCommand::unsubscribe("ETH-USD"));
+        set.apply(&ClientCommand::Ping);
+
+        assert_eq!(set.replay_commands(), vec![ClientCommand::subscribe("BTC-USD")]);
+    }
+}
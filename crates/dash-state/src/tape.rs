@@ -0,0 +1,258 @@
+//! Trade tape options: value shading, running volume, repeat collapse and
+//! holding the tape still
+
+use std::collections::HashMap;
+
+use dash_core::{
+    NotionalBuckets, RelativeBuckets, RingBuffer, Trade, TradeCoalescer, TradeGroup,
+    ValueBucketer,
+};
+
+/// How tape rows are shaded by trade value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TapeShading {
+    Off,
+    /// Fixed dollar buckets
+    #[default]
+    Notional,
+    /// Multiples of the median print on the tape
+    Relative,
+}
+
+impl TapeShading {
+    /// Bucketing strategy, with `tape` as the reference prints
+    pub fn bucketer(&self, tape: &[Trade]) -> Option<Box<dyn ValueBucketer>> {
+        match self {
+            Self::Off => None,
+            Self::Notional => Some(Box::new(NotionalBuckets::default())),
+            Self::Relative => Some(Box::new(RelativeBuckets::over(tape))),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Off => "Off",
+            Self::Notional => "$ value",
+            Self::Relative => "vs median",
+        }
+    }
+
+    pub fn all() -> &'static [Self] {
+        &[Self::Off, Self::Notional, Self::Relative]
+    }
+}
+
+/// Window within which repeat prints (same price and side) collapse into
+/// one tape row
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TapeCollapse {
+    #[default]
+    Off,
+    Ms100,
+    Ms500,
+    S1,
+}
+
+impl TapeCollapse {
+    /// Merge window, `None` when repeats are shown one per row
+    pub fn window_ms(&self) -> Option<i64> {
+        match self {
+            Self::Off => None,
+            Self::Ms100 => Some(100),
+            Self::Ms500 => Some(500),
+            Self::S1 => Some(1_000),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Off => "Off",
+            Self::Ms100 => "100ms",
+            Self::Ms500 => "500ms",
+            Self::S1 => "1s",
+        }
+    }
+
+    pub fn all() -> &'static [Self] {
+        &[Self::Off, Self::Ms100, Self::Ms500, Self::S1]
+    }
+}
+
+/// The tape's trades streamed through a `TradeCoalescer` as they arrive
+#[derive(Debug, Clone)]
+pub struct TapeGroups {
+    coalescer: TradeCoalescer,
+    /// Newest trade fed in, where the next update picks up
+    last_id: Option<String>,
+}
+
+impl TapeGroups {
+    pub fn new(window_ms: i64, capacity: usize) -> Self {
+        Self { coalescer: TradeCoalescer::new(window_ms, capacity), last_id: None }
+    }
+
+    pub fn window_ms(&self) -> i64 {
+        self.coalescer.window_ms()
+    }
+
+    /// Feed the trades on `tape` (oldest first) that arrived since the last
+    /// update. A tape no longer holding the last trade fed (cleared, or
+    /// scrubbed in replay) is coalesced afresh. Returns whether the groups
+    /// changed.
+    pub fn update(&mut self, tape: &[Trade]) -> bool {
+        let start = match &self.last_id {
+            Some(id) => match tape.iter().rposition(|t| &t.id == id) {
+                Some(at) => at + 1,
+                None => {
+                    self.coalescer.clear();
+                    self.last_id = None;
+                    if tape.is_empty() {
+                        return true;
+                    }
+                    0
+                }
+            },
+            None => 0,
+        };
+        let Some(newest) = tape.get(start..).and_then(<[Trade]>::last) else {
+            return false;
+        };
+        self.last_id = Some(newest.id.clone());
+        for trade in &tape[start..] {
+            self.coalescer.push(trade);
+        }
+        true
+    }
+
+    /// Groups, oldest first
+    pub fn groups(&self) -> &RingBuffer<TradeGroup> {
+        self.coalescer.groups()
+    }
+}
+
+/// Trades on `tape` (oldest first) newer than trade `id`; all of them when
+/// `id` is `None` or has left the tape
+pub fn trades_after(tape: &[Trade], id: Option<&str>) -> usize {
+    id.and_then(|id| tape.iter().rposition(|t| t.id == id))
+        .map_or(tape.len(), |at| tape.len() - at - 1)
+}
+
+/// Running volume of the trades printed since a tape was opened, as of
+/// each of those trades
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TapeVolume {
+    since_ms: i64,
+    total: f64,
+    running: HashMap<String, f64>,
+}
+
+impl TapeVolume {
+    /// Count trades stamped at or after `since_ms`
+    pub fn new(since_ms: i64) -> Self {
+        Self { since_ms, ..Default::default() }
+    }
+
+    /// Add the trades on `tape` (oldest first) not counted yet; totals of
+    /// trades that left the tape are dropped. Returns whether anything was
+    /// added.
+    pub fn update(&mut self, tape: &[Trade]) -> bool {
+        let mut added = false;
+        for trade in tape {
+            if trade.timestamp.timestamp_millis() < self.since_ms
+                || self.running.contains_key(&trade.id)
+            {
+                continue;
+            }
+            self.total += trade.quantity.as_f64();
+            self.running.insert(trade.id.clone(), self.total);
+            added = true;
+        }
+        if self.running.len() > tape.len() {
+            self.running.retain(|id, _| tape.iter().any(|t| &t.id == id));
+        }
+        added
+    }
+
+    /// Volume up to and including trade `id`; `None` for trades from before
+    /// the tape was opened
+    pub fn at(&self, id: &str) -> Option<f64> {
+        self.running.get(id).copied()
+    }
+
+    pub fn total(&self) -> f64 {
+        self.total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use dash_core::{Symbol, TradeSide};
+
+    fn trade(id: &str, ms: i64, qty: f64) -> Trade {
+        let mut trade = Trade::new(Symbol::default(), 100.0, qty, TradeSide::Buy);
+        trade.id = id.to_string();
+        trade.timestamp = Utc.timestamp_millis_opt(ms).unwrap();
+        trade
+    }
+
+    #[test]
+    fn test_tape_volume() {
+        let mut volume = TapeVolume::new(1_000);
+        let mut tape = vec![trade("old", 500, 9.0), trade("a", 1_000, 1.0)];
+        assert!(volume.update(&tape));
+        assert_eq!((volume.at("old"), volume.at("a")), (None, Some(1.0)));
+
+        tape.push(trade("b", 1_200, 2.5));
+        assert!(volume.update(&tape));
+        assert!(!volume.update(&tape));
+        assert_eq!(volume.at("b"), Some(3.5));
+
+        // Trades falling off the tape keep counting towards the total
+        tape.drain(..2);
+        tape.push(trade("c", 1_300, 0.5));
+        volume.update(&tape);
+        assert_eq!((volume.at("a"), volume.at("c")), (None, Some(4.0)));
+        assert_eq!(volume.total(), 4.0);
+    }
+
+    #[test]
+    fn test_tape_groups() {
+        let mut groups = TapeGroups::new(500, 10);
+        let mut tape = vec![trade("a", 1_000, 1.0), trade("b", 1_100, 2.0)];
+        assert!(groups.update(&tape));
+        assert!(!groups.update(&tape));
+        assert_eq!(groups.groups().len(), 1);
+
+        // New prints continue the run
+        tape.push(trade("c", 1_200, 0.5));
+        assert!(groups.update(&tape));
+        let run = &groups.groups()[0];
+        assert_eq!((run.count, run.trade.quantity.as_f64(), run.last_id.as_str()), (3, 3.5, "c"));
+
+        // Scrubbed back to before "c": regrouped from the tape
+        tape.pop();
+        assert!(groups.update(&tape));
+        assert_eq!(groups.groups()[0].count, 2);
+        assert!(groups.update(&[]));
+        assert!(groups.groups().is_empty());
+    }
+
+    #[test]
+    fn test_trades_after() {
+        let tape = vec![trade("a", 0, 1.0), trade("b", 0, 1.0), trade("c", 0, 1.0)];
+        assert_eq!(trades_after(&tape, Some("a")), 2);
+        assert_eq!(trades_after(&tape, Some("c")), 0);
+        assert_eq!(trades_after(&tape, Some("gone")), 3);
+        assert_eq!(trades_after(&tape, None), 3);
+    }
+
+    #[test]
+    fn test_tape_shading() {
+        assert!(TapeShading::Off.bucketer(&[]).is_none());
+        let bucketer = TapeShading::Relative.bucketer(&[]).unwrap();
+        // No baseline yet: dollar buckets
+        assert_eq!(bucketer.bucket_count(), NotionalBuckets::default().bucket_count());
+    }
+}
@@ -0,0 +1,245 @@
+//! Account sign-in and document sync
+//!
+//! Sends the account menu's sign-ins to `/api/auth`, then keeps the user's
+//! settings, layout and alert rules in step with `/api/me`. Right after
+//! sign-in the account's documents replace the dashboard's, and documents
+//! the account does not have yet are pushed; from then on every change is
+//! pushed as it happens. The user's role is asked for on load and again
+//! on every sign-in and sign-out.
+
+use std::collections::HashMap;
+
+use dash_core::{AccountError, Credentials, Role, Session, UserDocument};
+use dash_state::{AccountSync, AppState};
+use gloo_net::http::{Request, Response};
+use leptos::prelude::*;
+use serde_json::Value;
+
+/// Documents the dashboard keeps in step with the account
+const SYNCED: [UserDocument; 3] =
+    [UserDocument::Settings, UserDocument::Layout, UserDocument::Alerts];
+
+/// Why a `/api/me` request was answered with `status`
+fn document_error(status: u16) -> AccountError {
+    match status {
+        401 => AccountError::Expired,
+        503 => AccountError::Unavailable,
+        _ => {
+            tracing::warn!("Account sync failed: HTTP {}", status);
+            AccountError::Failed
+        }
+    }
+}
+
+async fn send(request: Request, url: &str) -> Result<Response, AccountError> {
+    request.send().await.map_err(|e| {
+        tracing::debug!("Account request failed ({}): {}", url, e);
+        AccountError::Failed
+    })
+}
+
+/// Sign in, or open an account when `signup` is set
+pub async fn sign_in(
+    base_url: &str,
+    credentials: &Credentials,
+    signup: bool,
+) -> Result<Session, AccountError> {
+    let url = format!("{}/api/auth/{}", base_url, if signup { "signup" } else { "login" });
+    let request = Request::post(&url).json(credentials).map_err(|_| AccountError::Failed)?;
+    let response = send(request, &url).await?;
+    match response.status() {
+        200 | 201 => response.json().await.map_err(|_| AccountError::Failed),
+        401 => Err(AccountError::WrongPassword),
+        409 => Err(AccountError::UsernameTaken),
+        422 => Err(credentials.signup_error().unwrap_or(AccountError::Failed)),
+        // A server without accounts, or without storage for them
+        404 | 503 => Err(AccountError::Unavailable),
+        status => {
+            tracing::warn!("Sign-in failed: HTTP {}", status);
+            Err(AccountError::Failed)
+        }
+    }
+}
+
+/// A document from the account; `None` when it has not been stored yet
+pub async fn fetch_document(
+    base_url: &str,
+    token: &str,
+    kind: UserDocument,
+) -> Result<Option<Value>, AccountError> {
+    let url = format!("{}/api/me/{}", base_url, kind.as_str());
+    let request = Request::get(&url).header("Authorization", &format!("Bearer {}", token));
+    let response = send(request.build().map_err(|_| AccountError::Failed)?, &url).await?;
+    match response.status() {
+        200 => response.json().await.map(Some).map_err(|_| AccountError::Failed),
+        404 => Ok(None),
+        status => Err(document_error(status)),
+    }
+}
+
+/// Replace a document on the account
+pub async fn put_document(
+    base_url: &str,
+    token: &str,
+    kind: UserDocument,
+    document: &Value,
+) -> Result<(), AccountError> {
+    let url = format!("{}/api/me/{}", base_url, kind.as_str());
+    let request = Request::put(&url)
+        .header("Authorization", &format!("Bearer {}", token))
+        .json(document)
+        .map_err(|_| AccountError::Failed)?;
+    match send(request, &url).await?.status() {
+        200..=299 => Ok(()),
+        status => Err(document_error(status)),
+    }
+}
+
+/// The role the server gives the bearer of `token`, or a visitor without
+/// one; `None` when the server does not say (it predates roles)
+pub async fn fetch_role(base_url: &str, token: Option<&str>) -> Option<Role> {
+    let url = format!("{}/api/auth/role", base_url);
+    let mut request = Request::get(&url);
+    if let Some(token) = token {
+        request = request.header("Authorization", &format!("Bearer {}", token));
+    }
+    let response = send(request.build().ok()?, &url).await.ok()?;
+    if response.status() != 200 {
+        return None;
+    }
+    response.json().await.ok()
+}
+
+/// Report how a sync went; an expired session signs the user out
+fn settle(state: &AppState, result: Result<(), AccountError>) {
+    match result {
+        Ok(()) => state.account_sync.try_set(AccountSync::Synced),
+        Err(err) => {
+            if err == AccountError::Expired {
+                state.account.try_set(None);
+            }
+            state.account_sync.try_set(AccountSync::Failed(err))
+        }
+    };
+}
+
+/// Bring the dashboard and the account together after sign-in; returns
+/// what the account holds once done
+async fn pull(
+    base_url: &str,
+    token: &str,
+    state: &AppState,
+) -> Result<HashMap<UserDocument, Value>, AccountError> {
+    let mut held = HashMap::new();
+    for kind in SYNCED {
+        match fetch_document(base_url, token, kind).await? {
+            Some(document) => {
+                if !state.apply_user_document(kind, document) {
+                    tracing::warn!("Ignoring unreadable {} document", kind.as_str());
+                }
+            }
+            None => {
+                if let Some(local) = untrack(|| state.user_document(kind)) {
+                    put_document(base_url, token, kind, &local).await?;
+                }
+            }
+        }
+        if let Some(document) = untrack(|| state.user_document(kind)) {
+            held.insert(kind, document);
+        }
+    }
+    Ok(held)
+}
+
+/// Send sign-ins from the account menu, keep the signed-in user's
+/// documents in step with the dashboard and track the user's role
+pub fn sync_account(base_url: String, state: AppState) {
+    let (account, sync, requests, role) =
+        (state.account, state.account_sync, state.sign_in, state.role);
+
+    let url = base_url.clone();
+    Effect::new(move |_| {
+        let Some(request) = requests.get() else { return };
+        requests.set(None);
+        sync.set(AccountSync::SigningIn);
+        let url = url.clone();
+        leptos::task::spawn_local(async move {
+            match sign_in(&url, &request.credentials, request.signup).await {
+                Ok(session) => {
+                    account.try_set(Some(session));
+                }
+                Err(err) => {
+                    sync.try_set(AccountSync::Failed(err));
+                }
+            }
+        });
+    });
+
+    let token = Memo::new(move |_| account.with(|s| s.as_ref().map(|s| s.token.clone())));
+    let url = base_url.clone();
+    Effect::new(move |_| {
+        let (url, asked) = (url.clone(), token.get());
+        leptos::task::spawn_local(async move {
+            let Some(answer) = fetch_role(&url, asked.as_deref()).await else { return };
+            // Dropped when the user signed in or out meanwhile
+            if token.try_get_untracked() == Some(asked) {
+                role.try_set(answer);
+            }
+        });
+    });
+
+    // What the account holds; `None` until the pull after sign-in is done,
+    // so documents being applied are not pushed straight back
+    let held = StoredValue::new(None::<HashMap<UserDocument, Value>>);
+    // Answers for an earlier session are dropped
+    let generation = StoredValue::new(0u64);
+
+    let (url, pulling) = (base_url.clone(), state.clone());
+    Effect::new(move |_| {
+        generation.update_value(|g| *g += 1);
+        held.set_value(None);
+        let Some(token) = token.get() else {
+            // Keep telling the user why they were signed out
+            if sync.get_untracked() != AccountSync::Failed(AccountError::Expired) {
+                sync.set(AccountSync::SignedOut);
+            }
+            return;
+        };
+        sync.set(AccountSync::Syncing);
+        let (url, state, current) = (url.clone(), pulling.clone(), generation.get_value());
+        leptos::task::spawn_local(async move {
+            let result = pull(&url, &token, &state).await;
+            if generation.try_get_value() != Some(current) {
+                return;
+            }
+            let result = result.map(|documents| held.set_value(Some(documents)));
+            settle(&state, result);
+        });
+    });
+
+    for kind in SYNCED {
+        let pushing = state.clone();
+        let document = Memo::new(move |_| pushing.user_document(kind));
+        let (url, state) = (base_url.clone(), state.clone());
+        Effect::new(move |_| {
+            let Some(document) = document.get() else { return };
+            let changed = held.with_value(|held| {
+                held.as_ref().is_some_and(|held| held.get(&kind) != Some(&document))
+            });
+            let Some(token) = token.get_untracked().filter(|_| changed) else { return };
+            held.update_value(|held| {
+                if let Some(held) = held {
+                    held.insert(kind, document.clone());
+                }
+            });
+            sync.set(AccountSync::Syncing);
+            let (url, state, current) = (url.clone(), state.clone(), generation.get_value());
+            leptos::task::spawn_local(async move {
+                let result = put_document(&url, &token, kind, &document).await;
+                if generation.try_get_value() == Some(current) {
+                    settle(&state, result);
+                }
+            });
+        });
+    }
+}
@@ -0,0 +1,123 @@
+//! Admin page requests
+//!
+//! Polls `/api/control/status` while the admin page is open and sends its
+//! controls. Requests carry the signed-in user's token; the server answers
+//! anyone but an operator with 401 or 403.
+
+use dash_core::{AdminError, AdminStatus, ScenarioRequest, SourceRequest};
+use dash_state::{AdminCommand, AdminView, AppState};
+use gloo_net::http::{Request, RequestBuilder};
+use gloo_timers::future::TimeoutFuture;
+use leptos::prelude::*;
+
+/// Interval between status polls while the admin page is open (ms)
+pub const ADMIN_POLL_MS: u32 = 2_000;
+
+fn authorized(request: RequestBuilder, token: Option<&str>) -> RequestBuilder {
+    match token {
+        Some(token) => request.header("Authorization", &format!("Bearer {}", token)),
+        None => request,
+    }
+}
+
+/// Status of the server for the admin page
+pub async fn fetch_admin_status(
+    base_url: &str,
+    token: Option<&str>,
+) -> Result<AdminStatus, AdminError> {
+    let url = format!("{}/api/control/status", base_url);
+    let request = authorized(Request::get(&url), token).build().map_err(|_| AdminError::Failed)?;
+    let response = request.send().await.map_err(|e| {
+        tracing::debug!("Admin status request failed ({}): {}", url, e);
+        AdminError::Failed
+    })?;
+    match response.status() {
+        200 => response.json().await.map_err(|_| AdminError::Failed),
+        status => Err(AdminError::from_status(status)),
+    }
+}
+
+/// Send one admin control
+pub async fn send_admin_command(
+    base_url: &str,
+    token: Option<&str>,
+    command: &AdminCommand,
+) -> Result<(), AdminError> {
+    let (path, body) = match command {
+        AdminCommand::Source(source) => {
+            ("source", serde_json::to_value(SourceRequest { source: source.clone() }))
+        }
+        AdminCommand::Scenario(scenario) => {
+            let request = ScenarioRequest { scenario: *scenario, secs: None };
+            ("scenario", serde_json::to_value(request))
+        }
+        AdminCommand::Recording(recording) => {
+            ("recording", Ok(serde_json::json!({ "recording": recording })))
+        }
+    };
+    let url = format!("{}/api/control/{}", base_url, path);
+    let body = body.map_err(|_| AdminError::Failed)?;
+    let request = authorized(Request::put(&url), token)
+        .json(&body)
+        .map_err(|_| AdminError::Failed)?;
+    let response = request.send().await.map_err(|e| {
+        tracing::debug!("Admin request failed ({}): {}", url, e);
+        AdminError::Failed
+    })?;
+    match response.status() {
+        200..=299 => Ok(()),
+        status => {
+            tracing::warn!("Admin {} refused: HTTP {}", path, status);
+            Err(AdminError::from_status(status))
+        }
+    }
+}
+
+async fn refresh(base_url: &str, state: &AppState) {
+    let token = state.account.with_untracked(|s| s.as_ref().map(|s| s.token.clone()));
+    let view = match fetch_admin_status(base_url, token.as_deref()).await {
+        Ok(status) => AdminView::Ready(status),
+        Err(err) => AdminView::Failed(err),
+    };
+    state.admin.try_set(view);
+}
+
+/// Keep the admin page's status current while it is open and send its
+/// controls
+pub fn sync_admin(base_url: String, state: AppState) {
+    let (open, account, commands) = (state.admin_open, state.account, state.admin_command);
+    let token = Memo::new(move |_| account.with(|s| s.as_ref().map(|s| s.token.clone())));
+
+    // Right away when the page opens or the user signs in or out
+    let (url, refreshing) = (base_url.clone(), state.clone());
+    Effect::new(move |_| {
+        token.track();
+        if !open.get() {
+            return;
+        }
+        let (url, state) = (url.clone(), refreshing.clone());
+        leptos::task::spawn_local(async move { refresh(&url, &state).await });
+    });
+
+    let (url, sending) = (base_url.clone(), state.clone());
+    Effect::new(move |_| {
+        let Some(command) = commands.get() else { return };
+        commands.set(None);
+        let (url, state) = (url.clone(), sending.clone());
+        leptos::task::spawn_local(async move {
+            let token = token.try_get_untracked().flatten();
+            // A refused control shows as the status it left unchanged
+            let _ = send_admin_command(&url, token.as_deref(), &command).await;
+            refresh(&url, &state).await;
+        });
+    });
+
+    leptos::task::spawn_local(async move {
+        loop {
+            TimeoutFuture::new(ADMIN_POLL_MS).await;
+            if open.try_get_untracked() == Some(true) {
+                refresh(&base_url, &state).await;
+            }
+        }
+    });
+}
@@ -0,0 +1,74 @@
+//! Server-side alert sync
+//!
+//! Pushes the alert rules and webhook to `PUT /api/alerts` whenever either
+//! changes, so the server can deliver alerts while the dashboard is closed.
+
+use dash_core::{ServerAlerts, Symbol};
+use dash_state::{AlertSync, AppState};
+use gloo_net::http::Request;
+use leptos::prelude::*;
+
+/// Replace the server's alerts with `alerts`
+pub async fn push_alerts(base_url: &str, token: &str, alerts: &ServerAlerts) -> AlertSync {
+    let url = format!("{}/api/alerts", base_url);
+    let request = Request::put(&url)
+        .header("Authorization", &format!("Bearer {}", token.trim()))
+        .json(alerts);
+    let response = match request {
+        Ok(request) => request.send().await,
+        Err(e) => {
+            tracing::warn!("Failed to encode alerts: {}", e);
+            return AlertSync::Failed;
+        }
+    };
+    match response.map(|r| r.status()) {
+        Ok(200..=299) => AlertSync::Synced,
+        Ok(401) => AlertSync::Unauthorized,
+        Ok(404) => AlertSync::Unavailable,
+        Ok(422) => AlertSync::InvalidWebhook,
+        Ok(status) => {
+            tracing::warn!("Alert sync failed: HTTP {}", status);
+            AlertSync::Failed
+        }
+        Err(e) => {
+            tracing::debug!("Alert sync request failed ({}): {}", url, e);
+            AlertSync::Failed
+        }
+    }
+}
+
+/// Keep the server's alerts in step with the user's rules and delivery
+/// settings. Firing a rule does not re-push; only edits do.
+pub fn sync_alerts(base_url: String, state: AppState) {
+    let (alerts, delivery, sync) = (state.alerts, state.alert_delivery, state.alert_sync);
+    // What the server holds, compared instead of the compiled rules
+    let pushed = Memo::new(move |_| {
+        let rules: Vec<(u64, Symbol, String)> = alerts.with(|book| {
+            book.rules()
+                .iter()
+                .map(|rule| (rule.id(), rule.symbol().clone(), rule.source().to_string()))
+                .collect()
+        });
+        delivery.with(|d| d.server_enabled().then(|| (rules, d.webhook(), d.token.clone())))
+    });
+    // Answers to earlier pushes are dropped once a newer one is under way
+    let generation = StoredValue::new(0u64);
+    Effect::new(move |_| {
+        generation.update_value(|g| *g += 1);
+        let Some((_, _, token)) = pushed.get() else {
+            sync.set(AlertSync::Off);
+            return;
+        };
+        let body = alerts.with_untracked(|book| {
+            delivery.with_untracked(|d| d.server_alerts(book)).unwrap_or_default()
+        });
+        sync.set(AlertSync::Pending);
+        let (base_url, current) = (base_url.clone(), generation.get_value());
+        leptos::task::spawn_local(async move {
+            let status = push_alerts(&base_url, &token, &body).await;
+            if generation.try_get_value() == Some(current) {
+                sync.try_set(status);
+            }
+        });
+    });
+}
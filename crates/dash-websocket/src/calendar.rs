@@ -0,0 +1,23 @@
+//! Economic calendar polling for the session clock and chart markers
+
+use dash_core::ScheduledEvent;
+use dash_state::AppState;
+use gloo_timers::future::TimeoutFuture;
+use leptos::prelude::*;
+
+use crate::snapshot::fetch_json;
+
+/// Interval between `/api/calendar` polls (ms)
+pub const CALENDAR_POLL_MS: u32 = 10 * 60_000;
+
+/// Poll `/api/calendar` for the lifetime of the page. A failed poll keeps
+/// the events already known.
+pub async fn poll_calendar(base_url: String, state: AppState) {
+    let url = format!("{}/api/calendar", base_url);
+    loop {
+        if let Some(events) = fetch_json::<Vec<ScheduledEvent>>(&url).await {
+            state.calendar.set(events);
+        }
+        TimeoutFuture::new(CALENDAR_POLL_MS).await;
+    }
+}
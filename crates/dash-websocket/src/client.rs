@@ -1,24 +1,40 @@
 //! WebSocket client implementation with auto-reconnection
 
-use crate::{ReconnectPolicy, WsConfig};
-use dash_core::WsMessage;
-use dash_state::AppState;
-use futures::StreamExt;
-use gloo_net::websocket::{futures::WebSocket, Message};
-use gloo_timers::future::TimeoutFuture;
+use crate::sequence::{BookOrder, BookSequencer};
+use crate::shared::{run_shared, RelayMessage};
+use crate::transport::{self, Message, Socket};
+use crate::{ConnectionSharing, ReconnectPolicy, WsConfig};
+use dash_core::{
+    gzip_decode, is_gzip, ClientCommand, ConnectionState, OrderBookSnapshot, Rejected,
+    Subscription, Symbol, TolerantDecoder, WsEnvelope, WsMessage, PROTOCOL_VERSION,
+};
+use dash_state::{AppState, ParseError, VenueView};
+use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use futures::stream::SplitSink;
+use futures::{SinkExt, StreamExt};
 use leptos::prelude::*;
+use std::cell::RefCell;
+use std::rc::Rc;
 use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
-use wasm_bindgen_futures::spawn_local;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use tracing::Instrument;
+use web_sys::BroadcastChannel;
 
 // ============================================================================
 // WEBSOCKET CLIENT
 // ============================================================================
 
 /// WebSocket client for market data streaming
+#[derive(Clone)]
 pub struct WsClient {
-    config: WsConfig,
-    state: AppState,
+    pub(crate) config: WsConfig,
+    pub(crate) state: AppState,
+    /// Channel to rebroadcast frames on when this tab is the relay leader
+    relay: Option<BroadcastChannel>,
+    /// Inbound frame decoder, shared by clones of this client
+    decoder: Rc<RefCell<TolerantDecoder>>,
+    /// Book sequence checks, shared by clones of this client
+    books: Rc<RefCell<BookSequencer>>,
 }
 
 impl WsClient {
@@ -27,12 +43,27 @@ impl WsClient {
         Self {
             config: WsConfig::default(),
             state,
+            relay: None,
+            decoder: Rc::default(),
+            books: Rc::default(),
         }
     }
 
     /// Create with custom configuration
     pub fn with_config(state: AppState, config: WsConfig) -> Self {
-        Self { config, state }
+        Self {
+            config,
+            state,
+            relay: None,
+            decoder: Rc::default(),
+            books: Rc::default(),
+        }
+    }
+
+    /// Rebroadcast every received frame to follower tabs
+    pub(crate) fn with_relay(mut self, channel: BroadcastChannel) -> Self {
+        self.relay = Some(channel);
+        self
     }
 
     /// Set WebSocket URL
@@ -43,20 +74,26 @@ impl WsClient {
 
     /// Start the WebSocket connection (spawns async task)
     pub fn connect(self) -> WsHandle {
-        let handle = WsHandle::new();
+        let (handle, commands) = WsHandle::with_in_flight(self.state.ws_queue.clone());
         let handle_clone = handle.clone();
 
-        spawn_local(async move {
-            self.run_connection_loop(handle_clone).await;
+        transport::spawn(async move {
+            if self.config.sharing == ConnectionSharing::Dedicated {
+                self.run_connection_loop(handle_clone, commands).await;
+            } else {
+                run_shared(self, handle_clone, commands).await;
+            }
         });
 
         handle
     }
 
     /// Main connection loop with reconnection logic
-    async fn run_connection_loop(self, handle: WsHandle) {
+    pub(crate) async fn run_connection_loop(self, handle: WsHandle, commands: UnboundedReceiver<ClientCommand>) {
         let mut attempt = 0u32;
         let mut policy = self.config.reconnect_policy.clone();
+        let mut commands = commands;
+        let mut replay = ReplayState::default();
 
         loop {
             if handle.is_stopped() {
@@ -66,93 +103,324 @@ impl WsClient {
             }
 
             self.state.set_connecting();
-            tracing::info!("Connecting to WebSocket: {}", self.config.url);
+            let url = self.config.connect_url();
+            // Everything logged for this attempt, messages included
+            let span = tracing::info_span!("ws_connection", attempt = attempt + 1, %url);
+            span.in_scope(|| tracing::info!("Connecting to WebSocket"));
 
-            match WebSocket::open(&self.config.url) {
+            match transport::open(&url).await {
                 Ok(ws) => {
                     self.state.set_connected();
                     policy.reset();
                     attempt = 0;
 
-                    tracing::info!("WebSocket connected");
+                    span.in_scope(|| tracing::info!("WebSocket connected"));
 
-                    self.handle_connection(ws, &handle).await;
+                    self.handle_connection(ws, &handle, &mut commands, &mut replay)
+                        .instrument(span.clone())
+                        .await;
 
                     if handle.is_stopped() {
-                        tracing::info!("WebSocket stopped during connection");
+                        span.in_scope(|| tracing::info!("WebSocket stopped during connection"));
+                        break;
+                    }
+                    // Reconnecting cannot help until the page is reloaded
+                    if self.update_required() {
                         break;
                     }
 
                     self.state.set_disconnected();
-                    tracing::warn!("WebSocket disconnected");
+                    span.in_scope(|| tracing::warn!("WebSocket disconnected"));
                 }
                 Err(e) => {
-                    tracing::error!("WebSocket connection failed: {:?}", e);
-                    self.state.set_error(format!("Connection failed: {:?}", e));
+                    span.in_scope(|| tracing::error!(error = %e, "WebSocket connection failed"));
+                    self.state.set_error(format!("Connection failed: {}", e));
                 }
             }
 
             if !policy.should_reconnect(attempt) {
-                tracing::error!("Max reconnection attempts ({}) reached", attempt);
+                tracing::error!(attempt, "Max reconnection attempts reached");
                 self.state.set_error("Max reconnection attempts reached");
                 break;
             }
 
             let delay = policy.delay_ms(attempt);
             self.state.set_reconnecting();
-            tracing::info!("Reconnecting in {}ms (attempt {})", delay, attempt + 1);
+            tracing::info!(delay_ms = delay, attempt = attempt + 1, "Reconnecting");
 
-            TimeoutFuture::new(delay).await;
+            transport::sleep(delay).await;
             attempt += 1;
         }
     }
 
     /// Handle an active WebSocket connection
-    async fn handle_connection(&self, ws: WebSocket, handle: &WsHandle) {
-        let (_write, mut read) = ws.split();
+    async fn handle_connection(
+        &self,
+        ws: Socket,
+        handle: &WsHandle,
+        commands: &mut UnboundedReceiver<ClientCommand>,
+        replay: &mut ReplayState,
+    ) {
+        let (mut write, read) = ws.split();
+        let mut read = read.fuse();
+        self.books.borrow_mut().reconnected();
+
+        // Restore auth and the desired subscriptions before anything else
+        let restore = replay
+            .commands()
+            .into_iter()
+            .chain(self.state.subscriptions.get_untracked().replay_commands());
+
+        for cmd in restore {
+            if !Self::write_command(&mut write, &cmd).await {
+                return;
+            }
+        }
 
-        while let Some(msg) = read.next().await {
-            if handle.is_stopped() {
-                break;
+        if let Some(cmd) = replay.retry.take() {
+            if !Self::write_command(&mut write, &cmd).await {
+                replay.retry = Some(cmd);
+                return;
             }
+            handle.in_flight.fetch_sub(1, Ordering::SeqCst);
+            self.record_command(replay, &cmd);
+        }
 
-            match msg {
-                Ok(Message::Text(text)) => {
-                    self.process_message(&text);
+        loop {
+            futures::select! {
+                msg = read.next() => {
+                    let Some(msg) = msg else { break };
+
+                    if handle.is_stopped() {
+                        break;
+                    }
+
+                    match msg {
+                        Ok(Message::Text(text)) => {
+                            self.state.stats.update(|s| s.record(text.len(), text.len()));
+                            self.relay_frame(&text);
+                            self.process_message(&text);
+                        }
+                        Ok(Message::Bytes(bytes)) => {
+                            if let Some(text) = Self::decode_binary(&bytes) {
+                                self.state.stats.update(|s| s.record(bytes.len(), text.len()));
+                                self.relay_frame(&text);
+                                self.process_message(&text);
+                            }
+                        }
+                        Err(e) => {
+                            tracing::error!("WebSocket error: {:?}", e);
+                            break;
+                        }
+                    }
+                    if self.update_required() {
+                        break;
+                    }
+                    // A failed write surfaces as a closed socket on the next read
+                    for cmd in self.take_resyncs() {
+                        if !Self::write_command(&mut write, &cmd).await {
+                            break;
+                        }
+                    }
                 }
-                Ok(Message::Bytes(bytes)) => {
-                    if let Ok(text) = String::from_utf8(bytes) {
-                        self.process_message(&text);
+                cmd = commands.next() => {
+                    // All handles dropped: nobody can send anymore, keep reading
+                    let Some(cmd) = cmd else { continue };
+
+                    if !Self::write_command(&mut write, &cmd).await {
+                        // Not delivered; stays queued for the next connection
+                        replay.retry = Some(cmd);
+                        break;
                     }
+                    handle.in_flight.fetch_sub(1, Ordering::SeqCst);
+                    self.record_command(replay, &cmd);
                 }
+            }
+        }
+    }
+
+    /// Track a delivered command so it can be restored after reconnect
+    fn record_command(&self, replay: &mut ReplayState, cmd: &ClientCommand) {
+        replay.record(cmd);
+        self.track_subscription(cmd);
+    }
+
+    /// Keep the desired subscription set in sync with sent commands
+    pub(crate) fn track_subscription(&self, cmd: &ClientCommand) {
+        if matches!(cmd, ClientCommand::Subscribe(_) | ClientCommand::Unsubscribe { .. }) {
+            self.state.subscriptions.update(|subs| subs.apply(cmd));
+        }
+    }
+
+    /// Mirror the connection state reported by the tab/worker owning the socket
+    pub(crate) fn set_relayed_state(&self, connected: bool) {
+        if self.update_required() {
+            return;
+        }
+        if connected {
+            if !self.state.connection.get_untracked().is_connected() {
+                self.state.set_connected();
+            }
+        } else {
+            self.state.set_reconnecting();
+        }
+    }
+
+    /// Serialize and write a single command, returning false if the socket is gone
+    async fn write_command(write: &mut SplitSink<Socket, Message>, cmd: &ClientCommand) -> bool {
+        let json = match serde_json::to_string(cmd) {
+            Ok(json) => json,
+            Err(e) => {
+                tracing::error!("Failed to serialize command: {}", e);
+                return true;
+            }
+        };
+
+        match write.send(Message::Text(json)).await {
+            Ok(()) => {
+                tracing::debug!("Sent command: {:?}", cmd);
+                true
+            }
+            Err(e) => {
+                tracing::warn!("Failed to send command: {:?}", e);
+                false
+            }
+        }
+    }
+
+    /// Decode a binary frame (gzip-compressed or raw UTF-8 JSON)
+    fn decode_binary(bytes: &[u8]) -> Option<String> {
+        let decoded = if is_gzip(bytes) {
+            match gzip_decode(bytes) {
+                Ok(decoded) => decoded,
                 Err(e) => {
-                    tracing::error!("WebSocket error: {:?}", e);
-                    break;
+                    tracing::warn!("Failed to decompress frame: {}", e);
+                    return None;
                 }
             }
+        } else {
+            bytes.to_vec()
+        };
+
+        String::from_utf8(decoded).ok()
+    }
+
+    /// Forward a frame to follower tabs when acting as relay leader
+    fn relay_frame(&self, text: &str) {
+        if let Some(relay) = &self.relay {
+            RelayMessage::Frame { text: text.to_string() }.post(relay);
         }
     }
 
     /// Process a received WebSocket message
-    fn process_message(&self, text: &str) {
-        match serde_json::from_str::<WsMessage>(text) {
-            Ok(msg) => {
-                self.dispatch_message(msg);
+    pub(crate) fn process_message(&self, text: &str) {
+        let _span = tracing::trace_span!("dispatch", bytes = text.len()).entered();
+        let started = transport::now_ms();
+        self.apply_frame(text);
+        let now = transport::now_ms();
+        self.state.diagnostics.update_value(|d| d.dispatch.record(now - started, now));
+    }
+
+    fn apply_frame(&self, text: &str) {
+        match self.decode(text) {
+            Ok(frame) if PROTOCOL_VERSION.reads(frame.v) => {
+                self.dispatch_message(frame.message);
             }
-            Err(e) => {
-                tracing::warn!("Failed to parse WebSocket message: {}", e);
+            Ok(frame) => {
+                tracing::error!(server = frame.v, client = %PROTOCOL_VERSION, "Protocol mismatch");
+                self.state.set_update_required(frame.v);
             }
+            Err(Rejected::Failed { kind, error }) => {
+                tracing::warn!(%kind, %error, "Failed to parse message");
+                self.record_parse_error(kind, error);
+            }
+            Err(Rejected::Quarantined { kind, error }) => {
+                tracing::warn!(%kind, %error, "Quarantining messages after repeated failures");
+                self.record_parse_error(kind, error);
+            }
+            Err(Rejected::Dropped { .. }) => {}
+        }
+    }
+
+    fn record_parse_error(&self, kind: String, error: String) {
+        let error = ParseError { timestamp: transport::date_now() as i64, kind, error };
+        self.state.diagnostics.update_value(|d| d.record_parse_error(error));
+    }
+
+    /// Decode a frame, publishing the decoder's metrics when a frame needed
+    /// repair or was rejected
+    fn decode(&self, text: &str) -> Result<WsEnvelope, Rejected> {
+        let mut decoder = self.decoder.borrow_mut();
+        let result = decoder.decode(text, transport::date_now() as i64);
+        let stats = &decoder.stats;
+        let counts = (stats.failed(), stats.recovered(), stats.dropped());
+        let published = self.state.decode_stats.with_untracked(|s| {
+            (s.failed(), s.recovered(), s.dropped())
+        });
+        if counts != published {
+            self.state.decode_stats.set(stats.clone());
+        }
+        result
+    }
+
+    /// Check a book's sequence, noting gaps for the overlay; false drops it
+    fn sequence_book(&self, book: &OrderBookSnapshot) -> bool {
+        let mut books = self.books.borrow_mut();
+        let order = books.observe(book);
+        if let BookOrder::Gap { missed } = order {
+            tracing::warn!(symbol = %book.symbol, missed, "Order book sequence gap, resyncing");
         }
+        let stats = books.stats;
+        self.state.diagnostics.update_value(|d| d.books = stats);
+        order != BookOrder::Stale
+    }
+
+    /// Resync requests owed for sequence gaps
+    pub(crate) fn take_resyncs(&self) -> Vec<ClientCommand> {
+        let mut books = self.books.borrow_mut();
+        let symbols = books.take_resyncs();
+        if symbols.is_empty() {
+            return Vec::new();
+        }
+        let stats = books.stats;
+        self.state.diagnostics.update_value(|d| d.books = stats);
+        symbols.into_iter().map(ClientCommand::resync).collect()
+    }
+
+    fn update_required(&self) -> bool {
+        self.state.connection.get_untracked() == ConnectionState::UpdateRequired
     }
 
     /// Dispatch parsed message to appropriate state handler
     fn dispatch_message(&self, msg: WsMessage) {
+        let market = &self.state.market;
+        // Market data moves what alert rules read
+        let market_data = matches!(
+            msg,
+            WsMessage::Trade(_)
+                | WsMessage::OrderBook(_)
+                | WsMessage::ConsolidatedBook(_)
+                | WsMessage::Ticker(_)
+                | WsMessage::Candle(_)
+        );
         match msg {
             WsMessage::Trade(trade) => {
-                self.state.market.add_trade(trade);
+                // Paper orders fill on every live print, even while paused
+                #[cfg(feature = "paper-trading")]
+                self.state.fill_paper_orders(&trade);
+                if market.venue_view.with_untracked(|v| v.shows(trade.venue.as_deref())) {
+                    market.add_trade(trade);
+                }
             }
             WsMessage::OrderBook(book) => {
-                self.state.market.update_orderbook(book);
+                if self.sequence_book(&book)
+                    && market.venue_view.with_untracked(|v| v.shows_book(book.venue.as_deref()))
+                {
+                    market.update_orderbook(book);
+                }
+            }
+            WsMessage::ConsolidatedBook(book) => {
+                market.update_consolidated(book);
             }
             WsMessage::Ticker(ticker) => {
                 self.state.market.update_ticker(ticker);
@@ -161,30 +429,120 @@ impl WsClient {
                 self.state.market.update_candle(candle);
             }
             WsMessage::Depth(depth) => {
-                self.state.market.depth.set(Some(depth));
+                // Server depth is single-venue; merged views derive it from the book
+                if market.venue_view.with_untracked(|v| *v != VenueView::Consolidated) {
+                    market.update_depth(depth);
+                }
             }
             WsMessage::Heartbeat { timestamp } => {
                 tracing::trace!("Heartbeat received: {}", timestamp);
+                self.state.record_server_time(timestamp, transport::date_now() as i64);
+            }
+            WsMessage::SourceStatus(status) => {
+                if status.is_degraded() {
+                    tracing::warn!("Market data degraded: {:?} from {}", status.status, status.source);
+                }
+                self.state.source_status.set(Some(status));
             }
+            WsMessage::News(item) => {
+                self.state.add_news(item);
+            }
+            WsMessage::AlertTriggered(notice) => {
+                self.state.raise_server_alert(&notice);
+            }
+        }
+        if market_data {
+            self.state.check_alerts();
+        }
+    }
+}
+
+// ============================================================================
+// COMMAND REPLAY
+// ============================================================================
+
+/// Connection-scoped commands re-sent whenever a new connection is established.
+/// Subscriptions are restored from `AppState::subscriptions`.
+#[derive(Debug, Default)]
+struct ReplayState {
+    auth: Option<ClientCommand>,
+    /// Command whose write failed on the previous connection
+    retry: Option<ClientCommand>,
+}
+
+impl ReplayState {
+    /// Track a command that was successfully written to the socket
+    fn record(&mut self, cmd: &ClientCommand) {
+        if let ClientCommand::Auth { .. } = cmd {
+            self.auth = Some(cmd.clone());
         }
     }
+
+    /// Commands to send on (re)connect, ahead of subscriptions
+    fn commands(&self) -> Vec<ClientCommand> {
+        self.auth.iter().cloned().collect()
+    }
 }
 
 // ============================================================================
 // WEBSOCKET HANDLE (Send + Sync)
 // ============================================================================
 
+
 /// Handle for controlling the WebSocket connection
 #[derive(Clone)]
 pub struct WsHandle {
     stopped: Arc<AtomicBool>,
+    commands: UnboundedSender<ClientCommand>,
+    /// Commands queued but not yet written to the socket
+    pub(crate) in_flight: Arc<AtomicUsize>,
 }
 
 impl WsHandle {
-    fn new() -> Self {
-        Self {
-            stopped: Arc::new(AtomicBool::new(false)),
+    pub(crate) fn new() -> (Self, UnboundedReceiver<ClientCommand>) {
+        Self::with_in_flight(Arc::new(AtomicUsize::new(0)))
+    }
+
+    /// Handle counting its queued commands in `in_flight`
+    pub(crate) fn with_in_flight(
+        in_flight: Arc<AtomicUsize>,
+    ) -> (Self, UnboundedReceiver<ClientCommand>) {
+        let (tx, rx) = mpsc::unbounded();
+        let handle = Self { stopped: Arc::new(AtomicBool::new(false)), commands: tx, in_flight };
+        (handle, rx)
+    }
+
+    /// Queue a command for the server.
+    ///
+    /// Commands sent while disconnected are delivered once the connection is
+    /// re-established. Returns false if the connection loop has shut down.
+    pub fn send(&self, cmd: ClientCommand) -> bool {
+        if self.commands.unbounded_send(cmd).is_err() {
+            tracing::warn!("WebSocket command dropped: connection loop is gone");
+            return false;
         }
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        true
+    }
+
+    /// Subscribe to market data for a symbol
+    pub fn subscribe(&self, symbol: impl Into<Symbol>) -> bool {
+        self.send(ClientCommand::subscribe(symbol))
+    }
+
+    /// Subscribe with explicit stream parameters (interval, depth levels)
+    pub fn subscribe_with(&self, sub: Subscription) -> bool {
+        self.send(ClientCommand::Subscribe(sub))
+    }
+
+    /// Unsubscribe from market data for a symbol
+    pub fn unsubscribe(&self, symbol: impl Into<Symbol>) -> bool {
+        self.send(ClientCommand::unsubscribe(symbol))
+    }
+
+    /// Number of commands queued but not yet written to the socket
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
     }
 
     /// Stop the WebSocket connection
@@ -228,7 +586,7 @@ mod tests {
 
     #[test]
     fn test_ws_handle() {
-        let handle = WsHandle::new();
+        let (handle, _commands) = WsHandle::new();
         assert!(!handle.is_stopped());
         assert!(handle.is_running());
 
@@ -237,6 +595,43 @@ mod tests {
         assert!(!handle.is_running());
     }
 
+    #[test]
+    fn test_ws_handle_send_queue() {
+        let (handle, mut commands) = WsHandle::new();
+
+        assert!(handle.subscribe("BTC-USD"));
+        assert!(handle.send(ClientCommand::Ping));
+        assert_eq!(handle.in_flight(), 2);
+
+        assert_eq!(commands.try_next().unwrap(), Some(ClientCommand::subscribe("BTC-USD")));
+        assert_eq!(commands.try_next().unwrap(), Some(ClientCommand::Ping));
+
+        drop(commands);
+        assert!(!handle.send(ClientCommand::Ping));
+        assert_eq!(handle.in_flight(), 2);
+    }
+
+    #[test]
+    fn test_decode_binary() {
+        let json = r#"{"type":"heartbeat","data":{"timestamp":1}}"#;
+        let gz = dash_core::gzip_encode(json.as_bytes()).unwrap();
+
+        assert_eq!(WsClient::decode_binary(&gz).as_deref(), Some(json));
+        assert_eq!(WsClient::decode_binary(json.as_bytes()).as_deref(), Some(json));
+        assert_eq!(WsClient::decode_binary(&[0x1f, 0x8b, 0x00]), None);
+    }
+
+    #[test]
+    fn test_replay_state() {
+        let mut replay = ReplayState::default();
+        replay.record(&ClientCommand::auth("old"));
+        replay.record(&ClientCommand::subscribe("BTC-USD"));
+        replay.record(&ClientCommand::auth("secret"));
+        replay.record(&ClientCommand::Ping);
+
+        assert_eq!(replay.commands(), vec![ClientCommand::auth("secret")]);
+    }
+
     #[test]
     fn test_ws_config() {
         let config = WsConfig::new("ws://localhost:8080")
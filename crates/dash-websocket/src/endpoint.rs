@@ -0,0 +1,72 @@
+//! WebSocket endpoint discovery
+//!
+//! By default the client connects back to the origin that served the page,
+//! so deployments behind HTTPS or a reverse proxy get `wss://` on the right
+//! host without rebuilding. Overrides, in priority order:
+//! 1. `?ws=wss://host/ws` on the page URL
+//! 2. `DASH_WS_URL` at build time
+//! 3. `window.location` (`https:` -> `wss:`)
+//! 4. [`DEFAULT_WS_URL`](crate::DEFAULT_WS_URL)
+
+/// Path the server exposes its WebSocket endpoint on
+pub const WS_PATH: &str = "/ws";
+
+/// Page query parameter overriding the WebSocket URL
+pub const WS_URL_PARAM: &str = "ws";
+
+/// WebSocket URL on the page's own origin (None for non-HTTP pages, e.g. `file:`)
+pub fn ws_url_for_location(protocol: &str, host: &str) -> Option<String> {
+    let scheme = match protocol.trim_end_matches(':') {
+        "https" => "wss",
+        "http" => "ws",
+        _ => return None,
+    };
+    (!host.is_empty()).then(|| format!("{}://{}{}", scheme, host, WS_PATH))
+}
+
+/// Accept only absolute `ws://` / `wss://` overrides
+fn valid_override(url: &str) -> Option<String> {
+    let url = url.trim();
+    (url.starts_with("ws://") || url.starts_with("wss://")).then(|| url.to_string())
+}
+
+/// Resolve the WebSocket URL for this page (see module docs for precedence)
+pub fn resolve_ws_url() -> String {
+    let location = web_sys::window().map(|w| w.location());
+
+    let from_query = location
+        .as_ref()
+        .and_then(|l| l.search().ok())
+        .and_then(|search| web_sys::UrlSearchParams::new_with_str(&search).ok())
+        .and_then(|params| params.get(WS_URL_PARAM))
+        .and_then(|url| valid_override(&url));
+    let from_build = option_env!("DASH_WS_URL").and_then(valid_override);
+    let from_location = location.as_ref().and_then(|l| {
+        ws_url_for_location(&l.protocol().ok()?, &l.host().ok()?)
+    });
+
+    from_query
+        .or(from_build)
+        .or(from_location)
+        .unwrap_or_else(|| crate::DEFAULT_WS_URL.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ws_url_for_location() {
+        assert_eq!(ws_url_for_location("https:", "dash.example.com").as_deref(), Some("wss://dash.example.com/ws"));
+        assert_eq!(ws_url_for_location("http:", "127.0.0.1:8080").as_deref(), Some("ws://127.0.0.1:8080/ws"));
+        assert_eq!(ws_url_for_location("file:", ""), None);
+        assert_eq!(ws_url_for_location("http:", ""), None);
+    }
+
+    #[test]
+    fn test_override_validation() {
+        assert_eq!(valid_override(" wss://feed.example.com/ws ").as_deref(), Some("wss://feed.example.com/ws"));
+        assert_eq!(valid_override("https://feed.example.com/ws"), None);
+        assert_eq!(valid_override("/ws"), None);
+    }
+}
@@ -0,0 +1,22 @@
+//! Server feed health polling for the status bar
+
+use dash_core::HealthReport;
+use dash_state::AppState;
+use gloo_timers::future::TimeoutFuture;
+use leptos::prelude::*;
+
+use crate::snapshot::fetch_json;
+
+/// Interval between `/healthz` polls (ms)
+pub const HEALTH_POLL_MS: u32 = 5_000;
+
+/// Poll `/healthz` for the lifetime of the page.
+/// An unreachable server clears the report.
+pub async fn poll_health(base_url: String, state: AppState) {
+    let url = format!("{}/healthz", base_url);
+    loop {
+        let report = fetch_json::<HealthReport>(&url).await;
+        state.server_health.set(report);
+        TimeoutFuture::new(HEALTH_POLL_MS).await;
+    }
+}
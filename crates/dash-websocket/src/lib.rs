@@ -3,11 +3,32 @@
 //! WebSocket client with automatic reconnection and message handling.
 //! Uses Strategy pattern for reconnection backoff policies.
 
+pub mod account;
+pub mod admin;
+pub mod alerts;
+pub mod calendar;
 pub mod client;
-
+pub mod endpoint;
+pub mod health;
+pub mod sequence;
+pub mod shared;
+pub mod snapshot;
+mod transport;
+
+pub use account::{fetch_document, fetch_role, put_document, sign_in, sync_account};
+pub use admin::{fetch_admin_status, send_admin_command, sync_admin};
+pub use alerts::{push_alerts, sync_alerts};
+pub use calendar::poll_calendar;
 pub use client::*;
+pub use endpoint::{resolve_ws_url, ws_url_for_location};
+pub use health::poll_health;
+pub use sequence::{BookOrder, BookSequencer};
+pub use shared::ConnectionSharing;
+pub use snapshot::{load_candle_history, load_snapshot, resync_snapshot, rest_base_url};
+
+use dash_core::{WireEncoding, ENCODING_PARAM, PROTOCOL_PARAM, PROTOCOL_VERSION};
 
-/// Default WebSocket server URL
+/// Fallback WebSocket server URL when none can be derived from the page
 pub const DEFAULT_WS_URL: &str = "ws://127.0.0.1:3001/ws";
 
 // ============================================================================
@@ -204,6 +225,10 @@ pub struct WsConfig {
     pub heartbeat_interval_ms: u32,
     /// Connection timeout in milliseconds
     pub connect_timeout_ms: u32,
+    /// Cross-tab connection sharing mode
+    pub sharing: ConnectionSharing,
+    /// Requested frame encoding (negotiated via URL query parameter)
+    pub encoding: WireEncoding,
 }
 
 impl Default for WsConfig {
@@ -213,6 +238,8 @@ impl Default for WsConfig {
             reconnect_policy: ExponentialBackoff::default(),
             heartbeat_interval_ms: 30000,
             connect_timeout_ms: 10000,
+            sharing: ConnectionSharing::Dedicated,
+            encoding: WireEncoding::Text,
         }
     }
 }
@@ -239,6 +266,26 @@ impl WsConfig {
         self.connect_timeout_ms = timeout_ms;
         self
     }
+
+    pub fn sharing(mut self, sharing: ConnectionSharing) -> Self {
+        self.sharing = sharing;
+        self
+    }
+
+    pub fn encoding(mut self, encoding: WireEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// URL to open, including the protocol and encoding negotiation parameters
+    pub fn connect_url(&self) -> String {
+        let sep = if self.url.contains('?') { '&' } else { '?' };
+        let url = format!("{}{}{}={}", self.url, sep, PROTOCOL_PARAM, PROTOCOL_VERSION);
+        if self.encoding == WireEncoding::Text {
+            return url;
+        }
+        format!("{}&{}={}", url, ENCODING_PARAM, self.encoding)
+    }
 }
 
 #[cfg(test)]
@@ -288,4 +335,16 @@ mod tests {
         assert_eq!(policy.delay_ms(2), 2000);
         assert_eq!(policy.delay_ms(10), 5000); // Capped
     }
+
+    #[test]
+    fn test_connect_url_encoding() {
+        let config = WsConfig::new("ws://localhost:3001/ws");
+        assert_eq!(config.connect_url(), "ws://localhost:3001/ws?protocol=2.2");
+
+        let config = config.encoding(WireEncoding::Gzip);
+        assert_eq!(config.connect_url(), "ws://localhost:3001/ws?protocol=2.2&encoding=gzip");
+
+        let config = WsConfig::new("ws://host/ws?token=abc").encoding(WireEncoding::Gzip);
+        assert_eq!(config.connect_url(), "ws://host/ws?token=abc&protocol=2.2&encoding=gzip");
+    }
 }
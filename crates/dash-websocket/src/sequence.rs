@@ -0,0 +1,125 @@
+//! Order book sequence checks
+//!
+//! Books are full snapshots numbered per symbol and venue. One that skips
+//! numbers means the stream lost frames, so the client asks the server to
+//! resync instead of waiting for the next update. One at or below the
+//! latest applied is a duplicate or arrived late and is dropped, so the
+//! panels never step back in time.
+
+use std::collections::HashMap;
+
+use dash_core::{OrderBookSnapshot, Symbol};
+use dash_state::BookGaps;
+
+/// Where a received book falls relative to the last applied one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookOrder {
+    /// First book of its stream, or the one right after the last
+    Next,
+    /// `missed` books never arrived; apply it and resync
+    Gap { missed: u64 },
+    /// Already applied or superseded; drop it
+    Stale,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Track {
+    last: u64,
+    /// Set on reconnect: the server may have restarted its numbering
+    resumed: bool,
+}
+
+/// Last applied book sequence per symbol and venue
+#[derive(Debug, Default)]
+pub struct BookSequencer {
+    tracks: HashMap<(Symbol, Option<String>), Track>,
+    /// Symbols waiting for a resync request to be sent
+    resyncs: Vec<Symbol>,
+    pub stats: BookGaps,
+}
+
+impl BookSequencer {
+    pub fn observe(&mut self, book: &OrderBookSnapshot) -> BookOrder {
+        let key = (book.symbol.clone(), book.venue.clone());
+        let seq = book.sequence;
+        let order = match self.tracks.get(&key) {
+            None => BookOrder::Next,
+            Some(track) if seq > track.last + 1 => BookOrder::Gap { missed: seq - track.last - 1 },
+            Some(track) if seq > track.last || track.resumed => BookOrder::Next,
+            Some(_) => BookOrder::Stale,
+        };
+        if order != BookOrder::Stale {
+            self.tracks.insert(key, Track { last: seq, resumed: false });
+        }
+
+        match order {
+            BookOrder::Gap { missed } => {
+                self.stats.gaps += 1;
+                self.stats.missed += missed;
+                if !self.resyncs.contains(&book.symbol) {
+                    self.resyncs.push(book.symbol.clone());
+                }
+            }
+            BookOrder::Stale => self.stats.stale += 1,
+            BookOrder::Next => {}
+        }
+        order
+    }
+
+    /// A new connection: books continue from where they were, unless the
+    /// server restarted and numbers from scratch
+    pub fn reconnected(&mut self) {
+        for track in self.tracks.values_mut() {
+            track.resumed = true;
+        }
+    }
+
+    /// Symbols to request a resync for, each once per gap
+    pub fn take_resyncs(&mut self) -> Vec<Symbol> {
+        self.stats.resyncs += self.resyncs.len() as u64;
+        std::mem::take(&mut self.resyncs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn book(sequence: u64, venue: Option<&str>) -> OrderBookSnapshot {
+        let mut book = OrderBookSnapshot::new(Symbol::new("BTC-USD"));
+        book.sequence = sequence;
+        book.venue = venue.map(str::to_string);
+        book
+    }
+
+    #[test]
+    fn test_book_sequencer() {
+        let mut sequencer = BookSequencer::default();
+        assert_eq!(sequencer.observe(&book(5, None)), BookOrder::Next);
+        assert_eq!(sequencer.observe(&book(6, None)), BookOrder::Next);
+        assert_eq!(sequencer.observe(&book(6, None)), BookOrder::Stale);
+        assert_eq!(sequencer.observe(&book(9, None)), BookOrder::Gap { missed: 2 });
+        assert_eq!(sequencer.observe(&book(8, None)), BookOrder::Stale);
+        assert_eq!(sequencer.observe(&book(12, None)), BookOrder::Gap { missed: 2 });
+        // Venues number their books independently
+        assert_eq!(sequencer.observe(&book(1, Some("kraken"))), BookOrder::Next);
+
+        assert_eq!(sequencer.take_resyncs(), vec![Symbol::new("BTC-USD")]);
+        assert!(sequencer.take_resyncs().is_empty());
+        let stats = sequencer.stats;
+        assert_eq!((stats.gaps, stats.missed, stats.stale, stats.resyncs), (2, 4, 2, 1));
+    }
+
+    #[test]
+    fn test_book_sequencer_reconnect() {
+        let mut sequencer = BookSequencer::default();
+        sequencer.observe(&book(10, None));
+        sequencer.reconnected();
+        // A restarted server numbers from scratch
+        assert_eq!(sequencer.observe(&book(1, None)), BookOrder::Next);
+        assert_eq!(sequencer.observe(&book(1, None)), BookOrder::Stale);
+
+        sequencer.reconnected();
+        assert_eq!(sequencer.observe(&book(4, None)), BookOrder::Gap { missed: 2 });
+    }
+}
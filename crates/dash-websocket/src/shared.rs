@@ -0,0 +1,490 @@
+//! Cross-tab connection sharing
+//!
+//! Lets several dashboard tabs share one upstream WebSocket, either through a
+//! SharedWorker or by electing a leader tab that relays frames to the others
+//! over a BroadcastChannel.
+
+use crate::{WsClient, WsHandle};
+use dash_core::ClientCommand;
+use dash_state::SubscriptionSet;
+use futures::channel::mpsc::UnboundedReceiver;
+use futures::{FutureExt, StreamExt};
+use gloo_timers::future::TimeoutFuture;
+use leptos::ev;
+use leptos::prelude::{window_event_listener, GetUntracked};
+use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+use std::sync::atomic::Ordering;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{BroadcastChannel, MessageEvent, MessagePort, SharedWorker};
+
+/// BroadcastChannel name used by the relay
+pub const RELAY_CHANNEL: &str = "dash-ws-relay";
+
+/// Interval between leader heartbeats (ms)
+pub const HEARTBEAT_INTERVAL_MS: u32 = 1000;
+
+/// A leader is considered gone after this long without a heartbeat (ms)
+pub const LEADER_TIMEOUT_MS: f64 = 3000.0;
+
+/// How long a new tab listens for an existing leader before claiming (ms)
+pub const ELECTION_GRACE_MS: f64 = 1500.0;
+
+// ============================================================================
+// CONFIGURATION
+// ============================================================================
+
+/// How a tab obtains its market data stream
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ConnectionSharing {
+    /// Each tab opens its own WebSocket
+    #[default]
+    Dedicated,
+    /// One WebSocket owned by a SharedWorker running `script_url`.
+    /// Falls back to `BroadcastRelay` where SharedWorker is unavailable.
+    SharedWorker { script_url: String },
+    /// Tabs elect a leader that owns the WebSocket and relays frames
+    BroadcastRelay,
+}
+
+impl ConnectionSharing {
+    pub fn shared_worker(script_url: impl Into<String>) -> Self {
+        Self::SharedWorker {
+            script_url: script_url.into(),
+        }
+    }
+}
+
+// ============================================================================
+// RELAY PROTOCOL
+// ============================================================================
+
+/// Messages exchanged between tabs and with the shared worker
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum RelayMessage {
+    /// Leader is alive; `connected` mirrors its upstream connection
+    Heartbeat { leader: u64, connected: bool },
+    /// Raw server frame
+    Frame { text: String },
+    /// Command from a follower to be sent upstream
+    Command { cmd: ClientCommand },
+    /// Ask the shared worker to open the upstream socket
+    Open { url: String },
+    /// Tab is going away (shared worker stops fanning out to it)
+    Detach,
+}
+
+impl RelayMessage {
+    fn to_js(&self) -> Option<JsValue> {
+        serde_json::to_string(self).ok().map(|json| JsValue::from_str(&json))
+    }
+
+    fn from_event(event: &MessageEvent) -> Option<Self> {
+        event
+            .data()
+            .as_string()
+            .and_then(|json| serde_json::from_str(&json).ok())
+    }
+
+    pub(crate) fn post(&self, channel: &BroadcastChannel) {
+        if let Some(value) = self.to_js() {
+            let _ = channel.post_message(&value);
+        }
+    }
+
+    fn post_port(&self, port: &MessagePort) {
+        if let Some(value) = self.to_js() {
+            let _ = port.post_message(&value);
+        }
+    }
+}
+
+// ============================================================================
+// LEADER ELECTION
+// ============================================================================
+
+/// Role of this tab in the relay
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Role {
+    Follower,
+    Leader,
+}
+
+/// Heartbeat-based leader election; the lowest tab id wins ties
+#[derive(Debug)]
+pub(crate) struct LeaderElection {
+    id: u64,
+    role: Role,
+    started_at: f64,
+    /// Last heartbeat seen from another leader (id, time)
+    leader_seen: Option<(u64, f64)>,
+}
+
+impl LeaderElection {
+    pub(crate) fn new(id: u64, now: f64) -> Self {
+        Self {
+            id,
+            role: Role::Follower,
+            started_at: now,
+            leader_seen: None,
+        }
+    }
+
+    /// Record a heartbeat; a leader yields to any leader with a lower id
+    pub(crate) fn observe_heartbeat(&mut self, from: u64, now: f64) {
+        if from == self.id {
+            return;
+        }
+        self.leader_seen = Some((from, now));
+        if self.role == Role::Leader && from < self.id {
+            self.role = Role::Follower;
+        }
+    }
+
+    /// Whether another tab is currently leading
+    pub(crate) fn has_leader(&self, now: f64) -> bool {
+        self.leader_seen
+            .is_some_and(|(_, seen)| now - seen < LEADER_TIMEOUT_MS)
+    }
+
+    /// Re-evaluate and return this tab's role
+    pub(crate) fn tick(&mut self, now: f64) -> Role {
+        if self.role == Role::Follower
+            && !self.has_leader(now)
+            && now - self.started_at >= ELECTION_GRACE_MS
+        {
+            self.role = Role::Leader;
+        }
+        self.role
+    }
+}
+
+/// Reasonably unique id for this tab
+fn tab_id() -> u64 {
+    let now = js_sys::Date::now() as u64;
+    let salt = (js_sys::Math::random() * 1_000_000.0) as u64;
+    now * 1_000_000 + salt
+}
+
+fn has_global(name: &str) -> bool {
+    js_sys::Reflect::has(&js_sys::global(), &JsValue::from_str(name)).unwrap_or(false)
+}
+
+// ============================================================================
+// RUNTIME
+// ============================================================================
+
+/// Run the client according to its sharing mode
+pub(crate) async fn run_shared(
+    client: WsClient,
+    handle: WsHandle,
+    commands: UnboundedReceiver<ClientCommand>,
+) {
+    match client.config.sharing.clone() {
+        ConnectionSharing::SharedWorker { script_url } if has_global("SharedWorker") => {
+            run_shared_worker(client, handle, commands, &script_url).await;
+        }
+        ConnectionSharing::SharedWorker { .. } | ConnectionSharing::BroadcastRelay
+            if has_global("BroadcastChannel") =>
+        {
+            run_relay(client, handle, commands).await;
+        }
+        _ => {
+            client.run_connection_loop(handle, commands).await;
+        }
+    }
+}
+
+/// SharedWorker mode: the worker owns the socket, this tab only relays
+async fn run_shared_worker(
+    client: WsClient,
+    handle: WsHandle,
+    mut commands: UnboundedReceiver<ClientCommand>,
+    script_url: &str,
+) {
+    let worker = match SharedWorker::new(script_url) {
+        Ok(worker) => worker,
+        Err(e) => {
+            tracing::warn!("SharedWorker unavailable ({:?}), using relay", e);
+            run_relay(client, handle, commands).await;
+            return;
+        }
+    };
+
+    let port = worker.port();
+    let onmessage = {
+        let (client, port) = (client.clone(), port.clone());
+        Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+            match RelayMessage::from_event(&event) {
+                Some(RelayMessage::Heartbeat { connected, .. }) => client.set_relayed_state(connected),
+                Some(RelayMessage::Frame { text }) => {
+                    client.process_message(&text);
+                    // Gaps this tab saw; the worker's socket carries the resync
+                    for cmd in client.take_resyncs() {
+                        RelayMessage::Command { cmd }.post_port(&port);
+                    }
+                }
+                _ => {}
+            }
+        })
+    };
+    port.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+    port.start();
+
+    tracing::info!("Sharing WebSocket via SharedWorker: {}", script_url);
+    client.state.set_connecting();
+    attach_port(&client, &port);
+
+    // A hidden page may never run again; a restored one attaches anew
+    let pagehide = {
+        let port = port.clone();
+        window_event_listener(ev::pagehide, move |_| RelayMessage::Detach.post_port(&port))
+    };
+    let pageshow = {
+        let (client, port) = (client.clone(), port.clone());
+        window_event_listener(ev::pageshow, move |event| {
+            if event.persisted() {
+                attach_port(&client, &port);
+            }
+        })
+    };
+
+    while !handle.is_stopped() {
+        futures::select! {
+            cmd = commands.next() => {
+                let Some(cmd) = cmd else { continue };
+                handle.in_flight.fetch_sub(1, Ordering::SeqCst);
+                client.track_subscription(&cmd);
+                RelayMessage::Command { cmd }.post_port(&port);
+            }
+            _ = TimeoutFuture::new(HEARTBEAT_INTERVAL_MS).fuse() => {}
+        }
+    }
+
+    pagehide.remove();
+    pageshow.remove();
+    RelayMessage::Detach.post_port(&port);
+    port.set_onmessage(None);
+    port.close();
+    client.state.set_disconnected();
+}
+
+/// Have the worker fan out to `port` and carry this tab's streams
+fn attach_port(client: &WsClient, port: &MessagePort) {
+    RelayMessage::Open {
+        url: client.config.url.clone(),
+    }
+    .post_port(port);
+
+    // Current desired streams; the worker replays them on its own reconnects
+    for cmd in client.state.subscriptions.get_untracked().replay_commands() {
+        RelayMessage::Command { cmd }.post_port(port);
+    }
+}
+
+/// BroadcastChannel mode: elect a leader tab that owns the socket
+async fn run_relay(
+    client: WsClient,
+    handle: WsHandle,
+    mut commands: UnboundedReceiver<ClientCommand>,
+) {
+    let channel = match BroadcastChannel::new(RELAY_CHANNEL) {
+        Ok(channel) => channel,
+        Err(e) => {
+            tracing::warn!("BroadcastChannel unavailable ({:?}), using dedicated socket", e);
+            client.run_connection_loop(handle, commands).await;
+            return;
+        }
+    };
+
+    let id = tab_id();
+    let election = Rc::new(RefCell::new(LeaderElection::new(id, js_sys::Date::now())));
+    let upstream: Rc<RefCell<Option<WsHandle>>> = Rc::new(RefCell::new(None));
+    // Streams every tab asked for; whichever tab leads next replays them all
+    let streams = Rc::new(RefCell::new(client.state.subscriptions.get_untracked()));
+
+    let onmessage = {
+        let client = client.clone();
+        let election = election.clone();
+        let upstream = upstream.clone();
+        let streams = streams.clone();
+        let channel = channel.clone();
+        let leader_seen = Cell::new(None);
+        Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+            let Some(msg) = RelayMessage::from_event(&event) else {
+                return;
+            };
+            let leading = upstream.borrow().is_some();
+
+            match msg {
+                RelayMessage::Heartbeat { leader, connected } => {
+                    election.borrow_mut().observe_heartbeat(leader, js_sys::Date::now());
+                    if !leading {
+                        client.set_relayed_state(connected);
+                    }
+                    // A new leader may have joined after this tab subscribed
+                    if !leading && leader_seen.replace(Some(leader)) != Some(leader) {
+                        announce_streams(&client, &channel);
+                    }
+                }
+                RelayMessage::Frame { text } if !leading => client.process_message(&text),
+                RelayMessage::Command { cmd } => {
+                    streams.borrow_mut().apply(&cmd);
+                    if let Some(up) = upstream.borrow().as_ref() {
+                        up.send(cmd);
+                    }
+                }
+                _ => {}
+            }
+        })
+    };
+    channel.set_onmessage(Some(onmessage.as_ref().unchecked_ref()));
+
+    client.state.set_connecting();
+
+    while !handle.is_stopped() {
+        let now = js_sys::Date::now();
+        let role = election.borrow_mut().tick(now);
+        let leading = upstream.borrow().is_some();
+
+        match role {
+            Role::Leader if !leading => {
+                tracing::info!("Tab {} elected WebSocket leader", id);
+                let (up, up_commands) = WsHandle::new();
+                // Followers' streams too, not only this tab's own
+                let own = client.state.subscriptions.get_untracked();
+                for cmd in replay_streams(&streams.borrow(), &own) {
+                    up.send(cmd);
+                }
+                let leader = client.clone().with_relay(channel.clone());
+                spawn_local(leader.run_connection_loop(up.clone(), up_commands));
+                *upstream.borrow_mut() = Some(up);
+            }
+            Role::Follower if leading => {
+                tracing::info!("Tab {} stepping down as WebSocket leader", id);
+                if let Some(up) = upstream.borrow_mut().take() {
+                    up.stop();
+                }
+            }
+            _ => {}
+        }
+
+        // Forward this tab's commands once someone owns the socket
+        if upstream.borrow().is_some() || election.borrow().has_leader(now) {
+            while let Ok(Some(cmd)) = commands.try_next() {
+                handle.in_flight.fetch_sub(1, Ordering::SeqCst);
+                streams.borrow_mut().apply(&cmd);
+                match upstream.borrow().as_ref() {
+                    Some(up) => {
+                        up.send(cmd);
+                    }
+                    None => {
+                        client.track_subscription(&cmd);
+                        RelayMessage::Command { cmd }.post(&channel);
+                    }
+                }
+            }
+        }
+
+        if upstream.borrow().is_some() {
+            RelayMessage::Heartbeat {
+                leader: id,
+                connected: client.state.connection.get_untracked().is_connected(),
+            }
+            .post(&channel);
+        }
+
+        TimeoutFuture::new(HEARTBEAT_INTERVAL_MS).await;
+    }
+
+    if let Some(up) = upstream.borrow_mut().take() {
+        up.stop();
+    }
+    channel.set_onmessage(None);
+    channel.close();
+    client.state.set_disconnected();
+}
+
+/// Post this tab's streams to whoever leads
+fn announce_streams(client: &WsClient, channel: &BroadcastChannel) {
+    for cmd in client.state.subscriptions.get_untracked().replay_commands() {
+        RelayMessage::Command { cmd }.post(channel);
+    }
+}
+
+/// Subscriptions a new leader sends upstream: it replays `own` on every
+/// connect, so only streams other tabs asked for
+fn replay_streams(streams: &SubscriptionSet, own: &SubscriptionSet) -> Vec<ClientCommand> {
+    streams
+        .iter()
+        .filter(|sub| own.get(&sub.symbol) != Some(*sub))
+        .cloned()
+        .map(ClientCommand::Subscribe)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_election_claims_after_grace() {
+        let mut election = LeaderElection::new(5, 0.0);
+        assert_eq!(election.tick(500.0), Role::Follower);
+        assert_eq!(election.tick(ELECTION_GRACE_MS), Role::Leader);
+    }
+
+    #[test]
+    fn test_election_defers_to_live_leader() {
+        let mut election = LeaderElection::new(5, 0.0);
+        election.observe_heartbeat(9, 1000.0);
+        assert_eq!(election.tick(2000.0), Role::Follower);
+
+        // Leader went silent
+        assert_eq!(election.tick(1000.0 + LEADER_TIMEOUT_MS), Role::Leader);
+    }
+
+    #[test]
+    fn test_election_lower_id_wins() {
+        let mut election = LeaderElection::new(5, 0.0);
+        assert_eq!(election.tick(ELECTION_GRACE_MS), Role::Leader);
+
+        election.observe_heartbeat(7, 2000.0);
+        assert_eq!(election.tick(2000.0), Role::Leader);
+
+        election.observe_heartbeat(3, 2100.0);
+        assert_eq!(election.tick(2100.0), Role::Follower);
+    }
+
+    #[test]
+    fn test_new_leader_replays_follower_streams() {
+        let mut streams = SubscriptionSet::new();
+        for cmd in [
+            ClientCommand::subscribe("BTC-USD"),
+            ClientCommand::subscribe("ETH-USD"),
+            ClientCommand::subscribe("SOL-USD"),
+            ClientCommand::unsubscribe("SOL-USD"),
+        ] {
+            streams.apply(&cmd);
+        }
+        let mut own = SubscriptionSet::new();
+        own.apply(&ClientCommand::subscribe("BTC-USD"));
+
+        assert_eq!(replay_streams(&streams, &own), vec![ClientCommand::subscribe("ETH-USD")]);
+    }
+
+    #[test]
+    fn test_relay_message_format() {
+        let msg = RelayMessage::Heartbeat { leader: 1, connected: true };
+        let json = serde_json::to_string(&msg).unwrap();
+        assert_eq!(json, r#"{"kind":"heartbeat","leader":1,"connected":true}"#);
+
+        let cmd: RelayMessage =
+            serde_json::from_str(r#"{"kind":"command","cmd":{"type":"ping"}}"#).unwrap();
+        assert_eq!(cmd, RelayMessage::Command { cmd: ClientCommand::Ping });
+    }
+}
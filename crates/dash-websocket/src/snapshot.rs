@@ -0,0 +1,161 @@
+//! Initial state from the server's REST snapshot endpoints
+//!
+//! Fetched on load (and on symbol change) so panels render immediately,
+//! before the first WebSocket messages arrive, and again after an outage to
+//! fill in what the stream missed.
+
+use dash_core::{Candle, CandleInterval, OrderBookSnapshot, Symbol, Ticker, Trade};
+use dash_state::{MarketState, MAX_CANDLES};
+use gloo_net::http::Request;
+use leptos::prelude::*;
+use serde::de::DeserializeOwned;
+
+/// Trades requested from `/api/trades`
+pub const SNAPSHOT_TRADES: usize = 50;
+
+/// HTTP origin serving the REST API for a WebSocket URL
+/// (`ws://host:3001/ws` -> `http://host:3001`)
+pub fn rest_base_url(ws_url: &str) -> Option<String> {
+    let (scheme, rest) = ws_url.split_once("://")?;
+    let scheme = match scheme {
+        "ws" => "http",
+        "wss" => "https",
+        _ => return None,
+    };
+    let host = rest.split(['/', '?']).next().filter(|h| !h.is_empty())?;
+    Some(format!("{}://{}", scheme, host))
+}
+
+pub(crate) async fn fetch_json<T: DeserializeOwned>(url: &str) -> Option<T> {
+    let response = match Request::get(url).send().await {
+        Ok(response) => response,
+        Err(e) => {
+            tracing::debug!("Snapshot request failed ({}): {}", url, e);
+            return None;
+        }
+    };
+    if !response.ok() {
+        tracing::debug!("Snapshot unavailable ({}): HTTP {}", url, response.status());
+        return None;
+    }
+    match response.json::<T>().await {
+        Ok(value) => Some(value),
+        Err(e) => {
+            tracing::warn!("Invalid snapshot from {}: {}", url, e);
+            None
+        }
+    }
+}
+
+/// Fill empty panels from REST snapshots.
+/// Data that already arrived over the WebSocket is never overwritten.
+pub async fn load_snapshot(base_url: String, market: MarketState, symbol: Symbol) {
+    let query = format!("symbol={}", symbol.as_str());
+    let book_url = format!("{}/api/orderbook?{}", base_url, query);
+    let ticker_url = format!("{}/api/ticker?{}", base_url, query);
+    let trades_url = format!("{}/api/trades?{}&limit={}", base_url, query, SNAPSHOT_TRADES);
+
+    let (book, ticker, trades) = futures::join!(
+        fetch_json::<OrderBookSnapshot>(&book_url),
+        fetch_json::<Ticker>(&ticker_url),
+        fetch_json::<Vec<Trade>>(&trades_url),
+    );
+
+    // The user may have switched symbols while the requests were in flight
+    if market.symbol.get_untracked() != symbol {
+        return;
+    }
+
+    if let Some(book) = book.filter(|_| market.orderbook.with_untracked(Option::is_none)) {
+        market.update_orderbook(book);
+    }
+    if let Some(ticker) = ticker.filter(|_| market.ticker.with_untracked(Option::is_none)) {
+        market.update_ticker(ticker);
+    }
+    if let Some(mut trades) = trades.filter(|_| market.trades.with_untracked(|t| t.is_empty())) {
+        // Endpoint returns newest first; the buffer is oldest first
+        trades.reverse();
+        market.add_trades(trades);
+    }
+}
+
+/// Catch up after a stream outage: take the book, ticker and trades that
+/// are newer than what the panels froze on
+pub async fn resync_snapshot(base_url: String, market: MarketState, symbol: Symbol) {
+    let query = format!("symbol={}", symbol.as_str());
+    let book_url = format!("{}/api/orderbook?{}", base_url, query);
+    let ticker_url = format!("{}/api/ticker?{}", base_url, query);
+    let trades_url = format!("{}/api/trades?{}&limit={}", base_url, query, SNAPSHOT_TRADES);
+
+    let (book, ticker, trades) = futures::join!(
+        fetch_json::<OrderBookSnapshot>(&book_url),
+        fetch_json::<Ticker>(&ticker_url),
+        fetch_json::<Vec<Trade>>(&trades_url),
+    );
+
+    if market.symbol.get_untracked() != symbol {
+        return;
+    }
+
+    let updated = market.last_update.clone();
+    if let Some(book) = book.filter(|b| b.timestamp > updated.orderbook.get_untracked()) {
+        market.update_orderbook(book);
+    }
+    if let Some(ticker) = ticker.filter(|t| t.timestamp > updated.ticker.get_untracked()) {
+        market.update_ticker(ticker);
+    }
+    if let Some(trades) = trades {
+        let since = updated.trade.get_untracked();
+        let mut missed: Vec<Trade> = trades
+            .into_iter()
+            .filter(|t| t.timestamp.timestamp_millis() > since)
+            .collect();
+        missed.reverse();
+        if !missed.is_empty() {
+            tracing::debug!(count = missed.len(), "trades missed while offline");
+            market.add_trades(missed);
+        }
+    }
+}
+
+/// Fetch the latest candles for `symbol` and `interval` from the history
+/// endpoint and merge them over whatever the chart shows (cached or live)
+pub async fn load_candle_history(
+    base_url: String,
+    market: MarketState,
+    symbol: Symbol,
+    interval: CandleInterval,
+) {
+    let from = js_sys::Date::now() as i64 - interval.as_millis() * MAX_CANDLES as i64;
+    let url = format!(
+        "{}/api/history/candles?symbol={}&interval={}&from={}&limit={}",
+        base_url,
+        symbol.as_str(),
+        interval.code(),
+        from,
+        MAX_CANDLES,
+    );
+    let Some(candles) = fetch_json::<Vec<Candle>>(&url).await else {
+        return;
+    };
+
+    if market.symbol.get_untracked() != symbol || market.interval.get_untracked() != interval {
+        return;
+    }
+    if !candles.is_empty() {
+        market.reconcile_candles(candles);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rest_base_url() {
+        assert_eq!(rest_base_url("ws://127.0.0.1:3001/ws").as_deref(), Some("http://127.0.0.1:3001"));
+        assert_eq!(rest_base_url("wss://dash.example.com/ws?encoding=gzip").as_deref(), Some("https://dash.example.com"));
+        assert_eq!(rest_base_url("http://host/ws"), None);
+        assert_eq!(rest_base_url("ws:///ws"), None);
+    }
+}
@@ -0,0 +1,126 @@
+//! Socket, timers and clocks behind the connection loop
+//!
+//! The browser build uses `gloo-net` and the JS clocks. Native builds
+//! (integration tests, tools) run the same connection loop on tokio with
+//! tokio-tungstenite and speak plain `ws://`.
+
+pub(crate) use gloo_net::websocket::Message;
+pub(crate) use platform::*;
+
+#[cfg(target_arch = "wasm32")]
+mod platform {
+    use std::future::Future;
+
+    use gloo_timers::future::TimeoutFuture;
+
+    pub(crate) use gloo_net::websocket::futures::WebSocket as Socket;
+
+    /// Open a WebSocket to `url`
+    pub(crate) async fn open(url: &str) -> Result<Socket, String> {
+        Socket::open(url).map_err(|e| format!("{:?}", e))
+    }
+
+    pub(crate) async fn sleep(ms: u32) {
+        TimeoutFuture::new(ms).await;
+    }
+
+    pub(crate) fn spawn(task: impl Future<Output = ()> + 'static) {
+        wasm_bindgen_futures::spawn_local(task);
+    }
+
+    /// Local Unix millis
+    pub(crate) fn date_now() -> f64 {
+        js_sys::Date::now()
+    }
+
+    /// High-resolution local time (ms) for dispatch timings
+    pub(crate) fn now_ms() -> f64 {
+        web_sys::window()
+            .and_then(|w| w.performance())
+            .map_or_else(js_sys::Date::now, |p| p.now())
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod platform {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{ready, Context, Poll};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use futures::{Sink, SinkExt, Stream, StreamExt};
+    use tokio::net::TcpStream;
+    use tokio_tungstenite::tungstenite::{self, Message as Frame};
+    use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+    use super::Message;
+
+    /// tokio-tungstenite connection carrying `gloo-net` messages
+    pub(crate) struct Socket(WebSocketStream<MaybeTlsStream<TcpStream>>);
+
+    /// Open a WebSocket to `url`
+    pub(crate) async fn open(url: &str) -> Result<Socket, String> {
+        let (ws, _) = tokio_tungstenite::connect_async(url).await.map_err(|e| e.to_string())?;
+        Ok(Socket(ws))
+    }
+
+    pub(crate) async fn sleep(ms: u32) {
+        tokio::time::sleep(Duration::from_millis(ms.into())).await;
+    }
+
+    /// Run `task` on the current `LocalSet`; clients are not `Send`
+    pub(crate) fn spawn(task: impl Future<Output = ()> + 'static) {
+        tokio::task::spawn_local(task);
+    }
+
+    /// Local Unix millis
+    pub(crate) fn date_now() -> f64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64() * 1_000.0
+    }
+
+    pub(crate) fn now_ms() -> f64 {
+        date_now()
+    }
+
+    impl Stream for Socket {
+        type Item = Result<Message, tungstenite::Error>;
+
+        fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            loop {
+                let frame = match ready!(self.0.poll_next_unpin(cx)) {
+                    None | Some(Ok(Frame::Close(_))) => None,
+                    Some(Ok(Frame::Text(text))) => Some(Ok(Message::Text(text))),
+                    Some(Ok(Frame::Binary(bytes))) => Some(Ok(Message::Bytes(bytes))),
+                    // Pings are answered by tungstenite
+                    Some(Ok(_)) => continue,
+                    Some(Err(e)) => Some(Err(e)),
+                };
+                return Poll::Ready(frame);
+            }
+        }
+    }
+
+    impl Sink<Message> for Socket {
+        type Error = tungstenite::Error;
+
+        fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            self.0.poll_ready_unpin(cx)
+        }
+
+        fn start_send(mut self: Pin<&mut Self>, msg: Message) -> Result<(), Self::Error> {
+            let frame = match msg {
+                Message::Text(text) => Frame::Text(text),
+                Message::Bytes(bytes) => Frame::Binary(bytes),
+            };
+            self.0.start_send_unpin(frame)
+        }
+
+        fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            self.0.poll_flush_unpin(cx)
+        }
+
+        fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            self.0.poll_close_unpin(cx)
+        }
+    }
+}
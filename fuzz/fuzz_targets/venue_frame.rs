@@ -0,0 +1,14 @@
+//! Venue frames through every adapter decoder
+
+#![no_main]
+
+use dash_adapters::fuzzing::VenueDecoders;
+use libfuzzer_sys::fuzz_target;
+
+// Frames are split on newlines so the stateful book decoders see sequences
+fuzz_target!(|text: &str| {
+    let mut decoders = VenueDecoders::default();
+    for frame in text.lines() {
+        decoders.decode(frame);
+    }
+});
@@ -0,0 +1,14 @@
+//! Server frames through the client's tolerant decoder
+
+#![no_main]
+
+use dash_core::{decode_frame, TolerantDecoder};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|text: &str| {
+    let strict = decode_frame(text).is_ok();
+    let mut decoder = TolerantDecoder::default();
+    let tolerant = decoder.decode(text, 0).is_ok();
+    // Fallbacks only ever rescue frames, never lose one strict decoding reads
+    assert!(tolerant || !strict);
+});
@@ -0,0 +1,14 @@
+//! Generates the gRPC service from `proto/market.proto`
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // Use the vendored protoc so builds don't need a system install
+    if std::env::var_os("PROTOC").is_none() {
+        // SAFETY: build scripts are single-threaded
+        unsafe { std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?) };
+    }
+
+    tonic_prost_build::configure()
+        .build_client(false)
+        .compile_protos(&["proto/market.proto"], &["proto"])?;
+    Ok(())
+}
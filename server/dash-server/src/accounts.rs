@@ -0,0 +1,411 @@
+//! User accounts and per-user documents
+//!
+//! `POST /api/auth/signup` and `POST /api/auth/login` answer with a
+//! `Session` whose token is an HS256 JWT signed with `$DASH_JWT_SECRET`.
+//! With `Authorization: Bearer <token>`, `GET`/`PUT /api/me/:kind` read and
+//! replace the user's settings, layout, watchlist or alert rules. Accounts
+//! live in history storage and are unavailable without it.
+//!
+//! Passwords are stored as salted PBKDF2-HMAC-SHA256 hashes.
+//!
+//! Only the signed-in users `$DASH_OPERATORS` names are operators: everyone
+//! else, signed in or not, is a read-only viewer and is refused by the
+//! control endpoints (`/api/control`). Unset, nobody is an operator. Those
+//! names cannot be signed up, so nobody can claim an operator's name before
+//! the operator does: operators open their account first, then are listed.
+
+use std::num::NonZeroU32;
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::{hmac, pbkdf2};
+use serde::{Deserialize, Serialize};
+
+use crate::storage::Storage;
+use crate::AppState;
+use dash_core::{Credentials, Role, Session, UserDocument, MAX_DOCUMENT_BYTES};
+
+/// Environment variable holding the token signing secret
+pub const JWT_SECRET_ENV: &str = "DASH_JWT_SECRET";
+
+/// Environment variable listing the operators' usernames (comma-separated)
+pub const OPERATORS_ENV: &str = "DASH_OPERATORS";
+
+/// How long a session token is accepted (seconds)
+const SESSION_TTL_SECS: i64 = 7 * 24 * 60 * 60;
+
+const PBKDF2_ITERATIONS: u32 = 100_000;
+const SALT_LEN: usize = 16;
+const HASH_LEN: usize = 32;
+
+/// `{"alg":"HS256","typ":"JWT"}`, the only header tokens are issued with
+const JWT_HEADER: &str = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9";
+
+/// What a session token asserts
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Claims {
+    /// Username
+    pub sub: String,
+    /// User id in storage
+    pub uid: i64,
+    /// Issued at (unix seconds)
+    pub iat: i64,
+    /// Expires at (unix seconds)
+    pub exp: i64,
+}
+
+/// Issues and checks session tokens
+pub struct Auth {
+    key: hmac::Key,
+}
+
+impl Auth {
+    pub fn new(secret: &[u8]) -> Self {
+        Self { key: hmac::Key::new(hmac::HMAC_SHA256, secret) }
+    }
+
+    /// Key from `DASH_JWT_SECRET`, or a random one (sessions then end when
+    /// the server restarts)
+    pub fn from_env() -> Self {
+        match std::env::var(JWT_SECRET_ENV).ok().filter(|v| !v.trim().is_empty()) {
+            Some(secret) => Self::new(secret.as_bytes()),
+            None => {
+                tracing::warn!("{} not set; sessions will not survive a restart", JWT_SECRET_ENV);
+                Self::default()
+            }
+        }
+    }
+
+    /// Sign a token for a user
+    pub fn issue(&self, uid: i64, username: &str, now: i64) -> Session {
+        let exp = now + SESSION_TTL_SECS;
+        let claims = Claims { sub: username.to_string(), uid, iat: now, exp };
+        let payload = serde_json::to_vec(&claims).expect("claims serialize");
+        let signed = format!("{}.{}", JWT_HEADER, URL_SAFE_NO_PAD.encode(payload));
+        let signature = hmac::sign(&self.key, signed.as_bytes());
+        Session {
+            username: claims.sub,
+            token: format!("{}.{}", signed, URL_SAFE_NO_PAD.encode(signature.as_ref())),
+            expires_at: claims.exp * 1000,
+        }
+    }
+
+    /// Claims of a token this server signed and that has not expired
+    pub fn verify(&self, token: &str, now: i64) -> Option<Claims> {
+        let (signed, signature) = token.rsplit_once('.')?;
+        let (head, payload) = signed.split_once('.')?;
+        if head != JWT_HEADER {
+            return None;
+        }
+        let signature = URL_SAFE_NO_PAD.decode(signature).ok()?;
+        hmac::verify(&self.key, signed.as_bytes(), &signature).ok()?;
+        let claims: Claims = serde_json::from_slice(&URL_SAFE_NO_PAD.decode(payload).ok()?).ok()?;
+        (now < claims.exp).then_some(claims)
+    }
+}
+
+impl Default for Auth {
+    /// Random per-process key
+    fn default() -> Self {
+        let mut secret = [0u8; 32];
+        SystemRandom::new().fill(&mut secret).expect("system randomness");
+        Self::new(&secret)
+    }
+}
+
+/// Who may use the operator features
+#[derive(Debug, Clone, Default)]
+pub struct Roles {
+    /// Operators' usernames, lowercased; empty when nobody is one
+    operators: Vec<String>,
+}
+
+impl Roles {
+    pub fn new(operators: Vec<String>) -> Self {
+        let operators = operators
+            .iter()
+            .map(|n| n.trim().to_lowercase())
+            .filter(|n| !n.is_empty())
+            .collect();
+        Self { operators }
+    }
+
+    /// Operators from `DASH_OPERATORS`; nobody when unset
+    pub fn from_env() -> Self {
+        let names = std::env::var(OPERATORS_ENV).unwrap_or_default();
+        Self::new(names.split(',').map(str::to_string).collect())
+    }
+
+    /// Whether `username` is one of the operators' names
+    pub fn is_operator_name(&self, username: &str) -> bool {
+        self.operators.contains(&username.trim().to_lowercase())
+    }
+
+    /// Role of a signed-in user, or of a visitor without a session
+    pub fn role_of(&self, claims: Option<&Claims>) -> Role {
+        match claims {
+            Some(claims) if self.is_operator_name(&claims.sub) => Role::Operator,
+            _ => Role::Viewer,
+        }
+    }
+}
+
+// ============================================================================
+// PASSWORDS
+// ============================================================================
+
+fn iterations(n: u32) -> NonZeroU32 {
+    NonZeroU32::new(n).unwrap_or(NonZeroU32::MIN)
+}
+
+/// `pbkdf2-sha256$<iterations>$<salt>$<hash>`, base64 parts
+pub fn hash_password(password: &str) -> String {
+    let mut salt = [0u8; SALT_LEN];
+    SystemRandom::new().fill(&mut salt).expect("system randomness");
+    let mut hash = [0u8; HASH_LEN];
+    pbkdf2::derive(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations(PBKDF2_ITERATIONS),
+        &salt,
+        password.as_bytes(),
+        &mut hash,
+    );
+    format!(
+        "pbkdf2-sha256${}${}${}",
+        PBKDF2_ITERATIONS,
+        URL_SAFE_NO_PAD.encode(salt),
+        URL_SAFE_NO_PAD.encode(hash)
+    )
+}
+
+/// Whether `password` matches a stored hash
+pub fn verify_password(password: &str, stored: &str) -> bool {
+    let mut parts = stored.split('$');
+    let (Some("pbkdf2-sha256"), Some(iters), Some(salt), Some(hash), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return false;
+    };
+    let (Ok(iters), Ok(salt), Ok(hash)) =
+        (iters.parse(), URL_SAFE_NO_PAD.decode(salt), URL_SAFE_NO_PAD.decode(hash))
+    else {
+        return false;
+    };
+    pbkdf2::verify(
+        pbkdf2::PBKDF2_HMAC_SHA256,
+        iterations(iters),
+        &salt,
+        password.as_bytes(),
+        &hash,
+    )
+    .is_ok()
+}
+
+// ============================================================================
+// HANDLERS
+// ============================================================================
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+/// Claims of the request's bearer token
+pub(crate) fn session(state: &AppState, headers: &HeaderMap) -> Result<Claims, StatusCode> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .and_then(|token| verify_session(state, token))
+        .ok_or(StatusCode::UNAUTHORIZED)
+}
+
+/// Claims of a session token that is still valid
+pub(crate) fn verify_session(state: &AppState, token: &str) -> Option<Claims> {
+    state.auth.verify(token, now())
+}
+
+/// Middleware refusing viewers: 401 without a session, whatever the
+/// operator list, and 403 with one that is not an operator's
+pub async fn require_operator(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Ok(claims) = session(&state, request.headers()) else {
+        tracing::debug!("Refused {} without a session", request.uri().path());
+        return StatusCode::UNAUTHORIZED.into_response();
+    };
+    if state.roles.role_of(Some(&claims)).is_operator() {
+        return next.run(request).await;
+    }
+    tracing::debug!("Refused {} to viewer {}", request.uri().path(), claims.sub);
+    StatusCode::FORBIDDEN.into_response()
+}
+
+/// `GET /api/auth/role` - the role of the bearer, or of visitors without a
+/// session
+pub async fn role_handler(State(state): State<Arc<AppState>>, headers: HeaderMap) -> Json<Role> {
+    let claims = session(&state, &headers).ok();
+    Json(state.roles.role_of(claims.as_ref()))
+}
+
+/// Run blocking account work (hashing, queries) off the async runtime
+async fn with_storage<T, F>(state: &AppState, work: F) -> Result<T, StatusCode>
+where
+    T: Send + 'static,
+    F: FnOnce(&Storage) -> rusqlite::Result<T> + Send + 'static,
+{
+    let storage = state.storage.clone().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    match tokio::task::spawn_blocking(move || work(&storage)).await {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(e)) => {
+            tracing::error!("Account query failed: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+        Err(e) => {
+            tracing::error!("Account query panicked: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// `POST /api/auth/signup` - open an account; 409 when the name is taken,
+/// 422 when the name or password is not accepted, 403 for an operator's name
+pub async fn signup_handler(
+    State(state): State<Arc<AppState>>,
+    Json(creds): Json<Credentials>,
+) -> Result<(StatusCode, Json<Session>), StatusCode> {
+    if creds.signup_error().is_some() {
+        return Err(StatusCode::UNPROCESSABLE_ENTITY);
+    }
+    if state.roles.is_operator_name(&creds.username) {
+        tracing::warn!("Refused signup for operator name {}", creds.username.trim());
+        return Err(StatusCode::FORBIDDEN);
+    }
+    let username = creds.username.trim().to_string();
+    let name = username.clone();
+    let uid = with_storage(&state, move |storage| {
+        storage.create_user(&name, &hash_password(&creds.password), now())
+    })
+    .await?
+    .ok_or(StatusCode::CONFLICT)?;
+    tracing::info!("Account created: {}", username);
+    Ok((StatusCode::CREATED, Json(state.auth.issue(uid, &username, now()))))
+}
+
+/// `POST /api/auth/login` - sign in; 401 for an unknown name or wrong password
+pub async fn login_handler(
+    State(state): State<Arc<AppState>>,
+    Json(creds): Json<Credentials>,
+) -> Result<Json<Session>, StatusCode> {
+    let username = creds.username.trim().to_string();
+    let user = with_storage(&state, move |storage| {
+        let user = storage.user_credentials(&username)?;
+        Ok(user.filter(|(_, _, hash)| verify_password(&creds.password, hash)))
+    })
+    .await?;
+    let (uid, name, _) = user.ok_or(StatusCode::UNAUTHORIZED)?;
+    Ok(Json(state.auth.issue(uid, &name, now())))
+}
+
+/// `GET /api/me/:kind` - a stored document; 404 when there is none yet
+pub async fn document_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(kind): Path<String>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let claims = session(&state, &headers)?;
+    let kind = UserDocument::parse(&kind).ok_or(StatusCode::NOT_FOUND)?;
+    let body = with_storage(&state, move |storage| storage.document(claims.uid, kind.as_str()))
+        .await?
+        .ok_or(StatusCode::NOT_FOUND)?;
+    serde_json::from_str(&body).map(Json).map_err(|e| {
+        tracing::error!("Stored {} document is not JSON: {}", kind.as_str(), e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
+/// `PUT /api/me/:kind` - replace a document (JSON, at most 256 KiB)
+pub async fn put_document_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(kind): Path<String>,
+    Json(document): Json<serde_json::Value>,
+) -> StatusCode {
+    let claims = match session(&state, &headers) {
+        Ok(claims) => claims,
+        Err(status) => return status,
+    };
+    let Some(kind) = UserDocument::parse(&kind) else {
+        return StatusCode::NOT_FOUND;
+    };
+    let body = document.to_string();
+    if body.len() > MAX_DOCUMENT_BYTES {
+        return StatusCode::PAYLOAD_TOO_LARGE;
+    }
+    let stored = with_storage(&state, move |storage| {
+        storage.put_document(claims.uid, kind.as_str(), &body, now())
+    })
+    .await;
+    match stored {
+        Ok(()) => StatusCode::NO_CONTENT,
+        Err(status) => status,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_tokens() {
+        let auth = Auth::new(b"secret");
+        let session = auth.issue(7, "trader", 1_000);
+        assert_eq!(session.expires_at, (1_000 + SESSION_TTL_SECS) * 1000);
+
+        let claims = auth.verify(&session.token, 2_000).unwrap();
+        assert_eq!((claims.uid, claims.sub.as_str()), (7, "trader"));
+        assert!(auth.verify(&session.token, 1_000 + SESSION_TTL_SECS).is_none());
+
+        // Signed with another key, or with the payload swapped
+        assert!(Auth::new(b"other").verify(&session.token, 2_000).is_none());
+        let forged = auth.issue(8, "admin", 1_000);
+        let mut parts: Vec<&str> = session.token.split('.').collect();
+        parts[1] = forged.token.split('.').nth(1).unwrap();
+        assert!(auth.verify(&parts.join("."), 2_000).is_none());
+    }
+
+    #[test]
+    fn test_roles() {
+        let claims = |name: &str| Claims { sub: name.into(), uid: 1, iat: 0, exp: 1 };
+        assert_eq!(Roles::default().role_of(None), Role::Viewer);
+        assert_eq!(Roles::default().role_of(Some(&claims("alice"))), Role::Viewer);
+
+        let roles = Roles::new(vec![" Alice ".into(), String::new()]);
+        assert_eq!(roles.role_of(Some(&claims("alice"))), Role::Operator);
+        assert_eq!(roles.role_of(Some(&claims("bob"))), Role::Viewer);
+        assert_eq!(roles.role_of(None), Role::Viewer);
+        assert!(roles.is_operator_name("ALICE "));
+        assert!(!roles.is_operator_name("bob"));
+    }
+
+    #[test]
+    fn test_password_hashes() {
+        let stored = hash_password("correct horse");
+        assert!(stored.starts_with("pbkdf2-sha256$100000$"));
+        assert!(verify_password("correct horse", &stored));
+        assert!(!verify_password("wrong horse", &stored));
+        assert!(!verify_password("correct horse", "plaintext"));
+        // Salted: the same password hashes differently
+        assert_ne!(hash_password("correct horse"), stored);
+    }
+}
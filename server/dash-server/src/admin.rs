@@ -0,0 +1,215 @@
+//! Operator endpoints behind the dashboard's admin page
+//!
+//! One status document with the connected clients, per-channel throughput
+//! and adapter health, plus switching the upstream source, triggering mock
+//! scenarios and setting chaos mode. Recording is paused and resumed in
+//! `storage`.
+
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::{extract::State, http::StatusCode, Json};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::AppState;
+use dash_core::{
+    AdminStatus, ChannelThroughput, ChaosConfig, ClientConnections, FeedChannel,
+    ScenarioRequest, SourceRequest, WsMessage, MAX_SCENARIO_SECS, SCENARIO_SECS,
+};
+
+/// Rates are averaged over this long
+const THROUGHPUT_WINDOW: Duration = Duration::from_secs(5);
+
+// ============================================================================
+// THROUGHPUT
+// ============================================================================
+
+/// Messages broadcast to clients per channel
+#[derive(Debug)]
+pub struct Throughput {
+    window_start: Instant,
+    window: HashMap<FeedChannel, u64>,
+    totals: HashMap<FeedChannel, u64>,
+    /// Per second over the last complete window
+    rates: HashMap<FeedChannel, f64>,
+}
+
+impl Throughput {
+    pub fn new(now: Instant) -> Self {
+        Self {
+            window_start: now,
+            window: HashMap::new(),
+            totals: HashMap::new(),
+            rates: HashMap::new(),
+        }
+    }
+
+    pub fn record(&mut self, msg: &WsMessage, now: Instant) {
+        self.roll(now);
+        if let Some(channel) = FeedChannel::for_message(msg) {
+            *self.window.entry(channel).or_default() += 1;
+            *self.totals.entry(channel).or_default() += 1;
+        }
+    }
+
+    /// Close the window once it is long enough; a quiet feed closes it late
+    /// and so averages down to nothing
+    fn roll(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.window_start);
+        if elapsed < THROUGHPUT_WINDOW {
+            return;
+        }
+        let secs = elapsed.as_secs_f64();
+        self.rates = self.window.drain().map(|(channel, n)| (channel, n as f64 / secs)).collect();
+        self.window_start = now;
+    }
+
+    pub fn report(&mut self, now: Instant) -> Vec<ChannelThroughput> {
+        self.roll(now);
+        FeedChannel::all()
+            .iter()
+            .map(|channel| ChannelThroughput {
+                channel: *channel,
+                per_sec: self.rates.get(channel).copied().unwrap_or_default(),
+                total: self.totals.get(channel).copied().unwrap_or_default(),
+            })
+            .collect()
+    }
+}
+
+impl Default for Throughput {
+    fn default() -> Self {
+        Self::new(Instant::now())
+    }
+}
+
+fn write_throughput(state: &AppState) -> std::sync::RwLockWriteGuard<'_, Throughput> {
+    state.throughput.write().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Count every broadcast message against its channel
+pub async fn run_throughput_meter(state: Arc<AppState>) {
+    let mut rx = state.tx.subscribe();
+
+    loop {
+        match rx.recv().await {
+            Ok(msg) => write_throughput(&state).record(&msg, Instant::now()),
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::warn!("Throughput meter lagged, skipped {} messages", skipped);
+            }
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
+
+// ============================================================================
+// HANDLERS
+// ============================================================================
+
+fn status(state: &AppState) -> AdminStatus {
+    let now = Instant::now();
+    let health = state
+        .health
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .report(now);
+    let clients = state.limits.connections.clients().into_iter();
+    AdminStatus {
+        clients: clients
+            .map(|(ip, connections)| ClientConnections { ip: ip.to_string(), connections })
+            .collect(),
+        throughput: write_throughput(state).report(now),
+        active_source: health.adapters.first().map(|a| a.name.clone()).unwrap_or_default(),
+        health,
+        sources: state.sources.clone(),
+        scenario: state.mock.as_ref().and_then(|mock| mock.scenario()),
+        scenarios: state.mock.is_some(),
+        recording: state.storage.is_some().then(|| state.recording.load(Ordering::Relaxed)),
+        chaos: *state.chaos.borrow(),
+    }
+}
+
+/// `GET /api/control/status` - clients, throughput, health and controls
+pub async fn status_handler(State(state): State<Arc<AppState>>) -> Json<AdminStatus> {
+    Json(status(&state))
+}
+
+/// `PUT /api/control/source` - feed clients from another source in the
+/// chain; 404 for a source that is not in it
+pub async fn put_source_handler(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<SourceRequest>,
+) -> StatusCode {
+    if !state.sources.contains(&body.source) {
+        return StatusCode::NOT_FOUND;
+    }
+    tracing::info!("Upstream switch to {} requested", body.source);
+    state.source_switch.send_replace(Some(body.source));
+    StatusCode::ACCEPTED
+}
+
+/// `PUT /api/control/scenario` - start or end a mock market scenario;
+/// 503 without the mock engine
+pub async fn put_scenario_handler(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<ScenarioRequest>,
+) -> StatusCode {
+    let Some(mock) = state.mock.as_ref() else { return StatusCode::SERVICE_UNAVAILABLE };
+    let secs = body.secs.unwrap_or(SCENARIO_SECS).clamp(1, MAX_SCENARIO_SECS);
+    match body.scenario {
+        Some(scenario) => tracing::info!("Mock scenario {} for {}s", scenario.as_str(), secs),
+        None => tracing::info!("Mock scenario ended"),
+    }
+    mock.set_scenario(body.scenario, Duration::from_secs(secs));
+    StatusCode::NO_CONTENT
+}
+
+/// `GET /api/control/chaos` - faults injected into client connections
+pub async fn chaos_handler(State(state): State<Arc<AppState>>) -> Json<ChaosConfig> {
+    Json(*state.chaos.borrow())
+}
+
+/// `PUT /api/control/chaos` - replace the chaos settings (all defaults turn
+/// it off); answers with them as applied, clamped to their limits
+pub async fn put_chaos_handler(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<ChaosConfig>,
+) -> Json<ChaosConfig> {
+    let config = body.clamped();
+    if config.is_active() {
+        tracing::warn!("Chaos mode on: {:?}", config);
+    } else {
+        tracing::info!("Chaos mode off");
+    }
+    state.chaos.send_replace(config);
+    Json(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dash_core::{Symbol, Ticker};
+
+    #[test]
+    fn test_throughput_rates() {
+        let start = Instant::now();
+        let mut meter = Throughput::new(start);
+        let ticker = WsMessage::Ticker(Ticker::new(Symbol::new("BTC-USD"), 50_000.0));
+        for _ in 0..10 {
+            meter.record(&ticker, start);
+        }
+        meter.record(&WsMessage::Heartbeat { timestamp: 0 }, start);
+
+        // Nothing until the first window closes
+        let rate = |report: &[ChannelThroughput]| {
+            report.iter().find(|c| c.channel == FeedChannel::Ticker).map(|c| (c.per_sec, c.total))
+        };
+        assert_eq!(rate(&meter.report(start)), Some((0.0, 10)));
+        assert_eq!(rate(&meter.report(start + THROUGHPUT_WINDOW)), Some((2.0, 10)));
+
+        // A quiet window brings the rate back down
+        assert_eq!(rate(&meter.report(start + THROUGHPUT_WINDOW * 3)), Some((0.0, 10)));
+    }
+}
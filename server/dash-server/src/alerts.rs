@@ -0,0 +1,719 @@
+//! Server-side alert evaluation
+//!
+//! Rules reach the server two ways, both behind
+//! `Authorization: Bearer $DASH_ALERTS_TOKEN` (disabled without one):
+//!
+//! - the dashboard pushes its own rules and a webhook with `PUT /api/alerts`;
+//!   it checks them itself while open, so their firings only go to the webhook
+//! - `POST /api/alerts/rules` registers a rule that lives on the server
+//!   (`GET` lists them, `DELETE /api/alerts/rules/:id` removes one) and
+//!   fires to the webhook. A signed-in user's session token works there too
+//!   and owns the rules it registers, up to `MAX_RULES_PER_USER`: their
+//!   firings go only to the WebSocket connections signed in as that user
+//!   (`WsMessage::AlertTriggered`, after an `auth` command)
+//!
+//! Both are checked against the broadcast stream the same way the client
+//! checks its rules, so alerts work without any dashboard open. Rules are
+//! kept in memory.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    Json,
+};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use ring::hmac;
+use ring::rand::SystemRandom;
+use serde::Deserialize;
+use serde_json::json;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::accounts;
+use crate::AppState;
+use dash_core::{
+    AlertBook, AlertInputs, AlertNotice, AlertRule, Candle, CandleHistory, CandleInterval,
+    ExprError, Locale, ServerAlerts, Symbol, Ticker, Webhook, WebhookFormat, WsMessage,
+};
+
+/// Candles kept per symbol for `rsi(14)`-style conditions; the chart's
+/// history length, so both sides see the same window
+pub const ALERT_CANDLES: usize = 200;
+
+/// Registered rules one user may have at a time; every rule is checked on
+/// each market update
+pub const MAX_RULES_PER_USER: usize = 20;
+
+/// How long a webhook POST may take
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Bearer token for `/api/alerts`; server-side alerts are off without one
+pub fn alerts_token_from_env() -> Option<String> {
+    std::env::var("DASH_ALERTS_TOKEN").ok().filter(|v| !v.trim().is_empty())
+}
+
+/// Rules the server checks and where to deliver them
+#[derive(Debug, Default)]
+pub struct AlertDesk {
+    /// Rules pushed by the dashboard, replaced as a whole
+    pushed: AlertBook,
+    /// Rules registered one at a time; ids are their own
+    registered: AlertBook,
+    /// Users who registered rules, by rule id; token-registered rules have none
+    owners: HashMap<u64, String>,
+    webhook: Option<Webhook>,
+    token: Option<TokenCheck>,
+}
+
+/// The alerts token as an HMAC tag under a random key, so checking a guess
+/// takes the same time however much of it is right
+#[derive(Debug)]
+struct TokenCheck {
+    key: hmac::Key,
+    tag: hmac::Tag,
+}
+
+impl TokenCheck {
+    fn new(token: &str) -> Self {
+        let key = hmac::Key::generate(hmac::HMAC_SHA256, &SystemRandom::new())
+            .expect("system randomness");
+        let tag = hmac::sign(&key, token.as_bytes());
+        Self { key, tag }
+    }
+
+    fn matches(&self, given: &str) -> bool {
+        hmac::verify(&self.key, given.as_bytes(), self.tag.as_ref()).is_ok()
+    }
+}
+
+impl AlertDesk {
+    pub fn new(token: Option<String>) -> Self {
+        Self { token: token.as_deref().map(TokenCheck::new), ..Self::default() }
+    }
+
+    /// Replace the pushed rules and webhook; false when the webhook URL is
+    /// not a usable https URL
+    pub fn replace(&mut self, alerts: ServerAlerts) -> bool {
+        if alerts.webhook.as_ref().is_some_and(|hook| !hook.is_valid()) {
+            return false;
+        }
+        self.pushed.restore(alerts.rules);
+        self.webhook = alerts.webhook;
+        true
+    }
+
+    /// The pushed rules and webhook
+    pub fn snapshot(&self) -> ServerAlerts {
+        ServerAlerts { webhook: self.webhook.clone(), rules: self.pushed.rules().to_vec() }
+    }
+
+    /// Register a rule for `owner`; returns it as compiled
+    pub fn register(
+        &mut self,
+        symbol: Symbol,
+        source: &str,
+        owner: Option<&str>,
+    ) -> Result<AlertRule, RegisterError> {
+        if let Some(owner) = owner
+            && self.owners.values().filter(|o| *o == owner).count() >= MAX_RULES_PER_USER
+        {
+            return Err(RegisterError::TooMany);
+        }
+        let id = self.registered.add(symbol, source).map_err(RegisterError::Invalid)?;
+        if let Some(owner) = owner {
+            self.owners.insert(id, owner.to_string());
+        }
+        Ok(self.registered.rules().iter().find(|rule| rule.id() == id).cloned().expect("added"))
+    }
+
+    /// Remove a registered rule `caller` may see; false when there is none
+    /// with `id`
+    pub fn unregister(&mut self, id: u64, caller: &Caller) -> bool {
+        if !caller.sees(self.owners.get(&id)) || !self.registered.remove(id) {
+            return false;
+        }
+        self.owners.remove(&id);
+        true
+    }
+
+    /// The registered rules `caller` may see
+    pub fn registered(&self, caller: &Caller) -> Vec<AlertRule> {
+        let rules = self.registered.rules().iter();
+        rules.filter(|rule| caller.sees(self.owners.get(&rule.id()))).cloned().collect()
+    }
+
+    /// Who registered rule `id`
+    pub fn owner(&self, id: u64) -> Option<&str> {
+        self.owners.get(&id).map(String::as_str)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pushed.is_empty() && self.registered.is_empty()
+    }
+
+    /// Whether `headers` carry the alerts token
+    fn authorizes(&self, headers: &HeaderMap) -> Option<bool> {
+        let token = self.token.as_ref()?;
+        Some(bearer(headers).is_some_and(|given| token.matches(given)))
+    }
+}
+
+/// Why a rule was not registered
+#[derive(Debug)]
+pub enum RegisterError {
+    /// The condition does not compile
+    Invalid(ExprError),
+    /// The user already has `MAX_RULES_PER_USER` rules
+    TooMany,
+}
+
+/// Who is calling the registered-rule endpoints
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Caller {
+    /// Holder of the alerts token, who sees every rule
+    Token,
+    /// A signed-in user, who sees the rules they registered
+    User(String),
+}
+
+impl Caller {
+    fn sees(&self, owner: Option<&String>) -> bool {
+        match self {
+            Self::Token => true,
+            Self::User(name) => owner == Some(name),
+        }
+    }
+
+    fn owner(&self) -> Option<&str> {
+        match self {
+            Self::Token => None,
+            Self::User(name) => Some(name),
+        }
+    }
+}
+
+fn bearer(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+}
+
+/// A registered rule's firing, for the connections signed in as `user`
+#[derive(Debug, Clone)]
+pub struct UserAlert {
+    pub user: String,
+    pub notice: AlertNotice,
+}
+
+fn desk(state: &AppState) -> std::sync::RwLockWriteGuard<'_, AlertDesk> {
+    state.alerts.write().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+// ============================================================================
+// EVALUATION
+// ============================================================================
+
+/// Live values of one symbol, as the client's market state keeps them
+#[derive(Debug)]
+struct LiveMarket {
+    last_trade: Option<f64>,
+    /// Best bid and ask of the latest book
+    quote: Option<(f64, f64)>,
+    ticker: Option<Ticker>,
+    candles: CandleHistory,
+}
+
+impl LiveMarket {
+    fn new(symbol: Symbol) -> Self {
+        Self {
+            last_trade: None,
+            quote: None,
+            ticker: None,
+            candles: CandleHistory::with_capacity(symbol, CandleInterval::M1, ALERT_CANDLES),
+        }
+    }
+
+    /// Replace the forming candle or append a newer one
+    fn merge_candle(&mut self, candle: &Candle) {
+        if candle.interval != self.candles.interval {
+            return;
+        }
+        match self.candles.latest_mut() {
+            Some(last) if last.timestamp == candle.timestamp => *last = candle.clone(),
+            Some(last) if last.timestamp > candle.timestamp => {}
+            _ => self.candles.push(candle.clone()),
+        }
+    }
+
+    fn inputs(&self) -> AlertInputs<'_> {
+        let ticker = self.ticker.as_ref();
+        let quote = self
+            .quote
+            .or_else(|| ticker.map(|t| (t.bid_price.as_f64(), t.ask_price.as_f64())));
+        AlertInputs {
+            price: self.last_trade.or_else(|| ticker.map(|t| t.last_price.as_f64())),
+            bid: quote.map(|(bid, _)| bid),
+            ask: quote.map(|(_, ask)| ask),
+            change_pct: ticker.map(|t| t.change_percent_24h),
+            high_24h: ticker.map(|t| t.high_24h.as_f64()),
+            low_24h: ticker.map(|t| t.low_24h.as_f64()),
+            volume_24h: ticker.map(|t| t.volume_24h.as_f64()),
+            candles: &self.candles.candles[..],
+        }
+    }
+}
+
+/// Live values per symbol, fed from the broadcast stream
+#[derive(Debug, Default)]
+struct LiveMarkets {
+    symbols: HashMap<Symbol, LiveMarket>,
+}
+
+impl LiveMarkets {
+    /// Record a market data message; returns the symbol's values when it
+    /// moved them
+    fn apply(&mut self, msg: &WsMessage) -> Option<&LiveMarket> {
+        let symbol = match msg {
+            WsMessage::Trade(trade) => &trade.symbol,
+            WsMessage::OrderBook(book) => &book.symbol,
+            WsMessage::Ticker(ticker) => &ticker.symbol,
+            WsMessage::Candle(candle) => &candle.symbol,
+            _ => return None,
+        };
+        let market =
+            self.symbols.entry(symbol.clone()).or_insert_with(|| LiveMarket::new(symbol.clone()));
+        match msg {
+            WsMessage::Trade(trade) => market.last_trade = Some(trade.price.as_f64()),
+            WsMessage::OrderBook(book) => {
+                if let (Some(bid), Some(ask)) = (book.best_bid(), book.best_ask()) {
+                    market.quote = Some((bid.price.as_f64(), ask.price.as_f64()));
+                }
+            }
+            WsMessage::Ticker(ticker) => market.ticker = Some(ticker.clone()),
+            WsMessage::Candle(candle) => market.merge_candle(candle),
+            _ => {}
+        }
+        Some(market)
+    }
+}
+
+/// Body of the POST for `notice` in the webhook's format
+pub fn webhook_body(format: WebhookFormat, notice: &AlertNotice) -> serde_json::Value {
+    match format {
+        WebhookFormat::Discord => json!({ "content": format!("🔔 {}", notice.message()) }),
+        WebhookFormat::Slack => json!({ "text": format!(":bell: {}", notice.message()) }),
+        WebhookFormat::Json => json!(notice),
+    }
+}
+
+/// Whether a webhook may be sent to `ip`: public unicast addresses only, so
+/// one cannot be pointed at the server's own host or network
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            let shared = a == 100 && (64..128).contains(&b);
+            let benchmarking = a == 198 && (18..20).contains(&b);
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || ip.is_multicast()
+                || a == 0
+                || a >= 240
+                || shared
+                || benchmarking)
+        }
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public(ip.into()),
+            None => {
+                let first = ip.segments()[0];
+                let unique_local = first & 0xfe00 == 0xfc00;
+                let link_local = first & 0xffc0 == 0xfe80;
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    || unique_local
+                    || link_local)
+            }
+        },
+    }
+}
+
+/// Whether the webhook's host resolves, and only to public addresses
+async fn reaches_public_host(webhook: &Webhook) -> bool {
+    let Ok(url) = reqwest::Url::parse(&webhook.url) else { return false };
+    let Some(host) = url.host_str() else { return false };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    let port = url.port_or_known_default().unwrap_or(443);
+    match tokio::net::lookup_host((host, port)).await {
+        Ok(addrs) => {
+            let addrs: Vec<SocketAddr> = addrs.collect();
+            !addrs.is_empty() && addrs.iter().all(|addr| is_public(addr.ip()))
+        }
+        Err(_) => false,
+    }
+}
+
+/// Webhook DNS that drops non-public addresses, so a host re-pointed after
+/// it was accepted still cannot reach internal ones
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_string();
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| is_public(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("{} has no public address", host).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+async fn deliver(client: reqwest::Client, webhook: Webhook, notice: AlertNotice) {
+    let sent = client
+        .post(&webhook.url)
+        .json(&webhook_body(webhook.format, &notice))
+        .send()
+        .await
+        .and_then(|response| response.error_for_status());
+    match sent {
+        Ok(_) => tracing::info!("Alert delivered: {}", notice.message()),
+        Err(e) => tracing::warn!("Alert webhook failed for {}: {}", notice.message(), e),
+    }
+}
+
+fn deliver_or_log(client: &reqwest::Client, webhook: Option<&Webhook>, notice: AlertNotice) {
+    match webhook {
+        Some(webhook) => {
+            tokio::spawn(deliver(client.clone(), webhook.clone(), notice));
+        }
+        None => tracing::info!("Alert fired: {}", notice.message()),
+    }
+}
+
+/// Check the server's rules as market data is broadcast and POST each firing
+/// to the webhook, sending those of users' registered rules to their
+/// connections
+pub async fn run_alert_evaluator(state: Arc<AppState>) {
+    // Redirects are not followed: they could lead to an address the
+    // resolver never saw
+    let client = reqwest::Client::builder()
+        .timeout(WEBHOOK_TIMEOUT)
+        .dns_resolver(Arc::new(PublicResolver))
+        .redirect(reqwest::redirect::Policy::none())
+        .build();
+    let client = match client {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::error!("Alert webhooks disabled: {}", e);
+            return;
+        }
+    };
+    let mut rx = state.tx.subscribe();
+    let mut markets = LiveMarkets::default();
+
+    loop {
+        match rx.recv().await {
+            Ok(msg) => {
+                let Some(market) = markets.apply(&msg) else { continue };
+                let (symbol, inputs) = (&market.candles.symbol, market.inputs());
+                let (pushed, registered, webhook) = {
+                    let mut desk = desk(&state);
+                    if desk.is_empty() {
+                        continue;
+                    }
+                    let pushed = desk.pushed.check(symbol, &inputs);
+                    let registered: Vec<_> = desk
+                        .registered
+                        .check(symbol, &inputs)
+                        .into_iter()
+                        .map(|rule| {
+                            let owner = desk.owner(rule.id()).map(str::to_string);
+                            (rule, owner)
+                        })
+                        .collect();
+                    (pushed, registered, desk.webhook.clone())
+                };
+                let now = chrono::Utc::now().timestamp_millis();
+                for (rule, owner) in registered {
+                    let notice = AlertNotice::new(&rule, inputs.price, now);
+                    match owner {
+                        // No receivers when the owner has no dashboard open;
+                        // the operator's webhook is not theirs
+                        Some(user) => {
+                            let _ = state.user_alerts.send(UserAlert { user, notice });
+                        }
+                        None => deliver_or_log(&client, webhook.as_ref(), notice),
+                    }
+                }
+                let notices = |rules: Vec<AlertRule>| {
+                    rules.into_iter().map(move |rule| AlertNotice::new(&rule, inputs.price, now))
+                };
+                for notice in notices(pushed) {
+                    deliver_or_log(&client, webhook.as_ref(), notice);
+                }
+            }
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::warn!("Alert evaluator lagged, skipped {} messages", skipped);
+            }
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
+
+// ============================================================================
+// HANDLERS
+// ============================================================================
+
+fn authorize(state: &AppState, headers: &HeaderMap) -> Result<(), StatusCode> {
+    match desk(state).authorizes(headers) {
+        None => Err(StatusCode::NOT_FOUND),
+        Some(false) => Err(StatusCode::UNAUTHORIZED),
+        Some(true) => Ok(()),
+    }
+}
+
+/// The alerts token or, failing that, a signed-in user's session
+fn authorize_caller(state: &AppState, headers: &HeaderMap) -> Result<Caller, StatusCode> {
+    match authorize(state, headers) {
+        Ok(()) => Ok(Caller::Token),
+        Err(StatusCode::UNAUTHORIZED) => {
+            let claims = accounts::session(state, headers)?;
+            Ok(Caller::User(claims.sub))
+        }
+        Err(status) => Err(status),
+    }
+}
+
+/// `GET /api/alerts` - the pushed rules and the webhook
+pub async fn alerts_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<ServerAlerts>, StatusCode> {
+    authorize(&state, &headers)?;
+    Ok(Json(desk(&state).snapshot()))
+}
+
+/// `PUT /api/alerts` - replace the pushed rules and webhook (bearer token
+/// required; the webhook host must resolve to public addresses only)
+pub async fn put_alerts_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(alerts): Json<ServerAlerts>,
+) -> StatusCode {
+    if let Err(status) = authorize(&state, &headers) {
+        return status;
+    }
+    if let Some(webhook) = &alerts.webhook
+        && !reaches_public_host(webhook).await
+    {
+        tracing::warn!("Alert webhook refused, no public address: {}", webhook.url);
+        return StatusCode::UNPROCESSABLE_ENTITY;
+    }
+    let rules = alerts.rules.len();
+    if !desk(&state).replace(alerts) {
+        return StatusCode::UNPROCESSABLE_ENTITY;
+    }
+    tracing::info!("Server-side alerts replaced: {} rules", rules);
+    StatusCode::NO_CONTENT
+}
+
+/// Body of `POST /api/alerts/rules`
+#[derive(Debug, Deserialize)]
+pub struct RulePost {
+    symbol: String,
+    /// Condition, e.g. `price > 100000 && rsi(14) < 30`
+    source: String,
+}
+
+/// Status and body for a rule that was not registered
+fn register_error_response(err: &RegisterError) -> (StatusCode, String) {
+    match err {
+        RegisterError::Invalid(err) => (StatusCode::UNPROCESSABLE_ENTITY, expr_error_text(err)),
+        RegisterError::TooMany => (
+            StatusCode::TOO_MANY_REQUESTS,
+            format!("At most {} alert rules per user", MAX_RULES_PER_USER),
+        ),
+    }
+}
+
+/// What is wrong with a condition, for the 422 body
+fn expr_error_text(err: &ExprError) -> String {
+    let text = Locale::En.text(err.msg());
+    match err.name() {
+        Some(name) => format!("{}: {}", text, name),
+        None => format!("{} (at {})", text, err.at + 1),
+    }
+}
+
+/// `GET /api/alerts/rules` - the registered rules (a user's own only)
+pub async fn rules_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<AlertRule>>, StatusCode> {
+    let caller = authorize_caller(&state, &headers)?;
+    Ok(Json(desk(&state).registered(&caller)))
+}
+
+/// `POST /api/alerts/rules` - register a rule (alerts token or session
+/// required); a condition that does not compile is answered with 422 and
+/// the reason, a user's rule past `MAX_RULES_PER_USER` with 429
+pub async fn post_rule_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(post): Json<RulePost>,
+) -> Result<(StatusCode, Json<AlertRule>), (StatusCode, String)> {
+    let caller = authorize_caller(&state, &headers).map_err(|status| (status, String::new()))?;
+    let symbol = Symbol::new(post.symbol.trim().to_ascii_uppercase());
+    let rule = desk(&state)
+        .register(symbol, &post.source, caller.owner())
+        .map_err(|err| register_error_response(&err))?;
+    tracing::info!("Alert rule {} registered: {}: {}", rule.id(), rule.symbol(), rule.source());
+    Ok((StatusCode::CREATED, Json(rule)))
+}
+
+/// `DELETE /api/alerts/rules/:id` - remove a registered rule (a user's own
+/// only)
+pub async fn delete_rule_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Path(id): Path<u64>,
+) -> StatusCode {
+    let caller = match authorize_caller(&state, &headers) {
+        Ok(caller) => caller,
+        Err(status) => return status,
+    };
+    if desk(&state).unregister(id, &caller) {
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dash_core::{OrderBookLevel, OrderBookSnapshot, Trade, TradeSide};
+
+    fn alerts(json: &str) -> ServerAlerts {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_alert_desk() {
+        let mut desk = AlertDesk::new(Some("secret".into()));
+        let mut headers = HeaderMap::new();
+        assert_eq!(AlertDesk::new(None).authorizes(&headers), None);
+        assert_eq!(desk.authorizes(&headers), Some(false));
+        headers.insert(header::AUTHORIZATION, "Bearer secre".parse().unwrap());
+        assert_eq!(desk.authorizes(&headers), Some(false));
+        headers.insert(header::AUTHORIZATION, "Bearer secret".parse().unwrap());
+        assert_eq!(desk.authorizes(&headers), Some(true));
+
+        assert!(!desk.replace(alerts(r#"{"webhook":{"url":"http://example.com/hook"}}"#)));
+        assert!(desk.replace(alerts(
+            r#"{"webhook":{"url":"https://hooks.slack.com/services/x","format":"slack"},
+                "rules":[{"id":4,"symbol":"BTC-USD","source":"price > 100"}]}"#,
+        )));
+        let snapshot = desk.snapshot();
+        assert_eq!(snapshot.webhook.unwrap().format, WebhookFormat::Slack);
+        assert_eq!(snapshot.rules.len(), 1);
+
+        // Registered rules are kept apart from the pushed ones
+        let rule = desk.register(Symbol::new("ETH-USD"), "rsi(14) < 30", None).unwrap();
+        assert_eq!((rule.id(), desk.registered(&Caller::Token).len()), (0, 1));
+        let err = desk.register(Symbol::new("ETH-USD"), "rsi(14) < nope", None).unwrap_err();
+        let response = register_error_response(&err);
+        assert_eq!(response, (StatusCode::UNPROCESSABLE_ENTITY, "Unknown name: nope".into()));
+        assert!(desk.replace(ServerAlerts::default()));
+        assert_eq!(desk.registered(&Caller::Token).len(), 1);
+        assert!(desk.unregister(rule.id(), &Caller::Token));
+        assert!(!desk.unregister(rule.id(), &Caller::Token));
+        assert!(desk.is_empty());
+
+        // Users see and remove only the rules they registered
+        let (alice, bob) = (Caller::User("alice".into()), Caller::User("bob".into()));
+        let owned = desk.register(Symbol::new("BTC-USD"), "price > 1", alice.owner()).unwrap();
+        assert_eq!(desk.owner(owned.id()), Some("alice"));
+        assert_eq!(desk.registered(&alice).len(), 1);
+        assert!(desk.registered(&bob).is_empty());
+        assert!(!desk.unregister(owned.id(), &bob));
+        assert!(desk.unregister(owned.id(), &alice));
+        assert_eq!(desk.owner(owned.id()), None);
+
+        // Each user has a quota; the token does not count against it
+        for _ in 0..MAX_RULES_PER_USER {
+            desk.register(Symbol::new("BTC-USD"), "price > 1", alice.owner()).unwrap();
+        }
+        let err = desk.register(Symbol::new("BTC-USD"), "price > 1", alice.owner()).unwrap_err();
+        assert_eq!(register_error_response(&err).0, StatusCode::TOO_MANY_REQUESTS);
+        assert!(desk.register(Symbol::new("BTC-USD"), "price > 1", bob.owner()).is_ok());
+        assert!(desk.register(Symbol::new("BTC-USD"), "price > 1", None).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_webhook_hosts() {
+        for ip in ["8.8.8.8", "2606:4700::1111", "::ffff:1.1.1.1"] {
+            assert!(is_public(ip.parse().unwrap()), "{}", ip);
+        }
+        for ip in [
+            "127.0.0.1", "10.1.2.3", "172.16.0.1", "192.168.1.1", "169.254.169.254", "100.64.0.1",
+            "0.0.0.0", "::1", "fd00::1", "fe80::1", "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public(ip.parse().unwrap()), "{}", ip);
+        }
+
+        let hook = |url: &str| Webhook { url: url.into(), format: WebhookFormat::Discord };
+        assert!(reaches_public_host(&hook("https://1.1.1.1/hook")).await);
+        for url in [
+            "https://127.0.0.1:8080/hook",
+            "https://[::1]/hook",
+            "https://169.254.169.254/latest/meta-data",
+            "https://localhost/hook",
+            "https://nope.invalid/hook",
+        ] {
+            assert!(!reaches_public_host(&hook(url)).await, "{}", url);
+        }
+    }
+
+    #[test]
+    fn test_live_markets() {
+        let symbol = Symbol::new("BTC-USD");
+        let mut book = AlertBook::new();
+        book.add(symbol.clone(), "price > 100 && spread < 5").unwrap();
+        let mut markets = LiveMarkets::default();
+
+        let trade = Trade::new(symbol.clone(), 101.0, 1.0, TradeSide::Buy);
+        let market = markets.apply(&WsMessage::Trade(trade)).unwrap();
+        // No quote yet, so the spread is unknown
+        assert!(book.check(&symbol, &market.inputs()).is_empty());
+
+        let mut snapshot = OrderBookSnapshot::new(symbol.clone());
+        snapshot.bids = vec![OrderBookLevel::new(100.0, 1.0, 1)];
+        snapshot.asks = vec![OrderBookLevel::new(102.0, 1.0, 1)];
+        let market = markets.apply(&WsMessage::OrderBook(snapshot)).unwrap();
+        let fired: Vec<AlertRule> = book.check(&symbol, &market.inputs());
+        assert_eq!(fired.len(), 1);
+        assert!(markets.apply(&WsMessage::Heartbeat { timestamp: 0 }).is_none());
+
+        let notice = AlertNotice::new(&fired[0], Some(101.0), 0);
+        let text = "🔔 BTC-USD: price > 100 && spread < 5 (last 101)";
+        assert_eq!(webhook_body(WebhookFormat::Discord, &notice), json!({ "content": text }));
+        let slack = webhook_body(WebhookFormat::Slack, &notice);
+        assert!(slack["text"].as_str().unwrap().starts_with(":bell: BTC-USD"));
+        assert_eq!(webhook_body(WebhookFormat::Json, &notice)["price"], 101.0);
+    }
+}
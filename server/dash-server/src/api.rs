@@ -0,0 +1,435 @@
+//! REST snapshot and history endpoints
+//!
+//! The server keeps the latest book, ticker and recent trades per symbol so
+//! clients can render before the first WebSocket messages arrive and external
+//! tools can poll without holding a socket open. Longer ranges are served
+//! from history storage.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Deserialize;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::storage::{HistoryRange, Storage, MAX_DOWNSAMPLE_SOURCE_ROWS, MAX_HISTORY_ROWS};
+use crate::AppState;
+use dash_core::{
+    Candle, CandleHistory, CandleInterval, LineDownsampler, LinePoint, Lttb, OrderBookSnapshot,
+    RingBuffer, Symbol, SymbolInfo, Ticker, Trade, WsMessage,
+};
+
+/// Trades retained per symbol for `/api/trades`
+pub const MAX_CACHED_TRADES: usize = 500;
+
+/// `limit` used by `/api/trades` when none is given
+const DEFAULT_TRADE_LIMIT: usize = 50;
+
+// ============================================================================
+// MARKET CACHE
+// ============================================================================
+
+/// Latest state for one symbol
+#[derive(Debug, Clone)]
+struct SymbolSnapshot {
+    orderbook: Option<OrderBookSnapshot>,
+    ticker: Option<Ticker>,
+    /// Oldest first
+    trades: RingBuffer<Trade>,
+}
+
+impl Default for SymbolSnapshot {
+    fn default() -> Self {
+        Self {
+            orderbook: None,
+            ticker: None,
+            trades: RingBuffer::new(MAX_CACHED_TRADES),
+        }
+    }
+}
+
+/// Latest market state per symbol, fed from the broadcast stream
+#[derive(Debug, Default)]
+pub struct MarketCache {
+    symbols: HashMap<Symbol, SymbolSnapshot>,
+}
+
+impl MarketCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a message published to clients
+    pub fn apply(&mut self, msg: &WsMessage) {
+        match msg {
+            WsMessage::OrderBook(book) => {
+                self.entry(&book.symbol).orderbook = Some(book.clone());
+            }
+            WsMessage::Ticker(ticker) => {
+                self.entry(&ticker.symbol).ticker = Some(ticker.clone());
+            }
+            WsMessage::Trade(trade) => {
+                self.entry(&trade.symbol).trades.push(trade.clone());
+            }
+            WsMessage::Candle(_)
+            | WsMessage::Depth(_)
+            | WsMessage::Heartbeat { .. }
+            | WsMessage::SourceStatus(_)
+            | WsMessage::ConsolidatedBook(_)
+            | WsMessage::News(_)
+            | WsMessage::AlertTriggered(_) => {}
+        }
+    }
+
+    pub fn orderbook(&self, symbol: &Symbol) -> Option<OrderBookSnapshot> {
+        self.symbols.get(symbol)?.orderbook.clone()
+    }
+
+    pub fn ticker(&self, symbol: &Symbol) -> Option<Ticker> {
+        self.symbols.get(symbol)?.ticker.clone()
+    }
+
+    /// Latest book and ticker for a client resyncing after a sequence gap
+    pub fn resync_messages(&self, symbol: &Symbol) -> Vec<WsMessage> {
+        let book = self.orderbook(symbol).map(WsMessage::OrderBook);
+        let ticker = self.ticker(symbol).map(WsMessage::Ticker);
+        book.into_iter().chain(ticker).collect()
+    }
+
+    /// Newest `limit` trades, newest first
+    pub fn recent_trades(&self, symbol: &Symbol, limit: usize) -> Vec<Trade> {
+        self.symbols
+            .get(symbol)
+            .map(|s| s.trades.recent(limit).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn entry(&mut self, symbol: &Symbol) -> &mut SymbolSnapshot {
+        self.symbols.entry(symbol.clone()).or_default()
+    }
+}
+
+/// Keep the cache in step with everything broadcast to WebSocket clients
+pub async fn run_cache_updater(state: Arc<AppState>) {
+    let mut rx = state.tx.subscribe();
+
+    loop {
+        match rx.recv().await {
+            Ok(msg) => write_cache(&state).apply(&msg),
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::warn!("Snapshot cache lagged, skipped {} messages", skipped);
+            }
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
+
+fn write_cache(state: &AppState) -> std::sync::RwLockWriteGuard<'_, MarketCache> {
+    state.cache.write().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn read_cache(state: &AppState) -> std::sync::RwLockReadGuard<'_, MarketCache> {
+    state.cache.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+// ============================================================================
+// HANDLERS
+// ============================================================================
+
+/// Query parameters shared by the snapshot endpoints
+#[derive(Debug, Default, Deserialize)]
+pub struct SnapshotParams {
+    /// Trading pair (defaults to BTC-USD)
+    symbol: Option<String>,
+    /// Number of trades for `/api/trades`
+    limit: Option<usize>,
+}
+
+impl SnapshotParams {
+    fn symbol(&self) -> Symbol {
+        parse_symbol(self.symbol.as_deref())
+    }
+}
+
+/// Normalize a `symbol` query value (defaults to BTC-USD)
+fn parse_symbol(symbol: Option<&str>) -> Symbol {
+    symbol
+        .map(|s| Symbol::new(s.trim().to_ascii_uppercase()))
+        .unwrap_or_default()
+}
+
+/// `GET /api/orderbook?symbol=` - latest order book snapshot
+pub async fn orderbook_handler(
+    Query(params): Query<SnapshotParams>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<OrderBookSnapshot>, StatusCode> {
+    read_cache(&state)
+        .orderbook(&params.symbol())
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+/// `GET /api/ticker?symbol=` - latest 24h ticker
+pub async fn ticker_handler(
+    Query(params): Query<SnapshotParams>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Ticker>, StatusCode> {
+    read_cache(&state)
+        .ticker(&params.symbol())
+        .map(Json)
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+/// `GET /api/trades?symbol=&limit=` - recent trades, newest first
+pub async fn trades_handler(
+    Query(params): Query<SnapshotParams>,
+    State(state): State<Arc<AppState>>,
+) -> Json<Vec<Trade>> {
+    let limit = params.limit.unwrap_or(DEFAULT_TRADE_LIMIT).min(MAX_CACHED_TRADES);
+    Json(read_cache(&state).recent_trades(&params.symbol(), limit))
+}
+
+/// `GET /api/symbols` - subscribed symbols with their venue instrument ids
+pub async fn symbols_handler(State(state): State<Arc<AppState>>) -> Json<Vec<SymbolInfo>> {
+    Json(state.symbols.clone())
+}
+
+// ============================================================================
+// HISTORY HANDLERS
+// ============================================================================
+
+/// Query parameters for the history endpoints
+#[derive(Debug, Default, Deserialize)]
+pub struct HistoryParams {
+    symbol: Option<String>,
+    /// Candle interval code (candles only, default 1m)
+    interval: Option<String>,
+    /// Range start, unix ms (inclusive)
+    from: Option<i64>,
+    /// Range end, unix ms (inclusive)
+    to: Option<i64>,
+    limit: Option<usize>,
+    /// Candles: coarsen the interval until the range fits in this many candles
+    /// (no resampling without it). Line: LTTB target point count.
+    max_points: Option<usize>,
+}
+
+impl HistoryParams {
+    fn symbol(&self) -> Symbol {
+        parse_symbol(self.symbol.as_deref())
+    }
+
+    fn interval(&self) -> Result<CandleInterval, StatusCode> {
+        match self.interval.as_deref() {
+            Some(code) => CandleInterval::from_code(code).ok_or(StatusCode::BAD_REQUEST),
+            None => Ok(CandleInterval::default()),
+        }
+    }
+
+    /// Range returned to the client
+    fn range(&self) -> HistoryRange {
+        HistoryRange {
+            from: self.from.unwrap_or(i64::MIN),
+            to: self.to.unwrap_or(i64::MAX),
+            limit: self.limit.unwrap_or(MAX_HISTORY_ROWS).min(MAX_HISTORY_ROWS),
+        }
+    }
+
+    /// Finest interval at or above `interval` that fits the requested range
+    /// in `max_points` candles; without `from` the newest `max_points` fit
+    /// as they are
+    fn resample_target(&self, interval: CandleInterval, max_points: usize) -> CandleInterval {
+        let Some(from) = self.from else { return interval };
+        let to = self.to.unwrap_or_else(|| chrono::Utc::now().timestamp_millis());
+        interval.coarsen_for(to.saturating_sub(from).max(0), max_points)
+    }
+
+    /// Range read from storage to build the newest `points` candles of
+    /// `target` from `interval` ones: one source row per base candle they
+    /// cover, plus a partial bucket at the start
+    fn source_range(
+        &self,
+        interval: CandleInterval,
+        target: CandleInterval,
+        points: usize,
+    ) -> HistoryRange {
+        let ratio = (target.as_millis() / interval.as_millis()) as usize;
+        HistoryRange {
+            limit: (points + 1).saturating_mul(ratio).min(MAX_DOWNSAMPLE_SOURCE_ROWS),
+            ..self.range()
+        }
+    }
+
+    /// Requested point budget, within bounds
+    fn max_points(&self) -> Option<usize> {
+        self.max_points.map(|points| points.clamp(MIN_MAX_POINTS, MAX_HISTORY_ROWS))
+    }
+}
+
+/// LTTB point budget used when `max_points` is not given
+const DEFAULT_LINE_POINTS: usize = 1_000;
+
+/// Smallest accepted `max_points` (LTTB needs at least 3)
+const MIN_MAX_POINTS: usize = 3;
+
+/// Resample oldest-first candles to `target` when it is coarser than theirs
+fn resample_candles(candles: Vec<Candle>, target: CandleInterval) -> Vec<Candle> {
+    let Some(first) = candles.first() else { return candles };
+    let interval = first.interval;
+    if target == interval {
+        return candles;
+    }
+
+    let mut history = CandleHistory::new(first.symbol.clone(), interval);
+    history.candles = candles.into();
+    match history.resample(target) {
+        Some(resampled) => resampled.candles.into_vec(),
+        None => history.candles.into_vec(),
+    }
+}
+
+/// Run a blocking storage query, mapping failures to HTTP statuses
+async fn query_storage<T, F>(state: &AppState, query: F) -> Result<Json<T>, StatusCode>
+where
+    T: Send + 'static,
+    F: FnOnce(&Storage) -> rusqlite::Result<T> + Send + 'static,
+{
+    let storage = state.storage.clone().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    match tokio::task::spawn_blocking(move || query(&storage)).await {
+        Ok(Ok(value)) => Ok(Json(value)),
+        Ok(Err(e)) => {
+            tracing::error!("History query failed: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+        Err(e) => {
+            tracing::error!("History query panicked: {}", e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+/// `GET /api/history/candles?symbol=&interval=&from=&to=&limit=&max_points=` - stored
+/// candles, oldest first. With `max_points`, a long `from`..`to` range is resampled
+/// to a coarser interval (check each candle's `interval`) so at most `max_points`
+/// candles are returned.
+pub async fn candle_history_handler(
+    Query(params): Query<HistoryParams>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<Candle>>, StatusCode> {
+    let interval = params.interval()?;
+    let (symbol, range) = (params.symbol(), params.range());
+    let Some(max_points) = params.max_points() else {
+        return query_storage(&state, move |storage| storage.candles(&symbol, interval, range))
+            .await;
+    };
+
+    let target = params.resample_target(interval, max_points);
+    let points = range.limit.min(max_points);
+    let source = params.source_range(interval, target, points);
+    query_storage(&state, move |storage| {
+        let candles = resample_candles(storage.candles(&symbol, interval, source)?, target);
+        Ok(newest(candles, points))
+    })
+    .await
+}
+
+/// `GET /api/history/line?symbol=&interval=&from=&to=&max_points=` - close prices as a
+/// line series, LTTB-downsampled to at most `max_points` points
+pub async fn line_history_handler(
+    Query(params): Query<HistoryParams>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<LinePoint>>, StatusCode> {
+    let interval = params.interval()?;
+    let symbol = params.symbol();
+    let max_points = params.max_points().unwrap_or(DEFAULT_LINE_POINTS);
+    let source = HistoryRange { limit: MAX_DOWNSAMPLE_SOURCE_ROWS, ..params.range() };
+
+    query_storage(&state, move |storage| {
+        let points: Vec<LinePoint> = storage
+            .candles(&symbol, interval, source)?
+            .iter()
+            .map(|c| LinePoint::new(c.timestamp, c.close.as_f64()))
+            .collect();
+        Ok(Lttb.downsample(&points, max_points))
+    })
+    .await
+}
+
+/// Keep the newest `limit` items of an oldest-first list
+fn newest<T>(mut items: Vec<T>, limit: usize) -> Vec<T> {
+    let excess = items.len().saturating_sub(limit);
+    items.drain(..excess);
+    items
+}
+
+/// `GET /api/history/trades?symbol=&from=&to=&limit=` - stored trades, newest first
+pub async fn trade_history_handler(
+    Query(params): Query<HistoryParams>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<Trade>>, StatusCode> {
+    let (symbol, range) = (params.symbol(), params.range());
+    query_storage(&state, move |storage| storage.trades(&symbol, range)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dash_core::TradeSide;
+
+    #[test]
+    fn test_market_cache() {
+        let mut cache = MarketCache::new();
+        let btc = Symbol::new("BTC-USD");
+
+        for i in 0..(MAX_CACHED_TRADES + 10) {
+            let trade = Trade::new(btc.clone(), 50_000.0 + i as f64, 0.1, TradeSide::Buy);
+            cache.apply(&WsMessage::Trade(trade));
+        }
+        cache.apply(&WsMessage::Ticker(Ticker::new(btc.clone(), 50_000.0)));
+
+        let trades = cache.recent_trades(&btc, 3);
+        assert_eq!(trades.len(), 3);
+        assert_eq!(trades[0].price.as_f64(), 50_000.0 + (MAX_CACHED_TRADES + 9) as f64);
+        assert_eq!(cache.recent_trades(&btc, usize::MAX).len(), MAX_CACHED_TRADES);
+
+        assert!(cache.ticker(&btc).is_some());
+        assert!(cache.orderbook(&btc).is_none());
+        assert!(matches!(cache.resync_messages(&btc)[..], [WsMessage::Ticker(_)]));
+        assert!(cache.recent_trades(&Symbol::new("ETH-USD"), 10).is_empty());
+    }
+
+    #[test]
+    fn test_downsample_candles() {
+        let btc = Symbol::new("BTC-USD");
+        // One day of 1m candles
+        let candles: Vec<Candle> = (0..1_440)
+            .map(|i| {
+                let mut candle = Candle::new(btc.clone(), CandleInterval::M1, i * 60_000, 100.0);
+                candle.close_candle();
+                candle
+            })
+            .collect();
+
+        let day = HistoryParams { from: Some(0), to: Some(86_399_999), ..Default::default() };
+        let target = day.resample_target(CandleInterval::M1, 500);
+        assert_eq!(target, CandleInterval::M5);
+        let resampled = resample_candles(candles.clone(), target);
+        assert_eq!(resampled.len(), 288);
+        assert!(resampled.iter().all(|c| c.interval == CandleInterval::M5));
+        // Only the rows the returned candles are built from are read
+        assert_eq!(day.source_range(CandleInterval::M1, target, 10).limit, 55);
+
+        // Short ranges and open-ended requests pass through untouched
+        let hour = HistoryParams { from: Some(0), to: Some(3_599_999), ..Default::default() };
+        assert_eq!(hour.resample_target(CandleInterval::M1, 500), CandleInterval::M1);
+        let newest_only = HistoryParams { limit: Some(10), ..Default::default() };
+        assert_eq!(newest_only.resample_target(CandleInterval::M1, 3), CandleInterval::M1);
+        assert_eq!(newest_only.source_range(CandleInterval::M1, CandleInterval::M1, 3).limit, 4);
+        assert_eq!(resample_candles(candles[..100].to_vec(), CandleInterval::M1).len(), 100);
+        assert_eq!(newest(vec![1, 2, 3], 2), vec![2, 3]);
+    }
+}
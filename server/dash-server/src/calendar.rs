@@ -0,0 +1,106 @@
+//! Economic calendar
+//!
+//! `GET /api/calendar` lists scheduled events (rate decisions, data
+//! releases, maintenance windows) from the JSON file named by
+//! `DASH_CALENDAR_FILE`, re-read on every request so edits apply without a
+//! restart. Without a file the server makes up a demo schedule around now.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Deserialize;
+
+use crate::AppState;
+use dash_core::{EventKind, ScheduledEvent};
+
+const HOUR_MS: i64 = 3_600_000;
+const DAY_MS: i64 = 24 * HOUR_MS;
+
+/// Range served when the request names none: a week back, a month ahead
+const DEFAULT_PAST_MS: i64 = 7 * DAY_MS;
+const DEFAULT_AHEAD_MS: i64 = 30 * DAY_MS;
+
+/// Calendar file from `DASH_CALENDAR_FILE`
+pub fn calendar_file_from_env() -> Option<PathBuf> {
+    std::env::var("DASH_CALENDAR_FILE").ok().filter(|v| !v.trim().is_empty()).map(PathBuf::from)
+}
+
+/// Demo schedule around `now_ms`: a past release for the chart, then
+/// upcoming data, a rate decision and a maintenance window
+pub fn demo_events(now_ms: i64) -> Vec<ScheduledEvent> {
+    let today = now_ms.div_euclid(DAY_MS) * DAY_MS;
+    // Day offset from today and UTC time of day in minutes
+    let at = |days: i64, minutes: i64| today + days * DAY_MS + minutes * 60_000;
+    vec![
+        ScheduledEvent::new("demo-nfp", "US jobs report", EventKind::Macro, at(-2, 12 * 60 + 30)),
+        ScheduledEvent::new("demo-cpi", "US CPI", EventKind::Macro, at(1, 12 * 60 + 30)),
+        ScheduledEvent::new("demo-fomc", "FOMC rate decision", EventKind::Macro, at(3, 18 * 60)),
+        ScheduledEvent::new(
+            "demo-maintenance",
+            "Exchange maintenance",
+            EventKind::Maintenance,
+            at(5, 2 * 60),
+        ),
+    ]
+}
+
+/// Query parameters for `/api/calendar` (Unix millis)
+#[derive(Debug, Default, Deserialize)]
+pub struct CalendarParams {
+    from: Option<i64>,
+    to: Option<i64>,
+}
+
+/// Events starting within `[from, to]`, soonest first
+fn in_range(mut events: Vec<ScheduledEvent>, from: i64, to: i64) -> Vec<ScheduledEvent> {
+    events.retain(|e| (from..=to).contains(&e.timestamp));
+    events.sort_by_key(|e| e.timestamp);
+    events
+}
+
+/// `GET /api/calendar?from=&to=` - scheduled events, soonest first
+pub async fn calendar_handler(
+    Query(params): Query<CalendarParams>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<ScheduledEvent>>, StatusCode> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let events = match &state.calendar_file {
+        Some(path) => {
+            let contents = tokio::fs::read_to_string(path).await.map_err(|e| {
+                tracing::warn!("Failed to read calendar file {:?}: {}", path, e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+            serde_json::from_str(&contents).map_err(|e| {
+                tracing::warn!("Invalid calendar file {:?}: {}", path, e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?
+        }
+        None => demo_events(now),
+    };
+    let from = params.from.unwrap_or(now - DEFAULT_PAST_MS);
+    let to = params.to.unwrap_or(now + DEFAULT_AHEAD_MS);
+    Ok(Json(in_range(events, from, to)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calendar_range() {
+        // Monday 2024-01-01 09:00 UTC
+        let now = 1_704_067_200_000 + 9 * HOUR_MS;
+        let events = demo_events(now);
+        assert!(events.iter().any(|e| e.timestamp < now));
+        assert!(events.iter().all(|e| (e.timestamp - now).abs() < DEFAULT_AHEAD_MS));
+
+        let upcoming = in_range(events, now, now + 4 * DAY_MS);
+        let ids: Vec<&str> = upcoming.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["demo-cpi", "demo-fomc"]);
+    }
+}
@@ -0,0 +1,160 @@
+//! Chaos mode: network faults injected into client connections
+//!
+//! Switched on with `DASH_CHAOS` at startup or `PUT /api/control/chaos`.
+//! Every connection then delays, drops, duplicates and reorders its frames
+//! and is closed after a while, as the [`ChaosConfig`] says. Latency beyond
+//! what the broadcast buffer absorbs ends a connection like any slow client.
+
+use std::time::{Duration, Instant};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use dash_core::{ChaosConfig, WsMessage};
+
+/// Chaos settings at startup (`DASH_CHAOS`), off when unset
+pub fn chaos_from_env() -> ChaosConfig {
+    let Ok(spec) = std::env::var("DASH_CHAOS") else {
+        return ChaosConfig::default();
+    };
+    let config = parse_spec(&spec);
+    if config.is_active() {
+        tracing::warn!("Chaos mode on: {:?}", config);
+    }
+    config
+}
+
+/// `latency=200,jitter=100,drop=0.05,duplicate=0.02,reorder=0.05,disconnect=60`;
+/// anything else is skipped with a warning
+pub fn parse_spec(spec: &str) -> ChaosConfig {
+    let mut config = ChaosConfig::default();
+    for pair in spec.split(',').map(str::trim).filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let value = value.trim();
+        let parsed = match key.trim() {
+            "latency" => value.parse().map(|ms| config.latency_ms = ms).is_ok(),
+            "jitter" => value.parse().map(|ms| config.jitter_ms = ms).is_ok(),
+            "drop" => value.parse().map(|rate| config.drop_rate = rate).is_ok(),
+            "duplicate" => value.parse().map(|rate| config.duplicate_rate = rate).is_ok(),
+            "reorder" => value.parse().map(|rate| config.reorder_rate = rate).is_ok(),
+            "disconnect" => value.parse().map(|secs| config.disconnect_secs = secs).is_ok(),
+            _ => false,
+        };
+        if !parsed {
+            tracing::warn!("DASH_CHAOS: ignoring {:?}", pair);
+        }
+    }
+    config.clamped()
+}
+
+/// Faults on one connection
+pub struct ChaosLink {
+    rng: StdRng,
+    opened: Instant,
+    /// Frame held back to go out after the next one
+    held: Option<WsMessage>,
+}
+
+impl ChaosLink {
+    pub fn new(opened: Instant) -> Self {
+        Self { rng: StdRng::from_entropy(), opened, held: None }
+    }
+
+    fn chance(&mut self, rate: f64) -> bool {
+        self.rng.r#gen::<f64>() < rate
+    }
+
+    /// Frames to send in place of `msg`, in order
+    pub fn apply(&mut self, config: &ChaosConfig, msg: WsMessage) -> Vec<WsMessage> {
+        if let Some(held) = self.held.take() {
+            return vec![msg, held];
+        }
+        if self.chance(config.drop_rate) {
+            return Vec::new();
+        }
+        if self.chance(config.reorder_rate) {
+            self.held = Some(msg);
+            return Vec::new();
+        }
+        if self.chance(config.duplicate_rate) {
+            return vec![msg.clone(), msg];
+        }
+        vec![msg]
+    }
+
+    /// Wait before sending the next frames
+    pub fn delay(&mut self, config: &ChaosConfig) -> Duration {
+        let jitter = match config.jitter_ms {
+            0 => 0,
+            max => self.rng.gen_range(0..=max),
+        };
+        Duration::from_millis(config.latency_ms + jitter)
+    }
+
+    /// Whether the connection is due to be closed
+    pub fn expired(&self, config: &ChaosConfig, now: Instant) -> bool {
+        config.disconnect_secs > 0
+            && now.saturating_duration_since(self.opened)
+                >= Duration::from_secs(config.disconnect_secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn heartbeat(timestamp: i64) -> WsMessage {
+        WsMessage::Heartbeat { timestamp }
+    }
+
+    fn timestamps(messages: Vec<WsMessage>) -> Vec<i64> {
+        messages
+            .into_iter()
+            .map(|msg| match msg {
+                WsMessage::Heartbeat { timestamp } => timestamp,
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_spec() {
+        let config = parse_spec("latency=200, drop=0.1,reorder=2,bogus=1,disconnect=x");
+        assert_eq!(
+            config,
+            ChaosConfig {
+                latency_ms: 200,
+                drop_rate: 0.1,
+                reorder_rate: 1.0,
+                ..ChaosConfig::default()
+            }
+        );
+        assert!(!parse_spec("").is_active());
+    }
+
+    #[test]
+    fn test_link_faults() {
+        let start = Instant::now();
+        let mut link = ChaosLink::new(start);
+        let off = ChaosConfig::default();
+        assert_eq!(timestamps(link.apply(&off, heartbeat(1))), vec![1]);
+        assert_eq!(link.delay(&off), Duration::ZERO);
+
+        let drop = ChaosConfig { drop_rate: 1.0, ..off };
+        assert!(link.apply(&drop, heartbeat(2)).is_empty());
+
+        let duplicate = ChaosConfig { duplicate_rate: 1.0, ..off };
+        assert_eq!(timestamps(link.apply(&duplicate, heartbeat(3))), vec![3, 3]);
+
+        // Every other frame swaps places with the one after it
+        let reorder = ChaosConfig { reorder_rate: 1.0, ..off };
+        let sent: Vec<i64> =
+            (4..8).flat_map(|n| timestamps(link.apply(&reorder, heartbeat(n)))).collect();
+        assert_eq!(sent, vec![5, 4, 7, 6]);
+
+        let disconnect = ChaosConfig { disconnect_secs: 30, ..off };
+        assert!(!link.expired(&disconnect, start + Duration::from_secs(29)));
+        assert!(link.expired(&disconnect, start + Duration::from_secs(30)));
+        assert!(!link.expired(&off, start + Duration::from_secs(3_600)));
+    }
+}
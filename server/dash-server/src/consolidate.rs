@@ -0,0 +1,124 @@
+//! Cross-venue consolidation
+//!
+//! Merges the latest venue-attributed order books for each pair into a
+//! `WsMessage::ConsolidatedBook` with per-venue attribution and a
+//! consolidated best bid/offer. Trades need no merging: each carries its
+//! venue and the client filters the tape by the selected view.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::Instant;
+
+use crate::AppState;
+use dash_core::{ConsolidatedBook, OrderBookSnapshot, Symbol, WsMessage, CONSOLIDATED_VENUE};
+
+/// Minimum time between consolidated books per symbol
+pub const CONSOLIDATE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Venue books older than this are left out of the merge
+pub const VENUE_BOOK_TTL: Duration = Duration::from_secs(10);
+
+#[derive(Debug)]
+struct VenueBook {
+    book: OrderBookSnapshot,
+    received: Instant,
+}
+
+/// Latest book per venue for each symbol
+#[derive(Debug, Default)]
+pub struct Consolidator {
+    books: HashMap<Symbol, HashMap<String, VenueBook>>,
+    last_emit: HashMap<Symbol, Instant>,
+}
+
+impl Consolidator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a venue book; returns a merged book when one is due
+    pub fn update(&mut self, book: &OrderBookSnapshot, now: Instant) -> Option<ConsolidatedBook> {
+        let venue = book.venue.clone()?;
+        if venue == CONSOLIDATED_VENUE {
+            return None;
+        }
+
+        let venues = self.books.entry(book.symbol.clone()).or_default();
+        venues.insert(venue, VenueBook { book: book.clone(), received: now });
+        venues.retain(|_, v| now.saturating_duration_since(v.received) <= VENUE_BOOK_TTL);
+
+        let due = self
+            .last_emit
+            .get(&book.symbol)
+            .is_none_or(|at| now.saturating_duration_since(*at) >= CONSOLIDATE_INTERVAL);
+        if !due {
+            return None;
+        }
+        self.last_emit.insert(book.symbol.clone(), now);
+
+        let fresh: Vec<&OrderBookSnapshot> = venues.values().map(|v| &v.book).collect();
+        Some(ConsolidatedBook::merge(book.symbol.clone(), &fresh))
+    }
+}
+
+/// Publish consolidated books built from the broadcast venue books
+pub async fn run_consolidator(state: Arc<AppState>) {
+    let mut rx = state.tx.subscribe();
+    let mut consolidator = Consolidator::new();
+
+    loop {
+        match rx.recv().await {
+            Ok(WsMessage::OrderBook(book)) => {
+                if let Some(merged) = consolidator.update(&book, Instant::now()) {
+                    let _ = state.tx.send(WsMessage::ConsolidatedBook(merged));
+                }
+            }
+            Ok(_) => {}
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::warn!("Consolidator lagged, skipped {} messages", skipped);
+            }
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dash_core::OrderBookLevel;
+
+    fn venue_book(venue: &str, bid: f64) -> OrderBookSnapshot {
+        let mut book = OrderBookSnapshot::new(Symbol::new("BTC-USD"));
+        book.bids = vec![OrderBookLevel::new(bid, 1.0, 1)];
+        book.asks = vec![OrderBookLevel::new(bid + 10.0, 1.0, 1)];
+        book.venue = Some(venue.to_string());
+        book
+    }
+
+    #[test]
+    fn test_consolidator_throttles_and_expires() {
+        let mut consolidator = Consolidator::new();
+        let start = Instant::now();
+
+        let first = consolidator.update(&venue_book("mock", 100.0), start).unwrap();
+        assert_eq!(first.venues, vec!["mock"]);
+
+        // Within the interval: stored but not emitted
+        assert!(consolidator.update(&venue_book("mock-2", 101.0), start).is_none());
+
+        let merged = consolidator.update(&venue_book("mock", 100.0), start + CONSOLIDATE_INTERVAL).unwrap();
+        assert_eq!(merged.venues, vec!["mock", "mock-2"]);
+        assert_eq!(merged.bbo.bid.unwrap().venue, "mock-2");
+
+        // mock-2 goes quiet past the TTL
+        let later = start + VENUE_BOOK_TTL + Duration::from_secs(1);
+        let merged = consolidator.update(&venue_book("mock", 100.0), later).unwrap();
+        assert_eq!(merged.venues, vec!["mock"]);
+
+        // Untagged books are not consolidated
+        assert!(consolidator.update(&OrderBookSnapshot::new(Symbol::new("BTC-USD")), later).is_none());
+    }
+}
@@ -0,0 +1,618 @@
+//! Upstream feed supervision: staleness detection, reconnects and failover
+//!
+//! The supervisor runs one upstream adapter (see `dash_adapters`) at a time
+//! and forwards its messages to the client broadcast channel. When a channel goes quiet for
+//! longer than its threshold the source is restarted; after repeated
+//! failures the next source in the chain takes over (typically the mock
+//! engine). While on a fallback the primary is probed in the background and
+//! resumes once it delivers every channel again. Every transition is pushed
+//! to clients as `WsMessage::SourceStatus`. Operators can switch the primary
+//! feed to any source in the chain (`PUT /api/control/source`); it stays
+//! there until it fails over.
+//!
+//! Books and trades are tagged with the source's name as their venue.
+//! Secondary venues (for cross-venue consolidation) run under their own
+//! supervisor that forwards only books and trades and stays out of health
+//! and status reporting.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+use crate::limits::env_or;
+use crate::AppState;
+use dash_adapters::{parse_overrides, run_adapter, AdapterConfig, AdapterRegistry, MarketDataAdapter};
+use dash_core::{FeedChannel, FeedStatus, SourceStatus, Subscription, SymbolInfo, WsMessage};
+
+// ============================================================================
+// CONFIGURATION
+// ============================================================================
+
+/// Staleness thresholds and retry limits
+#[derive(Debug, Clone)]
+pub struct FailoverConfig {
+    /// Silence tolerated per channel before the source counts as stalled
+    pub stale_after: HashMap<FeedChannel, Duration>,
+    /// Restarts of the same source before failing over
+    pub max_reconnects: u32,
+    /// How often staleness is evaluated
+    pub check_interval: Duration,
+    /// Time on a fallback before probing the primary again
+    pub failback_after: Duration,
+}
+
+impl Default for FailoverConfig {
+    fn default() -> Self {
+        Self {
+            stale_after: HashMap::from([
+                (FeedChannel::Trades, Duration::from_secs(30)),
+                (FeedChannel::OrderBook, Duration::from_secs(10)),
+                (FeedChannel::Ticker, Duration::from_secs(15)),
+                (FeedChannel::Candles, Duration::from_secs(30)),
+            ]),
+            max_reconnects: 3,
+            check_interval: Duration::from_secs(1),
+            failback_after: Duration::from_secs(60),
+        }
+    }
+}
+
+impl FailoverConfig {
+    /// Defaults overridden by `DASH_MAX_RECONNECTS` and `DASH_FAILBACK_SECS`
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            max_reconnects: env_or("DASH_MAX_RECONNECTS", defaults.max_reconnects),
+            failback_after: Duration::from_secs(env_or(
+                "DASH_FAILBACK_SECS",
+                defaults.failback_after.as_secs(),
+            )),
+            ..defaults
+        }
+    }
+
+    fn threshold(&self, channel: FeedChannel) -> Duration {
+        self.stale_after
+            .get(&channel)
+            .copied()
+            .unwrap_or(Duration::from_millis(dash_core::STALE_AFTER_MS))
+    }
+
+    /// Longest threshold; a probe gets this long to prove itself
+    fn max_threshold(&self) -> Duration {
+        FeedChannel::all()
+            .iter()
+            .map(|c| self.threshold(*c))
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+/// Adapters named in a comma-separated env var (e.g. `DASH_ADAPTERS=binance,coinbase`).
+/// `DASH_<NAME>_URL` overrides an adapter's endpoint and `DASH_<NAME>_SYMBOLS`
+/// its instrument ids (`BTC-USD=BTCFDUSD,...`); unknown names are skipped.
+pub fn adapters_from_env(registry: &AdapterRegistry, var: &str, default: &str) -> Vec<Arc<dyn MarketDataAdapter>> {
+    let spec = std::env::var(var).unwrap_or_else(|_| default.to_string());
+    spec.split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .filter_map(|name| {
+            let config = AdapterConfig {
+                endpoint: std::env::var(format!("DASH_{}_URL", name.to_uppercase())).ok(),
+                symbols: std::env::var(format!("DASH_{}_SYMBOLS", name.to_uppercase()))
+                    .map(|spec| parse_overrides(&spec))
+                    .unwrap_or_default(),
+            };
+            let adapter = registry.create(name, &config);
+            if adapter.is_none() {
+                tracing::warn!(
+                    "{}: unknown adapter {:?} (available: {})",
+                    var,
+                    name,
+                    registry.names().collect::<Vec<_>>().join(", ")
+                );
+            }
+            adapter
+        })
+        .collect()
+}
+
+/// Symbols every source subscribes to (`DASH_SYMBOLS`, default `BTC-USD`)
+pub fn subscriptions_from_env() -> Vec<Subscription> {
+    std::env::var("DASH_SYMBOLS")
+        .unwrap_or_else(|_| "BTC-USD".to_string())
+        .split(',')
+        .map(str::trim)
+        .filter(|symbol| !symbol.is_empty())
+        .map(|symbol| Subscription::new(symbol.to_uppercase().as_str()))
+        .collect()
+}
+
+/// Seed making the mock engine repeatable (`DASH_MOCK_SEED`); unset or
+/// invalid leaves it random
+pub fn mock_seed_from_env() -> Option<u64> {
+    let value = std::env::var("DASH_MOCK_SEED").ok()?;
+    let seed = value.trim().parse().ok();
+    if seed.is_none() {
+        tracing::warn!("Ignoring invalid DASH_MOCK_SEED={:?}", value);
+    }
+    seed
+}
+
+/// Instrument ids of each subscribed symbol on every configured venue
+pub fn symbol_info(subscriptions: &[Subscription], adapters: &[Arc<dyn MarketDataAdapter>]) -> Vec<SymbolInfo> {
+    subscriptions
+        .iter()
+        .map(|subscription| {
+            adapters.iter().fold(SymbolInfo::new(subscription.symbol.clone()), |info, adapter| {
+                let instrument = adapter.instrument(&info.symbol);
+                info.with_instrument(adapter.name(), instrument)
+            })
+        })
+        .collect()
+}
+
+// ============================================================================
+// STALENESS TRACKING
+// ============================================================================
+
+/// Last message time per channel since a source was (re)started
+#[derive(Debug)]
+struct Staleness {
+    started: Instant,
+    last: HashMap<FeedChannel, Instant>,
+}
+
+impl Staleness {
+    fn new(now: Instant) -> Self {
+        Self {
+            started: now,
+            last: HashMap::new(),
+        }
+    }
+
+    fn record(&mut self, msg: &WsMessage, now: Instant) {
+        if let Some(channel) = FeedChannel::for_message(msg) {
+            self.last.insert(channel, now);
+        }
+    }
+
+    /// Channels silent past their threshold (a fresh start gets one threshold of grace)
+    fn stale_channels(
+        &self,
+        channels: &[FeedChannel],
+        now: Instant,
+        config: &FailoverConfig,
+    ) -> Vec<FeedChannel> {
+        channels
+            .iter()
+            .copied()
+            .filter(|channel| {
+                let since = self.last.get(channel).copied().unwrap_or(self.started);
+                now.saturating_duration_since(since) > config.threshold(*channel)
+            })
+            .collect()
+    }
+
+    /// Channels that delivered data within their threshold
+    fn fresh_count(&self, channels: &[FeedChannel], now: Instant, config: &FailoverConfig) -> usize {
+        channels
+            .iter()
+            .filter(|channel| {
+                self.last
+                    .get(channel)
+                    .is_some_and(|at| now.saturating_duration_since(*at) <= config.threshold(**channel))
+            })
+            .count()
+    }
+
+    /// Every channel has produced at least one message
+    fn complete(&self, channels: &[FeedChannel]) -> bool {
+        channels.iter().all(|c| self.last.contains_key(c))
+    }
+}
+
+// ============================================================================
+// SUPERVISOR
+// ============================================================================
+
+/// A running source; aborted when dropped
+struct RunningSource {
+    index: usize,
+    rx: broadcast::Receiver<WsMessage>,
+    /// Source task ended; staleness checks will restart it
+    closed: bool,
+    task: JoinHandle<()>,
+    staleness: Staleness,
+}
+
+impl RunningSource {
+    async fn recv(&mut self) -> Result<WsMessage, RecvError> {
+        if self.closed {
+            return std::future::pending().await;
+        }
+        let result = self.rx.recv().await;
+        self.closed = matches!(result, Err(RecvError::Closed));
+        result
+    }
+}
+
+impl Drop for RunningSource {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Runs sources in priority order, restarting or failing over on staleness
+pub struct FeedSupervisor {
+    state: Arc<AppState>,
+    sources: Vec<Arc<dyn MarketDataAdapter>>,
+    /// Symbols requested from every source
+    subscriptions: Vec<Subscription>,
+    config: FailoverConfig,
+    /// Secondary venue: forward books and trades only, no status reporting
+    secondary: bool,
+}
+
+/// Channels a secondary venue contributes
+const VENUE_CHANNELS: &[FeedChannel] = &[FeedChannel::Trades, FeedChannel::OrderBook];
+
+/// Attribute books and trades to the venue they came from
+fn tag_venue(msg: &mut WsMessage, venue: &str) {
+    match msg {
+        WsMessage::Trade(trade) if trade.venue.is_none() => trade.venue = Some(venue.to_string()),
+        WsMessage::OrderBook(book) if book.venue.is_none() => book.venue = Some(venue.to_string()),
+        _ => {}
+    }
+}
+
+impl FeedSupervisor {
+    /// `sources` in priority order; the first is the primary
+    pub fn new(state: Arc<AppState>, sources: Vec<Arc<dyn MarketDataAdapter>>) -> Self {
+        assert!(!sources.is_empty(), "feed supervisor needs at least one source");
+        Self {
+            state,
+            sources,
+            subscriptions: vec![Subscription::new("BTC-USD")],
+            config: FailoverConfig::default(),
+            secondary: false,
+        }
+    }
+
+    /// Supervise an extra venue feeding cross-venue consolidation
+    pub fn secondary(mut self) -> Self {
+        self.secondary = true;
+        self
+    }
+
+    fn channels(&self) -> &'static [FeedChannel] {
+        if self.secondary { VENUE_CHANNELS } else { FeedChannel::all() }
+    }
+
+    pub fn with_config(mut self, config: FailoverConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn with_subscriptions(mut self, subscriptions: Vec<Subscription>) -> Self {
+        self.subscriptions = subscriptions;
+        self
+    }
+
+    fn start(&self, index: usize) -> RunningSource {
+        let source = &self.sources[index];
+        let (tx, rx) = broadcast::channel(1024);
+        tracing::info!("Starting upstream source {}", source.name());
+        RunningSource {
+            index,
+            rx,
+            closed: false,
+            task: tokio::spawn(run_adapter(source.clone(), self.subscriptions.clone(), tx)),
+            staleness: Staleness::new(Instant::now()),
+        }
+    }
+
+    fn publish_status(&self, active: &RunningSource, status: FeedStatus, stale: Vec<FeedChannel>) {
+        if self.secondary {
+            return;
+        }
+        let name = self.sources[active.index].name().to_string();
+        let msg = SourceStatus {
+            source: name,
+            status,
+            stale_channels: stale,
+            failover: active.index > 0,
+            timestamp: Utc::now().timestamp_millis(),
+        };
+        if msg.is_degraded() {
+            tracing::warn!("Upstream degraded: {:?}", msg);
+        } else {
+            tracing::info!("Upstream {} healthy", msg.source);
+        }
+        let _ = self.state.tx.send(WsMessage::SourceStatus(msg));
+    }
+
+    fn set_health_adapter(&self, index: usize) {
+        if self.secondary {
+            return;
+        }
+        self.state
+            .health
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .set_adapter(self.sources[index].name());
+    }
+
+    /// Supervise forever
+    pub async fn run(self) {
+        let mut active = self.start(0);
+        let mut probe: Option<RunningSource> = None;
+        let mut reconnects = 0;
+        let mut failed_over_at: Option<Instant> = None;
+        let mut last_reported: Option<(usize, FeedStatus, Vec<FeedChannel>)> = None;
+        let mut check = tokio::time::interval(self.config.check_interval);
+        let mut switch = self.state.source_switch.subscribe();
+        self.set_health_adapter(0);
+
+        loop {
+            tokio::select! {
+                msg = active.recv() => match msg {
+                    Ok(mut msg) => {
+                        active.staleness.record(&msg, Instant::now());
+                        if self.secondary && !matches!(msg, WsMessage::Trade(_) | WsMessage::OrderBook(_)) {
+                            continue;
+                        }
+                        tag_venue(&mut msg, self.sources[active.index].name());
+                        let _ = self.state.tx.send(msg);
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Feed supervisor lagged, skipped {} messages", skipped);
+                    }
+                    Err(RecvError::Closed) => {
+                        tracing::warn!("{} disconnected", self.sources[active.index].name());
+                    }
+                },
+
+                Ok(()) = switch.changed(), if !self.secondary => {
+                    let requested = switch.borrow_and_update().clone();
+                    let Some(index) = requested
+                        .and_then(|name| self.sources.iter().position(|s| s.name() == name))
+                    else {
+                        continue;
+                    };
+                    tracing::info!("Switching upstream to {} on request", self.sources[index].name());
+                    reconnects = 0;
+                    probe = None;
+                    // No failback from a source the operator chose
+                    failed_over_at = None;
+                    active = self.start(index);
+                    self.set_health_adapter(index);
+                }
+
+                msg = recv_probe(&mut probe) => {
+                    if let (Some(p), Some(msg)) = (probe.as_mut(), msg) {
+                        p.staleness.record(&msg, Instant::now());
+                    }
+                }
+
+                _ = check.tick() => {
+                    let now = Instant::now();
+                    let channels = self.channels();
+                    let stale = active.staleness.stale_channels(channels, now, &self.config);
+                    let status = FeedStatus::from_fresh(
+                        active.staleness.fresh_count(channels, now, &self.config),
+                        channels.len(),
+                    );
+
+                    // Report transitions (source, status or stale set changed),
+                    // staying quiet while a fresh source is still warming up
+                    let settled = status == FeedStatus::Up || !stale.is_empty();
+                    let report = (active.index, status, stale.clone());
+                    if settled && last_reported.as_ref() != Some(&report) {
+                        self.publish_status(&active, status, stale.clone());
+                        last_reported = Some(report);
+                    }
+
+                    // Primary is back: switch over without a gap
+                    if probe.as_ref().is_some_and(|p| p.staleness.complete(channels)) {
+                        active = probe.take().unwrap();
+                        reconnects = 0;
+                        failed_over_at = None;
+                        self.set_health_adapter(active.index);
+                        tracing::info!("Primary source recovered, failing back");
+                        continue;
+                    }
+
+                    // Probe had its chance; try again later
+                    if probe.as_ref().is_some_and(|p| {
+                        now.saturating_duration_since(p.staleness.started) > self.config.max_threshold()
+                    }) {
+                        probe = None;
+                        failed_over_at = Some(now);
+                    }
+
+                    if failed_over_at.is_some_and(|at| now.saturating_duration_since(at) >= self.config.failback_after)
+                        && probe.is_none()
+                    {
+                        probe = Some(self.start(0));
+                        failed_over_at = None;
+                    }
+
+                    // Still within the start-up grace period, or healthy
+                    if stale.is_empty() {
+                        if status == FeedStatus::Up {
+                            reconnects = 0;
+                        }
+                        continue;
+                    }
+
+                    if reconnects < self.config.max_reconnects {
+                        reconnects += 1;
+                        tracing::warn!(
+                            "{} stale on {:?}, reconnecting ({}/{})",
+                            self.sources[active.index].name(),
+                            stale,
+                            reconnects,
+                            self.config.max_reconnects
+                        );
+                        active = self.start(active.index);
+                    } else {
+                        let next = (active.index + 1) % self.sources.len();
+                        tracing::warn!(
+                            "{} still stale, failing over to {}",
+                            self.sources[active.index].name(),
+                            self.sources[next].name()
+                        );
+                        reconnects = 0;
+                        probe = None;
+                        failed_over_at = (next > 0).then_some(now);
+                        active = self.start(next);
+                        self.set_health_adapter(next);
+                    }
+                }
+            }
+        }
+    }
+}
+
+async fn recv_probe(probe: &mut Option<RunningSource>) -> Option<WsMessage> {
+    match probe {
+        Some(p) => p.recv().await.ok(),
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use dash_adapters::{AdapterResult, AdapterSession, MockAdapter};
+    use dash_core::AdapterHealth;
+    use futures::future::BoxFuture;
+
+    /// Source that never connects (a stalled upstream)
+    struct Silent;
+
+    impl MarketDataAdapter for Silent {
+        fn name(&self) -> &str {
+            "silent"
+        }
+
+        fn connect(&self) -> BoxFuture<'static, AdapterResult<Box<dyn AdapterSession>>> {
+            Box::pin(std::future::pending())
+        }
+
+        fn health(&self) -> AdapterHealth {
+            AdapterHealth::new("silent", Vec::new())
+        }
+    }
+
+    fn fast_config() -> FailoverConfig {
+        FailoverConfig {
+            stale_after: FeedChannel::all().iter().map(|c| (*c, Duration::from_millis(50))).collect(),
+            max_reconnects: 1,
+            check_interval: Duration::from_millis(10),
+            failback_after: Duration::from_secs(60),
+        }
+    }
+
+    #[test]
+    fn test_symbol_info_lists_venue_instruments() {
+        let adapters: Vec<Arc<dyn MarketDataAdapter>> = vec![
+            Arc::new(dash_adapters::BinanceAdapter::new()),
+            Arc::new(MockAdapter::new()),
+        ];
+        let info = symbol_info(&[Subscription::new("ETH-USD")], &adapters);
+        assert_eq!(info.len(), 1);
+        assert_eq!(info[0].quote, "USD");
+        assert_eq!(info[0].instrument("binance"), Some("ETHUSDT"));
+        assert_eq!(info[0].instrument("mock"), Some("ETH-USD"));
+    }
+
+    #[test]
+    fn test_staleness_grace_and_thresholds() {
+        let config = fast_config();
+        let start = Instant::now();
+        let mut staleness = Staleness::new(start);
+        let all = FeedChannel::all();
+        assert!(staleness.stale_channels(all, start, &config).is_empty());
+        assert_eq!(staleness.fresh_count(all, start, &config), 0);
+
+        let msg = WsMessage::Heartbeat { timestamp: 0 };
+        staleness.record(&msg, start);
+        assert!(!staleness.complete(all));
+
+        let later = start + Duration::from_millis(100);
+        assert_eq!(staleness.stale_channels(all, later, &config).len(), all.len());
+        assert_eq!(staleness.stale_channels(VENUE_CHANNELS, later, &config).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_fails_over_to_secondary() {
+        let state = Arc::new(AppState::new());
+        let mut rx = state.tx.subscribe();
+        let supervisor = FeedSupervisor::new(
+            state.clone(),
+            vec![Arc::new(Silent), Arc::new(MockAdapter::new())],
+        )
+        .with_config(fast_config());
+        let handle = tokio::spawn(supervisor.run());
+
+        let status = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                if let Ok(WsMessage::SourceStatus(status)) = rx.recv().await
+                    && status.source == "mock"
+                    && status.status == FeedStatus::Up
+                {
+                    return status;
+                }
+            }
+        })
+        .await
+        .expect("no failover");
+
+        assert!(status.failover);
+        assert!(status.is_degraded());
+        handle.abort();
+    }
+
+    #[tokio::test]
+    async fn test_switches_source_on_request() {
+        let state = Arc::new(AppState::new());
+        let mut rx = state.tx.subscribe();
+        let supervisor = FeedSupervisor::new(
+            state.clone(),
+            vec![Arc::new(MockAdapter::named("first")), Arc::new(MockAdapter::named("second"))],
+        );
+        let handle = tokio::spawn(supervisor.run());
+
+        let mut switched = false;
+        let status = tokio::time::timeout(Duration::from_secs(10), async {
+            loop {
+                let Ok(WsMessage::SourceStatus(status)) = rx.recv().await else { continue };
+                if status.status != FeedStatus::Up {
+                    continue;
+                }
+                if status.source == "second" {
+                    return status;
+                }
+                if !switched {
+                    state.source_switch.send_replace(Some("second".to_string()));
+                    switched = true;
+                }
+            }
+        })
+        .await
+        .expect("no switch");
+
+        assert!(status.failover);
+        let report = state.health.read().unwrap().report(std::time::Instant::now());
+        assert_eq!(report.adapters[0].name, "second");
+        handle.abort();
+    }
+}
@@ -0,0 +1,127 @@
+//! Per-connection stream filters negotiated through `subscribe` commands
+//!
+//! Constrained clients (mobile widgets) can ask for less than the full feed:
+//! only trades above a notional value, or only the top N book levels. Filters
+//! are kept per symbol and applied to the shared broadcast before a frame is
+//! encoded for the connection. Symbols without filters pass through unchanged.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use dash_core::{ClientCommand, Subscription, Symbol, WsMessage};
+
+/// Fewest book levels a client may request
+const MIN_DEPTH_LEVELS: usize = 1;
+
+/// Filters requested by one WebSocket connection
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionFilters {
+    subscriptions: HashMap<Symbol, Subscription>,
+}
+
+impl ConnectionFilters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Track subscribe/unsubscribe commands; returns whether filters changed
+    pub fn apply(&mut self, cmd: &ClientCommand) -> bool {
+        match cmd {
+            ClientCommand::Subscribe(sub) => {
+                let previous = self.subscriptions.insert(sub.symbol.clone(), sub.clone());
+                previous.as_ref() != Some(sub)
+            }
+            ClientCommand::Unsubscribe { symbol } => self.subscriptions.remove(symbol).is_some(),
+            _ => false,
+        }
+    }
+
+    /// The message as this connection should see it; `None` drops it
+    pub fn filter<'a>(&self, msg: &'a WsMessage) -> Option<Cow<'a, WsMessage>> {
+        let symbol = match msg {
+            WsMessage::Trade(trade) => &trade.symbol,
+            WsMessage::OrderBook(book) => &book.symbol,
+            WsMessage::Depth(depth) => &depth.symbol,
+            WsMessage::ConsolidatedBook(book) => &book.symbol,
+            _ => return Some(Cow::Borrowed(msg)),
+        };
+        let Some(sub) = self.subscriptions.get(symbol) else {
+            return Some(Cow::Borrowed(msg));
+        };
+
+        match msg {
+            WsMessage::Trade(trade) => match sub.min_trade_value {
+                Some(min) if trade.value() < min => None,
+                _ => Some(Cow::Borrowed(msg)),
+            },
+            _ => match sub.depth_levels.map(|levels| levels.max(MIN_DEPTH_LEVELS)) {
+                Some(levels) if book_levels(msg) > levels => Some(Cow::Owned(truncate(msg, levels))),
+                _ => Some(Cow::Borrowed(msg)),
+            },
+        }
+    }
+}
+
+/// Levels on the deeper side of a book-like message
+fn book_levels(msg: &WsMessage) -> usize {
+    match msg {
+        WsMessage::OrderBook(book) => book.bids.len().max(book.asks.len()),
+        WsMessage::Depth(depth) => depth.bid_depth.len().max(depth.ask_depth.len()),
+        WsMessage::ConsolidatedBook(book) => book.bids.len().max(book.asks.len()),
+        _ => 0,
+    }
+}
+
+/// Copy of a book-like message cut to `levels` per side
+fn truncate(msg: &WsMessage, levels: usize) -> WsMessage {
+    let mut msg = msg.clone();
+    match &mut msg {
+        WsMessage::OrderBook(book) => book.truncate(levels),
+        WsMessage::Depth(depth) => depth.truncate(levels),
+        WsMessage::ConsolidatedBook(book) => book.truncate(levels),
+        _ => {}
+    }
+    msg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dash_core::{OrderBookLevel, OrderBookSnapshot, Trade, TradeSide};
+
+    fn book(symbol: &str) -> WsMessage {
+        let mut book = OrderBookSnapshot::new(Symbol::new(symbol));
+        book.bids = (0..20).map(|i| OrderBookLevel::new(100.0 - i as f64, 1.0, 1)).collect();
+        book.asks = (0..20).map(|i| OrderBookLevel::new(101.0 + i as f64, 1.0, 1)).collect();
+        WsMessage::OrderBook(book)
+    }
+
+    #[test]
+    fn test_filters_per_symbol() {
+        let mut filters = ConnectionFilters::new();
+        let sub = Subscription::new("BTC-USD").depth_levels(10).min_trade_value(10_000.0);
+        assert!(filters.apply(&ClientCommand::Subscribe(sub.clone())));
+        assert!(!filters.apply(&ClientCommand::Subscribe(sub)));
+
+        let small = WsMessage::Trade(Trade::new(Symbol::new("BTC-USD"), 100.0, 1.0, TradeSide::Buy));
+        let whale = WsMessage::Trade(Trade::new(Symbol::new("BTC-USD"), 100.0, 150.0, TradeSide::Buy));
+        assert!(filters.filter(&small).is_none());
+        assert!(matches!(filters.filter(&whale), Some(Cow::Borrowed(_))));
+
+        match filters.filter(&book("BTC-USD")).as_deref() {
+            Some(WsMessage::OrderBook(book)) => assert_eq!((book.bids.len(), book.asks.len()), (10, 10)),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        // Other symbols, books already within the limit and non-book
+        // messages are untouched
+        assert!(matches!(filters.filter(&book("ETH-USD")), Some(Cow::Borrowed(_))));
+        filters.apply(&ClientCommand::Subscribe(Subscription::new("ETH-USD").depth_levels(50)));
+        assert!(matches!(filters.filter(&book("ETH-USD")), Some(Cow::Borrowed(_))));
+        let heartbeat = WsMessage::Heartbeat { timestamp: 1 };
+        assert!(matches!(filters.filter(&heartbeat), Some(Cow::Borrowed(_))));
+
+        assert!(filters.apply(&ClientCommand::unsubscribe("BTC-USD")));
+        assert!(filters.filter(&small).is_some());
+    }
+}
@@ -0,0 +1,311 @@
+//! gRPC streaming API for non-browser consumers (bots, recorders)
+//!
+//! Serves `dash.market.v1.MarketData` (see `proto/market.proto`) from the
+//! same broadcast channel that feeds WebSocket clients. Each client IP may
+//! hold a bounded number of open streams, as with WebSocket connections.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::pin::Pin;
+use std::sync::Arc;
+
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream};
+use tokio_stream::{Stream, StreamExt};
+use tonic::{Request, Response, Status};
+
+use crate::limits::ConnectionGuard;
+use crate::AppState;
+use dash_core::{CandleInterval, TradeSide, WsMessage};
+
+/// Generated protobuf types and service
+pub mod pb {
+    tonic::include_proto!("dash.market.v1");
+}
+
+use pb::market_data_server::{MarketData, MarketDataServer};
+
+type MessageStream<T> = Pin<Box<dyn Stream<Item = Result<T, Status>> + Send>>;
+
+// ============================================================================
+// CONVERSIONS (dash-core -> protobuf)
+// ============================================================================
+
+impl From<&dash_core::Trade> for pb::Trade {
+    fn from(trade: &dash_core::Trade) -> Self {
+        Self {
+            id: trade.id.clone(),
+            symbol: trade.symbol.as_str().to_string(),
+            price: trade.price.as_f64(),
+            quantity: trade.quantity.as_f64(),
+            side: match trade.side {
+                TradeSide::Buy => pb::TradeSide::Buy,
+                TradeSide::Sell => pb::TradeSide::Sell,
+            } as i32,
+            timestamp: trade.timestamp.timestamp_millis(),
+            maker_order_id: trade.maker_order_id.clone(),
+            taker_order_id: trade.taker_order_id.clone(),
+            venue: trade.venue.clone().unwrap_or_default(),
+        }
+    }
+}
+
+impl From<&dash_core::OrderBookLevel> for pb::OrderBookLevel {
+    fn from(level: &dash_core::OrderBookLevel) -> Self {
+        Self {
+            price: level.price.as_f64(),
+            quantity: level.quantity.as_f64(),
+            order_count: level.order_count,
+        }
+    }
+}
+
+impl From<&dash_core::OrderBookSnapshot> for pb::OrderBook {
+    fn from(book: &dash_core::OrderBookSnapshot) -> Self {
+        Self {
+            symbol: book.symbol.as_str().to_string(),
+            bids: book.bids.iter().map(Into::into).collect(),
+            asks: book.asks.iter().map(Into::into).collect(),
+            timestamp: book.timestamp,
+            sequence: book.sequence,
+            venue: book.venue.clone().unwrap_or_default(),
+        }
+    }
+}
+
+impl From<&dash_core::Candle> for pb::Candle {
+    fn from(candle: &dash_core::Candle) -> Self {
+        Self {
+            symbol: candle.symbol.as_str().to_string(),
+            interval: candle.interval.code().to_string(),
+            timestamp: candle.timestamp,
+            open: candle.open.as_f64(),
+            high: candle.high.as_f64(),
+            low: candle.low.as_f64(),
+            close: candle.close.as_f64(),
+            volume: candle.volume.as_f64(),
+            quote_volume: candle.quote_volume,
+            trade_count: candle.trade_count,
+            is_closed: candle.is_closed,
+        }
+    }
+}
+
+// ============================================================================
+// SERVICE
+// ============================================================================
+
+/// Symbol filter from a request (empty = all symbols)
+fn symbol_filter(symbol: &str) -> Option<String> {
+    let symbol = symbol.trim();
+    (!symbol.is_empty()).then(|| symbol.to_ascii_uppercase())
+}
+
+fn matches_symbol(filter: &Option<String>, symbol: &dash_core::Symbol) -> bool {
+    filter.as_deref().is_none_or(|f| f == symbol.as_str())
+}
+
+/// Stream broadcast messages selected and converted by `select`, holding
+/// the client's stream slot until it ends.
+/// Slow consumers skip what they missed instead of being disconnected.
+fn broadcast_stream<T, F>(
+    rx: broadcast::Receiver<WsMessage>,
+    guard: ConnectionGuard,
+    select: F,
+) -> MessageStream<T>
+where
+    T: Send + 'static,
+    F: Fn(&WsMessage) -> Option<T> + Send + 'static,
+{
+    let stream = BroadcastStream::new(rx).filter_map(move |msg| {
+        let _slot = &guard;
+        match msg {
+            Ok(msg) => select(&msg).map(Ok),
+            Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                tracing::warn!("gRPC stream lagged, skipped {} messages", skipped);
+                None
+            }
+        }
+    });
+    Box::pin(stream)
+}
+
+/// `MarketData` service backed by the server's broadcast channel
+pub struct MarketDataService {
+    state: Arc<AppState>,
+}
+
+impl MarketDataService {
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// Wrap in the generated tonic server
+    pub fn into_server(self) -> MarketDataServer<Self> {
+        MarketDataServer::new(self)
+    }
+
+    /// Take a stream slot for the client behind `request`
+    fn admit<T>(&self, request: &Request<T>) -> Result<ConnectionGuard, Status> {
+        let peer = request
+            .remote_addr()
+            .unwrap_or_else(|| SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0));
+        let headers = request.metadata().clone().into_headers();
+        let limits = &self.state.limits;
+        limits.admit_grpc(limits.client_ip(peer, &headers)).ok_or_else(|| {
+            let max = limits.config.max_grpc_streams_per_ip;
+            Status::resource_exhausted(format!("At most {} concurrent streams per client", max))
+        })
+    }
+}
+
+#[tonic::async_trait]
+impl MarketData for MarketDataService {
+    type StreamTradesStream = MessageStream<pb::Trade>;
+    type StreamOrderBookStream = MessageStream<pb::OrderBook>;
+    type StreamCandlesStream = MessageStream<pb::Candle>;
+
+    async fn stream_trades(
+        &self,
+        request: Request<pb::StreamRequest>,
+    ) -> Result<Response<Self::StreamTradesStream>, Status> {
+        let guard = self.admit(&request)?;
+        let filter = symbol_filter(&request.get_ref().symbol);
+        tracing::info!("gRPC trade stream opened ({})", filter.as_deref().unwrap_or("all"));
+
+        let rx = self.state.tx.subscribe();
+        Ok(Response::new(broadcast_stream(rx, guard, move |msg| match msg {
+            WsMessage::Trade(trade) if matches_symbol(&filter, &trade.symbol) => Some(trade.into()),
+            _ => None,
+        })))
+    }
+
+    async fn stream_order_book(
+        &self,
+        request: Request<pb::StreamRequest>,
+    ) -> Result<Response<Self::StreamOrderBookStream>, Status> {
+        let guard = self.admit(&request)?;
+        let filter = symbol_filter(&request.get_ref().symbol);
+        let depth_levels = match request.get_ref().depth_levels {
+            0 => usize::MAX,
+            levels => levels as usize,
+        };
+        tracing::info!("gRPC order book stream opened ({})", filter.as_deref().unwrap_or("all"));
+
+        let rx = self.state.tx.subscribe();
+        Ok(Response::new(broadcast_stream(rx, guard, move |msg| match msg {
+            WsMessage::OrderBook(book) if matches_symbol(&filter, &book.symbol) => {
+                let mut book = pb::OrderBook::from(book);
+                book.bids.truncate(depth_levels);
+                book.asks.truncate(depth_levels);
+                Some(book)
+            }
+            _ => None,
+        })))
+    }
+
+    async fn stream_candles(
+        &self,
+        request: Request<pb::CandleStreamRequest>,
+    ) -> Result<Response<Self::StreamCandlesStream>, Status> {
+        let req = request.get_ref();
+        let filter = symbol_filter(&req.symbol);
+        let interval = match req.interval.trim() {
+            "" => None,
+            code => Some(
+                CandleInterval::from_code(code)
+                    .ok_or_else(|| Status::invalid_argument(format!("unknown interval: {}", code)))?,
+            ),
+        };
+        let guard = self.admit(&request)?;
+        tracing::info!("gRPC candle stream opened ({})", filter.as_deref().unwrap_or("all"));
+
+        let rx = self.state.tx.subscribe();
+        Ok(Response::new(broadcast_stream(rx, guard, move |msg| match msg {
+            WsMessage::Candle(candle)
+                if matches_symbol(&filter, &candle.symbol)
+                    && interval.is_none_or(|i| i == candle.interval) =>
+            {
+                Some(candle.into())
+            }
+            _ => None,
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dash_core::{Candle, Symbol, Trade};
+
+    #[tokio::test]
+    async fn test_trade_stream_filters_symbol() {
+        let state = Arc::new(AppState::new());
+        let service = MarketDataService::new(state.clone());
+
+        let request = Request::new(pb::StreamRequest {
+            symbol: "btc-usd".into(),
+            depth_levels: 0,
+        });
+        let mut stream = service.stream_trades(request).await.unwrap().into_inner();
+
+        let eth = Trade::new(Symbol::new("ETH-USD"), 3_000.0, 1.0, TradeSide::Sell);
+        let btc = Trade::new(Symbol::new("BTC-USD"), 50_000.0, 0.5, TradeSide::Buy);
+        state.tx.send(WsMessage::Trade(eth)).unwrap();
+        state.tx.send(WsMessage::Trade(btc.clone())).unwrap();
+
+        let received = stream.next().await.unwrap().unwrap();
+        assert_eq!(received.id, btc.id);
+        assert_eq!(received.side(), pb::TradeSide::Buy);
+    }
+
+    #[tokio::test]
+    async fn test_order_book_stream_truncates_levels() {
+        let state = Arc::new(AppState::new());
+        let service = MarketDataService::new(state.clone());
+
+        let request = Request::new(pb::StreamRequest {
+            symbol: String::new(),
+            depth_levels: 5,
+        });
+        let mut stream = service.stream_order_book(request).await.unwrap().into_inner();
+
+        let mut book = dash_core::OrderBookSnapshot::new(Symbol::new("BTC-USD"));
+        book.bids = (0..20).map(|i| dash_core::OrderBookLevel::new(100.0 - i as f64, 1.0, 1)).collect();
+        state.tx.send(WsMessage::OrderBook(book)).unwrap();
+
+        let received = stream.next().await.unwrap().unwrap();
+        assert_eq!(received.bids.len(), 5);
+        assert_eq!(received.bids[0].price, 100.0);
+    }
+
+    #[tokio::test]
+    async fn test_streams_per_client_are_limited() {
+        let config =
+            crate::limits::LimitsConfig { max_grpc_streams_per_ip: 2, ..Default::default() };
+        let service = MarketDataService::new(Arc::new(AppState::new().with_limits(config)));
+        let request = || Request::new(pb::StreamRequest { symbol: String::new(), depth_levels: 0 });
+
+        let first = service.stream_trades(request()).await.unwrap();
+        let _second = service.stream_order_book(request()).await.unwrap();
+        let status = service.stream_trades(request()).await.err().unwrap();
+        assert_eq!(status.code(), tonic::Code::ResourceExhausted);
+
+        // Ending a stream frees its slot
+        drop(first);
+        assert!(service.stream_trades(request()).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_candle_stream_rejects_unknown_interval() {
+        let service = MarketDataService::new(Arc::new(AppState::new()));
+        let request = Request::new(pb::CandleStreamRequest {
+            symbol: String::new(),
+            interval: "7x".into(),
+        });
+        let status = service.stream_candles(request).await.err().unwrap();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+        let candle = Candle::new(Symbol::new("BTC-USD"), CandleInterval::H1, 0, 1.0);
+        assert_eq!(pb::Candle::from(&candle).interval, "1h");
+    }
+}
@@ -0,0 +1,156 @@
+//! Liveness and readiness endpoints
+//!
+//! Tracks when each market data channel last produced a message so
+//! orchestration (and the dashboard footer) can tell a stalled upstream
+//! from a healthy one.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::AppState;
+use dash_core::{AdapterHealth, ChannelHealth, FeedChannel, HealthReport, SourceStatus, WsMessage};
+
+/// Last message time per channel for the upstream feed
+#[derive(Debug)]
+pub struct HealthMonitor {
+    started: Instant,
+    adapter: String,
+    last_message: HashMap<FeedChannel, Instant>,
+    /// Last source change announced to clients
+    source_status: Option<SourceStatus>,
+}
+
+impl HealthMonitor {
+    pub fn new(adapter: impl Into<String>) -> Self {
+        Self {
+            started: Instant::now(),
+            adapter: adapter.into(),
+            last_message: HashMap::new(),
+            source_status: None,
+        }
+    }
+
+    /// Switch to a different upstream adapter, forgetting the old one's timings
+    pub fn set_adapter(&mut self, adapter: &str) {
+        if self.adapter != adapter {
+            self.adapter = adapter.to_string();
+            self.last_message.clear();
+        }
+    }
+
+    pub fn record(&mut self, msg: &WsMessage, now: Instant) {
+        // Secondary venues don't keep the primary adapter alive
+        if msg.venue().is_some_and(|venue| venue != self.adapter) {
+            return;
+        }
+        if let Some(channel) = FeedChannel::for_message(msg) {
+            self.last_message.insert(channel, now);
+        } else if let WsMessage::SourceStatus(status) = msg {
+            self.source_status = Some(status.clone());
+        }
+    }
+
+    pub fn source_status(&self) -> Option<&SourceStatus> {
+        self.source_status.as_ref()
+    }
+
+    pub fn report(&self, now: Instant) -> HealthReport {
+        let channels = FeedChannel::all()
+            .iter()
+            .map(|channel| {
+                let age = self
+                    .last_message
+                    .get(channel)
+                    .map(|at| now.saturating_duration_since(*at).as_millis() as u64);
+                ChannelHealth::new(*channel, age)
+            })
+            .collect();
+
+        HealthReport::new(
+            now.saturating_duration_since(self.started).as_secs(),
+            vec![AdapterHealth::new(self.adapter.clone(), channels)],
+        )
+    }
+}
+
+/// Record every broadcast message against its channel
+pub async fn run_health_monitor(state: Arc<AppState>) {
+    let mut rx = state.tx.subscribe();
+
+    loop {
+        match rx.recv().await {
+            Ok(msg) => write_monitor(&state).record(&msg, Instant::now()),
+            Err(RecvError::Lagged(skipped)) => {
+                tracing::warn!("Health monitor lagged, skipped {} messages", skipped);
+            }
+            Err(RecvError::Closed) => break,
+        }
+    }
+}
+
+fn write_monitor(state: &AppState) -> std::sync::RwLockWriteGuard<'_, HealthMonitor> {
+    state.health.write().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn current_report(state: &AppState) -> HealthReport {
+    state
+        .health
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .report(Instant::now())
+}
+
+// ============================================================================
+// HANDLERS
+// ============================================================================
+
+/// `GET /healthz` - process is alive; body carries adapter status
+pub async fn healthz_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    Json(current_report(&state))
+}
+
+/// `GET /readyz` - 503 until the upstream feed is delivering data
+pub async fn readyz_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let report = current_report(&state);
+    let status = if report.ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dash_core::{FeedStatus, Symbol, Ticker};
+    use std::time::Duration;
+
+    #[test]
+    fn test_report_tracks_channel_age() {
+        let mut monitor = HealthMonitor::new("mock");
+        let start = Instant::now();
+        assert!(!monitor.report(start).ready);
+
+        monitor.record(&WsMessage::Ticker(Ticker::new(Symbol::new("BTC-USD"), 50_000.0)), start);
+        monitor.record(&WsMessage::Heartbeat { timestamp: 0 }, start);
+
+        let report = monitor.report(start + Duration::from_millis(500));
+        let adapter = &report.adapters[0];
+        assert_eq!(adapter.name, "mock");
+        assert_eq!(adapter.status, FeedStatus::Degraded);
+
+        let ticker = adapter.channels.iter().find(|c| c.channel == FeedChannel::Ticker).unwrap();
+        assert_eq!(ticker.last_message_age_ms, Some(500));
+        assert!(!ticker.stale);
+        assert!(report.ready);
+
+        let later = monitor.report(start + Duration::from_secs(60));
+        assert_eq!(later.status, FeedStatus::Down);
+        assert!(!later.ready);
+    }
+}
@@ -0,0 +1,598 @@
+//! Per-IP connection quotas and REST rate limiting
+//!
+//! Keeps a public demo instance healthy: each client IP gets a bounded number
+//! of concurrent WebSocket connections and gRPC streams and a token bucket
+//! for REST calls.
+//! Rejections are structured JSON 429s and are counted in `/metrics`.
+//!
+//! The client IP is the TCP peer, unless the peer is one of the configured
+//! trusted proxies: then it is read from `Forwarded` or `X-Forwarded-For`,
+//! skipping further trusted hops from the right.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+
+use crate::AppState;
+
+/// Buckets idle this long are dropped
+const IDLE_BUCKET_TTL: Duration = Duration::from_secs(300);
+
+/// Longest retry delay suggested to a rate-limited client
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(3_600);
+
+/// Run idle-bucket cleanup every this many checks
+const CLEANUP_EVERY: u64 = 1_024;
+
+// ============================================================================
+// CONFIGURATION
+// ============================================================================
+
+/// Quotas applied per client IP
+#[derive(Debug, Clone, PartialEq)]
+pub struct LimitsConfig {
+    /// Concurrent WebSocket connections (0 = unlimited)
+    pub max_ws_per_ip: usize,
+    /// Concurrent gRPC streams (0 = unlimited)
+    pub max_grpc_streams_per_ip: usize,
+    /// REST requests allowed in a burst
+    pub rest_burst: u32,
+    /// Sustained REST requests per second (0 = unlimited)
+    pub rest_per_sec: f64,
+    /// Reverse proxies whose forwarding headers name the client
+    pub trusted_proxies: TrustedProxies,
+}
+
+impl Default for LimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_ws_per_ip: 8,
+            max_grpc_streams_per_ip: 8,
+            rest_burst: 30,
+            rest_per_sec: 10.0,
+            trusted_proxies: TrustedProxies::default(),
+        }
+    }
+}
+
+impl LimitsConfig {
+    /// Defaults overridden by `DASH_MAX_WS_PER_IP`,
+    /// `DASH_MAX_GRPC_STREAMS_PER_IP`, `DASH_REST_BURST`, `DASH_REST_RATE` and
+    /// `DASH_TRUSTED_PROXIES`
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            max_ws_per_ip: env_or("DASH_MAX_WS_PER_IP", defaults.max_ws_per_ip),
+            max_grpc_streams_per_ip: env_or(
+                "DASH_MAX_GRPC_STREAMS_PER_IP",
+                defaults.max_grpc_streams_per_ip,
+            ),
+            rest_burst: env_or("DASH_REST_BURST", defaults.rest_burst),
+            rest_per_sec: env_or("DASH_REST_RATE", defaults.rest_per_sec),
+            trusted_proxies: env_or("DASH_TRUSTED_PROXIES", defaults.trusted_proxies),
+        }
+    }
+}
+
+pub(crate) fn env_or<T: std::str::FromStr>(name: &str, default: T) -> T {
+    match std::env::var(name) {
+        Ok(value) => value.trim().parse().unwrap_or_else(|_| {
+            tracing::warn!("Ignoring invalid {}={:?}", name, value);
+            default
+        }),
+        Err(_) => default,
+    }
+}
+
+// ============================================================================
+// CLIENT ADDRESS
+// ============================================================================
+
+/// Addresses and CIDR ranges of trusted reverse proxies, parsed from a
+/// comma-separated list such as `127.0.0.1,10.0.0.0/8`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrustedProxies {
+    /// Network address and prefix length
+    ranges: Vec<(IpAddr, u8)>,
+}
+
+impl TrustedProxies {
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        self.ranges.iter().any(|&(net, prefix)| match (net, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                prefix_matches(&net.octets(), &ip.octets(), prefix)
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                prefix_matches(&net.octets(), &ip.octets(), prefix)
+            }
+            _ => false,
+        })
+    }
+
+    /// The client behind `peer`: `peer` itself unless it is a trusted proxy,
+    /// else the nearest hop in the forwarding headers not trusted either
+    pub fn client_ip(&self, peer: IpAddr, headers: &HeaderMap) -> IpAddr {
+        let mut client = peer;
+        if self.ranges.is_empty() {
+            return client;
+        }
+        for hop in forwarded_hops(headers).into_iter().rev() {
+            match hop {
+                Some(ip) if self.contains(client) => client = ip,
+                _ => break,
+            }
+        }
+        client
+    }
+}
+
+impl FromStr for TrustedProxies {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let ranges = s
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (addr, prefix) = entry.split_once('/').unwrap_or((entry, ""));
+                let addr: IpAddr = addr.parse().map_err(|_| format!("bad address: {}", entry))?;
+                let max = if addr.is_ipv4() { 32 } else { 128 };
+                let prefix = match prefix {
+                    "" => max,
+                    prefix => prefix
+                        .parse()
+                        .ok()
+                        .filter(|p| *p <= max)
+                        .ok_or_else(|| format!("bad prefix: {}", entry))?,
+                };
+                Ok((addr, prefix))
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Self { ranges })
+    }
+}
+
+fn prefix_matches(net: &[u8], ip: &[u8], prefix: u8) -> bool {
+    let (bytes, bits) = (usize::from(prefix / 8), prefix % 8);
+    if net[..bytes] != ip[..bytes] {
+        return false;
+    }
+    bits == 0 || (net[bytes] ^ ip[bytes]) >> (8 - bits) == 0
+}
+
+/// Addresses the proxies recorded, client first; `None` for a hop that is
+/// hidden or unreadable. `Forwarded` wins over `X-Forwarded-For`.
+fn forwarded_hops(headers: &HeaderMap) -> Vec<Option<IpAddr>> {
+    let values = |name| {
+        headers
+            .get_all(name)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .collect::<Vec<_>>()
+    };
+    let forwarded: Vec<_> = values(header::FORWARDED.as_str())
+        .into_iter()
+        .map(|element| {
+            element
+                .split(';')
+                .find_map(|pair| {
+                    let (name, value) = pair.trim().split_once('=')?;
+                    name.eq_ignore_ascii_case("for").then_some(value)
+                })
+                .and_then(parse_hop)
+        })
+        .collect();
+    if !forwarded.is_empty() {
+        return forwarded;
+    }
+    values("x-forwarded-for").into_iter().map(parse_hop).collect()
+}
+
+/// `1.2.3.4`, `1.2.3.4:5678`, `2001:db8::1` or `"[2001:db8::1]:5678"`
+fn parse_hop(hop: &str) -> Option<IpAddr> {
+    let hop = hop.trim().trim_matches('"');
+    hop.parse()
+        .ok()
+        .or_else(|| hop.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+        .or_else(|| hop.strip_prefix('[')?.strip_suffix(']')?.parse().ok())
+}
+
+// ============================================================================
+// REST RATE LIMITING
+// ============================================================================
+
+#[derive(Debug, Clone, Copy)]
+struct TokenBucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Token-bucket limiter keyed by client IP
+#[derive(Debug)]
+pub struct RateLimiter {
+    burst: f64,
+    per_sec: f64,
+    buckets: Mutex<HashMap<IpAddr, TokenBucket>>,
+    checks: AtomicU64,
+}
+
+impl RateLimiter {
+    /// A rate that is not a finite positive number means no limit
+    pub fn new(burst: u32, per_sec: f64) -> Self {
+        Self {
+            burst: burst.max(1) as f64,
+            per_sec: if per_sec.is_finite() { per_sec.max(0.0) } else { 0.0 },
+            buckets: Mutex::new(HashMap::new()),
+            checks: AtomicU64::new(0),
+        }
+    }
+
+    /// Take a token for `ip`, or return how long until one is available
+    pub fn check(&self, ip: IpAddr, now: Instant) -> Result<(), Duration> {
+        if self.per_sec <= 0.0 {
+            return Ok(());
+        }
+
+        let mut buckets = lock(&self.buckets);
+        if self.checks.fetch_add(1, Ordering::Relaxed).is_multiple_of(CLEANUP_EVERY) {
+            buckets.retain(|_, b| now.duration_since(b.updated) < IDLE_BUCKET_TTL);
+        }
+
+        let bucket = buckets.entry(ip).or_insert(TokenBucket {
+            tokens: self.burst,
+            updated: now,
+        });
+        let refill = now.duration_since(bucket.updated).as_secs_f64() * self.per_sec;
+        bucket.tokens = (bucket.tokens + refill).min(self.burst);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            // A tiny rate can put the next token beyond what `Duration` holds
+            let wait = (1.0 - bucket.tokens) / self.per_sec;
+            let wait = Duration::try_from_secs_f64(wait).unwrap_or(MAX_RETRY_AFTER);
+            Err(wait.min(MAX_RETRY_AFTER))
+        }
+    }
+}
+
+// ============================================================================
+// CONNECTION QUOTAS
+// ============================================================================
+
+/// Concurrent connection counts per client IP
+#[derive(Debug)]
+pub struct ConnectionTracker {
+    max_per_ip: usize,
+    active: Mutex<HashMap<IpAddr, usize>>,
+}
+
+impl ConnectionTracker {
+    pub fn new(max_per_ip: usize) -> Self {
+        Self {
+            max_per_ip,
+            active: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Reserve a slot for `ip`; released when the guard drops
+    pub fn try_acquire(self: &Arc<Self>, ip: IpAddr) -> Option<ConnectionGuard> {
+        let mut active = lock(&self.active);
+        let count = active.entry(ip).or_insert(0);
+        if self.max_per_ip > 0 && *count >= self.max_per_ip {
+            return None;
+        }
+        *count += 1;
+        Some(ConnectionGuard {
+            tracker: self.clone(),
+            ip,
+        })
+    }
+
+    pub fn active(&self, ip: IpAddr) -> usize {
+        lock(&self.active).get(&ip).copied().unwrap_or(0)
+    }
+
+    pub fn total(&self) -> usize {
+        lock(&self.active).values().sum()
+    }
+
+    /// Open connections per client IP, most first
+    pub fn clients(&self) -> Vec<(IpAddr, usize)> {
+        let mut clients: Vec<_> = lock(&self.active).iter().map(|(ip, n)| (*ip, *n)).collect();
+        clients.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        clients
+    }
+}
+
+/// Held for the lifetime of a connection
+#[derive(Debug)]
+pub struct ConnectionGuard {
+    tracker: Arc<ConnectionTracker>,
+    ip: IpAddr,
+}
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        let mut active = lock(&self.tracker.active);
+        if let Some(count) = active.get_mut(&self.ip) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                active.remove(&self.ip);
+            }
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+// ============================================================================
+// LIMITS + METRICS
+// ============================================================================
+
+/// Counters exported on `/metrics`
+#[derive(Debug, Default)]
+pub struct LimitMetrics {
+    pub rest_allowed: AtomicU64,
+    pub rest_limited: AtomicU64,
+    pub ws_accepted: AtomicU64,
+    pub ws_rejected: AtomicU64,
+}
+
+/// All quota state shared by the server
+#[derive(Debug)]
+pub struct Limits {
+    pub config: LimitsConfig,
+    pub rest: RateLimiter,
+    pub connections: Arc<ConnectionTracker>,
+    pub grpc_streams: Arc<ConnectionTracker>,
+    pub metrics: LimitMetrics,
+}
+
+impl Limits {
+    pub fn new(config: LimitsConfig) -> Self {
+        Self {
+            rest: RateLimiter::new(config.rest_burst, config.rest_per_sec),
+            connections: Arc::new(ConnectionTracker::new(config.max_ws_per_ip)),
+            grpc_streams: Arc::new(ConnectionTracker::new(config.max_grpc_streams_per_ip)),
+            metrics: LimitMetrics::default(),
+            config,
+        }
+    }
+
+    /// The client a connection from `peer` is for
+    pub fn client_ip(&self, peer: SocketAddr, headers: &HeaderMap) -> IpAddr {
+        self.config.trusted_proxies.client_ip(peer.ip(), headers)
+    }
+
+    /// Admit a WebSocket connection from `ip` (counted in metrics)
+    pub fn admit_ws(&self, ip: IpAddr) -> Result<ConnectionGuard, LimitExceeded> {
+        match self.connections.try_acquire(ip) {
+            Some(guard) => {
+                self.metrics.ws_accepted.fetch_add(1, Ordering::Relaxed);
+                Ok(guard)
+            }
+            None => {
+                self.metrics.ws_rejected.fetch_add(1, Ordering::Relaxed);
+                tracing::warn!("WebSocket quota exceeded for {}", ip);
+                Err(LimitExceeded::connections(self.config.max_ws_per_ip))
+            }
+        }
+    }
+
+    /// Admit a gRPC stream from `ip`
+    pub fn admit_grpc(&self, ip: IpAddr) -> Option<ConnectionGuard> {
+        let guard = self.grpc_streams.try_acquire(ip);
+        if guard.is_none() {
+            tracing::warn!("gRPC stream quota exceeded for {}", ip);
+        }
+        guard
+    }
+
+    /// Prometheus text exposition of the counters
+    pub fn render_metrics(&self) -> String {
+        let m = &self.metrics;
+        let counters = [
+            ("dash_rest_requests_allowed_total", "REST requests admitted", m.rest_allowed.load(Ordering::Relaxed)),
+            ("dash_rest_requests_limited_total", "REST requests rejected with 429", m.rest_limited.load(Ordering::Relaxed)),
+            ("dash_ws_connections_accepted_total", "WebSocket connections admitted", m.ws_accepted.load(Ordering::Relaxed)),
+            ("dash_ws_connections_rejected_total", "WebSocket connections rejected with 429", m.ws_rejected.load(Ordering::Relaxed)),
+        ];
+
+        let mut out = String::new();
+        for (name, help, value) in counters {
+            out.push_str(&format!("# HELP {name} {help}\n# TYPE {name} counter\n{name} {value}\n"));
+        }
+        out.push_str(&format!(
+            "# HELP dash_ws_connections_active Open WebSocket connections\n\
+             # TYPE dash_ws_connections_active gauge\n\
+             dash_ws_connections_active {}\n",
+            self.connections.total()
+        ));
+        out
+    }
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self::new(LimitsConfig::default())
+    }
+}
+
+/// Structured 429 body
+#[derive(Debug, Clone, Serialize)]
+pub struct LimitExceeded {
+    pub error: &'static str,
+    pub message: String,
+    /// Suggested wait before retrying (ms)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_after_ms: Option<u64>,
+}
+
+impl LimitExceeded {
+    fn rate(retry_after: Duration) -> Self {
+        Self {
+            error: "rate_limited",
+            message: "Too many requests; slow down".to_string(),
+            retry_after_ms: Some(retry_after.as_millis() as u64),
+        }
+    }
+
+    fn connections(max: usize) -> Self {
+        Self {
+            error: "connection_limit",
+            message: format!("At most {} concurrent connections per client", max),
+            retry_after_ms: None,
+        }
+    }
+}
+
+impl IntoResponse for LimitExceeded {
+    fn into_response(self) -> Response {
+        let retry_after = self.retry_after_ms.map(|ms| ms.div_ceil(1000).max(1));
+        let mut response = (StatusCode::TOO_MANY_REQUESTS, Json(self)).into_response();
+        if let Some(secs) = retry_after {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(secs));
+        }
+        response
+    }
+}
+
+/// Tower middleware applying the REST rate limit by client IP
+pub async fn rate_limit(
+    State(state): State<Arc<AppState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let limits = &state.limits;
+    let ip = limits.client_ip(addr, request.headers());
+    match limits.rest.check(ip, Instant::now()) {
+        Ok(()) => {
+            limits.metrics.rest_allowed.fetch_add(1, Ordering::Relaxed);
+            next.run(request).await
+        }
+        Err(retry_after) => {
+            limits.metrics.rest_limited.fetch_add(1, Ordering::Relaxed);
+            tracing::debug!("Rate limited {} {}", ip, request.uri().path());
+            LimitExceeded::rate(retry_after).into_response()
+        }
+    }
+}
+
+/// `GET /metrics` - quota counters in Prometheus text format
+pub async fn metrics_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        state.limits.render_metrics(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    const IP: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+    const OTHER: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+
+    #[test]
+    fn test_token_bucket() {
+        let limiter = RateLimiter::new(2, 1.0);
+        let start = Instant::now();
+
+        assert!(limiter.check(IP, start).is_ok());
+        assert!(limiter.check(IP, start).is_ok());
+        let retry = limiter.check(IP, start).unwrap_err();
+        assert!(retry <= Duration::from_secs(1));
+
+        // Other clients have their own bucket; tokens refill over time
+        assert!(limiter.check(OTHER, start).is_ok());
+        assert!(limiter.check(IP, start + Duration::from_secs(1)).is_ok());
+
+        // Degenerate rates neither panic nor suggest waiting forever
+        let slow = RateLimiter::new(1, 1e-300);
+        assert!(slow.check(IP, start).is_ok());
+        assert_eq!(slow.check(IP, start), Err(MAX_RETRY_AFTER));
+        for rate in [f64::NAN, f64::INFINITY, -1.0] {
+            let unlimited = RateLimiter::new(1, rate);
+            assert!((0..3).all(|_| unlimited.check(IP, start).is_ok()));
+        }
+    }
+
+    #[test]
+    fn test_client_ip_behind_proxies() {
+        let proxies: TrustedProxies = "127.0.0.1, 10.0.0.0/8, fd00::/8".parse().unwrap();
+        assert!(proxies.contains(IP) && proxies.contains("fd12::1".parse().unwrap()));
+        assert!(!proxies.contains("11.0.0.1".parse().unwrap()));
+        assert!("10.0.0.0/33".parse::<TrustedProxies>().is_err());
+        assert!("proxy".parse::<TrustedProxies>().is_err());
+
+        let client: IpAddr = "203.0.113.7".parse().unwrap();
+        let headers = |pairs: &[(&'static str, &str)]| {
+            let mut headers = HeaderMap::new();
+            for (name, value) in pairs {
+                headers.append(*name, value.parse().unwrap());
+            }
+            headers
+        };
+        let spoofed = headers(&[("x-forwarded-for", "198.51.100.1, 203.0.113.7, 10.0.0.9")]);
+        // Trusted hops are skipped from the right; the rest is the client's word
+        assert_eq!(proxies.client_ip(IP, &spoofed), client);
+        // Headers from anyone else are ignored
+        assert_eq!(proxies.client_ip(client, &spoofed), client);
+        assert_eq!(TrustedProxies::default().client_ip(IP, &spoofed), IP);
+        assert_eq!(proxies.client_ip(IP, &HeaderMap::new()), IP);
+
+        let forwarded = headers(&[
+            ("forwarded", r#"for="[2001:db8::1]:4711";proto=https"#),
+            ("forwarded", "for=10.0.0.9"),
+            ("x-forwarded-for", "198.51.100.1"),
+        ]);
+        assert_eq!(proxies.client_ip(IP, &forwarded), "2001:db8::1".parse::<IpAddr>().unwrap());
+        // An obfuscated hop ends the walk at the proxy that reported it
+        let hidden = headers(&[("forwarded", "for=_hidden, for=10.0.0.9")]);
+        assert_eq!(proxies.client_ip(IP, &hidden), "10.0.0.9".parse::<IpAddr>().unwrap());
+    }
+
+    #[test]
+    fn test_connection_quota() {
+        let limits = Limits::new(LimitsConfig { max_ws_per_ip: 2, ..Default::default() });
+
+        let first = limits.admit_ws(IP).unwrap();
+        let _second = limits.admit_ws(IP).unwrap();
+        assert_eq!(limits.admit_ws(IP).unwrap_err().error, "connection_limit");
+        assert!(limits.admit_ws(OTHER).is_ok());
+
+        assert_eq!(limits.connections.clients(), vec![(IP, 2)]);
+
+        drop(first);
+        assert_eq!(limits.connections.active(IP), 1);
+        assert!(limits.admit_ws(IP).is_ok());
+
+        let metrics = limits.render_metrics();
+        assert!(metrics.contains("dash_ws_connections_rejected_total 1"));
+        assert!(metrics.contains("dash_ws_connections_accepted_total 4"));
+    }
+}
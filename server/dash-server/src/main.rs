@@ -2,67 +2,359 @@
 //!
 //! Axum-based server providing:
 //! - WebSocket endpoint for real-time market data
+//! - REST snapshot endpoints for the latest book, ticker and trades
+//! - gRPC streaming API for non-browser consumers
+//! - SQLite history storage behind the REST history endpoints
+//! - News and announcements from a JSON file or `POST /api/news`
+//! - User accounts (`/api/auth`) with per-user settings, layouts,
+//!   watchlists and alert rules under `/api/me`
+//! - Viewer and operator roles; operators alone reach `/api/control`
+//! - Admin endpoints (`/api/control`): status, source switching, mock
+//!   scenarios, chaos mode and history recording
+//! - Alert rules pushed or registered under `/api/alerts`, delivered to a
+//!   webhook and to clients
+//! - Economic calendar (`/api/calendar`) for chart markers and countdowns
 //! - Static file serving for the WASM frontend
-//! - Mock data engine for demo mode
+//! - Exchange adapters (`dash-adapters`) with the mock engine as fallback
+//! - Text or JSON log lines (`DASH_LOG_FORMAT`), tagged per WebSocket connection
 
-mod mock;
+mod accounts;
+mod admin;
+mod alerts;
+mod api;
+mod calendar;
+mod chaos;
+mod consolidate;
+mod feed;
+mod filter;
+mod grpc;
+mod health;
+mod limits;
+mod news;
+mod storage;
+mod telemetry;
+mod tls;
 mod ws;
 
 use axum::{
-    routing::get,
+    middleware,
+    routing::{delete, get, post, put},
     Router,
 };
 use std::net::SocketAddr;
-use std::sync::Arc;
-use tokio::sync::broadcast;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, RwLock};
+use tokio::sync::{broadcast, watch};
 use tower_http::{
     cors::{Any, CorsLayer},
-    services::ServeDir,
+    services::{ServeDir, ServeFile},
     trace::TraceLayer,
 };
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use tracing::Instrument;
 
-use dash_core::WsMessage;
+use dash_adapters::{AdapterRegistry, MarketDataAdapter, MockAdapter};
+use dash_core::{ChaosConfig, SymbolInfo, WsMessage};
 
 /// Shared application state
 pub struct AppState {
     /// Broadcast channel for market data
     pub tx: broadcast::Sender<WsMessage>,
+    /// Latest market state for the REST snapshot endpoints
+    pub cache: RwLock<api::MarketCache>,
+    /// Persistent history (None when storage is unavailable)
+    pub storage: Option<Arc<storage::Storage>>,
+    /// Whether broadcast market data is written to storage
+    pub recording: AtomicBool,
+    /// Per-IP quotas and their metrics
+    pub limits: limits::Limits,
+    /// Upstream feed liveness for `/healthz` and `/readyz`
+    pub health: RwLock<health::HealthMonitor>,
+    /// Broadcast messages per channel for the admin page
+    pub throughput: RwLock<admin::Throughput>,
+    /// Upstream chain in failover order
+    pub sources: Vec<String>,
+    /// Source an operator asked the primary feed to switch to
+    pub source_switch: watch::Sender<Option<String>>,
+    /// Mock engine that scenarios are triggered on
+    pub mock: Option<Arc<MockAdapter>>,
+    /// Faults injected into client connections
+    pub chaos: watch::Sender<ChaosConfig>,
+    /// Subscribed symbols and their venue instrument ids for `/api/symbols`
+    pub symbols: Vec<SymbolInfo>,
+    /// Recent news and the posting token
+    pub news: RwLock<news::NewsDesk>,
+    /// Alert rules checked server-side and their webhook
+    pub alerts: RwLock<alerts::AlertDesk>,
+    /// Firings of users' registered rules, for their connections only
+    pub user_alerts: broadcast::Sender<alerts::UserAlert>,
+    /// Session token signing for user accounts
+    pub auth: accounts::Auth,
+    /// Which users are operators
+    pub roles: accounts::Roles,
+    /// Events file for `/api/calendar`; a demo schedule without one
+    pub calendar_file: Option<PathBuf>,
 }
 
 impl AppState {
     pub fn new() -> Self {
         let (tx, _) = broadcast::channel(1024);
-        Self { tx }
+        Self {
+            tx,
+            cache: RwLock::new(api::MarketCache::new()),
+            storage: None,
+            recording: AtomicBool::new(true),
+            limits: limits::Limits::default(),
+            health: RwLock::new(health::HealthMonitor::new("mock")),
+            throughput: RwLock::new(admin::Throughput::default()),
+            sources: Vec::new(),
+            source_switch: watch::Sender::new(None),
+            mock: None,
+            chaos: watch::Sender::new(ChaosConfig::default()),
+            symbols: Vec::new(),
+            news: RwLock::new(news::NewsDesk::default()),
+            alerts: RwLock::new(alerts::AlertDesk::default()),
+            user_alerts: broadcast::Sender::new(64),
+            auth: accounts::Auth::default(),
+            roles: accounts::Roles::default(),
+            calendar_file: None,
+        }
+    }
+
+    /// Replace the default per-IP quotas
+    pub fn with_limits(mut self, config: limits::LimitsConfig) -> Self {
+        self.limits = limits::Limits::new(config);
+        self
+    }
+
+    /// Symbol metadata served by `/api/symbols`
+    pub fn with_symbols(mut self, symbols: Vec<SymbolInfo>) -> Self {
+        self.symbols = symbols;
+        self
+    }
+
+    /// Upstream chain the admin page can switch between
+    pub fn with_sources(mut self, sources: Vec<String>) -> Self {
+        self.sources = sources;
+        self
+    }
+
+    /// Mock engine for the admin page's scenarios
+    pub fn with_mock(mut self, mock: Arc<MockAdapter>) -> Self {
+        self.mock = Some(mock);
+        self
+    }
+
+    /// Start with chaos mode configured
+    pub fn with_chaos(self, config: ChaosConfig) -> Self {
+        self.chaos.send_replace(config);
+        self
+    }
+
+    /// Replace the news desk (posting token)
+    pub fn with_news(mut self, desk: news::NewsDesk) -> Self {
+        self.news = RwLock::new(desk);
+        self
+    }
+
+    /// Replace the alert desk (push token)
+    pub fn with_alerts(mut self, desk: alerts::AlertDesk) -> Self {
+        self.alerts = RwLock::new(desk);
+        self
+    }
+
+    /// Replace the session signing key
+    pub fn with_auth(mut self, auth: accounts::Auth) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    /// Restrict the operator features to some users
+    pub fn with_roles(mut self, roles: accounts::Roles) -> Self {
+        self.roles = roles;
+        self
+    }
+
+    /// Serve `/api/calendar` from a JSON file
+    pub fn with_calendar(mut self, file: Option<PathBuf>) -> Self {
+        self.calendar_file = file;
+        self
+    }
+
+    /// Attach history storage
+    pub fn with_storage(mut self, storage: Option<Arc<storage::Storage>>) -> Self {
+        self.storage = storage;
+        self
+    }
+}
+
+impl Default for AppState {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 #[tokio::main]
 async fn main() {
-    // Initialize tracing
-    tracing_subscriber::registry()
-        .with(tracing_subscriber::EnvFilter::try_from_default_env()
-            .unwrap_or_else(|_| "dash_server=debug,tower_http=debug".into()))
-        .with(tracing_subscriber::fmt::layer())
-        .init();
+    // Text or JSON lines (`DASH_LOG_FORMAT`), filtered by `RUST_LOG`
+    telemetry::init(telemetry::LogFormat::from_env());
+
+    // Upstream sources: the `DASH_ADAPTERS` chain (ending in the mock
+    // engine) plus extra venues for the consolidated book - `DASH_VENUES`
+    // adapters and `DASH_MOCK_VENUES` demo venues tracking the mock market
+    let registry = AdapterRegistry::builtin();
+    let subscriptions = feed::subscriptions_from_env();
+    let mut mock = MockAdapter::new();
+    if let Some(seed) = feed::mock_seed_from_env() {
+        tracing::info!("Mock engine seeded with {}", seed);
+        mock = mock.with_seed(seed);
+    }
+    let mock = Arc::new(mock);
+    let mut sources = feed::adapters_from_env(&registry, "DASH_ADAPTERS", "mock");
+    // The chain's mock is the shared engine that scenarios and the seed reach
+    match sources.iter_mut().find(|source| source.name() == mock.name()) {
+        Some(source) => *source = mock.clone(),
+        None => sources.push(mock.clone()),
+    }
+    let mut venues = feed::adapters_from_env(&registry, "DASH_VENUES", "");
+    for n in 2..=limits::env_or("DASH_MOCK_VENUES", 1usize) {
+        venues.push(Arc::new(mock.venue(format!("mock-{}", n))));
+    }
+    let all: Vec<_> = sources.iter().chain(&venues).cloned().collect();
 
     // Create shared state
-    let state = Arc::new(AppState::new());
+    let news_config = news::NewsConfig::from_env();
+    let state = Arc::new(
+        AppState::new()
+            .with_storage(storage::open_from_env())
+            .with_limits(limits::LimitsConfig::from_env())
+            .with_symbols(feed::symbol_info(&subscriptions, &all))
+            .with_sources(sources.iter().map(|source| source.name().to_string()).collect())
+            .with_mock(mock.clone())
+            .with_chaos(chaos::chaos_from_env())
+            .with_news(news::NewsDesk::new(news_config.token.clone()))
+            .with_alerts(alerts::AlertDesk::new(alerts::alerts_token_from_env()))
+            .with_auth(accounts::Auth::from_env())
+            .with_roles(accounts::Roles::from_env())
+            .with_calendar(calendar::calendar_file_from_env()),
+    );
+    tracing::info!("Client quotas: {:?}", state.limits.config);
+
+    // Keep REST snapshots current (subscribed before the feed starts)
+    tokio::spawn(api::run_cache_updater(state.clone()));
+    tokio::spawn(health::run_health_monitor(state.clone()));
+    tokio::spawn(admin::run_throughput_meter(state.clone()));
+    tokio::spawn(consolidate::run_consolidator(state.clone()));
+    tokio::spawn(ws::run_heartbeat(state.clone()));
+
+    // News from the configured file
+    if let Some(file) = news_config.file.clone() {
+        tokio::spawn(news::run_news_file(state.clone(), file, news_config.poll));
+    }
+
+    // Alert rules pushed by dashboards, checked while they are closed
+    tokio::spawn(alerts::run_alert_evaluator(state.clone()));
+
+    // Persist history
+    if let Some(storage) = state.storage.clone() {
+        tokio::spawn(storage::run_storage_writer(state.clone(), storage));
+    }
+
+    // Supervised upstream feed (restarts stalled sources, fails over down the
+    // chain and finally to the mock engine)
+    let failover = feed::FailoverConfig::from_env();
+    tracing::info!(
+        "Upstream chain: {:?} for {:?}",
+        sources.iter().map(|s| s.name()).collect::<Vec<_>>(),
+        subscriptions.iter().map(|s| s.symbol.as_str()).collect::<Vec<_>>()
+    );
+    let supervisor = feed::FeedSupervisor::new(state.clone(), sources)
+        .with_config(failover.clone())
+        .with_subscriptions(subscriptions.clone());
+    tokio::spawn(supervisor.run().instrument(tracing::info_span!("feed", role = "primary")));
+
+    // Secondary venues, each under its own supervisor
+    for venue in venues {
+        let span = tracing::info_span!("feed", role = "venue", venue = venue.name());
+        let supervisor = feed::FeedSupervisor::new(state.clone(), vec![venue])
+            .with_config(failover.clone())
+            .with_subscriptions(subscriptions.clone())
+            .secondary();
+        tokio::spawn(supervisor.run().instrument(span));
+    }
 
-    // Start mock data engine
-    let mock_tx = state.tx.clone();
+    // gRPC streaming API on its own port, over TLS when the HTTP listener is
+    let grpc_addr = SocketAddr::from(([0, 0, 0, 0], limits::env_or("DASH_GRPC_PORT", 50051)));
+    let grpc_service = grpc::MarketDataService::new(state.clone()).into_server();
+    let tls = tls::TlsPaths::from_env();
+    let mut grpc_server = tonic::transport::Server::builder();
+    if let Some(paths) = &tls {
+        let config = paths.load_grpc().await.unwrap_or_else(|e| {
+            panic!("Failed to load TLS certificate {:?}: {}", paths.cert, e)
+        });
+        grpc_server = grpc_server.tls_config(config).expect("invalid gRPC TLS config");
+    }
     tokio::spawn(async move {
-        mock::run_mock_engine(mock_tx).await;
+        if let Err(e) = grpc_server.add_service(grpc_service).serve(grpc_addr).await {
+            tracing::error!("gRPC server failed: {}", e);
+        }
     });
 
     // Build router
+    // Server controls, operators only
+    let control = Router::new()
+        .route("/api/control/status", get(admin::status_handler))
+        .route("/api/control/source", put(admin::put_source_handler))
+        .route("/api/control/scenario", put(admin::put_scenario_handler))
+        .route("/api/control/chaos", get(admin::chaos_handler).put(admin::put_chaos_handler))
+        .route(
+            "/api/control/recording",
+            get(storage::recording_handler).put(storage::put_recording_handler),
+        )
+        .route_layer(middleware::from_fn_with_state(state.clone(), accounts::require_operator));
+
+    // REST API, rate limited per client IP
+    let api = Router::new()
+        // REST snapshots
+        .route("/api/orderbook", get(api::orderbook_handler))
+        .route("/api/ticker", get(api::ticker_handler))
+        .route("/api/trades", get(api::trades_handler))
+        .route("/api/symbols", get(api::symbols_handler))
+        .route("/api/history/candles", get(api::candle_history_handler))
+        .route("/api/history/trades", get(api::trade_history_handler))
+        .route("/api/history/line", get(api::line_history_handler))
+        .route("/api/news", get(news::news_handler).post(news::post_news_handler))
+        .route("/api/calendar", get(calendar::calendar_handler))
+        .route("/api/alerts", get(alerts::alerts_handler).put(alerts::put_alerts_handler))
+        .route("/api/alerts/rules", get(alerts::rules_handler).post(alerts::post_rule_handler))
+        .route("/api/alerts/rules/:id", delete(alerts::delete_rule_handler))
+        .route("/api/auth/signup", post(accounts::signup_handler))
+        .route("/api/auth/login", post(accounts::login_handler))
+        .route(
+            "/api/me/:kind",
+            get(accounts::document_handler).put(accounts::put_document_handler),
+        )
+        .route("/api/auth/role", get(accounts::role_handler))
+        .merge(control)
+        .route_layer(middleware::from_fn_with_state(state.clone(), limits::rate_limit));
+
     let app = Router::new()
-        // WebSocket endpoint
+        // WebSocket endpoint (per-IP connection quota)
         .route("/ws", get(ws::ws_handler))
-        // Health check
+        .merge(api)
+        // Quota metrics (Prometheus text format)
+        .route("/metrics", get(limits::metrics_handler))
+        // Health checks (`/healthz` liveness, `/readyz` upstream readiness)
         .route("/health", get(|| async { "OK" }))
-        // Static files (WASM frontend)
-        .fallback_service(ServeDir::new("dist").append_index_html_on_directories(true))
+        .route("/healthz", get(health::healthz_handler))
+        .route("/readyz", get(health::readyz_handler))
+        // Static files (WASM frontend); unknown paths serve the app so
+        // client-side routes like /d/BTC-USD/15m can be deep-linked
+        .fallback_service(
+            ServeDir::new("dist")
+                .append_index_html_on_directories(true)
+                .not_found_service(ServeFile::new("dist/index.html")),
+        )
         // Middleware
         .layer(TraceLayer::new_for_http())
         .layer(
@@ -74,11 +366,25 @@ async fn main() {
         .with_state(state);
 
     // Bind and serve
-    let addr = SocketAddr::from(([0, 0, 0, 0], 3001));
-    tracing::info!("🚀 Server starting on http://{}", addr);
-    tracing::info!("   WebSocket: ws://{}/ws", addr);
-    tracing::info!("   Frontend:  http://{}", addr);
+    let addr = SocketAddr::from(([0, 0, 0, 0], limits::env_or("DASH_PORT", 3001)));
+    let (http, ws) = if tls.is_some() { ("https", "wss") } else { ("http", "ws") };
+    tracing::info!("🚀 Server starting on {}://{}", http, addr);
+    tracing::info!("   WebSocket: {}://{}/ws", ws, addr);
+    tracing::info!("   Frontend:  {}://{}", http, addr);
+    tracing::info!("   gRPC:      {}://{}", http, grpc_addr);
 
-    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    // Client addresses are needed for per-IP quotas
+    let service = app.into_make_service_with_connect_info::<SocketAddr>();
+    match tls {
+        Some(paths) => {
+            let config = paths.load().await.unwrap_or_else(|e| {
+                panic!("Failed to load TLS certificate {:?}: {}", paths.cert, e)
+            });
+            axum_server::bind_rustls(addr, config).serve(service).await.unwrap();
+        }
+        None => {
+            let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+            axum::serve(listener, service).await.unwrap();
+        }
+    }
 }
@@ -0,0 +1,288 @@
+//! News and announcements
+//!
+//! Items reach clients as `WsMessage::News` from two places: a JSON file
+//! named by `DASH_NEWS_FILE`, re-read every `DASH_NEWS_POLL_SECS` (items
+//! already seen are skipped), and `POST /api/news`, which needs
+//! `Authorization: Bearer $DASH_NEWS_TOKEN` and is disabled without one.
+//! The latest items are kept so clients connecting later still get them.
+
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    extract::{Query, State},
+    http::{header, HeaderMap, StatusCode},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::limits::env_or;
+use crate::AppState;
+use dash_core::{NewsItem, RingBuffer, Symbol, WsMessage};
+
+/// Items kept for late joiners and `GET /api/news`
+pub const MAX_RECENT_NEWS: usize = 50;
+
+/// Source name for posted items that do not give one
+const DEFAULT_SOURCE: &str = "Announcements";
+
+/// Where news comes from
+#[derive(Debug, Clone, Default)]
+pub struct NewsConfig {
+    /// JSON file holding an array of items
+    pub file: Option<PathBuf>,
+    /// How often the file is re-read
+    pub poll: Duration,
+    /// Bearer token for `POST /api/news`; posting is off without one
+    pub token: Option<String>,
+}
+
+impl NewsConfig {
+    pub fn from_env() -> Self {
+        let non_empty = |name| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        Self {
+            file: non_empty("DASH_NEWS_FILE").map(PathBuf::from),
+            poll: Duration::from_secs(env_or("DASH_NEWS_POLL_SECS", 60u64).max(1)),
+            token: non_empty("DASH_NEWS_TOKEN"),
+        }
+    }
+}
+
+/// An item as posted or listed in the news file; everything but the
+/// headline is optional
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewsPost {
+    /// Dedup key; file items without one are keyed by headline
+    id: Option<String>,
+    headline: String,
+    body: Option<String>,
+    source: Option<String>,
+    url: Option<String>,
+    #[serde(default)]
+    symbols: Vec<String>,
+    timestamp: Option<DateTime<Utc>>,
+}
+
+impl NewsPost {
+    fn into_item(self, id: String) -> NewsItem {
+        let source = self.source.unwrap_or_else(|| DEFAULT_SOURCE.to_string());
+        let mut item = NewsItem::new(source, self.headline.trim()).with_symbols(
+            self.symbols.iter().map(|s| Symbol::new(s.trim().to_ascii_uppercase())).collect(),
+        );
+        item.id = id;
+        item.body = self.body.filter(|b| !b.trim().is_empty());
+        item.url = self.url.filter(|u| !u.trim().is_empty());
+        if let Some(timestamp) = self.timestamp {
+            item.timestamp = timestamp;
+        }
+        item
+    }
+}
+
+/// Recently published news
+#[derive(Debug)]
+pub struct NewsDesk {
+    /// Oldest first
+    recent: RingBuffer<NewsItem>,
+    token: Option<String>,
+}
+
+impl NewsDesk {
+    pub fn new(token: Option<String>) -> Self {
+        Self { recent: RingBuffer::new(MAX_RECENT_NEWS), token }
+    }
+
+    /// Keep `item`; false when an item with its id is already recent
+    pub fn record(&mut self, item: &NewsItem) -> bool {
+        if self.recent.iter().any(|n| n.id == item.id) {
+            return false;
+        }
+        self.recent.push(item.clone());
+        true
+    }
+
+    /// Recent items, oldest first
+    pub fn recent(&self) -> Vec<NewsItem> {
+        self.recent.as_slice().to_vec()
+    }
+
+    /// Whether `headers` carry the posting token
+    fn authorizes(&self, headers: &HeaderMap) -> Option<bool> {
+        let token = self.token.as_deref()?;
+        let given = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "));
+        Some(given == Some(token))
+    }
+}
+
+impl Default for NewsDesk {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+fn desk(state: &AppState) -> std::sync::RwLockWriteGuard<'_, NewsDesk> {
+    state.news.write().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Broadcast `item` unless it was already published; returns whether it was
+pub fn publish(state: &AppState, item: NewsItem) -> bool {
+    if !desk(state).record(&item) {
+        return false;
+    }
+    tracing::info!("News from {}: {}", item.source, item.headline);
+    let _ = state.tx.send(WsMessage::News(item));
+    true
+}
+
+/// Recent items for a client that just connected
+pub fn recent(state: &AppState) -> Vec<NewsItem> {
+    state.news.read().unwrap_or_else(|poisoned| poisoned.into_inner()).recent()
+}
+
+/// Items in the news file, keyed for dedup
+fn parse_news_file(contents: &str) -> serde_json::Result<Vec<NewsItem>> {
+    let posts: Vec<NewsPost> = serde_json::from_str(contents)?;
+    Ok(posts
+        .into_iter()
+        .filter(|post| !post.headline.trim().is_empty())
+        .map(|post| {
+            let id = post.id.clone().unwrap_or_else(|| post.headline.trim().to_string());
+            post.into_item(id)
+        })
+        .collect())
+}
+
+/// Publish items added to the news file, re-reading it every `poll`
+pub async fn run_news_file(state: Arc<AppState>, path: PathBuf, poll: Duration) {
+    tracing::info!("News file: {:?} every {:?}", path, poll);
+    // Ids in the file as last read; items are published once
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut interval = tokio::time::interval(poll);
+    loop {
+        interval.tick().await;
+        let items = match tokio::fs::read_to_string(&path).await {
+            Ok(contents) => parse_news_file(&contents),
+            Err(e) => {
+                tracing::warn!("Failed to read news file {:?}: {}", path, e);
+                continue;
+            }
+        };
+        let items = match items {
+            Ok(items) => items,
+            Err(e) => {
+                tracing::warn!("Invalid news file {:?}: {}", path, e);
+                continue;
+            }
+        };
+        let ids: HashSet<String> = items.iter().map(|item| item.id.clone()).collect();
+        for item in items.into_iter().filter(|item| !seen.contains(&item.id)) {
+            publish(&state, item);
+        }
+        seen = ids;
+    }
+}
+
+// ============================================================================
+// HANDLERS
+// ============================================================================
+
+/// Query parameters for `GET /api/news`
+#[derive(Debug, Default, Deserialize)]
+pub struct NewsParams {
+    /// Only items tagged with this pair (and untagged ones)
+    symbol: Option<String>,
+}
+
+/// `GET /api/news?symbol=` - recent items, newest first
+pub async fn news_handler(
+    Query(params): Query<NewsParams>,
+    State(state): State<Arc<AppState>>,
+) -> Json<Vec<NewsItem>> {
+    let symbol = params.symbol.map(|s| Symbol::new(s.trim().to_ascii_uppercase()));
+    let mut items = recent(&state);
+    items.retain(|item| symbol.as_ref().is_none_or(|s| item.concerns(s)));
+    items.reverse();
+    Json(items)
+}
+
+/// `POST /api/news` - publish an announcement (bearer token required)
+pub async fn post_news_handler(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    Json(post): Json<NewsPost>,
+) -> Result<(StatusCode, Json<NewsItem>), StatusCode> {
+    match desk(&state).authorizes(&headers) {
+        None => return Err(StatusCode::NOT_FOUND),
+        Some(false) => return Err(StatusCode::UNAUTHORIZED),
+        Some(true) => {}
+    }
+    if post.headline.trim().is_empty() {
+        return Err(StatusCode::UNPROCESSABLE_ENTITY);
+    }
+    let id = post.id.clone().unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let item = post.into_item(id);
+    if publish(&state, item.clone()) {
+        Ok((StatusCode::CREATED, Json(item)))
+    } else {
+        Err(StatusCode::CONFLICT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn post(json: &str) -> NewsPost {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_news_desk() {
+        let state = AppState::new().with_news(NewsDesk::new(Some("secret".into())));
+        let mut rx = state.tx.subscribe();
+        let item = post(r#"{"headline":"Listing SOL-USD","symbols":["sol-usd"]}"#)
+            .into_item("a".into());
+        assert_eq!(item.symbols, vec![Symbol::new("SOL-USD")]);
+        assert_eq!(item.source, DEFAULT_SOURCE);
+
+        assert!(publish(&state, item.clone()));
+        assert!(!publish(&state, item));
+        assert!(matches!(rx.try_recv(), Ok(WsMessage::News(n)) if n.id == "a"));
+        assert!(rx.try_recv().is_err());
+        assert_eq!(recent(&state).len(), 1);
+    }
+
+    #[test]
+    fn test_news_token() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(NewsDesk::new(None).authorizes(&headers), None);
+
+        let desk = NewsDesk::new(Some("secret".into()));
+        assert_eq!(desk.authorizes(&headers), Some(false));
+        headers.insert(header::AUTHORIZATION, "Bearer wrong".parse().unwrap());
+        assert_eq!(desk.authorizes(&headers), Some(false));
+        headers.insert(header::AUTHORIZATION, "Bearer secret".parse().unwrap());
+        assert_eq!(desk.authorizes(&headers), Some(true));
+    }
+
+    #[test]
+    fn test_parse_news_file() {
+        let items = parse_news_file(
+            r#"[{"id":"m1","headline":"Maintenance at 02:00 UTC","source":"ops"},
+                {"headline":"  ETH upgrade  ","symbols":["ETH-USD"]},
+                {"headline":" "}]"#,
+        )
+        .unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!((items[0].id.as_str(), items[0].source.as_str()), ("m1", "ops"));
+        // Keyed by headline without an id, so re-reads do not repeat it
+        assert_eq!(items[1].id, "ETH upgrade");
+        assert!(parse_news_file("{}").is_err());
+    }
+}
@@ -0,0 +1,555 @@
+//! Historical candle and trade storage (SQLite)
+//!
+//! Everything broadcast to clients is batched into a SQLite database so
+//! history survives restarts and can be served from the REST history
+//! endpoints. Schema changes are applied as numbered migrations tracked in
+//! `PRAGMA user_version`. Operators can pause recording with
+//! `PUT /api/control/recording`; history queries keep working meanwhile.
+
+use std::path::Path;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use axum::{extract::State, http::StatusCode, Json};
+use chrono::{TimeZone, Utc};
+use rusqlite::{params, Connection, Row};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::AppState;
+use dash_core::{Candle, CandleInterval, Price, Quantity, Symbol, Trade, TradeSide, WsMessage};
+
+/// Environment variable holding the database path (`:memory:` for no persistence)
+pub const DB_PATH_ENV: &str = "DASH_DB_PATH";
+
+/// Database file used when `DASH_DB_PATH` is unset
+pub const DEFAULT_DB_PATH: &str = "dash-history.db";
+
+/// How often buffered market data is written
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Upper bound on rows returned by one history request
+pub const MAX_HISTORY_ROWS: usize = 5_000;
+
+/// Upper bound on source rows read for server-side downsampling
+pub const MAX_DOWNSAMPLE_SOURCE_ROWS: usize = 50_000;
+
+/// Schema migrations, applied in order. Never edit a shipped entry; append a new one.
+const MIGRATIONS: &[&str] = &[
+    // 1: candles and trades
+    "CREATE TABLE candles (
+        symbol       TEXT    NOT NULL,
+        interval     TEXT    NOT NULL,
+        timestamp    INTEGER NOT NULL,
+        open         REAL    NOT NULL,
+        high         REAL    NOT NULL,
+        low          REAL    NOT NULL,
+        close        REAL    NOT NULL,
+        volume       REAL    NOT NULL,
+        quote_volume REAL    NOT NULL,
+        trade_count  INTEGER NOT NULL,
+        is_closed    INTEGER NOT NULL,
+        PRIMARY KEY (symbol, interval, timestamp)
+    ) WITHOUT ROWID;
+    CREATE TABLE trades (
+        id             TEXT    PRIMARY KEY,
+        symbol         TEXT    NOT NULL,
+        price          REAL    NOT NULL,
+        quantity       REAL    NOT NULL,
+        side           TEXT    NOT NULL,
+        timestamp      INTEGER NOT NULL,
+        maker_order_id TEXT,
+        taker_order_id TEXT
+    );
+    CREATE INDEX trades_symbol_time ON trades (symbol, timestamp);",
+    // 2: per-exchange attribution
+    "ALTER TABLE trades ADD COLUMN venue TEXT;",
+    // 3: user accounts and their documents
+    "CREATE TABLE users (
+        id            INTEGER PRIMARY KEY,
+        username      TEXT    NOT NULL UNIQUE COLLATE NOCASE,
+        password_hash TEXT    NOT NULL,
+        created_at    INTEGER NOT NULL
+    );
+    CREATE TABLE user_documents (
+        user_id    INTEGER NOT NULL REFERENCES users (id),
+        kind       TEXT    NOT NULL,
+        body       TEXT    NOT NULL,
+        updated_at INTEGER NOT NULL,
+        PRIMARY KEY (user_id, kind)
+    ) WITHOUT ROWID;",
+];
+
+/// Time window and size for history queries (ms, inclusive)
+#[derive(Debug, Clone, Copy)]
+pub struct HistoryRange {
+    pub from: i64,
+    pub to: i64,
+    pub limit: usize,
+}
+
+impl Default for HistoryRange {
+    fn default() -> Self {
+        Self {
+            from: i64::MIN,
+            to: i64::MAX,
+            limit: MAX_HISTORY_ROWS,
+        }
+    }
+}
+
+/// SQLite-backed history store
+pub struct Storage {
+    conn: Mutex<Connection>,
+}
+
+impl Storage {
+    /// Open (or create) a database file and bring its schema up to date
+    pub fn open(path: impl AsRef<Path>) -> rusqlite::Result<Self> {
+        let conn = Connection::open(path)?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.pragma_update(None, "synchronous", "NORMAL")?;
+        Self::with_connection(conn)
+    }
+
+    /// Database that lives only as long as the process
+    pub fn open_in_memory() -> rusqlite::Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(mut conn: Connection) -> rusqlite::Result<Self> {
+        migrate(&mut conn)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn conn(&self) -> MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Current schema version
+    pub fn schema_version(&self) -> rusqlite::Result<usize> {
+        schema_version(&self.conn())
+    }
+
+    /// Insert trades (duplicates by id are ignored)
+    pub fn insert_trades(&self, trades: &[Trade]) -> rusqlite::Result<usize> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        let mut inserted = 0;
+        {
+            let mut stmt = tx.prepare_cached(
+                "INSERT OR IGNORE INTO trades
+                    (id, symbol, price, quantity, side, timestamp, maker_order_id, taker_order_id, venue)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            )?;
+            for trade in trades {
+                inserted += stmt.execute(params![
+                    trade.id,
+                    trade.symbol.as_str(),
+                    trade.price.as_f64(),
+                    trade.quantity.as_f64(),
+                    side_code(trade.side),
+                    trade.timestamp.timestamp_millis(),
+                    trade.maker_order_id,
+                    trade.taker_order_id,
+                    trade.venue,
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(inserted)
+    }
+
+    /// Insert or replace candles (a forming candle is overwritten as it updates)
+    pub fn upsert_candles(&self, candles: &[Candle]) -> rusqlite::Result<()> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare_cached(
+                "INSERT OR REPLACE INTO candles
+                    (symbol, interval, timestamp, open, high, low, close,
+                     volume, quote_volume, trade_count, is_closed)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            )?;
+            // Placeholder candles from gap filling are client-side only
+            for candle in candles.iter().filter(|c| !c.synthetic) {
+                stmt.execute(params![
+                    candle.symbol.as_str(),
+                    candle.interval.code(),
+                    candle.timestamp,
+                    candle.open.as_f64(),
+                    candle.high.as_f64(),
+                    candle.low.as_f64(),
+                    candle.close.as_f64(),
+                    candle.volume.as_f64(),
+                    candle.quote_volume,
+                    candle.trade_count,
+                    candle.is_closed,
+                ])?;
+            }
+        }
+        tx.commit()
+    }
+
+    /// Candles in range, oldest first (the newest `limit` when truncated)
+    pub fn candles(
+        &self,
+        symbol: &Symbol,
+        interval: CandleInterval,
+        range: HistoryRange,
+    ) -> rusqlite::Result<Vec<Candle>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare_cached(
+            "SELECT symbol, interval, timestamp, open, high, low, close,
+                    volume, quote_volume, trade_count, is_closed
+             FROM candles
+             WHERE symbol = ?1 AND interval = ?2 AND timestamp BETWEEN ?3 AND ?4
+             ORDER BY timestamp DESC
+             LIMIT ?5",
+        )?;
+        let mut candles = stmt
+            .query_map(
+                params![symbol.as_str(), interval.code(), range.from, range.to, limit(range)],
+                candle_from_row,
+            )?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        candles.reverse();
+        Ok(candles)
+    }
+
+    /// Trades in range, newest first
+    pub fn trades(&self, symbol: &Symbol, range: HistoryRange) -> rusqlite::Result<Vec<Trade>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, symbol, price, quantity, side, timestamp, maker_order_id, taker_order_id, venue
+             FROM trades
+             WHERE symbol = ?1 AND timestamp BETWEEN ?2 AND ?3
+             ORDER BY timestamp DESC
+             LIMIT ?4",
+        )?;
+        stmt.query_map(
+            params![symbol.as_str(), range.from, range.to, limit(range)],
+            trade_from_row,
+        )?
+        .collect()
+    }
+
+    /// Create a user, or `None` when the name is taken (names ignore case)
+    pub fn create_user(
+        &self,
+        username: &str,
+        password_hash: &str,
+        now: i64,
+    ) -> rusqlite::Result<Option<i64>> {
+        let conn = self.conn();
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO users (username, password_hash, created_at)
+             VALUES (?1, ?2, ?3)",
+            params![username, password_hash, now],
+        )?;
+        Ok((inserted == 1).then(|| conn.last_insert_rowid()))
+    }
+
+    /// Id, stored name and password hash of a user
+    pub fn user_credentials(
+        &self,
+        username: &str,
+    ) -> rusqlite::Result<Option<(i64, String, String)>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, username, password_hash FROM users WHERE username = ?1",
+        )?;
+        let mut rows = stmt.query_map(params![username], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })?;
+        rows.next().transpose()
+    }
+
+    /// Store a user's document, replacing the previous one of that kind
+    pub fn put_document(
+        &self,
+        user_id: i64,
+        kind: &str,
+        body: &str,
+        now: i64,
+    ) -> rusqlite::Result<()> {
+        self.conn().execute(
+            "INSERT OR REPLACE INTO user_documents (user_id, kind, body, updated_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![user_id, kind, body, now],
+        )?;
+        Ok(())
+    }
+
+    /// A user's stored document of one kind
+    pub fn document(&self, user_id: i64, kind: &str) -> rusqlite::Result<Option<String>> {
+        let conn = self.conn();
+        let mut stmt = conn.prepare_cached(
+            "SELECT body FROM user_documents WHERE user_id = ?1 AND kind = ?2",
+        )?;
+        let mut rows = stmt.query_map(params![user_id, kind], |row| row.get(0))?;
+        rows.next().transpose()
+    }
+}
+
+fn limit(range: HistoryRange) -> i64 {
+    range.limit.min(MAX_DOWNSAMPLE_SOURCE_ROWS) as i64
+}
+
+fn schema_version(conn: &Connection) -> rusqlite::Result<usize> {
+    conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+}
+
+/// Apply pending migrations, each in its own transaction
+fn migrate(conn: &mut Connection) -> rusqlite::Result<()> {
+    let current = schema_version(conn)?;
+    for (index, sql) in MIGRATIONS.iter().enumerate().skip(current) {
+        let version = index + 1;
+        let tx = conn.transaction()?;
+        tx.execute_batch(sql)?;
+        tx.pragma_update(None, "user_version", version)?;
+        tx.commit()?;
+        tracing::info!("Applied storage migration {}", version);
+    }
+    Ok(())
+}
+
+fn side_code(side: TradeSide) -> &'static str {
+    match side {
+        TradeSide::Buy => "buy",
+        TradeSide::Sell => "sell",
+    }
+}
+
+fn candle_from_row(row: &Row<'_>) -> rusqlite::Result<Candle> {
+    let interval: String = row.get(1)?;
+    Ok(Candle {
+        symbol: Symbol::new(row.get::<_, String>(0)?),
+        interval: CandleInterval::from_code(&interval).unwrap_or_default(),
+        timestamp: row.get(2)?,
+        open: Price::new(row.get(3)?),
+        high: Price::new(row.get(4)?),
+        low: Price::new(row.get(5)?),
+        close: Price::new(row.get(6)?),
+        volume: Quantity::new(row.get(7)?),
+        quote_volume: row.get(8)?,
+        trade_count: row.get(9)?,
+        buy_volume: None,
+        sell_volume: None,
+        is_closed: row.get(10)?,
+        synthetic: false,
+    })
+}
+
+fn trade_from_row(row: &Row<'_>) -> rusqlite::Result<Trade> {
+    let side: String = row.get(4)?;
+    let timestamp: i64 = row.get(5)?;
+    Ok(Trade {
+        id: row.get(0)?,
+        symbol: Symbol::new(row.get::<_, String>(1)?),
+        price: Price::new(row.get(2)?),
+        quantity: Quantity::new(row.get(3)?),
+        side: if side == "sell" { TradeSide::Sell } else { TradeSide::Buy },
+        timestamp: Utc.timestamp_millis_opt(timestamp).single().unwrap_or_default(),
+        maker_order_id: row.get(6)?,
+        taker_order_id: row.get(7)?,
+        venue: row.get(8)?,
+    })
+}
+
+// ============================================================================
+// WRITER
+// ============================================================================
+
+/// Open the database named by `DASH_DB_PATH` (logs and returns None on failure)
+pub fn open_from_env() -> Option<Arc<Storage>> {
+    let path = std::env::var(DB_PATH_ENV).unwrap_or_else(|_| DEFAULT_DB_PATH.to_string());
+    let storage = if path == ":memory:" {
+        Storage::open_in_memory()
+    } else {
+        Storage::open(&path)
+    };
+
+    match storage {
+        Ok(storage) => {
+            tracing::info!("History storage: {}", path);
+            Some(Arc::new(storage))
+        }
+        Err(e) => {
+            tracing::error!("Failed to open history storage at {}: {} (history disabled)", path, e);
+            None
+        }
+    }
+}
+
+/// Buffer broadcast trades/candles and flush them to storage periodically
+pub async fn run_storage_writer(state: Arc<AppState>, storage: Arc<Storage>) {
+    let mut rx = state.tx.subscribe();
+    let mut flush = tokio::time::interval(FLUSH_INTERVAL);
+    let mut trades: Vec<Trade> = Vec::new();
+    let mut candles: Vec<Candle> = Vec::new();
+
+    loop {
+        tokio::select! {
+            msg = rx.recv() => match msg {
+                Ok(WsMessage::Trade(trade)) => trades.push(trade),
+                Ok(WsMessage::Candle(candle)) => {
+                    // Only the latest state of each candle needs writing
+                    match candles.iter_mut().find(|c| {
+                        c.timestamp == candle.timestamp
+                            && c.interval == candle.interval
+                            && c.symbol == candle.symbol
+                    }) {
+                        Some(existing) => *existing = candle,
+                        None => candles.push(candle),
+                    }
+                }
+                Ok(_) => {}
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!("Storage writer lagged, {} messages not persisted", skipped);
+                }
+                Err(RecvError::Closed) => break,
+            },
+            _ = flush.tick() => {
+                if !state.recording.load(Ordering::Relaxed) {
+                    trades.clear();
+                    candles.clear();
+                    continue;
+                }
+                if trades.is_empty() && candles.is_empty() {
+                    continue;
+                }
+                let batch = (std::mem::take(&mut trades), std::mem::take(&mut candles));
+                let storage = storage.clone();
+                let result = tokio::task::spawn_blocking(move || {
+                    storage.insert_trades(&batch.0)?;
+                    storage.upsert_candles(&batch.1)
+                })
+                .await;
+
+                match result {
+                    Ok(Ok(())) => {}
+                    Ok(Err(e)) => tracing::error!("Failed to persist market data: {}", e),
+                    Err(e) => tracing::error!("Storage task panicked: {}", e),
+                }
+            }
+        }
+    }
+}
+
+// ============================================================================
+// CONTROL
+// ============================================================================
+
+/// Body of `GET`/`PUT /api/control/recording`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Recording {
+    pub recording: bool,
+}
+
+/// `GET /api/control/recording` - whether market data is being written
+pub async fn recording_handler(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Recording>, StatusCode> {
+    state.storage.as_ref().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    Ok(Json(Recording { recording: state.recording.load(Ordering::Relaxed) }))
+}
+
+/// `PUT /api/control/recording` - start or pause writing market data
+/// (operators only)
+pub async fn put_recording_handler(
+    State(state): State<Arc<AppState>>,
+    Json(body): Json<Recording>,
+) -> Result<Json<Recording>, StatusCode> {
+    state.storage.as_ref().ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    let was = state.recording.swap(body.recording, Ordering::Relaxed);
+    if was != body.recording {
+        tracing::info!("History recording {}", if body.recording { "resumed" } else { "paused" });
+    }
+    Ok(Json(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn btc() -> Symbol {
+        Symbol::new("BTC-USD")
+    }
+
+    fn candle(ts: i64, close: f64) -> Candle {
+        let mut candle = Candle::new(btc(), CandleInterval::M1, ts, 50_000.0);
+        candle.update(close, 1.0);
+        candle
+    }
+
+    #[test]
+    fn test_migrations_applied_once() {
+        let storage = Storage::open_in_memory().unwrap();
+        assert_eq!(storage.schema_version().unwrap(), MIGRATIONS.len());
+
+        // Re-running is a no-op
+        migrate(&mut storage.conn()).unwrap();
+        assert_eq!(storage.schema_version().unwrap(), MIGRATIONS.len());
+    }
+
+    #[test]
+    fn test_candle_roundtrip() {
+        let storage = Storage::open_in_memory().unwrap();
+        storage
+            .upsert_candles(&[candle(0, 50_100.0), candle(60_000, 50_200.0)])
+            .unwrap();
+        // Forming candle update replaces the stored row
+        storage.upsert_candles(&[candle(60_000, 50_300.0)]).unwrap();
+
+        let candles = storage.candles(&btc(), CandleInterval::M1, HistoryRange::default()).unwrap();
+        assert_eq!(candles.len(), 2);
+        assert_eq!(candles[0].timestamp, 0);
+        assert_eq!(candles[1].close.as_f64(), 50_300.0);
+
+        let range = HistoryRange { from: 30_000, ..Default::default() };
+        assert_eq!(storage.candles(&btc(), CandleInterval::M1, range).unwrap().len(), 1);
+        assert!(storage.candles(&btc(), CandleInterval::H1, HistoryRange::default()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_trade_roundtrip() {
+        let storage = Storage::open_in_memory().unwrap();
+        let trades: Vec<Trade> = (0..5)
+            .map(|i| {
+                let mut trade = Trade::new(btc(), 50_000.0 + i as f64, 0.1, TradeSide::Sell);
+                trade.timestamp = Utc.timestamp_millis_opt(i * 1_000).unwrap();
+                trade
+            })
+            .collect();
+
+        assert_eq!(storage.insert_trades(&trades).unwrap(), 5);
+        assert_eq!(storage.insert_trades(&trades[..2]).unwrap(), 0);
+
+        let range = HistoryRange { limit: 2, ..Default::default() };
+        let latest = storage.trades(&btc(), range).unwrap();
+        assert_eq!(latest.len(), 2);
+        assert_eq!(latest[0].id, trades[4].id);
+        assert_eq!(latest[0].side, TradeSide::Sell);
+        assert_eq!(latest[0].timestamp, trades[4].timestamp);
+    }
+
+    #[test]
+    fn test_user_documents() {
+        let storage = Storage::open_in_memory().unwrap();
+        let id = storage.create_user("Trader", "hash", 0).unwrap().unwrap();
+        assert_eq!(storage.create_user("trader", "other", 0).unwrap(), None);
+
+        let (found, name, hash) = storage.user_credentials("TRADER").unwrap().unwrap();
+        assert_eq!((found, name.as_str(), hash.as_str()), (id, "Trader", "hash"));
+        assert!(storage.user_credentials("nobody").unwrap().is_none());
+
+        assert_eq!(storage.document(id, "layout").unwrap(), None);
+        storage.put_document(id, "layout", r#"{"a":1}"#, 1).unwrap();
+        storage.put_document(id, "layout", r#"{"a":2}"#, 2).unwrap();
+        assert_eq!(storage.document(id, "layout").unwrap().as_deref(), Some(r#"{"a":2}"#));
+    }
+}
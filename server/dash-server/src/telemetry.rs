@@ -0,0 +1,196 @@
+//! Log output and per-connection ids
+//!
+//! `DASH_LOG_FORMAT=json` writes one JSON object per line, carrying the
+//! fields of every span the event happened in, so each line logged for a
+//! WebSocket client has its `conn` id and address. Otherwise logs are
+//! human-readable text. `RUST_LOG` picks what is logged either way.
+
+use std::io::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use chrono::{SecondsFormat, Utc};
+use serde_json::{Map, Value};
+use tracing::field::{Field, Visit};
+use tracing::{span, Event, Subscriber};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+/// What is logged when `RUST_LOG` is unset
+const DEFAULT_FILTER: &str = "dash_server=debug,dash_adapters=debug,tower_http=debug";
+
+/// How log lines are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+impl LogFormat {
+    /// `DASH_LOG_FORMAT`: `json` or `text` (the default)
+    pub fn from_env() -> Self {
+        match std::env::var("DASH_LOG_FORMAT") {
+            Ok(format) if format.trim().eq_ignore_ascii_case("json") => Self::Json,
+            _ => Self::Text,
+        }
+    }
+}
+
+/// Install the global subscriber
+pub fn init(format: LogFormat) {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| DEFAULT_FILTER.into());
+    let registry = tracing_subscriber::registry().with(filter);
+    match format {
+        LogFormat::Text => registry.with(tracing_subscriber::fmt::layer()).init(),
+        LogFormat::Json => registry.with(JsonLayer::new(std::io::stdout)).init(),
+    }
+}
+
+/// Id for a new WebSocket connection, unique for the process
+pub fn next_connection_id() -> u64 {
+    static NEXT: AtomicU64 = AtomicU64::new(1);
+    NEXT.fetch_add(1, Ordering::Relaxed)
+}
+
+// ============================================================================
+// JSON LINES
+// ============================================================================
+
+/// Fields recorded on a span, kept in its extensions
+struct SpanFields(Map<String, Value>);
+
+/// Collects fields into a JSON object
+struct JsonVisitor<'a>(&'a mut Map<String, Value>);
+
+impl Visit for JsonVisitor<'_> {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), Value::from(value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.0.insert(field.name().to_string(), Value::from(format!("{:?}", value)));
+    }
+}
+
+/// Writes each event as a JSON object on its own line: timestamp, level,
+/// target, the names of the enclosing spans, their fields, then the
+/// event's own fields (`message` among them)
+pub struct JsonLayer<W> {
+    make_writer: W,
+}
+
+impl<W> JsonLayer<W> {
+    pub fn new(make_writer: W) -> Self {
+        Self { make_writer }
+    }
+}
+
+impl<S, W> Layer<S> for JsonLayer<W>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'a> MakeWriter<'a> + 'static,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        let mut fields = Map::new();
+        attrs.record(&mut JsonVisitor(&mut fields));
+        span.extensions_mut().insert(SpanFields(fields));
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else { return };
+        if let Some(fields) = span.extensions_mut().get_mut::<SpanFields>() {
+            values.record(&mut JsonVisitor(&mut fields.0));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let meta = event.metadata();
+        let mut line = Map::new();
+        let timestamp = Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true);
+        line.insert("timestamp".into(), Value::from(timestamp));
+        line.insert("level".into(), Value::from(meta.level().to_string()));
+        line.insert("target".into(), Value::from(meta.target()));
+
+        // Outer spans first, so inner fields win
+        let mut spans = Vec::new();
+        for span in ctx.event_scope(event).into_iter().flat_map(|scope| scope.from_root()) {
+            spans.push(span.name());
+            if let Some(fields) = span.extensions().get::<SpanFields>() {
+                line.extend(fields.0.clone());
+            }
+        }
+        if !spans.is_empty() {
+            line.insert("spans".into(), Value::from(spans.join(">")));
+        }
+        event.record(&mut JsonVisitor(&mut line));
+
+        let mut json = Value::Object(line).to_string();
+        json.push('\n');
+        let _ = self.make_writer.make_writer().write_all(json.as_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Writer appending to a shared buffer
+    struct Capture(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Capture {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_json_lines_carry_span_fields() {
+        let buffer = Arc::new(Mutex::new(Vec::new()));
+        let writer = {
+            let buffer = buffer.clone();
+            move || Capture(buffer.clone())
+        };
+        let subscriber = tracing_subscriber::registry().with(JsonLayer::new(writer));
+        tracing::subscriber::with_default(subscriber, || {
+            let conn = tracing::info_span!("ws_connection", conn = 7u64, ip = "10.0.0.1");
+            let _conn = conn.enter();
+            let _message = tracing::info_span!("client_message").entered();
+            tracing::info!(symbol = "BTC-USD", "client subscribed");
+        });
+
+        let output = buffer.lock().unwrap().clone();
+        let line: Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(line["conn"], 7);
+        assert_eq!(line["ip"], "10.0.0.1");
+        assert_eq!(line["spans"], "ws_connection>client_message");
+        assert_eq!(line["message"], "client subscribed");
+        assert_eq!(line["symbol"], "BTC-USD");
+        assert_eq!(line["level"], "INFO");
+        assert!(output.ends_with(b"\n"));
+    }
+}
@@ -0,0 +1,74 @@
+//! Native TLS (rustls) for the HTTP/WebSocket and gRPC listeners
+//!
+//! Enabled by pointing `DASH_TLS_CERT` and `DASH_TLS_KEY` at PEM files.
+//! Without them the server speaks plain HTTP, e.g. behind a TLS-terminating
+//! reverse proxy.
+
+use std::path::PathBuf;
+
+use axum_server::tls_rustls::RustlsConfig;
+use tonic::transport::{Identity, ServerTlsConfig};
+
+/// PEM certificate chain and private key locations
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlsPaths {
+    pub cert: PathBuf,
+    pub key: PathBuf,
+}
+
+impl TlsPaths {
+    /// Read `DASH_TLS_CERT` / `DASH_TLS_KEY` (None = serve plain HTTP)
+    pub fn from_env() -> Option<Self> {
+        Self::from_vars(
+            std::env::var("DASH_TLS_CERT").ok(),
+            std::env::var("DASH_TLS_KEY").ok(),
+        )
+    }
+
+    fn from_vars(cert: Option<String>, key: Option<String>) -> Option<Self> {
+        let non_empty = |v: Option<String>| v.filter(|v| !v.trim().is_empty());
+        match (non_empty(cert), non_empty(key)) {
+            (Some(cert), Some(key)) => Some(Self {
+                cert: cert.into(),
+                key: key.into(),
+            }),
+            (None, None) => None,
+            _ => {
+                tracing::warn!("TLS needs both DASH_TLS_CERT and DASH_TLS_KEY; serving plain HTTP");
+                None
+            }
+        }
+    }
+
+    /// Load the certificate and key into a rustls server config
+    pub async fn load(&self) -> std::io::Result<RustlsConfig> {
+        // Several providers may be linked in; pin ring explicitly
+        let _ = rustls::crypto::ring::default_provider().install_default();
+        RustlsConfig::from_pem_file(&self.cert, &self.key).await
+    }
+
+    /// Load the certificate and key for the gRPC listener
+    pub async fn load_grpc(&self) -> std::io::Result<ServerTlsConfig> {
+        let cert = tokio::fs::read(&self.cert).await?;
+        let key = tokio::fs::read(&self.key).await?;
+        Ok(ServerTlsConfig::new().identity(Identity::from_pem(cert, key)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tls_paths_require_both() {
+        let some = |s: &str| Some(s.to_string());
+
+        assert_eq!(
+            TlsPaths::from_vars(some("cert.pem"), some("key.pem")),
+            Some(TlsPaths { cert: "cert.pem".into(), key: "key.pem".into() })
+        );
+        assert_eq!(TlsPaths::from_vars(some("cert.pem"), None), None);
+        assert_eq!(TlsPaths::from_vars(some(""), some("")), None);
+        assert_eq!(TlsPaths::from_vars(None, None), None);
+    }
+}
@@ -1,52 +1,244 @@
 //! WebSocket handler for client connections
 
+use std::net::SocketAddr;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use axum::{
     extract::{
         ws::{Message, WebSocket},
-        State, WebSocketUpgrade,
+        ConnectInfo, Query, State, WebSocketUpgrade,
     },
-    response::IntoResponse,
+    http::HeaderMap,
+    response::{IntoResponse, Response},
 };
 use futures::{SinkExt, StreamExt};
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, mpsc, watch};
+use tracing::Instrument;
 
+use crate::accounts;
+use crate::chaos::ChaosLink;
+use crate::filter::ConnectionFilters;
+use crate::limits::ConnectionGuard;
+use crate::news;
+use crate::telemetry;
 use crate::AppState;
-use dash_core::WsMessage;
+use dash_core::{
+    encode_message, gzip_encode, ClientCommand, ProtocolVersion, Symbol, WireEncoding, WsMessage,
+    GZIP_MIN_BYTES, PROTOCOL_VERSION,
+};
+use serde::Deserialize;
+
+/// How often the server clock is broadcast for client countdowns
+const HEARTBEAT_PERIOD: Duration = Duration::from_secs(5);
+
+/// Resync requests queued per connection; further requests are dropped
+/// until the queue drains, since each answer carries the latest state
+const RESYNC_QUEUE: usize = 8;
+
+/// Query parameters accepted on the WebSocket upgrade
+#[derive(Debug, Default, Deserialize)]
+pub struct WsParams {
+    /// Requested frame encoding (`text` or `gzip`)
+    encoding: Option<String>,
+    /// Client protocol version (`major.minor`); absent for 1.0 clients
+    protocol: Option<String>,
+}
 
 /// WebSocket upgrade handler
+///
+/// Note: permessage-deflate is not offered because tungstenite (0.24) does not
+/// implement the extension; browsers silently fall back to uncompressed frames.
+/// Clients that want compression request `?encoding=gzip` instead (see the
+/// README for the trade-off).
+///
+/// Frames are down-converted to the client's protocol version; unparseable
+/// versions are treated like an unversioned (1.0) client.
+///
+/// Upgrades beyond the per-IP connection quota are rejected with a 429.
+///
+/// In chaos mode (see `chaos`) frames are delayed, dropped, duplicated and
+/// reordered on their way out, and the server closes the connection after a
+/// while. Answers to `resync` requests are exempt.
+///
+/// A connection that sent an `auth` command with a valid session token gets
+/// the firings of that user's registered alert rules.
+///
+/// Everything logged for the connection happens in a `ws_connection` span
+/// carrying its `conn` id and client address.
 pub async fn ws_handler(
     ws: WebSocketUpgrade,
+    Query(params): Query<WsParams>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     State(state): State<Arc<AppState>>,
-) -> impl IntoResponse {
-    ws.on_upgrade(|socket| handle_socket(socket, state))
+) -> Response {
+    let ip = state.limits.client_ip(addr, &headers);
+    let guard = match state.limits.admit_ws(ip) {
+        Ok(guard) => guard,
+        Err(exceeded) => return exceeded.into_response(),
+    };
+
+    let encoding = params
+        .encoding
+        .as_deref()
+        .map_or(WireEncoding::Text, WireEncoding::from_param);
+
+    let requested = params.protocol.as_deref().and_then(ProtocolVersion::parse);
+    let protocol = ProtocolVersion::negotiate(requested);
+    if let Some(requested) = requested.filter(|v| !v.reads(protocol.major)) {
+        tracing::warn!(
+            "Client protocol {} is no longer served (server {})",
+            requested,
+            PROTOCOL_VERSION
+        );
+    }
+    let wire = Wire { encoding, protocol };
+
+    let span = tracing::info_span!(
+        "ws_connection",
+        conn = telemetry::next_connection_id(),
+        ip = %ip
+    );
+    ws.on_upgrade(move |socket| handle_socket(socket, state, wire, guard).instrument(span))
+}
+
+/// Frame format negotiated for one connection
+#[derive(Debug, Clone, Copy)]
+struct Wire {
+    encoding: WireEncoding,
+    protocol: ProtocolVersion,
+}
+
+/// Encode a message for the wire using the negotiated encoding and protocol;
+/// `None` when the client's protocol predates the message type
+fn encode_frame(msg: &WsMessage, wire: Wire) -> Option<Result<Message, serde_json::Error>> {
+    let json = match encode_message(msg, wire.protocol)? {
+        Ok(json) => json,
+        Err(e) => return Some(Err(e)),
+    };
+    let encoding = wire.encoding;
+
+    if encoding == WireEncoding::Gzip && json.len() >= GZIP_MIN_BYTES {
+        match gzip_encode(json.as_bytes()) {
+            Ok(bytes) => return Some(Ok(Message::Binary(bytes))),
+            Err(e) => tracing::warn!("gzip failed, sending text frame: {}", e),
+        }
+    }
+
+    Some(Ok(Message::Text(json)))
 }
 
 /// Handle individual WebSocket connection
-async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
+/// `_guard` holds the client's connection slot until the socket closes
+async fn handle_socket(
+    socket: WebSocket,
+    state: Arc<AppState>,
+    wire: Wire,
+    _guard: ConnectionGuard,
+) {
     let (mut sender, mut receiver) = socket.split();
 
     // Subscribe to broadcast channel
     let mut rx = state.tx.subscribe();
+    let chaos_rx = state.chaos.subscribe();
+    let mut link = ChaosLink::new(Instant::now());
+
+    // Filters requested by this client (min trade value, book depth)
+    let (filters_tx, filters_rx) = watch::channel(ConnectionFilters::new());
+    // Symbols the client asked to resync after a sequence gap
+    let (resync_tx, mut resync_rx) = mpsc::channel::<Symbol>(RESYNC_QUEUE);
+    // User the client signed in as, whose alerts it receives
+    let (user_tx, user_rx) = watch::channel(None::<String>);
+    let mut user_alerts = state.user_alerts.subscribe();
+    let cache_state = state.clone();
+    let auth_state = state.clone();
 
-    tracing::info!("New WebSocket client connected");
+    // Late joiners still learn about a degraded upstream
+    let source_status = state
+        .health
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .source_status()
+        .cloned();
+    // ... and get the news they missed
+    let news = news::recent(&state);
+
+    tracing::info!(
+        encoding = %wire.encoding,
+        protocol = %wire.protocol,
+        "WebSocket client connected"
+    );
 
     // Spawn task to forward broadcast messages to client
     let send_task = tokio::spawn(async move {
-        while let Ok(msg) = rx.recv().await {
-            match serde_json::to_string(&msg) {
-                Ok(json) => {
-                    if sender.send(Message::Text(json)).await.is_err() {
-                        break;
+        if let Some(Ok(frame)) =
+            source_status.and_then(|status| encode_frame(&WsMessage::SourceStatus(status), wire))
+        {
+            let _ = sender.send(frame).await;
+        }
+        for item in news {
+            if let Some(Ok(frame)) = encode_frame(&WsMessage::News(item), wire)
+                && sender.send(frame).await.is_err()
+            {
+                return;
+            }
+        }
+
+        loop {
+            let messages = tokio::select! {
+                msg = rx.recv() => {
+                    let Ok(msg) = msg else { return };
+                    let chaos = *chaos_rx.borrow();
+                    if link.expired(&chaos, Instant::now()) {
+                        tracing::info!("Chaos mode: closing the connection");
+                        let _ = sender.send(Message::Close(None)).await;
+                        return;
+                    }
+                    let delay = link.delay(&chaos);
+                    if !delay.is_zero() {
+                        tokio::time::sleep(delay).await;
                     }
+                    link.apply(&chaos, msg)
                 }
-                Err(e) => {
-                    tracing::error!("Failed to serialize message: {}", e);
+                Some(symbol) = resync_rx.recv() => {
+                    let snapshot = cache_state
+                        .cache
+                        .read()
+                        .unwrap_or_else(|poisoned| poisoned.into_inner())
+                        .resync_messages(&symbol);
+                    tracing::debug!(%symbol, messages = snapshot.len(), "Answering resync");
+                    snapshot
+                }
+                Ok(alert) = user_alerts.recv() => {
+                    if user_rx.borrow().as_deref() != Some(alert.user.as_str()) {
+                        continue;
+                    }
+                    vec![WsMessage::AlertTriggered(alert.notice)]
+                }
+            };
+
+            for msg in messages {
+                // Filtered per connection; dropped messages are never encoded
+                let encoded = match filters_rx.borrow().filter(&msg) {
+                    Some(msg) => encode_frame(&msg, wire),
+                    None => continue,
+                };
+                let Some(encoded) = encoded else { continue };
+                match encoded {
+                    Ok(frame) => {
+                        if sender.send(frame).await.is_err() {
+                            return;
+                        }
+                    }
+                    Err(e) => {
+                        tracing::error!(error = %e, "Failed to serialize message");
+                    }
                 }
             }
         }
-    });
+    }
+    .in_current_span());
 
     // Spawn task to handle incoming messages from client
     let recv_task = tokio::spawn(async move {
@@ -54,9 +246,10 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
             match msg {
                 Message::Text(text) => {
                     // Handle client messages (e.g., subscription requests)
-                    handle_client_message(&text).await;
+                    let inbound = Inbound { filters: &filters_tx, resync: &resync_tx, user: &user_tx };
+                    handle_client_message(&text, &auth_state, inbound);
                 }
-                Message::Ping(data) => {
+                Message::Ping(_) => {
                     tracing::trace!("Received ping");
                     // Pong is sent automatically by axum
                 }
@@ -67,7 +260,8 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                 _ => {}
             }
         }
-    });
+    }
+    .in_current_span());
 
     // Wait for either task to complete
     tokio::select! {
@@ -82,39 +276,133 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
     tracing::info!("WebSocket client disconnected");
 }
 
+/// Where client commands take effect on their connection
+struct Inbound<'a> {
+    filters: &'a watch::Sender<ConnectionFilters>,
+    resync: &'a mpsc::Sender<Symbol>,
+    user: &'a watch::Sender<Option<String>>,
+}
+
 /// Handle messages from client
-async fn handle_client_message(text: &str) {
-    // Parse client commands (e.g., subscribe to specific symbols)
-    #[derive(serde::Deserialize)]
-    #[serde(tag = "type")]
-    enum ClientMessage {
-        #[serde(rename = "subscribe")]
-        Subscribe { symbol: String },
-        #[serde(rename = "unsubscribe")]
-        Unsubscribe { symbol: String },
-        #[serde(rename = "ping")]
-        Ping,
-    }
+fn handle_client_message(text: &str, state: &AppState, inbound: Inbound<'_>) {
+    let Inbound { filters, resync, user } = inbound;
+    let _span = tracing::debug_span!("client_message", bytes = text.len()).entered();
+    let Ok(cmd) = serde_json::from_str::<ClientCommand>(text) else {
+        tracing::trace!(text, "Unknown client message");
+        return;
+    };
 
-    match serde_json::from_str::<ClientMessage>(text) {
-        Ok(ClientMessage::Subscribe { symbol }) => {
-            tracing::info!("Client subscribed to {}", symbol);
-            // TODO: Implement subscription filtering
+    // Subscriptions carry this connection's stream filters
+    filters.send_if_modified(|filters| filters.apply(&cmd));
+
+    match cmd {
+        ClientCommand::Subscribe(sub) => {
+            tracing::info!(
+                symbol = %sub.symbol,
+                interval = %sub.interval,
+                depth_levels = ?sub.depth_levels,
+                min_trade_value = ?sub.min_trade_value,
+                "Client subscribed"
+            );
         }
-        Ok(ClientMessage::Unsubscribe { symbol }) => {
-            tracing::info!("Client unsubscribed from {}", symbol);
+        ClientCommand::Unsubscribe { symbol } => {
+            tracing::info!(%symbol, "Client unsubscribed");
         }
-        Ok(ClientMessage::Ping) => {
-            tracing::trace!("Client ping");
+        ClientCommand::Resync { symbol } => {
+            tracing::info!(%symbol, "Client requested resync");
+            if resync.try_send(symbol).is_err() {
+                tracing::debug!("Resync queue full, dropping request");
+            }
+        }
+        ClientCommand::Auth { token } => {
+            let claims = accounts::verify_session(state, &token);
+            tracing::debug!(user = ?claims.as_ref().map(|c| &c.sub), "Client sent auth token");
+            user.send_replace(claims.map(|c| c.sub));
         }
-        Err(_) => {
-            tracing::trace!("Unknown client message: {}", text);
+        ClientCommand::Ping => {
+            tracing::trace!("Client ping");
         }
     }
 }
 
+/// Broadcast the server time so clients can align candle-close countdowns
+pub async fn run_heartbeat(state: Arc<AppState>) {
+    let mut ticker = tokio::time::interval(HEARTBEAT_PERIOD);
+    loop {
+        ticker.tick().await;
+        let timestamp = chrono::Utc::now().timestamp_millis();
+        // Ignore send errors (no receivers)
+        let _ = state.tx.send(WsMessage::Heartbeat { timestamp });
+    }
+}
+
 /// Broadcast a message to all connected clients
+#[allow(dead_code)]
 pub async fn broadcast(tx: &broadcast::Sender<WsMessage>, msg: WsMessage) {
     // Ignore send errors (no receivers)
     let _ = tx.send(msg);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dash_core::gzip_decode;
+
+    fn wire(encoding: WireEncoding) -> Wire {
+        Wire { encoding, protocol: ProtocolVersion::LEGACY }
+    }
+
+    #[test]
+    fn test_encode_frame_gzip_threshold() {
+        let small = WsMessage::Heartbeat { timestamp: 1 };
+        let frame = encode_frame(&small, wire(WireEncoding::Gzip)).unwrap().unwrap();
+        assert!(matches!(frame, Message::Text(_)));
+
+        let mut big = dash_core::OrderBookSnapshot::new(dash_core::Symbol::default());
+        big.bids = (0..20).map(|i| dash_core::OrderBookLevel::new(100.0 - i as f64, 1.0, 1)).collect();
+        let msg = WsMessage::OrderBook(big);
+
+        match encode_frame(&msg, wire(WireEncoding::Gzip)).unwrap().unwrap() {
+            Message::Binary(bytes) => {
+                let json = String::from_utf8(gzip_decode(&bytes).unwrap()).unwrap();
+                assert_eq!(json, serde_json::to_string(&msg).unwrap());
+            }
+            other => panic!("expected binary frame, got {:?}", other),
+        }
+
+        let frame = encode_frame(&msg, wire(WireEncoding::Text)).unwrap().unwrap();
+        assert!(matches!(frame, Message::Text(_)));
+    }
+
+    #[test]
+    fn test_encode_frame_protocol() {
+        let msg = WsMessage::Heartbeat { timestamp: 1 };
+        let current = Wire { encoding: WireEncoding::Text, protocol: PROTOCOL_VERSION };
+        match encode_frame(&msg, current).unwrap().unwrap() {
+            Message::Text(json) => assert!(json.starts_with(r#"{"v":2,"#)),
+            other => panic!("expected text frame, got {:?}", other),
+        }
+        match encode_frame(&msg, wire(WireEncoding::Text)).unwrap().unwrap() {
+            Message::Text(json) => assert!(json.starts_with(r#"{"type":"heartbeat""#)),
+            other => panic!("expected text frame, got {:?}", other),
+        }
+    }
+    #[test]
+    fn test_resync_requests_are_bounded() {
+        let state = AppState::new();
+        let (filters, _) = watch::channel(ConnectionFilters::new());
+        let (resync, mut resync_rx) = mpsc::channel(RESYNC_QUEUE);
+        let (user, _) = watch::channel(None);
+        let cmd = r#"{"type":"resync","symbol":"BTCUSDT"}"#;
+        for _ in 0..100 {
+            let inbound = Inbound { filters: &filters, resync: &resync, user: &user };
+            handle_client_message(cmd, &state, inbound);
+        }
+
+        let mut queued = 0;
+        while resync_rx.try_recv().is_ok() {
+            queued += 1;
+        }
+        assert_eq!(queued, RESYNC_QUEUE);
+    }
+}
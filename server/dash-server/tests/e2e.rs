@@ -0,0 +1,469 @@
+//! End to end: the server binary on the seeded mock engine
+//!
+//! Market data is read by the dashboard's own WebSocket client, running its
+//! connection loop natively. Chaos and access checks look at the raw frames,
+//! over the WebSocket transport the exchange adapters use.
+//!
+//! Each test starts its own server on free ports with in-memory history.
+//! Operators' names cannot be signed up, so tests needing an operator open
+//! the account on a first server and restart it on the same database file.
+
+use std::net::TcpListener;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+use dash_adapters::ws::{self, WsConnection};
+use dash_core::{
+    decode_frame, ChaosConfig, ClientCommand, Credentials, OrderBookSnapshot, Session,
+    Subscription, WsMessage, PROTOCOL_VERSION,
+};
+use dash_state::{AppState, BookGaps, VenueView};
+use dash_websocket::{ExponentialBackoff, WsClient, WsConfig, WsHandle};
+use futures::StreamExt;
+use leptos::prelude::*;
+use tokio::task::LocalSet;
+
+const SYMBOL: &str = "BTC-USD";
+
+/// Venue the mock engine reports; merged books carry no sequence
+const VENUE: &str = "mock";
+
+/// Book levels per side the test client asks for
+const DEPTH_LEVELS: usize = 10;
+
+/// Longest wait for a frame; the mock sends several a second
+const FRAME_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Longest wait for the client to reach an expected state
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Client reconnect delay; the mock publishes a book every 250 ms, so a
+/// reconnect always misses some
+const RECONNECT_DELAY_MS: u32 = 600;
+
+/// The user `DASH_OPERATORS` names in tests that need one
+const OPERATOR: &str = "operator";
+
+/// A `dash-server` process, killed on drop
+struct Server {
+    child: Child,
+    port: u16,
+    /// Database file removed on drop, for servers not on in-memory history
+    db: Option<PathBuf>,
+}
+
+fn free_port() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("no free port");
+    listener.local_addr().unwrap().port()
+}
+
+impl Server {
+    fn start() -> Self {
+        Self::start_with(&[])
+    }
+
+    /// Start with extra environment variables
+    fn start_with(env: &[(&str, &str)]) -> Self {
+        let port = free_port();
+        let child = Command::new(env!("CARGO_BIN_EXE_dash-server"))
+            .env("DASH_PORT", port.to_string())
+            .env("DASH_GRPC_PORT", free_port().to_string())
+            .env("DASH_DB_PATH", ":memory:")
+            .env("DASH_ADAPTERS", "mock")
+            .env("DASH_SYMBOLS", SYMBOL)
+            .env("DASH_MOCK_SEED", "42")
+            .env("RUST_LOG", "warn")
+            .envs(env.iter().copied())
+            .current_dir(std::env::temp_dir())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("failed to start dash-server");
+        Self { child, port, db: None }
+    }
+
+    /// Start with `OPERATOR` signed up and named in `DASH_OPERATORS`, plus
+    /// extra environment variables
+    async fn start_with_operator(env: &[(&str, &str)]) -> (Self, Session) {
+        let db = std::env::temp_dir().join(format!("dash-e2e-{}.db", free_port()));
+        let db_path = db.to_str().expect("temp dir is not UTF-8");
+        let shared = [("DASH_DB_PATH", db_path), ("DASH_JWT_SECRET", "e2e")];
+
+        let setup = Self::start_with(&shared);
+        setup.connect().await;
+        let session = setup.sign_up(OPERATOR).await;
+        drop(setup);
+
+        let mut env = env.to_vec();
+        env.extend(shared);
+        env.push(("DASH_OPERATORS", OPERATOR));
+        let mut server = Self::start_with(&env);
+        server.db = Some(db);
+        (server, session)
+    }
+
+    /// Connect and subscribe once the server is listening
+    async fn connect(&self) -> WsConnection {
+        let url = format!("ws://127.0.0.1:{}/ws?protocol={}", self.port, PROTOCOL_VERSION);
+        for _ in 0..100 {
+            if let Ok(mut conn) = ws::connect(&url).await {
+                let subscribe = Subscription::new(SYMBOL).depth_levels(DEPTH_LEVELS);
+                let command = serde_json::to_value(ClientCommand::Subscribe(subscribe)).unwrap();
+                ws::send_json(&mut conn, &command).await.expect("subscribe failed");
+                return conn;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        panic!("dash-server is not accepting connections on port {}", self.port);
+    }
+
+    /// The dashboard client, subscribed to `SYMBOL`. It connects once the
+    /// server is listening; must run inside a `LocalSet`.
+    fn client(&self) -> Client {
+        let url = format!("ws://127.0.0.1:{}/ws", self.port);
+        let policy = ExponentialBackoff::new()
+            .initial_delay(RECONNECT_DELAY_MS)
+            .multiplier(1.0)
+            .with_jitter(false);
+        let state = AppState::new();
+        state.market.set_venue_view(VenueView::Venue(VENUE.into()));
+        let handle = WsClient::with_config(state.clone(), WsConfig::new(url).with_policy(policy))
+            .connect();
+        handle.subscribe_with(Subscription::new(SYMBOL).depth_levels(DEPTH_LEVELS));
+        Client { state, handle }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("http://127.0.0.1:{}{}", self.port, path)
+    }
+
+    /// Open an account and sign in as `username`
+    async fn sign_up(&self, username: &str) -> Session {
+        let credentials =
+            Credentials { username: username.into(), password: "correct horse".into() };
+        reqwest::Client::new()
+            .post(self.url("/api/auth/signup"))
+            .json(&credentials)
+            .send()
+            .await
+            .expect("signup request failed")
+            .error_for_status()
+            .unwrap()
+            .json()
+            .await
+            .expect("invalid session")
+    }
+
+    /// `POST /api/alerts/rules` as the signed-in `session`
+    async fn register_rule(&self, session: &Session, source: &str) {
+        reqwest::Client::new()
+            .post(self.url("/api/alerts/rules"))
+            .bearer_auth(&session.token)
+            .json(&serde_json::json!({ "symbol": SYMBOL, "source": source }))
+            .send()
+            .await
+            .expect("rule request failed")
+            .error_for_status()
+            .unwrap();
+    }
+
+    /// `PUT /api/control/chaos`, with `token` as the bearer if given
+    async fn put_chaos(&self, config: &ChaosConfig, token: Option<&str>) -> reqwest::Response {
+        let mut request = reqwest::Client::new().put(self.url("/api/control/chaos")).json(config);
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+        request.send().await.expect("chaos request failed")
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        if let Some(db) = &self.db {
+            for suffix in ["", "-wal", "-shm"] {
+                let _ = std::fs::remove_file(format!("{}{}", db.display(), suffix));
+            }
+        }
+    }
+}
+
+/// Next message; `None` once the server closes the connection
+async fn try_next_message(conn: &mut WsConnection) -> Option<WsMessage> {
+    loop {
+        let frame = tokio::time::timeout(FRAME_TIMEOUT, conn.next())
+            .await
+            .expect("no frame from dash-server")?
+            .expect("WebSocket error");
+        if frame.is_close() {
+            return None;
+        }
+        if !frame.is_text() {
+            continue;
+        }
+        let text = frame.into_text().unwrap();
+        match decode_frame(&text) {
+            Ok(envelope) => return Some(envelope.message),
+            Err(e) => panic!("undecodable frame {}: {}", text, e),
+        }
+    }
+}
+
+/// A running dashboard client, stopped on drop
+struct Client {
+    state: AppState,
+    handle: WsHandle,
+}
+
+impl Client {
+    /// Poll the client's state until `done` holds
+    async fn wait_for(&self, what: &str, mut done: impl FnMut(&AppState) -> bool) {
+        let deadline = Instant::now() + CLIENT_TIMEOUT;
+        while !done(&self.state) {
+            assert!(Instant::now() < deadline, "timed out waiting for {}", what);
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    }
+
+    fn book(&self) -> Option<OrderBookSnapshot> {
+        self.state.market.orderbook.with_untracked(|book| book.as_deref().cloned())
+    }
+
+    /// Sequence gaps and resyncs the client recorded
+    fn gaps(&self) -> BookGaps {
+        self.state.diagnostics.with_value(|d| d.books)
+    }
+}
+
+impl Drop for Client {
+    fn drop(&mut self) {
+        self.handle.stop();
+    }
+}
+
+/// Book sequences as the client displays them; fails if one ever goes back
+fn displayed_sequences(client: &Client, sequences: &mut Vec<u64>) {
+    let Some(book) = client.book() else { return };
+    match sequences.last() {
+        Some(&last) if book.sequence == last => {}
+        Some(&last) => {
+            assert!(book.sequence > last, "book went back from {} to {}", last, book.sequence);
+            sequences.push(book.sequence);
+        }
+        None => sequences.push(book.sequence),
+    }
+}
+
+async fn next_message(conn: &mut WsConnection) -> WsMessage {
+    try_next_message(conn).await.expect("dash-server closed the connection")
+}
+
+async fn next_book(conn: &mut WsConnection) -> OrderBookSnapshot {
+    loop {
+        if let WsMessage::OrderBook(book) = next_message(conn).await {
+            return book;
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_streams_subscribed_market() {
+    let server = Server::start();
+    LocalSet::new()
+        .run_until(async {
+            let client = server.client();
+            let market = &client.state.market;
+
+            let mut sequences = Vec::new();
+            client
+                .wait_for("candles, trades, a ticker and 8 books", |_| {
+                    displayed_sequences(&client, &mut sequences);
+                    market.candles.with_untracked(|c| !c.is_empty())
+                        && market.trades.with_untracked(|t| !t.is_empty())
+                        && market.ticker.with_untracked(Option::is_some)
+                        && sequences.len() >= 8
+                })
+                .await;
+            assert!(client.state.connection.get_untracked().is_connected());
+            assert_eq!(market.ticker.get_untracked().unwrap().symbol.as_str(), SYMBOL);
+            assert!(market.trades.with_untracked(|t| t.iter().all(|t| t.price.as_f64() > 0.0)));
+
+            // Every book reached the client, in order
+            assert_eq!(client.gaps(), BookGaps::default(), "displayed {:?}", sequences);
+
+            // The subscription's depth applies from the next book on
+            let book = client.book().unwrap();
+            assert_eq!(book.symbol.as_str(), SYMBOL);
+            assert!(book.bids.len() <= DEPTH_LEVELS && book.asks.len() <= DEPTH_LEVELS);
+            let (bid, ask) = (book.best_bid().unwrap(), book.best_ask().unwrap());
+            assert!(bid.price.as_f64() < ask.price.as_f64());
+        })
+        .await;
+}
+
+#[tokio::test]
+async fn test_resyncs_after_gap() {
+    // Lost books are gaps, duplicated ones arrive stale
+    let (server, session) =
+        Server::start_with_operator(&[("DASH_CHAOS", "drop=0.3,duplicate=0.3")]).await;
+    LocalSet::new()
+        .run_until(async {
+            let client = server.client();
+
+            let mut sequences = Vec::new();
+            client
+                .wait_for("gaps, stale books and resyncs", |_| {
+                    displayed_sequences(&client, &mut sequences);
+                    let gaps = client.gaps();
+                    gaps.gaps >= 3 && gaps.stale >= 3 && gaps.resyncs >= 1
+                })
+                .await;
+            // One request per gap at most; gaps found together share one
+            let gaps = client.gaps();
+            assert!(gaps.resyncs <= gaps.gaps);
+
+            // With every stream frame dropped, only resync answers get through
+            let blackout = ChaosConfig { drop_rate: 1.0, ..Default::default() };
+            server.put_chaos(&blackout, Some(&session.token)).await.error_for_status().unwrap();
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            let frozen = client.book().unwrap().sequence;
+            tokio::time::sleep(Duration::from_millis(500)).await;
+            assert_eq!(client.book().unwrap().sequence, frozen, "stream not blacked out");
+
+            assert!(client.handle.send(ClientCommand::resync(SYMBOL)));
+            client
+                .wait_for("the resync answer", |_| {
+                    displayed_sequences(&client, &mut sequences);
+                    client.book().is_some_and(|book| book.sequence > frozen)
+                })
+                .await;
+        })
+        .await;
+}
+
+#[tokio::test]
+async fn test_resyncs_after_reconnect() {
+    let server = Server::start_with(&[("DASH_CHAOS", "disconnect=2")]);
+    LocalSet::new()
+        .run_until(async {
+            let client = server.client();
+
+            // The server hangs up; books missed while reconnecting are a gap
+            let mut sequences = Vec::new();
+            client
+                .wait_for("a resync after reconnecting", |_| {
+                    displayed_sequences(&client, &mut sequences);
+                    client.gaps().resyncs >= 1
+                })
+                .await;
+            let resumed = client.book().unwrap().sequence;
+            client
+                .wait_for("books after the reconnect", |state| {
+                    displayed_sequences(&client, &mut sequences);
+                    state.connection.get_untracked().is_connected()
+                        && client.book().is_some_and(|book| book.sequence > resumed)
+                })
+                .await;
+        })
+        .await;
+}
+
+#[tokio::test]
+async fn test_rule_alerts_reach_owner_only() {
+    let server = Server::start_with(&[("DASH_ALERTS_TOKEN", "secret")]);
+    let mut alice_conn = server.connect().await;
+    let mut bob_conn = server.connect().await;
+    let mut anonymous = server.connect().await;
+    let (alice, bob) = (server.sign_up("alice").await, server.sign_up("bob").await);
+    for (conn, session) in [(&mut alice_conn, &alice), (&mut bob_conn, &bob)] {
+        let auth = serde_json::to_value(ClientCommand::auth(&session.token)).unwrap();
+        ws::send_json(conn, &auth).await.expect("auth failed");
+    }
+    // Both connections have read their auth by the time they stream books
+    next_book(&mut alice_conn).await;
+    next_book(&mut bob_conn).await;
+
+    server.register_rule(&alice, "price > 0").await;
+    let notice = loop {
+        if let WsMessage::AlertTriggered(notice) = next_message(&mut alice_conn).await {
+            break notice;
+        }
+    };
+    assert_eq!(notice.symbol.as_str(), SYMBOL);
+
+    // The others keep streaming but never see it
+    let deadline = Instant::now() + Duration::from_secs(1);
+    while Instant::now() < deadline {
+        for conn in [&mut bob_conn, &mut anonymous] {
+            let msg = next_message(conn).await;
+            assert!(!matches!(msg, WsMessage::AlertTriggered(_)), "alert leaked: {:?}", msg);
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_chaos_mode() {
+    let (server, session) =
+        Server::start_with_operator(&[("DASH_CHAOS", "duplicate=1,disconnect=2")]).await;
+    let mut conn = server.connect().await;
+
+    // Every book arrives twice until the server hangs up
+    let mut sequences = Vec::new();
+    while let Some(msg) = try_next_message(&mut conn).await {
+        if let WsMessage::OrderBook(book) = msg {
+            sequences.push(book.sequence);
+        }
+    }
+    assert!(!sequences.is_empty());
+    assert!(
+        sequences.chunks(2).all(|pair| pair.len() == 2 && pair[0] == pair[1]),
+        "books not duplicated: {:?}",
+        sequences
+    );
+
+    // Switched off from the admin endpoint by an operator
+    let off: ChaosConfig = server
+        .put_chaos(&ChaosConfig::default(), Some(&session.token))
+        .await
+        .error_for_status()
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(!off.is_active());
+    let mut conn = server.connect().await;
+    let first = next_book(&mut conn).await;
+    assert_eq!(next_book(&mut conn).await.sequence, first.sequence + 1);
+    // Nobody else can sign up under the operator's name
+    let credentials =
+        Credentials { username: OPERATOR.to_uppercase(), password: "correct horse".into() };
+    let signup = reqwest::Client::new()
+        .post(server.url("/api/auth/signup"))
+        .json(&credentials)
+        .send()
+        .await
+        .expect("signup request failed");
+    assert_eq!(signup.status(), reqwest::StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_control_refuses_viewers() {
+    // Without `DASH_OPERATORS` nobody is an operator
+    let server = Server::start();
+    server.connect().await;
+    let chaos = ChaosConfig { duplicate_rate: 1.0, ..Default::default() };
+
+    let anonymous = server.put_chaos(&chaos, None).await;
+    assert_eq!(anonymous.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    let session = server.sign_up("viewer").await;
+    let signed_in = server.put_chaos(&chaos, Some(&session.token)).await;
+    assert_eq!(signed_in.status(), reqwest::StatusCode::FORBIDDEN);
+
+    // Chaos is still off: books arrive once each
+    let mut conn = server.connect().await;
+    let first = next_book(&mut conn).await;
+    assert_eq!(next_book(&mut conn).await.sequence, first.sequence + 1);
+}
@@ -0,0 +1,95 @@
+// Shared WebSocket for dash-websocket's `ConnectionSharing::SharedWorker` mode.
+// Speaks the same JSON envelope as the BroadcastChannel relay (see shared.rs):
+//   tab -> worker: {kind: "open", url} | {kind: "command", cmd} | {kind: "detach"}
+//   worker -> tab: {kind: "frame", text} | {kind: "heartbeat", leader, connected}
+
+const HEARTBEAT_INTERVAL_MS = 1000;
+const MAX_RECONNECT_DELAY_MS = 5000;
+
+const ports = new Set();
+const subscriptions = new Map();
+let socket = null;
+let url = null;
+let attempt = 0;
+
+function broadcast(msg) {
+    const text = JSON.stringify(msg);
+    for (const port of ports) {
+        try {
+            port.postMessage(text);
+        } catch (e) {
+            // The tab is gone without detaching
+            ports.delete(port);
+        }
+    }
+}
+
+function isOpen() {
+    return socket !== null && socket.readyState === WebSocket.OPEN;
+}
+
+function heartbeat() {
+    broadcast({ kind: "heartbeat", leader: 0, connected: isOpen() });
+}
+
+function open() {
+    socket = new WebSocket(url);
+
+    socket.onopen = () => {
+        attempt = 0;
+        for (const cmd of subscriptions.values()) {
+            socket.send(JSON.stringify(cmd));
+        }
+        heartbeat();
+    };
+
+    socket.onmessage = (event) => {
+        if (typeof event.data === "string") {
+            broadcast({ kind: "frame", text: event.data });
+        }
+    };
+
+    socket.onclose = () => {
+        socket = null;
+        heartbeat();
+        const delay = Math.min(500 * Math.pow(1.5, attempt++), MAX_RECONNECT_DELAY_MS);
+        setTimeout(open, delay);
+    };
+}
+
+function send(cmd) {
+    if (cmd.type === "subscribe") {
+        subscriptions.set(cmd.symbol, cmd);
+    } else if (cmd.type === "unsubscribe") {
+        subscriptions.delete(cmd.symbol);
+    }
+
+    if (isOpen()) {
+        socket.send(JSON.stringify(cmd));
+    }
+}
+
+onconnect = (event) => {
+    const port = event.ports[0];
+    ports.add(port);
+
+    port.onmessage = (e) => {
+        const msg = JSON.parse(e.data);
+        if (msg.kind === "open") {
+            // Also sent by a tab restored after detaching on pagehide
+            ports.add(port);
+            if (url === null) {
+                url = msg.url;
+                open();
+            }
+        } else if (msg.kind === "command") {
+            send(msg.cmd);
+        } else if (msg.kind === "detach") {
+            ports.delete(port);
+        }
+    };
+
+    heartbeat();
+};
+
+setInterval(heartbeat, HEARTBEAT_INTERVAL_MS);